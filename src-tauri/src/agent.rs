@@ -2,14 +2,67 @@
 //!
 //! 用于集中管理多工具 Agent 定义（system prompt / 角色卡）。
 
-use crate::app_config::McpApps;
+use crate::app_config::AppType;
 use serde::{Deserialize, Serialize};
 
+/// Agent 应用启用状态（标记 Agent 同步到哪些 CLI 工具）
+///
+/// 与 [`crate::app_config::McpApps`] 结构相同，但额外支持 OpenClaw。
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct AgentApps {
+    #[serde(default)]
+    pub claude: bool,
+    #[serde(default)]
+    pub codex: bool,
+    #[serde(default)]
+    pub gemini: bool,
+    #[serde(default)]
+    pub opencode: bool,
+    #[serde(default)]
+    pub openclaw: bool,
+}
+
+impl AgentApps {
+    /// 检查指定应用是否启用
+    pub fn is_enabled_for(&self, app: &AppType) -> bool {
+        match app {
+            AppType::Claude => self.claude,
+            AppType::Codex => self.codex,
+            AppType::Gemini => self.gemini,
+            AppType::OpenCode => self.opencode,
+            AppType::OpenClaw => self.openclaw,
+        }
+    }
+
+    /// 设置指定应用的启用状态
+    pub fn set_enabled_for(&mut self, app: &AppType, enabled: bool) {
+        match app {
+            AppType::Claude => self.claude = enabled,
+            AppType::Codex => self.codex = enabled,
+            AppType::Gemini => self.gemini = enabled,
+            AppType::OpenCode => self.opencode = enabled,
+            AppType::OpenClaw => self.openclaw = enabled,
+        }
+    }
+
+    /// 获取所有启用的应用列表
+    pub fn enabled_apps(&self) -> Vec<AppType> {
+        AppType::all()
+            .filter(|app| self.is_enabled_for(app))
+            .collect()
+    }
+
+    /// 检查是否所有应用都未启用
+    pub fn is_empty(&self) -> bool {
+        !self.claude && !self.codex && !self.gemini && !self.opencode && !self.openclaw
+    }
+}
+
 /// Agent 定义（统一结构）
 ///
 /// 对应数据库 `agent_definitions` 表。
-/// `apps` 字段复用 [`McpApps`]，表示该 agent 已被启用到哪些 CLI 工具。
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// `apps` 字段表示该 agent 已被启用到哪些 CLI 工具。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AgentDefinition {
     /// slug，用作文件名（不可重复）
@@ -21,9 +74,178 @@ pub struct AgentDefinition {
     /// 可选描述
     pub description: Option<String>,
     /// 已启用的 CLI 工具集合
-    pub apps: McpApps,
+    pub apps: AgentApps,
+    /// 允许使用的工具列表（Claude frontmatter `tools`，逗号分隔，如 `Read, Grep`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<String>,
+    /// 使用的模型（Claude frontmatter `model`，如 `sonnet` / `opus` / `inherit`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 界面显示颜色（Claude frontmatter `color`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// 排序位置（越小越靠前；决定列表与共享 marker 文件中的区块顺序）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_index: Option<usize>,
+    /// 可选分组名（仅用于界面归类）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// 标签（如 `frontend` / `infra`），用于筛选；与分组不同，一个 agent 可以有多个
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     /// 创建时间（Unix 毫秒）
     pub created_at: Option<i64>,
     /// 更新时间（Unix 毫秒）
     pub updated_at: Option<i64>,
 }
+
+/// Agent 历史版本快照
+///
+/// 对应数据库 `agent_definition_history` 表，每次保存 Agent 时追加一条。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentRevision {
+    pub agent_id: String,
+    /// 版本号（同一 agent 内从 1 递增）
+    pub version: i64,
+    /// 快照时间（Unix 毫秒）
+    pub created_at: i64,
+    /// 保存时的完整定义
+    pub snapshot: AgentDefinition,
+}
+
+/// 行级差异类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Equal,
+    Added,
+    Removed,
+}
+
+/// 正文差异中的一行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
+/// 两个历史版本之间的差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentRevisionDiff {
+    pub agent_id: String,
+    pub from_version: i64,
+    pub to_version: i64,
+    /// 发生变化的字段名（camelCase，与前端字段一致）
+    pub changed_fields: Vec<String>,
+    /// `content` 的逐行差异
+    pub content_diff: Vec<DiffLine>,
+}
+
+/// 工具文件与数据库定义的偏差类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentDriftStatus {
+    /// 文件内容被外部修改
+    Modified,
+    /// 已启用但文件（或区块）不存在
+    Missing,
+}
+
+/// 单个 agent 在某个工具中的文件偏差
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentDrift {
+    pub agent_id: String,
+    pub app: String,
+    pub status: AgentDriftStatus,
+    /// 文件路径（共享 marker 文件的工具为整个文件）
+    pub path: String,
+    /// 发生变化的字段名（camelCase）；文件缺失时为空
+    pub changed_fields: Vec<String>,
+    /// 数据库正文 → 文件正文的逐行差异；文件缺失时为空
+    pub content_diff: Vec<DiffLine>,
+}
+
+/// Agent 定义的单条校验问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentValidationIssue {
+    /// 出问题的字段（`id` / `name` / `description` / `content`）
+    pub field: String,
+    /// 机器可读的问题代码，供前端本地化
+    pub code: String,
+    /// 中文说明
+    pub message: String,
+}
+
+/// 扫描工具目录时未通过校验的 agent 文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentScanInvalid {
+    pub agent_id: String,
+    pub app: String,
+    pub issues: Vec<AgentValidationIssue>,
+}
+
+/// 扫描工具目录的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentScanResult {
+    /// 新导入的 agent
+    pub imported: Vec<AgentDefinition>,
+    /// 多个工具中同名但正文不同的 agent id，均未导入，需要先在工具中统一内容
+    pub conflicts: Vec<String>,
+    /// 未通过校验、未导入的 agent 文件
+    pub invalid: Vec<AgentScanInvalid>,
+}
+
+/// Agent 导出包格式标识
+pub const AGENT_BUNDLE_FORMAT: &str = "cc-switch-agents";
+/// 当前导出包版本
+pub const AGENT_BUNDLE_VERSION: u32 = 1;
+
+/// 可移植的 Agent 导出包（JSON）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentBundle {
+    /// 固定为 [`AGENT_BUNDLE_FORMAT`]
+    pub format: String,
+    pub version: u32,
+    /// 导出时间（Unix 毫秒）
+    pub exported_at: i64,
+    pub agents: Vec<AgentDefinition>,
+}
+
+/// 导入时遇到已存在 id 的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateStrategy {
+    /// 保留本地版本，跳过导入
+    #[default]
+    Skip,
+    /// 用导入包中的版本覆盖本地
+    Overwrite,
+    /// 以新 id 导入（`{id}-2`、`{id}-3` ...）
+    Rename,
+}
+
+/// 导入时被重命名的 Agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// Agent 导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentImportResult {
+    /// 新增的 agent id
+    pub imported: Vec<String>,
+    /// 被覆盖的 agent id
+    pub overwritten: Vec<String>,
+    /// 跳过的 agent id
+    pub skipped: Vec<String>,
+    pub renamed: Vec<AgentRename>,
+}
@@ -1,43 +1,64 @@
 //! Claude Code agent 文件同步
 //!
 //! 写入路径：`~/.claude/agents/{id}.md`
-//! 格式：YAML frontmatter（name, description）+ Markdown body（content）
+//! 格式：YAML frontmatter（name, description, tools, model, color）+ Markdown body（content）
 
+use super::frontmatter::push_field;
+use super::write_agent_file;
 use crate::agent::AgentDefinition;
-use crate::config::{get_claude_config_dir, write_text_file};
+use crate::app_config::AppType;
+use crate::config::get_claude_config_dir;
 use crate::error::AppError;
+use crate::managed_banner::banner_for;
 use std::path::PathBuf;
 
-fn agent_path(id: &str) -> PathBuf {
-    get_claude_config_dir().join("agents").join(format!("{id}.md"))
+/// agent 文件所在目录
+pub(crate) fn agents_dir() -> PathBuf {
+    get_claude_config_dir().join("agents")
+}
+
+pub(super) fn agent_path(id: &str) -> PathBuf {
+    agents_dir().join(format!("{id}.md"))
 }
 
 /// 写入 `~/.claude/agents/{id}.md`
 pub fn write_agent(agent: &AgentDefinition) -> Result<(), AppError> {
-    let path = agent_path(&agent.id);
     let content = build_frontmatter_md(agent);
-    write_text_file(&path, &content)
+    write_agent_file(
+        &AppType::Claude,
+        &agent.id,
+        &agent_path(&agent.id),
+        Some(&content),
+    )
 }
 
 /// 删除 `~/.claude/agents/{id}.md`（不存在时静默忽略）
 pub fn remove_agent(id: &str) -> Result<(), AppError> {
-    let path = agent_path(id);
-    if path.exists() {
-        std::fs::remove_file(&path).map_err(|e| AppError::io(&path, e))?;
-    }
-    Ok(())
+    write_agent_file(&AppType::Claude, id, &agent_path(id), None)
 }
 
-fn build_frontmatter_md(agent: &AgentDefinition) -> String {
+pub(super) fn build_frontmatter_md(agent: &AgentDefinition) -> String {
     let mut fm = String::from("---\n");
-    fm.push_str(&format!("name: {}\n", agent.name));
+    push_field(&mut fm, "name", &agent.name);
     if let Some(desc) = &agent.description {
         if !desc.is_empty() {
-            fm.push_str(&format!("description: {}\n", desc));
+            push_field(&mut fm, "description", desc);
+        }
+    }
+    for (key, value) in [
+        ("tools", &agent.tools),
+        ("model", &agent.model),
+        ("color", &agent.color),
+    ] {
+        if let Some(value) = value.as_deref().map(str::trim) {
+            if !value.is_empty() {
+                push_field(&mut fm, key, value);
+            }
         }
     }
     fm.push_str("---\n");
     fm.push('\n');
+    fm.push_str(&banner_for("agent", &agent.id));
     fm.push_str(&agent.content);
     // 确保文件末尾有换行
     if !fm.ends_with('\n') {
@@ -45,3 +66,28 @@ fn build_frontmatter_md(agent: &AgentDefinition) -> String {
     }
     fm
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frontmatter_includes_extended_fields() {
+        let agent = AgentDefinition {
+            id: "reviewer".to_string(),
+            name: "reviewer".to_string(),
+            content: "Review the diff.".to_string(),
+            description: Some("Code reviewer".to_string()),
+            tools: Some("Read, Grep".to_string()),
+            model: Some("sonnet".to_string()),
+            color: Some(" ".to_string()),
+            ..Default::default()
+        };
+        let md = build_frontmatter_md(&agent);
+        assert!(md.starts_with("---\nname: reviewer\ndescription: Code reviewer\n"));
+        assert!(md.contains("tools: Read, Grep\n"));
+        assert!(md.contains("model: sonnet\n"));
+        assert!(!md.contains("color:"));
+        assert!(md.ends_with("Review the diff.\n"));
+    }
+}
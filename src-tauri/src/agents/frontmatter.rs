@@ -0,0 +1,143 @@
+//! Agent frontmatter 解析
+//!
+//! 解析 `---` 包裹的 YAML frontmatter + Markdown 正文格式，
+//! 用于从 Claude / OpenCode 的 agent 目录导入已有 agent；写入时的字段也经这里编码。
+
+use serde::{Deserialize, Deserializer};
+
+use crate::managed_banner::strip_banners;
+
+/// frontmatter 中 cc-switch 关心的字段（其余字段忽略）
+#[derive(Debug, Default, Deserialize)]
+pub struct AgentFrontmatter {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// 支持 `tools: Read, Grep` 与 YAML 列表两种写法，统一为逗号分隔字符串
+    #[serde(default, deserialize_with = "deserialize_tools")]
+    pub tools: Option<String>,
+    pub model: Option<String>,
+    pub color: Option<String>,
+}
+
+fn deserialize_tools<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tools {
+        Text(String),
+        List(Vec<String>),
+    }
+
+    Ok(match Option::<Tools>::deserialize(deserializer)? {
+        Some(Tools::Text(text)) => Some(text),
+        Some(Tools::List(list)) => Some(list.join(", ")),
+        None => None,
+    })
+}
+
+/// 向 frontmatter 追加一行 `key: value`
+///
+/// 值经 YAML 编码：含 `: `、`#`、引号或换行的值会被加引号或写成块标量，保证文件仍可解析。
+pub(super) fn push_field(fm: &mut String, key: &str, value: &str) {
+    let encoded = serde_yaml::to_string(value).unwrap_or_else(|_| format!("{value:?}\n"));
+    fm.push_str(key);
+    fm.push_str(": ");
+    fm.push_str(&encoded);
+    if !fm.ends_with('\n') {
+        fm.push('\n');
+    }
+}
+
+/// 解析 frontmatter Markdown，返回 (frontmatter, 正文)
+///
+/// 没有 frontmatter 或 YAML 无法解析时返回默认 frontmatter，正文保持原样；
+/// 有 frontmatter 时正文开头的来源横幅（见 [`crate::managed_banner`]）会被去掉。
+pub fn parse_frontmatter_md(content: &str) -> (AgentFrontmatter, String) {
+    let content = content.trim_start_matches('\u{feff}');
+
+    let Some(after_open) = content.strip_prefix("---") else {
+        return (AgentFrontmatter::default(), content.to_string());
+    };
+    let Some(close_pos) = after_open.find("\n---") else {
+        return (AgentFrontmatter::default(), content.to_string());
+    };
+
+    let yaml = &after_open[..close_pos];
+    let after_close = &after_open[close_pos + "\n---".len()..];
+    // 跳过结束标记所在行的剩余部分
+    let body = match after_close.find('\n') {
+        Some(pos) => &after_close[pos + 1..],
+        None => "",
+    };
+    let body = body.trim_start_matches(['\r', '\n']);
+
+    let yaml = yaml.trim();
+    let meta = if yaml.is_empty() {
+        AgentFrontmatter::default()
+    } else {
+        serde_yaml::from_str::<AgentFrontmatter>(yaml).unwrap_or_else(|e| {
+            log::warn!("解析 agent frontmatter 失败，忽略元数据: {e}");
+            AgentFrontmatter::default()
+        })
+    };
+
+    (meta, strip_banners(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoded_fields_round_trip() {
+        let mut fm = String::from("---\n");
+        push_field(&mut fm, "name", "Reviewer");
+        push_field(&mut fm, "tools", "Read, Grep");
+        push_field(&mut fm, "model", "provider: sonnet # fast");
+        push_field(&mut fm, "color", "red\nblue");
+        fm.push_str("---\nBody\n");
+
+        assert!(fm.contains("name: Reviewer\ntools: Read, Grep\n"));
+        let (meta, body) = parse_frontmatter_md(&fm);
+        assert_eq!(meta.name.as_deref(), Some("Reviewer"));
+        assert_eq!(meta.tools.as_deref(), Some("Read, Grep"));
+        assert_eq!(meta.model.as_deref(), Some("provider: sonnet # fast"));
+        assert_eq!(meta.color.as_deref(), Some("red\nblue"));
+        assert_eq!(body, "Body\n");
+    }
+
+    #[test]
+    fn parses_name_description_and_body() {
+        let md = "---\nname: Reviewer\ndescription: Reviews code\ntools: Read\n---\n\nYou review code.\n";
+        let (meta, body) = parse_frontmatter_md(md);
+        assert_eq!(meta.name.as_deref(), Some("Reviewer"));
+        assert_eq!(meta.description.as_deref(), Some("Reviews code"));
+        assert_eq!(meta.tools.as_deref(), Some("Read"));
+        assert_eq!(body, "You review code.\n");
+    }
+
+    #[test]
+    fn tools_list_is_joined() {
+        let md = "---\nname: a\ntools:\n  - Read\n  - Grep\nmodel: opus\n---\nBody\n";
+        let (meta, _) = parse_frontmatter_md(md);
+        assert_eq!(meta.tools.as_deref(), Some("Read, Grep"));
+        assert_eq!(meta.model.as_deref(), Some("opus"));
+    }
+
+    #[test]
+    fn content_without_frontmatter_is_body() {
+        let (meta, body) = parse_frontmatter_md("Just a prompt.\n");
+        assert!(meta.name.is_none());
+        assert_eq!(body, "Just a prompt.\n");
+    }
+
+    #[test]
+    fn handles_crlf_and_bom() {
+        let md = "\u{feff}---\r\nname: Win\r\n---\r\nBody\r\n";
+        let (meta, body) = parse_frontmatter_md(md);
+        assert_eq!(meta.name.as_deref(), Some("Win"));
+        assert_eq!(body, "Body\r\n");
+    }
+}
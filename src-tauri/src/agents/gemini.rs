@@ -1,120 +1,128 @@
 //! Gemini agent 文件同步
 //!
-//! 写入路径：`~/.gemini/GEMINI.md`（共享文件，每个 agent 占一个 marker 区块）
-//!
-//! 区块格式：
-//! ```text
-//! <!-- cc-switch:agent:{id} -->
-//! # {name}
-//!
-//! {content}
-//!
-//! <!-- /cc-switch:agent:{id} -->
-//! ```
+//! 两种输出方式（设置 `geminiAgentOutput`）：
+//! - `markers`（默认）：写入 `~/.gemini/GEMINI.md`，每个 agent 占一个 marker 区块，格式见 [`super::marker_file`]
+//! - `files`：写入 `~/.gemini/agents/{id}.md`，YAML frontmatter（name, description）+ Markdown body
 
+use serde::{Deserialize, Serialize};
+
+use super::{marker_file, opencode, write_agent_file, AgentChange};
 use crate::agent::AgentDefinition;
-use crate::config::write_text_file;
+use crate::app_config::AppType;
 use crate::error::AppError;
 use crate::gemini_config::get_gemini_dir;
 use std::path::PathBuf;
 
-fn agents_file_path() -> PathBuf {
+/// Gemini agent 输出方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GeminiAgentOutput {
+    /// GEMINI.md 中的 marker 区块
+    #[default]
+    Markers,
+    /// 每个 agent 一个文件
+    Files,
+}
+
+pub(super) fn agents_file_path() -> PathBuf {
     get_gemini_dir().join("GEMINI.md")
 }
 
-fn start_marker(id: &str) -> String {
-    format!("<!-- cc-switch:agent:{id} -->")
+/// 按文件输出时 agent 文件所在目录
+pub(crate) fn agents_dir() -> PathBuf {
+    get_gemini_dir().join("agents")
 }
 
-fn end_marker(id: &str) -> String {
-    format!("<!-- /cc-switch:agent:{id} -->")
+pub(super) fn agent_path(id: &str) -> PathBuf {
+    agents_dir().join(format!("{id}.md"))
 }
 
-fn build_block(agent: &AgentDefinition) -> String {
-    let mut block = String::new();
-    block.push_str(&start_marker(&agent.id));
-    block.push('\n');
-    block.push_str(&format!("# {}\n", agent.name));
-    block.push('\n');
-    block.push_str(&agent.content);
-    if !block.ends_with('\n') {
-        block.push('\n');
-    }
-    block.push('\n');
-    block.push_str(&end_marker(&agent.id));
-    block.push('\n');
-    block
+/// 当前设置的输出方式
+pub(super) fn output() -> GeminiAgentOutput {
+    crate::settings::get_gemini_agent_output()
 }
 
-/// Upsert agent 区块到 `~/.gemini/GEMINI.md`
-pub fn write_agent(agent: &AgentDefinition) -> Result<(), AppError> {
-    let path = agents_file_path();
-    let existing = if path.exists() {
-        std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?
-    } else {
-        String::new()
-    };
-
-    let new_content = upsert_block(&existing, agent);
-    write_text_file(&path, &new_content)
+/// 文件格式与 OpenCode 相同（name / description frontmatter + 正文）
+pub(super) fn build_frontmatter_md(agent: &AgentDefinition) -> String {
+    opencode::build_frontmatter_md(agent)
 }
 
-/// 从 `~/.gemini/GEMINI.md` 中删除指定 agent 区块
-pub fn remove_agent(id: &str) -> Result<(), AppError> {
-    let path = agents_file_path();
-    if !path.exists() {
-        return Ok(());
-    }
+/// 写入 `~/.gemini/agents/{id}.md`
+fn write_agent(agent: &AgentDefinition) -> Result<(), AppError> {
+    let content = build_frontmatter_md(agent);
+    write_agent_file(
+        &AppType::Gemini,
+        &agent.id,
+        &agent_path(&agent.id),
+        Some(&content),
+    )
+}
 
-    let content = std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?;
-    let new_content = remove_block(&content, id);
-    write_text_file(&path, &new_content)
+/// 删除 `~/.gemini/agents/{id}.md`（不存在时静默忽略）
+fn remove_agent(id: &str) -> Result<(), AppError> {
+    write_agent_file(&AppType::Gemini, id, &agent_path(id), None)
 }
 
-fn upsert_block(content: &str, agent: &AgentDefinition) -> String {
-    let start = start_marker(&agent.id);
-    let end = end_marker(&agent.id);
-    let new_block = build_block(agent);
-
-    if let (Some(start_pos), Some(end_pos)) = (content.find(&start), content.find(&end)) {
-        let after_end = end_pos + end.len();
-        let after_end = if content[after_end..].starts_with('\n') {
-            after_end + 1
-        } else {
-            after_end
-        };
-        format!("{}{}{}", &content[..start_pos], new_block, &content[after_end..])
-    } else {
-        let mut result = content.to_string();
-        if !result.is_empty() && !result.ends_with('\n') {
-            result.push('\n');
+/// 按当前输出方式应用一批变更
+pub fn apply_changes(changes: &[AgentChange<'_>]) -> Result<(), AppError> {
+    apply_changes_with(output(), changes)
+}
+
+/// 按指定输出方式应用一批变更（迁移时新旧两种方式各调用一次）
+pub(super) fn apply_changes_with(
+    output: GeminiAgentOutput,
+    changes: &[AgentChange<'_>],
+) -> Result<(), AppError> {
+    match output {
+        GeminiAgentOutput::Markers => {
+            marker_file::apply_changes(&AppType::Gemini, &agents_file_path(), changes)?;
         }
-        if !result.is_empty() && !result.ends_with("\n\n") {
-            result.push('\n');
+        GeminiAgentOutput::Files => {
+            changes.iter().try_for_each(|change| match change {
+                AgentChange::Upsert(agent) => write_agent(agent),
+                AgentChange::Remove(id) => remove_agent(id),
+            })?;
         }
-        result.push_str(&new_block);
-        result
     }
+    Ok(())
 }
 
-fn remove_block(content: &str, id: &str) -> String {
-    let start = start_marker(id);
-    let end = end_marker(id);
+/// 读取 agent 在指定输出方式下的原始内容（区块或整个文件），不存在时返回 None
+pub(super) fn read_raw(output: GeminiAgentOutput, id: &str) -> Result<Option<String>, AppError> {
+    match output {
+        GeminiAgentOutput::Markers => marker_file::read_block(&agents_file_path(), id),
+        GeminiAgentOutput::Files => {
+            let path = agent_path(id);
+            if !path.exists() {
+                return Ok(None);
+            }
+            std::fs::read_to_string(&path)
+                .map(Some)
+                .map_err(|e| AppError::io(&path, e))
+        }
+    }
+}
 
-    if let (Some(start_pos), Some(end_pos)) = (content.find(&start), content.find(&end)) {
-        let after_end = end_pos + end.len();
-        let after_end = if content[after_end..].starts_with('\n') {
-            after_end + 1
-        } else {
-            after_end
-        };
-        let start_pos = if start_pos > 0 && content[..start_pos].ends_with("\n\n") {
-            start_pos - 1
-        } else {
-            start_pos
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::parse_frontmatter_md;
+
+    #[test]
+    fn per_file_output_roundtrips_frontmatter() {
+        let agent = AgentDefinition {
+            id: "reviewer".to_string(),
+            name: "Reviewer".to_string(),
+            description: Some("Reviews diffs".to_string()),
+            content: "Check the diff.".to_string(),
+            ..Default::default()
         };
-        format!("{}{}", &content[..start_pos], &content[after_end..])
-    } else {
-        content.to_string()
+        let (meta, body) = parse_frontmatter_md(&build_frontmatter_md(&agent));
+        assert_eq!(meta.name.as_deref(), Some("Reviewer"));
+        assert_eq!(meta.description.as_deref(), Some("Reviews diffs"));
+        assert_eq!(body.trim_end(), "Check the diff.");
+
+        let output: GeminiAgentOutput = serde_json::from_str("\"files\"").unwrap();
+        assert_eq!(output, GeminiAgentOutput::Files);
     }
 }
@@ -0,0 +1,652 @@
+//! 共享 marker 文件的区块读写（Codex / Gemini / OpenClaw 共用）
+//!
+//! 多个 agent 写入同一个文件（`AGENTS.md` / `GEMINI.md`），每个 agent 占一个区块：
+//! ```text
+//! <!-- cc-switch:agent:{id} -->
+//! # {name}
+//!
+//! {content}
+//!
+//! <!-- /cc-switch:agent:{id} -->
+//! ```
+//!
+//! 一次调用中的多个变更只读写文件一次；区块内容未变化时跳过，
+//! 所有变更都无效时不触碰文件，避免触发其他工具的文件监听。
+//! 每个区块写入后的摘要持久化在 [`crate::external_edits`] 的摘要记录中，下次写入前据此识别外部修改。
+//!
+//! 使用托管区域（[`apply_changes_in_region`]）时，所有区块集中在
+//! `<!-- cc-switch:agents:begin -->` / `<!-- cc-switch:agents:end -->` 之间，
+//! 整个区域位于用户内容的顶部或底部。
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::AgentChange;
+use crate::agent::AgentDefinition;
+use crate::app_config::AppType;
+use crate::config::write_text_file;
+use crate::error::AppError;
+use crate::external_edits::{self, ManagedKind, ManagedUnit};
+use crate::managed_banner::{banner_for, strip_banners};
+
+const REGION_BEGIN: &str = "<!-- cc-switch:agents:begin -->";
+const REGION_END: &str = "<!-- cc-switch:agents:end -->";
+const BLOCK_START_PREFIX: &str = "<!-- cc-switch:agent:";
+
+/// 托管区域相对用户内容的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionPlacement {
+    /// 位于文件开头，用户内容在其后
+    Top,
+    /// 位于文件末尾（默认）
+    #[default]
+    Bottom,
+}
+
+pub(super) fn start_marker(id: &str) -> String {
+    format!("<!-- cc-switch:agent:{id} -->")
+}
+
+pub(super) fn end_marker(id: &str) -> String {
+    format!("<!-- /cc-switch:agent:{id} -->")
+}
+
+pub(super) fn build_block(agent: &AgentDefinition) -> String {
+    let mut block = String::new();
+    block.push_str(&start_marker(&agent.id));
+    block.push('\n');
+    block.push_str(&banner_for("agent", &agent.id));
+    block.push_str(&format!("# {}\n", agent.name));
+    block.push('\n');
+    block.push_str(&agent.content);
+    if !block.ends_with('\n') {
+        block.push('\n');
+    }
+    block.push('\n');
+    block.push_str(&end_marker(&agent.id));
+    block.push('\n');
+    block
+}
+
+/// 定位指定 agent 的区块，返回 `[start, end)`（含结束标记后的换行）
+fn find_block(content: &str, id: &str) -> Option<(usize, usize)> {
+    let start = start_marker(id);
+    let end = end_marker(id);
+
+    let start_pos = content.find(&start)?;
+    let end_pos = start_pos + content[start_pos..].find(&end)?;
+    let after_end = end_pos + end.len();
+    // 跳过末尾的换行
+    let after_end = if content[after_end..].starts_with('\n') {
+        after_end + 1
+    } else {
+        after_end
+    };
+    Some((start_pos, after_end))
+}
+
+/// 读取 `path` 中指定 agent 的完整区块（含首尾标记）；文件或区块不存在时返回 None
+pub(super) fn read_block(path: &Path, id: &str) -> Result<Option<String>, AppError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+    Ok(find_block(&content, id).map(|(start, end)| content[start..end].to_string()))
+}
+
+/// 将区块解析回 (标题, 正文)，是 [`build_block`] 的逆过程
+///
+/// 来源横幅先被去掉；区块首行之后若紧跟 `# ` 开头的行，则视为标题；正文去掉首尾空行后以单个换行结尾。
+pub(super) fn parse_block(block: &str) -> (Option<String>, String) {
+    let block = strip_banners(block);
+    let inner = block.split_once('\n').map(|(_, rest)| rest).unwrap_or("");
+    let inner = match inner.rfind("<!-- /cc-switch:agent:") {
+        Some(pos) => &inner[..pos],
+        None => inner,
+    };
+    let (name, body) = match inner.strip_prefix("# ") {
+        Some(rest) => {
+            let (name, body) = rest.split_once('\n').unwrap_or((rest, ""));
+            (Some(name.trim().to_string()), body)
+        }
+        None => (None, inner),
+    };
+
+    let mut content = body.trim_matches('\n').to_string();
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    (name.filter(|n| !n.is_empty()), content)
+}
+
+/// 将一批变更应用到 `path`，只在内容实际变化时写入一次
+///
+/// 被外部修改的区块按 `app` 的外部修改策略处理（见 [`crate::external_edits`]）。返回是否写入了文件。
+pub(super) fn apply_changes(
+    app: &AppType,
+    path: &Path,
+    changes: &[AgentChange<'_>],
+) -> Result<bool, AppError> {
+    let original = if path.exists() {
+        std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?
+    } else {
+        String::new()
+    };
+
+    let mut content = original.clone();
+    let (dirty, applied) = apply_guarded(app, path, &mut content, changes)?;
+
+    let written = if !dirty || content == original {
+        log::debug!("agent 区块无变化，跳过写入: {}", path.display());
+        false
+    } else if !path.exists() && content.trim().is_empty() {
+        false
+    } else {
+        write_text_file(path, &content)?;
+        true
+    };
+    record_blocks(app, path, &content, &applied);
+    Ok(written)
+}
+
+/// 依次应用变更，跳过被外部修改且策略为保留的区块
+///
+/// 返回 (内容是否变化, 实际处理的 agent id)。
+fn apply_guarded<'a>(
+    app: &AppType,
+    path: &Path,
+    content: &mut String,
+    changes: &[AgentChange<'a>],
+) -> Result<(bool, Vec<&'a str>), AppError> {
+    let mut dirty = false;
+    let mut applied = Vec::new();
+    for change in changes {
+        let (id, next) = match *change {
+            AgentChange::Upsert(agent) => (agent.id.as_str(), Some(build_block(agent))),
+            AgentChange::Remove(id) => (id, None),
+        };
+        let current = find_block(content, id).map(|(start, end)| content[start..end].to_string());
+        let unit = block_unit(app, path, id);
+        if !external_edits::before_write(&unit, current.as_deref(), next.as_deref())? {
+            continue;
+        }
+        dirty |= match change {
+            AgentChange::Upsert(agent) => upsert_block(content, agent),
+            AgentChange::Remove(id) => remove_block(content, id),
+        };
+        applied.push(id);
+    }
+    Ok((dirty, applied))
+}
+
+fn block_unit<'a>(app: &'a AppType, path: &'a Path, id: &'a str) -> ManagedUnit<'a> {
+    ManagedUnit {
+        app,
+        kind: ManagedKind::Agent,
+        id,
+        path,
+    }
+}
+
+/// 记录已处理区块写入后的内容，供下次检测外部修改
+fn record_blocks(app: &AppType, path: &Path, content: &str, ids: &[&str]) {
+    let written: Vec<_> = ids
+        .iter()
+        .map(|id| {
+            let block = find_block(content, id).map(|(start, end)| &content[start..end]);
+            (block_unit(app, path, id), block)
+        })
+        .collect();
+    external_edits::after_write_many(&written);
+}
+
+/// 将一批变更应用到 `path`，并把所有区块收拢到一个连续的托管区域
+///
+/// 区域外散落的区块（旧版本直接追加在文件末尾的）会按原顺序移入区域；新区块追加在
+/// 区域末尾。`changes` 为空时仅按 `placement` 整理文件。只在内容实际变化时写入，返回是否写入。
+pub(super) fn apply_changes_in_region(
+    app: &AppType,
+    path: &Path,
+    changes: &[AgentChange<'_>],
+    placement: SectionPlacement,
+) -> Result<bool, AppError> {
+    let original = if path.exists() {
+        std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?
+    } else {
+        String::new()
+    };
+
+    let (user, mut region) = split_region(&original);
+    let (_, applied) = apply_guarded(app, path, &mut region, changes)?;
+    let content = join_region(&user, &region, placement);
+
+    let written = if content == original {
+        log::debug!("agent 托管区域无变化，跳过写入: {}", path.display());
+        false
+    } else if !path.exists() && content.trim().is_empty() {
+        false
+    } else {
+        write_text_file(path, &content)?;
+        true
+    };
+    record_blocks(app, path, &content, &applied);
+    Ok(written)
+}
+
+/// 推演只保留 `agents` 时文件的完整内容（不写入）
+///
+/// 不在 `agents` 中的区块被移除，其余按与实际同步相同的规则原地更新或追加；
+/// `placement` 为 Some 时按托管区域方式整理（同 [`apply_changes_in_region`]）。
+pub(super) fn compose(
+    original: &str,
+    agents: &[AgentDefinition],
+    placement: Option<SectionPlacement>,
+) -> String {
+    let apply = |content: &mut String| {
+        for id in block_ids(content) {
+            if !agents.iter().any(|a| a.id == id) {
+                remove_block(content, &id);
+            }
+        }
+        for agent in agents {
+            upsert_block(content, agent);
+        }
+    };
+    match placement {
+        Some(placement) => {
+            let (user, mut region) = split_region(original);
+            apply(&mut region);
+            join_region(&user, &region, placement)
+        }
+        None => {
+            let mut content = original.to_string();
+            apply(&mut content);
+            content
+        }
+    }
+}
+
+/// 内容中所有区块的 id（按出现顺序）
+fn block_ids(content: &str) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut rest = content;
+    while let Some(id) = next_block_id(rest) {
+        let next = rest.find(BLOCK_START_PREFIX).unwrap_or_default() + BLOCK_START_PREFIX.len();
+        ids.push(id);
+        rest = &rest[next..];
+    }
+    ids
+}
+
+/// 拆分出 (用户内容, 区域内的区块)，区块按在文件中出现的顺序以空行分隔
+fn split_region(content: &str) -> (String, String) {
+    let mut user = content.to_string();
+    let mut region = String::new();
+    while let Some(id) = next_block_id(&user) {
+        let Some((start, end)) = find_block(&user, &id) else {
+            // 缺少结束标记的残缺区块保留在用户内容中
+            break;
+        };
+        if !region.is_empty() {
+            region.push('\n');
+        }
+        region.push_str(&user[start..end]);
+        remove_block(&mut user, &id);
+    }
+    for marker in [REGION_BEGIN, REGION_END] {
+        remove_line(&mut user, marker);
+    }
+    // 区域位于文件开头时，去掉它与用户内容之间的分隔空行
+    if content.starts_with(REGION_BEGIN) {
+        user = user.trim_start_matches('\n').to_string();
+    }
+    (user, region)
+}
+
+/// 去掉内容中所有 cc-switch 写入的 agent 区块与托管区域标记，只保留用户内容
+pub(super) fn strip_managed(content: &str) -> String {
+    split_region(content).0
+}
+
+/// 第一个区块起始标记中的 id
+fn next_block_id(content: &str) -> Option<String> {
+    let start = content.find(BLOCK_START_PREFIX)? + BLOCK_START_PREFIX.len();
+    let end = start + content[start..].find(" -->")?;
+    Some(content[start..end].to_string())
+}
+
+/// 删除 `marker` 所在行（连同其前方多余的空行）
+fn remove_line(content: &mut String, marker: &str) {
+    let Some(start) = content.find(marker) else {
+        return;
+    };
+    let mut end = start + marker.len();
+    if content[end..].starts_with('\n') {
+        end += 1;
+    }
+    let start = if start > 0 && content[..start].ends_with("\n\n") {
+        start - 1
+    } else {
+        start
+    };
+    content.replace_range(start..end, "");
+}
+
+/// 按位置拼回文件；区域为空时不写区域标记
+fn join_region(user: &str, region: &str, placement: SectionPlacement) -> String {
+    // 删除首个区块后残留的空行
+    let region = region.trim_start_matches('\n');
+    if region.is_empty() {
+        return user.to_string();
+    }
+    let section = format!("{REGION_BEGIN}\n{region}{REGION_END}\n");
+    match placement {
+        SectionPlacement::Top => {
+            let user = user.trim_start_matches('\n');
+            if user.is_empty() {
+                section
+            } else {
+                format!("{section}\n{user}")
+            }
+        }
+        SectionPlacement::Bottom => {
+            let user = user.trim_end_matches('\n');
+            if user.is_empty() {
+                section
+            } else {
+                format!("{user}\n\n{section}")
+            }
+        }
+    }
+}
+
+/// 按 `order` 重排 `path` 中已存在的托管区块，只在顺序变化时写入
+///
+/// 返回是否写入了文件。
+pub(super) fn reorder_blocks(path: &Path, order: &[&str]) -> Result<bool, AppError> {
+    if !path.exists() {
+        return Ok(false);
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+    let Some(reordered) = reorder_content(&content, order) else {
+        return Ok(false);
+    };
+    write_text_file(path, &reordered)?;
+    Ok(true)
+}
+
+/// 重排区块：各区块原来占据的位置保持不变，依次填入按 `order` 排列后的区块，
+/// 区块之间的用户内容与空行原样保留。顺序已一致时返回 None。
+fn reorder_content(content: &str, order: &[&str]) -> Option<String> {
+    let mut slots: Vec<(usize, usize)> = Vec::new();
+    let mut desired: Vec<&str> = Vec::new();
+    for id in order {
+        if let Some(range) = find_block(content, id) {
+            slots.push(range);
+            desired.push(&content[range.0..range.1]);
+        }
+    }
+    slots.sort_unstable();
+
+    let current: Vec<&str> = slots.iter().map(|&(s, e)| &content[s..e]).collect();
+    if current == desired {
+        return None;
+    }
+
+    let mut result = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for (&(start, end), block) in slots.iter().zip(&desired) {
+        result.push_str(&content[cursor..start]);
+        result.push_str(block);
+        cursor = end;
+    }
+    result.push_str(&content[cursor..]);
+    Some(result)
+}
+
+/// 在文件内容中 upsert 指定 agent 的区块，返回内容是否变化
+fn upsert_block(content: &mut String, agent: &AgentDefinition) -> bool {
+    let new_block = build_block(agent);
+
+    if let Some((start_pos, after_end)) = find_block(content, &agent.id) {
+        // 区块已存在：内容一致则跳过，否则替换
+        if content[start_pos..after_end] == new_block {
+            return false;
+        }
+        content.replace_range(start_pos..after_end, &new_block);
+    } else {
+        // 区块不存在：追加
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        if !content.is_empty() && !content.ends_with("\n\n") {
+            content.push('\n');
+        }
+        content.push_str(&new_block);
+    }
+    true
+}
+
+/// 从文件内容中删除指定 agent 的区块，返回内容是否变化
+fn remove_block(content: &mut String, id: &str) -> bool {
+    let Some((start_pos, after_end)) = find_block(content, id) else {
+        return false;
+    };
+    // 如果区块前面有额外的空行，也一并删除
+    let start_pos = if start_pos > 0 && content[..start_pos].ends_with("\n\n") {
+        start_pos - 1
+    } else {
+        start_pos
+    };
+    content.replace_range(start_pos..after_end, "");
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    fn make_agent(id: &str, name: &str, content: &str) -> AgentDefinition {
+        AgentDefinition {
+            id: id.to_string(),
+            name: name.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_upsert_empty_file() {
+        let agent = make_agent("test-agent", "Test Agent", "You are a test agent.");
+        let mut result = String::new();
+        assert!(upsert_block(&mut result, &agent));
+        assert!(result.contains("<!-- cc-switch:agent:test-agent -->"));
+        assert!(result.contains("<!-- /cc-switch:agent:test-agent -->"));
+        assert!(result.contains("# Test Agent"));
+        assert!(result.contains("You are a test agent."));
+    }
+
+    #[test]
+    fn test_upsert_existing_block() {
+        let mut result = String::new();
+        upsert_block(
+            &mut result,
+            &make_agent("test-agent", "Test Agent", "Initial content."),
+        );
+
+        let agent2 = make_agent("test-agent", "Test Agent", "Updated content.");
+        assert!(upsert_block(&mut result, &agent2));
+        assert!(result.contains("Updated content."));
+        assert!(!result.contains("Initial content."));
+        // Should only have one block
+        assert_eq!(
+            result
+                .matches("<!-- cc-switch:agent:test-agent -->")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_upsert_unchanged_block_is_noop() {
+        let agent = make_agent("test-agent", "Test Agent", "Same content.");
+        let mut content = "# Notes\n".to_string();
+        upsert_block(&mut content, &agent);
+        let before = content.clone();
+
+        assert!(!upsert_block(&mut content, &agent));
+        assert_eq!(content, before);
+    }
+
+    #[test]
+    fn test_remove_block() {
+        let agent = make_agent("test-agent", "Test Agent", "Some content.");
+        let mut content = String::new();
+        upsert_block(&mut content, &agent);
+        assert!(remove_block(&mut content, "test-agent"));
+        assert!(!content.contains("cc-switch:agent:test-agent"));
+    }
+
+    #[test]
+    fn test_remove_nonexistent_block() {
+        let mut content = "Some existing content\n".to_string();
+        assert!(!remove_block(&mut content, "nonexistent"));
+        assert_eq!(content, "Some existing content\n");
+    }
+
+    #[test]
+    fn test_parse_block_roundtrip() {
+        let agent = make_agent("test-agent", "Test Agent", "Line one.\n\nLine two.");
+        let block = build_block(&agent);
+        let (name, content) = parse_block(&block);
+        assert_eq!(name.as_deref(), Some("Test Agent"));
+        assert_eq!(content, "Line one.\n\nLine two.\n");
+
+        let mut file = String::from("# Notes\n");
+        upsert_block(&mut file, &agent);
+        let (start, end) = find_block(&file, "test-agent").expect("block");
+        assert_eq!(&file[start..end], block);
+    }
+
+    #[test]
+    fn test_reorder_keeps_surrounding_content() {
+        let mut content = "# Notes\n".to_string();
+        for id in ["a", "b", "c"] {
+            upsert_block(&mut content, &make_agent(id, id, "Body."));
+        }
+        content.push_str("\nFooter\n");
+
+        let reordered = reorder_content(&content, &["c", "a", "b"]).expect("order changed");
+        let pos = |id: &str| reordered.find(&start_marker(id)).expect("block present");
+        assert!(pos("c") < pos("a") && pos("a") < pos("b"));
+        assert!(reordered.starts_with("# Notes\n") && reordered.ends_with("\nFooter\n"));
+        assert_eq!(reordered.len(), content.len());
+
+        assert!(reorder_content(&reordered, &["c", "missing", "a", "b"]).is_none());
+    }
+
+    #[test]
+    fn test_region_collects_blocks_and_respects_placement() {
+        let a = make_agent("a", "A", "Alpha.");
+        let b = make_agent("b", "B", "Beta.");
+        // 旧版本：区块直接追加在用户内容之后，且用户在其后又写了内容
+        let mut legacy = "# Notes\n".to_string();
+        upsert_block(&mut legacy, &a);
+        legacy.push_str("\nFooter\n");
+
+        let (user, mut region) = split_region(&legacy);
+        assert_eq!(user, "# Notes\n\nFooter\n");
+        upsert_block(&mut region, &b);
+
+        let bottom = join_region(&user, &region, SectionPlacement::Bottom);
+        assert!(bottom.starts_with("# Notes\n\nFooter\n\n<!-- cc-switch:agents:begin -->\n"));
+        assert!(bottom.ends_with("<!-- cc-switch:agents:end -->\n"));
+        assert!(bottom.find(&start_marker("a")) < bottom.find(&start_marker("b")));
+
+        // 规范格式下重新拆分、拼回结果不变
+        let (user2, region2) = split_region(&bottom);
+        assert_eq!(
+            join_region(&user2, &region2, SectionPlacement::Bottom),
+            bottom
+        );
+
+        let top = join_region(&user2, &region2, SectionPlacement::Top);
+        assert!(top.starts_with("<!-- cc-switch:agents:begin -->\n"));
+        assert!(top.ends_with("<!-- cc-switch:agents:end -->\n\n# Notes\n\nFooter\n"));
+        let (user3, region3) = split_region(&top);
+        assert_eq!(join_region(&user3, &region3, SectionPlacement::Top), top);
+
+        // 删除最后一个区块后区域标记一并移除
+        let mut region4 = region3.clone();
+        remove_block(&mut region4, "a");
+        remove_block(&mut region4, "b");
+        assert_eq!(
+            join_region(&user3, &region4, SectionPlacement::Top),
+            "# Notes\n\nFooter\n"
+        );
+    }
+
+    #[test]
+    fn test_compose_drops_disabled_blocks_and_keeps_user_content() {
+        let a = make_agent("a", "A", "Alpha.");
+        let b = make_agent("b", "B", "Beta.");
+        let mut original = "# Notes\n".to_string();
+        upsert_block(&mut original, &a);
+
+        let composed = compose(&original, std::slice::from_ref(&b), None);
+        assert!(composed.starts_with("# Notes\n"));
+        assert!(!composed.contains(&start_marker("a")));
+        assert!(composed.contains(&start_marker("b")));
+
+        let in_region = compose(
+            &original,
+            &[a.clone(), b.clone()],
+            Some(SectionPlacement::Top),
+        );
+        assert!(in_region.starts_with("<!-- cc-switch:agents:begin -->\n"));
+        assert!(in_region.find(&start_marker("a")) < in_region.find(&start_marker("b")));
+        assert_eq!(
+            compose(&in_region, &[a, b], Some(SectionPlacement::Top)),
+            in_region
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_apply_changes_batches_into_single_write() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        // 摘要记录与外部修改策略都读写配置目录，指向临时目录避免碰到真实用户数据
+        std::env::set_var("CC_SWITCH_TEST_HOME", dir.path());
+        crate::settings::update_settings(crate::settings::AppSettings::default())
+            .expect("reset settings");
+        let path = dir.path().join("AGENTS.md");
+        let a = make_agent("a", "A", "Alpha.");
+        let b = make_agent("b", "B", "Beta.");
+
+        let written = apply_changes(
+            &AppType::Gemini,
+            &path,
+            &[AgentChange::Upsert(&a), AgentChange::Upsert(&b)],
+        )
+        .expect("apply changes");
+        assert!(written);
+        let content = std::fs::read_to_string(&path).expect("read file");
+        assert!(content.contains("Alpha.") && content.contains("Beta."));
+
+        let written = apply_changes(
+            &AppType::Gemini,
+            &path,
+            &[AgentChange::Upsert(&a), AgentChange::Remove("missing")],
+        )
+        .expect("apply changes");
+        assert!(!written, "unchanged blocks should not rewrite the file");
+        assert!(dir
+            .path()
+            .join(".cc-switch")
+            .join("managed-hashes.json")
+            .exists());
+    }
+}
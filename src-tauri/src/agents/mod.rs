@@ -11,40 +11,516 @@
 //! | OpenCode  | `~/.config/opencode/agents/{id}.md`    | YAML frontmatter + Markdown body  |
 //! | Codex     | `~/.codex/AGENTS.md`                   | cc-switch marker 分区块            |
 //! | Gemini    | `~/.gemini/GEMINI.md`                  | cc-switch marker 分区块            |
+//! | Gemini    | `~/.gemini/agents/{id}.md`（按文件输出） | YAML frontmatter + Markdown body  |
+//! | OpenClaw  | `~/.openclaw/workspace/AGENTS.md`      | cc-switch marker 分区块            |
 
 mod claude;
 mod codex;
+mod frontmatter;
 mod gemini;
+mod marker_file;
+mod openclaw;
 mod opencode;
+mod template;
+mod templates;
+mod validate;
+
+pub use frontmatter::parse_frontmatter_md;
+pub use gemini::GeminiAgentOutput;
+pub use marker_file::SectionPlacement;
+pub use template::{
+    matches_rendered, os_name, render_template, restore_template_lines, TemplateVars,
+};
+pub use templates::{all_templates, find_template, AgentTemplate};
+pub use validate::validate_agent;
+
+use std::path::{Path, PathBuf};
 
 use crate::agent::AgentDefinition;
 use crate::app_config::AppType;
+use crate::config::{delete_file, write_text_file};
 use crate::error::AppError;
+use crate::external_edits::{self, ManagedKind, ManagedUnit};
+use crate::managed_banner::strip_banners;
+
+/// 单个 agent 文件变更
+#[derive(Debug, Clone, Copy)]
+pub enum AgentChange<'a> {
+    /// 写入或更新 agent
+    Upsert(&'a AgentDefinition),
+    /// 按 id 移除 agent
+    Remove(&'a str),
+}
+
+/// 将一批变更应用到指定工具
+///
+/// 写入前按 `vars` 替换 agent 正文中的模板变量。
+/// 共享 marker 文件的工具（Codex / Gemini / OpenClaw）只读写一次文件，
+/// 内容未变化时不写入；该工具已暂停同步时不做任何改动。
+pub fn apply_agent_changes(
+    app: &AppType,
+    changes: &[AgentChange<'_>],
+    vars: &TemplateVars,
+) -> Result<(), AppError> {
+    if crate::settings::is_app_sync_paused(app) {
+        log::debug!("{} 同步已暂停，跳过 agent 文件写入", app.as_str());
+        return Ok(());
+    }
+    let rendered: Vec<Option<AgentDefinition>> = changes
+        .iter()
+        .map(|change| match change {
+            AgentChange::Upsert(agent) => Some(render_agent(agent, vars)),
+            AgentChange::Remove(_) => None,
+        })
+        .collect();
+    let changes: Vec<AgentChange<'_>> = changes
+        .iter()
+        .zip(&rendered)
+        .map(|(change, rendered)| match rendered {
+            Some(agent) => AgentChange::Upsert(agent),
+            None => *change,
+        })
+        .collect();
+    write_changes(app, &changes)
+}
+
+/// 返回正文已替换模板变量的 agent 副本
+pub fn render_agent(agent: &AgentDefinition, vars: &TemplateVars) -> AgentDefinition {
+    AgentDefinition {
+        content: render_template(&agent.content, vars),
+        ..agent.clone()
+    }
+}
+
+fn write_changes(app: &AppType, changes: &[AgentChange<'_>]) -> Result<(), AppError> {
+    match app {
+        AppType::Codex => codex::apply_changes(changes),
+        AppType::Gemini => gemini::apply_changes(changes),
+        AppType::OpenClaw => openclaw::apply_changes(changes),
+        AppType::Claude => changes.iter().try_for_each(|change| match change {
+            AgentChange::Upsert(agent) => claude::write_agent(agent),
+            AgentChange::Remove(id) => claude::remove_agent(id),
+        }),
+        AppType::OpenCode => changes.iter().try_for_each(|change| match change {
+            AgentChange::Upsert(agent) => opencode::write_agent(agent),
+            AgentChange::Remove(id) => opencode::remove_agent(id),
+        }),
+    }
+}
+
+/// 写入（`content` 为 Some）或删除独立成文件的 agent，写入前按外部修改策略检查
+///
+/// 文件被外部修改且策略为保留时不做任何改动，见 [`crate::external_edits`]。
+fn write_agent_file(
+    app: &AppType,
+    id: &str,
+    path: &Path,
+    content: Option<&str>,
+) -> Result<(), AppError> {
+    let unit = ManagedUnit {
+        app,
+        kind: ManagedKind::Agent,
+        id,
+        path,
+    };
+    let current = if path.is_file() {
+        Some(std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?)
+    } else {
+        None
+    };
+    if !external_edits::before_write(&unit, current.as_deref(), content)? {
+        return Ok(());
+    }
+    match content {
+        Some(content) => write_text_file(path, content)?,
+        None => delete_file(path)?,
+    }
+    external_edits::after_write(&unit, content);
+    Ok(())
+}
+
+/// 去掉共享文件内容中 cc-switch 写入的 agent 区块（导入提示词时使用）
+pub fn strip_managed_agent_blocks(content: &str) -> String {
+    marker_file::strip_managed(content)
+}
+
+/// 按当前的位置设置整理 Codex `AGENTS.md` 中的托管区域（不改变区块内容）
+pub fn relayout_codex_agents() -> Result<(), AppError> {
+    if crate::settings::is_app_sync_paused(&AppType::Codex) {
+        return Ok(());
+    }
+    codex::apply_changes(&[])
+}
+
+/// 按 `ids` 的顺序重排共享 marker 文件中的 agent 区块
+///
+/// 每个 agent 独立成文件的工具没有顺序概念，直接返回；该工具已暂停同步时同样不做改动。
+pub fn reorder_agent_blocks(app: &AppType, ids: &[&str]) -> Result<(), AppError> {
+    if crate::settings::is_app_sync_paused(app) {
+        return Ok(());
+    }
+    let Some(path) = shared_agents_file(app) else {
+        return Ok(());
+    };
+    marker_file::reorder_blocks(&path, ids)?;
+    Ok(())
+}
+
+/// agent 在指定工具中的写入位置
+#[derive(Debug, Clone)]
+pub struct AgentFileTarget {
+    pub path: PathBuf,
+    /// 共享 marker 文件中的区块起止标记；独立文件的工具为 None
+    pub markers: Option<(String, String)>,
+}
+
+/// 计算 agent 同步到指定工具时会写入的位置（不读写文件）
+pub fn agent_file_target(id: &str, app: &AppType) -> AgentFileTarget {
+    let shared = |path: PathBuf| AgentFileTarget {
+        path,
+        markers: Some((marker_file::start_marker(id), marker_file::end_marker(id))),
+    };
+    match app {
+        AppType::Claude => AgentFileTarget {
+            path: claude::agent_path(id),
+            markers: None,
+        },
+        AppType::OpenCode => AgentFileTarget {
+            path: opencode::agent_path(id),
+            markers: None,
+        },
+        AppType::Codex => shared(codex::agents_file_path()),
+        AppType::Gemini => match gemini::output() {
+            GeminiAgentOutput::Markers => shared(gemini::agents_file_path()),
+            GeminiAgentOutput::Files => AgentFileTarget {
+                path: gemini::agent_path(id),
+                markers: None,
+            },
+        },
+        AppType::OpenClaw => shared(openclaw::agents_file_path()),
+    }
+}
+
+/// 推演把 `agents`（已渲染，按优先级排序）完整同步到共享 marker 文件后的内容（不写入）
+///
+/// `original` 为该文件当前内容；每个 agent 独立成文件的工具返回 None。
+pub fn compose_shared_file(
+    app: &AppType,
+    original: &str,
+    agents: &[AgentDefinition],
+) -> Option<String> {
+    match app {
+        AppType::Codex => Some(marker_file::compose(
+            original,
+            agents,
+            Some(crate::settings::get_codex_agents_placement()),
+        )),
+        AppType::Gemini | AppType::OpenClaw if shared_agents_file(app).is_some() => {
+            Some(marker_file::compose(original, agents, None))
+        }
+        _ => None,
+    }
+}
 
-/// 将 Agent 同步到指定工具
-pub fn sync_agent_to_app(agent: &AgentDefinition, app: &AppType) -> Result<(), AppError> {
+/// agent 独立成文件时写入的完整文件内容；共享 marker 文件的工具返回 None
+pub fn agent_file_content(app: &AppType, agent: &AgentDefinition) -> Option<String> {
     match app {
-        AppType::Claude => claude::write_agent(agent),
-        AppType::Codex => codex::write_agent(agent),
-        AppType::Gemini => gemini::write_agent(agent),
-        AppType::OpenCode => opencode::write_agent(agent),
-        AppType::OpenClaw => {
-            log::debug!("OpenClaw agent sync not supported, skipping");
-            Ok(())
+        AppType::Claude => Some(claude::build_frontmatter_md(agent)),
+        AppType::OpenCode => Some(opencode::build_frontmatter_md(agent)),
+        AppType::Gemini if gemini::output() == GeminiAgentOutput::Files => {
+            Some(gemini::build_frontmatter_md(agent))
         }
+        _ => None,
     }
 }
 
+/// 将 Agent 同步到指定工具（正文中的模板变量按 `vars` 替换）
+pub fn sync_agent_to_app(
+    agent: &AgentDefinition,
+    app: &AppType,
+    vars: &TemplateVars,
+) -> Result<(), AppError> {
+    apply_agent_changes(app, &[AgentChange::Upsert(agent)], vars)
+}
+
 /// 从指定工具中移除 Agent
 pub fn remove_agent_from_app(id: &str, app: &AppType) -> Result<(), AppError> {
+    apply_agent_changes(app, &[AgentChange::Remove(id)], &TemplateVars::default())
+}
+
+/// 查找 agent 写入指定工具时会覆盖的非托管文件
+///
+/// 只有每个 agent 独立成文件的工具（Claude / OpenCode / 按文件输出的 Gemini）存在这个问题，
+/// 共享 marker 文件的工具只改写自己的区块。目标文件已存在且内容与即将写入的不同时返回其路径。
+/// 文件是否由 cc-switch 创建（即该 agent 此前是否已同步到该工具）由调用方判断。
+pub fn find_unmanaged_file(
+    agent: &AgentDefinition,
+    app: &AppType,
+    vars: &TemplateVars,
+) -> Result<Option<PathBuf>, AppError> {
+    if agent_files_dir(app).is_none() || crate::settings::is_app_sync_paused(app) {
+        return Ok(None);
+    }
+    let snapshot = inspect_agent_file(agent, app, vars)?;
+    if snapshot.actual.is_some() && snapshot.is_drifted() {
+        Ok(Some(snapshot.path))
+    } else {
+        Ok(None)
+    }
+}
+
+/// 工具文件中某个 agent 的实际内容与 cc-switch 期望写入内容的对照
+#[derive(Debug, Clone)]
+pub struct AgentFileSnapshot {
+    /// 对应的文件路径（共享 marker 文件的工具为整个文件）
+    pub path: PathBuf,
+    /// 按数据库定义渲染出的内容，日期变量为通配占位（见 [`matches_rendered`]）
+    pub expected: String,
+    /// 文件（或 marker 区块）中的实际内容，不存在时为 None
+    pub actual: Option<String>,
+    /// 按 [`external_edits::edit_status`] 判断的实际内容是否偏离上次写入的内容；
+    /// 没有写入记录（旧版本写入、手工创建的文件）时为 None
+    pub edited_externally: Option<bool>,
+}
+
+impl AgentFileSnapshot {
+    /// 实际内容是否与期望内容不一致（包括文件或区块缺失）
+    ///
+    /// 与期望内容不同、且写入记录（与写入前的冲突检查同一依据）没有表明内容出自 cc-switch 时才算漂移。
+    /// 来源横幅不参与比较，开关横幅设置后尚未重新同步的文件不算漂移。
+    pub fn is_drifted(&self) -> bool {
+        match &self.actual {
+            Some(actual) => {
+                self.edited_externally != Some(false)
+                    && !matches_rendered(&strip_banners(&self.expected), &strip_banners(actual))
+            }
+            None => true,
+        }
+    }
+}
+
+/// 读取 agent 在指定工具中的实际内容，并与期望内容一起返回
+///
+/// 期望内容按 `vars` 替换模板变量，与同步时写入的内容保持一致；日期变量不参与比较，
+/// 以免前一天写入的文件被误判为外部修改。
+pub fn inspect_agent_file(
+    agent: &AgentDefinition,
+    app: &AppType,
+    vars: &TemplateVars,
+) -> Result<AgentFileSnapshot, AppError> {
+    let agent = &render_agent(agent, &vars.with_volatile_wildcards());
+    let (path, expected, actual) = match app {
+        AppType::Claude => whole_file_snapshot(
+            claude::agent_path(&agent.id),
+            claude::build_frontmatter_md(agent),
+        )?,
+        AppType::OpenCode => whole_file_snapshot(
+            opencode::agent_path(&agent.id),
+            opencode::build_frontmatter_md(agent),
+        )?,
+        AppType::Codex => marker_block_snapshot(codex::agents_file_path(), agent)?,
+        AppType::Gemini => match gemini::output() {
+            GeminiAgentOutput::Markers => marker_block_snapshot(gemini::agents_file_path(), agent)?,
+            GeminiAgentOutput::Files => whole_file_snapshot(
+                gemini::agent_path(&agent.id),
+                gemini::build_frontmatter_md(agent),
+            )?,
+        },
+        AppType::OpenClaw => marker_block_snapshot(openclaw::agents_file_path(), agent)?,
+    };
+    let unit = ManagedUnit {
+        app,
+        kind: ManagedKind::Agent,
+        id: &agent.id,
+        path: &path,
+    };
+    let edited_externally = external_edits::edit_status(&unit, actual.as_deref());
+    Ok(AgentFileSnapshot {
+        path,
+        expected,
+        actual,
+        edited_externally,
+    })
+}
+
+fn whole_file_snapshot(
+    path: PathBuf,
+    expected: String,
+) -> Result<(PathBuf, String, Option<String>), AppError> {
+    let actual = if path.exists() {
+        Some(std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?)
+    } else {
+        None
+    };
+    Ok((path, expected, actual))
+}
+
+fn marker_block_snapshot(
+    path: PathBuf,
+    agent: &AgentDefinition,
+) -> Result<(PathBuf, String, Option<String>), AppError> {
+    let actual = marker_file::read_block(&path, &agent.id)?;
+    Ok((path, marker_file::build_block(agent), actual))
+}
+
+/// 将工具文件中的 agent 内容解析回定义
+///
+/// 以 `agent` 为基础，只覆盖该工具文件格式中携带的字段：
+/// Claude 包含 frontmatter 全部字段，OpenCode 与按文件输出的 Gemini 只有 name / description，
+/// marker 区块只有标题与正文。
+pub fn parse_agent_file(app: &AppType, agent: &AgentDefinition, raw: &str) -> AgentDefinition {
+    let per_file = match app {
+        AppType::Claude | AppType::OpenCode => true,
+        AppType::Gemini => gemini::output() == GeminiAgentOutput::Files,
+        AppType::Codex | AppType::OpenClaw => false,
+    };
+    if per_file {
+        parse_frontmatter_file(app, agent, raw)
+    } else {
+        parse_marker_block(agent, raw)
+    }
+}
+
+fn parse_frontmatter_file(app: &AppType, agent: &AgentDefinition, raw: &str) -> AgentDefinition {
+    let non_empty = |v: Option<String>| v.filter(|s| !s.trim().is_empty());
+    let mut parsed = agent.clone();
+    let (meta, body) = parse_frontmatter_md(raw);
+    if let Some(name) = non_empty(meta.name) {
+        parsed.name = name;
+    }
+    parsed.description = non_empty(meta.description);
+    parsed.content = body;
+    if matches!(app, AppType::Claude) {
+        parsed.tools = non_empty(meta.tools);
+        parsed.model = non_empty(meta.model);
+        parsed.color = non_empty(meta.color);
+    }
+    parsed
+}
+
+fn parse_marker_block(agent: &AgentDefinition, raw: &str) -> AgentDefinition {
+    let mut parsed = agent.clone();
+    let (name, content) = marker_file::parse_block(raw);
+    if let Some(name) = name {
+        parsed.name = name;
+    }
+    parsed.content = content;
+    parsed
+}
+
+/// 切换 Gemini agent 输出方式时迁移已写入的内容
+///
+/// 对 `agents` 中的每一项：旧方式下已有内容时按原样解析迁移（保留用户在文件中的修改），
+/// 否则按数据库定义渲染；先写入新位置再从旧位置移除。返回迁移的数量。
+pub fn migrate_gemini_output(
+    agents: &[AgentDefinition],
+    from: GeminiAgentOutput,
+    to: GeminiAgentOutput,
+    vars: &TemplateVars,
+) -> Result<usize, AppError> {
+    if from == to || agents.is_empty() {
+        return Ok(0);
+    }
+    let mut migrated = Vec::with_capacity(agents.len());
+    for agent in agents {
+        let rendered = render_agent(agent, vars);
+        let current = match gemini::read_raw(from, &agent.id)? {
+            Some(raw) => match from {
+                GeminiAgentOutput::Markers => parse_marker_block(&rendered, &raw),
+                GeminiAgentOutput::Files => {
+                    parse_frontmatter_file(&AppType::Gemini, &rendered, &raw)
+                }
+            },
+            None => rendered,
+        };
+        migrated.push(current);
+    }
+
+    let upserts: Vec<AgentChange<'_>> = migrated.iter().map(AgentChange::Upsert).collect();
+    gemini::apply_changes_with(to, &upserts)?;
+    let removals: Vec<AgentChange<'_>> = agents
+        .iter()
+        .map(|agent| AgentChange::Remove(&agent.id))
+        .collect();
+    gemini::apply_changes_with(from, &removals)?;
+    Ok(migrated.len())
+}
+
+/// 每个 agent 独立成文件的工具的 agent 目录（共享 marker 文件的工具返回 None）
+pub fn agent_files_dir(app: &AppType) -> Option<PathBuf> {
+    match app {
+        AppType::Claude => Some(claude::agents_dir()),
+        AppType::OpenCode => Some(opencode::agents_dir()),
+        AppType::Gemini if gemini::output() == GeminiAgentOutput::Files => {
+            Some(gemini::agents_dir())
+        }
+        AppType::Codex | AppType::Gemini | AppType::OpenClaw => None,
+    }
+}
+
+/// 共享 marker 文件的工具的 agent 文件路径（每个 agent 独立成文件的工具返回 None）
+pub fn shared_agents_file(app: &AppType) -> Option<PathBuf> {
     match app {
-        AppType::Claude => claude::remove_agent(id),
-        AppType::Codex => codex::remove_agent(id),
-        AppType::Gemini => gemini::remove_agent(id),
-        AppType::OpenCode => opencode::remove_agent(id),
-        AppType::OpenClaw => {
-            log::debug!("OpenClaw agent remove not supported, skipping");
-            Ok(())
+        AppType::Codex => Some(codex::agents_file_path()),
+        AppType::Gemini if gemini::output() == GeminiAgentOutput::Markers => {
+            Some(gemini::agents_file_path())
+        }
+        AppType::OpenClaw => Some(openclaw::agents_file_path()),
+        AppType::Claude | AppType::OpenCode | AppType::Gemini => None,
+    }
+}
+
+/// cc-switch 可能写入 agent 的所有文件：共享 marker 文件与各 agent 目录下的 `*.md`
+///
+/// 不区分 Gemini 当前的输出方式，两种位置都会列出；不存在的共享文件同样返回，由调用方判断。
+pub fn managed_agent_files() -> Result<Vec<PathBuf>, AppError> {
+    let mut files = vec![
+        codex::agents_file_path(),
+        gemini::agents_file_path(),
+        openclaw::agents_file_path(),
+    ];
+    for dir in [
+        claude::agents_dir(),
+        opencode::agents_dir(),
+        gemini::agents_dir(),
+    ] {
+        if !dir.is_dir() {
+            continue;
+        }
+        let entries = std::fs::read_dir(&dir).map_err(|e| AppError::io(&dir, e))?;
+        files.extend(entries.flatten().map(|entry| entry.path()).filter(|path| {
+            path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("md")
+        }));
+    }
+    Ok(files)
+}
+
+/// 读取工具 agent 目录下的所有 `*.md` 文件，返回 (id, 文件内容)
+///
+/// id 取自文件名（去掉 `.md` 后缀），按 id 排序；目录不存在时返回空列表。
+pub fn read_agent_files(app: &AppType) -> Result<Vec<(String, String)>, AppError> {
+    let Some(dir) = agent_files_dir(app) else {
+        return Ok(Vec::new());
+    };
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    let entries = std::fs::read_dir(&dir).map_err(|e| AppError::io(&dir, e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(content) => files.push((id.to_string(), content)),
+            Err(e) => log::warn!("读取 agent 文件失败: {}: {e}", path.display()),
         }
     }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
 }
@@ -0,0 +1,22 @@
+//! OpenClaw agent 文件同步
+//!
+//! 写入路径：`~/.openclaw/workspace/AGENTS.md`（共享文件，每个 agent 占一个 marker 区块，格式见 [`super::marker_file`]）
+//!
+//! OpenClaw 在每次会话开始时加载工作区中的 `AGENTS.md` 作为 agent 指令，
+//! 因此与 Codex / Gemini 一样采用 marker 区块，保留用户手写内容。
+
+use super::{marker_file, AgentChange};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::openclaw_config::get_openclaw_dir;
+use std::path::PathBuf;
+
+pub(super) fn agents_file_path() -> PathBuf {
+    get_openclaw_dir().join("workspace").join("AGENTS.md")
+}
+
+/// 将一批区块变更合并写入 `~/.openclaw/workspace/AGENTS.md`
+pub fn apply_changes(changes: &[AgentChange<'_>]) -> Result<(), AppError> {
+    marker_file::apply_changes(&AppType::OpenClaw, &agents_file_path(), changes)?;
+    Ok(())
+}
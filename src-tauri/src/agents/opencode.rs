@@ -3,42 +3,51 @@
 //! 写入路径：`~/.config/opencode/agents/{id}.md`
 //! 格式：YAML frontmatter（name, description）+ Markdown body（content）
 
+use super::frontmatter::push_field;
+use super::write_agent_file;
 use crate::agent::AgentDefinition;
-use crate::config::write_text_file;
+use crate::app_config::AppType;
 use crate::error::AppError;
+use crate::managed_banner::banner_for;
 use crate::opencode_config::get_opencode_dir;
 use std::path::PathBuf;
 
-fn agent_path(id: &str) -> PathBuf {
-    get_opencode_dir().join("agents").join(format!("{id}.md"))
+/// agent 文件所在目录
+pub(crate) fn agents_dir() -> PathBuf {
+    get_opencode_dir().join("agents")
+}
+
+pub(super) fn agent_path(id: &str) -> PathBuf {
+    agents_dir().join(format!("{id}.md"))
 }
 
 /// 写入 `~/.config/opencode/agents/{id}.md`
 pub fn write_agent(agent: &AgentDefinition) -> Result<(), AppError> {
-    let path = agent_path(&agent.id);
     let content = build_frontmatter_md(agent);
-    write_text_file(&path, &content)
+    write_agent_file(
+        &AppType::OpenCode,
+        &agent.id,
+        &agent_path(&agent.id),
+        Some(&content),
+    )
 }
 
 /// 删除 `~/.config/opencode/agents/{id}.md`（不存在时静默忽略）
 pub fn remove_agent(id: &str) -> Result<(), AppError> {
-    let path = agent_path(id);
-    if path.exists() {
-        std::fs::remove_file(&path).map_err(|e| AppError::io(&path, e))?;
-    }
-    Ok(())
+    write_agent_file(&AppType::OpenCode, id, &agent_path(id), None)
 }
 
-fn build_frontmatter_md(agent: &AgentDefinition) -> String {
+pub(super) fn build_frontmatter_md(agent: &AgentDefinition) -> String {
     let mut fm = String::from("---\n");
-    fm.push_str(&format!("name: {}\n", agent.name));
+    push_field(&mut fm, "name", &agent.name);
     if let Some(desc) = &agent.description {
         if !desc.is_empty() {
-            fm.push_str(&format!("description: {}\n", desc));
+            push_field(&mut fm, "description", desc);
         }
     }
     fm.push_str("---\n");
     fm.push('\n');
+    fm.push_str(&banner_for("agent", &agent.id));
     fm.push_str(&agent.content);
     if !fm.ends_with('\n') {
         fm.push('\n');
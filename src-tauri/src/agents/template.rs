@@ -0,0 +1,222 @@
+//! Agent 与提示词正文中的模板变量
+//!
+//! 同步时替换以下变量，让写入的文件反映当前工具正在使用的供应商、模型与运行环境：
+//! - `{{today}}` / `{{date}}`：当天日期
+//! - `{{provider_name}}` / `{{active_provider}}`：该工具当前供应商名称
+//! - `{{model}}`：当前供应商配置的模型
+//! - `{{os}}`：操作系统（macOS / Windows / Linux）
+//! - `{{app}}`：写入的目标工具（如 `claude`）
+//!
+//! 数据库中始终保存未替换的原文。
+//! 未知占位符原样保留；变量没有取值（例如该工具尚未选择供应商）时替换为空字符串。
+//!
+//! 日期变量的取值每天都会变化，判断文件是否被外部修改时按任意日期匹配（见 [`matches_rendered`]），
+//! 否则前一天写入的文件第二天就会被误报为漂移。
+
+/// 比较时代替日期取值的通配占位，与真实日期同形，渲染和编码方式保持一致
+const DATE_WILDCARD: &str = "0000-00-00";
+
+/// 同步到某个工具时可用的模板变量取值
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateVars {
+    /// 当天日期（YYYY-MM-DD，本地时区）
+    pub today: String,
+    /// 该工具当前供应商名称
+    pub provider_name: Option<String>,
+    /// 该工具当前供应商配置的模型
+    pub model: Option<String>,
+    /// 操作系统名称，见 [`os_name`]
+    pub os: String,
+    /// 目标工具（`AppType::as_str`）
+    pub app: String,
+}
+
+impl TemplateVars {
+    /// 把随日期变化的变量换成通配占位，渲染结果配合 [`matches_rendered`] 使用
+    pub fn with_volatile_wildcards(&self) -> Self {
+        Self {
+            today: DATE_WILDCARD.to_string(),
+            ..self.clone()
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&str> {
+        match name {
+            "today" | "date" => Some(self.today.as_str()),
+            "provider_name" | "active_provider" => {
+                Some(self.provider_name.as_deref().unwrap_or(""))
+            }
+            "model" => Some(self.model.as_deref().unwrap_or("")),
+            "os" => Some(self.os.as_str()),
+            "app" => Some(self.app.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// 当前操作系统的展示名称
+pub fn os_name() -> &'static str {
+    match std::env::consts::OS {
+        "macos" => "macOS",
+        "windows" => "Windows",
+        "linux" => "Linux",
+        other => other,
+    }
+}
+
+/// 替换正文中的模板变量（占位符内允许首尾空白，如 `{{ model }}`）
+pub fn render_template(content: &str, vars: &TemplateVars) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        match vars.lookup(after[..end].trim()) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `actual` 是否与用 [`TemplateVars::with_volatile_wildcards`] 渲染出的 `pattern` 一致
+///
+/// 日期通配占位匹配任意 `YYYY-MM-DD` 日期，其余部分须完全相同。
+pub fn matches_rendered(pattern: &str, actual: &str) -> bool {
+    let mut parts = pattern.split(DATE_WILDCARD);
+    let Some(mut rest) = actual.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    for part in parts {
+        let Some(date) = rest.get(..DATE_WILDCARD.len()) else {
+            return false;
+        };
+        if !is_date(date) {
+            return false;
+        }
+        let Some(after) = rest[DATE_WILDCARD.len()..].strip_prefix(part) else {
+            return false;
+        };
+        rest = after;
+    }
+    rest.is_empty()
+}
+
+fn is_date(s: &str) -> bool {
+    s.len() == DATE_WILDCARD.len()
+        && s.bytes().enumerate().all(|(i, b)| match i {
+            4 | 7 => b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+/// 把从文件拉回的正文中未改动的模板行换回模板原文
+///
+/// 逐行比较：与 `template` 中某个含变量的行的渲染结果一致（日期按任意日期匹配）的行换回该模板行，
+/// 避免把变量固化成写入当时的取值；被外部改动过的行保留文件中的内容。
+pub fn restore_template_lines(edited: &str, template: &str, vars: &TemplateVars) -> String {
+    let wildcards = vars.with_volatile_wildcards();
+    let templated: Vec<(&str, String)> = template
+        .lines()
+        .map(|line| (line, render_template(line, &wildcards)))
+        .filter(|(line, rendered)| line != rendered)
+        .collect();
+    if templated.is_empty() {
+        return edited.to_string();
+    }
+
+    edited
+        .split_inclusive('\n')
+        .map(|line| {
+            let body = line.trim_end_matches(['\r', '\n']);
+            match templated
+                .iter()
+                .find(|(_, pattern)| matches_rendered(pattern, body))
+            {
+                Some((original, _)) => format!("{original}{}", &line[body.len()..]),
+                None => line.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_known_placeholders_and_keeps_unknown() {
+        let vars = TemplateVars {
+            today: "2026-01-02".to_string(),
+            provider_name: Some("Acme".to_string()),
+            model: None,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_template(
+                "{{today}} via {{ provider_name }} ({{model}}) {{other}} {{open",
+                &vars
+            ),
+            "2026-01-02 via Acme () {{other}} {{open"
+        );
+        assert_eq!(render_template("no vars", &vars), "no vars");
+    }
+
+    #[test]
+    fn prompt_aliases_resolve_to_the_same_values() {
+        let vars = TemplateVars {
+            today: "2026-01-02".to_string(),
+            provider_name: Some("Acme".to_string()),
+            os: "Linux".to_string(),
+            app: "codex".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_template("{{date}} {{active_provider}} {{os}} {{app}}", &vars),
+            "2026-01-02 Acme Linux codex"
+        );
+    }
+
+    #[test]
+    fn date_wildcard_matches_any_day_but_nothing_else() {
+        let vars = TemplateVars {
+            today: "2026-01-02".to_string(),
+            provider_name: Some("Acme".to_string()),
+            ..Default::default()
+        };
+        let pattern = render_template(
+            "Today is {{today}} on {{provider_name}}",
+            &vars.with_volatile_wildcards(),
+        );
+        assert!(matches_rendered(&pattern, "Today is 2026-01-02 on Acme"));
+        assert!(matches_rendered(&pattern, "Today is 2025-12-31 on Acme"));
+        assert!(!matches_rendered(&pattern, "Today is soon on Acme"));
+        assert!(!matches_rendered(&pattern, "Today is 2025-12-31 on Other"));
+        assert!(!matches_rendered(&pattern, "Today is 2025-12-31 on Acme!"));
+    }
+
+    #[test]
+    fn restore_template_lines_keeps_edits_and_unfreezes_variables() {
+        let vars = TemplateVars {
+            today: "2026-01-03".to_string(),
+            provider_name: Some("Acme".to_string()),
+            ..Default::default()
+        };
+        let template = "Date: {{today}}\nUse {{provider_name}}.\nBe brief.\n";
+        let edited = "Date: 2026-01-02\nUse Acme.\nBe very brief.\nNew line\n";
+        assert_eq!(
+            restore_template_lines(edited, template, &vars),
+            "Date: {{today}}\nUse {{provider_name}}.\nBe very brief.\nNew line\n"
+        );
+        assert_eq!(
+            restore_template_lines("Use Other.\n", template, &vars),
+            "Use Other.\n"
+        );
+    }
+}
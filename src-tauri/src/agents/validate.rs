@@ -0,0 +1,147 @@
+//! Agent 定义写入前的校验
+//!
+//! `name` / `description` 会原样写进 YAML frontmatter（`name: {value}`），
+//! 值里出现 `: `、` #`、开头的引号等字符时生成的文件无法被 CLI 解析。
+//! 这里在保存前拦下这些输入，而不是写出损坏的文件。
+
+use crate::agent::{AgentDefinition, AgentValidationIssue};
+
+/// id 最大长度（用作文件名）
+const MAX_ID_LEN: usize = 64;
+
+/// 校验 Agent 定义，返回全部问题（为空表示通过）
+pub fn validate_agent(agent: &AgentDefinition) -> Vec<AgentValidationIssue> {
+    let mut issues = Vec::new();
+
+    if !is_safe_slug(&agent.id) {
+        issues.push(issue(
+            "id",
+            "invalid_slug",
+            format!(
+                "id \"{}\" 不合法：只能包含字母、数字、- 和 _，以字母或数字开头，且不超过 {MAX_ID_LEN} 个字符",
+                agent.id
+            ),
+        ));
+    }
+
+    if agent.name.trim().is_empty() {
+        issues.push(issue("name", "empty", "名称不能为空".to_string()));
+    } else if let Some(code) = yaml_problem(&agent.name) {
+        issues.push(yaml_issue("name", "名称", code));
+    }
+
+    if let Some(desc) = agent.description.as_deref().filter(|d| !d.is_empty()) {
+        if let Some(code) = yaml_problem(desc) {
+            issues.push(yaml_issue("description", "描述", code));
+        }
+    }
+
+    if agent.content.trim().is_empty() {
+        issues.push(issue("content", "empty", "正文不能为空".to_string()));
+    }
+
+    issues
+}
+
+fn is_safe_slug(id: &str) -> bool {
+    id.len() <= MAX_ID_LEN
+        && id.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// 判断值作为未加引号的 YAML 标量写入后能否原样读回
+///
+/// 返回问题代码：`multiline`（含换行）或 `unsafe_yaml`（解析失败或读回的值不同）。
+fn yaml_problem(value: &str) -> Option<&'static str> {
+    if value.contains(['\n', '\r']) {
+        return Some("multiline");
+    }
+    let parsed = serde_yaml::from_str::<serde_yaml::Mapping>(&format!("v: {value}")).ok();
+    let round_trip = parsed
+        .as_ref()
+        .and_then(|map| map.get("v"))
+        .and_then(serde_yaml::Value::as_str);
+    if round_trip == Some(value.trim()) {
+        None
+    } else {
+        Some("unsafe_yaml")
+    }
+}
+
+fn yaml_issue(field: &str, label: &str, code: &'static str) -> AgentValidationIssue {
+    let message = match code {
+        "multiline" => format!("{label}不能包含换行"),
+        _ => format!(
+            "{label}包含会破坏 YAML frontmatter 的内容（如 \": \"、\" #\"、开头的引号或 - 等），或会被解析为非文本值"
+        ),
+    };
+    issue(field, code, message)
+}
+
+fn issue(field: &str, code: &str, message: String) -> AgentValidationIssue {
+    AgentValidationIssue {
+        field: field.to_string(),
+        code: code.to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(id: &str, name: &str, description: Option<&str>, content: &str) -> AgentDefinition {
+        AgentDefinition {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.map(str::to_string),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn codes(agent: &AgentDefinition) -> Vec<(String, String)> {
+        validate_agent(agent)
+            .into_iter()
+            .map(|i| (i.field, i.code))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_plain_definition() {
+        let a = agent(
+            "code-reviewer_2",
+            "Code Reviewer",
+            Some("Reviews diffs, flags bugs (and style)"),
+            "You review code.",
+        );
+        assert!(validate_agent(&a).is_empty());
+    }
+
+    #[test]
+    fn rejects_unsafe_fields() {
+        let a = agent("../evil", "Reviewer: strict", Some("\"quoted"), "  \n");
+        assert_eq!(
+            codes(&a),
+            vec![
+                ("id".to_string(), "invalid_slug".to_string()),
+                ("name".to_string(), "unsafe_yaml".to_string()),
+                ("description".to_string(), "unsafe_yaml".to_string()),
+                ("content".to_string(), "empty".to_string()),
+            ]
+        );
+
+        for name in ["- item", "true", "a #comment", "line\nbreak"] {
+            assert_eq!(codes(&agent("ok", name, None, "x")).len(), 1, "{name}");
+        }
+        assert_eq!(
+            codes(&agent("-lead", " ", None, "x")),
+            vec![
+                ("id".to_string(), "invalid_slug".to_string()),
+                ("name".to_string(), "empty".to_string()),
+            ]
+        );
+    }
+}
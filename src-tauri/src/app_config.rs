@@ -208,8 +208,36 @@ pub struct McpServer {
     pub docs: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    /// 外部来源；有值时为只读条目，由扫描结果维护，见 [`McpOrigin`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<McpOrigin>,
 }
 
+impl McpServer {
+    /// 是否为外部来源的只读条目
+    pub fn is_read_only(&self) -> bool {
+        self.origin.is_some()
+    }
+}
+
+/// MCP 服务器的外部来源
+///
+/// 目前只有 Gemini CLI 扩展自带的服务器：它们由 Gemini CLI 从扩展目录直接加载，
+/// cc-switch 只展示，不写入任何 live 配置，也不允许编辑、删除或切换启用状态。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpOrigin {
+    /// 来源类型，见 [`MCP_ORIGIN_GEMINI_EXTENSION`]
+    pub kind: String,
+    /// 来源名称（扩展名）
+    pub name: String,
+    /// 定义所在的文件
+    pub path: String,
+}
+
+/// Gemini CLI 扩展来源
+pub const MCP_ORIGIN_GEMINI_EXTENSION: &str = "gemini-extension";
+
 /// MCP 配置：单客户端维度（v3.6.x 及以前，保留用于向后兼容）
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct McpConfig {
@@ -286,8 +314,9 @@ pub struct PromptRoot {
 
 use crate::config::{copy_file, get_app_config_dir, get_app_config_path, write_json_file};
 use crate::error::AppError;
-use crate::prompt_files::prompt_file_path;
+use crate::prompt_files::{normalize_imported_prompt, prompt_file_path};
 use crate::provider::ProviderManager;
+use crate::services::id_generator::{local_time_label, IdGenerator, IdKind};
 
 /// 应用类型
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -618,7 +647,9 @@ impl MultiAppConfig {
 
         // 读取文件内容
         let content = match std::fs::read_to_string(&file_path) {
-            Ok(c) => c,
+            Ok(c) => {
+                normalize_imported_prompt(&c, &crate::settings::get_prompt_import_normalization())
+            }
             Err(e) => {
                 log::warn!("读取提示词文件失败: {file_path:?}, 错误: {e}");
                 return Ok(false); // 失败时不中断，继续处理其他应用
@@ -642,26 +673,31 @@ impl MultiAppConfig {
                 0
             });
 
-        let id = format!("auto-imported-{timestamp}");
+        let id = {
+            let existing = match app {
+                AppType::Claude => &config.prompts.claude.prompts,
+                AppType::Codex => &config.prompts.codex.prompts,
+                AppType::Gemini => &config.prompts.gemini.prompts,
+                AppType::OpenCode => &config.prompts.opencode.prompts,
+                AppType::OpenClaw => &config.prompts.openclaw.prompts,
+            };
+            IdGenerator::new(IdKind::AutoImportedPrompt)
+                .app(&app)
+                .generate(|id| Ok(existing.contains_key(id)))?
+        };
 
         // 构建 apps 标志：只启用当前 app
         let mut apps = crate::prompt::PromptApps::default();
-        match app {
-            AppType::Claude => apps.claude = true,
-            AppType::Codex => apps.codex = true,
-            AppType::Gemini => apps.gemini = true,
-            AppType::OpenCode | AppType::OpenClaw => apps.opencode = true,
-        }
+        apps.set_enabled_for(&app, true);
 
         let prompt = crate::prompt::Prompt {
             id: id.clone(),
-            name: format!(
-                "Auto-imported Prompt {}",
-                chrono::Local::now().format("%Y-%m-%d %H:%M")
-            ),
+            name: format!("Auto-imported Prompt {}", local_time_label()),
             content,
             description: Some("Automatically imported on first launch".to_string()),
+            tags: Vec::new(),
             apps,
+            sort_index: None,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
@@ -784,6 +820,7 @@ impl MultiAppConfig {
                             homepage,
                             docs,
                             tags,
+                            origin: None,
                         },
                     );
                 }
@@ -890,7 +927,7 @@ mod tests {
             .values()
             .next()
             .expect("prompt exists");
-        assert!(prompt.enabled);
+        assert!(prompt.apps.claude);
         assert_eq!(prompt.content, "# hello");
 
         let config_path = crate::config::get_app_config_path();
@@ -966,7 +1003,7 @@ mod tests {
             .values()
             .next()
             .expect("gemini prompt exists");
-        assert!(prompt.enabled, "gemini prompt should be enabled");
+        assert!(prompt.apps.gemini, "gemini prompt should be enabled");
         assert_eq!(prompt.content, "# Gemini Prompt\n\nTest content");
         assert_eq!(
             prompt.description,
@@ -998,7 +1035,8 @@ mod tests {
                 .values()
                 .next()
                 .unwrap()
-                .enabled
+                .apps
+                .claude
         );
         assert!(
             config
@@ -1008,7 +1046,8 @@ mod tests {
                 .values()
                 .next()
                 .unwrap()
-                .enabled
+                .apps
+                .codex
         );
         assert!(
             config
@@ -1018,7 +1057,8 @@ mod tests {
                 .values()
                 .next()
                 .unwrap()
-                .enabled
+                .apps
+                .gemini
         );
     }
 }
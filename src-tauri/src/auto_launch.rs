@@ -31,9 +31,11 @@ fn get_auto_launch() -> Result<AutoLaunch, AppError> {
     // 使用 AutoLaunchBuilder 消除平台差异
     // macOS: 使用 AppleScript 方式（默认），需要 .app bundle 路径
     // Windows/Linux: 使用注册表/XDG autostart
+    // 附带 `--autostart`，启动时据此判断是否进入后台模式（macOS 登录项会忽略参数）
     let auto_launch = AutoLaunchBuilder::new()
         .set_app_name(app_name)
         .set_app_path(&app_path.to_string_lossy())
+        .set_args(&[crate::startup::AUTOSTART_ARG])
         .build()
         .map_err(|e| AppError::Message(format!("创建 AutoLaunch 失败: {e}")))?;
 
@@ -60,6 +62,24 @@ pub fn disable_auto_launch() -> Result<(), AppError> {
     Ok(())
 }
 
+/// 按设置校正系统中的开机自启注册
+///
+/// 设置开启时重新注册，顺带为旧版本注册的启动项补上 `--autostart` 参数；
+/// macOS 登录项不携带参数，已注册时不再重复注册。设置关闭但仍有注册时将其移除。
+pub fn sync_with_setting(enabled: bool) -> Result<(), AppError> {
+    let registered = is_auto_launch_enabled()?;
+    if enabled {
+        if registered && cfg!(target_os = "macos") {
+            return Ok(());
+        }
+        enable_auto_launch()
+    } else if registered {
+        disable_auto_launch()
+    } else {
+        Ok(())
+    }
+}
+
 /// 检查是否已启用开机自启
 pub fn is_auto_launch_enabled() -> Result<bool, AppError> {
     let auto_launch = get_auto_launch()?;
@@ -68,7 +88,7 @@ pub fn is_auto_launch_enabled() -> Result<bool, AppError> {
         .map_err(|e| AppError::Message(format!("检查开机自启状态失败: {e}")))
 }
 
-#[cfg(test)]
+#[cfg(all(test, target_os = "macos"))]
 mod tests {
     use super::*;
 
@@ -6,26 +6,55 @@ use indexmap::IndexMap;
 use std::str::FromStr;
 use tauri::State;
 
-use crate::agent::AgentDefinition;
+use crate::agent::{
+    AgentApps, AgentDefinition, AgentDrift, AgentImportResult, AgentRevision, AgentRevisionDiff,
+    AgentScanResult, AgentValidationIssue, DuplicateStrategy,
+};
+use crate::agents::{self, AgentTemplate, GeminiAgentOutput};
 use crate::app_config::AppType;
-use crate::services::AgentsService;
+use crate::error::CommandError;
+use crate::services::{AgentsMdService, AgentsService};
 use crate::store::AppState;
 
-/// 获取所有 Agent 定义
+/// 获取所有 Agent 定义（`tags` 非空时只返回带有全部指定标签的 agent）
 #[tauri::command]
 pub async fn get_agent_definitions(
     state: State<'_, AppState>,
-) -> Result<IndexMap<String, AgentDefinition>, String> {
-    AgentsService::get_all(&state).map_err(|e| e.to_string())
+    tags: Option<Vec<String>>,
+) -> Result<IndexMap<String, AgentDefinition>, CommandError> {
+    AgentsService::get_all(&state, tags.as_deref().unwrap_or_default()).map_err(CommandError::from)
+}
+
+/// 按关键词搜索 Agent，结果顺序与列表一致（查询为空时返回全部，`tags` 用法同上）
+#[tauri::command]
+pub async fn search_agents(
+    state: State<'_, AppState>,
+    query: String,
+    tags: Option<Vec<String>>,
+) -> Result<Vec<AgentDefinition>, CommandError> {
+    AgentsService::search(&state, &query, tags.as_deref().unwrap_or_default())
+        .map_err(CommandError::from)
 }
 
 /// 新增或更新 Agent 定义
+///
+/// `overwrite_unmanaged` 为 true 时覆盖新启用工具中同名的非 cc-switch 文件，默认拒绝。
 #[tauri::command]
 pub async fn upsert_agent_definition(
     state: State<'_, AppState>,
     agent: AgentDefinition,
-) -> Result<(), String> {
-    AgentsService::upsert(&state, agent).map_err(|e| e.to_string())
+    overwrite_unmanaged: Option<bool>,
+) -> Result<(), CommandError> {
+    AgentsService::upsert(&state, agent, overwrite_unmanaged.unwrap_or(false))
+        .map_err(CommandError::from)
+}
+
+/// 校验 Agent 定义，返回全部问题（为空表示可以保存）
+#[tauri::command]
+pub async fn validate_agent_definition(
+    agent: AgentDefinition,
+) -> Result<Vec<AgentValidationIssue>, CommandError> {
+    Ok(agents::validate_agent(&agent))
 }
 
 /// 删除 Agent 定义
@@ -33,18 +62,218 @@ pub async fn upsert_agent_definition(
 pub async fn delete_agent_definition(
     state: State<'_, AppState>,
     id: String,
-) -> Result<bool, String> {
-    AgentsService::delete(&state, &id).map_err(|e| e.to_string())
+) -> Result<bool, CommandError> {
+    AgentsService::delete(&state, &id).map_err(CommandError::from)
 }
 
 /// 切换 Agent 在指定工具的启用状态
+///
+/// 启用时目标文件已存在且不是 cc-switch 写入的，默认拒绝；`overwrite_unmanaged` 为 true 时覆盖。
 #[tauri::command]
 pub async fn toggle_agent_app(
     state: State<'_, AppState>,
     agent_id: String,
     app: String,
     enabled: bool,
-) -> Result<(), String> {
-    let app_ty = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    AgentsService::toggle_app(&state, &agent_id, app_ty, enabled).map_err(|e| e.to_string())
+    overwrite_unmanaged: Option<bool>,
+) -> Result<(), CommandError> {
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    AgentsService::toggle_app(
+        &state,
+        &agent_id,
+        app_ty,
+        enabled,
+        overwrite_unmanaged.unwrap_or(false),
+    )
+    .map_err(CommandError::from)
+}
+
+/// 批量切换多个 Agent 在多个工具上的启用状态，返回实际变化的 agent id
+#[tauri::command]
+pub async fn bulk_toggle_agents(
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+    apps: AgentApps,
+    enabled: bool,
+    overwrite_unmanaged: Option<bool>,
+) -> Result<Vec<String>, CommandError> {
+    AgentsService::bulk_toggle(
+        &state,
+        &ids,
+        apps,
+        enabled,
+        overwrite_unmanaged.unwrap_or(false),
+    )
+    .map_err(CommandError::from)
+}
+
+/// 按给定 id 顺序调整 Agent 排序
+#[tauri::command]
+pub async fn reorder_agents(
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> Result<(), CommandError> {
+    AgentsService::reorder(&state, &ids).map_err(CommandError::from)
+}
+
+/// 获取内置 Agent 模板
+#[tauri::command]
+pub async fn get_agent_templates() -> Result<Vec<AgentTemplate>, CommandError> {
+    Ok(AgentsService::templates())
+}
+
+/// 基于内置模板新建 Agent
+///
+/// `id` 为空时使用模板 id；`apps` 为空时仅写入数据库，不同步到任何工具。
+#[tauri::command]
+pub async fn create_agent_from_template(
+    state: State<'_, AppState>,
+    template_id: String,
+    id: Option<String>,
+    apps: Option<AgentApps>,
+) -> Result<AgentDefinition, CommandError> {
+    AgentsService::create_from_template(&state, &template_id, id, apps.unwrap_or_default())
+        .map_err(CommandError::from)
+}
+
+/// 获取 Agent 的历史版本列表（最新在前）
+#[tauri::command]
+pub async fn get_agent_history(
+    state: State<'_, AppState>,
+    agent_id: String,
+) -> Result<Vec<AgentRevision>, CommandError> {
+    AgentsService::history(&state, &agent_id).map_err(CommandError::from)
+}
+
+/// 对比 Agent 的两个历史版本
+#[tauri::command]
+pub async fn diff_agent_versions(
+    state: State<'_, AppState>,
+    agent_id: String,
+    from_version: i64,
+    to_version: i64,
+) -> Result<AgentRevisionDiff, CommandError> {
+    AgentsService::diff_versions(&state, &agent_id, from_version, to_version)
+        .map_err(CommandError::from)
+}
+
+/// 将 Agent 回滚到指定历史版本
+#[tauri::command]
+pub async fn rollback_agent(
+    state: State<'_, AppState>,
+    agent_id: String,
+    version: i64,
+) -> Result<AgentDefinition, CommandError> {
+    AgentsService::rollback(&state, &agent_id, version).map_err(CommandError::from)
+}
+
+/// 导出 Agent 到 JSON 导出包文件
+///
+/// `ids` 为空时导出全部 Agent；返回导出的数量。
+#[tauri::command]
+pub async fn export_agents_bundle(
+    state: State<'_, AppState>,
+    file_path: String,
+    ids: Option<Vec<String>>,
+) -> Result<usize, CommandError> {
+    AgentsService::export_bundle_to_file(&state, std::path::Path::new(&file_path), ids.as_deref())
+        .map_err(CommandError::from)
+}
+
+/// 从 JSON 导出包文件导入 Agent
+///
+/// `strategy` 决定已存在 id 的处理方式（skip / overwrite / rename），默认 skip。
+#[tauri::command]
+pub async fn import_agents_bundle(
+    state: State<'_, AppState>,
+    file_path: String,
+    strategy: Option<DuplicateStrategy>,
+) -> Result<AgentImportResult, CommandError> {
+    AgentsService::import_bundle_from_file(
+        &state,
+        std::path::Path::new(&file_path),
+        strategy.unwrap_or_default(),
+    )
+    .map_err(CommandError::from)
+}
+
+/// 扫描工具目录并导入尚未管理的 Agent
+#[tauri::command]
+pub async fn scan_existing_agents(
+    state: State<'_, AppState>,
+) -> Result<AgentScanResult, CommandError> {
+    AgentsService::scan_existing(&state).map_err(CommandError::from)
+}
+
+/// 检查工具中的 Agent 文件是否被外部修改
+#[tauri::command]
+pub async fn check_agent_drift(
+    state: State<'_, AppState>,
+) -> Result<Vec<AgentDrift>, CommandError> {
+    AgentsService::check_drift(&state).map_err(CommandError::from)
+}
+
+/// 将指定工具文件中的外部修改拉回数据库
+#[tauri::command]
+pub async fn pull_agent_from_app(
+    state: State<'_, AppState>,
+    agent_id: String,
+    app: String,
+) -> Result<AgentDefinition, CommandError> {
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    AgentsService::pull_from_app(&state, &agent_id, app_ty).map_err(CommandError::from)
+}
+
+/// 接管指定工具中同名的非 cc-switch 文件，把文件内容导入数据库并对该工具启用
+#[tauri::command]
+pub async fn adopt_agent_file(
+    state: State<'_, AppState>,
+    agent_id: String,
+    app: String,
+) -> Result<AgentDefinition, CommandError> {
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    AgentsService::adopt_unmanaged(&state, &agent_id, app_ty).map_err(CommandError::from)
+}
+
+/// 按数据库定义恢复指定工具中的 agent 文件（丢弃外部修改）
+#[tauri::command]
+pub async fn restore_agent_file(
+    state: State<'_, AppState>,
+    agent_id: String,
+    app: String,
+) -> Result<(), CommandError> {
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    AgentsService::restore_file(&state, &agent_id, app_ty).map_err(CommandError::from)
+}
+
+/// 切换 Gemini agent 输出方式（markers / files），返回迁移的 agent 数量
+#[tauri::command]
+pub async fn set_gemini_agent_output(
+    state: State<'_, AppState>,
+    output: GeminiAgentOutput,
+) -> Result<usize, CommandError> {
+    AgentsService::set_gemini_output(&state, output).map_err(CommandError::from)
+}
+
+/// 将已启用的提示词与 Agent 导出为项目目录下的 AGENTS.md
+///
+/// `app` 为空时导出对任一工具启用的条目；返回写入的文件路径。
+#[tauri::command]
+pub async fn export_agents_md(
+    state: State<'_, AppState>,
+    project_dir: String,
+    app: Option<String>,
+) -> Result<String, CommandError> {
+    let app_ty = app
+        .as_deref()
+        .map(AppType::from_str)
+        .transpose()
+        .map_err(CommandError::from)?;
+    let path = AgentsMdService::export_to_dir(
+        &state,
+        std::path::Path::new(project_dir.trim()),
+        app_ty.as_ref(),
+    )
+    .map_err(CommandError::from)?;
+    Ok(path.display().to_string())
 }
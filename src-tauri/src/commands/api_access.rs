@@ -0,0 +1,29 @@
+//! 本地 API 访问令牌命令
+
+use tauri::State;
+
+use crate::error::CommandError;
+use crate::services::{ApiAccessService, ApiScope, ApiToken, IssuedApiToken};
+use crate::store::AppState;
+
+/// 获取全部 API 令牌
+#[tauri::command]
+pub fn get_api_tokens(state: State<'_, AppState>) -> Result<Vec<ApiToken>, CommandError> {
+    ApiAccessService::list_tokens(&state.db).map_err(CommandError::from)
+}
+
+/// 创建 API 令牌，明文只在返回值中出现一次
+#[tauri::command]
+pub fn create_api_token(
+    state: State<'_, AppState>,
+    name: String,
+    scopes: Vec<ApiScope>,
+) -> Result<IssuedApiToken, CommandError> {
+    ApiAccessService::create_token(&state.db, &name, scopes).map_err(CommandError::from)
+}
+
+/// 吊销 API 令牌
+#[tauri::command]
+pub fn revoke_api_token(state: State<'_, AppState>, id: String) -> Result<bool, CommandError> {
+    ApiAccessService::revoke_token(&state.db, &id).map_err(CommandError::from)
+}
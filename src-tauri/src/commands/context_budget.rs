@@ -0,0 +1,30 @@
+//! 上下文预算命令
+
+use std::str::FromStr;
+
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::error::CommandError;
+use crate::services::{ComposedContext, ContextBudget, ContextBudgetService};
+use crate::store::AppState;
+
+/// 估算指定工具中已启用的提示词、Agent 与 Skill 描述占用的上下文
+#[tauri::command]
+pub async fn get_context_budget(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<ContextBudget, CommandError> {
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    ContextBudgetService::get_context_budget(&state, &app_ty).map_err(CommandError::from)
+}
+
+/// 预览同步后指定工具实际读到的提示词与 Agent 内容（含 cc-switch 标记，不写入文件）
+#[tauri::command]
+pub async fn preview_composed_context(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<ComposedContext, CommandError> {
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    ContextBudgetService::preview_composed_context(&state, &app_ty).map_err(CommandError::from)
+}
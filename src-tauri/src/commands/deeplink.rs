@@ -2,6 +2,7 @@ use crate::deeplink::{
     import_mcp_from_deeplink, import_prompt_from_deeplink, import_provider_from_deeplink,
     import_skill_from_deeplink, parse_deeplink_url, DeepLinkImportRequest,
 };
+use crate::services::ShareLinkService;
 use crate::store::AppState;
 use tauri::State;
 
@@ -84,6 +85,18 @@ pub async fn import_from_deeplink_unified(
                 "key": skill_key
             }))
         }
+        "share" => {
+            let share = request
+                .share
+                .as_ref()
+                .ok_or_else(|| "Missing share reference".to_string())?;
+            let result = ShareLinkService::import_share(&state, share)
+                .await
+                .map_err(|e| e.to_string())?;
+            let mut value = serde_json::to_value(result).map_err(|e| e.to_string())?;
+            value["type"] = serde_json::json!("share");
+            Ok(value)
+        }
         _ => Err(format!("Unsupported resource type: {}", request.resource)),
     }
 }
@@ -3,7 +3,11 @@
 //! 管理代理模式下的故障转移队列（基于 providers 表的 in_failover_queue 字段）
 
 use crate::database::FailoverQueueItem;
+use crate::error::{AppError, CommandError, ErrorCode};
 use crate::provider::Provider;
+use crate::proxy::failure_injection::{self, FailureKind, InjectedFailure};
+use crate::services::failover_bundle::{FailoverBundleService, FailoverImportResult};
+use crate::services::ProviderService;
 use crate::store::AppState;
 use std::str::FromStr;
 use tauri::Emitter;
@@ -13,11 +17,11 @@ use tauri::Emitter;
 pub async fn get_failover_queue(
     state: tauri::State<'_, AppState>,
     app_type: String,
-) -> Result<Vec<FailoverQueueItem>, String> {
+) -> Result<Vec<FailoverQueueItem>, CommandError> {
     state
         .db
         .get_failover_queue(&app_type)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 获取可添加到故障转移队列的供应商（不在队列中的）
@@ -25,24 +29,32 @@ pub async fn get_failover_queue(
 pub async fn get_available_providers_for_failover(
     state: tauri::State<'_, AppState>,
     app_type: String,
-) -> Result<Vec<Provider>, String> {
+) -> Result<Vec<Provider>, CommandError> {
     state
         .db
         .get_available_providers_for_failover(&app_type)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 添加供应商到故障转移队列
+///
+/// 未验证的供应商需要 `confirm_untrusted` 为 true（前端在用户确认后重试），
+/// 否则返回键为 `provider.untrusted_needs_confirmation` 的错误。
 #[tauri::command]
 pub async fn add_to_failover_queue(
     state: tauri::State<'_, AppState>,
     app_type: String,
     provider_id: String,
-) -> Result<(), String> {
-    state
-        .db
-        .add_to_failover_queue(&app_type, &provider_id)
-        .map_err(|e| e.to_string())
+    confirm_untrusted: Option<bool>,
+) -> Result<(), CommandError> {
+    let app = crate::app_config::AppType::from_str(&app_type).map_err(CommandError::from)?;
+    ProviderService::add_to_failover_queue(
+        state.inner(),
+        app,
+        &provider_id,
+        confirm_untrusted.unwrap_or(false),
+    )
+    .map_err(CommandError::from)
 }
 
 /// 从故障转移队列移除供应商
@@ -51,11 +63,11 @@ pub async fn remove_from_failover_queue(
     state: tauri::State<'_, AppState>,
     app_type: String,
     provider_id: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     state
         .db
         .remove_from_failover_queue(&app_type, &provider_id)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 获取指定应用的自动故障转移开关状态（从 proxy_config 表读取）
@@ -63,13 +75,13 @@ pub async fn remove_from_failover_queue(
 pub async fn get_auto_failover_enabled(
     state: tauri::State<'_, AppState>,
     app_type: String,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     state
         .db
         .get_proxy_config_for_app(&app_type)
         .await
         .map(|config| config.auto_failover_enabled)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 设置指定应用的自动故障转移开关状态（写入 proxy_config 表）
@@ -81,7 +93,7 @@ pub async fn set_auto_failover_enabled(
     state: tauri::State<'_, AppState>,
     app_type: String,
     enabled: bool,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     log::info!(
         "[Failover] Setting auto_failover_enabled: app_type='{app_type}', enabled={enabled}"
     );
@@ -95,34 +107,39 @@ pub async fn set_auto_failover_enabled(
         let mut queue = state
             .db
             .get_failover_queue(&app_type)
-            .map_err(|e| e.to_string())?;
+            .map_err(CommandError::from)?;
 
         if queue.is_empty() {
-            let app_enum = crate::app_config::AppType::from_str(&app_type)
-                .map_err(|_| format!("无效的应用类型: {app_type}"))?;
+            let app_enum =
+                crate::app_config::AppType::from_str(&app_type).map_err(CommandError::from)?;
 
             let current_id = crate::settings::get_effective_current_provider(&state.db, &app_enum)
-                .map_err(|e| e.to_string())?;
+                .map_err(CommandError::from)?;
 
             let Some(current_id) = current_id else {
-                return Err("故障转移队列为空，且未设置当前供应商，无法开启故障转移".to_string());
+                return Err(CommandError::new(
+                    ErrorCode::Validation,
+                    "故障转移队列为空，且未设置当前供应商，无法开启故障转移",
+                ));
             };
 
             state
                 .db
                 .add_to_failover_queue(&app_type, &current_id)
-                .map_err(|e| e.to_string())?;
+                .map_err(CommandError::from)?;
 
             queue = state
                 .db
                 .get_failover_queue(&app_type)
-                .map_err(|e| e.to_string())?;
+                .map_err(CommandError::from)?;
         }
 
         queue
             .first()
             .map(|item| item.provider_id.clone())
-            .ok_or_else(|| "故障转移队列为空，无法开启故障转移".to_string())?
+            .ok_or_else(|| {
+                CommandError::new(ErrorCode::Validation, "故障转移队列为空，无法开启故障转移")
+            })?
     } else {
         String::new()
     };
@@ -132,7 +149,7 @@ pub async fn set_auto_failover_enabled(
         .db
         .get_proxy_config_for_app(&app_type)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(CommandError::from)?;
 
     // 更新 auto_failover_enabled 字段
     config.auto_failover_enabled = enabled;
@@ -142,14 +159,15 @@ pub async fn set_auto_failover_enabled(
         .db
         .update_proxy_config_for_app(config)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(CommandError::from)?;
 
     // 开启后立即切到 P1：更新 is_current + 本地 settings + Live 备份（接管模式下）
     if enabled {
         state
             .proxy_service
             .switch_proxy_target(&app_type, &p1_provider_id)
-            .await?;
+            .await
+            .map_err(CommandError::from)?;
 
         // 发射 provider-switched 事件（让前端刷新当前供应商）
         let event_data = serde_json::json!({
@@ -169,3 +187,70 @@ pub async fn set_auto_failover_enabled(
 
     Ok(())
 }
+
+/// 为供应商注入模拟故障（调试用，仅保存在内存中）
+///
+/// `kind` 为空时清除该供应商的注入故障。注入后代理请求会把该供应商视为失败，
+/// 用于验证故障转移队列、通知与回切行为。
+#[tauri::command]
+pub async fn inject_provider_failure(
+    state: tauri::State<'_, AppState>,
+    app: String,
+    id: String,
+    kind: Option<FailureKind>,
+) -> Result<(), CommandError> {
+    let app_type = crate::app_config::AppType::from_str(&app).map_err(CommandError::from)?;
+    if kind.is_some()
+        && state
+            .db
+            .get_provider_by_id(&id, app_type.as_str())
+            .map_err(CommandError::from)?
+            .is_none()
+    {
+        return Err(AppError::localized(
+            "provider.not_found",
+            format!("供应商不存在: {id}"),
+            format!("Provider not found: {id}"),
+        )
+        .into());
+    }
+    failure_injection::set_failure(app_type.as_str(), &id, kind);
+    Ok(())
+}
+
+/// 获取当前生效的故障注入
+#[tauri::command]
+pub async fn get_injected_failures() -> Result<Vec<InjectedFailure>, CommandError> {
+    Ok(failure_injection::list())
+}
+
+/// 清除所有故障注入
+#[tauri::command]
+pub async fn clear_injected_failures() -> Result<(), CommandError> {
+    failure_injection::clear_all();
+    Ok(())
+}
+
+/// 导出故障转移队列与熔断 / 重试策略到 JSON 文件
+#[tauri::command]
+pub async fn export_failover_config(
+    state: tauri::State<'_, AppState>,
+    file_path: String,
+) -> Result<(), CommandError> {
+    FailoverBundleService::export_to_file(&state, std::path::Path::new(&file_path))
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 从 JSON 文件导入故障转移配置
+///
+/// 供应商按 id 匹配，id 不存在时按名称匹配并重映射；返回重映射与未匹配的条目。
+#[tauri::command]
+pub async fn import_failover_config(
+    state: tauri::State<'_, AppState>,
+    file_path: String,
+) -> Result<FailoverImportResult, CommandError> {
+    FailoverBundleService::import_from_file(&state, std::path::Path::new(&file_path))
+        .await
+        .map_err(CommandError::from)
+}
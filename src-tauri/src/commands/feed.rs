@@ -0,0 +1,97 @@
+//! 订阅源命令
+
+use tauri::State;
+
+use crate::error::CommandError;
+use crate::services::feed::{FeedItemKind, FeedSubscription, FeedUpdate};
+use crate::services::FeedService;
+use crate::store::AppState;
+
+/// 获取全部订阅
+#[tauri::command]
+pub async fn get_feed_subscriptions(
+    state: State<'_, AppState>,
+) -> Result<Vec<FeedSubscription>, CommandError> {
+    FeedService::list_subscriptions(&state.db).map_err(CommandError::from)
+}
+
+/// 添加订阅（仅 https），添加后立即拉取一次
+#[tauri::command]
+pub async fn add_feed_subscription(
+    state: State<'_, AppState>,
+    url: String,
+    name: Option<String>,
+) -> Result<FeedSubscription, CommandError> {
+    FeedService::add_subscription(&state.db, &url, name)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 删除订阅
+#[tauri::command]
+pub async fn remove_feed_subscription(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<bool, CommandError> {
+    FeedService::remove_subscription(&state.db, &id).map_err(CommandError::from)
+}
+
+/// 启用或暂停订阅的自动拉取
+#[tauri::command]
+pub async fn set_feed_subscription_enabled(
+    state: State<'_, AppState>,
+    id: String,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    FeedService::set_subscription_enabled(&state.db, &id, enabled).map_err(CommandError::from)
+}
+
+/// 立即拉取订阅，返回该订阅的待处理更新数
+#[tauri::command]
+pub async fn refresh_feed_subscription(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<usize, CommandError> {
+    FeedService::refresh(&state.db, &id)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 列出所有订阅中待处理的更新
+#[tauri::command]
+pub async fn get_feed_updates(state: State<'_, AppState>) -> Result<Vec<FeedUpdate>, CommandError> {
+    FeedService::get_pending_updates(&state).map_err(CommandError::from)
+}
+
+/// 接受订阅更新，写入本地并同步到已启用的工具
+///
+/// 新增或改变启动命令的 MCP 服务器需要 `confirmed` 为 true。
+#[tauri::command]
+pub async fn accept_feed_update(
+    state: State<'_, AppState>,
+    subscription_id: String,
+    kind: FeedItemKind,
+    item_id: String,
+    confirmed: Option<bool>,
+) -> Result<(), CommandError> {
+    FeedService::accept_update(
+        &state,
+        &subscription_id,
+        kind,
+        &item_id,
+        confirmed.unwrap_or(false),
+    )
+    .map_err(CommandError::from)
+}
+
+/// 忽略订阅更新的当前版本
+#[tauri::command]
+pub async fn dismiss_feed_update(
+    state: State<'_, AppState>,
+    subscription_id: String,
+    kind: FeedItemKind,
+    item_id: String,
+) -> Result<(), CommandError> {
+    FeedService::dismiss_update(&state.db, &subscription_id, kind, &item_id)
+        .map_err(CommandError::from)
+}
@@ -2,16 +2,23 @@
 
 use serde_json::{json, Value};
 use std::path::PathBuf;
-use tauri::State;
+use std::str::FromStr;
+use tauri::{AppHandle, State};
 use tauri_plugin_dialog::DialogExt;
 
+use crate::agent::DuplicateStrategy;
 use crate::commands::sync_support::{
     post_sync_warning_from_result, run_post_import_sync, success_payload_with_warning,
 };
 use crate::database::backup::BackupEntry;
 use crate::database::Database;
-use crate::error::AppError;
+use crate::error::{AppError, CommandError};
+use crate::services::config_report::{ConfigReportService, ReportFormat};
 use crate::services::provider::ProviderService;
+use crate::services::{
+    EnablementApplyResult, EnablementService, JobService, MarkdownImportResult,
+    MarkdownImportService, MarkdownImportTarget, MarkdownNote,
+};
 use crate::store::AppState;
 
 // ─── File import/export ──────────────────────────────────────
@@ -37,6 +44,28 @@ pub async fn export_config_to_file(
     .map_err(|e: AppError| e.to_string())
 }
 
+/// 生成配置报告（HTML / Markdown，供应商凭据已遮蔽）
+#[tauri::command]
+pub async fn generate_config_report(
+    path: String,
+    format: String,
+    state: State<'_, AppState>,
+) -> Result<Value, CommandError> {
+    let format = ReportFormat::from_str(&format).map_err(CommandError::from)?;
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        ConfigReportService::write_to_file(&db, &PathBuf::from(&path), format)?;
+        Ok::<_, AppError>(json!({
+            "success": true,
+            "message": "Report generated successfully",
+            "filePath": path
+        }))
+    })
+    .await
+    .map_err(|e| CommandError::from(format!("生成配置报告失败: {e}")))?
+    .map_err(CommandError::from)
+}
+
 /// 从 SQL 备份导入数据库
 #[tauri::command]
 pub async fn import_config_from_file(
@@ -75,6 +104,26 @@ pub async fn sync_current_providers_live(state: State<'_, AppState>) -> Result<V
     .map_err(|e: AppError| e.to_string())
 }
 
+/// 在后台将当前供应商、MCP 与 Skills 全量重新写入各工具配置，立即返回任务 id
+#[tauri::command]
+pub fn start_full_resync_job(app: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let db = state.db.clone();
+    Ok(JobService::spawn(
+        &app,
+        "full-resync",
+        move |ctx| async move {
+            ctx.progress(0, 1, "");
+            tauri::async_runtime::spawn_blocking(move || {
+                ProviderService::sync_current_to_live(&AppState::new(db))
+            })
+            .await
+            .map_err(|e| AppError::Message(format!("同步当前供应商失败: {e}")))??;
+            ctx.progress(1, 1, "");
+            Ok::<_, AppError>(())
+        },
+    ))
+}
+
 // ─── File dialogs ────────────────────────────────────────────
 
 /// 保存文件对话框
@@ -142,6 +191,27 @@ pub async fn restore_db_backup(
         .map_err(|e: AppError| e.to_string())
 }
 
+/// 在后台创建数据库备份，立即返回任务 id（结果为备份 id，未生成备份时为空字符串）
+#[tauri::command]
+pub fn start_db_backup_job(app: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let db = state.db.clone();
+    Ok(JobService::spawn(
+        &app,
+        "db-backup",
+        move |ctx| async move {
+            ctx.progress(0, 1, "");
+            let path = tauri::async_runtime::spawn_blocking(move || db.backup_database_file())
+                .await
+                .map_err(|e| AppError::Message(format!("创建备份失败: {e}")))??;
+            ctx.progress(1, 1, "");
+            Ok::<_, AppError>(
+                path.and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+                    .unwrap_or_default(),
+            )
+        },
+    ))
+}
+
 /// Rename a database backup file
 #[tauri::command]
 pub fn rename_db_backup(
@@ -150,3 +220,46 @@ pub fn rename_db_backup(
 ) -> Result<String, String> {
     Database::rename_backup(&oldFilename, &newName).map_err(|e| e.to_string())
 }
+
+/// 预览 Markdown 笔记（Obsidian / Notion 导出的文件或文件夹）解析出的条目
+#[tauri::command]
+pub async fn preview_markdown_notes(path: String) -> Result<Vec<MarkdownNote>, CommandError> {
+    MarkdownImportService::preview(std::path::Path::new(&path)).map_err(CommandError::from)
+}
+
+/// 将 Markdown 笔记导入为提示词或 Agent（`target` 为 "prompt" / "agent"，`strategy` 默认 skip）
+#[tauri::command]
+pub async fn import_markdown_notes(
+    state: State<'_, AppState>,
+    path: String,
+    target: MarkdownImportTarget,
+    strategy: Option<DuplicateStrategy>,
+) -> Result<MarkdownImportResult, CommandError> {
+    MarkdownImportService::import(
+        &state,
+        std::path::Path::new(&path),
+        target,
+        strategy.unwrap_or_default(),
+    )
+    .map_err(CommandError::from)
+}
+
+/// 导出启用状态矩阵（各 app 启用的提示词 / Agent / MCP 与当前供应商）为 YAML 文件
+#[tauri::command]
+pub async fn export_enablement_matrix(
+    state: State<'_, AppState>,
+    file_path: String,
+) -> Result<(), CommandError> {
+    EnablementService::export_to_file(&state, std::path::Path::new(&file_path))
+        .map_err(CommandError::from)
+}
+
+/// 从 YAML 文件应用启用状态矩阵
+#[tauri::command]
+pub async fn import_enablement_matrix(
+    state: State<'_, AppState>,
+    file_path: String,
+) -> Result<EnablementApplyResult, CommandError> {
+    EnablementService::import_from_file(&state, std::path::Path::new(&file_path))
+        .map_err(CommandError::from)
+}
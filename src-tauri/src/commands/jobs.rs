@@ -0,0 +1,16 @@
+//! 后台任务命令
+
+use crate::error::CommandError;
+use crate::services::JobService;
+
+/// 请求取消后台任务；任务不存在或已结束时返回 false
+#[tauri::command]
+pub fn cancel_job(id: String) -> Result<bool, CommandError> {
+    Ok(JobService::cancel(&id))
+}
+
+/// 获取运行中的后台任务 id（前端重新加载后用于恢复状态）
+#[tauri::command]
+pub fn get_running_jobs() -> Result<Vec<String>, CommandError> {
+    Ok(JobService::running())
+}
@@ -0,0 +1,41 @@
+//! 旧版 config.json 重新合并命令
+
+use tauri::State;
+
+use crate::error::CommandError;
+use crate::services::{
+    LegacyConfigDiff, LegacyConfigService, LegacyConfigStatus, LegacyMergeResult,
+    LegacyMergeSelection,
+};
+use crate::store::AppState;
+
+/// 检查是否有比上次迁移更新的旧版 config.json
+#[tauri::command]
+pub fn get_legacy_config_status(
+    state: State<'_, AppState>,
+) -> Result<Option<LegacyConfigStatus>, CommandError> {
+    LegacyConfigService::detect(&state).map_err(CommandError::from)
+}
+
+/// 预览合并旧版 config.json 会带来的改动
+#[tauri::command]
+pub fn preview_legacy_config_merge(
+    state: State<'_, AppState>,
+) -> Result<Option<LegacyConfigDiff>, CommandError> {
+    LegacyConfigService::preview(&state).map_err(CommandError::from)
+}
+
+/// 合并选中的条目
+#[tauri::command]
+pub fn merge_legacy_config(
+    state: State<'_, AppState>,
+    selections: Vec<LegacyMergeSelection>,
+) -> Result<LegacyMergeResult, CommandError> {
+    LegacyConfigService::merge(&state, &selections).map_err(CommandError::from)
+}
+
+/// 忽略当前这一版旧版 config.json
+#[tauri::command]
+pub fn dismiss_legacy_config(state: State<'_, AppState>) -> Result<(), CommandError> {
+    LegacyConfigService::dismiss(&state).map_err(CommandError::from)
+}
@@ -8,37 +8,41 @@ use tauri::State;
 
 use crate::app_config::AppType;
 use crate::claude_mcp;
-use crate::services::McpService;
+use crate::error::CommandError;
+use crate::services::{McpBindingService, McpDevcontainerExport, McpProviderBinding, McpService};
 use crate::store::AppState;
 
 /// 获取 Claude MCP 状态
 #[tauri::command]
-pub async fn get_claude_mcp_status() -> Result<claude_mcp::McpStatus, String> {
-    claude_mcp::get_mcp_status().map_err(|e| e.to_string())
+pub async fn get_claude_mcp_status() -> Result<claude_mcp::McpStatus, CommandError> {
+    claude_mcp::get_mcp_status().map_err(CommandError::from)
 }
 
 /// 读取 mcp.json 文本内容
 #[tauri::command]
-pub async fn read_claude_mcp_config() -> Result<Option<String>, String> {
-    claude_mcp::read_mcp_json().map_err(|e| e.to_string())
+pub async fn read_claude_mcp_config() -> Result<Option<String>, CommandError> {
+    claude_mcp::read_mcp_json().map_err(CommandError::from)
 }
 
 /// 新增或更新一个 MCP 服务器条目
 #[tauri::command]
-pub async fn upsert_claude_mcp_server(id: String, spec: serde_json::Value) -> Result<bool, String> {
-    claude_mcp::upsert_mcp_server(&id, spec).map_err(|e| e.to_string())
+pub async fn upsert_claude_mcp_server(
+    id: String,
+    spec: serde_json::Value,
+) -> Result<bool, CommandError> {
+    claude_mcp::upsert_mcp_server(&id, spec).map_err(CommandError::from)
 }
 
 /// 删除一个 MCP 服务器条目
 #[tauri::command]
-pub async fn delete_claude_mcp_server(id: String) -> Result<bool, String> {
-    claude_mcp::delete_mcp_server(&id).map_err(|e| e.to_string())
+pub async fn delete_claude_mcp_server(id: String) -> Result<bool, CommandError> {
+    claude_mcp::delete_mcp_server(&id).map_err(CommandError::from)
 }
 
 /// 校验命令是否在 PATH 中可用（不执行）
 #[tauri::command]
-pub async fn validate_mcp_command(cmd: String) -> Result<bool, String> {
-    claude_mcp::validate_command_in_path(&cmd).map_err(|e| e.to_string())
+pub async fn validate_mcp_command(cmd: String) -> Result<bool, CommandError> {
+    claude_mcp::validate_command_in_path(&cmd).map_err(CommandError::from)
 }
 
 #[derive(Serialize)]
@@ -55,12 +59,12 @@ use std::str::FromStr;
 pub async fn get_mcp_config(
     state: State<'_, AppState>,
     app: String,
-) -> Result<McpConfigResponse, String> {
+) -> Result<McpConfigResponse, CommandError> {
     let config_path = crate::config::get_app_config_path()
         .to_string_lossy()
         .to_string();
-    let app_ty = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    let servers = McpService::get_servers(&state, app_ty).map_err(|e| e.to_string())?;
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    let servers = McpService::get_servers(&state, app_ty).map_err(CommandError::from)?;
     Ok(McpConfigResponse {
         config_path,
         servers,
@@ -76,14 +80,14 @@ pub async fn upsert_mcp_server_in_config(
     id: String,
     spec: serde_json::Value,
     sync_other_side: Option<bool>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     use crate::app_config::McpServer;
 
-    let app_ty = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
 
     // 读取现有的服务器（如果存在）
     let existing_server = {
-        let servers = state.db.get_all_mcp_servers().map_err(|e| e.to_string())?;
+        let servers = state.db.get_all_mcp_servers().map_err(CommandError::from)?;
         servers.get(&id).cloned()
     };
 
@@ -114,6 +118,7 @@ pub async fn upsert_mcp_server_in_config(
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            origin: None,
         }
     };
 
@@ -127,7 +132,7 @@ pub async fn upsert_mcp_server_in_config(
 
     McpService::upsert_server(&state, new_server)
         .map(|_| true)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 在 config.json 中删除一个 MCP 服务器定义
@@ -136,8 +141,8 @@ pub async fn delete_mcp_server_in_config(
     state: State<'_, AppState>,
     _app: String, // 参数保留用于向后兼容，但在统一结构中不再需要
     id: String,
-) -> Result<bool, String> {
-    McpService::delete_server(&state, &id).map_err(|e| e.to_string())
+) -> Result<bool, CommandError> {
+    McpService::delete_server(&state, &id).map_err(CommandError::from)
 }
 
 /// 设置启用状态并同步到客户端配置
@@ -148,9 +153,9 @@ pub async fn set_mcp_enabled(
     app: String,
     id: String,
     enabled: bool,
-) -> Result<bool, String> {
-    let app_ty = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    McpService::set_enabled(&state, app_ty, &id, enabled).map_err(|e| e.to_string())
+) -> Result<bool, CommandError> {
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    McpService::set_enabled(&state, app_ty, &id, enabled).map_err(CommandError::from)
 }
 
 // ============================================================================
@@ -163,8 +168,8 @@ use crate::app_config::McpServer;
 #[tauri::command]
 pub async fn get_mcp_servers(
     state: State<'_, AppState>,
-) -> Result<IndexMap<String, McpServer>, String> {
-    McpService::get_all_servers(&state).map_err(|e| e.to_string())
+) -> Result<IndexMap<String, McpServer>, CommandError> {
+    McpService::get_all_servers(&state).map_err(CommandError::from)
 }
 
 /// 添加或更新 MCP 服务器
@@ -172,14 +177,17 @@ pub async fn get_mcp_servers(
 pub async fn upsert_mcp_server(
     state: State<'_, AppState>,
     server: McpServer,
-) -> Result<(), String> {
-    McpService::upsert_server(&state, server).map_err(|e| e.to_string())
+) -> Result<(), CommandError> {
+    McpService::upsert_server(&state, server).map_err(CommandError::from)
 }
 
 /// 删除 MCP 服务器
 #[tauri::command]
-pub async fn delete_mcp_server(state: State<'_, AppState>, id: String) -> Result<bool, String> {
-    McpService::delete_server(&state, &id).map_err(|e| e.to_string())
+pub async fn delete_mcp_server(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<bool, CommandError> {
+    McpService::delete_server(&state, &id).map_err(CommandError::from)
 }
 
 /// 切换 MCP 服务器在指定应用的启用状态
@@ -189,18 +197,54 @@ pub async fn toggle_mcp_app(
     server_id: String,
     app: String,
     enabled: bool,
-) -> Result<(), String> {
-    let app_ty = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    McpService::toggle_app(&state, &server_id, app_ty, enabled).map_err(|e| e.to_string())
+) -> Result<(), CommandError> {
+    let app_ty = AppType::from_str(&app).map_err(CommandError::from)?;
+    McpService::toggle_app(&state, &server_id, app_ty, enabled).map_err(CommandError::from)
+}
+
+/// 获取所有 MCP 服务器的供应商绑定
+#[tauri::command]
+pub async fn get_mcp_provider_bindings(
+    state: State<'_, AppState>,
+) -> Result<Vec<McpProviderBinding>, CommandError> {
+    McpBindingService::list(&state).map_err(CommandError::from)
+}
+
+/// 设置 MCP 服务器的供应商绑定（立即按绑定改写服务器 env）
+#[tauri::command]
+pub async fn set_mcp_provider_binding(
+    state: State<'_, AppState>,
+    binding: McpProviderBinding,
+) -> Result<(), CommandError> {
+    McpBindingService::set(&state, binding).map_err(CommandError::from)
+}
+
+/// 解除 MCP 服务器的供应商绑定
+#[tauri::command]
+pub async fn remove_mcp_provider_binding(
+    state: State<'_, AppState>,
+    server_id: String,
+) -> Result<bool, CommandError> {
+    McpBindingService::remove(&state, &server_id).map_err(CommandError::from)
+}
+
+/// 将选中的 MCP 服务器导出为 devcontainer / docker-compose 片段（密钥替换为环境变量引用）
+#[tauri::command]
+pub async fn export_mcp_as_devcontainer(
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> Result<McpDevcontainerExport, CommandError> {
+    McpService::export_as_devcontainer(&state, &ids).map_err(CommandError::from)
 }
 
 /// 从所有应用导入 MCP 服务器（复用已有的导入逻辑）
 #[tauri::command]
-pub async fn import_mcp_from_apps(state: State<'_, AppState>) -> Result<usize, String> {
+pub async fn import_mcp_from_apps(state: State<'_, AppState>) -> Result<usize, CommandError> {
     let mut total = 0;
     total += McpService::import_from_claude(&state).unwrap_or(0);
     total += McpService::import_from_codex(&state).unwrap_or(0);
     total += McpService::import_from_gemini(&state).unwrap_or(0);
     total += McpService::import_from_opencode(&state).unwrap_or(0);
+    total += McpService::import_from_gemini_extensions(&state).unwrap_or(0);
     Ok(total)
 }
@@ -2,7 +2,7 @@
 
 use crate::app_config::AppType;
 use crate::init_status::{InitErrorPayload, SkillsMigrationPayload};
-use crate::services::ProviderService;
+use crate::services::{ProviderService, RemoteCacheService};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
@@ -85,6 +85,8 @@ pub struct ToolVersion {
     name: String,
     version: Option<String>,
     latest_version: Option<String>, // 新增字段：最新版本
+    /// 最新版本来自过期缓存（联网刷新失败）
+    latest_version_stale: bool,
     error: Option<String>,
     /// 工具运行环境: "windows", "wsl", "macos", "linux", "unknown"
     env_type: String,
@@ -130,6 +132,7 @@ fn tool_env_type_and_wsl_distro(_tool: &str) -> (String, Option<String>) {
 
 #[tauri::command]
 pub async fn get_tool_versions(
+    state: State<'_, crate::store::AppState>,
     tools: Option<Vec<String>>,
     wsl_shell_by_tool: Option<HashMap<String, WslShellPreferenceInput>>,
 ) -> Result<Vec<ToolVersion>, String> {
@@ -150,14 +153,24 @@ pub async fn get_tool_versions(
         let tool_wsl_shell = pref.and_then(|p| p.wsl_shell.as_deref());
         let tool_wsl_shell_flag = pref.and_then(|p| p.wsl_shell_flag.as_deref());
 
-        results.push(get_single_tool_version_impl(tool, tool_wsl_shell, tool_wsl_shell_flag).await);
+        results.push(
+            get_single_tool_version_impl(&state.db, tool, tool_wsl_shell, tool_wsl_shell_flag)
+                .await,
+        );
     }
 
     Ok(results)
 }
 
+/// 清空远程数据缓存（技能仓库索引、工具版本等），返回删除的条目数
+#[tauri::command]
+pub async fn clear_remote_cache(state: State<'_, crate::store::AppState>) -> Result<usize, String> {
+    RemoteCacheService::clear(&state.db).map_err(|e| e.to_string())
+}
+
 /// 获取单个工具的版本信息（内部实现）
 async fn get_single_tool_version_impl(
+    db: &crate::database::Database,
     tool: &str,
     wsl_shell: Option<&str>,
     wsl_shell_flag: Option<&str>,
@@ -185,25 +198,42 @@ async fn get_single_tool_version_impl(
         }
     };
 
-    // 2. 获取远程最新版本
-    let latest_version = match tool {
-        "claude" => fetch_npm_latest_version(&client, "@anthropic-ai/claude-code").await,
-        "codex" => fetch_npm_latest_version(&client, "@openai/codex").await,
-        "gemini" => fetch_npm_latest_version(&client, "@google/gemini-cli").await,
-        "opencode" => fetch_github_latest_version(&client, "anomalyco/opencode").await,
-        _ => None,
+    // 2. 获取远程最新版本（带本地缓存，离线时使用过期缓存）
+    let latest = RemoteCacheService::get_or_fetch(
+        db,
+        &format!("tool-version:{tool}"),
+        TOOL_VERSION_CACHE_TTL_SECS,
+        || async {
+            let latest = match tool {
+                "claude" => fetch_npm_latest_version(&client, "@anthropic-ai/claude-code").await,
+                "codex" => fetch_npm_latest_version(&client, "@openai/codex").await,
+                "gemini" => fetch_npm_latest_version(&client, "@google/gemini-cli").await,
+                "opencode" => fetch_github_latest_version(&client, "anomalyco/opencode").await,
+                _ => None,
+            };
+            latest.ok_or("failed to fetch latest version")
+        },
+    )
+    .await;
+    let (latest_version, latest_version_stale) = match latest {
+        Ok(cached) => (Some(cached.data), cached.stale),
+        Err(_) => (None, false),
     };
 
     ToolVersion {
         name: tool.to_string(),
         version: local_version,
         latest_version,
+        latest_version_stale,
         error: local_error,
         env_type,
         wsl_distro,
     }
 }
 
+/// 工具最新版本缓存有效期（1 小时）
+const TOOL_VERSION_CACHE_TTL_SECS: i64 = 60 * 60;
+
 /// Helper function to fetch latest version from npm registry
 async fn fetch_npm_latest_version(client: &reqwest::Client, package: &str) -> Option<String> {
     let url = format!("https://registry.npmjs.org/{package}");
@@ -1038,21 +1068,21 @@ exec bash --norc --noprofile
         let pref_args = default_terminals
             .iter()
             .find(|(name, _)| *name == pref.as_str())
-            .map(|(_, args)| args.iter().map(|s| *s).collect::<Vec<&str>>())
+            .map(|(_, args)| args.to_vec())
             .unwrap_or_else(|| vec!["-e"]); // Default args for unknown terminals
 
         let mut list = vec![(pref.as_str(), pref_args)];
         // Add remaining terminals as fallbacks
         for (name, args) in &default_terminals {
             if *name != pref.as_str() {
-                list.push((*name, args.iter().map(|s| *s).collect()));
+                list.push((*name, args.to_vec()));
             }
         }
         list
     } else {
         default_terminals
             .iter()
-            .map(|(name, args)| (*name, args.iter().map(|s| *s).collect()))
+            .map(|(name, args)| (*name, args.to_vec()))
             .collect()
     };
 
@@ -1281,7 +1311,7 @@ mod tests {
 
         let count = paths
             .iter()
-            .filter(|path| **path == PathBuf::from("/same/path"))
+            .filter(|path| path.as_path() == Path::new("/same/path"))
             .count();
         assert_eq!(count, 1);
     }
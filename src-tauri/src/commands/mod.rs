@@ -1,22 +1,30 @@
 #![allow(non_snake_case)]
 
 mod agents;
+mod api_access;
 mod config;
+mod context_budget;
 mod deeplink;
 mod env;
 mod failover;
+mod feed;
 mod global_proxy;
 mod import_export;
+mod jobs;
+mod legacy_config;
 mod mcp;
 mod misc;
 mod omo;
 mod openclaw;
 mod plugin;
 mod prompt;
+mod prompt_project;
 mod provider;
 mod proxy;
+mod schedule;
 mod session_manager;
 mod settings;
+mod share;
 pub mod skill;
 mod stream_check;
 mod sync_support;
@@ -25,22 +33,30 @@ mod webdav_sync;
 mod workspace;
 
 pub use agents::*;
+pub use api_access::*;
 pub use config::*;
+pub use context_budget::*;
 pub use deeplink::*;
 pub use env::*;
 pub use failover::*;
+pub use feed::*;
 pub use global_proxy::*;
 pub use import_export::*;
+pub use jobs::*;
+pub use legacy_config::*;
 pub use mcp::*;
 pub use misc::*;
 pub use omo::*;
 pub use openclaw::*;
 pub use plugin::*;
 pub use prompt::*;
+pub use prompt_project::*;
 pub use provider::*;
 pub use proxy::*;
+pub use schedule::*;
 pub use session_manager::*;
 pub use settings::*;
+pub use share::*;
 pub use skill::*;
 pub use stream_check::*;
 pub use usage::*;
@@ -3,56 +3,291 @@ use std::str::FromStr;
 
 use tauri::State;
 
+use crate::agent::DuplicateStrategy;
 use crate::app_config::AppType;
-use crate::prompt::Prompt;
-use crate::services::PromptService;
+use crate::error::CommandError;
+use crate::prompt::{
+    Prompt, PromptFilePreview, PromptImportResult, PromptIssue, PromptRevision, TrashedPrompt,
+};
+use crate::prompt_files::OpenCodePromptTarget;
+use crate::services::{
+    BulkReplaceKind, BulkReplaceResult, BulkReplaceService, DriftResolution, MarkdownImportResult,
+    MarkdownImportService, PromptBackupService, PromptBundleService, PromptDrift,
+    PromptDriftService, PromptFileBackup, PromptService,
+};
 use crate::store::AppState;
 
 #[tauri::command]
 pub async fn get_prompts(
     state: State<'_, AppState>,
-) -> Result<IndexMap<String, Prompt>, String> {
-    PromptService::get_prompts(&state).map_err(|e| e.to_string())
+) -> Result<IndexMap<String, Prompt>, CommandError> {
+    PromptService::get_prompts(&state).map_err(CommandError::from)
 }
 
+/// 获取对 app 启用的提示词（按优先级排序）
+#[tauri::command]
+pub async fn get_prompts_for_app(
+    app: String,
+    state: State<'_, AppState>,
+) -> Result<IndexMap<String, Prompt>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::get_prompts_for_app(&state, &app_type).map_err(CommandError::from)
+}
+
+/// 获取对 app 启用的优先级最高的提示词
+#[tauri::command]
+pub async fn get_enabled_prompt(
+    app: String,
+    state: State<'_, AppState>,
+) -> Result<Option<Prompt>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::get_enabled_prompt(&state, &app_type).map_err(CommandError::from)
+}
+
+/// 按关键词全文搜索提示词，结果按优先级排序（查询为空时返回全部）
+#[tauri::command]
+pub async fn search_prompts(
+    query: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<Prompt>, CommandError> {
+    PromptService::search_prompts(&state, &query).map_err(CommandError::from)
+}
+
+/// 保存提示词，返回校验警告（存在错误级别的问题时拒绝保存）
 #[tauri::command]
 pub async fn upsert_prompt(
     prompt: Prompt,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    PromptService::upsert_prompt(&state, prompt).map_err(|e| e.to_string())
+) -> Result<Vec<PromptIssue>, CommandError> {
+    PromptService::upsert_prompt(&state, prompt).map_err(CommandError::from)
+}
+
+/// 校验提示词（大小限制与已知会出错的写法），不保存
+#[tauri::command]
+pub async fn validate_prompt(
+    prompt: Prompt,
+    state: State<'_, AppState>,
+) -> Result<Vec<PromptIssue>, CommandError> {
+    PromptService::validate_prompt(&state, &prompt).map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn delete_prompt(id: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    PromptService::delete_prompt(&state, &id).map_err(CommandError::from)
 }
 
+/// 复制提示词（副本不对任何 app 启用）
 #[tauri::command]
-pub async fn delete_prompt(
+pub async fn duplicate_prompt(
     id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    PromptService::delete_prompt(&state, &id).map_err(|e| e.to_string())
+) -> Result<Prompt, CommandError> {
+    PromptService::duplicate_prompt(&state, &id).map_err(CommandError::from)
 }
 
+/// 获取提示词的历史快照（最新在前）
+#[tauri::command]
+pub async fn get_prompt_history(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<PromptRevision>, CommandError> {
+    PromptService::history(&state, &id).map_err(CommandError::from)
+}
+
+/// 将提示词回滚到指定历史快照
+#[tauri::command]
+pub async fn rollback_prompt(
+    id: String,
+    version: i64,
+    state: State<'_, AppState>,
+) -> Result<Prompt, CommandError> {
+    PromptService::rollback(&state, &id, version).map_err(CommandError::from)
+}
+
+/// 获取回收站中的提示词（最近删除的在前）
+#[tauri::command]
+pub async fn get_prompt_trash(
+    state: State<'_, AppState>,
+) -> Result<Vec<TrashedPrompt>, CommandError> {
+    PromptService::get_trash(&state).map_err(CommandError::from)
+}
+
+/// 从回收站恢复提示词，并按删除前的启用状态重写工具文件
+#[tauri::command]
+pub async fn restore_prompt(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<Prompt, CommandError> {
+    PromptService::restore_prompt(&state, &id).map_err(CommandError::from)
+}
+
+/// 永久删除回收站中的提示词；`id` 为空时清空回收站，返回删除的数量
+#[tauri::command]
+pub async fn purge_prompt_trash(
+    id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<usize, CommandError> {
+    PromptService::purge_trash(&state, id.as_deref()).map_err(CommandError::from)
+}
+
+/// 切换提示词启用状态；`exclusive` 缺省为 true（启用时取消同 app 的其他提示词）
 #[tauri::command]
 pub async fn toggle_prompt_app(
     id: String,
     app: String,
     enabled: bool,
+    exclusive: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::toggle_prompt_app(&state, &id, app_type, enabled, exclusive.unwrap_or(true))
+        .map_err(CommandError::from)
+}
+
+/// 按给定 id 顺序调整提示词优先级（决定同一 app 启用多个提示词时的拼接顺序）
+#[tauri::command]
+pub async fn reorder_prompts(
+    ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    PromptService::reorder_prompts(&state, &ids).map_err(CommandError::from)
+}
+
+/// 预览编辑中的正文展开引用、替换模板变量（`{{os}}`、`{{date}}`、`{{active_provider}}` 等）后的结果
+#[tauri::command]
+pub async fn preview_prompt_render(
+    app: String,
+    content: String,
+    state: State<'_, AppState>,
+) -> Result<String, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::render_preview(&state, &app_type, &content).map_err(CommandError::from)
+}
+
+/// 预览同步后 app 提示词文件的完整内容与启用提示词的渲染结果（不写入）
+///
+/// 传入 `enabled_ids` 时按这组 id 模拟该 app 的启用状态，便于在切换前查看结果。
+#[tauri::command]
+pub async fn preview_prompt_file(
+    app: String,
+    enabled_ids: Option<Vec<String>>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    PromptService::toggle_prompt_app(&state, &id, app_type, enabled).map_err(|e| e.to_string())
+) -> Result<PromptFilePreview, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::preview_app_file(&state, &app_type, enabled_ids.as_deref())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn import_prompt_from_file(
     app: String,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    PromptService::import_from_file(&state, app_type).map_err(|e| e.to_string())
+) -> Result<String, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::import_from_file(&state, app_type).map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_current_prompt_file_content(app: String) -> Result<Option<String>, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    PromptService::get_current_file_content(app_type).map_err(|e| e.to_string())
+pub async fn get_current_prompt_file_content(app: String) -> Result<Option<String>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptService::get_current_file_content(app_type).map_err(CommandError::from)
+}
+
+/// 列出 cc-switch 首次改写提示词文件前留下的备份
+#[tauri::command]
+pub async fn list_prompt_backups() -> Result<Vec<PromptFileBackup>, CommandError> {
+    PromptBackupService::list().map_err(CommandError::from)
+}
+
+/// 用备份覆盖原提示词文件
+#[tauri::command]
+pub async fn restore_prompt_backup(id: String) -> Result<PromptFileBackup, CommandError> {
+    PromptBackupService::restore(&id).map_err(CommandError::from)
+}
+
+/// 检查各 app 的提示词文件是否在 cc-switch 之外被修改过
+#[tauri::command]
+pub async fn detect_prompt_drift(
+    state: State<'_, AppState>,
+) -> Result<Vec<PromptDrift>, CommandError> {
+    PromptDriftService::detect(&state).map_err(CommandError::from)
+}
+
+/// 处理提示词文件的外部修改（import / merge / discard），返回导入或写回的提示词 id
+#[tauri::command]
+pub async fn resolve_prompt_drift(
+    state: State<'_, AppState>,
+    app: String,
+    resolution: DriftResolution,
+) -> Result<Option<String>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptDriftService::resolve(&state, app_type, resolution).map_err(CommandError::from)
+}
+
+/// 切换 OpenCode 全局提示词的写入位置（agents / instructions），并迁移已写入的内容
+#[tauri::command]
+pub async fn set_opencode_prompt_target(
+    state: State<'_, AppState>,
+    target: OpenCodePromptTarget,
+) -> Result<(), CommandError> {
+    PromptService::set_opencode_target(&state, target).map_err(CommandError::from)
+}
+
+/// 导出提示词到导出包文件（`.zip` 为 Markdown 压缩包，其余为 JSON）
+///
+/// `ids` 为空时导出全部提示词；返回导出的数量。
+#[tauri::command]
+pub async fn export_prompts_bundle(
+    state: State<'_, AppState>,
+    file_path: String,
+    ids: Option<Vec<String>>,
+) -> Result<usize, CommandError> {
+    PromptBundleService::export_to_file(&state, std::path::Path::new(&file_path), ids.as_deref())
+        .map_err(CommandError::from)
+}
+
+/// 把文件夹中的 Markdown 文件批量导入为提示词（`strategy` 默认 skip），返回导入摘要
+#[tauri::command]
+pub async fn import_prompts_from_dir(
+    state: State<'_, AppState>,
+    path: String,
+    strategy: Option<DuplicateStrategy>,
+) -> Result<MarkdownImportResult, CommandError> {
+    MarkdownImportService::import_prompts_from_dir(
+        &state,
+        std::path::Path::new(&path),
+        strategy.unwrap_or_default(),
+    )
+    .map_err(CommandError::from)
+}
+
+/// 从导出包文件导入提示词（`strategy` 为 skip / overwrite / rename，默认 skip）
+#[tauri::command]
+pub async fn import_prompts_bundle(
+    state: State<'_, AppState>,
+    file_path: String,
+    strategy: Option<DuplicateStrategy>,
+) -> Result<PromptImportResult, CommandError> {
+    PromptBundleService::import_from_file(
+        &state,
+        std::path::Path::new(&file_path),
+        strategy.unwrap_or_default(),
+    )
+    .map_err(CommandError::from)
+}
+
+/// 在提示词 / Agent 正文中批量查找替换
+///
+/// `dry_run` 为 true 时只返回预览；否则在一个事务中写入全部替换并同步工具文件。
+#[tauri::command]
+pub async fn bulk_replace(
+    state: State<'_, AppState>,
+    kind: BulkReplaceKind,
+    pattern: String,
+    replacement: String,
+    regex: bool,
+    dry_run: bool,
+) -> Result<BulkReplaceResult, CommandError> {
+    BulkReplaceService::bulk_replace(&state, kind, &pattern, &replacement, regex, dry_run)
+        .map_err(CommandError::from)
 }
@@ -0,0 +1,51 @@
+//! 项目级提示词命令
+
+use std::str::FromStr;
+
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::error::CommandError;
+use crate::services::{PromptProject, PromptProjectService};
+use crate::store::AppState;
+
+/// 获取全部登记的项目（含提示词挂载）
+#[tauri::command]
+pub async fn get_prompt_projects(
+    state: State<'_, AppState>,
+) -> Result<Vec<PromptProject>, CommandError> {
+    PromptProjectService::list_projects(&state.db).map_err(CommandError::from)
+}
+
+/// 登记项目目录；`name` 缺省时取目录名
+#[tauri::command]
+pub async fn add_prompt_project(
+    state: State<'_, AppState>,
+    path: String,
+    name: Option<String>,
+) -> Result<PromptProject, CommandError> {
+    PromptProjectService::add_project(&state.db, &path, name).map_err(CommandError::from)
+}
+
+/// 取消登记项目，并移除项目文件中 cc-switch 写入的提示词区域
+#[tauri::command]
+pub async fn remove_prompt_project(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<bool, CommandError> {
+    PromptProjectService::remove_project(&state, &id).map_err(CommandError::from)
+}
+
+/// 在项目中挂载或取消挂载提示词（写入 `<project>/CLAUDE.md` / `AGENTS.md` / `GEMINI.md`）
+#[tauri::command]
+pub async fn toggle_project_prompt(
+    state: State<'_, AppState>,
+    project_id: String,
+    prompt_id: String,
+    app: String,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    PromptProjectService::toggle_project_prompt(&state, &project_id, &prompt_id, app_type, enabled)
+        .map_err(CommandError::from)
+}
@@ -1,9 +1,17 @@
 use indexmap::IndexMap;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 use crate::app_config::AppType;
-use crate::error::AppError;
-use crate::provider::Provider;
+use crate::error::{AppError, CommandError};
+use crate::provider::{Provider, ProviderTrust};
+use crate::services::provider::{
+    BulkUpdateResult, FastestProviderPick, MissingSecret, ProfileApplyResult, ProviderAttachment,
+    ProviderAuditEntry, ProviderAuditOrigin, ProviderBenchmark, ProviderDiff, ProviderEnvScript,
+    ProviderImportResult, ProviderIssue, ProviderMetrics, ProviderPreset, ProviderProfile,
+    ProviderQuota, ProviderSetupGuide, ProviderSortMode, ProviderTag, SecretUpdateResult,
+    StaleEndpoint, TrashedProvider, UniversalApplyResult, DEFAULT_STALE_FAILURES,
+};
+use crate::services::stream_check::StreamCheckService;
 use crate::services::{
     EndpointLatency, ProviderService, ProviderSortUpdate, SpeedtestService, SwitchResult,
 };
@@ -14,15 +22,86 @@ use std::str::FromStr;
 pub fn get_providers(
     state: State<'_, AppState>,
     app: String,
-) -> Result<IndexMap<String, Provider>, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::list(state.inner(), app_type).map_err(|e| e.to_string())
+    sort: Option<ProviderSortMode>,
+) -> Result<IndexMap<String, Provider>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::list_sorted(state.inner(), app_type, sort.unwrap_or_default())
+        .map_err(CommandError::from)
+}
+
+/// 比较两个供应商的配置，按 JSON 路径列出差异
+#[tauri::command]
+pub fn diff_providers(
+    state: State<'_, AppState>,
+    app: String,
+    id_a: String,
+    id_b: String,
+) -> Result<ProviderDiff, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::diff(state.inner(), app_type, &id_a, &id_b).map_err(CommandError::from)
+}
+
+/// 按当前供应商重新生成环境变量脚本，返回各 shell 的脚本路径与需要加入启动文件的一行
+#[tauri::command]
+pub fn get_provider_env_scripts(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<Vec<ProviderEnvScript>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::write_env_scripts(state.inner(), &app_type).map_err(CommandError::from)
 }
 
+/// 获取带有指定标签的供应商
 #[tauri::command]
-pub fn get_current_provider(state: State<'_, AppState>, app: String) -> Result<String, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::current(state.inner(), app_type).map_err(|e| e.to_string())
+pub fn get_providers_by_tag(
+    state: State<'_, AppState>,
+    app: String,
+    tag: String,
+) -> Result<IndexMap<String, Provider>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::list_by_tag(state.inner(), app_type, &tag).map_err(CommandError::from)
+}
+
+/// 获取应用下使用过的标签及各标签的供应商数量
+#[tauri::command]
+pub fn get_provider_tags(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<Vec<ProviderTag>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::list_tags(state.inner(), app_type).map_err(CommandError::from)
+}
+
+/// 获取供应商变更审计日志（按时间倒序）；不传 `provider_id` 时返回该应用的全部记录
+#[tauri::command]
+pub fn get_provider_audit_log(
+    state: State<'_, AppState>,
+    app: String,
+    provider_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<ProviderAuditEntry>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::get_audit_log(state.inner(), app_type, provider_id.as_deref(), limit)
+        .map_err(CommandError::from)
+}
+
+/// 获取各供应商的运行指标（延迟、成功率与最近使用时间）
+#[tauri::command]
+pub fn get_provider_metrics(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<HashMap<String, ProviderMetrics>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::get_metrics(state.inner(), app_type).map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub fn get_current_provider(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<String, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::current(state.inner(), app_type).map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -30,9 +109,9 @@ pub fn add_provider(
     state: State<'_, AppState>,
     app: String,
     provider: Provider,
-) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::add(state.inner(), app_type, provider).map_err(|e| e.to_string())
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::add(state.inner(), app_type, provider).map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -40,21 +119,214 @@ pub fn update_provider(
     state: State<'_, AppState>,
     app: String,
     provider: Provider,
-) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::update(state.inner(), app_type, provider).map_err(|e| e.to_string())
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::update(state.inner(), app_type, provider).map_err(CommandError::from)
+}
+
+/// 批量编辑：对选中供应商的 `settingsConfig` 应用同一个 JSON Merge Patch（同一事务写入）
+#[tauri::command]
+pub fn bulk_update_providers(
+    state: State<'_, AppState>,
+    app: String,
+    ids: Vec<String>,
+    patch: serde_json::Value,
+) -> Result<BulkUpdateResult, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::bulk_update(state.inner(), app_type, &ids, &patch).map_err(CommandError::from)
+}
+
+/// 将文件添加为供应商备注的附件
+#[tauri::command]
+pub fn add_provider_attachment(
+    state: State<'_, AppState>,
+    app: String,
+    provider_id: String,
+    file_path: String,
+) -> Result<ProviderAttachment, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::add_attachment(
+        state.inner(),
+        app_type,
+        &provider_id,
+        std::path::Path::new(&file_path),
+    )
+    .map_err(CommandError::from)
 }
 
+/// 获取供应商备注的附件
+#[tauri::command]
+pub fn list_provider_attachments(
+    state: State<'_, AppState>,
+    app: String,
+    provider_id: String,
+) -> Result<Vec<ProviderAttachment>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::list_attachments(state.inner(), app_type, &provider_id)
+        .map_err(CommandError::from)
+}
+
+/// 用系统默认程序打开附件
+#[tauri::command]
+pub fn open_provider_attachment(
+    handle: AppHandle,
+    state: State<'_, AppState>,
+    app: String,
+    provider_id: String,
+    id: String,
+) -> Result<bool, CommandError> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    let path = ProviderService::attachment_path(state.inner(), app_type, &provider_id, &id)
+        .map_err(CommandError::from)?;
+    handle
+        .opener()
+        .open_path(path.to_string_lossy().to_string(), None::<String>)
+        .map_err(|e| CommandError::from(format!("打开附件失败: {e}")))?;
+    Ok(true)
+}
+
+/// 删除供应商备注的附件
+#[tauri::command]
+pub fn remove_provider_attachment(
+    state: State<'_, AppState>,
+    app: String,
+    provider_id: String,
+    id: String,
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::remove_attachment(state.inner(), app_type, &provider_id, &id)
+        .map(|_| true)
+        .map_err(CommandError::from)
+}
+
+/// 复制供应商（含配置、元数据与自定义端点）为可编辑的本地副本
+///
+/// 只读引用只能通过复制来修改。
+#[tauri::command]
+pub fn duplicate_provider(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+) -> Result<Provider, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::duplicate(state.inner(), app_type, &id).map_err(CommandError::from)
+}
+
+/// 将团队模板仓库中的供应商写入为只读引用，返回写入的 id
+#[tauri::command]
+pub fn sync_reference_providers(
+    state: State<'_, AppState>,
+    app: String,
+    source: String,
+    providers: Vec<Provider>,
+) -> Result<Vec<String>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::sync_references(state.inner(), app_type, &source, providers)
+        .map_err(CommandError::from)
+}
+
+/// 获取供应商当前的凭据问题及处理建议（来自健康检查与代理请求的认证错误）
+#[tauri::command]
+pub fn get_provider_issues(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+) -> Result<Vec<ProviderIssue>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::get_issues(&state.db, app_type.as_str(), &id).map_err(CommandError::from)
+}
+
+/// 列出密钥为空或为占位符的供应商（`app` 为空时检查全部应用）
+#[tauri::command]
+pub fn get_providers_missing_secrets(
+    state: State<'_, AppState>,
+    app: Option<String>,
+) -> Result<Vec<MissingSecret>, CommandError> {
+    let app_type = app
+        .map(|app| AppType::from_str(&app))
+        .transpose()
+        .map_err(CommandError::from)?;
+    ProviderService::get_providers_missing_secrets(state.inner(), app_type)
+        .map_err(CommandError::from)
+}
+
+/// 为供应商补录密钥，可选地随后做一次健康检查
+#[tauri::command]
+pub async fn set_provider_secret(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: String,
+    #[allow(non_snake_case)] keyPath: String,
+    value: String,
+    probe: Option<bool>,
+) -> Result<SecretUpdateResult, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::set_provider_secret(
+        state.inner(),
+        app_type,
+        &providerId,
+        &keyPath,
+        &value,
+        probe.unwrap_or(false),
+    )
+    .await
+    .map_err(CommandError::from)
+}
+
+/// 切换供应商密钥的存储位置（系统钥匙串 / 数据库明文），并迁移已保存的密钥
+#[tauri::command]
+pub fn set_keychain_storage(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<usize, CommandError> {
+    ProviderService::set_keychain_storage(state.inner(), enabled).map_err(CommandError::from)
+}
+
+/// 删除供应商（移入回收站）；删除当前供应商时先切换到备用供应商
 #[tauri::command]
 pub fn delete_provider(
     state: State<'_, AppState>,
     app: String,
     id: String,
-) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::delete(state.inner(), app_type, &id)
         .map(|_| true)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
+}
+
+/// 获取回收站中的供应商（最近删除的在前）
+#[tauri::command]
+pub fn get_provider_trash(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<Vec<TrashedProvider>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::get_trash(state.inner(), app_type).map_err(CommandError::from)
+}
+
+/// 从回收站恢复供应商
+#[tauri::command]
+pub fn restore_provider(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+) -> Result<Provider, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::restore(state.inner(), app_type, &id).map_err(CommandError::from)
+}
+
+/// 永久删除回收站中的供应商；`id` 为空时清空该应用的回收站，返回删除的数量
+#[tauri::command]
+pub fn purge_provider_trash(
+    state: State<'_, AppState>,
+    app: String,
+    id: Option<String>,
+) -> Result<usize, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::purge_trash(state.inner(), app_type, id.as_deref()).map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -62,11 +334,11 @@ pub fn remove_provider_from_live_config(
     state: tauri::State<'_, AppState>,
     app: String,
     id: String,
-) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::remove_from_live_config(state.inner(), app_type, &id)
         .map(|_| true)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 fn switch_provider_internal(
@@ -86,14 +358,186 @@ pub fn switch_provider_test_hook(
     switch_provider_internal(state, app_type, id)
 }
 
+/// 切换供应商；开启切换前预检时先验证目标供应商，`force` 为 true 时跳过预检
 #[tauri::command]
-pub fn switch_provider(
+pub async fn switch_provider(
+    handle: AppHandle,
     state: State<'_, AppState>,
     app: String,
     id: String,
-) -> Result<SwitchResult, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    switch_provider_internal(&state, app_type, &id).map_err(|e| e.to_string())
+    force: Option<bool>,
+) -> Result<SwitchResult, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    let result = ProviderService::switch_with_preflight(
+        state.inner(),
+        app_type.clone(),
+        &id,
+        force.unwrap_or(false),
+    )
+    .await
+    .map_err(CommandError::from)?;
+    // 预检已经完成了一次请求，不再预热
+    if result.preflight.is_none() {
+        StreamCheckService::spawn_warm_up(&handle, app_type, id, "switch");
+    }
+    Ok(result)
+}
+
+/// 将供应商标记为维护中直到 `until`（Unix 秒）
+#[tauri::command]
+pub fn set_provider_maintenance(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+    until: i64,
+) -> Result<Provider, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::set_maintenance(state.inner(), app_type, &id, until)
+        .map_err(CommandError::from)
+}
+
+/// 提前结束供应商的维护窗口
+#[tauri::command]
+pub fn clear_provider_maintenance(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::clear_maintenance(state.inner(), app_type, &id).map_err(CommandError::from)
+}
+
+/// 归档或取消归档供应商（归档后不出现在切换列表与故障转移队列中）
+#[tauri::command]
+pub fn set_provider_archived(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+    archived: bool,
+) -> Result<Provider, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::set_archived(state.inner(), app_type, &id, archived)
+        .map_err(CommandError::from)
+}
+
+/// 设置供应商的信任级别
+#[tauri::command]
+pub fn set_provider_trust(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+    trust: ProviderTrust,
+) -> Result<Provider, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::set_trust(state.inner(), app_type, &id, trust).map_err(CommandError::from)
+}
+
+/// 设置未验证的供应商是否允许被故障转移、自动选择最快供应商等自动化功能使用
+#[tauri::command]
+pub fn set_provider_automation_allowed(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+    allowed: bool,
+) -> Result<Provider, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::set_automation_allowed(state.inner(), app_type, &id, allowed)
+        .map_err(CommandError::from)
+}
+
+/// 按最近一次测速结果切换到最快的供应商（跳过未验证且未获允许的供应商）
+#[tauri::command]
+pub fn switch_to_fastest_provider(
+    handle: AppHandle,
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<FastestProviderPick, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    let pick = ProviderService::switch_to_fastest(
+        state.inner(),
+        app_type.clone(),
+        ProviderAuditOrigin::Ui,
+    )
+    .map_err(CommandError::from)?;
+    if let (true, Some(id)) = (pick.switched, pick.provider_id.clone()) {
+        StreamCheckService::spawn_warm_up(&handle, app_type, id, "switch");
+    }
+    Ok(pick)
+}
+
+/// 获取供应商分类的内置配置指南（离线可用），未收录的分类返回 None
+#[tauri::command]
+pub fn get_provider_setup_guide(category: String) -> Option<ProviderSetupGuide> {
+    ProviderService::get_setup_guide(&category)
+}
+
+/// 获取内置供应商预设；指定 `app` 时只返回支持该应用的预设
+#[tauri::command]
+pub fn get_provider_presets(app: Option<String>) -> Result<Vec<ProviderPreset>, CommandError> {
+    let app_type = app
+        .as_deref()
+        .map(AppType::from_str)
+        .transpose()
+        .map_err(CommandError::from)?;
+    Ok(ProviderService::list_presets(app_type.as_ref()))
+}
+
+/// 从内置预设创建供应商，只需提供 API Key（`name` 为空时使用预设名称）
+#[tauri::command]
+pub fn create_provider_from_preset(
+    state: State<'_, AppState>,
+    app: String,
+    preset_id: String,
+    api_key: String,
+    name: Option<String>,
+) -> Result<Provider, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::create_from_preset(
+        state.inner(),
+        app_type,
+        &preset_id,
+        &api_key,
+        name.as_deref(),
+        ProviderAuditOrigin::Ui,
+    )
+    .map_err(CommandError::from)
+}
+
+/// 导出应用的供应商到 JSON 文件，返回导出数量
+///
+/// 提供 `passphrase` 时密钥加密保存，否则密钥被清空；`ids` 为空时导出全部。
+#[tauri::command]
+pub fn export_providers(
+    state: State<'_, AppState>,
+    app: String,
+    file_path: String,
+    ids: Option<Vec<String>>,
+    passphrase: Option<String>,
+) -> Result<usize, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::export_providers_to_file(
+        state.inner(),
+        app_type,
+        std::path::Path::new(&file_path),
+        ids.as_deref(),
+        passphrase.as_deref(),
+    )
+    .map_err(CommandError::from)
+}
+
+/// 从 JSON 文件导入供应商，返回导入的 id 与仍需补录密钥的供应商
+#[tauri::command]
+pub fn import_providers(
+    state: State<'_, AppState>,
+    file_path: String,
+    passphrase: Option<String>,
+) -> Result<ProviderImportResult, CommandError> {
+    ProviderService::import_providers_from_file(
+        state.inner(),
+        std::path::Path::new(&file_path),
+        passphrase.as_deref(),
+    )
+    .map_err(CommandError::from)
 }
 
 fn import_default_config_internal(state: &AppState, app_type: AppType) -> Result<bool, AppError> {
@@ -111,22 +555,38 @@ pub fn import_default_config_test_hook(
 }
 
 #[tauri::command]
-pub fn import_default_config(state: State<'_, AppState>, app: String) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+pub fn import_default_config(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     import_default_config_internal(&state, app_type).map_err(Into::into)
 }
 
+/// 查询供应商的余额（按 `quotaType` 解析厂商或中转站的余额接口）
+#[tauri::command]
+pub async fn query_provider_quota(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+) -> Result<ProviderQuota, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::query_quota(state.inner(), app_type, &id)
+        .await
+        .map_err(CommandError::from)
+}
+
 #[allow(non_snake_case)]
 #[tauri::command]
 pub async fn queryProviderUsage(
     state: State<'_, AppState>,
     #[allow(non_snake_case)] providerId: String, // 使用 camelCase 匹配前端
     app: String,
-) -> Result<crate::provider::UsageResult, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<crate::provider::UsageResult, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::query_usage(state.inner(), app_type, &providerId)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 #[allow(non_snake_case)]
@@ -143,8 +603,8 @@ pub async fn testUsageScript(
     #[allow(non_snake_case)] accessToken: Option<String>,
     #[allow(non_snake_case)] userId: Option<String>,
     #[allow(non_snake_case)] templateType: Option<String>,
-) -> Result<crate::provider::UsageResult, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<crate::provider::UsageResult, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::test_usage_script(
         state.inner(),
         app_type,
@@ -158,29 +618,98 @@ pub async fn testUsageScript(
         templateType.as_deref(),
     )
     .await
-    .map_err(|e| e.to_string())
+    .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub fn read_live_provider_settings(app: String) -> Result<serde_json::Value, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::read_live_settings(app_type).map_err(|e| e.to_string())
+pub fn read_live_provider_settings(app: String) -> Result<serde_json::Value, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::read_live_settings(app_type).map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub fn patch_claude_live_settings(patch: serde_json::Value) -> Result<bool, String> {
-    ProviderService::patch_claude_live(patch).map_err(|e| e.to_string())?;
+pub fn patch_claude_live_settings(patch: serde_json::Value) -> Result<bool, CommandError> {
+    ProviderService::patch_claude_live(patch).map_err(CommandError::from)?;
     Ok(true)
 }
 
+/// 测速；同时传入 `app` 与 `providerId` 时，把结果记入该供应商自定义端点的测速记录
 #[tauri::command]
 pub async fn test_api_endpoints(
+    state: State<'_, AppState>,
     urls: Vec<String>,
     #[allow(non_snake_case)] timeoutSecs: Option<u64>,
-) -> Result<Vec<EndpointLatency>, String> {
-    SpeedtestService::test_endpoints(urls, timeoutSecs)
+    app: Option<String>,
+    #[allow(non_snake_case)] providerId: Option<String>,
+) -> Result<Vec<EndpointLatency>, CommandError> {
+    let results = SpeedtestService::test_endpoints(urls, timeoutSecs)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)?;
+    if let (Some(app), Some(provider_id)) = (app, providerId) {
+        let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+        ProviderService::record_endpoint_benchmarks(&state, app_type, &provider_id, &results)
+            .map_err(CommandError::from)?;
+    }
+    Ok(results)
+}
+
+/// 并发测速应用下全部供应商的接口延迟，返回按首字节耗时排序的结果
+#[tauri::command]
+pub async fn benchmark_providers(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] timeoutSecs: Option<u64>,
+) -> Result<Vec<ProviderBenchmark>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::benchmark_providers(&state, app_type, timeoutSecs)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 获取各供应商最近一次的测速结果
+#[tauri::command]
+pub fn get_provider_benchmarks(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<Vec<ProviderBenchmark>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::get_provider_benchmarks(&state, app_type).map_err(CommandError::from)
+}
+
+/// 获取全部供应商组合
+#[tauri::command]
+pub fn get_provider_profiles(
+    state: State<'_, AppState>,
+) -> Result<Vec<ProviderProfile>, CommandError> {
+    ProviderService::list_profiles(&state).map_err(CommandError::from)
+}
+
+/// 新增或更新供应商组合
+#[tauri::command]
+pub fn save_provider_profile(
+    state: State<'_, AppState>,
+    profile: ProviderProfile,
+) -> Result<ProviderProfile, CommandError> {
+    ProviderService::save_profile(&state, profile).map_err(CommandError::from)
+}
+
+/// 删除供应商组合
+#[tauri::command]
+pub fn delete_provider_profile(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<bool, CommandError> {
+    ProviderService::delete_profile(&state, &name).map_err(CommandError::from)
+}
+
+/// 应用供应商组合，任何一个应用切换失败时回滚已切换的应用
+#[tauri::command]
+pub fn apply_provider_profile(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<ProfileApplyResult, CommandError> {
+    ProviderService::apply_profile(&state, &name, ProviderAuditOrigin::Ui)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -188,10 +717,10 @@ pub fn get_custom_endpoints(
     state: State<'_, AppState>,
     app: String,
     #[allow(non_snake_case)] providerId: String,
-) -> Result<Vec<crate::settings::CustomEndpoint>, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<Vec<crate::settings::CustomEndpoint>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::get_custom_endpoints(state.inner(), app_type, &providerId)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -200,10 +729,10 @@ pub fn add_custom_endpoint(
     app: String,
     #[allow(non_snake_case)] providerId: String,
     url: String,
-) -> Result<(), String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::add_custom_endpoint(state.inner(), app_type, &providerId, url)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -212,10 +741,10 @@ pub fn remove_custom_endpoint(
     app: String,
     #[allow(non_snake_case)] providerId: String,
     url: String,
-) -> Result<(), String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::remove_custom_endpoint(state.inner(), app_type, &providerId, url)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -224,10 +753,30 @@ pub fn update_endpoint_last_used(
     app: String,
     #[allow(non_snake_case)] providerId: String,
     url: String,
-) -> Result<(), String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+) -> Result<(), CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
     ProviderService::update_endpoint_last_used(state.inner(), app_type, &providerId, url)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
+}
+
+/// 列出（`dryRun`）或移除连续测速失败的自定义端点，返回涉及的端点
+#[tauri::command]
+pub fn prune_stale_endpoints(
+    state: State<'_, AppState>,
+    app: String,
+    #[allow(non_snake_case)] providerId: Option<String>,
+    #[allow(non_snake_case)] minFailures: Option<u32>,
+    #[allow(non_snake_case)] dryRun: bool,
+) -> Result<Vec<StaleEndpoint>, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::prune_stale_endpoints(
+        state.inner(),
+        app_type,
+        providerId.as_deref(),
+        minFailures.unwrap_or(DEFAULT_STALE_FAILURES),
+        dryRun,
+    )
+    .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -235,14 +784,14 @@ pub fn update_providers_sort_order(
     state: State<'_, AppState>,
     app: String,
     updates: Vec<ProviderSortUpdate>,
-) -> Result<bool, String> {
-    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
-    ProviderService::update_sort_order(state.inner(), app_type, updates).map_err(|e| e.to_string())
+) -> Result<bool, CommandError> {
+    let app_type = AppType::from_str(&app).map_err(CommandError::from)?;
+    ProviderService::update_sort_order(state.inner(), app_type, updates).map_err(CommandError::from)
 }
 
 use crate::provider::UniversalProvider;
 use std::collections::HashMap;
-use tauri::{AppHandle, Emitter};
+use tauri::Emitter;
 
 #[derive(Clone, serde::Serialize)]
 pub struct UniversalProviderSyncedEvent {
@@ -263,16 +812,16 @@ fn emit_universal_provider_synced(app: &AppHandle, action: &str, id: &str) {
 #[tauri::command]
 pub fn get_universal_providers(
     state: State<'_, AppState>,
-) -> Result<HashMap<String, UniversalProvider>, String> {
-    ProviderService::list_universal(state.inner()).map_err(|e| e.to_string())
+) -> Result<HashMap<String, UniversalProvider>, CommandError> {
+    ProviderService::list_universal(state.inner()).map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub fn get_universal_provider(
     state: State<'_, AppState>,
     id: String,
-) -> Result<Option<UniversalProvider>, String> {
-    ProviderService::get_universal(state.inner(), &id).map_err(|e| e.to_string())
+) -> Result<Option<UniversalProvider>, CommandError> {
+    ProviderService::get_universal(state.inner(), &id).map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -280,10 +829,10 @@ pub fn upsert_universal_provider(
     app: AppHandle,
     state: State<'_, AppState>,
     provider: UniversalProvider,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     let id = provider.id.clone();
     let result =
-        ProviderService::upsert_universal(state.inner(), provider).map_err(|e| e.to_string())?;
+        ProviderService::upsert_universal(state.inner(), provider).map_err(CommandError::from)?;
 
     emit_universal_provider_synced(&app, "upsert", &id);
 
@@ -295,9 +844,9 @@ pub fn delete_universal_provider(
     app: AppHandle,
     state: State<'_, AppState>,
     id: String,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     let result =
-        ProviderService::delete_universal(state.inner(), &id).map_err(|e| e.to_string())?;
+        ProviderService::delete_universal(state.inner(), &id).map_err(CommandError::from)?;
 
     emit_universal_provider_synced(&app, "delete", &id);
 
@@ -309,26 +858,49 @@ pub fn sync_universal_provider(
     app: AppHandle,
     state: State<'_, AppState>,
     id: String,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     let result =
-        ProviderService::sync_universal_to_apps(state.inner(), &id).map_err(|e| e.to_string())?;
+        ProviderService::sync_universal_to_apps(state.inner(), &id).map_err(CommandError::from)?;
 
     emit_universal_provider_synced(&app, "sync", &id);
 
     Ok(result)
 }
 
+/// 把统一供应商应用到指定的应用（claude / codex / gemini），生成的供应商与统一供应商保持关联
 #[tauri::command]
-pub fn import_opencode_providers_from_live(state: State<'_, AppState>) -> Result<usize, String> {
+pub fn apply_universal_provider(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    apps: Vec<String>,
+) -> Result<UniversalApplyResult, CommandError> {
+    let app_types = apps
+        .iter()
+        .map(|name| AppType::from_str(name))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(CommandError::from)?;
+    let result = ProviderService::apply_universal(state.inner(), &id, &app_types)
+        .map_err(CommandError::from)?;
+
+    emit_universal_provider_synced(&app, "apply", &id);
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn import_opencode_providers_from_live(
+    state: State<'_, AppState>,
+) -> Result<usize, CommandError> {
     crate::services::provider::import_opencode_providers_from_live(state.inner())
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub fn get_opencode_live_provider_ids() -> Result<Vec<String>, String> {
+pub fn get_opencode_live_provider_ids() -> Result<Vec<String>, CommandError> {
     crate::opencode_config::get_providers()
         .map(|providers| providers.keys().cloned().collect())
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 // ============================================================================
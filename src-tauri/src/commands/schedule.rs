@@ -0,0 +1,45 @@
+//! 定时规则命令
+
+use tauri::State;
+
+use crate::error::CommandError;
+use crate::services::schedule::{Schedule, ScheduledRun};
+use crate::services::{PromptSchedule, PromptScheduleService};
+use crate::store::AppState;
+
+/// 预览定时规则接下来的执行时间（默认 5 次）
+#[tauri::command]
+pub fn get_next_runs(
+    schedule: Schedule,
+    count: Option<usize>,
+) -> Result<Vec<ScheduledRun>, CommandError> {
+    schedule
+        .next_runs(chrono::Utc::now(), count.unwrap_or(5))
+        .map_err(CommandError::from)
+}
+
+/// 获取全部定时切换提示词规则
+#[tauri::command]
+pub fn get_prompt_schedules(
+    state: State<'_, AppState>,
+) -> Result<Vec<PromptSchedule>, CommandError> {
+    PromptScheduleService::list(&state).map_err(CommandError::from)
+}
+
+/// 新增或更新定时切换提示词规则
+#[tauri::command]
+pub fn save_prompt_schedule(
+    state: State<'_, AppState>,
+    schedule: PromptSchedule,
+) -> Result<PromptSchedule, CommandError> {
+    PromptScheduleService::save(&state, schedule).map_err(CommandError::from)
+}
+
+/// 删除定时切换提示词规则
+#[tauri::command]
+pub fn delete_prompt_schedule(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<bool, CommandError> {
+    PromptScheduleService::delete(&state, &id).map_err(CommandError::from)
+}
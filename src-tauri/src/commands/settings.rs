@@ -1,7 +1,10 @@
 #![allow(non_snake_case)]
 
+use std::str::FromStr;
 use tauri::AppHandle;
 
+use crate::error::CommandError;
+
 fn merge_settings_for_save(
     mut incoming: crate::settings::AppSettings,
     existing: &crate::settings::AppSettings,
@@ -9,6 +12,13 @@ fn merge_settings_for_save(
     if incoming.webdav_sync.is_none() {
         incoming.webdav_sync = existing.webdav_sync.clone();
     }
+    // 暂停状态只能通过 set_app_sync_paused 修改（需要同时清理/恢复文件）
+    incoming.paused_sync_apps = existing.paused_sync_apps.clone();
+    // 输出方式切换需要迁移文件，只能通过 set_gemini_agent_output / set_opencode_prompt_target 修改
+    incoming.gemini_agent_output = existing.gemini_agent_output;
+    incoming.opencode_prompt_target = existing.opencode_prompt_target;
+    // 密钥存储位置切换需要迁移密钥，只能通过 set_keychain_storage 修改
+    incoming.store_secrets_in_keychain = existing.store_secrets_in_keychain;
     incoming
 }
 
@@ -23,7 +33,13 @@ pub async fn get_settings() -> Result<crate::settings::AppSettings, String> {
 pub async fn save_settings(settings: crate::settings::AppSettings) -> Result<bool, String> {
     let existing = crate::settings::get_settings();
     let merged = merge_settings_for_save(settings, &existing);
+    let placement_changed = merged.codex_agents_placement != existing.codex_agents_placement;
     crate::settings::update_settings(merged).map_err(|e| e.to_string())?;
+    if placement_changed {
+        if let Err(e) = crate::agents::relayout_codex_agents() {
+            log::warn!("按新位置整理 Codex AGENTS.md 失败: {e}");
+        }
+    }
     Ok(true)
 }
 
@@ -73,13 +89,15 @@ mod tests {
 
     #[test]
     fn save_settings_should_preserve_existing_webdav_when_payload_omits_it() {
-        let mut existing = AppSettings::default();
-        existing.webdav_sync = Some(WebDavSyncSettings {
-            base_url: "https://dav.example.com".to_string(),
-            username: "alice".to_string(),
-            password: "secret".to_string(),
-            ..WebDavSyncSettings::default()
-        });
+        let existing = AppSettings {
+            webdav_sync: Some(WebDavSyncSettings {
+                base_url: "https://dav.example.com".to_string(),
+                username: "alice".to_string(),
+                password: "secret".to_string(),
+                ..WebDavSyncSettings::default()
+            }),
+            ..AppSettings::default()
+        };
 
         let incoming = AppSettings::default();
         let merged = merge_settings_for_save(incoming, &existing);
@@ -93,21 +111,25 @@ mod tests {
 
     #[test]
     fn save_settings_should_keep_incoming_webdav_when_present() {
-        let mut existing = AppSettings::default();
-        existing.webdav_sync = Some(WebDavSyncSettings {
-            base_url: "https://dav.old.example.com".to_string(),
-            username: "old".to_string(),
-            password: "old-pass".to_string(),
-            ..WebDavSyncSettings::default()
-        });
-
-        let mut incoming = AppSettings::default();
-        incoming.webdav_sync = Some(WebDavSyncSettings {
-            base_url: "https://dav.new.example.com".to_string(),
-            username: "new".to_string(),
-            password: "new-pass".to_string(),
-            ..WebDavSyncSettings::default()
-        });
+        let existing = AppSettings {
+            webdav_sync: Some(WebDavSyncSettings {
+                base_url: "https://dav.old.example.com".to_string(),
+                username: "old".to_string(),
+                password: "old-pass".to_string(),
+                ..WebDavSyncSettings::default()
+            }),
+            ..AppSettings::default()
+        };
+
+        let incoming = AppSettings {
+            webdav_sync: Some(WebDavSyncSettings {
+                base_url: "https://dav.new.example.com".to_string(),
+                username: "new".to_string(),
+                password: "new-pass".to_string(),
+                ..WebDavSyncSettings::default()
+            }),
+            ..AppSettings::default()
+        };
 
         let merged = merge_settings_for_save(incoming, &existing);
 
@@ -171,3 +193,109 @@ pub async fn set_log_config(
     );
     Ok(true)
 }
+
+/// 获取已暂停同步的应用列表
+#[tauri::command]
+pub async fn get_paused_sync_apps() -> Result<Vec<String>, CommandError> {
+    Ok(crate::settings::get_paused_sync_apps())
+}
+
+/// 暂停或恢复指定应用的同步
+///
+/// 暂停时移除 cc-switch 管理的 agent / 提示词 / MCP 内容，恢复时按数据库状态重新写入。
+#[tauri::command]
+pub async fn set_app_sync_paused(
+    state: tauri::State<'_, crate::AppState>,
+    app: String,
+    paused: bool,
+) -> Result<bool, CommandError> {
+    let app_type = crate::app_config::AppType::from_str(&app).map_err(CommandError::from)?;
+    crate::services::SyncPauseService::set_paused(&state, &app_type, paused)
+        .map_err(CommandError::from)?;
+    Ok(true)
+}
+
+/// 设置指定应用检测到外部修改时的处理方式
+#[tauri::command]
+pub async fn set_external_edit_policy(
+    app: String,
+    policy: crate::external_edits::ExternalEditPolicy,
+) -> Result<bool, CommandError> {
+    let app_type = crate::app_config::AppType::from_str(&app).map_err(CommandError::from)?;
+    crate::settings::set_external_edit_policy(&app_type, policy).map_err(CommandError::from)?;
+    Ok(true)
+}
+
+/// 获取检测到的外部修改（最新在后）
+#[tauri::command]
+pub async fn get_external_edit_conflicts(
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<Vec<crate::external_edits::ExternalEditConflict>, CommandError> {
+    state
+        .db
+        .get_external_edit_conflicts()
+        .map_err(CommandError::from)
+}
+
+/// 清空外部修改提示
+#[tauri::command]
+pub async fn clear_external_edit_conflicts(
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<bool, CommandError> {
+    state
+        .db
+        .clear_external_edit_conflicts()
+        .map_err(CommandError::from)?;
+    Ok(true)
+}
+
+/// 列出可以实时跟踪的托管文件
+#[tauri::command]
+pub async fn list_managed_files() -> Result<Vec<String>, CommandError> {
+    crate::file_tail::managed_files()
+        .map(|files| files.iter().map(|p| p.display().to_string()).collect())
+        .map_err(CommandError::from)
+}
+
+/// 开始跟踪托管文件，变化通过 `managed-file-changed` 事件推送
+#[tauri::command]
+pub async fn watch_file(
+    app: AppHandle,
+    path: String,
+) -> Result<crate::file_tail::FileWatch, CommandError> {
+    crate::file_tail::watch(app, std::path::Path::new(&path)).map_err(CommandError::from)
+}
+
+/// 停止跟踪文件
+#[tauri::command]
+pub async fn unwatch_file(id: String) -> Result<bool, CommandError> {
+    Ok(crate::file_tail::unwatch(&id))
+}
+
+/// 获取正在跟踪的文件
+#[tauri::command]
+pub async fn get_file_watches() -> Result<Vec<crate::file_tail::FileWatch>, CommandError> {
+    Ok(crate::file_tail::list_watches())
+}
+
+/// 关闭来源横幅并从所有托管文件中去掉已写入的横幅，返回改写的文件数
+///
+/// 先关闭设置，避免后续同步再次写入横幅。
+#[tauri::command]
+pub async fn strip_managed_banners() -> Result<usize, CommandError> {
+    crate::settings::set_managed_banner(false).map_err(CommandError::from)?;
+    crate::managed_banner::strip_banners_from_managed_files()
+        .map(|paths| paths.len())
+        .map_err(CommandError::from)
+}
+
+/// 预览提示词 / agent 在各应用中对应的写入位置（`kind` 为 "prompt" 或 "agent"）
+#[tauri::command]
+pub async fn get_sync_targets(
+    state: tauri::State<'_, crate::AppState>,
+    kind: String,
+    id: String,
+) -> Result<Vec<crate::services::SyncTarget>, CommandError> {
+    let kind = crate::services::SyncTargetKind::from_str(&kind).map_err(CommandError::from)?;
+    crate::services::SyncTargetsService::get_targets(&state, kind, &id).map_err(CommandError::from)
+}
@@ -0,0 +1,20 @@
+//! 分享链接命令
+
+use tauri::State;
+
+use crate::error::CommandError;
+use crate::services::{ShareKind, ShareLink, ShareLinkService};
+use crate::store::AppState;
+
+/// 加密上传提示词或 Agent 到中转服务，返回 `ttl` 秒后过期的 `ccswitch://` 分享链接
+#[tauri::command]
+pub async fn create_share_link(
+    state: State<'_, AppState>,
+    kind: ShareKind,
+    id: String,
+    ttl: u64,
+) -> Result<ShareLink, CommandError> {
+    ShareLinkService::create_share_link(&state, kind, &id, ttl)
+        .await
+        .map_err(CommandError::from)
+}
@@ -5,23 +5,28 @@
 //! - SSOT 存储在 ~/.cc-switch/skills/
 
 use crate::app_config::{AppType, InstalledSkill, UnmanagedSkill};
-use crate::error::format_skill_error;
+use crate::error::{format_skill_error, AppError, CommandError, ErrorCode};
 use crate::services::skill::{DiscoverableSkill, Skill, SkillRepo, SkillService};
+use crate::services::{JobService, SkillIntegrityIssue, SkillRepairResult};
 use crate::store::AppState;
+use serde_json::json;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 /// SkillService 状态包装
 pub struct SkillServiceState(pub Arc<SkillService>);
 
 /// 解析 app 参数为 AppType
-fn parse_app_type(app: &str) -> Result<AppType, String> {
+fn parse_app_type(app: &str) -> Result<AppType, CommandError> {
     match app.to_lowercase().as_str() {
         "claude" => Ok(AppType::Claude),
         "codex" => Ok(AppType::Codex),
         "gemini" => Ok(AppType::Gemini),
         "opencode" => Ok(AppType::OpenCode),
-        _ => Err(format!("不支持的 app 类型: {app}")),
+        _ => Err(CommandError::new(
+            ErrorCode::Validation,
+            format!("不支持的 app 类型: {app}"),
+        )),
     }
 }
 
@@ -29,8 +34,10 @@ fn parse_app_type(app: &str) -> Result<AppType, String> {
 
 /// 获取所有已安装的 Skills
 #[tauri::command]
-pub fn get_installed_skills(app_state: State<'_, AppState>) -> Result<Vec<InstalledSkill>, String> {
-    SkillService::get_all_installed(&app_state.db).map_err(|e| e.to_string())
+pub fn get_installed_skills(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<InstalledSkill>, CommandError> {
+    SkillService::get_all_installed(&app_state.db).map_err(CommandError::from)
 }
 
 /// 安装 Skill（新版统一安装）
@@ -44,20 +51,23 @@ pub async fn install_skill_unified(
     current_app: String,
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<InstalledSkill, String> {
+) -> Result<InstalledSkill, CommandError> {
     let app_type = parse_app_type(&current_app)?;
 
     service
         .0
         .install(&app_state.db, &skill, &app_type)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 卸载 Skill（新版统一卸载）
 #[tauri::command]
-pub fn uninstall_skill_unified(id: String, app_state: State<'_, AppState>) -> Result<bool, String> {
-    SkillService::uninstall(&app_state.db, &id).map_err(|e| e.to_string())?;
+pub fn uninstall_skill_unified(
+    id: String,
+    app_state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
+    SkillService::uninstall(&app_state.db, &id).map_err(CommandError::from)?;
     Ok(true)
 }
 
@@ -68,9 +78,9 @@ pub fn toggle_skill_app(
     app: String,
     enabled: bool,
     app_state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     let app_type = parse_app_type(&app)?;
-    SkillService::toggle_app(&app_state.db, &id, &app_type, enabled).map_err(|e| e.to_string())?;
+    SkillService::toggle_app(&app_state.db, &id, &app_type, enabled).map_err(CommandError::from)?;
     Ok(true)
 }
 
@@ -78,8 +88,8 @@ pub fn toggle_skill_app(
 #[tauri::command]
 pub fn scan_unmanaged_skills(
     app_state: State<'_, AppState>,
-) -> Result<Vec<UnmanagedSkill>, String> {
-    SkillService::scan_unmanaged(&app_state.db).map_err(|e| e.to_string())
+) -> Result<Vec<UnmanagedSkill>, CommandError> {
+    SkillService::scan_unmanaged(&app_state.db).map_err(CommandError::from)
 }
 
 /// 从应用目录导入 Skills
@@ -87,8 +97,30 @@ pub fn scan_unmanaged_skills(
 pub fn import_skills_from_apps(
     directories: Vec<String>,
     app_state: State<'_, AppState>,
-) -> Result<Vec<InstalledSkill>, String> {
-    SkillService::import_from_apps(&app_state.db, directories).map_err(|e| e.to_string())
+) -> Result<Vec<InstalledSkill>, CommandError> {
+    SkillService::import_from_apps(&app_state.db, directories).map_err(CommandError::from)
+}
+
+/// 校验 SSOT 中 Skill 文件是否完整，返回文件缺失或被改动的 Skill
+#[tauri::command]
+pub fn verify_skills_integrity(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<SkillIntegrityIssue>, CommandError> {
+    SkillService::verify_integrity(&app_state.db).map_err(CommandError::from)
+}
+
+/// 按记录的来源仓库与提交重新下载并恢复 Skill（`ids` 为空时修复全部有问题的 Skill）
+#[tauri::command]
+pub async fn repair_skills(
+    ids: Vec<String>,
+    service: State<'_, SkillServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<SkillRepairResult, CommandError> {
+    service
+        .0
+        .repair(&app_state.db, &ids)
+        .await
+        .map_err(CommandError::from)
 }
 
 // ========== 发现功能命令 ==========
@@ -98,13 +130,57 @@ pub fn import_skills_from_apps(
 pub async fn discover_available_skills(
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<Vec<DiscoverableSkill>, String> {
-    let repos = app_state.db.get_skill_repos().map_err(|e| e.to_string())?;
+) -> Result<Vec<DiscoverableSkill>, CommandError> {
+    let repos = app_state.db.get_skill_repos().map_err(CommandError::from)?;
     service
         .0
-        .discover_available(repos)
+        .discover_available(repos, &app_state.db)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
+}
+
+/// 在后台强制刷新所有已启用仓库的技能索引，立即返回任务 id
+///
+/// 每个仓库为一步；单个仓库失败不影响其他仓库，失败的仓库列在结果的 `failed` 中。
+#[tauri::command]
+pub fn start_skill_repo_refresh_job(
+    app: AppHandle,
+    service: State<'_, SkillServiceState>,
+    app_state: State<'_, AppState>,
+) -> Result<String, CommandError> {
+    let repos: Vec<SkillRepo> = app_state
+        .db
+        .get_skill_repos()
+        .map_err(CommandError::from)?
+        .into_iter()
+        .filter(|repo| repo.enabled)
+        .collect();
+    let service = service.0.clone();
+    let db = app_state.db.clone();
+
+    Ok(JobService::spawn(
+        &app,
+        "skill-repo-refresh",
+        move |ctx| async move {
+            let total = repos.len();
+            let mut refreshed = 0;
+            let mut failed = Vec::new();
+            for (index, repo) in repos.iter().enumerate() {
+                ctx.ensure_not_cancelled()?;
+                let label = format!("{}/{}", repo.owner, repo.name);
+                ctx.progress(index, total, label.clone());
+                match service.refresh_repo_index(repo, &db).await {
+                    Ok(_) => refreshed += 1,
+                    Err(e) => {
+                        log::warn!("刷新技能仓库 {label} 失败: {e}");
+                        failed.push(label);
+                    }
+                }
+            }
+            ctx.progress(total, total, "");
+            Ok::<_, AppError>(json!({ "refreshed": refreshed, "failed": failed }))
+        },
+    ))
 }
 
 // ========== 兼容旧 API 的命令 ==========
@@ -114,13 +190,13 @@ pub async fn discover_available_skills(
 pub async fn get_skills(
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<Vec<Skill>, String> {
-    let repos = app_state.db.get_skill_repos().map_err(|e| e.to_string())?;
+) -> Result<Vec<Skill>, CommandError> {
+    let repos = app_state.db.get_skill_repos().map_err(CommandError::from)?;
     service
         .0
         .list_skills(repos, &app_state.db)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// 获取指定应用的技能列表（兼容旧 API）
@@ -129,7 +205,7 @@ pub async fn get_skills_for_app(
     app: String,
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<Vec<Skill>, String> {
+) -> Result<Vec<Skill>, CommandError> {
     // 新版本不再区分应用，统一返回所有技能
     let _ = parse_app_type(&app)?; // 验证 app 参数有效
     get_skills(service, app_state).await
@@ -141,7 +217,7 @@ pub async fn install_skill(
     directory: String,
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     install_skill_for_app("claude".to_string(), directory, service, app_state).await
 }
 
@@ -152,16 +228,16 @@ pub async fn install_skill_for_app(
     directory: String,
     service: State<'_, SkillServiceState>,
     app_state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     let app_type = parse_app_type(&app)?;
 
     // 先获取技能信息
-    let repos = app_state.db.get_skill_repos().map_err(|e| e.to_string())?;
+    let repos = app_state.db.get_skill_repos().map_err(CommandError::from)?;
     let skills = service
         .0
-        .discover_available(repos)
+        .discover_available(repos, &app_state.db)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(CommandError::from)?;
 
     let skill = skills
         .into_iter()
@@ -185,14 +261,17 @@ pub async fn install_skill_for_app(
         .0
         .install(&app_state.db, &skill, &app_type)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(CommandError::from)?;
 
     Ok(true)
 }
 
 /// 卸载技能（兼容旧 API）
 #[tauri::command]
-pub fn uninstall_skill(directory: String, app_state: State<'_, AppState>) -> Result<bool, String> {
+pub fn uninstall_skill(
+    directory: String,
+    app_state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
     uninstall_skill_for_app("claude".to_string(), directory, app_state)
 }
 
@@ -202,18 +281,18 @@ pub fn uninstall_skill_for_app(
     app: String,
     directory: String,
     app_state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     let _ = parse_app_type(&app)?; // 验证参数
 
     // 通过 directory 找到对应的 skill id
-    let skills = SkillService::get_all_installed(&app_state.db).map_err(|e| e.to_string())?;
+    let skills = SkillService::get_all_installed(&app_state.db).map_err(CommandError::from)?;
 
     let skill = skills
         .into_iter()
         .find(|s| s.directory.eq_ignore_ascii_case(&directory))
         .ok_or_else(|| format!("未找到已安装的 Skill: {directory}"))?;
 
-    SkillService::uninstall(&app_state.db, &skill.id).map_err(|e| e.to_string())?;
+    SkillService::uninstall(&app_state.db, &skill.id).map_err(CommandError::from)?;
 
     Ok(true)
 }
@@ -222,17 +301,20 @@ pub fn uninstall_skill_for_app(
 
 /// 获取技能仓库列表
 #[tauri::command]
-pub fn get_skill_repos(app_state: State<'_, AppState>) -> Result<Vec<SkillRepo>, String> {
-    app_state.db.get_skill_repos().map_err(|e| e.to_string())
+pub fn get_skill_repos(app_state: State<'_, AppState>) -> Result<Vec<SkillRepo>, CommandError> {
+    app_state.db.get_skill_repos().map_err(CommandError::from)
 }
 
 /// 添加技能仓库
 #[tauri::command]
-pub fn add_skill_repo(repo: SkillRepo, app_state: State<'_, AppState>) -> Result<bool, String> {
+pub fn add_skill_repo(
+    repo: SkillRepo,
+    app_state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
     app_state
         .db
         .save_skill_repo(&repo)
-        .map_err(|e| e.to_string())?;
+        .map_err(CommandError::from)?;
     Ok(true)
 }
 
@@ -242,11 +324,11 @@ pub fn remove_skill_repo(
     owner: String,
     name: String,
     app_state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     app_state
         .db
         .delete_skill_repo(&owner, &name)
-        .map_err(|e| e.to_string())?;
+        .map_err(CommandError::from)?;
     Ok(true)
 }
 
@@ -256,9 +338,9 @@ pub fn install_skills_from_zip(
     file_path: String,
     current_app: String,
     app_state: State<'_, AppState>,
-) -> Result<Vec<InstalledSkill>, String> {
+) -> Result<Vec<InstalledSkill>, CommandError> {
     let app_type = parse_app_type(&current_app)?;
     let path = std::path::Path::new(&file_path);
 
-    SkillService::install_from_zip(&app_state.db, path, &app_type).map_err(|e| e.to_string())
+    SkillService::install_from_zip(&app_state.db, path, &app_type).map_err(CommandError::from)
 }
@@ -2,16 +2,70 @@
 
 use crate::app_config::AppType;
 use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::provider::{IssueSource, ProviderService};
 use crate::services::stream_check::{
     HealthStatus, StreamCheckConfig, StreamCheckResult, StreamCheckService,
 };
+use crate::services::{JobContext, JobService};
 use crate::store::AppState;
 use std::collections::HashSet;
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// 根据检查结果更新供应商的凭据问题（失败不影响检查结果）
+fn record_credential_outcome(
+    state: &AppState,
+    app_type: &AppType,
+    provider_id: &str,
+    result: &StreamCheckResult,
+) {
+    if let Err(e) = ProviderService::record_credential_outcome(
+        &state.db,
+        app_type.as_str(),
+        provider_id,
+        result.success,
+        result.http_status,
+        &result.message,
+        IssueSource::HealthCheck,
+    ) {
+        log::warn!("记录供应商 {provider_id} 凭据问题失败: {e}");
+    }
+}
+
+/// 当前供应商的延迟相对滚动基线明显变慢时，发射 `latency-regression` 事件
+fn report_latency_regression(
+    app: &AppHandle,
+    state: &AppState,
+    app_type: &AppType,
+    provider: &Provider,
+    result: &StreamCheckResult,
+    log_id: i64,
+    config: &StreamCheckConfig,
+) {
+    let current_id = crate::settings::get_effective_current_provider(&state.db, app_type)
+        .ok()
+        .flatten();
+    if current_id.as_deref() != Some(provider.id.as_str()) {
+        return;
+    }
+
+    match StreamCheckService::detect_latency_regression(
+        &state.db, app_type, provider, result, log_id, config,
+    ) {
+        Ok(Some(regression)) => {
+            if let Err(e) = app.emit("latency-regression", &regression) {
+                log::warn!("发射延迟回归事件失败: {e}");
+            }
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("检测延迟回归失败: {e}"),
+    }
+}
 
 /// 流式健康检查（单个供应商）
 #[tauri::command]
 pub async fn stream_check_provider(
+    app: AppHandle,
     state: State<'_, AppState>,
     app_type: AppType,
     provider_id: String,
@@ -25,11 +79,22 @@ pub async fn stream_check_provider(
 
     let result = StreamCheckService::check_with_retry(&app_type, provider, &config).await?;
 
+    // 维护中的供应商仍可手动检查，但不记录凭据问题、不发出告警
+    let in_maintenance = provider.in_maintenance(chrono::Utc::now().timestamp());
+    if !in_maintenance {
+        record_credential_outcome(&state, &app_type, &provider_id, &result);
+    }
+
     // 记录日志
-    let _ =
+    if let Ok(log_id) =
         state
             .db
-            .save_stream_check_log(&provider_id, &provider.name, app_type.as_str(), &result);
+            .save_stream_check_log(&provider_id, &provider.name, app_type.as_str(), &result)
+    {
+        if !in_maintenance {
+            report_latency_regression(&app, &state, &app_type, provider, &result, log_id, &config);
+        }
+    }
 
     Ok(result)
 }
@@ -37,9 +102,39 @@ pub async fn stream_check_provider(
 /// 批量流式健康检查
 #[tauri::command]
 pub async fn stream_check_all_providers(
+    app: AppHandle,
     state: State<'_, AppState>,
     app_type: AppType,
     proxy_targets_only: bool,
+) -> Result<Vec<(String, StreamCheckResult)>, AppError> {
+    check_providers(&app, &state, &app_type, proxy_targets_only, None).await
+}
+
+/// 在后台执行批量流式健康检查，立即返回任务 id（结果随 `job-completed` 事件返回）
+#[tauri::command]
+pub fn start_stream_check_job(
+    app: AppHandle,
+    app_type: AppType,
+    proxy_targets_only: bool,
+) -> Result<String, AppError> {
+    let handle = app.clone();
+    Ok(JobService::spawn(
+        &app,
+        "stream-check",
+        move |ctx| async move {
+            let state = handle.state::<AppState>();
+            check_providers(&handle, &state, &app_type, proxy_targets_only, Some(&ctx)).await
+        },
+    ))
+}
+
+/// 依次检查供应商（跳过维护中的供应商）；`job` 存在时每个供应商上报一次进度，并在供应商之间响应取消
+async fn check_providers(
+    app: &AppHandle,
+    state: &AppState,
+    app_type: &AppType,
+    proxy_targets_only: bool,
+    job: Option<&JobContext>,
 ) -> Result<Vec<(String, StreamCheckResult)>, AppError> {
     let config = state.db.get_stream_check_config()?;
     let providers = state.db.get_all_providers(app_type.as_str())?;
@@ -60,14 +155,21 @@ pub async fn stream_check_all_providers(
         None
     };
 
-    for (id, provider) in providers {
-        if let Some(ids) = &allowed_ids {
-            if !ids.contains(&id) {
-                continue;
-            }
+    let now = chrono::Utc::now().timestamp();
+    let targets: Vec<(String, Provider)> = providers
+        .into_iter()
+        .filter(|(id, _)| allowed_ids.as_ref().is_none_or(|ids| ids.contains(id)))
+        .filter(|(_, provider)| !provider.in_maintenance(now))
+        .collect();
+    let total = targets.len();
+
+    for (index, (id, provider)) in targets.into_iter().enumerate() {
+        if let Some(job) = job {
+            job.ensure_not_cancelled()?;
+            job.progress(index, total, provider.name.clone());
         }
 
-        let result = StreamCheckService::check_with_retry(&app_type, &provider, &config)
+        let result = StreamCheckService::check_with_retry(app_type, &provider, &config)
             .await
             .unwrap_or_else(|e| StreamCheckResult {
                 status: HealthStatus::Failed,
@@ -80,13 +182,22 @@ pub async fn stream_check_all_providers(
                 retry_count: 0,
             });
 
-        let _ = state
-            .db
-            .save_stream_check_log(&id, &provider.name, app_type.as_str(), &result);
+        record_credential_outcome(state, app_type, &id, &result);
+
+        if let Ok(log_id) =
+            state
+                .db
+                .save_stream_check_log(&id, &provider.name, app_type.as_str(), &result)
+        {
+            report_latency_regression(app, state, app_type, &provider, &result, log_id, &config);
+        }
 
         results.push((id, result));
     }
 
+    if let Some(job) = job {
+        job.progress(total, total, "");
+    }
     Ok(results)
 }
 
@@ -31,6 +31,19 @@ pub fn get_provider_stats(state: State<'_, AppState>) -> Result<Vec<ProviderStat
     state.db.get_provider_stats()
 }
 
+/// 获取供应商按小时（本地时间）的延迟分布，`days` 缺省为 30
+#[tauri::command]
+pub fn get_latency_heatmap(
+    state: State<'_, AppState>,
+    provider_id: String,
+    app_type: Option<String>,
+    days: Option<u32>,
+) -> Result<LatencyHeatmap, AppError> {
+    state
+        .db
+        .get_latency_heatmap(&provider_id, app_type.as_deref(), days.unwrap_or(30))
+}
+
 /// 获取模型统计
 #[tauri::command]
 pub fn get_model_stats(state: State<'_, AppState>) -> Result<Vec<ModelStats>, AppError> {
@@ -235,6 +235,7 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), AppError> {
             source: e,
         })?;
     }
+    crate::file_tail::note_self_write(path);
     Ok(())
 }
 
@@ -286,6 +287,7 @@ pub fn copy_file(from: &Path, to: &Path) -> Result<(), AppError> {
 pub fn delete_file(path: &Path) -> Result<(), AppError> {
     if path.exists() {
         fs::remove_file(path).map_err(|e| AppError::io(path, e))?;
+        crate::file_tail::note_self_write(path);
     }
     Ok(())
 }
@@ -0,0 +1,56 @@
+//! AES-256-GCM 加解密
+//!
+//! 分享链接与供应商导出共用的密文格式：随机 nonce + 密文 + tag。调用方各自负责编码
+//! （base64 / base64url）与附加数据（AAD），以区分不同用途的密文。
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::error::AppError;
+
+/// AES-256 密钥长度
+pub(crate) const KEY_LEN: usize = 32;
+
+/// 生成安全随机字节（密钥、nonce、盐）
+pub(crate) fn random_bytes<const N: usize>() -> Result<[u8; N], AppError> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| AppError::Message("生成随机数失败".to_string()))?;
+    Ok(bytes)
+}
+
+/// 加密，输出为 nonce + 密文 + tag
+pub(crate) fn seal(key: &[u8; KEY_LEN], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+    let sealing = LessSafeKey::new(
+        UnboundKey::new(&AES_256_GCM, key)
+            .map_err(|_| AppError::Message("初始化加密密钥失败".to_string()))?,
+    );
+    let nonce = random_bytes::<NONCE_LEN>()?;
+    let mut in_out = plaintext.to_vec();
+    sealing
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(aad),
+            &mut in_out,
+        )
+        .map_err(|_| AppError::Message("加密数据失败".to_string()))?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&in_out);
+    Ok(sealed)
+}
+
+/// 解密 [`seal`] 的输出；密钥错误、附加数据不符或内容被篡改时返回 `None`
+pub(crate) fn open(key: &[u8], aad: &[u8], sealed: &[u8]) -> Option<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let opening = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).ok()?);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = opening
+        .open_in_place(nonce, Aad::from(aad), &mut in_out)
+        .ok()?;
+    Some(plaintext.to_vec())
+}
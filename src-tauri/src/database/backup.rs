@@ -2,7 +2,7 @@
 //!
 //! 提供 SQL 导出/导入和二进制快照备份功能。
 
-use super::{lock_conn, Database};
+use super::{lock_conn, Database, AGENT_FTS_TABLE, PROMPT_FTS_TABLE};
 use crate::config::get_app_config_dir;
 use crate::error::AppError;
 use chrono::Utc;
@@ -295,6 +295,10 @@ impl Database {
             if name.starts_with("sqlite_") {
                 continue;
             }
+            // 全文索引可由源表重建，导入后由 create_tables 重新创建
+            if name.starts_with(AGENT_FTS_TABLE) || name.starts_with(PROMPT_FTS_TABLE) {
+                continue;
+            }
 
             output.push_str(&sql);
             output.push_str(";\n");
@@ -2,155 +2,445 @@
 //!
 //! 提供 agent_definitions 表的 CRUD 操作。
 
-use crate::agent::AgentDefinition;
-use crate::app_config::McpApps;
-use crate::database::{lock_conn, Database};
+use super::providers::tags_json;
+use crate::agent::{AgentApps, AgentDefinition, AgentRevision};
+use crate::database::{lock_conn, Database, AGENT_FTS_TABLE};
 use crate::error::AppError;
 use indexmap::IndexMap;
-use rusqlite::params;
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+
+/// SELECT 列清单（与 [`row_to_agent`] 的列序保持一致）
+const AGENT_COLUMNS: &str = "id, name, content, description,
+    enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_openclaw,
+    created_at, updated_at, tools, model, color, sort_index, group_name, tags";
+
+/// 每个 Agent 保留的历史版本数上限（超出后删除最旧的版本）
+const MAX_AGENT_REVISIONS: i64 = 50;
+
+/// 将查询行映射为 AgentDefinition
+fn row_to_agent(row: &Row<'_>) -> rusqlite::Result<AgentDefinition> {
+    Ok(AgentDefinition {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        content: row.get(2)?,
+        description: row.get(3)?,
+        apps: AgentApps {
+            claude: row.get(4)?,
+            codex: row.get(5)?,
+            gemini: row.get(6)?,
+            opencode: row.get(7)?,
+            openclaw: row.get(8)?,
+        },
+        tools: row.get(11)?,
+        model: row.get(12)?,
+        color: row.get(13)?,
+        sort_index: row.get(14)?,
+        group: row.get(15)?,
+        tags: parse_tags(row.get::<_, Option<String>>(16)?.as_deref()),
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
 
 impl Database {
-    /// 获取所有 Agent 定义（按 created_at ASC, id ASC 排序）
+    /// 获取所有 Agent 定义（按 sort_index，再按 created_at、id 排序）
     pub fn get_all_agents(&self) -> Result<IndexMap<String, AgentDefinition>, AppError> {
         let conn = lock_conn!(self.conn);
-        let mut stmt = conn.prepare(
-            "SELECT id, name, content, description,
-                    enabled_claude, enabled_codex, enabled_gemini, enabled_opencode,
-                    created_at, updated_at
+        let sql = format!(
+            "SELECT {AGENT_COLUMNS}
              FROM agent_definitions
-             ORDER BY created_at ASC, id ASC",
-        )
-        .map_err(|e| AppError::Database(e.to_string()))?;
+             ORDER BY COALESCE(sort_index, 999999), created_at ASC, id ASC"
+        );
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         let agent_iter = stmt
-            .query_map([], |row| {
-                let id: String = row.get(0)?;
-                let name: String = row.get(1)?;
-                let content: String = row.get(2)?;
-                let description: Option<String> = row.get(3)?;
-                let enabled_claude: bool = row.get(4)?;
-                let enabled_codex: bool = row.get(5)?;
-                let enabled_gemini: bool = row.get(6)?;
-                let enabled_opencode: bool = row.get(7)?;
-                let created_at: Option<i64> = row.get(8)?;
-                let updated_at: Option<i64> = row.get(9)?;
-
-                Ok((
-                    id.clone(),
-                    AgentDefinition {
-                        id,
-                        name,
-                        content,
-                        description,
-                        apps: McpApps {
-                            claude: enabled_claude,
-                            codex: enabled_codex,
-                            gemini: enabled_gemini,
-                            opencode: enabled_opencode,
-                        },
-                        created_at,
-                        updated_at,
-                    },
-                ))
-            })
+            .query_map([], row_to_agent)
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let mut agents = IndexMap::new();
         for agent_res in agent_iter {
-            let (id, agent) = agent_res.map_err(|e| AppError::Database(e.to_string()))?;
-            agents.insert(id, agent);
+            let agent = agent_res.map_err(|e| AppError::Database(e.to_string()))?;
+            agents.insert(agent.id.clone(), agent);
         }
         Ok(agents)
     }
 
+    /// 按关键词搜索 Agent（匹配 name / description / content，不区分大小写）
+    ///
+    /// 以空白分隔的多个关键词需全部命中；结果顺序与 [`Self::get_all_agents`] 一致。
+    /// 不少于 3 个字符的关键词走 FTS5 trigram 索引，更短的关键词退回 LIKE 子串匹配。
+    /// 查询为空时返回全部 Agent。
+    pub fn search_agents(&self, query: &str) -> Result<Vec<AgentDefinition>, AppError> {
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() {
+            return Ok(self.get_all_agents()?.into_values().collect());
+        }
+
+        let (conditions, values) = search_conditions(&terms, AGENT_FTS_TABLE);
+        let conn = lock_conn!(self.conn);
+        let sql = format!(
+            "SELECT {AGENT_COLUMNS}
+             FROM agent_definitions
+             WHERE {conditions}
+             ORDER BY COALESCE(sort_index, 999999), created_at ASC, id ASC"
+        );
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), row_to_agent)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.map(|r| r.map_err(|e| AppError::Database(e.to_string())))
+            .collect()
+    }
+
     /// 按 id 查询单个 Agent（避免全表扫描）
     pub fn get_agent_by_id(&self, id: &str) -> Result<Option<AgentDefinition>, AppError> {
         let conn = lock_conn!(self.conn);
-        let mut stmt = conn.prepare(
-            "SELECT id, name, content, description,
-                    enabled_claude, enabled_codex, enabled_gemini, enabled_opencode,
-                    created_at, updated_at
+        let sql = format!(
+            "SELECT {AGENT_COLUMNS}
              FROM agent_definitions
-             WHERE id = ?1",
-        )
-        .map_err(|e| AppError::Database(e.to_string()))?;
+             WHERE id = ?1"
+        );
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| AppError::Database(e.to_string()))?;
 
         let mut rows = stmt
             .query(params![id])
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         if let Some(row) = rows.next().map_err(|e| AppError::Database(e.to_string()))? {
-            let agent_id: String = row.get(0).map_err(|e| AppError::Database(e.to_string()))?;
-            let name: String = row.get(1).map_err(|e| AppError::Database(e.to_string()))?;
-            let content: String = row.get(2).map_err(|e| AppError::Database(e.to_string()))?;
-            let description: Option<String> =
-                row.get(3).map_err(|e| AppError::Database(e.to_string()))?;
-            let enabled_claude: bool =
-                row.get(4).map_err(|e| AppError::Database(e.to_string()))?;
-            let enabled_codex: bool =
-                row.get(5).map_err(|e| AppError::Database(e.to_string()))?;
-            let enabled_gemini: bool =
-                row.get(6).map_err(|e| AppError::Database(e.to_string()))?;
-            let enabled_opencode: bool =
-                row.get(7).map_err(|e| AppError::Database(e.to_string()))?;
-            let created_at: Option<i64> =
-                row.get(8).map_err(|e| AppError::Database(e.to_string()))?;
-            let updated_at: Option<i64> =
-                row.get(9).map_err(|e| AppError::Database(e.to_string()))?;
-
-            Ok(Some(AgentDefinition {
-                id: agent_id,
-                name,
-                content,
-                description,
-                apps: McpApps {
-                    claude: enabled_claude,
-                    codex: enabled_codex,
-                    gemini: enabled_gemini,
-                    opencode: enabled_opencode,
-                },
-                created_at,
-                updated_at,
-            }))
+            let agent = row_to_agent(row).map_err(|e| AppError::Database(e.to_string()))?;
+            Ok(Some(agent))
         } else {
             Ok(None)
         }
     }
 
-    /// 保存（新增或替换）Agent 定义
+    /// 保存（新增或替换）Agent 定义，并追加一条历史版本快照
+    ///
+    /// `sort_index` 为空时沿用已有记录的位置，新 agent 排在最后。
     pub fn save_agent(&self, agent: &AgentDefinition) -> Result<(), AppError> {
+        self.save_agents(std::slice::from_ref(agent))
+    }
+
+    /// 在同一个事务中批量保存 Agent 定义（每个 agent 各追加一条历史版本）
+    pub fn save_agents(&self, agents: &[AgentDefinition]) -> Result<(), AppError> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for agent in agents {
+            write_agent_row(&tx, agent, now)?;
+        }
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 撤销刚刚保存的 Agent（工具文件同步失败时的补偿）
+    ///
+    /// 每一项为 (id, 保存前的定义)：删除本次保存追加的历史版本，
+    /// 再把行恢复为保存前的定义；保存前不存在（新建）时删除该行。
+    pub fn revert_agent_saves(
+        &self,
+        reverts: &[(&str, Option<&AgentDefinition>)],
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for (id, previous) in reverts {
+            tx.execute(
+                "DELETE FROM agent_definition_history
+                 WHERE agent_id = ?1 AND version = (
+                     SELECT MAX(version) FROM agent_definition_history WHERE agent_id = ?1
+                 )",
+                params![id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            match previous {
+                Some(agent) => upsert_agent_row(&tx, agent)?,
+                None => {
+                    tx.execute("DELETE FROM agent_definitions WHERE id = ?1", params![id])
+                        .map_err(|e| AppError::Database(e.to_string()))?;
+                }
+            }
+        }
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 只追加一条历史版本快照（不改动当前定义），返回新版本号
+    pub fn append_agent_revision(&self, agent: &AgentDefinition) -> Result<i64, AppError> {
         let conn = lock_conn!(self.conn);
-        conn.execute(
-            "INSERT OR REPLACE INTO agent_definitions (
-                id, name, content, description,
-                enabled_claude, enabled_codex, enabled_gemini, enabled_opencode,
-                created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![
-                agent.id,
-                agent.name,
-                agent.content,
-                agent.description,
-                agent.apps.claude,
-                agent.apps.codex,
-                agent.apps.gemini,
-                agent.apps.opencode,
-                agent.created_at,
-                agent.updated_at,
-            ],
+        write_agent_revision(&conn, agent, chrono::Utc::now().timestamp_millis())
+    }
+
+    /// 获取 Agent 的历史版本（按版本号倒序，最新在前）
+    pub fn get_agent_history(&self, agent_id: &str) -> Result<Vec<AgentRevision>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT version, snapshot, created_at
+                 FROM agent_definition_history
+                 WHERE agent_id = ?1
+                 ORDER BY version DESC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![agent_id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut revisions = Vec::new();
+        for row in rows {
+            let (version, snapshot, created_at) =
+                row.map_err(|e| AppError::Database(e.to_string()))?;
+            revisions.push(parse_revision(agent_id, version, &snapshot, created_at)?);
+        }
+        Ok(revisions)
+    }
+
+    /// 获取 Agent 的指定历史版本
+    pub fn get_agent_revision(
+        &self,
+        agent_id: &str,
+        version: i64,
+    ) -> Result<Option<AgentRevision>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let row = conn
+            .query_row(
+                "SELECT snapshot, created_at
+                 FROM agent_definition_history
+                 WHERE agent_id = ?1 AND version = ?2",
+                params![agent_id, version],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        row.map(|(snapshot, created_at)| parse_revision(agent_id, version, &snapshot, created_at))
+            .transpose()
+    }
+
+    /// 按给定顺序重写 Agent 的 sort_index（单个事务，不产生历史版本）
+    ///
+    /// 未出现在 `ids` 中的 agent 保持原有 sort_index，排在重排后的 agent 之后。
+    pub fn reorder_agents(&self, ids: &[String]) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        tx.execute(
+            "UPDATE agent_definitions SET sort_index = sort_index + ?1 WHERE sort_index IS NOT NULL",
+            params![ids.len() as i64],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
+        for (index, id) in ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE agent_definitions SET sort_index = ?1 WHERE id = ?2",
+                params![index as i64, id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
 
-    /// 删除 Agent 定义
+    /// 删除 Agent 定义（连同其历史版本）
     pub fn delete_agent(&self, id: &str) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
+        conn.execute("DELETE FROM agent_definitions WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
         conn.execute(
-            "DELETE FROM agent_definitions WHERE id = ?1",
+            "DELETE FROM agent_definition_history WHERE agent_id = ?1",
             params![id],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
 }
+
+/// 生成关键词搜索的 WHERE 条件与参数（name / description / content 三列）
+///
+/// 不少于 3 个字符的关键词合并为一个对 `fts_table` 的 MATCH，其余各自生成 LIKE 条件，全部以 AND 连接。
+pub(super) fn search_conditions(terms: &[&str], fts_table: &str) -> (String, Vec<String>) {
+    let mut conditions = Vec::new();
+    let mut values: Vec<String> = Vec::new();
+    let fts_terms: Vec<String> = terms
+        .iter()
+        .filter(|t| t.chars().count() >= 3)
+        .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+        .collect();
+    if !fts_terms.is_empty() {
+        values.push(fts_terms.join(" "));
+        conditions.push(format!(
+            "rowid IN (SELECT rowid FROM {fts_table} WHERE {fts_table} MATCH ?{})",
+            values.len()
+        ));
+    }
+    for term in terms.iter().filter(|t| t.chars().count() < 3) {
+        values.push(format!("%{}%", escape_like(term)));
+        let n = values.len();
+        conditions.push(format!(
+            "(name LIKE ?{n} ESCAPE '\\' OR description LIKE ?{n} ESCAPE '\\' \
+             OR content LIKE ?{n} ESCAPE '\\')"
+        ));
+    }
+    (conditions.join(" AND "), values)
+}
+
+/// 转义 LIKE 模式中的通配符（配合 `ESCAPE '\'` 使用）
+fn escape_like(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+    for c in term.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// 写入单个 Agent 并追加历史快照（超出上限的旧版本一并清理）
+pub(super) fn write_agent_row(
+    tx: &Transaction<'_>,
+    agent: &AgentDefinition,
+    now: i64,
+) -> Result<(), AppError> {
+    upsert_agent_row(tx, agent)?;
+    write_agent_revision(tx, agent, now)?;
+    Ok(())
+}
+
+/// 追加一条 agent 历史版本并裁剪超出上限的旧版本，返回新版本号
+fn write_agent_revision(
+    conn: &Connection,
+    agent: &AgentDefinition,
+    now: i64,
+) -> Result<i64, AppError> {
+    let snapshot = serde_json::to_string(agent).map_err(|e| AppError::Database(e.to_string()))?;
+    let version: i64 = conn
+        .query_row(
+            "INSERT INTO agent_definition_history (agent_id, version, snapshot, created_at)
+             SELECT ?1, COALESCE(MAX(version), 0) + 1, ?2, ?3
+             FROM agent_definition_history WHERE agent_id = ?1
+             RETURNING version",
+            params![agent.id, snapshot, now],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    conn.execute(
+        "DELETE FROM agent_definition_history
+         WHERE agent_id = ?1 AND version <= (
+             SELECT MAX(version) - ?2 FROM agent_definition_history WHERE agent_id = ?1
+         )",
+        params![agent.id, MAX_AGENT_REVISIONS],
+    )
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(version)
+}
+
+/// 写入或更新 agent_definitions 中的一行（不产生历史版本）
+fn upsert_agent_row(tx: &Transaction<'_>, agent: &AgentDefinition) -> Result<(), AppError> {
+    // 使用 UPSERT 而非 INSERT OR REPLACE：REPLACE 隐式删除旧行时不会触发删除触发器，
+    // 全文索引中会残留旧内容
+    tx.execute(
+        "INSERT INTO agent_definitions (
+            id, name, content, description,
+            enabled_claude, enabled_codex, enabled_gemini, enabled_opencode, enabled_openclaw,
+            created_at, updated_at, tools, model, color, sort_index, group_name, tags
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14,
+            COALESCE(
+                ?15,
+                (SELECT sort_index FROM agent_definitions WHERE id = ?1),
+                (SELECT COALESCE(MAX(sort_index), -1) + 1 FROM agent_definitions)
+            ),
+            ?16, ?17
+        )
+        ON CONFLICT(id) DO UPDATE SET
+            name = excluded.name,
+            content = excluded.content,
+            description = excluded.description,
+            enabled_claude = excluded.enabled_claude,
+            enabled_codex = excluded.enabled_codex,
+            enabled_gemini = excluded.enabled_gemini,
+            enabled_opencode = excluded.enabled_opencode,
+            enabled_openclaw = excluded.enabled_openclaw,
+            created_at = excluded.created_at,
+            updated_at = excluded.updated_at,
+            tools = excluded.tools,
+            model = excluded.model,
+            color = excluded.color,
+            sort_index = excluded.sort_index,
+            group_name = excluded.group_name,
+            tags = excluded.tags",
+        params![
+            agent.id,
+            agent.name,
+            agent.content,
+            agent.description,
+            agent.apps.claude,
+            agent.apps.codex,
+            agent.apps.gemini,
+            agent.apps.opencode,
+            agent.apps.openclaw,
+            agent.created_at,
+            agent.updated_at,
+            agent.tools,
+            agent.model,
+            agent.color,
+            agent.sort_index.map(|i| i as i64),
+            agent.group,
+            tags_json(&agent.tags)?,
+        ],
+    )
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// 解析 tags 列（JSON 数组）；内容损坏时记录日志并视为无标签
+pub(super) fn parse_tags(raw: Option<&str>) -> Vec<String> {
+    let Some(raw) = raw.filter(|s| !s.trim().is_empty()) else {
+        return Vec::new();
+    };
+    serde_json::from_str(raw).unwrap_or_else(|e| {
+        log::warn!("解析 tags 列失败，按无标签处理: {e}");
+        Vec::new()
+    })
+}
+
+/// 反序列化历史快照
+fn parse_revision(
+    agent_id: &str,
+    version: i64,
+    snapshot: &str,
+    created_at: i64,
+) -> Result<AgentRevision, AppError> {
+    let snapshot: AgentDefinition = serde_json::from_str(snapshot).map_err(|e| {
+        AppError::Database(format!(
+            "解析 agent 历史快照失败 ({agent_id} v{version}): {e}"
+        ))
+    })?;
+    Ok(AgentRevision {
+        agent_id: agent_id.to_string(),
+        version,
+        created_at,
+        snapshot,
+    })
+}
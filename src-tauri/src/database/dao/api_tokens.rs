@@ -0,0 +1,112 @@
+//! 本地 API 访问令牌 DAO
+//!
+//! 令牌只以 SHA-256 摘要保存，按摘要查找；权限列表以 JSON 保存在 `scopes` 列。
+
+use rusqlite::{params, OptionalExtension, Row};
+
+use crate::database::{lock_conn, to_json_string, Database};
+use crate::error::AppError;
+use crate::services::api_access::ApiToken;
+
+const TOKEN_COLUMNS: &str = "id, name, scopes, created_at, last_used_at";
+
+type TokenRow = (String, String, String, i64, Option<i64>);
+
+fn token_from_row(row: &Row<'_>) -> rusqlite::Result<TokenRow> {
+    Ok((
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+    ))
+}
+
+fn parse_token(
+    (id, name, scopes, created_at, last_used_at): TokenRow,
+) -> Result<ApiToken, AppError> {
+    let scopes = serde_json::from_str(&scopes)
+        .map_err(|e| AppError::Database(format!("解析 API 令牌 {id} 的权限失败: {e}")))?;
+    Ok(ApiToken {
+        id,
+        name,
+        scopes,
+        created_at,
+        last_used_at,
+    })
+}
+
+impl Database {
+    /// 获取全部 API 令牌（按创建时间排序，不含摘要）
+    pub fn get_api_tokens(&self) -> Result<Vec<ApiToken>, AppError> {
+        let rows = {
+            let conn = lock_conn!(self.conn);
+            let mut stmt = conn
+                .prepare(&format!(
+                    "SELECT {TOKEN_COLUMNS} FROM api_tokens ORDER BY created_at, id"
+                ))
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let rows = stmt
+                .query_map([], token_from_row)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Database(e.to_string()))?
+        };
+        rows.into_iter().map(parse_token).collect()
+    }
+
+    /// 按令牌摘要查找
+    pub fn find_api_token_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, AppError> {
+        let row = {
+            let conn = lock_conn!(self.conn);
+            conn.query_row(
+                &format!("SELECT {TOKEN_COLUMNS} FROM api_tokens WHERE token_hash = ?1"),
+                params![token_hash],
+                token_from_row,
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?
+        };
+        row.map(parse_token).transpose()
+    }
+
+    /// 保存新令牌
+    pub fn insert_api_token(&self, token: &ApiToken, token_hash: &str) -> Result<(), AppError> {
+        let scopes = to_json_string(&token.scopes)?;
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO api_tokens (id, name, token_hash, scopes, created_at, last_used_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                token.id,
+                token.name,
+                token_hash,
+                scopes,
+                token.created_at,
+                token.last_used_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 吊销令牌，返回是否存在
+    pub fn delete_api_token(&self, id: &str) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute("DELETE FROM api_tokens WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+
+    /// 记录令牌最近一次使用时间
+    pub fn touch_api_token(&self, id: &str, used_at: i64) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "UPDATE api_tokens SET last_used_at = ?2 WHERE id = ?1",
+            params![id, used_at],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! 供应商凭据问题 DAO
+
+use rusqlite::{params, OptionalExtension};
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::provider::{CredentialIssue, CredentialIssueKind, IssueSource};
+
+impl Database {
+    /// 保存（覆盖）供应商的凭据问题
+    pub fn save_credential_issue(&self, issue: &CredentialIssue) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO provider_credential_issues
+             (provider_id, app_type, kind, http_status, message, source, detected_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                issue.provider_id,
+                issue.app_type,
+                issue.kind.as_str(),
+                issue.http_status as i64,
+                issue.message,
+                issue.source.as_str(),
+                issue.detected_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 清除供应商的凭据问题（不存在时无操作）
+    pub fn clear_credential_issue(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "DELETE FROM provider_credential_issues WHERE provider_id = ?1 AND app_type = ?2",
+            params![provider_id, app_type],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 供应商是否有已记录的凭据问题
+    ///
+    /// 只读查询，供请求成功的热路径判断是否需要清除，避免每次请求都执行写语句。
+    pub fn has_credential_issue(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM provider_credential_issues
+             WHERE provider_id = ?1 AND app_type = ?2)",
+            params![provider_id, app_type],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 获取供应商的凭据问题
+    pub fn get_credential_issue(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+    ) -> Result<Option<CredentialIssue>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let row = conn
+            .query_row(
+                "SELECT kind, http_status, message, source, detected_at
+                 FROM provider_credential_issues
+                 WHERE provider_id = ?1 AND app_type = ?2",
+                params![provider_id, app_type],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, i64>(4)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let Some((kind, http_status, message, source, detected_at)) = row else {
+            return Ok(None);
+        };
+        let Some(kind) = CredentialIssueKind::parse(&kind) else {
+            log::warn!("忽略未知的凭据问题类型: {kind}");
+            return Ok(None);
+        };
+        Ok(Some(CredentialIssue {
+            provider_id: provider_id.to_string(),
+            app_type: app_type.to_string(),
+            kind,
+            http_status: http_status as u16,
+            message,
+            source: IssueSource::parse(&source).unwrap_or(IssueSource::HealthCheck),
+            detected_at,
+        }))
+    }
+}
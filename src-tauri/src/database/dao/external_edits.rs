@@ -0,0 +1,96 @@
+//! 外部修改冲突记录 DAO
+
+use rusqlite::{params, Row};
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::external_edits::{ExternalEditConflict, ExternalEditPolicy, ManagedKind};
+
+/// 最多保留的冲突记录条数，超出后删除最早的记录
+const MAX_CONFLICTS: i64 = 200;
+
+fn conflict_from_row(row: &Row<'_>) -> rusqlite::Result<Option<ExternalEditConflict>> {
+    let kind: String = row.get(1)?;
+    let policy: String = row.get(4)?;
+    let (Some(kind), Some(policy)) = (
+        ManagedKind::parse(&kind),
+        ExternalEditPolicy::parse(&policy),
+    ) else {
+        log::warn!("忽略无法识别的外部修改记录: {kind} / {policy}");
+        return Ok(None);
+    };
+    Ok(Some(ExternalEditConflict {
+        app: row.get(0)?,
+        kind,
+        id: row.get(2)?,
+        path: row.get(3)?,
+        policy,
+        snapshot: row.get(5)?,
+        revision: row.get(6)?,
+        detected_at: row.get(7)?,
+    }))
+}
+
+impl Database {
+    /// 写入一条冲突记录
+    pub fn insert_external_edit_conflict(
+        &self,
+        conflict: &ExternalEditConflict,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO external_edit_conflicts
+                (app, kind, item_id, path, policy, snapshot, revision, detected_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                conflict.app,
+                conflict.kind.as_str(),
+                conflict.id,
+                conflict.path,
+                conflict.policy.as_str(),
+                conflict.snapshot,
+                conflict.revision,
+                conflict.detected_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM external_edit_conflicts WHERE id <= (
+                SELECT id FROM external_edit_conflicts ORDER BY id DESC LIMIT 1 OFFSET ?1
+            )",
+            params![MAX_CONFLICTS],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 获取全部冲突记录（最新在后）
+    pub fn get_external_edit_conflicts(&self) -> Result<Vec<ExternalEditConflict>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT app, kind, item_id, path, policy, snapshot, revision, detected_at
+                 FROM external_edit_conflicts
+                 ORDER BY id",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], conflict_from_row)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut conflicts = Vec::new();
+        for row in rows {
+            if let Some(conflict) = row.map_err(|e| AppError::Database(e.to_string()))? {
+                conflicts.push(conflict);
+            }
+        }
+        Ok(conflicts)
+    }
+
+    /// 清空冲突记录，返回删除的条数
+    pub fn clear_external_edit_conflicts(&self) -> Result<usize, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute("DELETE FROM external_edit_conflicts", [])
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+}
@@ -2,9 +2,13 @@
 //!
 //! 管理代理模式下的故障转移队列（基于 providers 表的 in_failover_queue 字段）
 
+use super::prompt_schedules::write_prompt_schedule_row;
+use super::proxy::write_app_proxy_config_row;
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
 use crate::provider::Provider;
+use crate::proxy::types::AppProxyConfig;
+use crate::services::prompt_schedule::PromptSchedule;
 use serde::{Deserialize, Serialize};
 
 /// 故障转移队列条目（简化版，用于前端展示）
@@ -25,7 +29,7 @@ impl Database {
             .prepare(
                 "SELECT id, name, sort_index
                  FROM providers
-                 WHERE app_type = ?1 AND in_failover_queue = 1
+                 WHERE app_type = ?1 AND in_failover_queue = 1 AND archived = 0
                  ORDER BY COALESCE(sort_index, 999999), id ASC",
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -45,13 +49,13 @@ impl Database {
         Ok(items)
     }
 
-    /// 获取故障转移队列中的供应商（完整 Provider 信息，按顺序）
+    /// 获取故障转移队列中的供应商（完整 Provider 信息，按顺序，不含已归档的供应商）
     pub fn get_failover_providers(&self, app_type: &str) -> Result<Vec<Provider>, AppError> {
         let all_providers = self.get_all_providers(app_type)?;
 
         let result: Vec<Provider> = all_providers
             .into_values()
-            .filter(|p| p.in_failover_queue)
+            .filter(|p| p.in_failover_queue && !p.archived)
             .collect();
 
         Ok(result)
@@ -110,6 +114,40 @@ impl Database {
         Ok(())
     }
 
+    /// 在一个事务中替换故障转移设置：重建各应用的队列、写入代理配置，并保存定时规则
+    ///
+    /// `apps` 中每项为 (代理配置, 队列中的供应商 id)。任一步失败时整体回滚。
+    pub fn replace_failover_setup(
+        &self,
+        apps: &[(AppProxyConfig, Vec<String>)],
+        schedules: &[PromptSchedule],
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for (config, queue) in apps {
+            tx.execute(
+                "UPDATE providers SET in_failover_queue = 0 WHERE app_type = ?1",
+                [&config.app_type],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            for provider_id in queue {
+                tx.execute(
+                    "UPDATE providers SET in_failover_queue = 1 WHERE id = ?1 AND app_type = ?2",
+                    rusqlite::params![provider_id, config.app_type],
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+            write_app_proxy_config_row(&tx, config)?;
+        }
+        for schedule in schedules {
+            write_prompt_schedule_row(&tx, schedule)?;
+        }
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     /// 检查供应商是否在故障转移队列中
     pub fn is_in_failover_queue(
         &self,
@@ -129,7 +167,7 @@ impl Database {
         Ok(in_queue)
     }
 
-    /// 获取可添加到故障转移队列的供应商（不在队列中的）
+    /// 获取可添加到故障转移队列的供应商（不在队列中且未归档的）
     pub fn get_available_providers_for_failover(
         &self,
         app_type: &str,
@@ -138,7 +176,7 @@ impl Database {
 
         let available: Vec<Provider> = all_providers
             .into_values()
-            .filter(|p| !p.in_failover_queue)
+            .filter(|p| !p.in_failover_queue && !p.archived)
             .collect();
 
         Ok(available)
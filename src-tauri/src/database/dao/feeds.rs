@@ -0,0 +1,260 @@
+//! 订阅源 DAO
+//!
+//! 订阅本身存放在 `feed_subscriptions`，每次拉取到的条目存放在 `feed_items`。
+
+use rusqlite::{params, OptionalExtension, Row};
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::feed::{FeedItemKind, FeedItemRecord, FeedSubscription};
+
+const SUBSCRIPTION_COLUMNS: &str =
+    "id, url, name, enabled, last_checked_at, last_error, created_at";
+
+const ITEM_COLUMNS: &str =
+    "subscription_id, kind, item_id, name, payload, content_hash, accepted_hash, applied, fetched_at";
+
+fn subscription_from_row(row: &Row<'_>) -> rusqlite::Result<FeedSubscription> {
+    Ok(FeedSubscription {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        name: row.get(2)?,
+        enabled: row.get(3)?,
+        last_checked_at: row.get(4)?,
+        last_error: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn item_from_row(row: &Row<'_>) -> rusqlite::Result<Option<FeedItemRecord>> {
+    let kind: String = row.get(1)?;
+    let Some(kind) = FeedItemKind::parse(&kind) else {
+        log::warn!("忽略未知类型的订阅条目: {kind}");
+        return Ok(None);
+    };
+    Ok(Some(FeedItemRecord {
+        subscription_id: row.get(0)?,
+        kind,
+        item_id: row.get(2)?,
+        name: row.get(3)?,
+        payload: row.get(4)?,
+        content_hash: row.get(5)?,
+        accepted_hash: row.get(6)?,
+        applied: row.get(7)?,
+        fetched_at: row.get(8)?,
+    }))
+}
+
+impl Database {
+    /// 获取全部订阅（按添加时间排序）
+    pub fn get_feed_subscriptions(&self) -> Result<Vec<FeedSubscription>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {SUBSCRIPTION_COLUMNS} FROM feed_subscriptions ORDER BY created_at, id"
+            ))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], subscription_from_row)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 按 id 获取订阅
+    pub fn get_feed_subscription(&self, id: &str) -> Result<Option<FeedSubscription>, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            &format!("SELECT {SUBSCRIPTION_COLUMNS} FROM feed_subscriptions WHERE id = ?1"),
+            params![id],
+            subscription_from_row,
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 新增或更新订阅
+    ///
+    /// 不使用 INSERT OR REPLACE：REPLACE 会先删除旧行，级联清空已拉取的条目。
+    pub fn save_feed_subscription(&self, sub: &FeedSubscription) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO feed_subscriptions (id, url, name, enabled, last_checked_at, last_error, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(id) DO UPDATE SET
+                url = excluded.url,
+                name = excluded.name,
+                enabled = excluded.enabled,
+                last_checked_at = excluded.last_checked_at,
+                last_error = excluded.last_error",
+            params![
+                sub.id,
+                sub.url,
+                sub.name,
+                sub.enabled,
+                sub.last_checked_at,
+                sub.last_error,
+                sub.created_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除订阅（条目随外键级联删除），返回是否存在
+    pub fn delete_feed_subscription(&self, id: &str) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute("DELETE FROM feed_subscriptions WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+
+    /// 记录一次拉取结果（`error` 为 None 表示成功）
+    pub fn record_feed_check(
+        &self,
+        id: &str,
+        checked_at: i64,
+        error: Option<&str>,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "UPDATE feed_subscriptions SET last_checked_at = ?2, last_error = ?3 WHERE id = ?1",
+            params![id, checked_at, error],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 用最新拉取的条目替换订阅下的全部条目（单个事务）
+    ///
+    /// 已存在的条目保留 `accepted_hash`，源中已移除的条目一并删除。
+    pub fn replace_feed_items(
+        &self,
+        subscription_id: &str,
+        items: &[FeedItemRecord],
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let existing: Vec<(String, String)> = {
+            let mut stmt = tx
+                .prepare("SELECT kind, item_id FROM feed_items WHERE subscription_id = ?1")
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let rows = stmt
+                .query_map(params![subscription_id], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            rows.collect::<Result<_, _>>()
+                .map_err(|e| AppError::Database(e.to_string()))?
+        };
+        for (kind, item_id) in existing {
+            let still_listed = items
+                .iter()
+                .any(|item| item.kind.as_str() == kind && item.item_id == item_id);
+            if !still_listed {
+                tx.execute(
+                    "DELETE FROM feed_items WHERE subscription_id = ?1 AND kind = ?2 AND item_id = ?3",
+                    params![subscription_id, kind, item_id],
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+        }
+
+        for item in items {
+            tx.execute(
+                "INSERT INTO feed_items (subscription_id, kind, item_id, name, payload, content_hash, fetched_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT(subscription_id, kind, item_id) DO UPDATE SET
+                    name = excluded.name,
+                    payload = excluded.payload,
+                    content_hash = excluded.content_hash,
+                    fetched_at = excluded.fetched_at",
+                params![
+                    subscription_id,
+                    item.kind.as_str(),
+                    item.item_id,
+                    item.name,
+                    item.payload,
+                    item.content_hash,
+                    item.fetched_at,
+                ],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 获取条目；`subscription_id` 为 None 时返回所有订阅的条目
+    pub fn get_feed_items(
+        &self,
+        subscription_id: Option<&str>,
+    ) -> Result<Vec<FeedItemRecord>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {ITEM_COLUMNS} FROM feed_items
+                 WHERE ?1 IS NULL OR subscription_id = ?1
+                 ORDER BY subscription_id, kind, item_id"
+            ))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![subscription_id], item_from_row)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut items = Vec::new();
+        for row in rows {
+            if let Some(item) = row.map_err(|e| AppError::Database(e.to_string()))? {
+                items.push(item);
+            }
+        }
+        Ok(items)
+    }
+
+    /// 获取单个条目
+    pub fn get_feed_item(
+        &self,
+        subscription_id: &str,
+        kind: FeedItemKind,
+        item_id: &str,
+    ) -> Result<Option<FeedItemRecord>, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            &format!(
+                "SELECT {ITEM_COLUMNS} FROM feed_items
+                 WHERE subscription_id = ?1 AND kind = ?2 AND item_id = ?3"
+            ),
+            params![subscription_id, kind.as_str(), item_id],
+            item_from_row,
+        )
+        .optional()
+        .map(Option::flatten)
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 标记条目的某个内容版本已处理（接受或忽略）
+    ///
+    /// `applied` 为 true 表示内容已写入本地；忽略不会清除此前接受留下的标记。
+    pub fn set_feed_item_accepted(
+        &self,
+        subscription_id: &str,
+        kind: FeedItemKind,
+        item_id: &str,
+        hash: &str,
+        applied: bool,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "UPDATE feed_items SET accepted_hash = ?4, applied = MAX(applied, ?5)
+             WHERE subscription_id = ?1 AND kind = ?2 AND item_id = ?3",
+            params![subscription_id, kind.as_str(), item_id, hash, applied],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
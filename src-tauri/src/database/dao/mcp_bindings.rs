@@ -0,0 +1,82 @@
+//! MCP 服务器供应商绑定 DAO
+
+use std::collections::BTreeMap;
+
+use rusqlite::params;
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::mcp_binding::McpProviderBinding;
+
+impl Database {
+    /// 获取所有 MCP 供应商绑定
+    pub fn get_mcp_provider_bindings(&self) -> Result<Vec<McpProviderBinding>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT server_id, app_type, provider_id, env_map
+                 FROM mcp_provider_bindings
+                 ORDER BY server_id ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut bindings = Vec::new();
+        for row in rows {
+            let (server_id, app_type, provider_id, env_map) =
+                row.map_err(|e| AppError::Database(e.to_string()))?;
+            let env_map: BTreeMap<String, String> =
+                serde_json::from_str(&env_map).map_err(|e| {
+                    AppError::Database(format!("解析 MCP 绑定 {server_id} 的 env_map 失败: {e}"))
+                })?;
+            bindings.push(McpProviderBinding {
+                server_id,
+                app_type,
+                provider_id,
+                env_map,
+            });
+        }
+        Ok(bindings)
+    }
+
+    /// 保存（覆盖）MCP 服务器的供应商绑定
+    pub fn save_mcp_provider_binding(&self, binding: &McpProviderBinding) -> Result<(), AppError> {
+        let env_map = serde_json::to_string(&binding.env_map)
+            .map_err(|e| AppError::Database(format!("Failed to serialize env_map: {e}")))?;
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO mcp_provider_bindings (server_id, app_type, provider_id, env_map)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                binding.server_id,
+                binding.app_type,
+                binding.provider_id,
+                env_map
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除 MCP 服务器的供应商绑定，返回是否存在
+    pub fn delete_mcp_provider_binding(&self, server_id: &str) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let removed = conn
+            .execute(
+                "DELETE FROM mcp_provider_bindings WHERE server_id = ?1",
+                params![server_id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(removed > 0)
+    }
+}
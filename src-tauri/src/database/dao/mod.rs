@@ -3,13 +3,28 @@
 //! Database access operations for each domain
 
 pub mod agents;
+pub mod api_tokens;
+pub mod credential_issues;
+pub mod external_edits;
 pub mod failover;
+pub mod feeds;
 pub mod mcp;
+pub mod mcp_bindings;
 pub mod omo;
+pub mod prompt_projects;
+pub mod prompt_schedules;
+pub mod prompt_trash;
 pub mod prompts;
+pub mod provider_attachments;
+pub mod provider_audit;
+pub mod provider_benchmarks;
+pub mod provider_metrics;
+pub mod provider_profiles;
 pub mod providers;
 pub mod proxy;
+pub mod remote_cache;
 pub mod settings;
+pub mod skill_integrity;
 pub mod skills;
 pub mod stream_check;
 pub mod universal_providers;
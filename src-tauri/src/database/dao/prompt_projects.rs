@@ -0,0 +1,113 @@
+//! 项目级提示词 DAO
+//!
+//! 项目登记存放在 `prompt_projects`，提示词在项目中的挂载存放在 `prompt_project_links`。
+
+use rusqlite::{params, Row};
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::prompt_project::{PromptProject, PromptProjectLink};
+
+const PROJECT_COLUMNS: &str = "id, path, name, created_at";
+
+fn project_from_row(row: &Row<'_>) -> rusqlite::Result<PromptProject> {
+    Ok(PromptProject {
+        id: row.get(0)?,
+        path: row.get(1)?,
+        name: row.get(2)?,
+        created_at: row.get(3)?,
+        links: Vec::new(),
+    })
+}
+
+impl Database {
+    /// 获取全部项目及其挂载（按登记时间排序）
+    pub fn get_prompt_projects(&self) -> Result<Vec<PromptProject>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {PROJECT_COLUMNS} FROM prompt_projects ORDER BY created_at, id"
+            ))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let mut projects = stmt
+            .query_map([], project_from_row)
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT project_id, prompt_id, app_type FROM prompt_project_links
+                 ORDER BY project_id, prompt_id, app_type",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let links = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    PromptProjectLink {
+                        prompt_id: row.get(1)?,
+                        app: row.get(2)?,
+                    },
+                ))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for link in links {
+            let (project_id, link) = link.map_err(|e| AppError::Database(e.to_string()))?;
+            if let Some(project) = projects.iter_mut().find(|p| p.id == project_id) {
+                project.links.push(link);
+            }
+        }
+        Ok(projects)
+    }
+
+    /// 按 id 获取项目及其挂载
+    pub fn get_prompt_project(&self, id: &str) -> Result<Option<PromptProject>, AppError> {
+        Ok(self.get_prompt_projects()?.into_iter().find(|p| p.id == id))
+    }
+
+    /// 新增或更新项目登记（不涉及挂载）
+    pub fn save_prompt_project(&self, project: &PromptProject) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO prompt_projects (id, path, name, created_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET
+                path = excluded.path,
+                name = excluded.name",
+            params![project.id, project.path, project.name, project.created_at],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除项目（挂载随外键级联删除），返回是否存在
+    pub fn delete_prompt_project(&self, id: &str) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute("DELETE FROM prompt_projects WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+
+    /// 挂载或取消挂载提示词（`app` 为 `AppType::as_str`）
+    pub fn set_prompt_project_link(
+        &self,
+        project_id: &str,
+        prompt_id: &str,
+        app: &str,
+        enabled: bool,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        let sql = if enabled {
+            "INSERT OR IGNORE INTO prompt_project_links (project_id, prompt_id, app_type)
+             VALUES (?1, ?2, ?3)"
+        } else {
+            "DELETE FROM prompt_project_links
+             WHERE project_id = ?1 AND prompt_id = ?2 AND app_type = ?3"
+        };
+        conn.execute(sql, params![project_id, prompt_id, app])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+//! 定时切换提示词规则 DAO
+//!
+//! 规则存放在 `prompt_schedules`，定时规则本身以 JSON 保存在 `schedule` 列。
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::database::{lock_conn, to_json_string, Database};
+use crate::error::AppError;
+use crate::services::prompt_schedule::PromptSchedule;
+
+const SCHEDULE_COLUMNS: &str =
+    "id, app_type, prompt_id, schedule, enabled, last_run_at, last_error, created_at, updated_at";
+
+type ScheduleRow = (
+    String,
+    String,
+    String,
+    String,
+    bool,
+    Option<i64>,
+    Option<String>,
+    i64,
+    i64,
+);
+
+/// 读出一行规则（JSON 列在行外解析，以便报告具体的 id）
+fn schedule_from_row(row: &Row<'_>) -> rusqlite::Result<ScheduleRow> {
+    Ok((
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+        row.get(6)?,
+        row.get(7)?,
+        row.get(8)?,
+    ))
+}
+
+fn parse_schedule(row: ScheduleRow) -> Result<PromptSchedule, AppError> {
+    let (id, app, prompt_id, schedule, enabled, last_run_at, last_error, created_at, updated_at) =
+        row;
+    let schedule = serde_json::from_str(&schedule)
+        .map_err(|e| AppError::Database(format!("解析提示词定时规则 {id} 失败: {e}")))?;
+    Ok(PromptSchedule {
+        id,
+        app,
+        prompt_id,
+        schedule,
+        enabled,
+        last_run_at,
+        last_error,
+        created_at,
+        updated_at,
+    })
+}
+
+impl Database {
+    /// 获取全部定时切换规则（按创建时间排序）
+    pub fn get_prompt_schedules(&self) -> Result<Vec<PromptSchedule>, AppError> {
+        let rows = {
+            let conn = lock_conn!(self.conn);
+            let mut stmt = conn
+                .prepare(&format!(
+                    "SELECT {SCHEDULE_COLUMNS} FROM prompt_schedules ORDER BY created_at, id"
+                ))
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let rows = stmt
+                .query_map([], schedule_from_row)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Database(e.to_string()))?
+        };
+        rows.into_iter().map(parse_schedule).collect()
+    }
+
+    /// 按 id 获取定时切换规则
+    pub fn get_prompt_schedule(&self, id: &str) -> Result<Option<PromptSchedule>, AppError> {
+        let row = {
+            let conn = lock_conn!(self.conn);
+            conn.query_row(
+                &format!("SELECT {SCHEDULE_COLUMNS} FROM prompt_schedules WHERE id = ?1"),
+                params![id],
+                schedule_from_row,
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?
+        };
+        row.map(parse_schedule).transpose()
+    }
+
+    /// 新增或更新定时切换规则
+    pub fn save_prompt_schedule(&self, schedule: &PromptSchedule) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        write_prompt_schedule_row(&conn, schedule)
+    }
+
+    /// 删除定时切换规则，返回是否存在
+    pub fn delete_prompt_schedule(&self, id: &str) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute("DELETE FROM prompt_schedules WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+
+    /// 记录一次执行（`error` 为 None 表示成功）
+    pub fn record_prompt_schedule_run(
+        &self,
+        id: &str,
+        run_at: i64,
+        error: Option<&str>,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "UPDATE prompt_schedules SET last_run_at = ?2, last_error = ?3 WHERE id = ?1",
+            params![id, run_at, error],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// 写入一条定时切换规则（新增或覆盖同 id 的规则）
+pub(super) fn write_prompt_schedule_row(
+    conn: &Connection,
+    schedule: &PromptSchedule,
+) -> Result<(), AppError> {
+    let rule = to_json_string(&schedule.schedule)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO prompt_schedules (
+            id, app_type, prompt_id, schedule, enabled,
+            last_run_at, last_error, created_at, updated_at
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            schedule.id,
+            schedule.app,
+            schedule.prompt_id,
+            rule,
+            schedule.enabled,
+            schedule.last_run_at,
+            schedule.last_error,
+            schedule.created_at,
+            schedule.updated_at,
+        ],
+    )
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(())
+}
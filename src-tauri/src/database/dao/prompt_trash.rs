@@ -0,0 +1,185 @@
+//! 提示词回收站 DAO
+//!
+//! 删除提示词时整行移入 `prompt_trash`（连同项目挂载），恢复时写回 `prompts` 与 `prompt_project_links`。
+
+use rusqlite::{params, OptionalExtension, Row};
+
+use super::prompts::{row_to_prompt, write_prompt_row, PROMPT_COLUMNS};
+use crate::database::{lock_conn, to_json_string, Database};
+use crate::error::AppError;
+use crate::prompt::{Prompt, TrashedPrompt, TrashedPromptLink};
+
+const TRASH_COLUMNS: &str = "id, prompt, links, deleted_at";
+
+/// 读出一行回收站记录（JSON 列在行外解析，以便报告具体的 id）
+fn trash_from_row(row: &Row<'_>) -> rusqlite::Result<(String, String, String, i64)> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+}
+
+fn parse_trashed(
+    (id, prompt, links, deleted_at): (String, String, String, i64),
+) -> Result<TrashedPrompt, AppError> {
+    let prompt: Prompt = serde_json::from_str(&prompt)
+        .map_err(|e| AppError::Database(format!("解析回收站提示词 {id} 失败: {e}")))?;
+    let project_links: Vec<TrashedPromptLink> = serde_json::from_str(&links)
+        .map_err(|e| AppError::Database(format!("解析回收站提示词 {id} 的挂载失败: {e}")))?;
+    Ok(TrashedPrompt {
+        prompt,
+        deleted_at,
+        project_links,
+    })
+}
+
+impl Database {
+    /// 把提示词及其项目挂载移入回收站（单个事务），返回被移走的提示词；不存在时返回 None
+    ///
+    /// 回收站中已有同 id 的旧记录时被新记录替换。
+    pub fn trash_prompt(&self, id: &str, deleted_at: i64) -> Result<Option<Prompt>, AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let prompt = tx
+            .query_row(
+                &format!("SELECT {PROMPT_COLUMNS} FROM prompts WHERE id = ?1"),
+                params![id],
+                row_to_prompt,
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let Some(prompt) = prompt else {
+            return Ok(None);
+        };
+
+        let links = {
+            let mut stmt = tx
+                .prepare(
+                    "SELECT project_id, app_type FROM prompt_project_links
+                     WHERE prompt_id = ?1 ORDER BY project_id, app_type",
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let rows = stmt
+                .query_map(params![id], |row| {
+                    Ok(TrashedPromptLink {
+                        project_id: row.get(0)?,
+                        app: row.get(1)?,
+                    })
+                })
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Database(e.to_string()))?
+        };
+
+        tx.execute(
+            "INSERT OR REPLACE INTO prompt_trash (id, prompt, links, deleted_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                id,
+                to_json_string(&prompt)?,
+                to_json_string(&links)?,
+                deleted_at
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.execute("DELETE FROM prompts WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.execute(
+            "DELETE FROM prompt_project_links WHERE prompt_id = ?1",
+            params![id],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(Some(prompt))
+    }
+
+    /// 获取回收站中的提示词（最近删除的在前）
+    pub fn get_trashed_prompts(&self) -> Result<Vec<TrashedPrompt>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {TRASH_COLUMNS} FROM prompt_trash ORDER BY deleted_at DESC, id"
+            ))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], trash_from_row)
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.into_iter().map(parse_trashed).collect()
+    }
+
+    /// 把回收站中的提示词写回（排在现有提示词之后），并恢复仍然登记着的项目挂载
+    ///
+    /// 回收站中没有该 id 时返回 None；已有同 id 的提示词时报错，回收站记录保持不变。
+    pub fn restore_trashed_prompt(&self, id: &str) -> Result<Option<TrashedPrompt>, AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let row = tx
+            .query_row(
+                &format!("SELECT {TRASH_COLUMNS} FROM prompt_trash WHERE id = ?1"),
+                params![id],
+                trash_from_row,
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let mut trashed = parse_trashed(row)?;
+
+        let exists: bool = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM prompts WHERE id = ?1)",
+                params![id],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if exists {
+            return Err(AppError::localized(
+                "prompt.restore_conflict",
+                format!("已存在同 id 的提示词，无法恢复: {id}"),
+                format!("A prompt with the same id already exists: {id}"),
+            ));
+        }
+
+        trashed.prompt.sort_index = None;
+        write_prompt_row(&tx, &trashed.prompt)?;
+        for link in &trashed.project_links {
+            tx.execute(
+                "INSERT OR IGNORE INTO prompt_project_links (project_id, prompt_id, app_type)
+                 SELECT ?1, ?2, ?3 WHERE EXISTS (SELECT 1 FROM prompt_projects WHERE id = ?1)",
+                params![link.project_id, id, link.app],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        tx.execute("DELETE FROM prompt_trash WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(Some(trashed))
+    }
+
+    /// 从回收站中永久删除一条记录，返回是否存在
+    pub fn delete_trashed_prompt(&self, id: &str) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute("DELETE FROM prompt_trash WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+
+    /// 永久删除 `deleted_before`（Unix 秒）之前移入回收站的记录，返回删除的数量
+    pub fn purge_prompt_trash(&self, deleted_before: i64) -> Result<usize, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "DELETE FROM prompt_trash WHERE deleted_at < ?1",
+            params![deleted_before],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+}
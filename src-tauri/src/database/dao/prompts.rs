@@ -2,123 +2,305 @@
 //!
 //! 提供提示词（Prompt）的 CRUD 操作。
 
-use crate::database::{lock_conn, Database};
+use super::agents::{parse_tags, search_conditions, write_agent_row};
+use super::providers::tags_json;
+use crate::agent::AgentDefinition;
+use crate::database::{lock_conn, Database, PROMPT_FTS_TABLE};
 use crate::error::AppError;
-use crate::prompt::{Prompt, PromptApps};
+use crate::prompt::{Prompt, PromptApps, PromptRevision, PromptRevisionReason};
 use indexmap::IndexMap;
-use rusqlite::params;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+pub(super) const PROMPT_COLUMNS: &str = "id, name, content, description,
+    claude_enabled, codex_enabled, gemini_enabled, opencode_enabled,
+    created_at, updated_at, sort_index, openclaw_enabled, tags";
+const PROMPT_ORDER: &str = "COALESCE(sort_index, 999999), created_at ASC, id ASC";
+/// 每个提示词保留的历史快照数上限（超出后删除最旧的快照）
+const MAX_PROMPT_REVISIONS: i64 = 50;
+/// 各 app 的启用标志列
+const PROMPT_APP_COLUMNS: [&str; 5] = [
+    "claude_enabled",
+    "codex_enabled",
+    "gemini_enabled",
+    "opencode_enabled",
+    "openclaw_enabled",
+];
+
+/// 校验列名安全性（列名会拼入 SQL，防止注入）
+fn check_app_col(app_col: &str) -> Result<(), AppError> {
+    if PROMPT_APP_COLUMNS.contains(&app_col) {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!("非法的 app_col: {app_col}")))
+    }
+}
+
+pub(super) fn row_to_prompt(row: &Row<'_>) -> rusqlite::Result<Prompt> {
+    Ok(Prompt {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        content: row.get(2)?,
+        description: row.get(3)?,
+        tags: parse_tags(row.get::<_, Option<String>>(12)?.as_deref()),
+        apps: PromptApps {
+            claude: row.get(4)?,
+            codex: row.get(5)?,
+            gemini: row.get(6)?,
+            opencode: row.get(7)?,
+            openclaw: row.get(11)?,
+        },
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+        sort_index: row.get::<_, Option<i64>>(10)?.map(|i| i as usize),
+    })
+}
 
 impl Database {
-    /// 获取所有提示词（全局，不区分 app）
+    /// 获取所有提示词（全局，不区分 app；按优先级，再按 created_at、id 排序）
     pub fn get_prompts(&self) -> Result<IndexMap<String, Prompt>, AppError> {
         let conn = lock_conn!(self.conn);
         let mut stmt = conn
-            .prepare(
-                "SELECT id, name, content, description,
-                        claude_enabled, codex_enabled, gemini_enabled, opencode_enabled,
-                        created_at, updated_at
+            .prepare(&format!(
+                "SELECT {PROMPT_COLUMNS}
                  FROM prompts
-                 ORDER BY created_at ASC, id ASC",
-            )
+                 ORDER BY {PROMPT_ORDER}"
+            ))
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let prompt_iter = stmt
-            .query_map([], |row| {
-                let id: String = row.get(0)?;
-                let name: String = row.get(1)?;
-                let content: String = row.get(2)?;
-                let description: Option<String> = row.get(3)?;
-                let claude: bool = row.get(4)?;
-                let codex: bool = row.get(5)?;
-                let gemini: bool = row.get(6)?;
-                let opencode: bool = row.get(7)?;
-                let created_at: Option<i64> = row.get(8)?;
-                let updated_at: Option<i64> = row.get(9)?;
-
-                Ok((
-                    id.clone(),
-                    Prompt {
-                        id,
-                        name,
-                        content,
-                        description,
-                        apps: PromptApps {
-                            claude,
-                            codex,
-                            gemini,
-                            opencode,
-                        },
-                        created_at,
-                        updated_at,
-                    },
-                ))
-            })
+            .query_map([], row_to_prompt)
             .map_err(|e| AppError::Database(e.to_string()))?;
 
         let mut prompts = IndexMap::new();
         for prompt_res in prompt_iter {
-            let (id, prompt) = prompt_res.map_err(|e| AppError::Database(e.to_string()))?;
-            prompts.insert(id, prompt);
+            let prompt = prompt_res.map_err(|e| AppError::Database(e.to_string()))?;
+            prompts.insert(prompt.id.clone(), prompt);
         }
         Ok(prompts)
     }
 
-    /// 保存提示词（INSERT OR REPLACE）
-    pub fn save_prompt(&self, prompt: &Prompt) -> Result<(), AppError> {
+    /// 获取对某个 app 启用的提示词（按优先级排序，即拼接写入文件的顺序）
+    ///
+    /// `app_col` 为该 app 的启用标志列，如 `claude_enabled`。
+    pub fn get_prompts_for_app(&self, app_col: &str) -> Result<IndexMap<String, Prompt>, AppError> {
+        check_app_col(app_col)?;
         let conn = lock_conn!(self.conn);
-        conn.execute(
-            "INSERT OR REPLACE INTO prompts (
-                id, name, content, description,
-                claude_enabled, codex_enabled, gemini_enabled, opencode_enabled,
-                created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![
-                prompt.id,
-                prompt.name,
-                prompt.content,
-                prompt.description,
-                prompt.apps.claude,
-                prompt.apps.codex,
-                prompt.apps.gemini,
-                prompt.apps.opencode,
-                prompt.created_at,
-                prompt.updated_at,
-            ],
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {PROMPT_COLUMNS}
+                 FROM prompts
+                 WHERE {app_col} = 1
+                 ORDER BY {PROMPT_ORDER}"
+            ))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], row_to_prompt)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut prompts = IndexMap::new();
+        for row in rows {
+            let prompt = row.map_err(|e| AppError::Database(e.to_string()))?;
+            prompts.insert(prompt.id.clone(), prompt);
+        }
+        Ok(prompts)
+    }
+
+    /// 获取对某个 app 启用的优先级最高的提示词；没有启用的提示词时返回 None
+    pub fn get_enabled_prompt(&self, app_col: &str) -> Result<Option<Prompt>, AppError> {
+        check_app_col(app_col)?;
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            &format!(
+                "SELECT {PROMPT_COLUMNS}
+                 FROM prompts
+                 WHERE {app_col} = 1
+                 ORDER BY {PROMPT_ORDER}
+                 LIMIT 1"
+            ),
+            [],
+            row_to_prompt,
         )
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 按关键词搜索提示词（匹配 name / description / content，不区分大小写）
+    ///
+    /// 规则与 [`Self::search_agents`] 相同：关键词需全部命中，3 个字符以上走 FTS5 trigram 索引，
+    /// 更短的退回 LIKE；结果按优先级排序，查询为空时返回全部提示词。
+    pub fn search_prompts(&self, query: &str) -> Result<Vec<Prompt>, AppError> {
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() {
+            return Ok(self.get_prompts()?.into_values().collect());
+        }
+
+        let (conditions, values) = search_conditions(&terms, PROMPT_FTS_TABLE);
+        let conn = lock_conn!(self.conn);
+        let sql = format!(
+            "SELECT {PROMPT_COLUMNS}
+             FROM prompts
+             WHERE {conditions}
+             ORDER BY {PROMPT_ORDER}"
+        );
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), row_to_prompt)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.map(|r| r.map_err(|e| AppError::Database(e.to_string())))
+            .collect()
+    }
+
+    /// 保存提示词（按 id 新增或覆盖）
+    ///
+    /// `sort_index` 为空时沿用已有记录的位置，新提示词排在最后。
+    pub fn save_prompt(&self, prompt: &Prompt) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        write_prompt_row(&conn, prompt)
+    }
+
+    /// 在同一个事务中保存批量替换后的提示词与 Agent
+    ///
+    /// `prompts` 每项为 (替换前, 替换后)：提示词各记录一条替换前的历史快照，
+    /// Agent 各追加一条历史版本。任一行写入失败时整体回滚，不会出现只替换了一部分的情况。
+    pub fn save_replaced_content(
+        &self,
+        prompts: &[(Prompt, Prompt)],
+        agents: &[AgentDefinition],
+    ) -> Result<(), AppError> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for (before, after) in prompts {
+            write_prompt_revision(&tx, before, PromptRevisionReason::BulkReplace, now)?;
+            write_prompt_row(&tx, after)?;
+        }
+        for agent in agents {
+            write_agent_row(&tx, agent, now)?;
+        }
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
 
-    /// 删除提示词（按 id）
+    /// 记录提示词的一条历史快照
+    pub fn save_prompt_revision(
+        &self,
+        prompt: &Prompt,
+        reason: PromptRevisionReason,
+    ) -> Result<i64, AppError> {
+        let conn = lock_conn!(self.conn);
+        write_prompt_revision(&conn, prompt, reason, chrono::Utc::now().timestamp_millis())
+    }
+
+    /// 获取提示词的历史快照（按版本号倒序，最新在前）
+    pub fn get_prompt_history(&self, prompt_id: &str) -> Result<Vec<PromptRevision>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT version, reason, snapshot, created_at
+                 FROM prompt_history
+                 WHERE prompt_id = ?1
+                 ORDER BY version DESC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![prompt_id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut revisions = Vec::new();
+        for row in rows {
+            let (version, reason, snapshot, created_at) =
+                row.map_err(|e| AppError::Database(e.to_string()))?;
+            revisions.push(parse_prompt_revision(
+                prompt_id, version, &reason, &snapshot, created_at,
+            )?);
+        }
+        Ok(revisions)
+    }
+
+    /// 获取提示词的指定历史快照
+    pub fn get_prompt_revision(
+        &self,
+        prompt_id: &str,
+        version: i64,
+    ) -> Result<Option<PromptRevision>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let row = conn
+            .query_row(
+                "SELECT reason, snapshot, created_at
+                 FROM prompt_history
+                 WHERE prompt_id = ?1 AND version = ?2",
+                params![prompt_id, version],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, i64>(2)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        row.map(|(reason, snapshot, created_at)| {
+            parse_prompt_revision(prompt_id, version, &reason, &snapshot, created_at)
+        })
+        .transpose()
+    }
+
+    /// 删除提示词（按 id），同时清理它在项目中的挂载与历史快照
     pub fn delete_prompt(&self, id: &str) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
         conn.execute("DELETE FROM prompts WHERE id = ?1", params![id])
             .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM prompt_history WHERE prompt_id = ?1",
+            params![id],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM prompt_project_links WHERE prompt_id = ?1",
+            params![id],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
 
-    /// 切换提示词对指定 app 的启用状态（互斥：同 app 同时只能有一个启用）
+    /// 切换提示词对指定 app 的启用状态
     ///
-    /// - enabled=true：先清除该 app 所有提示词的启用标志，再设置目标
+    /// - enabled=true 且 exclusive=true：先清除该 app 所有提示词的启用标志，再设置目标（同 app 只保留一个）
+    /// - enabled=true 且 exclusive=false：只设置目标，与已启用的提示词并存
     /// - enabled=false：只清除目标
     pub fn toggle_prompt_app(
         &self,
         id: &str,
         app_col: &str,
         enabled: bool,
+        exclusive: bool,
     ) -> Result<(), AppError> {
-        // 校验列名安全性（防止 SQL 注入）
-        let allowed = ["claude_enabled", "codex_enabled", "gemini_enabled", "opencode_enabled"];
-        if !allowed.contains(&app_col) {
-            return Err(AppError::InvalidInput(format!("非法的 app_col: {app_col}")));
-        }
+        check_app_col(app_col)?;
 
         let conn = lock_conn!(self.conn);
         if enabled {
-            // 先全清，再设目标
-            let clear_sql = format!("UPDATE prompts SET {app_col} = 0");
-            conn.execute(&clear_sql, [])
-                .map_err(|e| AppError::Database(format!("清除 {app_col} 失败: {e}")))?;
+            if exclusive {
+                // 先全清，再设目标
+                let clear_sql = format!("UPDATE prompts SET {app_col} = 0");
+                conn.execute(&clear_sql, [])
+                    .map_err(|e| AppError::Database(format!("清除 {app_col} 失败: {e}")))?;
+            }
             let set_sql = format!("UPDATE prompts SET {app_col} = 1 WHERE id = ?1");
             conn.execute(&set_sql, params![id])
                 .map_err(|e| AppError::Database(format!("设置 {app_col} 失败: {e}")))?;
@@ -129,4 +311,136 @@ impl Database {
         }
         Ok(())
     }
+
+    /// 按给定顺序重写提示词的 sort_index（单个事务）
+    ///
+    /// 未出现在 `ids` 中的提示词保持原有顺序，排在重排后的提示词之后。
+    pub fn reorder_prompts(&self, ids: &[String]) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        tx.execute(
+            "UPDATE prompts SET sort_index = sort_index + ?1 WHERE sort_index IS NOT NULL",
+            params![ids.len() as i64],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        for (index, id) in ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE prompts SET sort_index = ?1 WHERE id = ?2",
+                params![index as i64, id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// 追加一条提示词历史快照（超出上限的旧快照一并清理）
+pub(super) fn write_prompt_revision(
+    conn: &Connection,
+    prompt: &Prompt,
+    reason: PromptRevisionReason,
+    now: i64,
+) -> Result<i64, AppError> {
+    let snapshot = serde_json::to_string(prompt).map_err(|e| AppError::Database(e.to_string()))?;
+    let version: i64 = conn
+        .query_row(
+            "INSERT INTO prompt_history (prompt_id, version, reason, snapshot, created_at)
+             SELECT ?1, COALESCE(MAX(version), 0) + 1, ?2, ?3, ?4
+             FROM prompt_history WHERE prompt_id = ?1
+             RETURNING version",
+            params![prompt.id, reason.as_str(), snapshot, now],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    conn.execute(
+        "DELETE FROM prompt_history
+         WHERE prompt_id = ?1 AND version <= (
+             SELECT MAX(version) - ?2 FROM prompt_history WHERE prompt_id = ?1
+         )",
+        params![prompt.id, MAX_PROMPT_REVISIONS],
+    )
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(version)
+}
+
+/// 反序列化提示词历史快照
+fn parse_prompt_revision(
+    prompt_id: &str,
+    version: i64,
+    reason: &str,
+    snapshot: &str,
+    created_at: i64,
+) -> Result<PromptRevision, AppError> {
+    let snapshot: Prompt = serde_json::from_str(snapshot).map_err(|e| {
+        AppError::Database(format!(
+            "解析提示词历史快照失败 ({prompt_id} v{version}): {e}"
+        ))
+    })?;
+    let reason = PromptRevisionReason::parse(reason).ok_or_else(|| {
+        AppError::Database(format!(
+            "未知的提示词快照原因 ({prompt_id} v{version}): {reason}"
+        ))
+    })?;
+    Ok(PromptRevision {
+        prompt_id: prompt_id.to_string(),
+        version,
+        reason,
+        created_at,
+        snapshot,
+    })
+}
+
+/// 写入单行提示词（单条保存与事务内批量保存共用）
+pub(super) fn write_prompt_row(conn: &Connection, prompt: &Prompt) -> Result<(), AppError> {
+    // 使用 UPSERT：INSERT OR REPLACE 隐式删除旧行时不会触发删除触发器，全文索引会残留旧内容
+    conn.execute(
+        "INSERT INTO prompts (
+            id, name, content, description,
+            claude_enabled, codex_enabled, gemini_enabled, opencode_enabled,
+            created_at, updated_at, sort_index, openclaw_enabled, tags
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
+            COALESCE(
+                ?11,
+                (SELECT sort_index FROM prompts WHERE id = ?1),
+                (SELECT COALESCE(MAX(sort_index), -1) + 1 FROM prompts)
+            ),
+            ?12, ?13
+        )
+        ON CONFLICT(id) DO UPDATE SET
+            name = excluded.name,
+            content = excluded.content,
+            description = excluded.description,
+            claude_enabled = excluded.claude_enabled,
+            codex_enabled = excluded.codex_enabled,
+            gemini_enabled = excluded.gemini_enabled,
+            opencode_enabled = excluded.opencode_enabled,
+            openclaw_enabled = excluded.openclaw_enabled,
+            created_at = excluded.created_at,
+            updated_at = excluded.updated_at,
+            sort_index = excluded.sort_index,
+            tags = excluded.tags",
+        params![
+            prompt.id,
+            prompt.name,
+            prompt.content,
+            prompt.description,
+            prompt.apps.claude,
+            prompt.apps.codex,
+            prompt.apps.gemini,
+            prompt.apps.opencode,
+            prompt.created_at,
+            prompt.updated_at,
+            prompt.sort_index.map(|i| i as i64),
+            prompt.apps.openclaw,
+            tags_json(&prompt.tags)?,
+        ],
+    )
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(())
 }
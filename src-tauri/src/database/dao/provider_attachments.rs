@@ -0,0 +1,98 @@
+//! 供应商备注附件 DAO
+
+use rusqlite::params;
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::provider::ProviderAttachment;
+
+impl Database {
+    pub fn insert_provider_attachment(
+        &self,
+        app_type: &str,
+        attachment: &ProviderAttachment,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO provider_attachments (id, provider_id, app_type, file_name, size, added_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                attachment.id,
+                attachment.provider_id,
+                app_type,
+                attachment.file_name,
+                attachment.size as i64,
+                attachment.added_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 获取供应商的附件（按添加时间排序）
+    pub fn list_provider_attachments(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+    ) -> Result<Vec<ProviderAttachment>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, provider_id, file_name, size, added_at FROM provider_attachments
+                 WHERE app_type = ?1 AND provider_id = ?2
+                 ORDER BY added_at, file_name",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![app_type, provider_id], |row| {
+                Ok(ProviderAttachment {
+                    id: row.get(0)?,
+                    provider_id: row.get(1)?,
+                    file_name: row.get(2)?,
+                    size: row.get::<_, i64>(3)? as u64,
+                    added_at: row.get(4)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 删除一个附件记录，返回是否存在
+    pub fn delete_provider_attachment(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        id: &str,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "DELETE FROM provider_attachments
+                 WHERE id = ?1 AND app_type = ?2 AND provider_id = ?3",
+                params![id, app_type, provider_id],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+
+    /// 删除供应商的全部附件记录，返回被删除的附件 id
+    pub fn delete_provider_attachments(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+    ) -> Result<Vec<String>, AppError> {
+        let ids = self
+            .list_provider_attachments(app_type, provider_id)?
+            .into_iter()
+            .map(|attachment| attachment.id)
+            .collect();
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "DELETE FROM provider_attachments WHERE app_type = ?1 AND provider_id = ?2",
+            params![app_type, provider_id],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(ids)
+    }
+}
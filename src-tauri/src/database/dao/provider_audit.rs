@@ -0,0 +1,82 @@
+//! 供应商变更审计日志 DAO
+
+use rusqlite::params;
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::provider::{ProviderAuditAction, ProviderAuditEntry, ProviderAuditOrigin};
+
+/// 审计日志最多保留的条数，超出后删除最早的记录
+const MAX_AUDIT_ENTRIES: i64 = 5000;
+
+impl Database {
+    /// 写入一条审计记录（`id` 由数据库生成）
+    pub fn insert_provider_audit(&self, entry: &ProviderAuditEntry) -> Result<(), AppError> {
+        let to_text = |value: &Option<serde_json::Value>| value.as_ref().map(|v| v.to_string());
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO provider_audit_log
+                (app_type, provider_id, action, origin, old_value, new_value, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                entry.app_type,
+                entry.provider_id,
+                entry.action.as_str(),
+                entry.origin.as_str(),
+                to_text(&entry.old_value),
+                to_text(&entry.new_value),
+                entry.created_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM provider_audit_log WHERE id <= (
+                SELECT id FROM provider_audit_log ORDER BY id DESC LIMIT 1 OFFSET ?1
+            )",
+            params![MAX_AUDIT_ENTRIES],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 按时间倒序获取审计记录；`provider_id` 为 None 时返回该应用的全部记录
+    pub fn get_provider_audit_log(
+        &self,
+        app_type: &str,
+        provider_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<ProviderAuditEntry>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, app_type, provider_id, action, origin, old_value, new_value, created_at
+                 FROM provider_audit_log
+                 WHERE app_type = ?1 AND (?2 IS NULL OR provider_id = ?2)
+                 ORDER BY id DESC LIMIT ?3",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![app_type, provider_id, limit], |row| {
+                let json = |idx: usize| -> rusqlite::Result<Option<serde_json::Value>> {
+                    Ok(row
+                        .get::<_, Option<String>>(idx)?
+                        .and_then(|text| serde_json::from_str(&text).ok()))
+                };
+                Ok(ProviderAuditEntry {
+                    id: row.get(0)?,
+                    app_type: row.get(1)?,
+                    provider_id: row.get(2)?,
+                    action: ProviderAuditAction::parse(&row.get::<_, String>(3)?)
+                        .unwrap_or(ProviderAuditAction::Update),
+                    origin: ProviderAuditOrigin::parse(&row.get::<_, String>(4)?)
+                        .unwrap_or(ProviderAuditOrigin::Ui),
+                    old_value: json(5)?,
+                    new_value: json(6)?,
+                    created_at: row.get(7)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+}
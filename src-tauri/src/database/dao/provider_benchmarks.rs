@@ -0,0 +1,97 @@
+//! 供应商延迟测速记录 DAO
+
+use rusqlite::params;
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::provider::ProviderBenchmark;
+
+impl Database {
+    /// 保存一次测速的结果，并只为每个供应商保留最近 `keep` 条记录
+    pub fn save_provider_benchmarks(
+        &self,
+        app_type: &str,
+        results: &[ProviderBenchmark],
+        keep: u32,
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        for result in results {
+            tx.execute(
+                "INSERT INTO provider_benchmarks
+                 (app_type, provider_id, provider_name, url, connect_ms, tls_ms,
+                  first_byte_ms, http_status, error, measured_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    app_type,
+                    result.provider_id,
+                    result.provider_name,
+                    result.url,
+                    result.connect_ms.map(|ms| ms as i64),
+                    result.tls_ms.map(|ms| ms as i64),
+                    result.first_byte_ms.map(|ms| ms as i64),
+                    result.http_status.map(|s| s as i64),
+                    result.error,
+                    result.measured_at,
+                ],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        tx.execute(
+            "DELETE FROM provider_benchmarks
+             WHERE app_type = ?1 AND id NOT IN (
+                 SELECT b.id FROM provider_benchmarks b
+                 WHERE b.app_type = ?1 AND b.provider_id = provider_benchmarks.provider_id
+                 ORDER BY b.id DESC
+                 LIMIT ?2
+             )",
+            params![app_type, keep],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 获取应用下每个供应商最近一次的测速结果
+    pub fn get_latest_provider_benchmarks(
+        &self,
+        app_type: &str,
+    ) -> Result<Vec<ProviderBenchmark>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT provider_id, provider_name, url, connect_ms, tls_ms, first_byte_ms,
+                        http_status, error, measured_at
+                 FROM provider_benchmarks
+                 WHERE id IN (
+                     SELECT MAX(id) FROM provider_benchmarks
+                     WHERE app_type = ?1
+                     GROUP BY provider_id
+                 )",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![app_type], |row| {
+                Ok(ProviderBenchmark {
+                    provider_id: row.get(0)?,
+                    provider_name: row.get(1)?,
+                    url: row.get(2)?,
+                    connect_ms: row.get::<_, Option<i64>>(3)?.map(|ms| ms.max(0) as u64),
+                    tls_ms: row.get::<_, Option<i64>>(4)?.map(|ms| ms.max(0) as u64),
+                    first_byte_ms: row.get::<_, Option<i64>>(5)?.map(|ms| ms.max(0) as u64),
+                    http_status: row.get::<_, Option<i64>>(6)?.map(|s| s as u16),
+                    error: row.get(7)?,
+                    measured_at: row.get(8)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+}
@@ -0,0 +1,88 @@
+//! 供应商运行指标 DAO
+//!
+//! 汇总流式检查、延迟测速与代理请求日志中的记录，供按指标排序供应商使用。
+
+use std::collections::HashMap;
+
+use rusqlite::params;
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::provider::ProviderMetrics;
+
+impl Database {
+    /// 获取应用下各供应商的运行指标
+    ///
+    /// 成功率按每个供应商最近 `recent_checks` 次流式检查计算；没有任何记录的供应商不出现在结果中。
+    pub fn get_provider_metrics(
+        &self,
+        app_type: &str,
+        recent_checks: u32,
+    ) -> Result<HashMap<String, ProviderMetrics>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT p.id, checked.ms, bench.ms, rate.rate, used.at
+                 FROM providers p
+                 LEFT JOIN (
+                     SELECT provider_id, response_time_ms AS ms FROM stream_check_logs
+                     WHERE id IN (
+                         SELECT MAX(id) FROM stream_check_logs
+                         WHERE app_type = ?1 AND success = 1 AND response_time_ms IS NOT NULL
+                         GROUP BY provider_id
+                     )
+                 ) checked ON checked.provider_id = p.id
+                 LEFT JOIN (
+                     SELECT provider_id, first_byte_ms AS ms FROM provider_benchmarks
+                     WHERE id IN (
+                         SELECT MAX(id) FROM provider_benchmarks
+                         WHERE app_type = ?1 AND error IS NULL AND first_byte_ms IS NOT NULL
+                         GROUP BY provider_id
+                     )
+                 ) bench ON bench.provider_id = p.id
+                 LEFT JOIN (
+                     SELECT provider_id, AVG(success) AS rate FROM (
+                         SELECT provider_id, success,
+                                ROW_NUMBER() OVER (PARTITION BY provider_id ORDER BY id DESC) AS rn
+                         FROM stream_check_logs WHERE app_type = ?1
+                     )
+                     WHERE rn <= ?2
+                     GROUP BY provider_id
+                 ) rate ON rate.provider_id = p.id
+                 LEFT JOIN (
+                     SELECT provider_id, MAX(created_at) AS at FROM proxy_request_logs
+                     WHERE app_type = ?1
+                     GROUP BY provider_id
+                 ) used ON used.provider_id = p.id
+                 WHERE p.app_type = ?1 AND p.deleted_at IS NULL",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![app_type, recent_checks], |row| {
+                let checked_ms: Option<i64> = row.get(1)?;
+                let bench_ms: Option<i64> = row.get(2)?;
+                Ok((
+                    row.get::<_, String>(0)?,
+                    ProviderMetrics {
+                        latency_ms: checked_ms.or(bench_ms).map(|ms| ms.max(0) as u64),
+                        success_rate: row.get(3)?,
+                        last_used_at: row.get(4)?,
+                    },
+                ))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut metrics = HashMap::new();
+        for row in rows {
+            let (id, item) = row.map_err(|e| AppError::Database(e.to_string()))?;
+            if item.latency_ms.is_some()
+                || item.success_rate.is_some()
+                || item.last_used_at.is_some()
+            {
+                metrics.insert(id, item);
+            }
+        }
+        Ok(metrics)
+    }
+}
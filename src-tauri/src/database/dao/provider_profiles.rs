@@ -0,0 +1,86 @@
+//! 供应商组合 DAO
+
+use rusqlite::{params, Row};
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::provider::ProviderProfile;
+
+fn profile_from_row(row: &Row<'_>) -> rusqlite::Result<ProviderProfile> {
+    let providers: String = row.get(1)?;
+    Ok(ProviderProfile {
+        name: row.get(0)?,
+        providers: serde_json::from_str(&providers).unwrap_or_default(),
+        created_at: row.get(2)?,
+        updated_at: row.get(3)?,
+    })
+}
+
+impl Database {
+    /// 获取全部供应商组合（按名称排序）
+    pub fn get_provider_profiles(&self) -> Result<Vec<ProviderProfile>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT name, providers, created_at, updated_at FROM provider_profiles
+                 ORDER BY name",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], profile_from_row)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 按名称获取供应商组合
+    pub fn get_provider_profile(&self, name: &str) -> Result<Option<ProviderProfile>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT name, providers, created_at, updated_at FROM provider_profiles
+                 WHERE name = ?1",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let mut rows = stmt
+            .query_map(params![name], profile_from_row)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.next()
+            .transpose()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 新增或更新供应商组合（保留原创建时间）
+    pub fn save_provider_profile(&self, profile: &ProviderProfile) -> Result<(), AppError> {
+        let providers = serde_json::to_string(&profile.providers)
+            .map_err(|source| AppError::JsonSerialize { source })?;
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO provider_profiles (name, providers, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(name) DO UPDATE SET
+                providers = excluded.providers,
+                updated_at = excluded.updated_at",
+            params![
+                profile.name,
+                providers,
+                profile.created_at,
+                profile.updated_at
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除供应商组合，返回是否存在
+    pub fn delete_provider_profile(&self, name: &str) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "DELETE FROM provider_profiles WHERE name = ?1",
+                params![name],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+}
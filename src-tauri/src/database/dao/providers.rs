@@ -1,9 +1,13 @@
+use crate::app_config::AppType;
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
 use crate::provider::{Provider, ProviderMeta};
+use crate::services::provider::{StaleEndpoint, TrashedProvider};
+use crate::tags::normalize_tags;
 use indexmap::IndexMap;
 use rusqlite::params;
 use std::collections::HashMap;
+use std::str::FromStr;
 
 type OmoProviderRow = (
     String,
@@ -20,16 +24,59 @@ impl Database {
     pub fn get_all_providers(
         &self,
         app_type: &str,
+    ) -> Result<IndexMap<String, Provider>, AppError> {
+        self.query_providers(app_type, None)
+    }
+
+    /// 获取带有指定标签的供应商（标签比较不区分大小写），顺序与 [`Self::get_all_providers`] 相同
+    pub fn get_providers_by_tag(
+        &self,
+        app_type: &str,
+        tag: &str,
+    ) -> Result<IndexMap<String, Provider>, AppError> {
+        self.query_providers(app_type, Some(tag.trim()))
+    }
+
+    /// 获取应用下使用过的标签及各标签的供应商数量（按标签排序）
+    ///
+    /// 大小写不同的同一标签合并为一项，显示其中排序最小的写法，保证结果稳定
+    pub fn get_provider_tags(&self, app_type: &str) -> Result<Vec<(String, usize)>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT MIN(t.value), COUNT(*) FROM providers p, json_each(p.tags) t
+                 WHERE p.app_type = ?1 AND p.deleted_at IS NULL
+                 GROUP BY t.value COLLATE NOCASE
+                 ORDER BY MIN(t.value) COLLATE NOCASE",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![app_type], |row| {
+                Ok((row.get(0)?, row.get::<_, i64>(1)? as usize))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 读取未删除的供应商；`tag` 不为空时只返回带有该标签的供应商
+    fn query_providers(
+        &self,
+        app_type: &str,
+        tag: Option<&str>,
     ) -> Result<IndexMap<String, Provider>, AppError> {
         let conn = lock_conn!(self.conn);
         let mut stmt = conn.prepare(
-            "SELECT id, name, settings_config, website_url, category, created_at, sort_index, notes, icon, icon_color, meta, in_failover_queue
-             FROM providers WHERE app_type = ?1
+            "SELECT id, name, settings_config, website_url, category, created_at, sort_index, notes, icon, icon_color, meta, in_failover_queue, archived, tags
+             FROM providers WHERE app_type = ?1 AND deleted_at IS NULL
+               AND (?2 IS NULL OR EXISTS (
+                   SELECT 1 FROM json_each(providers.tags) WHERE json_each.value = ?2 COLLATE NOCASE
+               ))
              ORDER BY COALESCE(sort_index, 999999), created_at ASC, id ASC"
         ).map_err(|e| AppError::Database(e.to_string()))?;
 
         let provider_iter = stmt
-            .query_map(params![app_type], |row| {
+            .query_map(params![app_type, tag], |row| {
                 let id: String = row.get(0)?;
                 let name: String = row.get(1)?;
                 let settings_config_str: String = row.get(2)?;
@@ -42,6 +89,8 @@ impl Database {
                 let icon_color: Option<String> = row.get(9)?;
                 let meta_str: String = row.get(10)?;
                 let in_failover_queue: bool = row.get(11)?;
+                let archived: bool = row.get(12)?;
+                let tags_str: String = row.get(13)?;
 
                 let settings_config =
                     serde_json::from_str(&settings_config_str).unwrap_or(serde_json::Value::Null);
@@ -62,6 +111,8 @@ impl Database {
                         icon,
                         icon_color,
                         in_failover_queue,
+                        archived,
+                        tags: serde_json::from_str(&tags_str).unwrap_or_default(),
                     },
                 ))
             })
@@ -71,9 +122,11 @@ impl Database {
         for provider_res in provider_iter {
             let (id, mut provider) = provider_res.map_err(|e| AppError::Database(e.to_string()))?;
             provider.id = id.clone();
+            resolve_secrets(app_type, &id, &mut provider.settings_config);
 
             let mut stmt_endpoints = conn.prepare(
-                "SELECT url, added_at FROM provider_endpoints WHERE provider_id = ?1 AND app_type = ?2 ORDER BY added_at ASC, url ASC"
+                "SELECT url, added_at, last_used_at, last_benchmarked_at, consecutive_failures, last_latency_ms
+                 FROM provider_endpoints WHERE provider_id = ?1 AND app_type = ?2 ORDER BY added_at ASC, url ASC"
             ).map_err(|e| AppError::Database(e.to_string()))?;
 
             let endpoints_iter = stmt_endpoints
@@ -85,7 +138,10 @@ impl Database {
                         crate::settings::CustomEndpoint {
                             url: "".to_string(),
                             added_at: added_at.unwrap_or(0),
-                            last_used: None,
+                            last_used: row.get(2)?,
+                            last_benchmarked: row.get(3)?,
+                            consecutive_failures: row.get(4)?,
+                            last_latency_ms: row.get(5)?,
                         },
                     ))
                 })
@@ -131,12 +187,22 @@ impl Database {
         &self,
         id: &str,
         app_type: &str,
+    ) -> Result<Option<Provider>, AppError> {
+        self.find_provider(id, app_type, false)
+    }
+
+    /// 按 id 读取供应商；`trashed` 为 true 时只查回收站中的记录，否则只查未删除的记录
+    fn find_provider(
+        &self,
+        id: &str,
+        app_type: &str,
+        trashed: bool,
     ) -> Result<Option<Provider>, AppError> {
         let conn = lock_conn!(self.conn);
         let result = conn.query_row(
-            "SELECT name, settings_config, website_url, category, created_at, sort_index, notes, icon, icon_color, meta, in_failover_queue
-             FROM providers WHERE id = ?1 AND app_type = ?2",
-            params![id, app_type],
+            "SELECT name, settings_config, website_url, category, created_at, sort_index, notes, icon, icon_color, meta, in_failover_queue, archived, tags
+             FROM providers WHERE id = ?1 AND app_type = ?2 AND (deleted_at IS NOT NULL) = ?3",
+            params![id, app_type, trashed],
             |row| {
                 let name: String = row.get(0)?;
                 let settings_config_str: String = row.get(1)?;
@@ -149,6 +215,8 @@ impl Database {
                 let icon_color: Option<String> = row.get(8)?;
                 let meta_str: String = row.get(9)?;
                 let in_failover_queue: bool = row.get(10)?;
+                let archived: bool = row.get(11)?;
+                let tags_str: String = row.get(12)?;
 
                 let settings_config = serde_json::from_str(&settings_config_str).unwrap_or(serde_json::Value::Null);
                 let meta: ProviderMeta = serde_json::from_str(&meta_str).unwrap_or_default();
@@ -166,18 +234,29 @@ impl Database {
                     icon,
                     icon_color,
                     in_failover_queue,
+                    archived,
+                    tags: serde_json::from_str(&tags_str).unwrap_or_default(),
                 })
             },
         );
 
         match result {
-            Ok(provider) => Ok(Some(provider)),
+            Ok(mut provider) => {
+                resolve_secrets(app_type, id, &mut provider.settings_config);
+                Ok(Some(provider))
+            }
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(AppError::Database(e.to_string())),
         }
     }
 
     pub fn save_provider(&self, app_type: &str, provider: &Provider) -> Result<(), AppError> {
+        // 回收站中同 id 的旧记录被新记录替换（先于密钥外置，避免清理钥匙串时删掉新密钥）
+        if self.find_provider(&provider.id, app_type, true)?.is_some() {
+            self.delete_provider(app_type, &provider.id)?;
+        }
+        let settings_config =
+            storable_settings_config(app_type, &provider.id, &provider.settings_config)?;
         let mut conn = lock_conn!(self.conn);
         let tx = conn
             .transaction()
@@ -186,17 +265,18 @@ impl Database {
         let mut meta_clone = provider.meta.clone().unwrap_or_default();
         let endpoints = std::mem::take(&mut meta_clone.custom_endpoints);
 
-        let existing: Option<(bool, bool)> = tx
+        // 归档状态与队列状态一样只通过专用接口修改，更新时保留数据库中的值
+        let existing: Option<(bool, bool, bool)> = tx
             .query_row(
-                "SELECT is_current, in_failover_queue FROM providers WHERE id = ?1 AND app_type = ?2",
+                "SELECT is_current, in_failover_queue, archived FROM providers WHERE id = ?1 AND app_type = ?2",
                 params![provider.id, app_type],
-                |row| Ok((row.get(0)?, row.get(1)?)),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
             )
             .ok();
 
         let is_update = existing.is_some();
-        let (is_current, in_failover_queue) =
-            existing.unwrap_or((false, provider.in_failover_queue));
+        let (is_current, in_failover_queue, archived) =
+            existing.unwrap_or((false, provider.in_failover_queue, provider.archived));
 
         if is_update {
             tx.execute(
@@ -212,11 +292,12 @@ impl Database {
                     icon_color = ?9,
                     meta = ?10,
                     is_current = ?11,
-                    in_failover_queue = ?12
-                WHERE id = ?13 AND app_type = ?14",
+                    in_failover_queue = ?12,
+                    tags = ?13
+                WHERE id = ?14 AND app_type = ?15",
                 params![
                     provider.name,
-                    serde_json::to_string(&provider.settings_config).map_err(|e| {
+                    serde_json::to_string(&settings_config).map_err(|e| {
                         AppError::Database(format!("Failed to serialize settings_config: {e}"))
                     })?,
                     provider.website_url,
@@ -231,6 +312,7 @@ impl Database {
                     )))?,
                     is_current,
                     in_failover_queue,
+                    tags_json(&provider.tags)?,
                     provider.id,
                     app_type,
                 ],
@@ -240,13 +322,14 @@ impl Database {
             tx.execute(
                 "INSERT INTO providers (
                     id, app_type, name, settings_config, website_url, category,
-                    created_at, sort_index, notes, icon, icon_color, meta, is_current, in_failover_queue
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                    created_at, sort_index, notes, icon, icon_color, meta, is_current, in_failover_queue,
+                    archived, tags
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
                 params![
                     provider.id,
                     app_type,
                     provider.name,
-                    serde_json::to_string(&provider.settings_config)
+                    serde_json::to_string(&settings_config)
                         .map_err(|e| AppError::Database(format!("Failed to serialize settings_config: {e}")))?,
                     provider.website_url,
                     provider.category,
@@ -259,15 +342,28 @@ impl Database {
                         .map_err(|e| AppError::Database(format!("Failed to serialize meta: {e}")))?,
                     is_current,
                     in_failover_queue,
+                    archived,
+                    tags_json(&provider.tags)?,
                 ],
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
 
             for (url, endpoint) in endpoints {
                 tx.execute(
-                    "INSERT INTO provider_endpoints (provider_id, app_type, url, added_at)
-                     VALUES (?1, ?2, ?3, ?4)",
-                    params![provider.id, app_type, url, endpoint.added_at],
+                    "INSERT INTO provider_endpoints (
+                        provider_id, app_type, url, added_at,
+                        last_used_at, last_benchmarked_at, consecutive_failures, last_latency_ms
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        provider.id,
+                        app_type,
+                        url,
+                        endpoint.added_at,
+                        endpoint.last_used,
+                        endpoint.last_benchmarked,
+                        endpoint.consecutive_failures,
+                        endpoint.last_latency_ms.map(|ms| ms as i64),
+                    ],
                 )
                 .map_err(|e| AppError::Database(e.to_string()))?;
             }
@@ -279,11 +375,40 @@ impl Database {
 
     pub fn delete_provider(&self, app_type: &str, id: &str) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
+        let stored_config: Option<String> = conn
+            .query_row(
+                "SELECT settings_config FROM providers WHERE id = ?1 AND app_type = ?2",
+                params![id, app_type],
+                |row| row.get(0),
+            )
+            .ok();
         conn.execute(
             "DELETE FROM providers WHERE id = ?1 AND app_type = ?2",
             params![id, app_type],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM provider_credential_issues WHERE provider_id = ?1 AND app_type = ?2",
+            params![id, app_type],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        drop(conn);
+
+        // 备注附件的文件一并删除
+        for attachment_id in self.delete_provider_attachments(app_type, id)? {
+            crate::services::provider::remove_attachment_files(&attachment_id);
+        }
+
+        // 供应商的密钥存在钥匙串中时一并清理
+        if stored_config
+            .is_some_and(|config| config.contains(crate::secret_store::SECRET_REF_PREFIX))
+        {
+            if let Ok(app) = AppType::from_str(app_type) {
+                if let Err(e) = crate::secret_store::forget(&app, id) {
+                    log::warn!("清理供应商 {id} 的钥匙串密钥失败: {e}");
+                }
+            }
+        }
         Ok(())
     }
 
@@ -309,19 +434,202 @@ impl Database {
         Ok(())
     }
 
+    /// 在同一个事务中更新多个供应商的 `settings_config`（批量编辑）
+    pub fn update_providers_settings(
+        &self,
+        app_type: &str,
+        updates: &[(String, serde_json::Value)],
+    ) -> Result<(), AppError> {
+        let updates = updates
+            .iter()
+            .map(|(id, config)| {
+                let config = storable_settings_config(app_type, id, config)?;
+                let config = serde_json::to_string(&config).map_err(|e| {
+                    AppError::Database(format!("Failed to serialize settings_config: {e}"))
+                })?;
+                Ok((id, config))
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for (id, config) in updates {
+            tx.execute(
+                "UPDATE providers SET settings_config = ?3 WHERE id = ?1 AND app_type = ?2",
+                params![id, app_type, config],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 把供应商移入回收站（软删除），同时取消当前状态、移出故障转移队列并清除健康状态
+    ///
+    /// 返回是否有记录被移入（不存在或已在回收站中时为 false）。
+    pub fn trash_provider(
+        &self,
+        app_type: &str,
+        id: &str,
+        deleted_at: i64,
+    ) -> Result<bool, AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let affected = tx
+            .execute(
+                "UPDATE providers SET deleted_at = ?3, is_current = 0, in_failover_queue = 0
+                 WHERE id = ?1 AND app_type = ?2 AND deleted_at IS NULL",
+                params![id, app_type, deleted_at],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        tx.execute(
+            "DELETE FROM provider_health WHERE provider_id = ?1 AND app_type = ?2",
+            params![id, app_type],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+
+    /// 从回收站恢复供应商，返回恢复后的供应商；不在回收站中时返回 None
+    pub fn restore_trashed_provider(
+        &self,
+        app_type: &str,
+        id: &str,
+    ) -> Result<Option<Provider>, AppError> {
+        {
+            let conn = lock_conn!(self.conn);
+            let affected = conn
+                .execute(
+                    "UPDATE providers SET deleted_at = NULL
+                     WHERE id = ?1 AND app_type = ?2 AND deleted_at IS NOT NULL",
+                    params![id, app_type],
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            if affected == 0 {
+                return Ok(None);
+            }
+        }
+        self.get_provider_by_id(id, app_type)
+    }
+
+    /// 回收站中的供应商（最近删除的在前）
+    pub fn get_trashed_providers(&self, app_type: &str) -> Result<Vec<TrashedProvider>, AppError> {
+        let rows: Vec<(String, i64)> = {
+            let conn = lock_conn!(self.conn);
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, deleted_at FROM providers
+                     WHERE app_type = ?1 AND deleted_at IS NOT NULL
+                     ORDER BY deleted_at DESC, id ASC",
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let rows = stmt
+                .query_map(params![app_type], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            rows.collect::<Result<_, _>>()
+                .map_err(|e| AppError::Database(e.to_string()))?
+        };
+
+        let mut trashed = Vec::with_capacity(rows.len());
+        for (id, deleted_at) in rows {
+            if let Some(provider) = self.find_provider(&id, app_type, true)? {
+                trashed.push(TrashedProvider {
+                    provider,
+                    deleted_at,
+                });
+            }
+        }
+        Ok(trashed)
+    }
+
+    /// 永久删除 `deleted_before`（Unix 秒）之前移入回收站的供应商；`app_type` 为 None 时清理所有应用
+    ///
+    /// 逐个调用 [`Self::delete_provider`]，附件与钥匙串中的密钥一并清理。返回删除的 (应用, 供应商 id)。
+    pub fn purge_provider_trash(
+        &self,
+        app_type: Option<&str>,
+        deleted_before: i64,
+    ) -> Result<Vec<(String, String)>, AppError> {
+        let expired: Vec<(String, String)> = {
+            let conn = lock_conn!(self.conn);
+            let mut stmt = conn
+                .prepare(
+                    "SELECT app_type, id FROM providers
+                     WHERE deleted_at IS NOT NULL AND deleted_at < ?1
+                       AND (?2 IS NULL OR app_type = ?2)",
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let rows = stmt
+                .query_map(params![deleted_before, app_type], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            rows.collect::<Result<_, _>>()
+                .map_err(|e| AppError::Database(e.to_string()))?
+        };
+
+        for (app, id) in &expired {
+            self.delete_provider(app, id)?;
+        }
+        Ok(expired)
+    }
+
+    /// 设置供应商的归档状态；归档时同时移出故障转移队列并清除健康状态
+    pub fn set_provider_archived(
+        &self,
+        app_type: &str,
+        id: &str,
+        archived: bool,
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        tx.execute(
+            "UPDATE providers SET archived = ?3 WHERE id = ?1 AND app_type = ?2",
+            params![id, app_type, archived],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        if archived {
+            tx.execute(
+                "UPDATE providers SET in_failover_queue = 0 WHERE id = ?1 AND app_type = ?2",
+                params![id, app_type],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            tx.execute(
+                "DELETE FROM provider_health WHERE provider_id = ?1 AND app_type = ?2",
+                params![id, app_type],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     pub fn update_provider_settings_config(
         &self,
         app_type: &str,
         provider_id: &str,
         settings_config: &serde_json::Value,
     ) -> Result<(), AppError> {
+        let settings_config = storable_settings_config(app_type, provider_id, settings_config)?;
         let conn = lock_conn!(self.conn);
         conn.execute(
             "UPDATE providers SET settings_config = ?1 WHERE id = ?2 AND app_type = ?3",
             params![
-                serde_json::to_string(settings_config).map_err(|e| AppError::Database(format!(
-                    "Failed to serialize settings_config: {e}"
-                )))?,
+                serde_json::to_string(&settings_config).map_err(|e| AppError::Database(
+                    format!("Failed to serialize settings_config: {e}")
+                ))?,
                 provider_id,
                 app_type
             ],
@@ -360,6 +668,82 @@ impl Database {
         Ok(())
     }
 
+    /// 记录端点最近一次被选用的时间，返回端点是否存在
+    pub fn touch_custom_endpoint(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        url: &str,
+        at: i64,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let changed = conn
+            .execute(
+                "UPDATE provider_endpoints SET last_used_at = ?4
+                 WHERE provider_id = ?1 AND app_type = ?2 AND url = ?3",
+                params![provider_id, app_type, url, at],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(changed > 0)
+    }
+
+    /// 记录一次测速结果：`latency_ms` 为 Some 表示成功，清零连续失败次数并记录延迟；
+    /// 失败时连续失败次数加一并清空延迟。返回端点是否存在
+    pub fn record_endpoint_benchmark(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        url: &str,
+        latency_ms: Option<u64>,
+        at: i64,
+    ) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let changed = conn
+            .execute(
+                "UPDATE provider_endpoints SET
+                    last_benchmarked_at = ?5,
+                    last_latency_ms = ?4,
+                    consecutive_failures = CASE WHEN ?4 IS NOT NULL THEN 0 ELSE consecutive_failures + 1 END
+                 WHERE provider_id = ?1 AND app_type = ?2 AND url = ?3",
+                params![provider_id, app_type, url, latency_ms.map(|ms| ms as i64), at],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(changed > 0)
+    }
+
+    /// 连续测速失败次数达到 `min_failures` 的端点（`provider_id` 为 None 时查询该 app 的全部供应商）
+    pub fn get_stale_endpoints(
+        &self,
+        app_type: &str,
+        provider_id: Option<&str>,
+        min_failures: u32,
+    ) -> Result<Vec<StaleEndpoint>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT provider_id, url, consecutive_failures, last_benchmarked_at, last_used_at
+                 FROM provider_endpoints
+                 WHERE app_type = ?1 AND (?2 IS NULL OR provider_id = ?2)
+                   AND consecutive_failures >= ?3
+                 ORDER BY provider_id ASC, url ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![app_type, provider_id, min_failures], |row| {
+                Ok(StaleEndpoint {
+                    provider_id: row.get(0)?,
+                    url: row.get(1)?,
+                    consecutive_failures: row.get(2)?,
+                    last_benchmarked_at: row.get(3)?,
+                    last_used_at: row.get(4)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.map(|r| r.map_err(|e| AppError::Database(e.to_string())))
+            .collect()
+    }
+
     pub fn set_omo_provider_current(
         &self,
         app_type: &str,
@@ -458,11 +842,12 @@ impl Database {
                 Err(e) => return Err(AppError::Database(e.to_string())),
             };
 
-        let settings_config = serde_json::from_str(&settings_config_str).map_err(|e| {
+        let mut settings_config = serde_json::from_str(&settings_config_str).map_err(|e| {
             AppError::Database(format!(
                 "Failed to parse {category} provider settings_config (provider_id={id}): {e}"
             ))
         })?;
+        resolve_secrets(app_type, &id, &mut settings_config);
         let meta: crate::provider::ProviderMeta = if meta_str.trim().is_empty() {
             crate::provider::ProviderMeta::default()
         } else {
@@ -486,6 +871,36 @@ impl Database {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         }))
     }
 }
+
+/// 标签序列化为 JSON 数组（先按 [`normalize_tags`] 规范化）
+pub(super) fn tags_json(tags: &[String]) -> Result<String, AppError> {
+    serde_json::to_string(&normalize_tags(tags))
+        .map_err(|e| AppError::Database(format!("Failed to serialize tags: {e}")))
+}
+
+/// 把本供应商密钥字段上的钥匙串引用换回真实密钥（见 [`crate::secret_store::resolve`]）
+fn resolve_secrets(app_type: &str, provider_id: &str, settings_config: &mut serde_json::Value) {
+    if let Ok(app) = AppType::from_str(app_type) {
+        crate::secret_store::resolve(&app, provider_id, settings_config);
+    }
+}
+
+/// 开启钥匙串存储时把密钥移入钥匙串，返回实际写入数据库的 settings_config
+fn storable_settings_config(
+    app_type: &str,
+    provider_id: &str,
+    settings_config: &serde_json::Value,
+) -> Result<serde_json::Value, AppError> {
+    let mut config = settings_config.clone();
+    if crate::settings::store_secrets_in_keychain() {
+        if let Ok(app) = AppType::from_str(app_type) {
+            crate::secret_store::externalize(&app, provider_id, &mut config)?;
+        }
+    }
+    Ok(config)
+}
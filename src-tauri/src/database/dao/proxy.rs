@@ -253,40 +253,7 @@ impl Database {
         config: AppProxyConfig,
     ) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
-
-        conn.execute(
-            "UPDATE proxy_config SET
-                enabled = ?2,
-                auto_failover_enabled = ?3,
-                max_retries = ?4,
-                streaming_first_byte_timeout = ?5,
-                streaming_idle_timeout = ?6,
-                non_streaming_timeout = ?7,
-                circuit_failure_threshold = ?8,
-                circuit_success_threshold = ?9,
-                circuit_timeout_seconds = ?10,
-                circuit_error_rate_threshold = ?11,
-                circuit_min_requests = ?12,
-                updated_at = datetime('now')
-             WHERE app_type = ?1",
-            rusqlite::params![
-                config.app_type,
-                if config.enabled { 1 } else { 0 },
-                if config.auto_failover_enabled { 1 } else { 0 },
-                config.max_retries as i32,
-                config.streaming_first_byte_timeout as i32,
-                config.streaming_idle_timeout as i32,
-                config.non_streaming_timeout as i32,
-                config.circuit_failure_threshold as i32,
-                config.circuit_success_threshold as i32,
-                config.circuit_timeout_seconds as i32,
-                config.circuit_error_rate_threshold,
-                config.circuit_min_requests as i32,
-            ],
-        )
-        .map_err(|e| AppError::Database(e.to_string()))?;
-
-        Ok(())
+        write_app_proxy_config_row(&conn, &config)
     }
 
     /// 确保指定 app_type 的 proxy_config 行存在（同步版本，用于 set_* 函数）
@@ -848,6 +815,45 @@ impl Database {
     }
 }
 
+/// 更新指定应用的代理配置行
+pub(super) fn write_app_proxy_config_row(
+    conn: &rusqlite::Connection,
+    config: &AppProxyConfig,
+) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE proxy_config SET
+            enabled = ?2,
+            auto_failover_enabled = ?3,
+            max_retries = ?4,
+            streaming_first_byte_timeout = ?5,
+            streaming_idle_timeout = ?6,
+            non_streaming_timeout = ?7,
+            circuit_failure_threshold = ?8,
+            circuit_success_threshold = ?9,
+            circuit_timeout_seconds = ?10,
+            circuit_error_rate_threshold = ?11,
+            circuit_min_requests = ?12,
+            updated_at = datetime('now')
+         WHERE app_type = ?1",
+        rusqlite::params![
+            config.app_type,
+            if config.enabled { 1 } else { 0 },
+            if config.auto_failover_enabled { 1 } else { 0 },
+            config.max_retries as i32,
+            config.streaming_first_byte_timeout as i32,
+            config.streaming_idle_timeout as i32,
+            config.non_streaming_timeout as i32,
+            config.circuit_failure_threshold as i32,
+            config.circuit_success_threshold as i32,
+            config.circuit_timeout_seconds as i32,
+            config.circuit_error_rate_threshold,
+            config.circuit_min_requests as i32,
+        ],
+    )
+    .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::database::Database;
@@ -0,0 +1,46 @@
+//! 远程数据缓存 DAO
+
+use rusqlite::{params, OptionalExtension};
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::remote_cache::RemoteCacheEntry;
+
+impl Database {
+    /// 读取缓存条目（含已过期的条目，由调用方判断是否新鲜）
+    pub fn get_remote_cache(&self, key: &str) -> Result<Option<RemoteCacheEntry>, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            "SELECT payload, fetched_at, expires_at FROM remote_cache WHERE key = ?1",
+            params![key],
+            |row| {
+                Ok(RemoteCacheEntry {
+                    payload: row.get(0)?,
+                    fetched_at: row.get(1)?,
+                    expires_at: row.get(2)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 写入（覆盖）缓存条目
+    pub fn put_remote_cache(&self, key: &str, entry: &RemoteCacheEntry) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO remote_cache (key, payload, fetched_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![key, entry.payload, entry.fetched_at, entry.expires_at],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 清空全部缓存，返回删除的条目数
+    pub fn clear_remote_cache(&self) -> Result<usize, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute("DELETE FROM remote_cache", [])
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+}
@@ -0,0 +1,85 @@
+//! Skill 文件校验记录 DAO
+
+use rusqlite::{params, Row};
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::skill_integrity::SkillIntegrityRecord;
+
+fn record_from_row(row: &Row<'_>) -> rusqlite::Result<SkillIntegrityRecord> {
+    let files: String = row.get(2)?;
+    Ok(SkillIntegrityRecord {
+        skill_id: row.get(0)?,
+        source_sha: row.get(1)?,
+        files: serde_json::from_str(&files).unwrap_or_default(),
+        recorded_at: row.get(3)?,
+    })
+}
+
+impl Database {
+    /// 获取全部 Skill 校验记录
+    pub fn get_skill_integrity_records(&self) -> Result<Vec<SkillIntegrityRecord>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT skill_id, source_sha, files, recorded_at FROM skill_integrity
+                 ORDER BY skill_id",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], record_from_row)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 获取单个 Skill 的校验记录
+    pub fn get_skill_integrity(
+        &self,
+        skill_id: &str,
+    ) -> Result<Option<SkillIntegrityRecord>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT skill_id, source_sha, files, recorded_at FROM skill_integrity
+                 WHERE skill_id = ?1",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let mut rows = stmt
+            .query_map(params![skill_id], record_from_row)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.next()
+            .transpose()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 保存 Skill 校验记录（覆盖旧记录）
+    pub fn save_skill_integrity(&self, record: &SkillIntegrityRecord) -> Result<(), AppError> {
+        let files = serde_json::to_string(&record.files)
+            .map_err(|source| AppError::JsonSerialize { source })?;
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO skill_integrity (skill_id, source_sha, files, recorded_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                record.skill_id,
+                record.source_sha,
+                files,
+                record.recorded_at
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除 Skill 校验记录
+    pub fn delete_skill_integrity(&self, skill_id: &str) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "DELETE FROM skill_integrity WHERE skill_id = ?1",
+            params![skill_id],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
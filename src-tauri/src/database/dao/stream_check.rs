@@ -2,7 +2,7 @@
 
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
-use crate::services::stream_check::{StreamCheckConfig, StreamCheckResult};
+use crate::services::stream_check::{LatencyCandidate, StreamCheckConfig, StreamCheckResult};
 
 impl Database {
     /// 保存流式检查日志
@@ -39,6 +39,73 @@ impl Database {
         Ok(conn.last_insert_rowid())
     }
 
+    /// 获取供应商在 `before_id` 之前最近 `limit` 次成功检查的延迟（毫秒）
+    pub fn get_recent_stream_check_latencies(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        before_id: i64,
+        limit: u32,
+    ) -> Result<Vec<u64>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT response_time_ms FROM stream_check_logs
+                 WHERE app_type = ?1 AND provider_id = ?2 AND id < ?3
+                   AND success = 1 AND response_time_ms IS NOT NULL
+                 ORDER BY id DESC
+                 LIMIT ?4",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(
+                rusqlite::params![app_type, provider_id, before_id, limit],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut latencies = Vec::new();
+        for row in rows {
+            let ms = row.map_err(|e| AppError::Database(e.to_string()))?;
+            latencies.push(ms.max(0) as u64);
+        }
+        Ok(latencies)
+    }
+
+    /// 获取应用下各供应商自 `since`（Unix 秒）以来最近一次成功检查的延迟
+    pub fn get_latest_stream_check_latencies(
+        &self,
+        app_type: &str,
+        since: i64,
+    ) -> Result<Vec<LatencyCandidate>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT provider_id, provider_name, response_time_ms FROM stream_check_logs
+                 WHERE id IN (
+                     SELECT MAX(id) FROM stream_check_logs
+                     WHERE app_type = ?1 AND success = 1 AND response_time_ms IS NOT NULL
+                       AND tested_at >= ?2
+                     GROUP BY provider_id
+                 )",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params![app_type, since], |row| {
+                Ok(LatencyCandidate {
+                    provider_id: row.get(0)?,
+                    provider_name: row.get(1)?,
+                    latency_ms: row.get::<_, i64>(2)?.max(0) as u64,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
     /// 获取流式检查配置
     pub fn get_stream_check_config(&self) -> Result<StreamCheckConfig, AppError> {
         match self.get_setting("stream_check_config")? {
@@ -45,7 +45,13 @@ use std::sync::Mutex;
 
 /// 当前 Schema 版本号
 /// 每次修改表结构时递增，并在 schema.rs 中添加相应的迁移逻辑
-pub(crate) const SCHEMA_VERSION: i32 = 7;
+pub(crate) const SCHEMA_VERSION: i32 = 40;
+
+/// Agent 全文索引表名（其影子表与同步触发器均以此为前缀）
+pub(crate) const AGENT_FTS_TABLE: &str = "agent_definitions_fts";
+
+/// 提示词全文索引表名（同上）
+pub(crate) const PROMPT_FTS_TABLE: &str = "prompts_fts";
 
 /// 安全地序列化 JSON，避免 unwrap panic
 pub(crate) fn to_json_string<T: Serialize>(value: &T) -> Result<String, AppError> {
@@ -156,6 +162,15 @@ impl Database {
         Ok(count == 0)
     }
 
+    /// 检查供应商表是否为空（回收站中的供应商也算在内）
+    pub fn is_providers_table_empty(&self) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM providers", [], |row| row.get(0))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(count == 0)
+    }
+
     /// 检查提示词表是否为空
     pub fn is_prompts_table_empty(&self) -> Result<bool, AppError> {
         let conn = lock_conn!(self.conn);
@@ -2,7 +2,7 @@
 //!
 //! 负责数据库表结构的创建和版本迁移。
 
-use super::{lock_conn, Database, SCHEMA_VERSION};
+use super::{lock_conn, Database, AGENT_FTS_TABLE, PROMPT_FTS_TABLE, SCHEMA_VERSION};
 use crate::error::AppError;
 use rusqlite::Connection;
 
@@ -32,6 +32,9 @@ impl Database {
                 meta TEXT NOT NULL DEFAULT '{}',
                 is_current BOOLEAN NOT NULL DEFAULT 0,
                 in_failover_queue BOOLEAN NOT NULL DEFAULT 0,
+                archived BOOLEAN NOT NULL DEFAULT 0,
+                deleted_at INTEGER,
+                tags TEXT NOT NULL DEFAULT '[]',
                 PRIMARY KEY (id, app_type)
             )",
             [],
@@ -46,6 +49,10 @@ impl Database {
                 app_type TEXT NOT NULL,
                 url TEXT NOT NULL,
                 added_at INTEGER,
+                last_used_at INTEGER,
+                last_benchmarked_at INTEGER,
+                consecutive_failures INTEGER NOT NULL DEFAULT 0,
+                last_latency_ms INTEGER,
                 FOREIGN KEY (provider_id, app_type) REFERENCES providers(id, app_type) ON DELETE CASCADE
             )",
             [],
@@ -58,7 +65,8 @@ impl Database {
             id TEXT PRIMARY KEY, name TEXT NOT NULL, server_config TEXT NOT NULL,
             description TEXT, homepage TEXT, docs TEXT, tags TEXT NOT NULL DEFAULT '[]',
             enabled_claude BOOLEAN NOT NULL DEFAULT 0, enabled_codex BOOLEAN NOT NULL DEFAULT 0,
-            enabled_gemini BOOLEAN NOT NULL DEFAULT 0, enabled_opencode BOOLEAN NOT NULL DEFAULT 0
+            enabled_gemini BOOLEAN NOT NULL DEFAULT 0, enabled_opencode BOOLEAN NOT NULL DEFAULT 0,
+            origin TEXT
         )",
             [],
         )
@@ -75,7 +83,10 @@ impl Database {
             gemini_enabled   BOOLEAN NOT NULL DEFAULT 0,
             opencode_enabled BOOLEAN NOT NULL DEFAULT 0,
             created_at INTEGER,
-            updated_at INTEGER
+            updated_at INTEGER,
+            sort_index INTEGER,
+            openclaw_enabled BOOLEAN NOT NULL DEFAULT 0,
+            tags TEXT NOT NULL DEFAULT '[]'
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
 
         // 5. Skills 表（v3.10.0+ 统一结构）
@@ -317,13 +328,74 @@ impl Database {
                 enabled_codex    BOOLEAN NOT NULL DEFAULT 0,
                 enabled_gemini   BOOLEAN NOT NULL DEFAULT 0,
                 enabled_opencode BOOLEAN NOT NULL DEFAULT 0,
+                enabled_openclaw BOOLEAN NOT NULL DEFAULT 0,
                 created_at INTEGER,
-                updated_at INTEGER
+                updated_at INTEGER,
+                tools TEXT,
+                model TEXT,
+                color TEXT,
+                sort_index INTEGER,
+                group_name TEXT,
+                tags TEXT NOT NULL DEFAULT '[]'
             )",
             [],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+        // 14. Agent 历史版本表（v9→v10 迁移新增）
+        Self::create_agent_history_table(conn)?;
+
+        // 15. 供应商凭据问题表（v10→v11 迁移新增）
+        Self::create_credential_issues_table(conn)?;
+
+        // 16. 远程数据缓存表（v12→v13 迁移新增）
+        Self::create_remote_cache_table(conn)?;
+
+        // 17. MCP 服务器与供应商凭据绑定表（v13→v14 迁移新增）
+        Self::create_mcp_provider_bindings_table(conn)?;
+
+        // 18. Agent 全文索引（v14→v15 迁移新增）
+        Self::create_agent_search_index(conn)?;
+
+        // 19. 订阅源及其条目表（v17→v18 迁移新增）
+        Self::create_feed_tables(conn)?;
+
+        // 20. 项目级提示词：项目登记表与提示词挂载表（v18→v19 迁移新增）
+        Self::create_prompt_project_tables(conn)?;
+
+        // 21. 提示词全文索引（v19→v20 迁移新增）
+        Self::create_prompt_search_index(conn)?;
+
+        // 22. 提示词回收站（v20→v21 迁移新增）
+        Self::create_prompt_trash_table(conn)?;
+
+        // 23. 定时切换提示词（v21→v22 迁移新增）
+        Self::create_prompt_schedules_table(conn)?;
+
+        // 24. 本地 API 访问令牌（v22→v23 迁移新增）
+        Self::create_api_tokens_table(conn)?;
+
+        // 25. 供应商延迟测速记录（v26→v27 迁移新增）
+        Self::create_provider_benchmarks_table(conn)?;
+
+        // 26. 供应商组合（v27→v28 迁移新增）
+        Self::create_provider_profiles_table(conn)?;
+
+        // 27. Skill 文件校验记录（v28→v29 迁移新增）
+        Self::create_skill_integrity_table(conn)?;
+
+        // 28. 供应商备注附件（v30→v31 迁移新增）
+        Self::create_provider_attachments_table(conn)?;
+
+        // 29. 供应商变更审计日志（v34→v35 迁移新增）
+        Self::create_provider_audit_log_table(conn)?;
+
+        // 30. 提示词历史快照（v35→v36 迁移新增）
+        Self::create_prompt_history_table(conn)?;
+
+        // 31. 外部修改冲突记录（v37→v38 迁移新增）
+        Self::create_external_edit_conflicts_table(conn)?;
+
         // 为故障转移队列创建索引（基于 providers 表）
         let _ = conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_providers_failover
@@ -395,6 +467,171 @@ impl Database {
                         Self::migrate_v6_to_v7(conn)?;
                         Self::set_user_version(conn, 7)?;
                     }
+                    7 => {
+                        log::info!("迁移数据库从 v7 到 v8（OpenClaw Agent 同步）");
+                        Self::migrate_v7_to_v8(conn)?;
+                        Self::set_user_version(conn, 8)?;
+                    }
+                    8 => {
+                        log::info!("迁移数据库从 v8 到 v9（Agent 扩展 frontmatter 字段）");
+                        Self::migrate_v8_to_v9(conn)?;
+                        Self::set_user_version(conn, 9)?;
+                    }
+                    9 => {
+                        log::info!("迁移数据库从 v9 到 v10（Agent 历史版本）");
+                        Self::migrate_v9_to_v10(conn)?;
+                        Self::set_user_version(conn, 10)?;
+                    }
+                    10 => {
+                        log::info!("迁移数据库从 v10 到 v11（供应商凭据问题识别）");
+                        Self::migrate_v10_to_v11(conn)?;
+                        Self::set_user_version(conn, 11)?;
+                    }
+                    11 => {
+                        log::info!("迁移数据库从 v11 到 v12（Agent 排序与分组）");
+                        Self::migrate_v11_to_v12(conn)?;
+                        Self::set_user_version(conn, 12)?;
+                    }
+                    12 => {
+                        log::info!("迁移数据库从 v12 到 v13（远程数据缓存）");
+                        Self::migrate_v12_to_v13(conn)?;
+                        Self::set_user_version(conn, 13)?;
+                    }
+                    13 => {
+                        log::info!("迁移数据库从 v13 到 v14（MCP 供应商绑定）");
+                        Self::migrate_v13_to_v14(conn)?;
+                        Self::set_user_version(conn, 14)?;
+                    }
+                    14 => {
+                        log::info!("迁移数据库从 v14 到 v15（Agent 全文搜索）");
+                        Self::migrate_v14_to_v15(conn)?;
+                        Self::set_user_version(conn, 15)?;
+                    }
+                    15 => {
+                        log::info!("迁移数据库从 v15 到 v16（Agent 标签）");
+                        Self::migrate_v15_to_v16(conn)?;
+                        Self::set_user_version(conn, 16)?;
+                    }
+                    16 => {
+                        log::info!("迁移数据库从 v16 到 v17（提示词优先级）");
+                        Self::migrate_v16_to_v17(conn)?;
+                        Self::set_user_version(conn, 17)?;
+                    }
+                    17 => {
+                        log::info!("迁移数据库从 v17 到 v18（订阅源）");
+                        Self::migrate_v17_to_v18(conn)?;
+                        Self::set_user_version(conn, 18)?;
+                    }
+                    18 => {
+                        log::info!("迁移数据库从 v18 到 v19（项目级提示词）");
+                        Self::migrate_v18_to_v19(conn)?;
+                        Self::set_user_version(conn, 19)?;
+                    }
+                    19 => {
+                        log::info!("迁移数据库从 v19 到 v20（提示词全文索引）");
+                        Self::migrate_v19_to_v20(conn)?;
+                        Self::set_user_version(conn, 20)?;
+                    }
+                    20 => {
+                        log::info!("迁移数据库从 v20 到 v21（提示词回收站）");
+                        Self::migrate_v20_to_v21(conn)?;
+                        Self::set_user_version(conn, 21)?;
+                    }
+                    21 => {
+                        log::info!("迁移数据库从 v21 到 v22（定时切换提示词）");
+                        Self::migrate_v21_to_v22(conn)?;
+                        Self::set_user_version(conn, 22)?;
+                    }
+                    22 => {
+                        log::info!("迁移数据库从 v22 到 v23（API 访问令牌）");
+                        Self::migrate_v22_to_v23(conn)?;
+                        Self::set_user_version(conn, 23)?;
+                    }
+                    23 => {
+                        log::info!("迁移数据库从 v23 到 v24（OpenClaw 提示词启用列）");
+                        Self::migrate_v23_to_v24(conn)?;
+                        Self::set_user_version(conn, 24)?;
+                    }
+                    24 => {
+                        log::info!("迁移数据库从 v24 到 v25（端点使用与测速记录）");
+                        Self::migrate_v24_to_v25(conn)?;
+                        Self::set_user_version(conn, 25)?;
+                    }
+                    25 => {
+                        log::info!("迁移数据库从 v25 到 v26（MCP 服务器来源）");
+                        Self::migrate_v25_to_v26(conn)?;
+                        Self::set_user_version(conn, 26)?;
+                    }
+                    26 => {
+                        log::info!("迁移数据库从 v26 到 v27（供应商延迟测速记录）");
+                        Self::migrate_v26_to_v27(conn)?;
+                        Self::set_user_version(conn, 27)?;
+                    }
+                    27 => {
+                        log::info!("迁移数据库从 v27 到 v28（供应商组合）");
+                        Self::migrate_v27_to_v28(conn)?;
+                        Self::set_user_version(conn, 28)?;
+                    }
+                    28 => {
+                        log::info!("迁移数据库从 v28 到 v29（Skill 文件校验记录）");
+                        Self::migrate_v28_to_v29(conn)?;
+                        Self::set_user_version(conn, 29)?;
+                    }
+                    29 => {
+                        log::info!("迁移数据库从 v29 到 v30（供应商归档）");
+                        Self::migrate_v29_to_v30(conn)?;
+                        Self::set_user_version(conn, 30)?;
+                    }
+                    30 => {
+                        log::info!("迁移数据库从 v30 到 v31（供应商备注附件）");
+                        Self::migrate_v30_to_v31(conn)?;
+                        Self::set_user_version(conn, 31)?;
+                    }
+                    31 => {
+                        log::info!("迁移数据库从 v31 到 v32（供应商回收站）");
+                        Self::migrate_v31_to_v32(conn)?;
+                        Self::set_user_version(conn, 32)?;
+                    }
+                    32 => {
+                        log::info!("迁移数据库从 v32 到 v33（供应商标签）");
+                        Self::migrate_v32_to_v33(conn)?;
+                        Self::set_user_version(conn, 33)?;
+                    }
+                    33 => {
+                        log::info!("迁移数据库从 v33 到 v34（端点延迟记录）");
+                        Self::migrate_v33_to_v34(conn)?;
+                        Self::set_user_version(conn, 34)?;
+                    }
+                    34 => {
+                        log::info!("迁移数据库从 v34 到 v35（供应商变更审计日志）");
+                        Self::migrate_v34_to_v35(conn)?;
+                        Self::set_user_version(conn, 35)?;
+                    }
+                    35 => {
+                        log::info!("迁移数据库从 v35 到 v36（提示词历史快照）");
+                        Self::migrate_v35_to_v36(conn)?;
+                        Self::set_user_version(conn, 36)?;
+                    }
+                    36 => {
+                        log::info!("迁移数据库从 v36 到 v37（订阅条目来源标记）");
+                        Self::migrate_v36_to_v37(conn)?;
+                        Self::set_user_version(conn, 37)?;
+                    }
+                    37 => {
+                        log::info!("迁移数据库从 v37 到 v38（外部修改冲突记录）");
+                        Self::migrate_v37_to_v38(conn)?;
+                        Self::set_user_version(conn, 38)?;
+                    }
+                    38 => {
+                        log::info!("迁移数据库从 v38 到 v39（提示词标签）");
+                        Self::migrate_v38_to_v39(conn)?;
+                        Self::set_user_version(conn, 39)?;
+                    }
+                    39 => {
+                        log::info!("迁移数据库从 v39 到 v40（保留已在故障转移队列中的供应商）");
+                        Self::migrate_v39_to_v40(conn)?;
+                        Self::set_user_version(conn, 40)?;
+                    }
                     _ => {
                         return Err(AppError::Database(format!(
                             "未知的数据库版本 {version}，无法迁移到 {SCHEMA_VERSION}"
@@ -1081,6 +1318,813 @@ impl Database {
         Ok(())
     }
 
+    /// v7 -> v8 迁移：agent_definitions 新增 enabled_openclaw 列
+    fn migrate_v7_to_v8(conn: &Connection) -> Result<(), AppError> {
+        Self::add_column_if_missing(
+            conn,
+            "agent_definitions",
+            "enabled_openclaw",
+            "BOOLEAN NOT NULL DEFAULT 0",
+        )?;
+
+        log::info!("v7 -> v8 迁移完成：agent_definitions 已添加 enabled_openclaw 列");
+        Ok(())
+    }
+
+    /// v8 -> v9 迁移：agent_definitions 新增 tools / model / color 列
+    fn migrate_v8_to_v9(conn: &Connection) -> Result<(), AppError> {
+        for column in ["tools", "model", "color"] {
+            Self::add_column_if_missing(conn, "agent_definitions", column, "TEXT")?;
+        }
+
+        log::info!("v8 -> v9 迁移完成：agent_definitions 已添加 tools/model/color 列");
+        Ok(())
+    }
+
+    /// v9 -> v10 迁移：新增 agent_definition_history 表
+    fn migrate_v9_to_v10(conn: &Connection) -> Result<(), AppError> {
+        Self::create_agent_history_table(conn)?;
+
+        log::info!("v9 -> v10 迁移完成：已添加 agent_definition_history 表");
+        Ok(())
+    }
+
+    /// 创建 Agent 历史版本表（快照以 JSON 保存，字段增减无需再迁移）
+    fn create_agent_history_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS agent_definition_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                agent_id TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                snapshot TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                UNIQUE(agent_id, version)
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// v10 -> v11 迁移：记录从 401 等认证错误中识别出的凭据问题
+    fn migrate_v10_to_v11(conn: &Connection) -> Result<(), AppError> {
+        Self::create_credential_issues_table(conn)?;
+
+        log::info!("v10 -> v11 迁移完成：已添加 provider_credential_issues 表");
+        Ok(())
+    }
+
+    /// v11 -> v12 迁移：agent_definitions 新增 sort_index / group_name 列
+    ///
+    /// 已有 agent 按原来的 created_at、id 顺序回填 sort_index，保持迁移前的显示顺序。
+    fn migrate_v11_to_v12(conn: &Connection) -> Result<(), AppError> {
+        Self::add_column_if_missing(conn, "agent_definitions", "sort_index", "INTEGER")?;
+        Self::add_column_if_missing(conn, "agent_definitions", "group_name", "TEXT")?;
+
+        conn.execute(
+            "UPDATE agent_definitions SET sort_index = (
+                SELECT COUNT(*) FROM agent_definitions AS other
+                WHERE (COALESCE(other.created_at, 0), other.id)
+                    < (COALESCE(agent_definitions.created_at, 0), agent_definitions.id)
+            )
+            WHERE sort_index IS NULL",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("回填 agent sort_index 失败: {e}")))?;
+
+        log::info!("v11 -> v12 迁移完成：agent_definitions 已添加 sort_index/group_name 列");
+        Ok(())
+    }
+
+    /// v12 -> v13 迁移：新增 remote_cache 表
+    fn migrate_v12_to_v13(conn: &Connection) -> Result<(), AppError> {
+        Self::create_remote_cache_table(conn)?;
+
+        log::info!("v12 -> v13 迁移完成：已添加 remote_cache 表");
+        Ok(())
+    }
+
+    /// v13 -> v14 迁移：新增 mcp_provider_bindings 表
+    fn migrate_v13_to_v14(conn: &Connection) -> Result<(), AppError> {
+        Self::create_mcp_provider_bindings_table(conn)?;
+
+        log::info!("v13 -> v14 迁移完成：已添加 mcp_provider_bindings 表");
+        Ok(())
+    }
+
+    /// v14 -> v15 迁移：新增 agent_definitions 的 FTS5 全文索引，并为已有 agent 建立索引
+    fn migrate_v14_to_v15(conn: &Connection) -> Result<(), AppError> {
+        Self::create_agent_search_index(conn)?;
+
+        log::info!("v14 -> v15 迁移完成：已添加 agent_definitions_fts 全文索引");
+        Ok(())
+    }
+
+    /// v15 -> v16 迁移：agent_definitions 新增 tags 列（JSON 数组，已有 agent 为空数组）
+    fn migrate_v15_to_v16(conn: &Connection) -> Result<(), AppError> {
+        Self::add_column_if_missing(
+            conn,
+            "agent_definitions",
+            "tags",
+            "TEXT NOT NULL DEFAULT '[]'",
+        )?;
+
+        log::info!("v15 -> v16 迁移完成：agent_definitions 已添加 tags 列");
+        Ok(())
+    }
+
+    /// v16 -> v17 迁移：prompts 新增 sort_index 列
+    ///
+    /// 同一 app 可以启用多个提示词后，按该列决定拼接顺序；已有提示词按 created_at、id 回填，保持原有显示顺序。
+    fn migrate_v16_to_v17(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "prompts")? {
+            return Ok(());
+        }
+        Self::add_column_if_missing(conn, "prompts", "sort_index", "INTEGER")?;
+
+        conn.execute(
+            "UPDATE prompts SET sort_index = (
+                SELECT COUNT(*) FROM prompts AS other
+                WHERE (COALESCE(other.created_at, 0), other.id)
+                    < (COALESCE(prompts.created_at, 0), prompts.id)
+            )
+            WHERE sort_index IS NULL",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("回填 prompt sort_index 失败: {e}")))?;
+
+        log::info!("v16 -> v17 迁移完成：prompts 已添加 sort_index 列");
+        Ok(())
+    }
+
+    /// v17 -> v18 迁移：新增 feed_subscriptions / feed_items 表
+    fn migrate_v17_to_v18(conn: &Connection) -> Result<(), AppError> {
+        Self::create_feed_tables(conn)?;
+
+        log::info!("v17 -> v18 迁移完成：已添加订阅源表");
+        Ok(())
+    }
+
+    /// v18 -> v19 迁移：新增 prompt_projects / prompt_project_links 表
+    fn migrate_v18_to_v19(conn: &Connection) -> Result<(), AppError> {
+        Self::create_prompt_project_tables(conn)?;
+
+        log::info!("v18 -> v19 迁移完成：已添加项目级提示词表");
+        Ok(())
+    }
+
+    /// v19 -> v20 迁移：新增 prompts 的 FTS5 全文索引，并为已有提示词建立索引
+    fn migrate_v19_to_v20(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "prompts")? {
+            return Ok(());
+        }
+        Self::create_prompt_search_index(conn)?;
+
+        log::info!("v19 -> v20 迁移完成：已添加 prompts_fts 全文索引");
+        Ok(())
+    }
+
+    /// v20 -> v21 迁移：新增 prompt_trash 表
+    fn migrate_v20_to_v21(conn: &Connection) -> Result<(), AppError> {
+        Self::create_prompt_trash_table(conn)?;
+
+        log::info!("v20 -> v21 迁移完成：已添加提示词回收站");
+        Ok(())
+    }
+
+    /// v21 -> v22 迁移：新增 prompt_schedules 表
+    fn migrate_v21_to_v22(conn: &Connection) -> Result<(), AppError> {
+        Self::create_prompt_schedules_table(conn)?;
+
+        log::info!("v21 -> v22 迁移完成：已添加定时切换提示词规则表");
+        Ok(())
+    }
+
+    /// v22 -> v23 迁移：新增 api_tokens 表
+    fn migrate_v22_to_v23(conn: &Connection) -> Result<(), AppError> {
+        Self::create_api_tokens_table(conn)?;
+
+        log::info!("v22 -> v23 迁移完成：已添加 API 访问令牌表");
+        Ok(())
+    }
+
+    /// v23 -> v24 迁移：prompts 新增 openclaw_enabled 列
+    ///
+    /// 此前 OpenClaw 借用 opencode_enabled 列，但提示词从未写入 OpenClaw 的文件，
+    /// 因此新列默认全部关闭，不从 opencode_enabled 复制。
+    fn migrate_v23_to_v24(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "prompts")? {
+            return Ok(());
+        }
+        Self::add_column_if_missing(
+            conn,
+            "prompts",
+            "openclaw_enabled",
+            "BOOLEAN NOT NULL DEFAULT 0",
+        )?;
+
+        log::info!("v23 -> v24 迁移完成：prompts 已添加 openclaw_enabled 列");
+        Ok(())
+    }
+
+    /// v24 -> v25 迁移：provider_endpoints 新增最近使用、最近测速时间与连续失败次数
+    fn migrate_v24_to_v25(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "provider_endpoints")? {
+            return Ok(());
+        }
+        Self::add_column_if_missing(conn, "provider_endpoints", "last_used_at", "INTEGER")?;
+        Self::add_column_if_missing(conn, "provider_endpoints", "last_benchmarked_at", "INTEGER")?;
+        Self::add_column_if_missing(
+            conn,
+            "provider_endpoints",
+            "consecutive_failures",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+
+        log::info!("v24 -> v25 迁移完成：provider_endpoints 已添加使用与测速记录列");
+        Ok(())
+    }
+
+    /// v25 -> v26 迁移：mcp_servers 新增 origin 列（外部来源的只读条目，如 Gemini 扩展）
+    fn migrate_v25_to_v26(conn: &Connection) -> Result<(), AppError> {
+        Self::add_column_if_missing(conn, "mcp_servers", "origin", "TEXT")?;
+
+        log::info!("v25 -> v26 迁移完成：mcp_servers 已添加 origin 列");
+        Ok(())
+    }
+
+    /// v26 -> v27 迁移：新增 provider_benchmarks 表
+    fn migrate_v26_to_v27(conn: &Connection) -> Result<(), AppError> {
+        Self::create_provider_benchmarks_table(conn)?;
+
+        log::info!("v26 -> v27 迁移完成：已添加供应商延迟测速记录表");
+        Ok(())
+    }
+
+    /// v27 -> v28 迁移：新增 provider_profiles 表
+    fn migrate_v27_to_v28(conn: &Connection) -> Result<(), AppError> {
+        Self::create_provider_profiles_table(conn)?;
+
+        log::info!("v27 -> v28 迁移完成：已添加供应商组合表");
+        Ok(())
+    }
+
+    /// v28 -> v29 迁移：新增 skill_integrity 表
+    fn migrate_v28_to_v29(conn: &Connection) -> Result<(), AppError> {
+        Self::create_skill_integrity_table(conn)?;
+
+        log::info!("v28 -> v29 迁移完成：已添加 Skill 文件校验记录表");
+        Ok(())
+    }
+
+    /// v29 -> v30 迁移：providers 新增 archived 列
+    fn migrate_v29_to_v30(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "providers")? {
+            return Ok(());
+        }
+        Self::add_column_if_missing(conn, "providers", "archived", "BOOLEAN NOT NULL DEFAULT 0")?;
+
+        log::info!("v29 -> v30 迁移完成：providers 已添加 archived 列");
+        Ok(())
+    }
+
+    /// v30 -> v31 迁移：新增 provider_attachments 表
+    fn migrate_v30_to_v31(conn: &Connection) -> Result<(), AppError> {
+        Self::create_provider_attachments_table(conn)?;
+
+        log::info!("v30 -> v31 迁移完成：已添加供应商备注附件表");
+        Ok(())
+    }
+
+    /// v31 -> v32 迁移：providers 表新增 deleted_at 列（软删除）
+    fn migrate_v31_to_v32(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "providers")? {
+            return Ok(());
+        }
+        Self::add_column_if_missing(conn, "providers", "deleted_at", "INTEGER")?;
+
+        log::info!("v31 -> v32 迁移完成：providers 已添加 deleted_at 列");
+        Ok(())
+    }
+
+    /// v32 -> v33 迁移：providers 新增 tags 列（JSON 字符串数组）
+    fn migrate_v32_to_v33(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "providers")? {
+            return Ok(());
+        }
+        Self::add_column_if_missing(conn, "providers", "tags", "TEXT NOT NULL DEFAULT '[]'")?;
+
+        log::info!("v32 -> v33 迁移完成：providers 已添加 tags 列");
+        Ok(())
+    }
+
+    /// v33 -> v34 迁移：provider_endpoints 新增最近一次成功测速的延迟
+    fn migrate_v33_to_v34(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "provider_endpoints")? {
+            return Ok(());
+        }
+        Self::add_column_if_missing(conn, "provider_endpoints", "last_latency_ms", "INTEGER")?;
+
+        log::info!("v33 -> v34 迁移完成：provider_endpoints 已添加 last_latency_ms 列");
+        Ok(())
+    }
+
+    /// v34 -> v35 迁移：新增 provider_audit_log 表
+    fn migrate_v34_to_v35(conn: &Connection) -> Result<(), AppError> {
+        Self::create_provider_audit_log_table(conn)?;
+
+        log::info!("v34 -> v35 迁移完成：已添加供应商变更审计日志表");
+        Ok(())
+    }
+
+    /// v35 -> v36 迁移：新增 prompt_history 表
+    fn migrate_v35_to_v36(conn: &Connection) -> Result<(), AppError> {
+        Self::create_prompt_history_table(conn)?;
+
+        log::info!("v35 -> v36 迁移完成：已添加提示词历史快照表");
+        Ok(())
+    }
+
+    /// v36 -> v37 迁移：feed_items 新增 applied 列
+    ///
+    /// 旧版本没有区分接受与忽略，已处理过的条目一律视为写入过本地，保持原有的覆盖行为。
+    fn migrate_v36_to_v37(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "feed_items")? {
+            return Ok(());
+        }
+        if Self::add_column_if_missing(conn, "feed_items", "applied", "BOOLEAN NOT NULL DEFAULT 0")?
+        {
+            conn.execute(
+                "UPDATE feed_items SET applied = 1 WHERE accepted_hash IS NOT NULL",
+                [],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        log::info!("v36 -> v37 迁移完成：feed_items 已添加 applied 列");
+        Ok(())
+    }
+
+    /// v37 -> v38 迁移：新增 external_edit_conflicts 表
+    fn migrate_v37_to_v38(conn: &Connection) -> Result<(), AppError> {
+        Self::create_external_edit_conflicts_table(conn)?;
+
+        log::info!("v37 -> v38 迁移完成：已添加外部修改冲突记录表");
+        Ok(())
+    }
+
+    /// v38 -> v39 迁移：prompts 新增 tags 列（JSON 字符串数组，已有提示词为空数组）
+    fn migrate_v38_to_v39(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "prompts")? {
+            return Ok(());
+        }
+        Self::add_column_if_missing(conn, "prompts", "tags", "TEXT NOT NULL DEFAULT '[]'")?;
+
+        log::info!("v38 -> v39 迁移完成：prompts 已添加 tags 列");
+        Ok(())
+    }
+
+    /// v39 -> v40 迁移：已在故障转移队列中的供应商记为允许自动化使用
+    ///
+    /// 故障转移只选用允许自动化使用的供应商，升级前加入队列的未验证供应商按已确认处理；
+    /// 已明确设置过的值保持不变。
+    fn migrate_v39_to_v40(conn: &Connection) -> Result<(), AppError> {
+        if !Self::table_exists(conn, "providers")?
+            || !Self::has_column(conn, "providers", "in_failover_queue")?
+            || !Self::has_column(conn, "providers", "meta")?
+        {
+            return Ok(());
+        }
+        let updated = conn
+            .execute(
+                "UPDATE providers
+                 SET meta = json_set(meta, '$.automationAllowed', json('true'))
+                 WHERE in_failover_queue = 1
+                   AND json_valid(meta)
+                   AND json_type(meta, '$.automationAllowed') IS NULL",
+                [],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        log::info!("v39 -> v40 迁移完成：{updated} 个故障转移队列中的供应商已记为允许自动化使用");
+        Ok(())
+    }
+
+    /// 创建提示词历史快照表
+    ///
+    /// 与 agent 每次保存都追加版本不同，提示词只在批量改写、外部修改覆盖等
+    /// 不经编辑器的变更前记录快照；快照以 JSON 保存，字段增减无需再迁移。
+    fn create_prompt_history_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS prompt_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                prompt_id TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                reason TEXT NOT NULL,
+                snapshot TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                UNIQUE(prompt_id, version)
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建供应商凭据问题表（每个供应商只保留最近一次识别结果）
+    fn create_credential_issues_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_credential_issues (
+                provider_id TEXT NOT NULL,
+                app_type TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                http_status INTEGER NOT NULL,
+                message TEXT NOT NULL DEFAULT '',
+                source TEXT NOT NULL,
+                detected_at INTEGER NOT NULL,
+                PRIMARY KEY (provider_id, app_type)
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建远程数据缓存表（技能仓库索引、工具版本等网络请求结果，按 key 覆盖）
+    fn create_remote_cache_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS remote_cache (
+                key TEXT PRIMARY KEY,
+                payload TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建订阅源表与订阅条目表
+    ///
+    /// 条目按 (订阅, 类型, id) 唯一；`accepted_hash` 记录用户最近一次接受（或忽略）的内容摘要，
+    /// 与 `content_hash` 不一致即为待处理的更新；`applied` 标记条目是否曾被接受写入本地。
+    fn create_feed_tables(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS feed_subscriptions (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL DEFAULT '',
+                enabled BOOLEAN NOT NULL DEFAULT 1,
+                last_checked_at INTEGER,
+                last_error TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS feed_items (
+                subscription_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                item_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                accepted_hash TEXT,
+                applied BOOLEAN NOT NULL DEFAULT 0,
+                fetched_at INTEGER NOT NULL,
+                PRIMARY KEY (subscription_id, kind, item_id),
+                FOREIGN KEY (subscription_id) REFERENCES feed_subscriptions(id) ON DELETE CASCADE
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建项目登记表与项目提示词挂载表
+    ///
+    /// 挂载按 (项目, 提示词, app) 唯一；删除项目时挂载随外键级联删除。
+    /// prompt_id 不设外键：保存提示词使用 INSERT OR REPLACE，级联会把挂载一并清掉，
+    /// 因此由删除提示词时显式清理。
+    fn create_prompt_project_tables(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS prompt_projects (
+                id TEXT PRIMARY KEY,
+                path TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL DEFAULT '',
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS prompt_project_links (
+                project_id TEXT NOT NULL,
+                prompt_id TEXT NOT NULL,
+                app_type TEXT NOT NULL,
+                PRIMARY KEY (project_id, prompt_id, app_type),
+                FOREIGN KEY (project_id) REFERENCES prompt_projects(id) ON DELETE CASCADE
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建提示词回收站表
+    ///
+    /// 删除的提示词整行以 JSON 保存（`prompt`），连同删除时的项目挂载（`links`），恢复时原样写回。
+    fn create_prompt_trash_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS prompt_trash (
+                id TEXT PRIMARY KEY,
+                prompt TEXT NOT NULL,
+                links TEXT NOT NULL DEFAULT '[]',
+                deleted_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建定时切换提示词规则表
+    ///
+    /// 规则（时区、时刻、星期）以 JSON 保存在 `schedule` 列。后台任务从 `last_run_at`（最近一次
+    /// 已处理的计划执行时间）与 `updated_at` 中较晚者之后查找到期的执行，时间均为 Unix 毫秒。
+    fn create_prompt_schedules_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS prompt_schedules (
+                id TEXT PRIMARY KEY,
+                app_type TEXT NOT NULL,
+                prompt_id TEXT NOT NULL,
+                schedule TEXT NOT NULL,
+                enabled BOOLEAN NOT NULL DEFAULT 1,
+                last_run_at INTEGER,
+                last_error TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建本地 API 访问令牌表
+    ///
+    /// 只保存令牌的 SHA-256 摘要（`token_hash`），明文只在创建时返回一次；`scopes` 为权限列表 JSON。
+    fn create_api_tokens_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS api_tokens (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                token_hash TEXT NOT NULL UNIQUE,
+                scopes TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                last_used_at INTEGER
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建 MCP 服务器与供应商凭据的绑定表（每个服务器最多一条绑定）
+    fn create_mcp_provider_bindings_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS mcp_provider_bindings (
+                server_id TEXT PRIMARY KEY,
+                app_type TEXT NOT NULL,
+                provider_id TEXT,
+                env_map TEXT NOT NULL DEFAULT '{}'
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建供应商延迟测速记录表（每次测速每个供应商一行，耗时单位为毫秒）
+    fn create_provider_benchmarks_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_benchmarks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                app_type TEXT NOT NULL,
+                provider_id TEXT NOT NULL,
+                provider_name TEXT NOT NULL,
+                url TEXT NOT NULL,
+                connect_ms INTEGER,
+                tls_ms INTEGER,
+                first_byte_ms INTEGER,
+                http_status INTEGER,
+                error TEXT,
+                measured_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_provider_benchmarks_provider
+             ON provider_benchmarks(app_type, provider_id)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建供应商组合表（`providers` 为应用 -> 供应商 id 的 JSON 对象）
+    fn create_provider_profiles_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_profiles (
+                name TEXT PRIMARY KEY,
+                providers TEXT NOT NULL DEFAULT '{}',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建供应商备注附件表（文件本身保存在 `~/.cc-switch/attachments/<id>/` 下）
+    fn create_provider_attachments_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_attachments (
+                id TEXT PRIMARY KEY,
+                provider_id TEXT NOT NULL,
+                app_type TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                added_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_provider_attachments_provider
+             ON provider_attachments(app_type, provider_id)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建供应商变更审计日志表
+    ///
+    /// `old_value` / `new_value` 为变更前后的供应商（切换时为当前供应商 id）JSON，密钥已脱敏。
+    fn create_provider_audit_log_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                app_type TEXT NOT NULL,
+                provider_id TEXT NOT NULL,
+                action TEXT NOT NULL,
+                origin TEXT NOT NULL,
+                old_value TEXT,
+                new_value TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_provider_audit_log_app
+             ON provider_audit_log(app_type, provider_id, created_at)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建外部修改冲突记录表
+    ///
+    /// `snapshot` 为被覆盖前的外部修改内容（仅 `versions` 策略）；能对应到单个提示词或 agent 时，
+    /// 快照同时写入其历史，`revision` 为对应的版本号。
+    fn create_external_edit_conflicts_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS external_edit_conflicts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                app TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                item_id TEXT NOT NULL,
+                path TEXT NOT NULL,
+                policy TEXT NOT NULL,
+                snapshot TEXT,
+                revision INTEGER,
+                detected_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建 Skill 文件校验记录表
+    ///
+    /// `files` 为相对路径 -> { sha256, size } 的 JSON 对象；`source_sha` 为安装时下载的提交。
+    fn create_skill_integrity_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS skill_integrity (
+                skill_id TEXT PRIMARY KEY,
+                source_sha TEXT,
+                files TEXT NOT NULL DEFAULT '{}',
+                recorded_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 创建 agent 全文索引（FTS5 外部内容表，覆盖 name / description / content）
+    ///
+    /// 使用 trigram 分词以支持中文与任意子串匹配；通过触发器与 agent_definitions 保持同步。
+    /// 索引表本身不随 SQL 导出，首次创建时按现有数据重建。
+    fn create_agent_search_index(conn: &Connection) -> Result<(), AppError> {
+        let existed = Self::table_exists(conn, AGENT_FTS_TABLE)?;
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS agent_definitions_fts USING fts5(
+                name, description, content,
+                content='agent_definitions',
+                content_rowid='rowid',
+                tokenize='trigram'
+            );
+            CREATE TRIGGER IF NOT EXISTS agent_definitions_fts_ai
+            AFTER INSERT ON agent_definitions BEGIN
+                INSERT INTO agent_definitions_fts(rowid, name, description, content)
+                VALUES (new.rowid, new.name, new.description, new.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS agent_definitions_fts_ad
+            AFTER DELETE ON agent_definitions BEGIN
+                INSERT INTO agent_definitions_fts(agent_definitions_fts, rowid, name, description, content)
+                VALUES ('delete', old.rowid, old.name, old.description, old.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS agent_definitions_fts_au
+            AFTER UPDATE OF name, description, content ON agent_definitions BEGIN
+                INSERT INTO agent_definitions_fts(agent_definitions_fts, rowid, name, description, content)
+                VALUES ('delete', old.rowid, old.name, old.description, old.content);
+                INSERT INTO agent_definitions_fts(rowid, name, description, content)
+                VALUES (new.rowid, new.name, new.description, new.content);
+            END;",
+        )
+        .map_err(|e| AppError::Database(format!("创建 agent 全文索引失败: {e}")))?;
+
+        if !existed {
+            conn.execute(
+                "INSERT INTO agent_definitions_fts(agent_definitions_fts) VALUES ('rebuild')",
+                [],
+            )
+            .map_err(|e| AppError::Database(format!("重建 agent 全文索引失败: {e}")))?;
+        }
+        Ok(())
+    }
+
+    /// 创建提示词全文索引（FTS5 外部内容表，覆盖 name / description / content）
+    ///
+    /// 与 agent 索引相同使用 trigram 分词并由触发器同步，首次创建时按现有提示词重建。
+    fn create_prompt_search_index(conn: &Connection) -> Result<(), AppError> {
+        let existed = Self::table_exists(conn, PROMPT_FTS_TABLE)?;
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS prompts_fts USING fts5(
+                name, description, content,
+                content='prompts',
+                content_rowid='rowid',
+                tokenize='trigram'
+            );
+            CREATE TRIGGER IF NOT EXISTS prompts_fts_ai
+            AFTER INSERT ON prompts BEGIN
+                INSERT INTO prompts_fts(rowid, name, description, content)
+                VALUES (new.rowid, new.name, new.description, new.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS prompts_fts_ad
+            AFTER DELETE ON prompts BEGIN
+                INSERT INTO prompts_fts(prompts_fts, rowid, name, description, content)
+                VALUES ('delete', old.rowid, old.name, old.description, old.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS prompts_fts_au
+            AFTER UPDATE OF name, description, content ON prompts BEGIN
+                INSERT INTO prompts_fts(prompts_fts, rowid, name, description, content)
+                VALUES ('delete', old.rowid, old.name, old.description, old.content);
+                INSERT INTO prompts_fts(rowid, name, description, content)
+                VALUES (new.rowid, new.name, new.description, new.content);
+            END;",
+        )
+        .map_err(|e| AppError::Database(format!("创建提示词全文索引失败: {e}")))?;
+
+        if !existed {
+            conn.execute(
+                "INSERT INTO prompts_fts(prompts_fts) VALUES ('rebuild')",
+                [],
+            )
+            .map_err(|e| AppError::Database(format!("重建提示词全文索引失败: {e}")))?;
+        }
+        Ok(())
+    }
+
     /// 插入默认模型定价数据
     /// 格式: (model_id, display_name, input, output, cache_read, cache_creation)
     /// 注意: model_id 使用短横线格式（如 claude-haiku-4-5），与 API 返回的模型名称标准化后一致
@@ -182,6 +182,39 @@ fn schema_migration_rejects_future_version() {
     );
 }
 
+#[test]
+fn schema_migration_v40_keeps_queued_providers_usable_by_failover() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    for (id, queued, meta) in [
+        ("queued", 1, "{}"),
+        ("declined", 1, r#"{"automationAllowed":false}"#),
+        ("idle", 0, "{}"),
+    ] {
+        conn.execute(
+            "INSERT INTO providers (id, app_type, name, settings_config, meta, in_failover_queue)
+             VALUES (?1, 'claude', ?1, '{}', ?2, ?3)",
+            params![id, meta, queued],
+        )
+        .expect("seed provider");
+    }
+    Database::set_user_version(&conn, 39).expect("set user_version=39");
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+
+    let allowed = |id: &str| -> Option<bool> {
+        let meta: String = conn
+            .query_row("SELECT meta FROM providers WHERE id = ?1", [id], |row| {
+                row.get(0)
+            })
+            .expect("read meta");
+        serde_json::from_str::<serde_json::Value>(&meta).expect("meta json")["automationAllowed"]
+            .as_bool()
+    };
+    assert_eq!(allowed("queued"), Some(true));
+    assert_eq!(allowed("declined"), Some(false));
+    assert_eq!(allowed("idle"), None);
+}
+
 #[test]
 fn schema_migration_adds_missing_columns_for_providers() {
     let conn = Connection::open_in_memory().expect("open memory db");
@@ -199,6 +232,7 @@ fn schema_migration_adds_missing_columns_for_providers() {
         ("provider_endpoints", "added_at"),
         ("mcp_servers", "enabled_gemini"),
         ("prompts", "updated_at"),
+        ("prompts", "sort_index"),
         ("skills", "installed_at"),
         ("skill_repos", "enabled"),
     ] {
@@ -241,10 +275,11 @@ fn schema_migration_aligns_column_defaults_and_types() {
     assert_eq!(tags.notnull, 1);
     assert_eq!(normalize_default(&tags.default).as_deref(), Some("[]"));
 
-    let enabled = get_column_info(&conn, "prompts", "enabled");
+    // v6 -> v7 之后启用状态按应用分列存储
+    let enabled = get_column_info(&conn, "prompts", "claude_enabled");
     assert_eq!(enabled.r#type, "BOOLEAN");
     assert_eq!(enabled.notnull, 1);
-    assert_eq!(normalize_default(&enabled.default).as_deref(), Some("1"));
+    assert_eq!(normalize_default(&enabled.default).as_deref(), Some("0"));
 
     let installed_at = get_column_info(&conn, "skills", "installed_at");
     assert_eq!(installed_at.r#type, "INTEGER");
@@ -510,6 +545,192 @@ fn migration_from_v3_8_schema_v1_to_current_schema_v3() {
             .expect("check agent_definitions column"),
         "agent_definitions.enabled_claude should exist"
     );
+
+    // v7 -> v8：agent_definitions 新增 OpenClaw 启用列
+    assert!(
+        Database::has_column(&conn, "agent_definitions", "enabled_openclaw")
+            .expect("check agent_definitions column"),
+        "agent_definitions.enabled_openclaw should exist"
+    );
+
+    // v8 -> v9：agent_definitions 新增扩展 frontmatter 字段
+    for column in ["tools", "model", "color"] {
+        assert!(
+            Database::has_column(&conn, "agent_definitions", column).expect("check column"),
+            "agent_definitions.{column} should exist after migration"
+        );
+    }
+
+    // v9 -> v10：agent 历史版本表
+    assert!(
+        Database::table_exists(&conn, "agent_definition_history")
+            .expect("check agent_definition_history table"),
+        "agent_definition_history table should exist after v9->v10 migration"
+    );
+
+    // v10 -> v11：供应商凭据问题表
+    assert!(
+        Database::table_exists(&conn, "provider_credential_issues")
+            .expect("check provider_credential_issues table"),
+        "provider_credential_issues table should exist after v10->v11 migration"
+    );
+
+    // v11 -> v12：agent 排序与分组
+    for column in ["sort_index", "group_name"] {
+        assert!(
+            Database::has_column(&conn, "agent_definitions", column).expect("check column"),
+            "agent_definitions.{column} should exist after migration"
+        );
+    }
+
+    // v12 -> v13：远程数据缓存表
+    assert!(
+        Database::table_exists(&conn, "remote_cache").expect("check remote_cache table"),
+        "remote_cache table should exist after v12->v13 migration"
+    );
+
+    // v13 -> v14：MCP 供应商绑定表
+    assert!(
+        Database::table_exists(&conn, "mcp_provider_bindings")
+            .expect("check mcp_provider_bindings table"),
+        "mcp_provider_bindings table should exist after v13->v14 migration"
+    );
+
+    // v14 -> v15：Agent 全文索引
+    assert!(
+        Database::table_exists(&conn, "agent_definitions_fts")
+            .expect("check agent_definitions_fts table"),
+        "agent_definitions_fts should exist after v14->v15 migration"
+    );
+
+    // v15 -> v16：Agent 标签
+    assert!(
+        Database::has_column(&conn, "agent_definitions", "tags").expect("check tags column"),
+        "agent_definitions.tags should exist after v15->v16 migration"
+    );
+
+    // v17 -> v18：订阅源
+    for table in ["feed_subscriptions", "feed_items"] {
+        assert!(
+            Database::table_exists(&conn, table).expect("check feed table"),
+            "{table} should exist after v17->v18 migration"
+        );
+    }
+
+    // v18 -> v19：项目级提示词
+    for table in ["prompt_projects", "prompt_project_links"] {
+        assert!(
+            Database::table_exists(&conn, table).expect("check prompt project table"),
+            "{table} should exist after v18->v19 migration"
+        );
+    }
+
+    // v19 -> v20：提示词全文索引
+    assert!(
+        Database::table_exists(&conn, "prompts_fts").expect("check prompts_fts table"),
+        "prompts_fts should exist after v19->v20 migration"
+    );
+
+    // v20 -> v21：提示词回收站
+    assert!(
+        Database::table_exists(&conn, "prompt_trash").expect("check prompt_trash table"),
+        "prompt_trash should exist after v20->v21 migration"
+    );
+
+    // v21 -> v22：定时切换提示词
+    assert!(
+        Database::table_exists(&conn, "prompt_schedules").expect("check prompt_schedules table"),
+        "prompt_schedules should exist after v21->v22 migration"
+    );
+
+    // v22 -> v23：本地 API 访问令牌
+    assert!(
+        Database::table_exists(&conn, "api_tokens").expect("check api_tokens table"),
+        "api_tokens should exist after v22->v23 migration"
+    );
+
+    // v23 -> v24：OpenClaw 提示词启用列
+    assert!(
+        Database::has_column(&conn, "prompts", "openclaw_enabled")
+            .expect("check openclaw_enabled column"),
+        "prompts.openclaw_enabled should exist after v23->v24 migration"
+    );
+
+    // v24 -> v25：端点使用与测速记录
+    assert!(
+        Database::has_column(&conn, "provider_endpoints", "consecutive_failures")
+            .expect("check consecutive_failures column"),
+        "provider_endpoints.consecutive_failures should exist after v24->v25 migration"
+    );
+
+    // v25 -> v26：MCP 服务器来源
+    assert!(
+        Database::has_column(&conn, "mcp_servers", "origin").expect("check origin column"),
+        "mcp_servers.origin should exist after v25->v26 migration"
+    );
+
+    // v26 -> v27：供应商延迟测速记录
+    assert!(
+        Database::table_exists(&conn, "provider_benchmarks")
+            .expect("check provider_benchmarks table"),
+        "provider_benchmarks should exist after v26->v27 migration"
+    );
+
+    // v27 -> v28：供应商组合
+    assert!(
+        Database::table_exists(&conn, "provider_profiles").expect("check provider_profiles table"),
+        "provider_profiles should exist after v27->v28 migration"
+    );
+
+    // v28 -> v29：Skill 文件校验记录
+    assert!(
+        Database::table_exists(&conn, "skill_integrity").expect("check skill_integrity table"),
+        "skill_integrity should exist after v28->v29 migration"
+    );
+    assert!(
+        Database::has_column(&conn, "providers", "archived").expect("check archived column"),
+        "providers.archived should exist after v29->v30 migration"
+    );
+    assert!(
+        Database::table_exists(&conn, "provider_attachments")
+            .expect("check provider_attachments table"),
+        "provider_attachments should exist after v30->v31 migration"
+    );
+    assert!(
+        Database::has_column(&conn, "providers", "deleted_at").expect("check deleted_at column"),
+        "providers.deleted_at should exist after v31->v32 migration"
+    );
+    assert!(
+        Database::has_column(&conn, "providers", "tags").expect("check tags column"),
+        "providers.tags should exist after v32->v33 migration"
+    );
+    assert!(
+        Database::has_column(&conn, "provider_endpoints", "last_latency_ms")
+            .expect("check last_latency_ms column"),
+        "provider_endpoints.last_latency_ms should exist after v33->v34 migration"
+    );
+    assert!(
+        Database::table_exists(&conn, "provider_audit_log")
+            .expect("check provider_audit_log table"),
+        "provider_audit_log should exist after v34->v35 migration"
+    );
+    assert!(
+        Database::table_exists(&conn, "prompt_history").expect("check prompt_history table"),
+        "prompt_history should exist after v35->v36 migration"
+    );
+    assert!(
+        Database::has_column(&conn, "feed_items", "applied").expect("check applied column"),
+        "feed_items.applied should exist after v36->v37 migration"
+    );
+    assert!(
+        Database::table_exists(&conn, "external_edit_conflicts")
+            .expect("check external_edit_conflicts table"),
+        "external_edit_conflicts should exist after v37->v38 migration"
+    );
+    assert!(
+        Database::has_column(&conn, "prompts", "tags").expect("check prompt tags column"),
+        "prompts.tags should exist after v38->v39 migration"
+    );
 }
 
 #[test]
@@ -555,6 +776,8 @@ fn dry_run_validates_schema_compatibility() {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         },
     );
 
@@ -640,3 +863,607 @@ fn schema_model_pricing_is_seeded_on_init() {
         gemini_count
     );
 }
+
+#[test]
+fn save_agent_records_history_revisions() {
+    let db = Database::memory().expect("create memory db");
+    let mut agent = crate::agent::AgentDefinition {
+        id: "reviewer".to_string(),
+        name: "Reviewer".to_string(),
+        content: "v1".to_string(),
+        ..Default::default()
+    };
+    db.save_agent(&agent).expect("save v1");
+    agent.content = "v2".to_string();
+    db.save_agent(&agent).expect("save v2");
+
+    let history = db.get_agent_history("reviewer").expect("load history");
+    assert_eq!(
+        history.iter().map(|r| r.version).collect::<Vec<_>>(),
+        vec![2, 1]
+    );
+    let first = db
+        .get_agent_revision("reviewer", 1)
+        .expect("load revision")
+        .expect("revision exists");
+    assert_eq!(first.snapshot.content, "v1");
+
+    db.delete_agent("reviewer").expect("delete agent");
+    assert!(db
+        .get_agent_history("reviewer")
+        .expect("load history")
+        .is_empty());
+}
+
+#[test]
+fn agent_tags_roundtrip_as_json_array() {
+    let db = Database::memory().expect("create memory db");
+    db.save_agent(&crate::agent::AgentDefinition {
+        id: "reviewer".to_string(),
+        name: "Reviewer".to_string(),
+        content: "Review".to_string(),
+        tags: vec!["frontend".to_string(), "infra".to_string()],
+        ..Default::default()
+    })
+    .expect("save tagged agent");
+    db.save_agent(&crate::agent::AgentDefinition {
+        id: "writer".to_string(),
+        name: "Writer".to_string(),
+        content: "Write".to_string(),
+        ..Default::default()
+    })
+    .expect("save untagged agent");
+
+    let all = db.get_all_agents().expect("load agents");
+    assert_eq!(all["reviewer"].tags, vec!["frontend", "infra"]);
+    assert!(all["writer"].tags.is_empty());
+
+    let conn = db.conn.lock().expect("lock conn");
+    let raw: String = conn
+        .query_row(
+            "SELECT tags FROM agent_definitions WHERE id = 'writer'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("read raw tags");
+    assert_eq!(raw, "[]");
+}
+
+#[test]
+fn revert_agent_saves_restores_row_and_drops_revision() {
+    let db = Database::memory().expect("create memory db");
+    let v1 = crate::agent::AgentDefinition {
+        id: "reviewer".to_string(),
+        name: "Reviewer".to_string(),
+        content: "v1".to_string(),
+        ..Default::default()
+    };
+    db.save_agent(&v1).expect("save v1");
+    let v1 = db
+        .get_agent_by_id("reviewer")
+        .expect("load v1")
+        .expect("v1 exists");
+    let v2 = crate::agent::AgentDefinition {
+        content: "v2".to_string(),
+        ..v1.clone()
+    };
+    db.save_agent(&v2).expect("save v2");
+    let fresh = crate::agent::AgentDefinition {
+        id: "writer".to_string(),
+        name: "Writer".to_string(),
+        content: "draft".to_string(),
+        ..Default::default()
+    };
+    db.save_agent(&fresh).expect("save new agent");
+
+    db.revert_agent_saves(&[("reviewer", Some(&v1)), ("writer", None)])
+        .expect("revert saves");
+
+    let restored = db
+        .get_agent_by_id("reviewer")
+        .expect("load reviewer")
+        .expect("reviewer kept");
+    assert_eq!(restored.content, "v1");
+    assert_eq!(db.get_agent_history("reviewer").expect("history").len(), 1);
+    assert!(db.get_agent_by_id("writer").expect("load writer").is_none());
+    assert!(db.get_agent_history("writer").expect("history").is_empty());
+}
+
+#[test]
+fn search_agents_tracks_updates_and_deletes() {
+    let db = Database::memory().expect("create memory db");
+    let make = |id: &str, name: &str, description: Option<&str>, content: &str| {
+        crate::agent::AgentDefinition {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.map(str::to_string),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    };
+    let mut reviewer = make("reviewer", "Code Reviewer", None, "检查代码差异");
+    db.save_agent(&reviewer).expect("save reviewer");
+    db.save_agent(&make(
+        "writer",
+        "Doc Writer",
+        Some("writes 100% docs"),
+        "Draft",
+    ))
+    .expect("save writer");
+
+    let ids = |query: &str| {
+        db.search_agents(query)
+            .expect("search agents")
+            .into_iter()
+            .map(|a| a.id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids("review"), vec!["reviewer"]);
+    assert_eq!(ids("REVIEWER"), vec!["reviewer"]);
+    assert_eq!(ids("代码差异"), vec!["reviewer"]);
+    assert_eq!(ids("doc draft"), vec!["writer"]);
+    // 短关键词退回 LIKE，通配符按字面匹配
+    assert_eq!(ids("0%"), vec!["writer"]);
+    assert_eq!(ids("  "), vec!["reviewer", "writer"]);
+
+    reviewer.content = "Look for regressions".to_string();
+    db.save_agent(&reviewer).expect("update reviewer");
+    assert!(ids("代码差异").is_empty());
+    assert_eq!(ids("regression"), vec!["reviewer"]);
+
+    db.delete_agent("reviewer").expect("delete reviewer");
+    assert!(ids("regression").is_empty());
+}
+
+#[test]
+fn search_prompts_follows_saves_and_deletes() {
+    let db = Database::memory().expect("create memory db");
+    let make = |id: &str, name: &str, content: &str| crate::prompt::Prompt {
+        id: id.to_string(),
+        name: name.to_string(),
+        content: content.to_string(),
+        description: None,
+        tags: Vec::new(),
+        apps: Default::default(),
+        sort_index: None,
+        created_at: None,
+        updated_at: None,
+    };
+    let mut rules = make("rules", "Team Rules", "所有提交必须附带测试");
+    db.save_prompt(&rules).expect("save rules");
+    db.save_prompt(&make("style", "Style Guide", "Use rustfmt"))
+        .expect("save style");
+
+    let ids = |query: &str| {
+        db.search_prompts(query)
+            .expect("search prompts")
+            .into_iter()
+            .map(|p| p.id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids("RULES"), vec!["rules"]);
+    assert_eq!(ids("附带测试"), vec!["rules"]);
+    assert_eq!(ids("guide rustfmt"), vec!["style"]);
+    assert_eq!(ids("Us"), vec!["style"]);
+    assert_eq!(ids(""), vec!["rules", "style"]);
+
+    // 覆盖保存后旧内容不再命中（保存走 UPSERT，索引同步更新）
+    rules.content = "Squash before merging".to_string();
+    db.save_prompt(&rules).expect("update rules");
+    assert!(ids("附带测试").is_empty());
+    assert_eq!(ids("squash"), vec!["rules"]);
+
+    db.delete_prompt("rules").expect("delete rules");
+    assert!(ids("squash").is_empty());
+}
+
+#[test]
+fn credential_issue_round_trip_and_cleared_with_provider() {
+    use crate::services::provider::{CredentialIssue, CredentialIssueKind, IssueSource};
+
+    let db = Database::memory().expect("create memory db");
+    let issue = CredentialIssue {
+        provider_id: "p1".to_string(),
+        app_type: "claude".to_string(),
+        kind: CredentialIssueKind::Expired,
+        http_status: 401,
+        message: "token expired".to_string(),
+        source: IssueSource::Proxy,
+        detected_at: 1_700_000_000,
+    };
+    db.save_credential_issue(&issue).expect("save issue");
+
+    let loaded = db
+        .get_credential_issue("p1", "claude")
+        .expect("load issue")
+        .expect("issue exists");
+    assert_eq!(loaded.kind, CredentialIssueKind::Expired);
+    assert_eq!(loaded.source, IssueSource::Proxy);
+    assert!(db
+        .get_credential_issue("p1", "codex")
+        .expect("load other app")
+        .is_none());
+    assert!(db
+        .has_credential_issue("p1", "claude")
+        .expect("check issue"));
+    assert!(!db
+        .has_credential_issue("p1", "codex")
+        .expect("check other app"));
+
+    db.delete_provider("claude", "p1").expect("delete provider");
+    assert!(db
+        .get_credential_issue("p1", "claude")
+        .expect("load after delete")
+        .is_none());
+}
+
+#[test]
+fn reorder_prompts_updates_listing_order() {
+    let db = Database::memory().expect("create memory db");
+    let make = |id: &str| crate::prompt::Prompt {
+        id: id.to_string(),
+        name: id.to_string(),
+        content: "body".to_string(),
+        description: None,
+        tags: Vec::new(),
+        apps: Default::default(),
+        sort_index: None,
+        created_at: None,
+        updated_at: None,
+    };
+    for id in ["a", "b", "c"] {
+        db.save_prompt(&make(id)).expect("save prompt");
+    }
+    let ids = |db: &Database| {
+        db.get_prompts()
+            .expect("load")
+            .into_keys()
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(&db), vec!["a", "b", "c"]);
+
+    db.reorder_prompts(&["c".to_string(), "a".to_string()])
+        .expect("reorder");
+    assert_eq!(ids(&db), vec!["c", "a", "b"]);
+
+    // 编辑后重新保存不改变位置，新提示词排在最后
+    let a = db
+        .get_prompts()
+        .expect("load")
+        .shift_remove("a")
+        .expect("a exists");
+    db.save_prompt(&crate::prompt::Prompt {
+        sort_index: None,
+        content: "edited".to_string(),
+        ..a
+    })
+    .expect("resave a");
+    db.save_prompt(&make("d")).expect("save d");
+    assert_eq!(ids(&db), vec!["c", "a", "b", "d"]);
+}
+
+#[test]
+fn prompts_for_app_only_include_enabled_ones_in_priority_order() {
+    let db = Database::memory().expect("create memory db");
+    let make = |id: &str, claude: bool, codex: bool| crate::prompt::Prompt {
+        id: id.to_string(),
+        name: id.to_string(),
+        content: "body".to_string(),
+        description: None,
+        tags: Vec::new(),
+        apps: crate::prompt::PromptApps {
+            claude,
+            codex,
+            ..Default::default()
+        },
+        sort_index: None,
+        created_at: None,
+        updated_at: None,
+    };
+    db.save_prompt(&make("a", true, false)).expect("save a");
+    db.save_prompt(&make("b", false, false)).expect("save b");
+    db.save_prompt(&make("c", true, true)).expect("save c");
+    db.reorder_prompts(&["c".to_string()]).expect("reorder");
+
+    let claude = db.get_prompts_for_app("claude_enabled").expect("claude");
+    assert_eq!(claude.keys().collect::<Vec<_>>(), vec!["c", "a"]);
+    assert_eq!(
+        db.get_enabled_prompt("claude_enabled")
+            .expect("enabled")
+            .map(|p| p.id),
+        Some("c".to_string())
+    );
+    assert!(db
+        .get_enabled_prompt("gemini_enabled")
+        .expect("gemini")
+        .is_none());
+    assert!(db.get_prompts_for_app("id = id OR 1").is_err());
+}
+
+#[test]
+fn provider_benchmarks_keep_recent_runs_per_provider() {
+    use crate::services::provider::ProviderBenchmark;
+
+    let db = Database::memory().expect("create memory db");
+    let run = |provider_id: &str, first_byte_ms: u64, measured_at: i64| ProviderBenchmark {
+        provider_id: provider_id.to_string(),
+        provider_name: provider_id.to_string(),
+        url: "https://api.example.com".to_string(),
+        connect_ms: Some(10),
+        tls_ms: None,
+        first_byte_ms: Some(first_byte_ms),
+        http_status: Some(404),
+        error: None,
+        measured_at,
+    };
+    for i in 0..3 {
+        db.save_provider_benchmarks("claude", &[run("a", 100 + i, i as i64)], 2)
+            .expect("save benchmarks");
+    }
+    db.save_provider_benchmarks("claude", &[run("b", 50, 9)], 2)
+        .expect("save benchmarks");
+
+    let mut latest = db
+        .get_latest_provider_benchmarks("claude")
+        .expect("latest benchmarks");
+    latest.sort_by(|x, y| x.provider_id.cmp(&y.provider_id));
+    assert_eq!(latest.len(), 2);
+    assert_eq!(latest[0].first_byte_ms, Some(102));
+    assert_eq!(latest[1].http_status, Some(404));
+
+    let conn = db.conn.lock().expect("lock conn");
+    let kept: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM provider_benchmarks WHERE provider_id = 'a'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("count rows");
+    assert_eq!(kept, 2);
+}
+
+#[test]
+fn providers_filter_by_tag_case_insensitively() {
+    let db = Database::memory().expect("create memory db");
+    for (id, tags) in [
+        ("a", vec!["Relay", " cn ", "relay", ""]),
+        ("b", vec!["official"]),
+        ("c", vec!["CN"]),
+    ] {
+        let mut provider = Provider::with_id(id.to_string(), id.to_string(), json!({}), None);
+        provider.tags = tags.into_iter().map(String::from).collect();
+        db.save_provider("claude", &provider)
+            .expect("save provider");
+    }
+
+    let a = db.get_provider_by_id("a", "claude").unwrap().unwrap();
+    assert_eq!(a.tags, ["Relay", "cn"]);
+
+    let cn = db
+        .get_providers_by_tag("claude", "cn")
+        .expect("filter by tag");
+    assert_eq!(cn.keys().collect::<Vec<_>>(), ["a", "c"]);
+    assert!(db
+        .get_providers_by_tag("claude", "none")
+        .unwrap()
+        .is_empty());
+
+    let tags = db.get_provider_tags("claude").expect("list tags");
+    assert_eq!(
+        tags,
+        [
+            ("CN".to_string(), 2),
+            ("official".to_string(), 1),
+            ("Relay".to_string(), 1)
+        ]
+    );
+}
+
+#[test]
+fn provider_metrics_join_checks_benchmarks_and_request_logs() {
+    let db = Database::memory().expect("create memory db");
+    for id in ["a", "b", "c"] {
+        let provider = Provider::with_id(id.to_string(), id.to_string(), json!({}), None);
+        db.save_provider("claude", &provider)
+            .expect("save provider");
+    }
+    {
+        let conn = db.conn.lock().expect("lock conn");
+        conn.execute_batch(
+            "INSERT INTO stream_check_logs
+                 (provider_id, provider_name, app_type, status, success, message, response_time_ms, tested_at)
+             VALUES ('a', 'a', 'claude', 'operational', 1, 'ok', 800, 1),
+                    ('a', 'a', 'claude', 'failed', 0, 'err', NULL, 2),
+                    ('a', 'a', 'claude', 'operational', 1, 'ok', 400, 3),
+                    ('a', 'a', 'codex', 'operational', 1, 'ok', 10, 4);
+             INSERT INTO provider_benchmarks (app_type, provider_id, provider_name, url, first_byte_ms, measured_at)
+             VALUES ('claude', 'a', 'a', 'https://a', 50, 1),
+                    ('claude', 'b', 'b', 'https://b', 250, 1);
+             INSERT INTO proxy_request_logs
+                 (request_id, provider_id, app_type, model, latency_ms, status_code, created_at)
+             VALUES ('r1', 'b', 'claude', 'm', 100, 200, 10),
+                    ('r2', 'b', 'claude', 'm', 100, 200, 20);",
+        )
+        .expect("seed metrics");
+    }
+
+    let metrics = db.get_provider_metrics("claude", 2).expect("metrics");
+    assert_eq!(metrics.len(), 2);
+    assert_eq!(metrics["a"].latency_ms, Some(400));
+    assert_eq!(metrics["a"].success_rate, Some(0.5));
+    assert_eq!(metrics["a"].last_used_at, None);
+    assert_eq!(metrics["b"].latency_ms, Some(250));
+    assert_eq!(metrics["b"].success_rate, None);
+    assert_eq!(metrics["b"].last_used_at, Some(20));
+    assert!(!metrics.contains_key("c"));
+}
+
+#[test]
+fn endpoint_benchmarks_track_consecutive_failures() {
+    let db = Database::memory().expect("create memory db");
+    let mut provider = Provider::with_id("relay".to_string(), "Relay".to_string(), json!({}), None);
+    provider.meta = Some(Default::default());
+    db.save_provider("claude", &provider)
+        .expect("save provider");
+    for url in ["https://a.example.com", "https://b.example.com"] {
+        db.add_custom_endpoint("claude", "relay", url)
+            .expect("add endpoint");
+    }
+
+    let record = |url: &str, success: bool| {
+        assert!(db
+            .record_endpoint_benchmark("claude", "relay", url, success.then_some(120), 1)
+            .expect("record benchmark"));
+    };
+    for _ in 0..3 {
+        record("https://a.example.com", false);
+    }
+    record("https://b.example.com", false);
+    record("https://b.example.com", true);
+
+    let stale = db
+        .get_stale_endpoints("claude", Some("relay"), 3)
+        .expect("stale endpoints");
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0].url, "https://a.example.com");
+    assert_eq!(stale[0].consecutive_failures, 3);
+
+    // 记录随供应商一起读出，重新保存供应商不会丢失
+    assert!(db
+        .touch_custom_endpoint("claude", "relay", "https://b.example.com", 42)
+        .expect("touch endpoint"));
+    let loaded = db.get_all_providers("claude").expect("load providers")["relay"].clone();
+    db.save_provider("claude", &loaded)
+        .expect("resave provider");
+    let endpoints = db.get_all_providers("claude").expect("reload")["relay"]
+        .meta
+        .clone()
+        .expect("meta")
+        .custom_endpoints;
+    assert_eq!(endpoints["https://a.example.com"].consecutive_failures, 3);
+    assert_eq!(endpoints["https://b.example.com"].last_used, Some(42));
+    assert_eq!(endpoints["https://b.example.com"].last_benchmarked, Some(1));
+    assert_eq!(
+        endpoints["https://b.example.com"].last_latency_ms,
+        Some(120)
+    );
+    assert_eq!(endpoints["https://a.example.com"].last_latency_ms, None);
+}
+
+#[test]
+fn reorder_agents_updates_listing_order() {
+    let db = Database::memory().expect("create memory db");
+    for id in ["a", "b", "c"] {
+        db.save_agent(&crate::agent::AgentDefinition {
+            id: id.to_string(),
+            name: id.to_string(),
+            content: "body".to_string(),
+            ..Default::default()
+        })
+        .expect("save agent");
+    }
+    let ids = |db: &Database| {
+        db.get_all_agents()
+            .expect("load")
+            .into_keys()
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(&db), vec!["a", "b", "c"]);
+
+    db.reorder_agents(&["c".to_string(), "a".to_string()])
+        .expect("reorder");
+    assert_eq!(ids(&db), vec!["c", "a", "b"]);
+
+    // 重新保存（sort_index 为空）不改变位置
+    let a = db.get_agent_by_id("a").expect("load a").expect("a exists");
+    db.save_agent(&crate::agent::AgentDefinition {
+        sort_index: None,
+        ..a
+    })
+    .expect("resave a");
+    assert_eq!(ids(&db), vec!["c", "a", "b"]);
+}
+
+#[test]
+fn save_agents_writes_batch_with_history() {
+    let db = Database::memory().expect("create memory db");
+    let agents: Vec<_> = ["x", "y", "z"]
+        .into_iter()
+        .map(|id| crate::agent::AgentDefinition {
+            id: id.to_string(),
+            name: id.to_string(),
+            content: "body".to_string(),
+            ..Default::default()
+        })
+        .collect();
+    db.save_agents(&agents).expect("save batch");
+
+    let all = db.get_all_agents().expect("load");
+    assert_eq!(all.keys().collect::<Vec<_>>(), vec!["x", "y", "z"]);
+    let indices: Vec<_> = all.values().map(|a| a.sort_index).collect();
+    assert_eq!(indices, vec![Some(0), Some(1), Some(2)]);
+    for id in ["x", "y", "z"] {
+        assert_eq!(db.get_agent_history(id).expect("history").len(), 1);
+    }
+}
+
+#[test]
+fn feed_items_keep_accepted_hash_and_drop_removed_entries() {
+    use crate::services::feed::{FeedItemKind, FeedItemRecord, FeedSubscription};
+
+    let db = Database::memory().expect("create memory db");
+    db.save_feed_subscription(&FeedSubscription {
+        id: "team".to_string(),
+        url: "https://example.com/feed.json".to_string(),
+        name: "Team".to_string(),
+        enabled: true,
+        last_checked_at: None,
+        last_error: None,
+        created_at: 1,
+    })
+    .expect("save subscription");
+
+    let item = |id: &str, hash: &str| FeedItemRecord {
+        subscription_id: "team".to_string(),
+        kind: FeedItemKind::Agent,
+        item_id: id.to_string(),
+        name: id.to_string(),
+        payload: "{}".to_string(),
+        content_hash: hash.to_string(),
+        accepted_hash: None,
+        applied: false,
+        fetched_at: 1,
+    };
+
+    db.replace_feed_items("team", &[item("reviewer", "h1"), item("writer", "h1")])
+        .expect("first fetch");
+    db.set_feed_item_accepted("team", FeedItemKind::Agent, "reviewer", "h1", true)
+        .expect("accept reviewer");
+
+    // 再次拉取：reviewer 未变、writer 被移除
+    db.replace_feed_items("team", &[item("reviewer", "h1")])
+        .expect("second fetch");
+    let items = db.get_feed_items(Some("team")).expect("load items");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].accepted_hash.as_deref(), Some("h1"));
+    assert!(items[0].applied);
+
+    // 之后忽略新版本不会清除“已写入本地”的标记
+    db.replace_feed_items("team", &[item("reviewer", "h2")])
+        .expect("third fetch");
+    db.set_feed_item_accepted("team", FeedItemKind::Agent, "reviewer", "h2", false)
+        .expect("dismiss reviewer");
+    let items = db.get_feed_items(Some("team")).expect("load items");
+    assert_eq!(items[0].accepted_hash.as_deref(), Some("h2"));
+    assert!(items[0].applied);
+
+    // 更新订阅本身不应清空条目
+    let mut sub = db.get_feed_subscription("team").unwrap().unwrap();
+    sub.last_checked_at = Some(2);
+    db.save_feed_subscription(&sub)
+        .expect("update subscription");
+    assert_eq!(db.get_feed_items(None).unwrap().len(), 1);
+
+    assert!(db.delete_feed_subscription("team").unwrap());
+    assert!(db.get_feed_items(None).unwrap().is_empty());
+}
@@ -122,6 +122,7 @@ pub fn import_mcp_from_deeplink(
                 homepage: existing.homepage.clone(),
                 docs: existing.docs.clone(),
                 tags: existing.tags.clone(),
+                origin: existing.origin.clone(),
             }
         } else {
             // New server - create with provided config
@@ -135,6 +136,7 @@ pub fn import_mcp_from_deeplink(
                 homepage: None,
                 docs: None,
                 tags: vec!["imported".to_string()],
+                origin: None,
             }
         };
 
@@ -6,6 +6,7 @@
 //! - MCP server configurations
 //! - Prompts
 //! - Skills
+//! - Encrypted share links (`ccswitch://v1/share`, see `services::share_link`)
 //!
 //! See docs/ccswitch-deeplink-design.md for detailed design.
 
@@ -21,6 +22,8 @@ mod tests;
 
 use serde::{Deserialize, Serialize};
 
+use crate::services::share_link::ShareRef;
+
 // Re-export public API
 pub use mcp::import_mcp_from_deeplink;
 pub use parser::parse_deeplink_url;
@@ -37,7 +40,7 @@ pub use skill::import_skill_from_deeplink;
 pub struct DeepLinkImportRequest {
     /// Protocol version (e.g., "v1")
     pub version: String,
-    /// Resource type to import: "provider" | "prompt" | "mcp" | "skill"
+    /// Resource type to import: "provider" | "prompt" | "mcp" | "skill" | "share"
     pub resource: String,
 
     // ============ Common fields ============
@@ -136,4 +139,9 @@ pub struct DeepLinkImportRequest {
     /// Auto query interval in minutes (0 to disable)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage_auto_interval: Option<u64>,
+
+    // ============ Share link fields ============
+    /// Relay location and decryption key of a shared prompt/agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share: Option<ShareRef>,
 }
@@ -5,6 +5,7 @@
 use super::utils::validate_url;
 use super::DeepLinkImportRequest;
 use crate::error::AppError;
+use crate::services::share_link::{validate_relay_url, ShareRef};
 use std::collections::HashMap;
 use url::Url;
 
@@ -38,17 +39,20 @@ pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, AppErr
         )));
     }
 
-    // Extract path (should be "/import")
+    // Parse query parameters
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+    // Extract path (should be "/import", or "/share" for encrypted share links)
     let path = url.path();
+    if path == "/share" {
+        return parse_share_deeplink(&params, version);
+    }
     if path != "/import" {
         return Err(AppError::InvalidInput(format!(
             "Invalid path: expected '/import', got '{path}'"
         )));
     }
 
-    // Parse query parameters
-    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
-
     // Extract and validate resource type
     let resource = params
         .get("resource")
@@ -170,6 +174,7 @@ fn parse_provider_deeplink(
         usage_access_token,
         usage_user_id,
         usage_auto_interval,
+        share: None,
     })
 }
 
@@ -237,6 +242,7 @@ fn parse_prompt_deeplink(
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        share: None,
     })
 }
 
@@ -299,6 +305,7 @@ fn parse_mcp_deeplink(
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        share: None,
     })
 }
 
@@ -354,5 +361,62 @@ fn parse_skill_deeplink(
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        share: None,
+    })
+}
+
+/// Parse share link parameters
+///
+/// Expected format:
+/// ccswitch://v1/share?relay={relay url}&id={share id}&key={base64url key}
+fn parse_share_deeplink(
+    params: &HashMap<String, String>,
+    version: String,
+) -> Result<DeepLinkImportRequest, AppError> {
+    let get = |name: &str| {
+        params
+            .get(name)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| {
+                AppError::InvalidInput(format!("Missing '{name}' parameter for share link"))
+            })
+    };
+    let relay = validate_relay_url(&get("relay")?)?;
+    let id = get("id")?;
+    let key = get("key")?;
+
+    Ok(DeepLinkImportRequest {
+        version,
+        resource: "share".to_string(),
+        app: None,
+        name: None,
+        enabled: None,
+        homepage: None,
+        endpoint: None,
+        api_key: None,
+        icon: None,
+        model: None,
+        notes: None,
+        haiku_model: None,
+        sonnet_model: None,
+        opus_model: None,
+        content: None,
+        description: None,
+        apps: None,
+        repo: None,
+        directory: None,
+        branch: None,
+        config: None,
+        config_format: None,
+        config_url: None,
+        usage_enabled: None,
+        usage_script: None,
+        usage_api_key: None,
+        usage_base_url: None,
+        usage_access_token: None,
+        usage_user_id: None,
+        usage_auto_interval: None,
+        share: Some(ShareRef { relay, id, key }),
     })
 }
@@ -6,6 +6,8 @@ use super::utils::decode_base64_param;
 use super::DeepLinkImportRequest;
 use crate::error::AppError;
 use crate::prompt::{Prompt, PromptApps};
+use crate::prompt_files::normalize_imported_prompt;
+use crate::services::id_generator::{IdGenerator, IdKind};
 use crate::services::PromptService;
 use crate::store::AppState;
 use crate::AppType;
@@ -47,29 +49,25 @@ pub fn import_prompt_from_deeplink(
     let content = decode_base64_param("content", content_b64)?;
     let content = String::from_utf8(content)
         .map_err(|e| AppError::InvalidInput(format!("Invalid UTF-8 in content: {e}")))?;
+    let content = normalize_imported_prompt(
+        &content,
+        &crate::settings::get_prompt_import_normalization(),
+    );
 
-    // Generate ID
+    // Generate a readable ID that does not collide with existing prompts
     let timestamp = chrono::Utc::now().timestamp_millis();
-    let sanitized_name = name
-        .chars()
-        .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
-        .collect::<String>()
-        .to_lowercase();
-    let id = format!("{sanitized_name}-{timestamp}");
+    let existing = state.db.get_prompts()?;
+    let id = IdGenerator::new(IdKind::DeeplinkPrompt)
+        .name(&name)
+        .app(&app_type)
+        .generate(|id| Ok(existing.contains_key(id)))?;
 
     // Check if we should enable this prompt for the given app
     let should_enable = request.enabled.unwrap_or(false);
 
     // Build apps flags (enabled only if should_enable)
     let mut apps = PromptApps::default();
-    if should_enable {
-        match app_type {
-            AppType::Claude => apps.claude = true,
-            AppType::Codex => apps.codex = true,
-            AppType::Gemini => apps.gemini = true,
-            AppType::OpenCode | AppType::OpenClaw => apps.opencode = true,
-        }
-    }
+    apps.set_enabled_for(&app_type, should_enable);
 
     // Create Prompt
     let prompt = Prompt {
@@ -77,7 +75,9 @@ pub fn import_prompt_from_deeplink(
         name: name.clone(),
         content,
         description: request.description,
+        tags: Vec::new(),
         apps,
+        sort_index: None,
         created_at: Some(timestamp),
         updated_at: Some(timestamp),
     };
@@ -90,7 +90,7 @@ pub fn import_prompt_from_deeplink(
             ..prompt.clone()
         };
         PromptService::upsert_prompt(state, prompt_for_save)?;
-        PromptService::toggle_prompt_app(state, &id, app_type, true)?;
+        PromptService::toggle_prompt_app(state, &id, app_type, true, true)?;
         log::info!("Successfully imported and enabled prompt '{name}' for {app_str}");
     } else {
         PromptService::upsert_prompt(state, prompt)?;
@@ -6,7 +6,8 @@ use super::utils::{decode_base64_param, infer_homepage_from_endpoint};
 use super::DeepLinkImportRequest;
 use crate::error::AppError;
 use crate::provider::{Provider, ProviderMeta, UsageScript};
-use crate::services::ProviderService;
+use crate::services::id_generator::{IdGenerator, IdKind};
+use crate::services::{ProviderAuditOrigin, ProviderService};
 use crate::store::AppState;
 use crate::AppType;
 use serde_json::json;
@@ -98,19 +99,26 @@ pub fn import_provider_from_deeplink(
     // Build provider configuration based on app type
     let mut provider = build_provider_from_request(&app_type, &merged_request)?;
 
-    // Generate a unique ID for the provider using timestamp + sanitized name
-    let timestamp = chrono::Utc::now().timestamp_millis();
-    let sanitized_name = name
-        .chars()
-        .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
-        .collect::<String>()
-        .to_lowercase();
-    provider.id = format!("{sanitized_name}-{timestamp}");
+    // Generate a readable ID that does not collide with existing providers of this app
+    provider.id = IdGenerator::new(IdKind::DeeplinkProvider)
+        .name(&name)
+        .app(&app_type)
+        .generate(|id| {
+            Ok(state
+                .db
+                .get_provider_by_id(id, app_type.as_str())?
+                .is_some())
+        })?;
 
     let provider_id = provider.id.clone();
 
     // Use ProviderService to add the provider
-    ProviderService::add(state, app_type.clone(), provider)?;
+    ProviderService::add_from(
+        state,
+        app_type.clone(),
+        provider,
+        ProviderAuditOrigin::Deeplink,
+    )?;
 
     // Add extra endpoints as custom endpoints (skip first one as it's the primary)
     for ep in all_endpoints.iter().skip(1) {
@@ -129,7 +137,12 @@ pub fn import_provider_from_deeplink(
 
     // If enabled=true, set as current provider
     if merged_request.enabled.unwrap_or(false) {
-        ProviderService::switch(state, app_type.clone(), &provider_id)?;
+        ProviderService::switch_from(
+            state,
+            app_type.clone(),
+            &provider_id,
+            ProviderAuditOrigin::Deeplink,
+        )?;
         log::info!("Provider '{provider_id}' set as current for {app_type:?}");
     }
 
@@ -165,6 +178,8 @@ pub(crate) fn build_provider_from_request(
         icon: request.icon.clone(),
         icon_color: None,
         in_failover_queue: false,
+        archived: false,
+        tags: Vec::new(),
     };
 
     Ok(provider)
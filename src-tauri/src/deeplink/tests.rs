@@ -64,6 +64,29 @@ fn test_parse_unsupported_version() {
         .contains("Unsupported protocol version"));
 }
 
+#[test]
+fn test_parse_share_deeplink() {
+    let url =
+        "ccswitch://v1/share?relay=https%3A%2F%2Frelay.example.com%2Fapi&id=abc123&key=c2VjcmV0";
+
+    let request = parse_deeplink_url(url).unwrap();
+
+    assert_eq!(request.resource, "share");
+    let share = request.share.expect("share reference");
+    assert_eq!(share.relay, "https://relay.example.com/api");
+    assert_eq!(share.id, "abc123");
+    assert_eq!(share.key, "c2VjcmV0");
+
+    // Missing key or a plain-http relay is rejected
+    assert!(
+        parse_deeplink_url("ccswitch://v1/share?relay=https%3A%2F%2Fr.example.com&id=a").is_err()
+    );
+    assert!(
+        parse_deeplink_url("ccswitch://v1/share?relay=http%3A%2F%2Fr.example.com&id=a&key=k")
+            .is_err()
+    );
+}
+
 #[test]
 fn test_parse_missing_required_field() {
     // Name is still required even in v3.8+ (only homepage/endpoint/apiKey are optional)
@@ -152,6 +175,7 @@ fn test_build_gemini_provider_with_model() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        share: None,
     };
 
     let provider = build_provider_from_request(&AppType::Gemini, &request).unwrap();
@@ -205,6 +229,7 @@ fn test_build_gemini_provider_without_model() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        share: None,
     };
 
     let provider = build_provider_from_request(&AppType::Gemini, &request).unwrap();
@@ -253,6 +278,7 @@ fn test_parse_and_merge_config_claude() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        share: None,
     };
 
     let merged = parse_and_merge_config(&request).unwrap();
@@ -303,6 +329,7 @@ fn test_parse_and_merge_config_url_override() {
         usage_access_token: None,
         usage_user_id: None,
         usage_auto_interval: None,
+        share: None,
     };
 
     let merged = parse_and_merge_config(&request).unwrap();
@@ -333,7 +360,7 @@ fn test_import_prompt_allows_space_in_base64_content() {
 
     let prompt_id = import_prompt_from_deeplink(&state, request.clone()).expect("import prompt");
 
-    let prompts = state.db.get_prompts("codex").expect("get prompts");
+    let prompts = state.db.get_prompts().expect("get prompts");
     let prompt = prompts.get(&prompt_id).expect("prompt saved");
 
     assert_eq!(prompt.content, ">>>");
@@ -3,6 +3,9 @@ use std::sync::PoisonError;
 
 use thiserror::Error;
 
+use crate::agent::AgentValidationIssue;
+use crate::prompt::PromptIssue;
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("配置错误: {0}")]
@@ -42,6 +45,10 @@ pub enum AppError {
     Lock(String),
     #[error("MCP 校验失败: {0}")]
     McpValidation(String),
+    #[error("Agent 校验失败: {}", format_agent_issues(.0))]
+    AgentValidation(Vec<AgentValidationIssue>),
+    #[error("提示词校验失败: {}", format_prompt_issues(.0))]
+    PromptValidation(Vec<PromptIssue>),
     #[error("{0}")]
     Message(String),
     #[error("{zh} ({en})")]
@@ -91,6 +98,22 @@ impl AppError {
     }
 }
 
+fn format_agent_issues(issues: &[AgentValidationIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| issue.message.as_str())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn format_prompt_issues(issues: &[PromptIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| issue.message.as_str())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 impl<T> From<PoisonError<T>> for AppError {
     fn from(err: PoisonError<T>) -> Self {
         Self::Lock(err.to_string())
@@ -118,6 +141,145 @@ impl serde::Serialize for AppError {
     }
 }
 
+/// 命令错误的类别，前端据此选择恢复方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// 目标不存在（供应商、提示词、配置文件等）
+    NotFound,
+    /// 与现有数据冲突（同名、已安装、目录被占用等）
+    Conflict,
+    /// 输入或配置未通过校验
+    Validation,
+    /// 文件读写失败
+    Io,
+    /// 文件内容无法解析（JSON / TOML）
+    Parse,
+    Database,
+    Internal,
+}
+
+/// Tauri 命令返回给前端的结构化错误
+///
+/// `message` 与原先返回的错误字符串一致；`details` 携带可供前端处理的附加信息，
+/// 如本地化键、出错的文件路径、逐条校验问题。
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<AppError> for CommandError {
+    fn from(err: AppError) -> Self {
+        use serde_json::json;
+
+        let message = err.to_string();
+        match err {
+            AppError::Config(_) | AppError::InvalidInput(_) | AppError::McpValidation(_) => {
+                Self::new(ErrorCode::Validation, message)
+            }
+            AppError::AgentValidation(issues) => {
+                Self::new(ErrorCode::Validation, message).with_details(json!({ "issues": issues }))
+            }
+            AppError::PromptValidation(issues) => {
+                Self::new(ErrorCode::Validation, message).with_details(json!({ "issues": issues }))
+            }
+            AppError::Io { path, .. } => {
+                Self::new(ErrorCode::Io, message).with_details(json!({ "path": path }))
+            }
+            AppError::IoContext { .. } => Self::new(ErrorCode::Io, message),
+            AppError::Json { path, .. } | AppError::Toml { path, .. } => {
+                Self::new(ErrorCode::Parse, message).with_details(json!({ "path": path }))
+            }
+            AppError::Localized { key, zh, en } => Self::new(code_for_key(key), message)
+                .with_details(json!({ "key": key, "zh": zh, "en": en })),
+            AppError::Database(_) => Self::new(ErrorCode::Database, message),
+            AppError::OmoConfigNotFound | AppError::NoProvidersConfigured => {
+                Self::new(ErrorCode::NotFound, message)
+            }
+            AppError::JsonSerialize { .. }
+            | AppError::Lock(_)
+            | AppError::Message(_)
+            | AppError::AllProvidersCircuitOpen => Self::new(ErrorCode::Internal, message),
+        }
+    }
+}
+
+/// 字符串错误没有类别信息；技能模块的 JSON 错误（见 [`format_skill_error`]）按其错误码归类，
+/// `message` 保留原始 JSON 供前端继续解析
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        let skill_error = serde_json::from_str::<serde_json::Value>(&message)
+            .ok()
+            .filter(|v| v.get("code").is_some() && v.get("context").is_some());
+        let Some(details) = skill_error else {
+            return Self::new(ErrorCode::Internal, message);
+        };
+        let code = match details["code"].as_str().unwrap_or_default() {
+            "SKILL_NOT_FOUND" | "SKILL_DIR_NOT_FOUND" => ErrorCode::NotFound,
+            "SKILL_DIRECTORY_CONFLICT" => ErrorCode::Conflict,
+            "MISSING_REPO_INFO" | "EMPTY_ARCHIVE" | "NO_SKILLS_IN_ZIP" => ErrorCode::Validation,
+            _ => ErrorCode::Internal,
+        };
+        Self::new(code, message).with_details(details)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::from(message.to_string())
+    }
+}
+
+/// anyhow 错误中包裹的 [`AppError`] 保留其类别
+impl From<anyhow::Error> for CommandError {
+    fn from(err: anyhow::Error) -> Self {
+        match err.downcast::<AppError>() {
+            Ok(app_err) => Self::from(app_err),
+            Err(err) => Self::from(err.to_string()),
+        }
+    }
+}
+
+/// 按本地化键的命名约定推断错误类别
+fn code_for_key(key: &str) -> ErrorCode {
+    let name = key.rsplit('.').next().unwrap_or(key);
+    if name.contains("not_found") || name == "file_missing" {
+        ErrorCode::NotFound
+    } else if name.contains("conflict") || name.contains("exists") || name.contains("duplicate") {
+        ErrorCode::Conflict
+    } else if name.contains("failed") {
+        ErrorCode::Internal
+    } else {
+        ErrorCode::Validation
+    }
+}
+
 /// 格式化为 JSON 错误字符串，前端可解析为结构化错误
 pub fn format_skill_error(
     code: &str,
@@ -142,3 +304,38 @@ pub fn format_skill_error(
         format!("ERROR:{code}")
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_error_codes_follow_error_kind() {
+        let err = CommandError::from(AppError::localized(
+            "provider.not_found",
+            "供应商不存在: p1",
+            "Provider not found: p1",
+        ));
+        assert_eq!(err.code, ErrorCode::NotFound);
+        assert_eq!(err.details.unwrap()["key"], "provider.not_found");
+
+        let err = CommandError::from(AppError::io(
+            "/tmp/settings.json",
+            std::io::Error::from(std::io::ErrorKind::PermissionDenied),
+        ));
+        assert_eq!(err.code, ErrorCode::Io);
+        assert_eq!(err.details.unwrap()["path"], "/tmp/settings.json");
+
+        let err = CommandError::from(format_skill_error(
+            "SKILL_DIRECTORY_CONFLICT",
+            &[("directory", "demo")],
+            None,
+        ));
+        assert_eq!(err.code, ErrorCode::Conflict);
+        assert!(err.message.contains("SKILL_DIRECTORY_CONFLICT"));
+
+        let err = CommandError::from("plain failure");
+        assert_eq!(err.code, ErrorCode::Internal);
+        assert!(err.details.is_none());
+    }
+}
@@ -0,0 +1,392 @@
+//! 托管内容被外部修改时的冲突处理
+//!
+//! cc-switch 每次写入一个托管单元（提示词区域、agent 文件或 marker 区块、live 配置中的 MCP 条目）后，
+//! 在 `managed-hashes.json` 中记下写入内容的摘要。下次写入前，若单元当前内容与记录的摘要不一致，
+//! 说明它在 cc-switch 之外被改过，此时按该应用的 [`ExternalEditPolicy`] 处理：
+//!
+//! - `overwrite`：照常覆盖（默认，与旧版本行为一致）
+//! - `preserve`：保留外部修改、跳过这次写入，并记录一条冲突提示
+//! - `versions`：先把外部修改记入提示词或 agent 的历史版本，再覆盖
+//!
+//! 没有摘要记录（首次写入、旧版本写入的文件）时不视为冲突；当前内容与即将写入的内容一致时也不算冲突。
+//! 来源横幅不参与摘要计算，开关横幅设置不会引发冲突。
+//!
+//! 摘要记录同时是判断“文件是否在 cc-switch 之外被改过”的唯一依据：提示词对账与 agent 偏差检查
+//! （以及据此工作的文件监听）都经由 [`edit_status`] 判断。
+//!
+//! 冲突记录在数据库中（`external_edit_conflicts`），并发送 `external-edit-detected` 事件；
+//! 记录去处由启动时的 [`install_recorder`] 注册。
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::app_config::AppType;
+use crate::config::{get_app_config_dir, write_text_file};
+use crate::database::Database;
+use crate::error::AppError;
+use crate::managed_banner::strip_banners;
+use crate::prompt::{Prompt, PromptRevisionReason};
+use crate::prompt_files::prompt_file_path;
+use crate::services::jobs::EventSink;
+use crate::services::prompt::enabled_for;
+
+const LEDGER_FILE: &str = "managed-hashes.json";
+
+/// 检测到外部修改时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalEditPolicy {
+    /// 直接覆盖外部修改
+    #[default]
+    Overwrite,
+    /// 保留外部修改，跳过写入并提示
+    Preserve,
+    /// 把外部修改另存为版本快照后覆盖
+    Versions,
+}
+
+impl ExternalEditPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Overwrite => "overwrite",
+            Self::Preserve => "preserve",
+            Self::Versions => "versions",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "overwrite" => Some(Self::Overwrite),
+            "preserve" => Some(Self::Preserve),
+            "versions" => Some(Self::Versions),
+            _ => None,
+        }
+    }
+}
+
+/// 托管单元的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManagedKind {
+    Prompt,
+    Agent,
+    Mcp,
+}
+
+impl ManagedKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Prompt => "prompt",
+            Self::Agent => "agent",
+            Self::Mcp => "mcp",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "prompt" => Some(Self::Prompt),
+            "agent" => Some(Self::Agent),
+            "mcp" => Some(Self::Mcp),
+            _ => None,
+        }
+    }
+}
+
+/// 一个托管单元：某个文件中由 cc-switch 负责的一段内容
+#[derive(Debug, Clone)]
+pub struct ManagedUnit<'a> {
+    pub app: &'a AppType,
+    pub kind: ManagedKind,
+    /// 条目 id；整段区域（如提示词区域）使用固定名称
+    pub id: &'a str,
+    pub path: &'a Path,
+}
+
+impl ManagedUnit<'_> {
+    fn ledger_key(&self) -> String {
+        format!(
+            "{}:{}:{}#{}",
+            self.kind.as_str(),
+            self.app.as_str(),
+            self.path.display(),
+            self.id
+        )
+    }
+}
+
+/// 一次检测到的外部修改
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalEditConflict {
+    pub app: String,
+    pub kind: ManagedKind,
+    pub id: String,
+    pub path: String,
+    /// 实际采用的处理方式
+    pub policy: ExternalEditPolicy,
+    /// 被覆盖前的外部修改内容（仅 `versions`）
+    pub snapshot: Option<String>,
+    /// 快照在提示词或 agent 历史中的版本号；无法对应到单个条目（MCP、多个提示词拼接）时为 None
+    pub revision: Option<i64>,
+    /// 检测时间（Unix 秒）
+    pub detected_at: i64,
+}
+
+/// 冲突记录的去处
+struct Recorder {
+    db: Arc<Database>,
+    events: Option<EventSink>,
+}
+
+thread_local! {
+    static FORCE_OVERWRITE: Cell<bool> = const { Cell::new(false) };
+}
+
+fn ledger_lock() -> &'static Mutex<()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    &LOCK
+}
+
+fn recorder() -> &'static RwLock<Option<Recorder>> {
+    static RECORDER: RwLock<Option<Recorder>> = RwLock::new(None);
+    &RECORDER
+}
+
+/// 注册冲突记录的去处（数据库与可选的事件出口），重复调用以最后一次为准
+///
+/// 未注册时冲突只写日志，`versions` 策略因无处保存快照而退化为保留外部修改。
+pub fn install_recorder(db: Arc<Database>, events: Option<EventSink>) {
+    *recorder().write().unwrap_or_else(|e| e.into_inner()) = Some(Recorder { db, events });
+}
+
+fn digest(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(strip_banners(content).trim_end().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn ledger_path() -> PathBuf {
+    get_app_config_dir().join(LEDGER_FILE)
+}
+
+fn load_ledger() -> BTreeMap<String, String> {
+    let path = ledger_path();
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return BTreeMap::new();
+    };
+    serde_json::from_str(&text).unwrap_or_else(|e| {
+        log::warn!("托管内容摘要文件损坏，已忽略: {}: {e}", path.display());
+        BTreeMap::new()
+    })
+}
+
+fn save_ledger(ledger: &BTreeMap<String, String>) -> Result<(), AppError> {
+    let text =
+        serde_json::to_string_pretty(ledger).map_err(|e| AppError::JsonSerialize { source: e })?;
+    write_text_file(&ledger_path(), &text)
+}
+
+fn recorded_hash(unit: &ManagedUnit<'_>) -> Option<String> {
+    let _guard = ledger_lock().lock().unwrap_or_else(|e| e.into_inner());
+    load_ledger().remove(&unit.ledger_key())
+}
+
+/// 单元当前内容是否已偏离 cc-switch 上次写入的内容（`current` 为 None 表示单元已被移除）
+///
+/// 没有摘要记录（首次写入前、旧版本写入）时无从比较，返回 None。
+pub fn edit_status(unit: &ManagedUnit<'_>, current: Option<&str>) -> Option<bool> {
+    recorded_hash(unit).map(|hash| current.is_none_or(|current| digest(current) != hash))
+}
+
+/// 同 [`edit_status`]，没有摘要记录时视为未偏离
+pub fn has_drifted(unit: &ManagedUnit<'_>, current: Option<&str>) -> bool {
+    edit_status(unit, current).unwrap_or(false)
+}
+
+/// 在 `f` 执行期间忽略冲突策略、直接覆盖（用于用户明确要求丢弃外部修改的操作）
+pub fn overwrite_anyway<T>(f: impl FnOnce() -> T) -> T {
+    let previous = FORCE_OVERWRITE.with(|flag| flag.replace(true));
+    let result = f();
+    FORCE_OVERWRITE.with(|flag| flag.set(previous));
+    result
+}
+
+/// 写入前检查托管单元，返回是否应继续写入
+///
+/// `current` 为单元的当前内容（不存在时为 None），`next` 为即将写入的内容（移除时为 None）。
+pub fn before_write(
+    unit: &ManagedUnit<'_>,
+    current: Option<&str>,
+    next: Option<&str>,
+) -> Result<bool, AppError> {
+    let Some(current) = current else {
+        return Ok(true);
+    };
+    if next.is_some_and(|next| digest(next) == digest(current)) {
+        return Ok(true);
+    }
+    if recorded_hash(unit).is_none_or(|hash| hash == digest(current)) {
+        return Ok(true);
+    }
+
+    let mut policy = if FORCE_OVERWRITE.with(Cell::get) {
+        ExternalEditPolicy::Overwrite
+    } else {
+        crate::settings::get_external_edit_policy(unit.app)
+    };
+    let guard = recorder().read().unwrap_or_else(|e| e.into_inner());
+    let recorder = guard.as_ref();
+    if policy == ExternalEditPolicy::Versions && recorder.is_none() {
+        log::warn!("未注册冲突记录，无法保存外部修改快照，改为保留外部修改");
+        policy = ExternalEditPolicy::Preserve;
+    }
+    let (snapshot, revision) = match policy {
+        ExternalEditPolicy::Overwrite => {
+            log::info!(
+                "{} 中的 {} {} 被外部修改，按设置覆盖",
+                unit.path.display(),
+                unit.kind.as_str(),
+                unit.id
+            );
+            return Ok(true);
+        }
+        ExternalEditPolicy::Preserve => {
+            log::warn!(
+                "{} 中的 {} {} 被外部修改，已保留并跳过写入",
+                unit.path.display(),
+                unit.kind.as_str(),
+                unit.id
+            );
+            (None, None)
+        }
+        ExternalEditPolicy::Versions => {
+            let revision = recorder
+                .map(|recorder| save_to_history(&recorder.db, unit, current))
+                .transpose()?
+                .flatten();
+            log::info!(
+                "{} 中的 {} {} 被外部修改，已记录快照后覆盖",
+                unit.path.display(),
+                unit.kind.as_str(),
+                unit.id
+            );
+            (Some(current.to_string()), revision)
+        }
+    };
+
+    let conflict = ExternalEditConflict {
+        app: unit.app.as_str().to_string(),
+        kind: unit.kind,
+        id: unit.id.to_string(),
+        path: unit.path.display().to_string(),
+        policy,
+        snapshot,
+        revision,
+        detected_at: chrono::Utc::now().timestamp(),
+    };
+    if let Some(recorder) = recorder {
+        // 快照内容已保存在冲突记录中，写入失败时不能继续覆盖
+        let saved = recorder.db.insert_external_edit_conflict(&conflict);
+        if policy == ExternalEditPolicy::Versions {
+            saved?;
+        } else if let Err(e) = saved {
+            log::warn!("记录外部修改冲突失败: {e}");
+        }
+        if let Some(events) = &recorder.events {
+            match serde_json::to_value(&conflict) {
+                Ok(payload) => events("external-edit-detected", payload),
+                Err(e) => log::debug!("序列化外部修改冲突失败: {e}"),
+            }
+        }
+    }
+    Ok(policy != ExternalEditPolicy::Preserve)
+}
+
+/// 把外部修改记入对应提示词或 agent 的历史，返回版本号
+///
+/// 提示词区域只在该 app 的提示词文件恰好只启用一个提示词时能对应回去；
+/// 多个提示词拼接、项目文件与 MCP 条目返回 None，快照只保存在冲突记录中。
+fn save_to_history(
+    db: &Database,
+    unit: &ManagedUnit<'_>,
+    content: &str,
+) -> Result<Option<i64>, AppError> {
+    match unit.kind {
+        ManagedKind::Agent => {
+            let Some(agent) = db.get_agent_by_id(unit.id)? else {
+                return Ok(None);
+            };
+            let edited = crate::agents::parse_agent_file(unit.app, &agent, content);
+            db.append_agent_revision(&edited).map(Some)
+        }
+        ManagedKind::Prompt => {
+            if prompt_file_path(unit.app).ok().as_deref() != Some(unit.path) {
+                return Ok(None);
+            }
+            let prompts = db.get_prompts()?;
+            let [target] = enabled_for(&prompts, unit.app)[..] else {
+                return Ok(None);
+            };
+            let edited = Prompt {
+                content: strip_banners(content),
+                ..target.clone()
+            };
+            db.save_prompt_revision(&edited, PromptRevisionReason::ExternalEdit)
+                .map(Some)
+        }
+        ManagedKind::Mcp => Ok(None),
+    }
+}
+
+/// 写入后记录单元内容的摘要；`written` 为 None 表示单元已移除
+///
+/// 记录失败只影响下次的冲突检测，不影响本次写入，因此只记日志。
+pub fn after_write(unit: &ManagedUnit<'_>, written: Option<&str>) {
+    after_write_many(&[(unit.clone(), written)]);
+}
+
+/// 同 [`after_write`]，一次记录多个单元（共享文件中的一批区块），摘要文件只读写一次
+pub fn after_write_many(units: &[(ManagedUnit<'_>, Option<&str>)]) {
+    if units.is_empty() {
+        return;
+    }
+    let _guard = ledger_lock().lock().unwrap_or_else(|e| e.into_inner());
+    let mut ledger = load_ledger();
+    let mut changed = false;
+    for (unit, written) in units {
+        let key = unit.ledger_key();
+        changed |= match written {
+            Some(content) => {
+                let hash = digest(content);
+                ledger.insert(key, hash.clone()) != Some(hash)
+            }
+            None => ledger.remove(&key).is_some(),
+        };
+    }
+    if changed {
+        if let Err(e) = save_ledger(&ledger) {
+            log::warn!("记录托管内容摘要失败: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_ignores_banners() {
+        let plain = "Review the diff.\n";
+        let with_banner = format!(
+            "{}{plain}",
+            crate::managed_banner::banner_line("prompt", "p1")
+        );
+        assert_eq!(digest(plain), digest(&with_banner));
+        assert_ne!(digest(plain), digest("Review the diff!\n"));
+    }
+}
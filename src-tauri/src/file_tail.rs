@@ -0,0 +1,325 @@
+//! 托管文件的实时跟踪（调试用）
+//!
+//! 排查 cc-switch 与 CLI 工具互相改写配置的问题时，可以对任一托管文件（live 配置、
+//! 提示词文件、agent 文件）开启跟踪：文件每次变化都通过 `managed-file-changed` 事件
+//! 推送变化类型、逐行差异，以及这次写入来自 cc-switch 还是外部程序。
+//!
+//! 来源的判断依据是 cc-switch 自己的写入记录：[`note_self_write`] 在原子写入与删除时调用，
+//! 只记录正在被跟踪的文件，短时间内紧跟其后的变化视为 cc-switch 所为。
+//! 跟踪只在本次运行中有效，应用退出或调用 [`unwatch`] 后停止。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc::channel;
+
+use crate::agent::{DiffLine, DiffLineKind};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::agents::diff_lines;
+
+/// 文件变化事件名
+pub const FILE_CHANGED_EVENT: &str = "managed-file-changed";
+/// 同时跟踪的文件数上限
+const MAX_WATCHES: usize = 16;
+/// 合并连续文件事件的等待时间
+const DEBOUNCE_MS: u64 = 200;
+/// cc-switch 写入后多长时间内的变化归因于 cc-switch
+const SELF_WRITE_WINDOW_MS: i64 = 2000;
+/// 超过此行数的文件不计算逐行差异
+const MAX_DIFF_LINES: usize = 5000;
+
+/// 一个正在跟踪的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileWatch {
+    pub id: String,
+    pub path: String,
+    /// Unix 毫秒
+    pub started_at: i64,
+}
+
+/// 文件变化类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+/// 变化来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileChangeSource {
+    CcSwitch,
+    External,
+}
+
+/// 推送给前端的一次文件变化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeEvent {
+    pub watch_id: String,
+    pub path: String,
+    pub kind: FileChangeKind,
+    pub source: FileChangeSource,
+    /// Unix 毫秒
+    pub at: i64,
+    /// 只含新增 / 删除行
+    pub diff: Vec<DiffLine>,
+    /// 文件过大、未计算差异
+    pub diff_truncated: bool,
+}
+
+struct ActiveWatch {
+    info: FileWatch,
+    target: PathBuf,
+    // 持有 watcher；移除后事件通道关闭，推送任务随之退出
+    _watcher: RecommendedWatcher,
+}
+
+static WATCHES: LazyLock<Mutex<HashMap<String, ActiveWatch>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static SELF_WRITES: LazyLock<Mutex<HashMap<PathBuf, i64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 记录 cc-switch 对 `path` 的写入（未被跟踪的文件直接忽略）
+pub fn note_self_write(path: &Path) {
+    let watches = WATCHES.lock().unwrap_or_else(|e| e.into_inner());
+    if watches.is_empty() {
+        return;
+    }
+    let Some(target) = normalize(path) else {
+        return;
+    };
+    if watches.values().any(|w| w.target == target) {
+        SELF_WRITES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(target, chrono::Utc::now().timestamp_millis());
+    }
+}
+
+/// 可以跟踪的托管文件（不存在的 live 配置与提示词文件同样列出）
+pub fn managed_files() -> Result<Vec<PathBuf>, AppError> {
+    let mut files = vec![
+        crate::config::get_claude_settings_path(),
+        crate::config::get_claude_mcp_path(),
+        crate::codex_config::get_codex_config_path(),
+        crate::codex_config::get_codex_auth_path(),
+        crate::gemini_config::get_gemini_env_path(),
+        crate::gemini_config::get_gemini_settings_path(),
+        crate::opencode_config::get_opencode_config_path(),
+        crate::openclaw_config::get_openclaw_config_path(),
+    ];
+    for app in AppType::all() {
+        files.push(crate::prompt_files::prompt_file_path(&app)?);
+    }
+    files.extend(crate::agents::managed_agent_files()?);
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// 开始跟踪托管文件，变化通过 [`FILE_CHANGED_EVENT`] 推送；同一文件重复调用返回已有的跟踪
+pub fn watch(app: AppHandle, path: &Path) -> Result<FileWatch, AppError> {
+    let not_managed = || {
+        AppError::localized(
+            "file_tail.not_managed",
+            format!("不是 cc-switch 托管的文件: {}", path.display()),
+            format!("Not a file managed by cc-switch: {}", path.display()),
+        )
+    };
+    let target = normalize(path).ok_or_else(not_managed)?;
+    if !managed_files()?
+        .iter()
+        .filter_map(|file| normalize(file))
+        .any(|file| file == target)
+    {
+        return Err(not_managed());
+    }
+
+    let mut watches = WATCHES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = watches.values().find(|w| w.target == target) {
+        return Ok(existing.info.clone());
+    }
+    if watches.len() >= MAX_WATCHES {
+        return Err(AppError::localized(
+            "file_tail.too_many",
+            format!("最多同时跟踪 {MAX_WATCHES} 个文件"),
+            format!("At most {MAX_WATCHES} files can be watched at once"),
+        ));
+    }
+
+    let (tx, mut rx) = channel::<()>(1);
+    let event_target = target.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event)
+            if !matches!(event.kind, EventKind::Access(_))
+                && event.paths.iter().any(|p| p == &event_target) =>
+        {
+            let _ = tx.try_send(());
+        }
+        Ok(_) => {}
+        Err(e) => log::debug!("[FileTail] 文件监听错误: {e}"),
+    })
+    .map_err(|e| AppError::Message(format!("创建文件监听失败: {e}")))?;
+    let dir = target.parent().ok_or_else(not_managed)?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| AppError::Message(format!("监听 {} 失败: {e}", dir.display())))?;
+
+    let info = FileWatch {
+        id: uuid::Uuid::new_v4().to_string(),
+        path: target.display().to_string(),
+        started_at: chrono::Utc::now().timestamp_millis(),
+    };
+    let watch_id = info.id.clone();
+    let task_target = target.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut last = read_lossy(&task_target);
+        while rx.recv().await.is_some() {
+            tokio::time::sleep(Duration::from_millis(DEBOUNCE_MS)).await;
+            while rx.try_recv().is_ok() {}
+
+            let current = read_lossy(&task_target);
+            let Some((kind, diff, diff_truncated)) =
+                describe_change(last.as_deref(), current.as_deref())
+            else {
+                continue;
+            };
+            let at = chrono::Utc::now().timestamp_millis();
+            let event = FileChangeEvent {
+                watch_id: watch_id.clone(),
+                path: task_target.display().to_string(),
+                kind,
+                source: source_of(&task_target, at),
+                at,
+                diff,
+                diff_truncated,
+            };
+            if let Err(e) = app.emit(FILE_CHANGED_EVENT, &event) {
+                log::debug!("[FileTail] 发送 {FILE_CHANGED_EVENT} 事件失败: {e}");
+            }
+            last = current;
+        }
+    });
+
+    log::info!("[FileTail] 开始跟踪 {}", target.display());
+    watches.insert(
+        info.id.clone(),
+        ActiveWatch {
+            info: info.clone(),
+            target,
+            _watcher: watcher,
+        },
+    );
+    Ok(info)
+}
+
+/// 停止跟踪，返回跟踪是否存在
+pub fn unwatch(id: &str) -> bool {
+    let removed = WATCHES.lock().unwrap_or_else(|e| e.into_inner()).remove(id);
+    match removed {
+        Some(watch) => {
+            SELF_WRITES
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&watch.target);
+            log::info!("[FileTail] 停止跟踪 {}", watch.info.path);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 当前正在跟踪的文件
+pub fn list_watches() -> Vec<FileWatch> {
+    let mut list: Vec<FileWatch> = WATCHES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .map(|w| w.info.clone())
+        .collect();
+    list.sort_by_key(|w| w.started_at);
+    list
+}
+
+/// 统一路径形式：目录取规范路径（解析符号链接），文件名保持原样，使文件不存在时也能比较
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    let dir = path.parent()?;
+    let dir = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    Some(dir.join(name))
+}
+
+fn read_lossy(path: &Path) -> Option<String> {
+    std::fs::read(path)
+        .ok()
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn source_of(target: &Path, at: i64) -> FileChangeSource {
+    let writes = SELF_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+    match writes.get(target) {
+        Some(written) if at - written <= SELF_WRITE_WINDOW_MS + DEBOUNCE_MS as i64 => {
+            FileChangeSource::CcSwitch
+        }
+        _ => FileChangeSource::External,
+    }
+}
+
+/// 比较前后内容，没有变化时返回 None
+fn describe_change(
+    before: Option<&str>,
+    after: Option<&str>,
+) -> Option<(FileChangeKind, Vec<DiffLine>, bool)> {
+    let kind = match (before, after) {
+        (None, None) => return None,
+        (Some(a), Some(b)) if a == b => return None,
+        (None, Some(_)) => FileChangeKind::Created,
+        (Some(_), None) => FileChangeKind::Removed,
+        (Some(_), Some(_)) => FileChangeKind::Modified,
+    };
+    let (before, after) = (before.unwrap_or_default(), after.unwrap_or_default());
+    if before.lines().count() > MAX_DIFF_LINES || after.lines().count() > MAX_DIFF_LINES {
+        return Some((kind, Vec::new(), true));
+    }
+    let diff = diff_lines(before, after)
+        .into_iter()
+        .filter(|line| line.kind != DiffLineKind::Equal)
+        .collect();
+    Some((kind, diff, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_change_reports_kind_and_changed_lines_only() {
+        assert!(describe_change(None, None).is_none());
+        assert!(describe_change(Some("a\n"), Some("a\n")).is_none());
+
+        let (kind, diff, truncated) = describe_change(Some("a\nb\n"), Some("a\nc\n")).unwrap();
+        assert_eq!(kind, FileChangeKind::Modified);
+        assert!(!truncated);
+        let lines: Vec<_> = diff.iter().map(|l| (l.kind, l.text.as_str())).collect();
+        assert_eq!(
+            lines,
+            vec![(DiffLineKind::Removed, "b"), (DiffLineKind::Added, "c")]
+        );
+
+        let (kind, diff, _) = describe_change(Some("a\n"), None).unwrap();
+        assert_eq!(kind, FileChangeKind::Removed);
+        assert_eq!(diff.len(), 1);
+        let (kind, _, _) = describe_change(None, Some("")).unwrap();
+        assert_eq!(kind, FileChangeKind::Created);
+    }
+}
@@ -49,26 +49,31 @@ pub fn read_mcp_servers_map() -> Result<std::collections::HashMap<String, Value>
         .unwrap_or_default();
 
     // 反向格式转换：Gemini 特有格式 → 统一 MCP 格式
-    for (_, spec) in servers.iter_mut() {
-        if let Some(obj) = spec.as_object_mut() {
-            // httpUrl → url + type: "http"
-            if let Some(http_url) = obj.remove("httpUrl") {
-                obj.insert("url".to_string(), http_url);
-                obj.insert("type".to_string(), Value::String("http".to_string()));
-            }
+    for spec in servers.values_mut() {
+        normalize_gemini_spec(spec);
+    }
+
+    Ok(servers)
+}
 
-            // Gemini CLI 不使用 type 字段：这里补齐成统一结构，便于校验与导入
-            if obj.get("type").is_none() {
-                if obj.contains_key("command") {
-                    obj.insert("type".to_string(), Value::String("stdio".to_string()));
-                } else if obj.contains_key("url") {
-                    obj.insert("type".to_string(), Value::String("sse".to_string()));
-                }
+/// 把 Gemini 格式的单个服务器定义转换为统一 MCP 结构（规则见 [`read_mcp_servers_map`]）
+pub(crate) fn normalize_gemini_spec(spec: &mut Value) {
+    if let Some(obj) = spec.as_object_mut() {
+        // httpUrl → url + type: "http"
+        if let Some(http_url) = obj.remove("httpUrl") {
+            obj.insert("url".to_string(), http_url);
+            obj.insert("type".to_string(), Value::String("http".to_string()));
+        }
+
+        // Gemini CLI 不使用 type 字段：这里补齐成统一结构，便于校验与导入
+        if obj.get("type").is_none() {
+            if obj.contains_key("command") {
+                obj.insert("type".to_string(), Value::String("stdio".to_string()));
+            } else if obj.contains_key("url") {
+                obj.insert("type".to_string(), Value::String("sse".to_string()));
             }
         }
     }
-
-    Ok(servers)
 }
 
 /// 将给定的启用 MCP 服务器映射写入到 Gemini settings.json 的 mcpServers 字段
@@ -8,12 +8,16 @@ mod claude_plugin;
 mod codex_config;
 mod commands;
 mod config;
+mod crypto;
 mod database;
 mod deeplink;
 mod error;
+mod external_edits;
+mod file_tail;
 mod gemini_config;
 mod gemini_mcp;
 mod init_status;
+mod managed_banner;
 mod mcp;
 mod openclaw_config;
 mod opencode_config;
@@ -23,14 +27,17 @@ mod prompt_files;
 mod provider;
 mod provider_defaults;
 mod proxy;
+mod secret_store;
 mod services;
 mod session_manager;
 mod settings;
+mod startup;
 mod store;
+mod tags;
 mod tray;
 mod usage_script;
 
-pub use agent::AgentDefinition;
+pub use agent::{AgentApps, AgentDefinition, AgentDriftStatus, DiffLineKind, DuplicateStrategy};
 pub use app_config::{AppType, McpApps, McpServer, MultiAppConfig};
 pub use codex_config::{get_codex_auth_path, get_codex_config_path, write_codex_live_atomic};
 pub use commands::open_provider_terminal;
@@ -39,16 +46,28 @@ pub use config::{get_claude_mcp_path, get_claude_settings_path, read_json_file};
 pub use database::Database;
 pub use deeplink::{import_provider_from_deeplink, parse_deeplink_url, DeepLinkImportRequest};
 pub use error::AppError;
+pub use external_edits::{install_recorder, ExternalEditConflict, ExternalEditPolicy};
 pub use mcp::{
     import_from_claude, import_from_codex, import_from_gemini, remove_server_from_claude,
     remove_server_from_codex, remove_server_from_gemini, sync_enabled_to_claude,
     sync_enabled_to_codex, sync_enabled_to_gemini, sync_single_server_to_claude,
     sync_single_server_to_codex, sync_single_server_to_gemini,
 };
-pub use provider::{Provider, ProviderMeta};
+pub use prompt::{
+    Prompt, PromptApps, PromptFilePreview, PromptImportResult, PromptIssue, PromptIssueLevel,
+    PromptRevision, PromptRevisionReason, PromptSizeLimit, TrashedPrompt,
+};
+pub use prompt_files::OpenCodePromptTarget;
+pub use provider::{Provider, ProviderMeta, ProviderTrust, UniversalProvider};
 pub use services::{
-    AgentsService, ConfigService, EndpointLatency, McpService, PromptService, ProviderService,
-    ProxyService, SkillService, SpeedtestService,
+    AgentsMdService, AgentsService, ApiAccessService, ApiScope, BulkReplaceKind,
+    BulkReplaceService, ConfigService, DriftResolution, EnablementService, EndpointLatency,
+    LegacyChangeAction, LegacyConfigService, LegacyEntryKind, LegacyMergeSelection,
+    MarkdownImportService, MarkdownImportTarget, McpService, PromptBackupService,
+    PromptBundleService, PromptDriftService, PromptProjectService, PromptSchedule,
+    PromptScheduleService, PromptService, ProviderAuditOrigin, ProviderProfile, ProviderService,
+    ProxyService, Schedule, SkillService, SpeedtestService, SyncTargetKind, SyncTargetMode,
+    SyncTargetsService,
 };
 pub use settings::{update_settings, AppSettings};
 pub use store::AppState;
@@ -128,12 +147,8 @@ fn handle_deeplink_url(
             }
 
             if focus_main_window {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.unminimize();
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                    log::info!("✓ Window shown and focused");
-                }
+                startup::show_main_window(app);
+                log::info!("✓ Window shown and focused");
             }
         }
         Err(e) => {
@@ -218,12 +233,8 @@ pub fn run() {
                 log::info!("ℹ No deep link URL found in args (this is expected on macOS when launched via system)");
             }
 
-            // Show and focus window regardless
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.unminimize();
-                let _ = window.show();
-                let _ = window.set_focus();
-            }
+            // Show and focus window regardless (created on demand after a background launch)
+            startup::show_main_window(app);
         }));
     }
 
@@ -397,6 +408,17 @@ pub fn run() {
             // 设置 AppHandle 用于代理故障转移时的 UI 更新
             app_state.proxy_service.set_app_handle(app.handle().clone());
 
+            // 托管内容的外部修改记录到数据库，并通知前端
+            let handle = app.handle().clone();
+            external_edits::install_recorder(
+                app_state.db.clone(),
+                Some(Arc::new(move |event, payload| {
+                    if let Err(e) = handle.emit(event, payload) {
+                        log::debug!("发送 {event} 事件失败: {e}");
+                    }
+                })),
+            );
+
             // ============================================================
             // 按表独立判断的导入逻辑（各类数据独立检查，互不影响）
             // ============================================================
@@ -451,6 +473,27 @@ pub fn run() {
                 Err(e) => log::warn!("✗ Failed to read skills migration flag: {e}"),
             }
 
+            // 1.2. 旧版 config.json 再次出现（如被 dotfiles 同步恢复）且比上次迁移更新时，
+            // 不再忽略：由前端预览差异并让用户选择性合并
+            match crate::services::LegacyConfigService::detect(&app_state) {
+                Ok(Some(status)) => {
+                    log::info!("检测到比上次迁移更新的旧版配置文件: {}", status.path);
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("✗ Failed to check legacy config.json: {e}"),
+            }
+
+            // 1.3. 首次启动（供应商表为空）时从现有 live 配置自动识别供应商
+            // 必须在 OpenCode / OpenClaw 导入之前执行，否则供应商表已不为空
+            match ProviderService::import_live_providers_on_first_launch(&app_state) {
+                Ok(apps) if !apps.is_empty() => {
+                    let apps: Vec<&str> = apps.iter().map(|app| app.as_str()).collect();
+                    log::info!("✓ Detected providers from live config: {}", apps.join(", "));
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("✗ Failed to detect providers from live config: {e}"),
+            }
+
             // 2. OpenCode 供应商导入（累加式模式，需特殊处理）
             // OpenCode 与其他应用不同：配置文件中可同时存在多个供应商
             // 需要遍历 provider 字段下的每个供应商并导入
@@ -561,6 +604,15 @@ pub fn run() {
                 }
             }
 
+            // 3.1 Gemini 扩展自带的 MCP 服务器（只读条目，每次启动刷新）
+            match crate::services::mcp::McpService::import_from_gemini_extensions(&app_state) {
+                Ok(count) if count > 0 => {
+                    log::info!("✓ Found {count} new MCP server(s) from Gemini extensions");
+                }
+                Ok(_) => log::debug!("○ No new Gemini extension MCP servers"),
+                Err(e) => log::warn!("✗ Failed to scan Gemini extensions for MCP: {e}"),
+            }
+
             // 4. 导入提示词文件（表空时触发）
             if app_state.db.is_prompts_table_empty().unwrap_or(false) {
                 log::info!("Prompts table empty, importing from live configurations...");
@@ -585,6 +637,16 @@ pub fn run() {
                 }
             }
 
+            // 5. 清理超过保留期的已删除提示词
+            if let Err(e) =
+                crate::services::prompt::PromptService::purge_expired_trash(&app_state.db)
+            {
+                log::warn!("✗ Failed to purge prompt trash: {e}");
+            }
+            if let Err(e) = ProviderService::purge_expired_trash(&app_state.db) {
+                log::warn!("✗ Failed to purge provider trash: {e}");
+            }
+
             // 迁移旧的 app_config_dir 配置到 Store
             if let Err(e) = app_store::migrate_app_config_dir_from_settings(app.handle()) {
                 log::warn!("迁移 app_config_dir 失败: {e}");
@@ -695,6 +757,8 @@ pub fn run() {
             );
             // 将同一个实例注入到全局状态，避免重复创建导致的不一致
             app.manage(app_state);
+            // 监听托管的 agent 文件，外部删除时恢复或提示
+            crate::services::agent_watcher::start_worker(app.handle().clone());
 
             // 从数据库加载日志配置并应用
             {
@@ -790,26 +854,108 @@ pub fn run() {
                         }
                     }
                 });
+
+                // 订阅源轮询：每小时检查一次，到期的订阅才会真正拉取
+                let db_for_feeds = state.db.clone();
+                let app_for_feeds = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    let mut interval =
+                        tokio::time::interval(std::time::Duration::from_secs(3600));
+                    loop {
+                        interval.tick().await;
+                        let pending = services::FeedService::refresh_due(&db_for_feeds).await;
+                        if pending > 0 {
+                            if let Err(e) = app_for_feeds.emit("feed-updates-available", pending) {
+                                log::warn!("发送订阅更新事件失败: {e}");
+                            }
+                        }
+                    }
+                });
+
+                // 端点健康探测：定时测速开启了自动选择的供应商的端点，切换时据此选择最快的端点
+                let app_for_endpoints = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                        services::provider::ENDPOINT_PROBE_INTERVAL_SECS,
+                    ));
+                    loop {
+                        interval.tick().await;
+                        let state = app_for_endpoints.state::<AppState>();
+                        match services::ProviderService::probe_auto_select_endpoints(&state).await
+                        {
+                            Ok(probed) if probed > 0 => log::debug!("已探测 {probed} 个端点"),
+                            Ok(_) => {}
+                            Err(e) => log::warn!("探测供应商端点失败: {e}"),
+                        }
+                    }
+                });
+
+                // 定时切换提示词：每分钟检查一次到期的规则
+                let app_for_schedules = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                        services::prompt_schedule::PROMPT_SCHEDULE_TICK_SECS,
+                    ));
+                    let mut last_day = chrono::Local::now().date_naive();
+                    loop {
+                        interval.tick().await;
+                        let state = app_for_schedules.state::<AppState>();
+                        // 跨过零点后刷新文件中的日期模板变量
+                        let today = chrono::Local::now().date_naive();
+                        if today != last_day {
+                            last_day = today;
+                            services::PromptService::resync_templates_for_new_day(&state);
+                        }
+                        let now = chrono::Utc::now();
+                        match services::PromptScheduleService::run_due(&state, now) {
+                            Ok(applied) if !applied.is_empty() => {
+                                if let Err(e) =
+                                    app_for_schedules.emit("prompt-schedule-applied", &applied)
+                                {
+                                    log::warn!("发送定时切换提示词事件失败: {e}");
+                                }
+                            }
+                            Ok(_) => {}
+                            Err(e) => log::warn!("执行定时切换提示词失败: {e}"),
+                        }
+                    }
+                });
             });
 
-            // Linux: 禁用 WebKitGTK 硬件加速，防止 EGL 初始化失败导致白屏
-            #[cfg(target_os = "linux")]
-            {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.with_webview(|webview| {
-                        use webkit2gtk::{WebViewExt, SettingsExt, HardwareAccelerationPolicy};
-                        let wk_webview = webview.inner();
-                        if let Some(settings) = WebViewExt::settings(&wk_webview) {
-                            SettingsExt::set_hardware_acceleration_policy(&settings, HardwareAccelerationPolicy::Never);
-                            log::info!("已禁用 WebKitGTK 硬件加速");
+            let settings = crate::settings::get_settings();
+
+            // 按设置校正开机自启注册（涉及注册表 / 登录项，放到后台执行）
+            let launch_on_startup = settings.launch_on_startup;
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = crate::auto_launch::sync_with_setting(launch_on_startup) {
+                    log::warn!("校正开机自启注册失败: {e}");
+                }
+            });
+
+            // 校验 Skills SSOT 目录：云盘同步不完整时文件可能被截断或丢失
+            let app_for_skills = app.handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                let state = app_for_skills.state::<AppState>();
+                match services::SkillService::verify_integrity(&state.db) {
+                    Ok(issues) if !issues.is_empty() => {
+                        log::warn!("检测到 {} 个 Skill 的文件缺失或被改动", issues.len());
+                        if let Err(e) = app_for_skills.emit("skills-integrity-issues", &issues) {
+                            log::warn!("发送 Skill 校验事件失败: {e}");
                         }
-                    });
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("校验 Skills 目录失败: {e}"),
                 }
-            }
+            });
 
-            // 静默启动：根据设置决定是否显示主窗口
-            let settings = crate::settings::get_settings();
-            if let Some(window) = app.get_webview_window("main") {
+            // 后台启动：托盘与后台服务已就绪，主窗口等到首次打开时再创建
+            let args: Vec<String> = std::env::args().collect();
+            if startup::is_background_launch(&args, &settings) {
+                #[cfg(target_os = "macos")]
+                tray::apply_tray_policy(app.handle(), false);
+                log::info!("后台启动模式：暂不创建主窗口");
+            } else if let Some(window) = startup::ensure_main_window(app.handle()) {
+                // 静默启动：根据设置决定是否显示主窗口
                 if settings.silent_startup {
                     // 静默启动模式：保持窗口隐藏
                     let _ = window.hide();
@@ -829,12 +975,44 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_providers,
+            commands::get_provider_metrics,
+            commands::get_providers_by_tag,
+            commands::get_provider_tags,
+            commands::get_provider_audit_log,
+            commands::get_provider_env_scripts,
+            commands::diff_providers,
             commands::get_current_provider,
             commands::add_provider,
             commands::update_provider,
+            commands::bulk_update_providers,
+            commands::add_provider_attachment,
+            commands::list_provider_attachments,
+            commands::open_provider_attachment,
+            commands::remove_provider_attachment,
+            commands::duplicate_provider,
+            commands::sync_reference_providers,
             commands::delete_provider,
+            commands::get_provider_trash,
+            commands::restore_provider,
+            commands::purge_provider_trash,
+            commands::get_provider_issues,
+            commands::get_providers_missing_secrets,
+            commands::set_provider_secret,
+            commands::set_keychain_storage,
             commands::remove_provider_from_live_config,
             commands::switch_provider,
+            commands::get_provider_setup_guide,
+            commands::get_provider_presets,
+            commands::create_provider_from_preset,
+            commands::export_providers,
+            commands::import_providers,
+            commands::set_provider_maintenance,
+            commands::clear_provider_maintenance,
+            commands::set_provider_archived,
+            commands::query_provider_quota,
+            commands::set_provider_trust,
+            commands::set_provider_automation_allowed,
+            commands::switch_to_fastest_provider,
             commands::import_default_config,
             commands::get_claude_config_status,
             commands::get_config_status,
@@ -846,6 +1024,10 @@ pub fn run() {
             commands::get_init_error,
             commands::get_migration_result,
             commands::get_skills_migration_result,
+            commands::get_legacy_config_status,
+            commands::preview_legacy_config_merge,
+            commands::merge_legacy_config,
+            commands::dismiss_legacy_config,
             commands::get_app_config_path,
             commands::open_app_config_folder,
             commands::get_common_config_snippet,
@@ -858,6 +1040,17 @@ pub fn run() {
             commands::set_rectifier_config,
             commands::get_log_config,
             commands::set_log_config,
+            commands::get_paused_sync_apps,
+            commands::set_app_sync_paused,
+            commands::set_external_edit_policy,
+            commands::get_external_edit_conflicts,
+            commands::clear_external_edit_conflicts,
+            commands::list_managed_files,
+            commands::watch_file,
+            commands::unwatch_file,
+            commands::get_file_watches,
+            commands::get_sync_targets,
+            commands::strip_managed_banners,
             commands::restart_app,
             commands::check_for_updates,
             commands::is_portable_mode,
@@ -887,19 +1080,76 @@ pub fn run() {
             commands::delete_mcp_server,
             commands::toggle_mcp_app,
             commands::import_mcp_from_apps,
+            commands::get_mcp_provider_bindings,
+            commands::set_mcp_provider_binding,
+            commands::remove_mcp_provider_binding,
+            commands::export_mcp_as_devcontainer,
             // Prompt management
             commands::get_prompts,
+            commands::get_prompts_for_app,
+            commands::get_enabled_prompt,
+            commands::search_prompts,
             commands::upsert_prompt,
+            commands::validate_prompt,
             commands::delete_prompt,
+            commands::duplicate_prompt,
+            commands::get_prompt_trash,
+            commands::restore_prompt,
+            commands::purge_prompt_trash,
+            commands::get_prompt_history,
+            commands::rollback_prompt,
             commands::toggle_prompt_app,
+            commands::reorder_prompts,
+            commands::preview_prompt_render,
+            commands::preview_prompt_file,
+            commands::get_prompt_projects,
+            commands::add_prompt_project,
+            commands::remove_prompt_project,
+            commands::toggle_project_prompt,
             commands::import_prompt_from_file,
             commands::get_current_prompt_file_content,
+            commands::list_prompt_backups,
+            commands::restore_prompt_backup,
+            commands::export_prompts_bundle,
+            commands::import_prompts_bundle,
+            commands::import_prompts_from_dir,
+            commands::set_opencode_prompt_target,
+            commands::detect_prompt_drift,
+            commands::resolve_prompt_drift,
+            commands::bulk_replace,
+            commands::get_context_budget,
+            commands::preview_composed_context,
+            // Schedules
+            commands::get_next_runs,
+            commands::get_prompt_schedules,
+            commands::save_prompt_schedule,
+            commands::delete_prompt_schedule,
+            // Local API tokens
+            commands::get_api_tokens,
+            commands::create_api_token,
+            commands::revoke_api_token,
+            // Remote feeds
+            commands::get_feed_subscriptions,
+            commands::add_feed_subscription,
+            commands::remove_feed_subscription,
+            commands::set_feed_subscription_enabled,
+            commands::refresh_feed_subscription,
+            commands::get_feed_updates,
+            commands::accept_feed_update,
+            commands::dismiss_feed_update,
             // ours: endpoint speed test + custom endpoint management
             commands::test_api_endpoints,
+            commands::benchmark_providers,
+            commands::get_provider_benchmarks,
+            commands::get_provider_profiles,
+            commands::save_provider_profile,
+            commands::delete_provider_profile,
+            commands::apply_provider_profile,
             commands::get_custom_endpoints,
             commands::add_custom_endpoint,
             commands::remove_custom_endpoint,
             commands::update_endpoint_last_used,
+            commands::prune_stale_endpoints,
             // app_config_dir override via Store
             commands::get_app_config_dir_override,
             commands::set_app_config_dir_override,
@@ -907,6 +1157,7 @@ pub fn run() {
             commands::update_providers_sort_order,
             // theirs: config import/export and dialogs
             commands::export_config_to_file,
+            commands::generate_config_report,
             commands::import_config_from_file,
             commands::webdav_test_connection,
             commands::webdav_sync_upload,
@@ -919,12 +1170,21 @@ pub fn run() {
             commands::list_db_backups,
             commands::restore_db_backup,
             commands::rename_db_backup,
+            commands::preview_markdown_notes,
+            commands::import_markdown_notes,
+            commands::export_enablement_matrix,
+            commands::import_enablement_matrix,
             commands::sync_current_providers_live,
+            commands::start_db_backup_job,
+            commands::start_full_resync_job,
+            commands::cancel_job,
+            commands::get_running_jobs,
             // Deep link import
             commands::parse_deeplink,
             commands::merge_deeplink_config,
             commands::import_from_deeplink,
             commands::import_from_deeplink_unified,
+            commands::create_share_link,
             update_tray_menu,
             // Environment variable management
             commands::check_env_conflicts,
@@ -937,7 +1197,10 @@ pub fn run() {
             commands::toggle_skill_app,
             commands::scan_unmanaged_skills,
             commands::import_skills_from_apps,
+            commands::verify_skills_integrity,
+            commands::repair_skills,
             commands::discover_available_skills,
+            commands::start_skill_repo_refresh_job,
             // Skill management (legacy API compatibility)
             commands::get_skills,
             commands::get_skills_for_app,
@@ -985,10 +1248,16 @@ pub fn run() {
             commands::remove_from_failover_queue,
             commands::get_auto_failover_enabled,
             commands::set_auto_failover_enabled,
+            commands::inject_provider_failure,
+            commands::get_injected_failures,
+            commands::clear_injected_failures,
+            commands::export_failover_config,
+            commands::import_failover_config,
             // Usage statistics
             commands::get_usage_summary,
             commands::get_usage_trends,
             commands::get_provider_stats,
+            commands::get_latency_heatmap,
             commands::get_model_stats,
             commands::get_request_logs,
             commands::get_request_detail,
@@ -999,6 +1268,7 @@ pub fn run() {
             // Stream health check
             commands::stream_check_provider,
             commands::stream_check_all_providers,
+            commands::start_stream_check_job,
             commands::get_stream_check_config,
             commands::save_stream_check_config,
             // Session manager
@@ -1006,6 +1276,7 @@ pub fn run() {
             commands::get_session_messages,
             commands::launch_session_terminal,
             commands::get_tool_versions,
+            commands::clear_remote_cache,
             // Provider terminal
             commands::open_provider_terminal,
             // Universal Provider management
@@ -1014,6 +1285,7 @@ pub fn run() {
             commands::upsert_universal_provider,
             commands::delete_universal_provider,
             commands::sync_universal_provider,
+            commands::apply_universal_provider,
             // OpenCode specific
             commands::import_opencode_providers_from_live,
             commands::get_opencode_live_provider_ids,
@@ -1058,9 +1330,27 @@ pub fn run() {
             commands::open_workspace_directory,
             // Agent management
             commands::get_agent_definitions,
+            commands::search_agents,
             commands::upsert_agent_definition,
+            commands::validate_agent_definition,
             commands::delete_agent_definition,
             commands::toggle_agent_app,
+            commands::bulk_toggle_agents,
+            commands::reorder_agents,
+            commands::get_agent_templates,
+            commands::create_agent_from_template,
+            commands::get_agent_history,
+            commands::diff_agent_versions,
+            commands::rollback_agent,
+            commands::export_agents_bundle,
+            commands::import_agents_bundle,
+            commands::scan_existing_agents,
+            commands::check_agent_drift,
+            commands::pull_agent_from_app,
+            commands::adopt_agent_file,
+            commands::restore_agent_file,
+            commands::set_gemini_agent_output,
+            commands::export_agents_md,
         ]);
 
     let app = builder
@@ -1093,16 +1383,7 @@ pub fn run() {
             match event {
                 // macOS 在 Dock 图标被点击并重新激活应用时会触发 Reopen 事件，这里手动恢复主窗口
                 RunEvent::Reopen { .. } => {
-                    if let Some(window) = app_handle.get_webview_window("main") {
-                        #[cfg(target_os = "windows")]
-                        {
-                            let _ = window.set_skip_taskbar(false);
-                        }
-                        let _ = window.unminimize();
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                        tray::apply_tray_policy(app_handle, true);
-                    }
+                    startup::show_main_window(app_handle);
                 }
                 // 处理通过自定义 URL 协议触发的打开事件（例如 ccswitch://...）
                 RunEvent::Opened { urls } => {
@@ -1148,11 +1429,7 @@ pub fn run() {
                             }
 
                             // 确保主窗口可见
-                            if let Some(window) = app_handle.get_webview_window("main") {
-                                let _ = window.unminimize();
-                                let _ = window.show();
-                                let _ = window.set_focus();
-                            }
+                            startup::show_main_window(app_handle);
                         }
                     }
                 }
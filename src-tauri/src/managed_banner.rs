@@ -0,0 +1,103 @@
+//! 托管内容的来源横幅
+//!
+//! 开启 `managed_banner` 设置后，cc-switch 写入的 agent 文件、marker 区块与提示词区域
+//! 顶部会多出一行 HTML 注释，注明内容来自 cc-switch 中的哪个条目，
+//! 让直接编辑文件的用户知道修改为什么会在下次同步时消失。
+//!
+//! 横幅只是提示，不属于条目内容：回读文件、检测漂移前都会先把它去掉，
+//! 因此开关设置不会让已同步的文件被判定为被外部修改。
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use crate::app_config::AppType;
+use crate::config::write_text_file;
+use crate::error::AppError;
+
+const BANNER_PREFIX: &str = "<!-- Managed by CC Switch";
+
+/// 横幅行（含结尾换行）；`kind` 为条目类型（agent / prompt），`id` 为条目 id
+pub fn banner_line(kind: &str, id: &str) -> String {
+    format!(
+        "{BANNER_PREFIX} ({kind}: {id}). Edit it in CC Switch; manual changes here will be overwritten. -->\n"
+    )
+}
+
+/// 设置开启时返回横幅行，否则返回空字符串
+pub fn banner_for(kind: &str, id: &str) -> String {
+    if crate::settings::managed_banner_enabled() {
+        banner_line(kind, id)
+    } else {
+        String::new()
+    }
+}
+
+fn is_banner_line(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with(BANNER_PREFIX) && line.ends_with("-->")
+}
+
+/// 去掉内容中的所有横幅行，其余内容逐字保留
+pub fn strip_banners(content: &str) -> String {
+    if !content.contains(BANNER_PREFIX) {
+        return content.to_string();
+    }
+    content
+        .split_inclusive('\n')
+        .filter(|line| !is_banner_line(line))
+        .collect()
+}
+
+/// 从 cc-switch 可能写入的所有文件中去掉横幅，返回实际改写的文件
+///
+/// 包括各工具的 agent 目录、共享 agent 文件与提示词文件；不存在或不含横幅的文件不触碰。
+pub fn strip_banners_from_managed_files() -> Result<Vec<PathBuf>, AppError> {
+    let mut paths: BTreeSet<PathBuf> = crate::agents::managed_agent_files()?.into_iter().collect();
+    for app in [
+        AppType::Claude,
+        AppType::Codex,
+        AppType::Gemini,
+        AppType::OpenCode,
+    ] {
+        paths.insert(crate::prompt_files::prompt_file_path(&app)?);
+    }
+
+    let mut stripped = Vec::new();
+    for path in paths {
+        if !path.is_file() {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?;
+        let cleaned = strip_banners(&content);
+        if cleaned != content {
+            write_text_file(&path, &cleaned)?;
+            stripped.push(path);
+        }
+    }
+    log::info!("已从 {} 个托管文件中去掉来源横幅", stripped.len());
+    Ok(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_banners_restores_the_plain_content() {
+        let plain = "---\nname: reviewer\n---\n\nReview the diff.\n";
+        let with_banner = plain.replacen(
+            "---\n\n",
+            &format!("---\n\n{}", banner_line("agent", "reviewer")),
+            1,
+        );
+        assert_ne!(with_banner, plain);
+        assert_eq!(strip_banners(&with_banner), plain);
+        assert_eq!(strip_banners(plain), plain);
+    }
+
+    #[test]
+    fn strip_banners_keeps_other_comments() {
+        let content = "<!-- keep me -->\n<!-- Managed by CC Switch (prompt: p1). x -->\nBody\n";
+        assert_eq!(strip_banners(content), "<!-- keep me -->\nBody\n");
+    }
+}
@@ -71,11 +71,16 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
                 continue;
             };
 
-            // type 缺省为 stdio
+            // Codex 官方配置不写 type：有 url 视为 http，否则为 stdio
+            let inferred = if entry_tbl.contains_key("url") {
+                "http"
+            } else {
+                "stdio"
+            };
             let typ = entry_tbl
                 .get("type")
                 .and_then(|v| v.as_str())
-                .unwrap_or("stdio");
+                .unwrap_or(inferred);
 
             // 构建 JSON 规范
             let mut spec = serde_json::Map::new();
@@ -97,7 +102,7 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
                     if let Some(args) = entry_tbl.get("args").and_then(|v| v.as_array()) {
                         let arr = args
                             .iter()
-                            .filter_map(|x| x.as_str())
+                            .filter_map(toml_scalar_to_string)
                             .map(|s| json!(s))
                             .collect::<Vec<_>>();
                         if !arr.is_empty() {
@@ -112,7 +117,7 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
                     if let Some(env_tbl) = entry_tbl.get("env").and_then(|v| v.as_table()) {
                         let mut env_json = serde_json::Map::new();
                         for (k, v) in env_tbl.iter() {
-                            if let Some(sv) = v.as_str() {
+                            if let Some(sv) = toml_scalar_to_string(v) {
                                 env_json.insert(k.clone(), json!(sv));
                             }
                         }
@@ -241,6 +246,7 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
                         homepage: None,
                         docs: None,
                         tags: Vec::new(),
+                        origin: None,
                     },
                 );
                 changed += 1;
@@ -382,16 +388,21 @@ pub fn sync_single_server_to_codex(
         }
     }
 
-    // 确保 [mcp_servers] 表存在
+    // 确保 [mcp_servers] 表存在（隐式表，不单独输出空的 [mcp_servers] 表头）
     if !doc.contains_key("mcp_servers") {
-        doc["mcp_servers"] = toml_edit::table();
+        let mut servers = toml_edit::Table::new();
+        servers.set_implicit(true);
+        doc["mcp_servers"] = Item::Table(servers);
     }
 
     // 将 JSON 服务器规范转换为 TOML 表
     let toml_table = json_server_to_toml_table(server_spec)?;
 
-    // 使用唯一正确的格式：[mcp_servers]
-    doc["mcp_servers"][id] = Item::Table(toml_table);
+    // 使用唯一正确的格式：[mcp_servers]；已有条目原地更新，保留未变化字段的格式与注释
+    match doc["mcp_servers"].get_mut(id).and_then(Item::as_table_mut) {
+        Some(existing) => merge_server_table(existing, toml_table),
+        None => doc["mcp_servers"][id] = Item::Table(toml_table),
+    }
 
     // 写回文件
     let new_text = doc.to_string();
@@ -449,6 +460,76 @@ pub fn remove_server_from_codex(id: &str) -> Result<(), AppError> {
 // TOML 转换辅助函数
 // ============================================================================
 
+/// 把 TOML 标量转成字符串（args / env 在统一结构中均为字符串，`PORT = 8080` 之类的写法也应保留）
+fn toml_scalar_to_string(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Integer(i) => Some(i.to_string()),
+        toml::Value::Float(f) => Some(f.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// 用新生成的服务器表更新 config.toml 中已有的条目
+///
+/// 值未变化的键保留原有的格式与注释，新规范中已不存在的键被移除；
+/// 原条目用内联表书写的子表（如 `env = { ... }`）继续以内联表写回。
+fn merge_server_table(existing: &mut toml_edit::Table, new: toml_edit::Table) {
+    use toml_edit::Item;
+
+    let stale: Vec<String> = existing
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !new.contains_key(key))
+        .collect();
+    for key in stale {
+        existing.remove(&key);
+    }
+
+    for (key, item) in new {
+        let item = match (existing.get(&key), item) {
+            (Some(Item::Value(toml_edit::Value::InlineTable(_))), Item::Table(table)) => {
+                Item::Value(toml_edit::Value::InlineTable(table.into_inline_table()))
+            }
+            (_, item) => item,
+        };
+        if existing
+            .get(&key)
+            .is_some_and(|old| same_toml_item(old, &item))
+        {
+            continue;
+        }
+        existing.insert(&key, item);
+    }
+}
+
+/// 忽略格式（空白、注释、数组换行）比较两个 TOML 项
+fn same_toml_item(a: &toml_edit::Item, b: &toml_edit::Item) -> bool {
+    use toml_edit::Item;
+
+    fn normalized(value: &toml_edit::Value) -> String {
+        let mut value = value.clone();
+        value.decor_mut().clear();
+        match &mut value {
+            toml_edit::Value::Array(arr) => arr.fmt(),
+            toml_edit::Value::InlineTable(tbl) => tbl.fmt(),
+            _ => {}
+        }
+        value.to_string()
+    }
+
+    match (a, b) {
+        (Item::Value(a), Item::Value(b)) => normalized(a) == normalized(b),
+        (Item::Table(a), Item::Table(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, item)| b.get(key).is_some_and(|other| same_toml_item(item, other)))
+        }
+        _ => false,
+    }
+}
+
 /// 通用 JSON 值到 TOML 值转换器（支持简单类型和浅层嵌套）
 ///
 /// 支持的类型转换：
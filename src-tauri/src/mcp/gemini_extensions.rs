@@ -0,0 +1,122 @@
+//! Gemini CLI 扩展自带的 MCP 服务器
+//!
+//! Gemini CLI 的扩展安装在 `~/.gemini/extensions/<目录>/`，`gemini-extension.json` 可以通过
+//! `mcpServers` 字段声明 MCP 服务器。Gemini CLI 启动时直接从扩展加载它们，不会写入 settings.json，
+//! 所以只看 settings.json 会漏掉这部分。这里扫描扩展清单，转换为带来源标记的只读条目。
+
+use std::fs;
+use std::path::{Path, MAIN_SEPARATOR_STR};
+
+use serde_json::Value;
+
+use crate::app_config::{McpApps, McpOrigin, McpServer, MCP_ORIGIN_GEMINI_EXTENSION};
+use crate::error::AppError;
+use crate::gemini_mcp::normalize_gemini_spec;
+
+use super::validation::validate_server_spec;
+
+/// 扩展清单文件名
+const EXTENSION_MANIFEST: &str = "gemini-extension.json";
+
+/// 扫描所有已安装扩展声明的 MCP 服务器（按 id 排序）
+///
+/// 单个扩展清单无法解析或服务器定义无效时跳过并记录日志，不影响其他扩展。
+pub fn scan_gemini_extensions() -> Result<Vec<McpServer>, AppError> {
+    let dir = crate::gemini_config::get_gemini_dir().join("extensions");
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut servers = Vec::new();
+    for entry in fs::read_dir(&dir)
+        .map_err(|e| AppError::io(&dir, e))?
+        .flatten()
+    {
+        let manifest = entry.path().join(EXTENSION_MANIFEST);
+        if !manifest.is_file() {
+            continue;
+        }
+        match read_extension(&entry.path(), &manifest) {
+            Ok(found) => servers.extend(found),
+            Err(e) => log::warn!("跳过无法解析的 Gemini 扩展 {}: {e}", manifest.display()),
+        }
+    }
+    servers.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(servers)
+}
+
+fn read_extension(ext_dir: &Path, manifest: &Path) -> Result<Vec<McpServer>, AppError> {
+    let text = fs::read_to_string(manifest).map_err(|e| AppError::io(manifest, e))?;
+    let root: Value = serde_json::from_str(&text).map_err(|e| AppError::json(manifest, e))?;
+    let name = root
+        .get("name")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            ext_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+    let Some(declared) = root.get("mcpServers").and_then(Value::as_object) else {
+        return Ok(Vec::new());
+    };
+
+    let ext_path = ext_dir.to_string_lossy();
+    let mut servers = Vec::new();
+    for (server_name, spec) in declared {
+        let mut spec = substitute_variables(spec, &ext_path);
+        normalize_gemini_spec(&mut spec);
+        if let Err(e) = validate_server_spec(&spec) {
+            log::warn!("跳过 Gemini 扩展 {name} 中无效的 MCP 服务器 '{server_name}': {e}");
+            continue;
+        }
+        servers.push(McpServer {
+            id: extension_server_id(&name, server_name),
+            name: server_name.clone(),
+            server: spec,
+            apps: McpApps {
+                gemini: true,
+                ..Default::default()
+            },
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+            origin: Some(McpOrigin {
+                kind: MCP_ORIGIN_GEMINI_EXTENSION.to_string(),
+                name: name.clone(),
+                path: manifest.display().to_string(),
+            }),
+        });
+    }
+    Ok(servers)
+}
+
+/// 扩展服务器的 id 带上扩展名前缀，避免与 settings.json 中的同名服务器冲突
+fn extension_server_id(extension: &str, server: &str) -> String {
+    format!("{extension}/{server}")
+}
+
+/// 替换清单中的路径变量：`${extensionPath}` 为扩展目录，`${/}` 与 `${pathSeparator}` 为路径分隔符
+fn substitute_variables(value: &Value, ext_path: &str) -> Value {
+    match value {
+        Value::String(s) => Value::String(
+            s.replace("${extensionPath}", ext_path)
+                .replace("${pathSeparator}", MAIN_SEPARATOR_STR)
+                .replace("${/}", MAIN_SEPARATOR_STR),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| substitute_variables(item, ext_path))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), substitute_variables(v, ext_path)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
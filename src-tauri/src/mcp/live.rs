@@ -0,0 +1,49 @@
+//! 读取 live 配置中的单个 MCP 条目，供外部修改检测使用
+
+use std::path::PathBuf;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+
+/// 应用存放 MCP 条目的 live 配置文件（不支持 MCP 的应用返回 None）
+pub fn live_config_path(app: &AppType) -> Option<PathBuf> {
+    match app {
+        AppType::Claude => Some(crate::config::get_claude_mcp_path()),
+        AppType::Codex => Some(crate::codex_config::get_codex_config_path()),
+        AppType::Gemini => Some(crate::gemini_config::get_gemini_settings_path()),
+        AppType::OpenCode => Some(crate::opencode_config::get_opencode_config_path()),
+        AppType::OpenClaw => None,
+    }
+}
+
+/// 读取 live 配置中 `id` 条目的文本形式，条目不存在时返回 None
+///
+/// JSON 配置按统一 MCP 结构序列化（Gemini、OpenCode 先做反向格式转换），Codex 取 TOML 表原文。
+pub fn read_live_server(app: &AppType, id: &str) -> Result<Option<String>, AppError> {
+    let spec = match app {
+        AppType::Claude => crate::claude_mcp::read_mcp_servers_map()?.remove(id),
+        AppType::Gemini => crate::gemini_mcp::read_mcp_servers_map()?.remove(id),
+        AppType::OpenCode => match crate::opencode_config::get_mcp_servers()?.get(id) {
+            Some(spec) => Some(super::opencode::convert_from_opencode_format(spec)?),
+            None => None,
+        },
+        AppType::Codex => return read_codex_server(id),
+        AppType::OpenClaw => None,
+    };
+    Ok(spec.map(|spec| spec.to_string()))
+}
+
+fn read_codex_server(id: &str) -> Result<Option<String>, AppError> {
+    let path = crate::codex_config::get_codex_config_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?;
+    let Ok(doc) = content.parse::<toml_edit::DocumentMut>() else {
+        return Ok(None);
+    };
+    Ok(doc
+        .get("mcp_servers")
+        .and_then(|servers| servers.get(id))
+        .map(|item| item.to_string()))
+}
@@ -8,11 +8,15 @@
 //! - `claude` - Claude MCP 同步和导入
 //! - `codex` - Codex MCP 同步和导入（含 TOML 转换）
 //! - `gemini` - Gemini MCP 同步和导入
+//! - `gemini_extensions` - 扫描 Gemini CLI 扩展自带的 MCP 服务器（只读）
 //! - `opencode` - OpenCode MCP 同步和导入（含 local/remote 格式转换）
+//! - `live` - 读取 live 配置中的单个条目（外部修改检测）
 
 mod claude;
 mod codex;
 mod gemini;
+mod gemini_extensions;
+mod live;
 mod opencode;
 mod validation;
 
@@ -28,6 +32,8 @@ pub use gemini::{
     import_from_gemini, remove_server_from_gemini, sync_enabled_to_gemini,
     sync_single_server_to_gemini,
 };
+pub use gemini_extensions::scan_gemini_extensions;
+pub use live::{live_config_path, read_live_server};
 pub use opencode::{
     import_from_opencode, remove_server_from_opencode, sync_single_server_to_opencode,
 };
@@ -264,6 +264,7 @@ pub fn import_from_opencode(config: &mut MultiAppConfig) -> Result<usize, AppErr
                     homepage: None,
                     docs: None,
                     tags: Vec::new(),
+                    origin: None,
                 },
             );
             changed += 1;
@@ -202,3 +202,40 @@ pub fn remove_plugin_by_prefix(prefix: &str) -> Result<(), AppError> {
 
     write_opencode_config(&config)
 }
+
+/// 在 `instructions` 数组中登记或移除一个文件路径，数组中的其他条目保持不变
+///
+/// 移除后数组为空时删除该键；没有变化时不写文件。
+pub fn set_instruction(path: &str, present: bool) -> Result<(), AppError> {
+    if !present && !get_opencode_config_path().exists() {
+        return Ok(());
+    }
+    let mut config = read_opencode_config()?;
+    let listed = config
+        .get("instructions")
+        .and_then(|v| v.as_array())
+        .is_some_and(|arr| arr.iter().any(|v| v.as_str() == Some(path)));
+    if listed == present {
+        return Ok(());
+    }
+
+    if present {
+        match config
+            .get_mut("instructions")
+            .and_then(|v| v.as_array_mut())
+        {
+            Some(arr) => arr.push(Value::String(path.to_string())),
+            None => config["instructions"] = json!([path]),
+        }
+    } else if let Some(arr) = config
+        .get_mut("instructions")
+        .and_then(|v| v.as_array_mut())
+    {
+        arr.retain(|v| v.as_str() != Some(path));
+        if arr.is_empty() {
+            config.as_object_mut().map(|obj| obj.remove("instructions"));
+        }
+    }
+
+    write_opencode_config(&config)
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use crate::agent::AgentRename;
+use crate::app_config::AppType;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PromptApps {
     #[serde(default)]
@@ -10,6 +13,37 @@ pub struct PromptApps {
     pub gemini: bool,
     #[serde(default)]
     pub opencode: bool,
+    #[serde(default)]
+    pub openclaw: bool,
+}
+
+impl PromptApps {
+    /// 检查指定应用是否启用
+    pub fn is_enabled_for(&self, app: &AppType) -> bool {
+        match app {
+            AppType::Claude => self.claude,
+            AppType::Codex => self.codex,
+            AppType::Gemini => self.gemini,
+            AppType::OpenCode => self.opencode,
+            AppType::OpenClaw => self.openclaw,
+        }
+    }
+
+    /// 设置指定应用的启用状态
+    pub fn set_enabled_for(&mut self, app: &AppType, enabled: bool) {
+        match app {
+            AppType::Claude => self.claude = enabled,
+            AppType::Codex => self.codex = enabled,
+            AppType::Gemini => self.gemini = enabled,
+            AppType::OpenCode => self.opencode = enabled,
+            AppType::OpenClaw => self.openclaw = enabled,
+        }
+    }
+
+    /// 是否至少对一个应用启用
+    pub fn any_enabled(&self) -> bool {
+        self.claude || self.codex || self.gemini || self.opencode || self.openclaw
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,10 +53,184 @@ pub struct Prompt {
     pub content: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// 标签（如从 Markdown 笔记导入时 frontmatter 中的 `tags`）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     #[serde(default)]
     pub apps: PromptApps,
+    /// 优先级（越小越靠前）：同一 app 启用多个提示词时按此顺序拼接
+    #[serde(rename = "sortIndex", default, skip_serializing_if = "Option::is_none")]
+    pub sort_index: Option<usize>,
     #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
     pub created_at: Option<i64>,
     #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<i64>,
 }
+
+/// 提示词校验问题的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptIssueLevel {
+    /// 仍会保存，由前端提示
+    Warning,
+    /// 拒绝保存
+    Error,
+}
+
+/// 提示词的单条校验问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptIssue {
+    /// 相关的应用；与应用无关的问题为 None
+    pub app: Option<String>,
+    pub level: PromptIssueLevel,
+    /// 机器可读的问题代码，供前端本地化
+    pub code: String,
+    /// 中文说明
+    pub message: String,
+}
+
+/// 应用提示词文件的大小限制（字节，按启用提示词拼接后的托管区域计算）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptSizeLimit {
+    /// 超过时给出警告
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warn_bytes: Option<u64>,
+    /// 超过时拒绝保存
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+}
+
+impl PromptSizeLimit {
+    /// 各应用的默认限制
+    ///
+    /// Codex 只读取 `AGENTS.md` 的前 32 KiB（`project_doc_max_bytes`），OpenClaw 会截断超过 20000 字符的
+    /// 工作区文件，超出部分实际不会生效，因此直接拒绝；Claude Code 在 `CLAUDE.md` 超过 40k 字符时提示影响性能。
+    pub fn default_for(app: &AppType) -> Self {
+        let (warn, max) = match app {
+            AppType::Claude => (40_000, None),
+            AppType::Codex => (24 * 1024, Some(32 * 1024)),
+            AppType::Gemini | AppType::OpenCode => (100_000, None),
+            AppType::OpenClaw => (16_000, Some(20_000)),
+        };
+        Self {
+            warn_bytes: Some(warn),
+            max_bytes: max,
+        }
+    }
+}
+
+/// 提示词导出包格式标识
+pub const PROMPT_BUNDLE_FORMAT: &str = "cc-switch-prompts";
+/// 当前导出包版本
+pub const PROMPT_BUNDLE_VERSION: u32 = 1;
+
+/// 可移植的提示词导出包（JSON）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptBundle {
+    /// 固定为 [`PROMPT_BUNDLE_FORMAT`]
+    pub format: String,
+    pub version: u32,
+    /// 导出时间（Unix 毫秒）
+    pub exported_at: i64,
+    /// 按优先级排列的提示词
+    #[serde(default)]
+    pub prompts: Vec<Prompt>,
+}
+
+/// 提示词导出包导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptImportResult {
+    /// 新增的提示词 id
+    pub imported: Vec<String>,
+    /// 被覆盖的提示词 id
+    pub overwritten: Vec<String>,
+    /// 跳过的提示词 id
+    pub skipped: Vec<String>,
+    pub renamed: Vec<AgentRename>,
+}
+
+/// app 提示词文件的同步预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFilePreview {
+    pub app: String,
+    /// 将被写入的文件路径
+    pub path: String,
+    /// 启用的提示词展开引用、替换模板变量后拼接的结果（托管区域正文，不含横幅）
+    pub rendered: String,
+    /// 同步后文件的完整内容（含托管区域外的用户内容）；为空表示文件将被删除或保持不存在
+    pub content: String,
+    /// 与文件当前内容是否不同
+    pub changed: bool,
+}
+
+/// 记录提示词历史快照的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptRevisionReason {
+    /// 批量查找替换改写前
+    BulkReplace,
+    /// 回滚到历史版本前
+    Rollback,
+    /// 文件中的外部修改被覆盖前（外部修改策略为 `versions`）
+    ExternalEdit,
+}
+
+impl PromptRevisionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BulkReplace => "bulk_replace",
+            Self::Rollback => "rollback",
+            Self::ExternalEdit => "external_edit",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "bulk_replace" => Some(Self::BulkReplace),
+            "rollback" => Some(Self::Rollback),
+            "external_edit" => Some(Self::ExternalEdit),
+            _ => None,
+        }
+    }
+}
+
+/// 提示词的一个历史快照
+///
+/// 对应数据库 `prompt_history` 表，在不经编辑器的批量变更前记录，可单独回滚。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptRevision {
+    pub prompt_id: String,
+    /// 版本号（同一提示词内从 1 递增）
+    pub version: i64,
+    pub reason: PromptRevisionReason,
+    /// 快照时间（Unix 毫秒）
+    pub created_at: i64,
+    /// 变更前的完整内容
+    pub snapshot: Prompt,
+}
+
+/// 回收站中的提示词
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedPrompt {
+    /// 删除前的完整内容（含各 app 的启用状态）
+    pub prompt: Prompt,
+    /// 删除时间（Unix 秒）
+    pub deleted_at: i64,
+    /// 删除时的项目挂载，恢复时一并写回（项目已取消登记的忽略）
+    #[serde(default)]
+    pub project_links: Vec<TrashedPromptLink>,
+}
+
+/// 回收站中记录的一次项目挂载
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedPromptLink {
+    pub project_id: String,
+    pub app: String,
+}
@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use serde::{Deserialize, Serialize};
+
 use crate::app_config::AppType;
 use crate::codex_config::get_codex_auth_path;
 use crate::config::get_claude_settings_path;
@@ -7,26 +9,66 @@ use crate::error::AppError;
 use crate::gemini_config::get_gemini_dir;
 use crate::openclaw_config::get_openclaw_dir;
 use crate::opencode_config::get_opencode_dir;
+use crate::settings::PromptImportNormalization;
+
+/// 提示词托管区域的起止标记：cc-switch 只改写两者之间的内容，区域外的用户内容保持不变
+pub const PROMPT_REGION_BEGIN: &str = "<!-- cc-switch:prompt:begin -->";
+pub const PROMPT_REGION_END: &str = "<!-- cc-switch:prompt:end -->";
+
+/// OpenCode 提示词独立文件名（位于 OpenCode 配置目录，登记在 `opencode.json` 的 `instructions` 中）
+pub const OPENCODE_INSTRUCTIONS_FILE: &str = "cc-switch-prompts.md";
+
+/// OpenCode 全局提示词的写入位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenCodePromptTarget {
+    /// `~/.config/opencode/AGENTS.md` 中的托管区域
+    #[default]
+    Agents,
+    /// 独立的 [`OPENCODE_INSTRUCTIONS_FILE`]，并登记到 `opencode.json` 的 `instructions` 数组
+    Instructions,
+}
+
+/// 指定写入位置下 OpenCode 全局提示词文件的路径
+pub fn opencode_prompt_path(target: OpenCodePromptTarget) -> PathBuf {
+    match target {
+        OpenCodePromptTarget::Agents => {
+            get_opencode_dir().join(prompt_file_name(&AppType::OpenCode))
+        }
+        OpenCodePromptTarget::Instructions => get_opencode_dir().join(OPENCODE_INSTRUCTIONS_FILE),
+    }
+}
 
 /// 返回指定应用所使用的提示词文件路径。
+///
+/// OpenCode 按设置的写入位置返回 `AGENTS.md` 或独立的 instructions 文件，见 [`OpenCodePromptTarget`]；
+/// OpenClaw 返回 `~/.openclaw/workspace/AGENTS.md`。
 pub fn prompt_file_path(app: &AppType) -> Result<PathBuf, AppError> {
     let base_dir: PathBuf = match app {
         AppType::Claude => get_base_dir_with_fallback(get_claude_settings_path(), ".claude")?,
         AppType::Codex => get_base_dir_with_fallback(get_codex_auth_path(), ".codex")?,
         AppType::Gemini => get_gemini_dir(),
-        AppType::OpenCode => get_opencode_dir(),
-        AppType::OpenClaw => get_openclaw_dir(),
+        AppType::OpenCode => {
+            return Ok(opencode_prompt_path(
+                crate::settings::get_opencode_prompt_target(),
+            ))
+        }
+        // OpenClaw 只加载工作区中的 AGENTS.md，与 agent 区块共用该文件
+        AppType::OpenClaw => get_openclaw_dir().join("workspace"),
     };
 
-    let filename = match app {
+    Ok(base_dir.join(prompt_file_name(app)))
+}
+
+/// 指定应用在项目目录中的提示词文件名（除 OpenCode 改用 instructions 文件外，也是全局文件名）
+pub fn prompt_file_name(app: &AppType) -> &'static str {
+    match app {
         AppType::Claude => "CLAUDE.md",
         AppType::Codex => "AGENTS.md",
         AppType::Gemini => "GEMINI.md",
         AppType::OpenCode => "AGENTS.md",
         AppType::OpenClaw => "AGENTS.md", // OpenClaw uses AGENTS.md for agent instructions
-    };
-
-    Ok(base_dir.join(filename))
+    }
 }
 
 fn get_base_dir_with_fallback(
@@ -45,3 +87,153 @@ fn get_base_dir_with_fallback(
             )
         })
 }
+
+/// 定位托管区域，返回 `[start, end)`（含结束标记后的换行）
+fn find_prompt_region(content: &str) -> Option<(usize, usize)> {
+    let start = content.find(PROMPT_REGION_BEGIN)?;
+    let end = start + content[start..].find(PROMPT_REGION_END)? + PROMPT_REGION_END.len();
+    let end = if content[end..].starts_with('\n') {
+        end + 1
+    } else {
+        end
+    };
+    Some((start, end))
+}
+
+/// 托管区域的正文（不含首尾标记），文件中没有托管区域时返回 None
+pub fn prompt_region_body(content: &str) -> Option<&str> {
+    let (start, end) = find_prompt_region(content)?;
+    content[start..end]
+        .strip_prefix(PROMPT_REGION_BEGIN)?
+        .trim_end_matches('\n')
+        .strip_suffix(PROMPT_REGION_END)
+        .map(|body| body.trim_start_matches('\n'))
+}
+
+/// 去掉托管区域，只保留区域外的用户内容
+pub fn strip_prompt_region(content: &str) -> String {
+    let Some((start, end)) = find_prompt_region(content) else {
+        return content.to_string();
+    };
+    let before = &content[..start];
+    let after = &content[end..];
+    if before.trim().is_empty() {
+        after.trim_start_matches('\n').to_string()
+    } else {
+        format!("{before}{after}")
+    }
+}
+
+/// 用 `prompt` 替换文件内容中的托管区域（区域位于文件开头，用户内容在其后）
+///
+/// `prompt` 为空时移除区域。文件中还没有托管区域时，区域外内容可能是旧版本整文件覆盖写入的
+/// 提示词：去掉 agent 区块后若与 `legacy` 中的某一段一致，就把这段旧内容丢弃，避免重复。
+pub fn replace_prompt_region(content: &str, prompt: &str, legacy: &[&str]) -> String {
+    let mut user = strip_prompt_region(content);
+    if find_prompt_region(content).is_none() {
+        let own = crate::agents::strip_managed_agent_blocks(&user);
+        let own = own.trim();
+        if !own.is_empty() && legacy.iter().any(|text| text.trim() == own) {
+            user = user
+                .replacen(own, "", 1)
+                .trim_start_matches('\n')
+                .to_string();
+        }
+    }
+
+    if prompt.trim().is_empty() {
+        return if user.trim().is_empty() {
+            String::new()
+        } else {
+            user
+        };
+    }
+
+    let mut region = format!("{PROMPT_REGION_BEGIN}\n{prompt}");
+    if !region.ends_with('\n') {
+        region.push('\n');
+    }
+    region.push_str(PROMPT_REGION_END);
+    region.push('\n');
+
+    let user = user.trim_start_matches('\n');
+    if user.trim().is_empty() {
+        region
+    } else {
+        format!("{region}\n{user}")
+    }
+}
+
+/// 按设置规范化导入的提示词内容，避免反复导入时累积 cc-switch 自己写入的内容与空白
+pub fn normalize_imported_prompt(content: &str, options: &PromptImportNormalization) -> String {
+    let mut text = content.to_string();
+    if options.normalize_line_endings {
+        text = text.replace("\r\n", "\n").replace('\r', "\n");
+    }
+    if options.strip_managed_sections {
+        text = crate::agents::strip_managed_agent_blocks(&text);
+        text = crate::services::agents_md::strip_region(&text);
+        text = strip_prompt_region(&text);
+    }
+    if options.trim_trailing_whitespace {
+        let trimmed: Vec<&str> = text.lines().map(str::trim_end).collect();
+        text = trimmed.join("\n").trim_end().to_string();
+        if !text.is_empty() {
+            text.push('\n');
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_strips_managed_sections_and_whitespace() {
+        let raw = "# Rules  \r\nBe nice.\r\n\r\n\
+<!-- cc-switch:agents:begin -->\n\
+<!-- cc-switch:agent:a -->\n# A\n\nAlpha.\n\n<!-- /cc-switch:agent:a -->\n\
+<!-- cc-switch:agents:end -->\n\n\n";
+        let all = PromptImportNormalization {
+            normalize_line_endings: true,
+            strip_managed_sections: true,
+            trim_trailing_whitespace: true,
+        };
+        assert_eq!(normalize_imported_prompt(raw, &all), "# Rules\nBe nice.\n");
+
+        let none = PromptImportNormalization::default();
+        assert_eq!(normalize_imported_prompt(raw, &none), raw);
+    }
+
+    #[test]
+    fn prompt_region_replaces_only_its_own_content() {
+        let with_region = replace_prompt_region("# Notes\n", "Be concise.\n", &[]);
+        assert_eq!(
+            with_region,
+            format!("{PROMPT_REGION_BEGIN}\nBe concise.\n{PROMPT_REGION_END}\n\n# Notes\n")
+        );
+
+        let updated = replace_prompt_region(&with_region, "Be thorough.", &[]);
+        assert!(updated.contains("Be thorough.\n"));
+        assert!(!updated.contains("Be concise."));
+        assert!(updated.ends_with("\n\n# Notes\n"));
+
+        assert_eq!(replace_prompt_region(&updated, "", &[]), "# Notes\n");
+        assert_eq!(strip_prompt_region(&updated), "# Notes\n");
+    }
+
+    #[test]
+    fn legacy_whole_file_prompt_is_not_duplicated() {
+        // 旧版本写入的提示词后面跟着 agent 区块：提示词被替换为区域，agent 区块保留
+        let legacy = "Old prompt\n\n<!-- cc-switch:agent:a -->\n# A\n\nAlpha.\n\n<!-- /cc-switch:agent:a -->\n";
+        let result = replace_prompt_region(legacy, "Old prompt\n", &["Old prompt\n"]);
+        assert_eq!(result.matches("Old prompt").count(), 1);
+        assert!(result.starts_with(PROMPT_REGION_BEGIN));
+        assert!(result.contains("<!-- /cc-switch:agent:a -->"));
+
+        // 与已知提示词不一致的内容视为用户内容
+        let kept = replace_prompt_region("Hand-written\n", "New\n", &["Old prompt\n"]);
+        assert!(kept.ends_with("\n\nHand-written\n"));
+    }
+}
@@ -23,7 +23,7 @@ pub struct Provider {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "sortIndex")]
     pub sort_index: Option<usize>,
-    /// 备注信息
+    /// 备注信息（Markdown，可附带附件，见 `provider_attachments` 表）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
     /// 供应商元数据（不写入 live 配置，仅存于 ~/.cc-switch/config.json）
@@ -40,6 +40,12 @@ pub struct Provider {
     #[serde(default)]
     #[serde(rename = "inFailoverQueue")]
     pub in_failover_queue: bool,
+    /// 是否已归档（不出现在切换列表与故障转移队列中，配置保留）
+    #[serde(default)]
+    pub archived: bool,
+    /// 标签（如厂商、地区、价格档位），用于分组与筛选
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 impl Provider {
@@ -63,8 +69,47 @@ impl Provider {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
+        }
+    }
+
+    /// 是否为只读引用（来自团队模板仓库）
+    pub fn is_reference(&self) -> bool {
+        self.meta
+            .as_ref()
+            .and_then(|m| m.origin.as_ref())
+            .is_some_and(|o| o.kind == ProviderOriginKind::Reference)
+    }
+
+    /// 信任级别：未显式设置时，官方、厂商官方与云服务商分类视为官方，其余视为未验证
+    pub fn trust_level(&self) -> ProviderTrust {
+        if let Some(trust) = self.meta.as_ref().and_then(|m| m.trust) {
+            return trust;
+        }
+        match self.category.as_deref() {
+            Some("official" | "cn_official" | "cloud_provider") => ProviderTrust::Official,
+            _ => ProviderTrust::Unverified,
         }
     }
+
+    /// 是否可以不经确认地交给自动化功能使用
+    pub fn allowed_in_automation(&self) -> bool {
+        self.trust_level() != ProviderTrust::Unverified
+            || self
+                .meta
+                .as_ref()
+                .and_then(|m| m.automation_allowed)
+                .unwrap_or(false)
+    }
+
+    /// `now`（Unix 秒）时是否处于维护窗口内
+    pub fn in_maintenance(&self, now: i64) -> bool {
+        self.meta
+            .as_ref()
+            .and_then(|m| m.maintenance_until)
+            .is_some_and(|until| until > now)
+    }
 }
 
 /// 供应商管理器
@@ -240,6 +285,80 @@ pub struct ProviderMeta {
     /// - "ANTHROPIC_API_KEY": 少数供应商需要原生 API Key
     #[serde(rename = "apiKeyField", skip_serializing_if = "Option::is_none")]
     pub api_key_field: Option<String>,
+    /// 来源信息；为空表示本地创建
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<ProviderOrigin>,
+    /// 维护窗口结束时间（Unix 秒）；到期前健康检查、故障转移与告警忽略该供应商
+    #[serde(rename = "maintenanceUntil", skip_serializing_if = "Option::is_none")]
+    pub maintenance_until: Option<i64>,
+    /// 信任级别；为空时按分类推断（见 [`Provider::trust_level`]）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trust: Option<ProviderTrust>,
+    /// 未验证的供应商是否允许被自动化功能（故障转移、自动选择最快供应商）使用
+    #[serde(rename = "automationAllowed", skip_serializing_if = "Option::is_none")]
+    pub automation_allowed: Option<bool>,
+    /// 余额接口地址；为空时使用余额接口类型的默认地址
+    #[serde(rename = "quotaUrl", skip_serializing_if = "Option::is_none")]
+    pub quota_url: Option<String>,
+    /// 余额接口类型；为空时按 Base URL 的域名识别
+    #[serde(rename = "quotaType", skip_serializing_if = "Option::is_none")]
+    pub quota_type: Option<QuotaType>,
+    /// 该供应商为当前供应商时导出的环境变量（变量名 -> 值）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+}
+
+/// 余额查询接口类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaType {
+    /// OpenAI 兼容的 `dashboard/billing` 接口（One API / New API 等中转站）
+    OpenaiBilling,
+    Openrouter,
+    Deepseek,
+    Siliconflow,
+    Moonshot,
+}
+
+/// 供应商信任级别
+///
+/// 未验证的供应商需要用户确认后才能加入故障转移队列，也不会被自动选择，
+/// 避免代码与密钥在用户不知情时经过来路不明的中转。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderTrust {
+    /// 厂商官方接口
+    Official,
+    /// 用户确认过的中转
+    VerifiedRelay,
+    /// 未经确认的中转
+    Unverified,
+}
+
+/// 供应商来源类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderOriginKind {
+    /// 本地创建或导入，可自由编辑
+    #[default]
+    Local,
+    /// 来自团队模板仓库的只读引用，只能复制后编辑
+    Reference,
+    /// 由统一供应商生成，`source` 为统一供应商 id，同步时随统一供应商更新
+    Universal,
+}
+
+/// 供应商来源
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderOrigin {
+    pub kind: ProviderOriginKind,
+    /// 来源标识（如模板仓库地址）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// 最近一次从来源同步的时间（Unix 毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synced_at: Option<i64>,
 }
 
 impl ProviderManager {
@@ -434,6 +553,8 @@ impl UniversalProvider {
             icon: self.icon.clone(),
             icon_color: self.icon_color.clone(),
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         })
     }
 
@@ -499,6 +620,8 @@ requires_openai_auth = true"#
             icon: self.icon.clone(),
             icon_color: self.icon_color.clone(),
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         })
     }
 
@@ -534,6 +657,8 @@ requires_openai_auth = true"#
             icon: self.icon.clone(),
             icon_color: self.icon_color.clone(),
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         })
     }
 }
@@ -639,14 +764,16 @@ pub struct OpenCodeModelLimit {
 mod tests {
     use super::{
         ClaudeModelConfig, CodexModelConfig, GeminiModelConfig, OpenCodeProviderConfig, Provider,
-        ProviderManager, ProviderMeta, UniversalProvider,
+        ProviderManager, ProviderMeta, ProviderTrust, UniversalProvider,
     };
     use serde_json::json;
 
     #[test]
     fn provider_meta_serializes_pricing_model_source() {
-        let mut meta = ProviderMeta::default();
-        meta.pricing_model_source = Some("response".to_string());
+        let meta = ProviderMeta {
+            pricing_model_source: Some("response".to_string()),
+            ..ProviderMeta::default()
+        };
 
         let value = serde_json::to_value(&meta).expect("serialize ProviderMeta");
 
@@ -667,6 +794,30 @@ mod tests {
         assert!(value.get("pricingModelSource").is_none());
     }
 
+    #[test]
+    fn trust_level_defaults_by_category() {
+        let mut provider = Provider::with_id("p".into(), "P".into(), json!({}), None);
+        assert_eq!(provider.trust_level(), ProviderTrust::Unverified);
+        assert!(!provider.allowed_in_automation());
+
+        provider.category = Some("cn_official".into());
+        assert_eq!(provider.trust_level(), ProviderTrust::Official);
+
+        provider.category = Some("third_party".into());
+        provider.meta = Some(ProviderMeta {
+            automation_allowed: Some(true),
+            ..ProviderMeta::default()
+        });
+        assert_eq!(provider.trust_level(), ProviderTrust::Unverified);
+        assert!(provider.allowed_in_automation());
+
+        provider.meta = Some(ProviderMeta {
+            trust: Some(ProviderTrust::VerifiedRelay),
+            ..ProviderMeta::default()
+        });
+        assert!(provider.allowed_in_automation());
+    }
+
     #[test]
     fn provider_with_id_populates_defaults() {
         let settings_config = json!({
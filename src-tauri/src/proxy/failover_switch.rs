@@ -9,6 +9,7 @@
 
 use crate::database::Database;
 use crate::error::AppError;
+use crate::services::{ProviderAuditOrigin, ProviderService};
 use std::collections::HashSet;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -99,7 +100,15 @@ impl FailoverSwitchManager {
         log::info!("[FO-001] 切换: {app_type} → {provider_name}");
 
         // 1. 更新数据库 is_current
+        let previous_id = self.db.get_current_provider(app_type).ok().flatten();
         self.db.set_current_provider(app_type, provider_id)?;
+        ProviderService::record_switch_audit(
+            &self.db,
+            app_type,
+            previous_id.as_deref(),
+            provider_id,
+            ProviderAuditOrigin::Failover,
+        );
 
         // 2. 更新本地 settings（设备级）
         let app_type_enum = crate::app_config::AppType::from_str(app_type)
@@ -140,6 +149,14 @@ impl FailoverSwitchManager {
             if let Err(e) = app.emit("provider-switched", event_data) {
                 log::error!("[Failover] 发射事件失败: {e}");
             }
+
+            // 预热新供应商（未开启时直接跳过）
+            crate::services::stream_check::StreamCheckService::spawn_warm_up(
+                app,
+                app_type_enum,
+                provider_id.to_string(),
+                "failover",
+            );
         }
 
         Ok(true)
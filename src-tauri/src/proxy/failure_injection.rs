@@ -0,0 +1,138 @@
+//! 故障注入（调试用）
+//!
+//! 让转发器把指定供应商的请求直接视为失败，而不真正发出请求，
+//! 用于在真实故障发生前验证故障转移队列、通知与回切行为。
+//!
+//! 注入状态只保存在内存中，应用重启后自动清除。
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use super::error::ProxyError;
+
+/// 注入的故障类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// 请求超时
+    Timeout,
+    /// 连接失败
+    Connection,
+    /// 上游 5xx
+    ServerError,
+    /// 上游 429 限流
+    RateLimit,
+}
+
+impl FailureKind {
+    /// 构造与真实故障一致的 ProxyError，使其走相同的熔断 / 故障转移路径
+    fn to_proxy_error(self) -> ProxyError {
+        match self {
+            FailureKind::Timeout => ProxyError::Timeout("[注入故障] 模拟请求超时".to_string()),
+            FailureKind::Connection => {
+                ProxyError::ForwardFailed("[注入故障] 模拟连接失败".to_string())
+            }
+            FailureKind::ServerError => ProxyError::UpstreamError {
+                status: 503,
+                body: Some(injected_body("server_error")),
+            },
+            FailureKind::RateLimit => ProxyError::UpstreamError {
+                status: 429,
+                body: Some(injected_body("rate_limit")),
+            },
+        }
+    }
+}
+
+fn injected_body(kind: &str) -> String {
+    serde_json::json!({
+        "error": {
+            "type": kind,
+            "message": "Injected failure from cc-switch failover test mode"
+        }
+    })
+    .to_string()
+}
+
+/// 当前生效的故障注入
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InjectedFailure {
+    pub app_type: String,
+    pub provider_id: String,
+    pub kind: FailureKind,
+}
+
+/// key 格式与熔断器一致: "app_type:provider_id"
+static INJECTED: Lazy<RwLock<HashMap<String, FailureKind>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+fn key(app_type: &str, provider_id: &str) -> String {
+    format!("{app_type}:{provider_id}")
+}
+
+/// 为供应商注入故障；`kind` 为 None 时清除
+pub fn set_failure(app_type: &str, provider_id: &str, kind: Option<FailureKind>) {
+    let mut map = INJECTED.write().unwrap_or_else(|e| e.into_inner());
+    match kind {
+        Some(kind) => {
+            log::warn!("[{app_type}] 已为供应商 {provider_id} 注入故障: {kind:?}");
+            map.insert(key(app_type, provider_id), kind);
+        }
+        None => {
+            if map.remove(&key(app_type, provider_id)).is_some() {
+                log::info!("[{app_type}] 已清除供应商 {provider_id} 的注入故障");
+            }
+        }
+    }
+}
+
+/// 清除所有注入的故障
+pub fn clear_all() {
+    INJECTED.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// 列出当前生效的故障注入
+pub fn list() -> Vec<InjectedFailure> {
+    let map = INJECTED.read().unwrap_or_else(|e| e.into_inner());
+    let mut items: Vec<InjectedFailure> = map
+        .iter()
+        .filter_map(|(k, kind)| {
+            let (app_type, provider_id) = k.split_once(':')?;
+            Some(InjectedFailure {
+                app_type: app_type.to_string(),
+                provider_id: provider_id.to_string(),
+                kind: *kind,
+            })
+        })
+        .collect();
+    items.sort_by(|a, b| (&a.app_type, &a.provider_id).cmp(&(&b.app_type, &b.provider_id)));
+    items
+}
+
+/// 若供应商被注入了故障，返回应模拟的错误
+pub(crate) fn injected_error(app_type: &str, provider_id: &str) -> Option<ProxyError> {
+    let map = INJECTED.read().unwrap_or_else(|e| e.into_inner());
+    map.get(&key(app_type, provider_id))
+        .map(|kind| kind.to_proxy_error())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn injected_failure_maps_to_proxy_error_until_cleared() {
+        set_failure("claude", "fi-test", Some(FailureKind::RateLimit));
+        assert!(matches!(
+            injected_error("claude", "fi-test"),
+            Some(ProxyError::UpstreamError { status: 429, .. })
+        ));
+        assert!(injected_error("codex", "fi-test").is_none());
+
+        set_failure("claude", "fi-test", None);
+        assert!(injected_error("claude", "fi-test").is_none());
+    }
+}
@@ -6,6 +6,7 @@ use super::{
     body_filter::filter_private_params_with_whitelist,
     error::*,
     failover_switch::FailoverSwitchManager,
+    failure_injection,
     provider_router::ProviderRouter,
     providers::{get_adapter, ProviderAdapter, ProviderType},
     thinking_budget_rectifier::{rectify_thinking_budget, should_rectify_thinking_budget},
@@ -195,10 +196,15 @@ impl RequestForwarder {
             }
 
             // 转发请求（每个 Provider 只尝试一次，重试由客户端控制）
-            match self
-                .forward(provider, endpoint, &body, &headers, adapter.as_ref())
-                .await
-            {
+            // 调试用故障注入：直接返回模拟错误，走与真实故障相同的处理路径
+            let result = match failure_injection::injected_error(app_type_str, &provider.id) {
+                Some(err) => Err(err),
+                None => {
+                    self.forward(provider, endpoint, &body, &headers, adapter.as_ref())
+                        .await
+                }
+            };
+            match result {
                 Ok(response) => {
                     // 成功：记录成功并更新熔断器
                     let _ = self
@@ -650,6 +656,14 @@ impl RequestForwarder {
                             Some(e.to_string()),
                         )
                         .await;
+                    if let ProxyError::UpstreamError { status, body } = &e {
+                        self.router.record_upstream_error(
+                            &provider.id,
+                            app_type_str,
+                            *status,
+                            body.as_deref(),
+                        );
+                    }
 
                     // 分类错误
                     let category = self.categorize_proxy_error(&e);
@@ -7,6 +7,7 @@ use crate::provider::Provider;
 use crate::proxy::{
     extract_session_id,
     forwarder::RequestForwarder,
+    in_flight::InFlightGuard,
     server::ProxyState,
     types::{AppProxyConfig, RectifierConfig},
     ProxyError,
@@ -59,6 +60,8 @@ pub struct RequestContext {
     pub session_id: String,
     /// 整流器配置
     pub rectifier_config: RectifierConfig,
+    /// 在途请求计数（从选定供应商开始，到响应体发送完毕为止）
+    in_flight: InFlightGuard,
 }
 
 impl RequestContext {
@@ -144,6 +147,8 @@ impl RequestContext {
             session_id
         );
 
+        let in_flight = state.in_flight.begin(app_type_str, &provider.id);
+
         Ok(Self {
             start_time,
             app_config,
@@ -156,9 +161,19 @@ impl RequestContext {
             app_type,
             session_id,
             rectifier_config,
+            in_flight,
         })
     }
 
+    /// 结束请求处理：在途计数转到实际处理请求的供应商（故障转移后可能已变化），
+    /// 并跟随响应体存活到发送完毕
+    pub fn into_tracked_response(
+        self,
+        response: axum::response::Response,
+    ) -> axum::response::Response {
+        self.in_flight.rebind(&self.provider.id).attach(response)
+    }
+
     /// 从 URI 提取模型名称（Gemini 专用）
     ///
     /// Gemini API 的模型名称在 URI 中，格式如：
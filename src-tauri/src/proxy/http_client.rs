@@ -423,6 +423,18 @@ pub fn get_for_provider(proxy_config: Option<&ProviderProxyConfig>) -> Client {
     get()
 }
 
+/// 构建一个新的 HTTP 客户端，不与其他请求共享连接池
+///
+/// 代理选择与 [`get_for_provider`] 相同。测速需要从新建连接开始计时，不能复用全局客户端的空闲连接。
+pub fn build_fresh_for_provider(
+    proxy_config: Option<&ProviderProxyConfig>,
+) -> Result<Client, String> {
+    if let Some(client) = build_client_for_provider(proxy_config) {
+        return Ok(client);
+    }
+    build_client(get_current_proxy_url().as_deref())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
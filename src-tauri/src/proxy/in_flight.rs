@@ -0,0 +1,147 @@
+//! 在途请求跟踪
+//!
+//! 按 (应用, 供应商) 统计代理正在处理的请求数。流式响应的计数一直持续到响应体发送完毕
+//! （或客户端断开），因此可以据此判断某个供应商上的长连接是否都已结束。
+//! 热切换供应商时先让新请求走新供应商，再等旧供应商的在途请求结束后才改写磁盘上的配置。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::response::Response;
+use futures::StreamExt;
+use tokio::sync::Notify;
+
+type Key = (String, String);
+
+#[derive(Default)]
+pub struct InFlightRequests {
+    counts: Mutex<HashMap<Key, usize>>,
+    changed: Notify,
+}
+
+impl InFlightRequests {
+    /// 登记一个发往 `provider_id` 的请求，返回的守卫被丢弃时结束计数
+    pub fn begin(self: &Arc<Self>, app_type: &str, provider_id: &str) -> InFlightGuard {
+        let key = (app_type.to_string(), provider_id.to_string());
+        *self.lock().entry(key.clone()).or_default() += 1;
+        InFlightGuard {
+            requests: Arc::clone(self),
+            key,
+        }
+    }
+
+    /// 某个供应商当前的在途请求数
+    pub fn count(&self, app_type: &str, provider_id: &str) -> usize {
+        self.lock()
+            .get(&(app_type.to_string(), provider_id.to_string()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// 全部在途请求数
+    pub fn total(&self) -> usize {
+        self.lock().values().sum()
+    }
+
+    /// 等待供应商的在途请求全部结束，超时返回 false
+    pub async fn wait_drained(&self, app_type: &str, provider_id: &str, timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // 先注册通知再检查计数，避免错过两者之间发生的结束事件
+            let notified = self.changed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.count(app_type, provider_id) == 0 {
+                return true;
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return self.count(app_type, provider_id) == 0;
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Key, usize>> {
+        self.counts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn finish(&self, key: &Key) {
+        let mut counts = self.lock();
+        if let Some(count) = counts.get_mut(key) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                counts.remove(key);
+            }
+        }
+        drop(counts);
+        self.changed.notify_waiters();
+    }
+}
+
+/// 在途请求守卫，丢弃时结束计数
+pub struct InFlightGuard {
+    requests: Arc<InFlightRequests>,
+    key: Key,
+}
+
+impl InFlightGuard {
+    /// 改为计入另一个供应商（同一供应商时原样返回）
+    pub fn rebind(self, provider_id: &str) -> InFlightGuard {
+        if self.key.1 == provider_id {
+            return self;
+        }
+        self.requests.begin(&self.key.0, provider_id)
+    }
+
+    /// 让守卫跟随响应体存活，直到响应体发送完毕或被丢弃
+    pub fn attach(self, response: Response) -> Response {
+        let (parts, body) = response.into_parts();
+        let stream = body.into_data_stream().map(move |chunk| {
+            let _ = &self;
+            chunk
+        });
+        Response::from_parts(parts, Body::from_stream(stream))
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.requests.finish(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn guard_attached_to_response_keeps_request_in_flight() {
+        let requests = Arc::new(InFlightRequests::default());
+        let guard = requests.begin("claude", "old");
+        assert_eq!(requests.count("claude", "old"), 1);
+        assert!(
+            !requests
+                .wait_drained("claude", "old", Duration::from_millis(20))
+                .await
+        );
+
+        let response = guard.attach(Response::new(Body::from("streamed")));
+        assert_eq!(requests.count("claude", "old"), 1);
+
+        let waiter = {
+            let requests = Arc::clone(&requests);
+            tokio::spawn(async move {
+                requests
+                    .wait_drained("claude", "old", Duration::from_secs(5))
+                    .await
+            })
+        };
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"streamed");
+        assert!(waiter.await.unwrap());
+        assert_eq!(requests.total(), 0);
+    }
+}
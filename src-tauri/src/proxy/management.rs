@@ -0,0 +1,112 @@
+//! 管理接口（`/cc-switch/v1/...`）
+//!
+//! 供自动化脚本通过代理端口管理 cc-switch。认证与权限检查都在
+//! [`ApiAccessService`] 中完成，这里只负责取出 Bearer 令牌、调用服务并映射状态码：
+//! 缺少/无效令牌为 401，权限不足为 403，参数错误为 400。
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use serde::Serialize;
+use serde_json::json;
+use tauri::Manager;
+
+use super::server::ProxyState;
+use crate::error::AppError;
+use crate::services::api_access::{ApiAccessService, ApiCaller};
+use crate::store::AppState;
+
+pub(crate) fn routes() -> Router<ProxyState> {
+    Router::new()
+        .route("/cc-switch/v1/providers/:app", get(list_providers))
+        .route(
+            "/cc-switch/v1/providers/:app/:id/switch",
+            post(switch_provider),
+        )
+        .route("/cc-switch/v1/providers/:app/:id", delete(delete_provider))
+}
+
+async fn list_providers(
+    State(state): State<ProxyState>,
+    headers: HeaderMap,
+    Path(app): Path<String>,
+) -> Response {
+    run(state, &headers, move |app_state, caller| {
+        ApiAccessService::list_providers(app_state, &caller, &app)
+    })
+    .await
+}
+
+async fn switch_provider(
+    State(state): State<ProxyState>,
+    headers: HeaderMap,
+    Path((app, id)): Path<(String, String)>,
+) -> Response {
+    run(state, &headers, move |app_state, caller| {
+        ApiAccessService::switch_provider(app_state, &caller, &app, &id)
+    })
+    .await
+}
+
+async fn delete_provider(
+    State(state): State<ProxyState>,
+    headers: HeaderMap,
+    Path((app, id)): Path<(String, String)>,
+) -> Response {
+    run(state, &headers, move |app_state, caller| {
+        ApiAccessService::delete_provider(app_state, &caller, &app, &id).map(|()| json!({}))
+    })
+    .await
+}
+
+/// 认证后在阻塞线程中执行操作（供应商切换等会同步写入配置文件）
+async fn run<T, F>(state: ProxyState, headers: &HeaderMap, op: F) -> Response
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce(&AppState, ApiCaller) -> Result<T, AppError> + Send + 'static,
+{
+    let Some(handle) = state.app_handle.clone() else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "management API is unavailable",
+        );
+    };
+    let secret = bearer_token(headers);
+    let result = tokio::task::spawn_blocking(move || {
+        let app_state = handle.state::<AppState>();
+        let caller = ApiAccessService::authenticate(&app_state.db, secret.as_deref())?;
+        op(&app_state, caller)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(value)) => Json(value).into_response(),
+        Ok(Err(e)) => error_response(status_for(&e), &e.to_string()),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(|s| s.trim().to_string())
+}
+
+fn status_for(error: &AppError) -> StatusCode {
+    match error {
+        AppError::Localized { key, .. } if *key == "api.unauthorized" => StatusCode::UNAUTHORIZED,
+        AppError::Localized { key, .. } if *key == "api.forbidden" => StatusCode::FORBIDDEN,
+        AppError::Localized { key, .. } if *key == "unsupported_app" => StatusCode::BAD_REQUEST,
+        AppError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
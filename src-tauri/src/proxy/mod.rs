@@ -7,13 +7,16 @@ pub mod circuit_breaker;
 pub mod error;
 pub mod error_mapper;
 pub(crate) mod failover_switch;
+pub mod failure_injection;
 mod forwarder;
 pub mod handler_config;
 pub mod handler_context;
 mod handlers;
 mod health;
 pub mod http_client;
+pub mod in_flight;
 pub mod log_codes;
+mod management;
 pub mod model_mapper;
 pub mod provider_router;
 pub mod providers;
@@ -173,6 +173,8 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         }
     }
 
@@ -190,6 +192,8 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         }
     }
 
@@ -211,6 +215,8 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         }
     }
 
@@ -7,6 +7,7 @@ use crate::database::Database;
 use crate::error::AppError;
 use crate::provider::Provider;
 use crate::proxy::circuit_breaker::{AllowResult, CircuitBreaker, CircuitBreakerConfig};
+use crate::services::provider::{IssueSource, ProviderService};
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -34,6 +35,8 @@ impl ProviderRouter {
     /// 返回按优先级排序的可用供应商列表：
     /// - 故障转移关闭时：仅返回当前供应商
     /// - 故障转移开启时：仅使用故障转移队列，按队列顺序依次尝试（P1 → P2 → ...）
+    ///   未验证且未获允许自动化使用的供应商不会被选中；
+    ///   维护窗口内的供应商被跳过，队列中没有其他可用供应商时才使用
     pub async fn select_providers(&self, app_type: &str) -> Result<Vec<Provider>, AppError> {
         let mut result = Vec::new();
         let mut total_providers = 0usize;
@@ -61,11 +64,23 @@ impl ProviderRouter {
                 .collect();
 
             total_providers = ordered_ids.len();
+            let now = chrono::Utc::now().timestamp();
+            let mut in_maintenance = Vec::new();
 
             for provider_id in ordered_ids {
                 let Some(provider) = all_providers.get(&provider_id).cloned() else {
                     continue;
                 };
+                if !provider.allowed_in_automation() {
+                    log::warn!(
+                        "[{app_type}] 供应商 {provider_id} 未验证且未获允许自动化使用，跳过"
+                    );
+                    continue;
+                }
+                if provider.in_maintenance(now) {
+                    in_maintenance.push(provider);
+                    continue;
+                }
 
                 let circuit_key = format!("{app_type}:{}", provider.id);
                 let breaker = self.get_or_create_circuit_breaker(&circuit_key).await;
@@ -76,6 +91,12 @@ impl ProviderRouter {
                     circuit_open_count += 1;
                 }
             }
+
+            // 维护中的供应商只在没有其他可用供应商时兜底
+            if result.is_empty() && !in_maintenance.is_empty() {
+                log::warn!("[{app_type}] 队列中没有维护窗口外的可用供应商，临时使用维护中的供应商");
+                result = in_maintenance;
+            }
         } else {
             // 故障转移关闭：仅使用当前供应商，跳过熔断器检查
             let current_id = AppType::from_str(app_type)
@@ -158,9 +179,46 @@ impl ProviderRouter {
             )
             .await?;
 
+        // 4. 请求成功说明凭据可用，清除之前识别的凭据问题（仅在确有记录时写库）
+        if success && self.db.has_credential_issue(provider_id, app_type)? {
+            self.db.clear_credential_issue(provider_id, app_type)?;
+        }
+
         Ok(())
     }
 
+    /// 从上游错误响应中识别凭据问题（过期 / 无效 / 额度耗尽）并记录到供应商
+    ///
+    /// 维护窗口内的供应商不记录。
+    pub fn record_upstream_error(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+        status: u16,
+        body: Option<&str>,
+    ) {
+        let in_maintenance = self
+            .db
+            .get_provider_by_id(provider_id, app_type)
+            .ok()
+            .flatten()
+            .is_some_and(|p| p.in_maintenance(chrono::Utc::now().timestamp()));
+        if in_maintenance {
+            return;
+        }
+        if let Err(e) = ProviderService::record_credential_outcome(
+            &self.db,
+            app_type,
+            provider_id,
+            false,
+            Some(status),
+            body.unwrap_or_default(),
+            IssueSource::Proxy,
+        ) {
+            log::warn!("[{app_type}] 记录供应商 {provider_id} 凭据问题失败: {e}");
+        }
+    }
+
     /// 重置熔断器（手动恢复）
     pub async fn reset_circuit_breaker(&self, circuit_key: &str) {
         let breakers = self.circuit_breakers.read().await;
@@ -306,6 +364,13 @@ mod tests {
         }
     }
 
+    /// 官方供应商，无需确认即可用于故障转移
+    fn official_provider(id: &str, name: &str) -> Provider {
+        let mut provider = Provider::with_id(id.to_string(), name.to_string(), json!({}), None);
+        provider.category = Some("official".to_string());
+        provider
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_provider_router_creation() {
@@ -323,10 +388,8 @@ mod tests {
         let _home = TempHome::new();
         let db = Arc::new(Database::memory().unwrap());
 
-        let provider_a =
-            Provider::with_id("a".to_string(), "Provider A".to_string(), json!({}), None);
-        let provider_b =
-            Provider::with_id("b".to_string(), "Provider B".to_string(), json!({}), None);
+        let provider_a = official_provider("a", "Provider A");
+        let provider_b = official_provider("b", "Provider B");
 
         db.save_provider("claude", &provider_a).unwrap();
         db.save_provider("claude", &provider_b).unwrap();
@@ -347,11 +410,9 @@ mod tests {
         let db = Arc::new(Database::memory().unwrap());
 
         // 设置 sort_index 来控制顺序：b=1, a=2
-        let mut provider_a =
-            Provider::with_id("a".to_string(), "Provider A".to_string(), json!({}), None);
+        let mut provider_a = official_provider("a", "Provider A");
         provider_a.sort_index = Some(2);
-        let mut provider_b =
-            Provider::with_id("b".to_string(), "Provider B".to_string(), json!({}), None);
+        let mut provider_b = official_provider("b", "Provider B");
         provider_b.sort_index = Some(1);
 
         db.save_provider("claude", &provider_a).unwrap();
@@ -381,10 +442,8 @@ mod tests {
         let _home = TempHome::new();
         let db = Arc::new(Database::memory().unwrap());
 
-        let provider_a =
-            Provider::with_id("a".to_string(), "Provider A".to_string(), json!({}), None);
-        let mut provider_b =
-            Provider::with_id("b".to_string(), "Provider B".to_string(), json!({}), None);
+        let provider_a = official_provider("a", "Provider A");
+        let mut provider_b = official_provider("b", "Provider B");
         provider_b.sort_index = Some(1);
 
         db.save_provider("claude", &provider_a).unwrap();
@@ -419,10 +478,8 @@ mod tests {
         .await
         .unwrap();
 
-        let provider_a =
-            Provider::with_id("a".to_string(), "Provider A".to_string(), json!({}), None);
-        let provider_b =
-            Provider::with_id("b".to_string(), "Provider B".to_string(), json!({}), None);
+        let provider_a = official_provider("a", "Provider A");
+        let provider_b = official_provider("b", "Provider B");
 
         db.save_provider("claude", &provider_a).unwrap();
         db.save_provider("claude", &provider_b).unwrap();
@@ -463,8 +520,7 @@ mod tests {
         .await
         .unwrap();
 
-        let provider_a =
-            Provider::with_id("a".to_string(), "Provider A".to_string(), json!({}), None);
+        let provider_a = official_provider("a", "Provider A");
         db.save_provider("claude", &provider_a).unwrap();
         db.add_to_failover_queue("claude", "a").unwrap();
 
@@ -500,4 +556,44 @@ mod tests {
         assert!(third.allowed);
         assert!(third.used_half_open_permit);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_failover_skips_unverified_providers_without_consent() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+
+        let relay = Provider::with_id("relay".to_string(), "Relay".to_string(), json!({}), None);
+        let mut allowed = Provider::with_id(
+            "allowed".to_string(),
+            "Allowed".to_string(),
+            json!({}),
+            None,
+        );
+        allowed.meta = Some(crate::provider::ProviderMeta {
+            automation_allowed: Some(true),
+            ..Default::default()
+        });
+        db.save_provider("claude", &relay).unwrap();
+        db.save_provider("claude", &allowed).unwrap();
+        db.save_provider("claude", &official_provider("a", "Provider A"))
+            .unwrap();
+        for id in ["relay", "allowed", "a"] {
+            db.add_to_failover_queue("claude", id).unwrap();
+        }
+
+        let mut config = db.get_proxy_config_for_app("claude").await.unwrap();
+        config.auto_failover_enabled = true;
+        db.update_proxy_config_for_app(config).await.unwrap();
+
+        let router = ProviderRouter::new(db.clone());
+        let ids: Vec<String> = router
+            .select_providers("claude")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(ids, vec!["a".to_string(), "allowed".to_string()]);
+    }
 }
@@ -338,6 +338,8 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         }
     }
 
@@ -355,6 +357,8 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         }
     }
 
@@ -226,6 +226,8 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         }
     }
 
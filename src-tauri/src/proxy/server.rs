@@ -3,8 +3,8 @@
 //! 基于Axum的HTTP服务器，处理代理请求
 
 use super::{
-    failover_switch::FailoverSwitchManager, handlers, log_codes::srv as log_srv,
-    provider_router::ProviderRouter, types::*, ProxyError,
+    failover_switch::FailoverSwitchManager, handlers, in_flight::InFlightRequests,
+    log_codes::srv as log_srv, provider_router::ProviderRouter, types::*, ProxyError,
 };
 use crate::database::Database;
 use axum::{
@@ -33,6 +33,8 @@ pub struct ProxyState {
     pub app_handle: Option<tauri::AppHandle>,
     /// 故障转移切换管理器
     pub failover_manager: Arc<FailoverSwitchManager>,
+    /// 各供应商的在途请求（热切换时据此等待旧供应商上的流式响应结束）
+    pub in_flight: Arc<InFlightRequests>,
 }
 
 /// 代理HTTP服务器
@@ -64,6 +66,7 @@ impl ProxyServer {
             provider_router,
             app_handle,
             failover_manager,
+            in_flight: Arc::new(InFlightRequests::default()),
         };
 
         Self {
@@ -178,6 +181,7 @@ impl ProxyServer {
         if let Some(start) = *self.state.start_time.read().await {
             status.uptime_seconds = start.elapsed().as_secs();
         }
+        status.active_connections = self.state.in_flight.total();
 
         // 从 current_providers HashMap 获取每个应用类型当前正在使用的 provider
         let current_providers = self.state.current_providers.read().await;
@@ -193,6 +197,10 @@ impl ProxyServer {
         status
     }
 
+    pub fn in_flight(&self) -> Arc<InFlightRequests> {
+        self.state.in_flight.clone()
+    }
+
     /// 更新某个应用类型当前“目标供应商”（用于 UI 展示 active_targets）
     ///
     /// 注意：这不代表该供应商一定已经处理过请求，而是用于“热切换/启用故障转移立即切 P1”
@@ -215,6 +223,8 @@ impl ProxyServer {
             // 健康检查
             .route("/health", get(handlers::health_check))
             .route("/status", get(handlers::get_status))
+            // 管理接口（需要 API 令牌）
+            .merge(super::management::routes())
             // Claude API (支持带前缀和不带前缀两种格式)
             .route("/v1/messages", post(handlers::handle_messages))
             .route("/claude/v1/messages", post(handlers::handle_messages))
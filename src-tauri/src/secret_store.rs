@@ -0,0 +1,499 @@
+//! 供应商密钥的系统钥匙串存储
+//!
+//! 开启后，供应商 `settings_config` 中的 API Key 保存到系统凭据存储（macOS 钥匙串、
+//! Windows 凭据管理器、Linux Secret Service），数据库里只留下形如
+//! `keychain:claude/packycode/env.ANTHROPIC_AUTH_TOKEN` 的引用。
+//!
+//! 移入与解析都在数据库层完成：保存供应商时把明文换成引用，读取时再换回真实密钥，
+//! 因此写入 live 配置、代理转发、健康检查拿到的都是真实密钥，而数据库备份与 SQL 导出中只有引用。
+//! 读取过的密钥缓存在进程内，避免反复访问钥匙串（部分系统每次访问都会弹出授权）。
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use regex::{Captures, Regex};
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+
+/// 数据库中密钥引用的前缀
+pub const SECRET_REF_PREFIX: &str = "keychain:";
+/// 钥匙串条目的服务名
+const KEYCHAIN_SERVICE: &str = "cc-switch";
+/// 检查钥匙串是否可用时写入的临时条目
+const PROBE_ACCOUNT: &str = "cc-switch-probe";
+
+static CACHE: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 字符串里内嵌的凭据：`name = "value"`、`"name": "value"`、`Name: value`（TOML、内联表、请求头行），
+/// 以及独立出现的 `Bearer <token>` / `Basic <credentials>`
+static EMBEDDED_SECRET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r#"["']?(?P<name>[A-Za-z][A-Za-z0-9_.\-]*)["']?[ \t]*[:=][ \t]*"#,
+        r#"(?:"(?P<dq>[^"\n]*)"|'(?P<sq>[^'\n]*)'|(?P<bare>[^\s"',{}\[\]](?:[^\n,}\]]*[^\s,}\]])?))"#,
+        r#"|(?i:\b(?P<scheme>bearer|basic)[ \t]+)(?P<token>[A-Za-z0-9._~+/=\-]+)"#,
+    ))
+    .expect("valid embedded secret regex")
+});
+
+#[cfg(test)]
+static MEMORY_ONLY: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// 各应用存放密钥的字段路径（`settingsConfig` 中以 `.` 分隔），按优先级排列
+pub(crate) fn secret_paths(app_type: &AppType) -> &'static [&'static str] {
+    match app_type {
+        AppType::Claude => &[
+            "env.ANTHROPIC_AUTH_TOKEN",
+            "env.ANTHROPIC_API_KEY",
+            "env.OPENROUTER_API_KEY",
+        ],
+        AppType::Codex => &["auth.OPENAI_API_KEY"],
+        AppType::Gemini => &["env.GEMINI_API_KEY", "env.GOOGLE_API_KEY"],
+        AppType::OpenCode => &["options.apiKey"],
+        AppType::OpenClaw => &["apiKey"],
+    }
+}
+
+/// 字段名或请求头名看起来像凭据：按 `_`、`-` 等分词后，有词以 key / token / secret / password 结尾，
+/// 或是 `Authorization`、`Cookie` 这类请求头
+///
+/// `MAX_OUTPUT_TOKENS` 这类用量字段不算。
+pub(crate) fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    matches!(
+        name.as_str(),
+        "authorization" | "proxy-authorization" | "cookie"
+    ) || name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| {
+            ["key", "token", "secret", "password"]
+                .iter()
+                .any(|marker| word.ends_with(marker))
+        })
+}
+
+/// 遮蔽供应商配置中的所有凭据，`mask(字段名, 密钥)` 返回替换后的文本
+///
+/// 整个字段替换的情况：路径在 [`secret_paths`] 中，或字段名像凭据；
+/// 其余字符串（自定义请求头、Codex 的 `config.toml` 等）只替换其中内嵌的凭据。
+/// 空值、占位符与钥匙串引用保持原样。
+pub(crate) fn mask_secrets(
+    app_type: &AppType,
+    settings: &mut Value,
+    mask: &mut dyn FnMut(&str, &str) -> String,
+) {
+    mask_value(secret_paths(app_type), "", "", settings, mask);
+}
+
+/// 把配置中的凭据替换为 `***`
+pub(crate) fn redact_secrets(app_type: &AppType, settings: &mut Value) {
+    mask_secrets(app_type, settings, &mut |_, _| "***".to_string());
+}
+
+fn mask_value(
+    paths: &[&str],
+    path: &str,
+    key: &str,
+    value: &mut Value,
+    mask: &mut dyn FnMut(&str, &str) -> String,
+) {
+    match value {
+        Value::Object(map) => {
+            for (child_key, child) in map.iter_mut() {
+                let child_path = if path.is_empty() {
+                    child_key.clone()
+                } else {
+                    format!("{path}.{child_key}")
+                };
+                mask_value(paths, &child_path, child_key, child, mask);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                mask_value(paths, path, key, item, mask);
+            }
+        }
+        Value::String(text) => {
+            if is_placeholder(text) || is_reference(text) {
+                return;
+            }
+            if paths.contains(&path) || is_secret_name(key) {
+                *text = mask_credential(key, text, mask);
+            } else if let Some(masked) = mask_embedded(text, mask) {
+                *text = masked;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 遮蔽一个凭据值，`Bearer` / `Basic` 前缀保持可见
+fn mask_credential(name: &str, value: &str, mask: &mut dyn FnMut(&str, &str) -> String) -> String {
+    if let Some((scheme, secret)) = value.split_once(char::is_whitespace) {
+        let secret = secret.trim_start();
+        if scheme.eq_ignore_ascii_case("bearer") || scheme.eq_ignore_ascii_case("basic") {
+            if is_placeholder(secret) {
+                return value.to_string();
+            }
+            return format!("{scheme} {}", mask(name, secret));
+        }
+    }
+    mask(name, value)
+}
+
+/// 遮蔽字符串里内嵌的凭据，没有可遮蔽的内容时返回 `None`
+fn mask_embedded(text: &str, mask: &mut dyn FnMut(&str, &str) -> String) -> Option<String> {
+    let mut changed = false;
+    let masked = EMBEDDED_SECRET.replace_all(text, |caps: &Captures| {
+        let whole = caps.get(0).expect("whole match");
+        let (value, replacement) = if let Some(token) = caps.name("token") {
+            (token, mask(&caps["scheme"], token.as_str()))
+        } else {
+            let value = caps
+                .name("dq")
+                .or_else(|| caps.name("sq"))
+                .or_else(|| caps.name("bare"))
+                .expect("assignment value");
+            let replacement = if is_placeholder(value.as_str()) || is_reference(value.as_str()) {
+                value.as_str().to_string()
+            } else if is_secret_name(&caps["name"]) {
+                mask_credential(&caps["name"], value.as_str(), mask)
+            } else {
+                mask_embedded(value.as_str(), mask).unwrap_or_else(|| value.as_str().to_string())
+            };
+            (value, replacement)
+        };
+        if replacement != value.as_str() {
+            changed = true;
+        }
+        let start = value.start() - whole.start();
+        let end = value.end() - whole.start();
+        format!(
+            "{}{replacement}{}",
+            &whole.as_str()[..start],
+            &whole.as_str()[end..]
+        )
+    });
+    changed.then(|| masked.into_owned())
+}
+
+/// 空值或导出时留下的占位符
+pub(crate) fn is_placeholder(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty()
+        || value.to_ascii_lowercase().contains("redacted")
+        || value.chars().all(|c| matches!(c, '*' | '•' | 'x' | 'X'))
+}
+
+/// 是否为钥匙串引用
+pub fn is_reference(value: &str) -> bool {
+    value.starts_with(SECRET_REF_PREFIX)
+}
+
+/// 配置中是否还有未解析的引用
+pub fn contains_reference(config: &Value) -> bool {
+    match config {
+        Value::String(s) => is_reference(s),
+        Value::Array(items) => items.iter().any(contains_reference),
+        Value::Object(map) => map.values().any(contains_reference),
+        _ => false,
+    }
+}
+
+fn account_for(app_type: &AppType, provider_id: &str, path: &str) -> String {
+    format!("{}/{provider_id}/{path}", app_type.as_str())
+}
+
+/// 把配置中的明文密钥移入钥匙串并替换为引用，返回移入的数量
+///
+/// 只处理该应用的密钥字段（见 [`secret_paths`]）；空值、占位符与已是引用的字段保持不变。
+pub fn externalize(
+    app_type: &AppType,
+    provider_id: &str,
+    config: &mut Value,
+) -> Result<usize, AppError> {
+    let mut moved = 0;
+    for path in secret_paths(app_type) {
+        let Some(slot) = path
+            .split('.')
+            .try_fold(&mut *config, |node, key| node.get_mut(key))
+        else {
+            continue;
+        };
+        let Some(secret) = slot.as_str() else {
+            continue;
+        };
+        if is_reference(secret) || is_placeholder(secret) {
+            continue;
+        }
+        let account = account_for(app_type, provider_id, path);
+        store(&account, secret)?;
+        *slot = Value::String(format!("{SECRET_REF_PREFIX}{account}"));
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+/// 把配置中的引用替换为钥匙串中的密钥；找不到或读取失败的引用保持原样并记录日志
+///
+/// 只解析该应用密钥字段（见 [`secret_paths`]）上、指向本供应商条目的引用；
+/// 其他位置或指向其他供应商的引用一律不解析，避免导入的配置借引用读出别的供应商的密钥。
+pub fn resolve(app_type: &AppType, provider_id: &str, config: &mut Value) {
+    for path in secret_paths(app_type) {
+        let Some(slot) = path
+            .split('.')
+            .try_fold(&mut *config, |node, key| node.get_mut(key))
+        else {
+            continue;
+        };
+        let Some(reference) = slot.as_str().filter(|s| is_reference(s)) else {
+            continue;
+        };
+        let account = &reference[SECRET_REF_PREFIX.len()..];
+        if account != account_for(app_type, provider_id, path) {
+            log::warn!("忽略不属于供应商 {provider_id} 的钥匙串引用 {reference}");
+            continue;
+        }
+        match load(account) {
+            Ok(Some(secret)) => *slot = Value::String(secret),
+            Ok(None) => log::warn!("钥匙串中找不到密钥 {reference}"),
+            Err(e) => log::warn!("读取钥匙串密钥 {reference} 失败: {e}"),
+        }
+    }
+}
+
+/// 清空配置中的全部钥匙串引用，返回清空的数量
+///
+/// 从外部（深链接、导入文件、旧配置合并等）新增的供应商不能带有引用，否则可以指向其他供应商的密钥。
+pub fn strip_references(config: &mut Value) -> usize {
+    match config {
+        Value::String(s) if is_reference(s) => {
+            s.clear();
+            1
+        }
+        Value::Array(items) => items.iter_mut().map(strip_references).sum(),
+        Value::Object(map) => map.values_mut().map(strip_references).sum(),
+        _ => 0,
+    }
+}
+
+/// 删除供应商在钥匙串中的全部密钥
+pub fn forget(app_type: &AppType, provider_id: &str) -> Result<(), AppError> {
+    for path in secret_paths(app_type) {
+        delete(&account_for(app_type, provider_id, path))?;
+    }
+    Ok(())
+}
+
+/// 写入并删除一个临时条目，确认系统钥匙串可用
+pub fn check_available() -> Result<(), AppError> {
+    store(PROBE_ACCOUNT, "ok")?;
+    delete(PROBE_ACCOUNT)
+}
+
+fn cache() -> std::sync::MutexGuard<'static, HashMap<String, String>> {
+    CACHE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 单元测试只使用进程内缓存，不触碰真实钥匙串
+fn memory_only() -> bool {
+    #[cfg(test)]
+    {
+        MEMORY_ONLY.load(std::sync::atomic::Ordering::Relaxed)
+    }
+    #[cfg(not(test))]
+    {
+        false
+    }
+}
+
+fn entry(account: &str) -> Result<keyring::Entry, AppError> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account).map_err(keychain_error)
+}
+
+fn store(account: &str, secret: &str) -> Result<(), AppError> {
+    if !memory_only() {
+        entry(account)?
+            .set_password(secret)
+            .map_err(keychain_error)?;
+    }
+    cache().insert(account.to_string(), secret.to_string());
+    Ok(())
+}
+
+fn load(account: &str) -> Result<Option<String>, AppError> {
+    if let Some(secret) = cache().get(account) {
+        return Ok(Some(secret.clone()));
+    }
+    if memory_only() {
+        return Ok(None);
+    }
+    match entry(account)?.get_password() {
+        Ok(secret) => {
+            cache().insert(account.to_string(), secret.clone());
+            Ok(Some(secret))
+        }
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(keychain_error(e)),
+    }
+}
+
+fn delete(account: &str) -> Result<(), AppError> {
+    cache().remove(account);
+    if memory_only() {
+        return Ok(());
+    }
+    match entry(account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(keychain_error(e)),
+    }
+}
+
+fn keychain_error(e: keyring::Error) -> AppError {
+    AppError::localized(
+        "keychain.unavailable",
+        format!("系统钥匙串不可用: {e}"),
+        format!("System keychain is unavailable: {e}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn secrets_round_trip_through_references() {
+        MEMORY_ONLY.store(true, std::sync::atomic::Ordering::Relaxed);
+        let app = AppType::Claude;
+        let mut config = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-live",
+                "ANTHROPIC_API_KEY": "<redacted>",
+                "ANTHROPIC_BASE_URL": "https://api.example.com"
+            }
+        });
+
+        assert_eq!(externalize(&app, "p1", &mut config).unwrap(), 1);
+        assert_eq!(
+            config["env"]["ANTHROPIC_AUTH_TOKEN"],
+            "keychain:claude/p1/env.ANTHROPIC_AUTH_TOKEN"
+        );
+        assert_eq!(config["env"]["ANTHROPIC_API_KEY"], "<redacted>");
+        // 已是引用的字段不会重复移入
+        assert_eq!(externalize(&app, "p1", &mut config).unwrap(), 0);
+
+        let mut resolved = config.clone();
+        resolve(&app, "p1", &mut resolved);
+        assert_eq!(resolved["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-live");
+        assert!(!contains_reference(&resolved));
+
+        forget(&app, "p1").unwrap();
+        let mut unresolved = config.clone();
+        resolve(&app, "p1", &mut unresolved);
+        assert!(contains_reference(&unresolved));
+    }
+
+    #[test]
+    fn references_to_other_providers_are_not_resolved() {
+        MEMORY_ONLY.store(true, std::sync::atomic::Ordering::Relaxed);
+        let app = AppType::Claude;
+        let mut victim = json!({"env": {"ANTHROPIC_AUTH_TOKEN": "sk-victim"}});
+        externalize(&app, "victim", &mut victim).unwrap();
+        let reference = victim["env"]["ANTHROPIC_AUTH_TOKEN"].clone();
+
+        // 同一引用放在其他供应商的密钥字段或任意字段上都不解析
+        let mut attacker = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": reference.clone(),
+                "ANTHROPIC_BASE_URL": "https://attacker.example.com",
+                "EXTRA": reference.clone()
+            }
+        });
+        resolve(&app, "attacker", &mut attacker);
+        assert_eq!(attacker["env"]["ANTHROPIC_AUTH_TOKEN"], reference);
+        assert_eq!(attacker["env"]["EXTRA"], reference);
+
+        assert_eq!(strip_references(&mut attacker), 2);
+        assert!(!contains_reference(&attacker));
+        assert_eq!(attacker["env"]["ANTHROPIC_AUTH_TOKEN"], "");
+
+        resolve(&app, "victim", &mut victim);
+        assert_eq!(victim["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-victim");
+        forget(&app, "victim").unwrap();
+    }
+
+    #[test]
+    fn redacts_only_secret_looking_fields() {
+        let mut config = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-live",
+                "ANTHROPIC_BASE_URL": "https://relay.example.com",
+                "CLAUDE_CODE_MAX_OUTPUT_TOKENS": "32000",
+                "ANTHROPIC_CUSTOM_HEADERS": "X-Api-Key: k1\nAuthorization: Bearer sk-header\nX-Team: core"
+            },
+            "options": {"apiKey": "sk-opencode"},
+            "auth": {"OPENAI_API_KEY": "sk-openai"},
+            "headers": [{"x-api-key": "k", "Authorization": "Bearer sk-h"}]
+        });
+        redact_secrets(&AppType::Claude, &mut config);
+        assert_eq!(config["env"]["ANTHROPIC_AUTH_TOKEN"], "***");
+        assert_eq!(
+            config["env"]["ANTHROPIC_BASE_URL"],
+            "https://relay.example.com"
+        );
+        assert_eq!(config["env"]["CLAUDE_CODE_MAX_OUTPUT_TOKENS"], "32000");
+        assert_eq!(
+            config["env"]["ANTHROPIC_CUSTOM_HEADERS"],
+            "X-Api-Key: ***\nAuthorization: Bearer ***\nX-Team: core"
+        );
+        assert_eq!(config["options"]["apiKey"], "***");
+        assert_eq!(config["auth"]["OPENAI_API_KEY"], "***");
+        assert_eq!(config["headers"][0]["x-api-key"], "***");
+        assert_eq!(config["headers"][0]["Authorization"], "Bearer ***");
+    }
+
+    #[test]
+    fn redacts_secrets_inside_codex_toml() {
+        let mut config = json!({
+            "auth": {"OPENAI_API_KEY": "sk-openai"},
+            "config": concat!(
+                "model_provider = \"relay\"\n",
+                "[model_providers.relay]\n",
+                "base_url = \"https://relay.example.com/v1\"\n",
+                "experimental_bearer_token = \"sk-toml\"\n",
+                "http_headers = { \"Authorization\" = \"Bearer sk-inline\", \"X-Team\" = \"core\" }\n",
+                "# curl -H 'Bearer sk-comment'\n",
+            )
+        });
+        redact_secrets(&AppType::Codex, &mut config);
+        let toml = config["config"].as_str().unwrap();
+        assert_eq!(config["auth"]["OPENAI_API_KEY"], "***");
+        for secret in ["sk-toml", "sk-inline", "sk-comment"] {
+            assert!(!toml.contains(secret), "{secret} leaked: {toml}");
+        }
+        assert!(toml.contains("experimental_bearer_token = \"***\""));
+        assert!(toml.contains("\"Authorization\" = \"Bearer ***\""));
+        assert!(toml.contains("base_url = \"https://relay.example.com/v1\""));
+        assert!(toml.contains("\"X-Team\" = \"core\""));
+    }
+
+    #[test]
+    fn references_and_placeholders_stay_visible() {
+        let mut config = json!({
+            "apiKey": "sk-claw",
+            "backupKey": "keychain:openclaw/p1/backupKey",
+            "spareToken": "<redacted>"
+        });
+        let mut seen = Vec::new();
+        mask_secrets(&AppType::OpenClaw, &mut config, &mut |name, secret| {
+            seen.push(format!("{name}={secret}"));
+            "***".to_string()
+        });
+        assert_eq!(seen, vec!["apiKey=sk-claw".to_string()]);
+        assert_eq!(config["backupKey"], "keychain:openclaw/p1/backupKey");
+        assert_eq!(config["spareToken"], "<redacted>");
+    }
+}
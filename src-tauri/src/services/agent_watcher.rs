@@ -0,0 +1,215 @@
+//! 托管 agent 文件监听
+//!
+//! 监听各工具的 agent 目录（Claude / OpenCode）与共享 marker 文件（AGENTS.md / GEMINI.md 等），
+//! 发现托管内容被外部删除或改坏时：被删除的条目按设置自动恢复，其余偏差通过
+//! `agent-files-changed` 事件通知前端，由用户选择拉回或恢复。
+//!
+//! 启动时尚不存在的目录会在之后定期重试监听。
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc::{channel, Receiver};
+
+use crate::agent::{AgentDrift, AgentDriftStatus};
+use crate::agents;
+use crate::app_config::AppType;
+use crate::services::AgentsService;
+use crate::store::AppState;
+
+/// 合并短时间内连续文件事件的等待时间
+const WATCH_DEBOUNCE_MS: u64 = 1000;
+/// 重新尝试监听尚不存在目录的间隔
+const RESCAN_INTERVAL_SECS: u64 = 60;
+
+static WATCHER_STARTED: OnceLock<()> = OnceLock::new();
+
+/// 需要关注的位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WatchLocation {
+    /// 每个 agent 独立成文件的目录（关注其中的 `*.md`）
+    Dir(PathBuf),
+    /// 共享 marker 文件
+    File(PathBuf),
+}
+
+impl WatchLocation {
+    /// 实际注册监听的目录（共享文件监听其所在目录，以便捕获删除后重建）
+    fn watch_dir(&self) -> Option<&Path> {
+        match self {
+            Self::Dir(dir) => Some(dir),
+            Self::File(file) => file.parent(),
+        }
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        match self {
+            Self::Dir(dir) => {
+                path == dir
+                    || (path.parent() == Some(dir.as_path())
+                        && path.extension().and_then(|e| e.to_str()) == Some("md"))
+            }
+            Self::File(file) => path == file,
+        }
+    }
+}
+
+fn watch_locations() -> Vec<WatchLocation> {
+    AppType::all()
+        .filter_map(|app| {
+            agents::agent_files_dir(&app)
+                .map(WatchLocation::Dir)
+                .or_else(|| agents::shared_agents_file(&app).map(WatchLocation::File))
+        })
+        .collect()
+}
+
+fn is_relevant(event: &Event) -> bool {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+    let locations = watch_locations();
+    event
+        .paths
+        .iter()
+        .any(|path| locations.iter().any(|loc| loc.matches(path)))
+}
+
+/// 启动文件监听（重复调用只生效一次）
+pub fn start_worker(app: AppHandle) {
+    if WATCHER_STARTED.set(()).is_err() {
+        return;
+    }
+
+    // 只需要“有变化”的信号，容量 1 即可
+    let (tx, rx) = channel::<()>(1);
+    let watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event) if is_relevant(&event) => {
+            let _ = tx.try_send(());
+        }
+        Ok(_) => {}
+        Err(e) => log::debug!("[AgentWatcher] 文件监听错误: {e}"),
+    });
+    let watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            log::warn!("[AgentWatcher] 无法创建文件监听，agent 文件删除将不会被自动发现: {e}");
+            return;
+        }
+    };
+
+    tauri::async_runtime::spawn(async move {
+        run_worker_loop(app, watcher, rx).await;
+    });
+}
+
+async fn run_worker_loop(app: AppHandle, mut watcher: RecommendedWatcher, mut rx: Receiver<()>) {
+    let mut watched = HashSet::new();
+    ensure_watched(&mut watcher, &mut watched);
+
+    let mut rescan = tokio::time::interval(Duration::from_secs(RESCAN_INTERVAL_SECS));
+    rescan.tick().await; // 跳过立即触发的第一次
+    loop {
+        tokio::select! {
+            signal = rx.recv() => {
+                if signal.is_none() {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(WATCH_DEBOUNCE_MS)).await;
+                while rx.try_recv().is_ok() {}
+                reconcile(&app);
+            }
+            _ = rescan.tick() => {}
+        }
+        ensure_watched(&mut watcher, &mut watched);
+    }
+}
+
+/// 注册新出现的目录，移除已被删除的目录（删除后需要重新注册）
+fn ensure_watched(watcher: &mut RecommendedWatcher, watched: &mut HashSet<PathBuf>) {
+    watched.retain(|dir| dir.exists());
+    for location in watch_locations() {
+        let Some(dir) = location.watch_dir() else {
+            continue;
+        };
+        if watched.contains(dir) || !dir.exists() {
+            continue;
+        }
+        match watcher.watch(dir, RecursiveMode::NonRecursive) {
+            Ok(()) => {
+                log::debug!("[AgentWatcher] 开始监听 {}", dir.display());
+                watched.insert(dir.to_path_buf());
+            }
+            Err(e) => log::warn!("[AgentWatcher] 监听 {} 失败: {e}", dir.display()),
+        }
+    }
+}
+
+/// 检查偏差：被删除的条目按设置恢复，其余通知前端
+fn reconcile(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let drifts = match AgentsService::check_drift(&state) {
+        Ok(drifts) => drifts,
+        Err(e) => {
+            log::warn!("[AgentWatcher] 检查 agent 文件偏差失败: {e}");
+            return;
+        }
+    };
+    if drifts.is_empty() {
+        return;
+    }
+
+    let auto_restore = crate::settings::auto_restore_agent_files_enabled();
+    let mut restored: Vec<AgentDrift> = Vec::new();
+    let mut flagged: Vec<AgentDrift> = Vec::new();
+    for drift in drifts {
+        if !auto_restore || drift.status != AgentDriftStatus::Missing {
+            flagged.push(drift);
+            continue;
+        }
+        let result = AppType::from_str(&drift.app)
+            .and_then(|app_type| AgentsService::restore_file(&state, &drift.agent_id, app_type));
+        match result {
+            Ok(()) => restored.push(drift),
+            Err(e) => {
+                log::warn!(
+                    "[AgentWatcher] 恢复 Agent {} 在 {} 中的文件失败: {e}",
+                    drift.agent_id,
+                    drift.app
+                );
+                flagged.push(drift);
+            }
+        }
+    }
+
+    let payload = json!({ "restored": restored, "drifts": flagged });
+    if let Err(e) = app.emit("agent-files-changed", payload) {
+        log::debug!("[AgentWatcher] 发送 agent-files-changed 事件失败: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_managed_paths_only() {
+        let dir = WatchLocation::Dir(PathBuf::from("/home/u/.claude/agents"));
+        assert!(dir.matches(Path::new("/home/u/.claude/agents/reviewer.md")));
+        assert!(dir.matches(Path::new("/home/u/.claude/agents")));
+        assert!(!dir.matches(Path::new("/home/u/.claude/agents/notes.txt")));
+        assert!(!dir.matches(Path::new("/home/u/.claude/agents/sub/x.md")));
+        assert_eq!(dir.watch_dir(), Some(Path::new("/home/u/.claude/agents")));
+
+        let file = WatchLocation::File(PathBuf::from("/home/u/.codex/AGENTS.md"));
+        assert!(file.matches(Path::new("/home/u/.codex/AGENTS.md")));
+        assert!(!file.matches(Path::new("/home/u/.codex/config.toml")));
+        assert_eq!(file.watch_dir(), Some(Path::new("/home/u/.codex")));
+    }
+}
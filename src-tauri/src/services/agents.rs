@@ -3,52 +3,104 @@
 //! 镜像 `services/mcp.rs`，处理 agent 的 CRUD 和文件同步。
 
 use indexmap::IndexMap;
+use std::collections::HashSet;
+use std::path::Path;
 
-use crate::agent::AgentDefinition;
-use crate::agents;
+use crate::agent::{
+    AgentApps, AgentBundle, AgentDefinition, AgentDrift, AgentDriftStatus, AgentImportResult,
+    AgentRename, AgentRevision, AgentRevisionDiff, AgentScanInvalid, AgentScanResult, DiffLine,
+    DiffLineKind, DuplicateStrategy, AGENT_BUNDLE_FORMAT, AGENT_BUNDLE_VERSION,
+};
+use crate::agents::{self, AgentChange, AgentTemplate, TemplateVars};
 use crate::app_config::AppType;
+use crate::config::write_text_file;
 use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::stream_check::StreamCheckService;
+use crate::services::sync_guard::{best_effort, sync_or_rollback};
 use crate::store::AppState;
+use crate::tags::normalize_tags;
 
 /// Agent 管理服务
 pub struct AgentsService;
 
 impl AgentsService {
-    /// 获取所有 Agent 定义
-    pub fn get_all(state: &AppState) -> Result<IndexMap<String, AgentDefinition>, AppError> {
-        state.db.get_all_agents()
+    /// 获取所有 Agent 定义；`tags` 非空时只保留带有全部指定标签的 agent
+    pub fn get_all(
+        state: &AppState,
+        tags: &[String],
+    ) -> Result<IndexMap<String, AgentDefinition>, AppError> {
+        let mut all = state.db.get_all_agents()?;
+        all.retain(|_, agent| has_all_tags(agent, tags));
+        Ok(all)
+    }
+
+    /// 按关键词搜索 Agent（name / description / content 全文匹配），可再按标签筛选
+    pub fn search(
+        state: &AppState,
+        query: &str,
+        tags: &[String],
+    ) -> Result<Vec<AgentDefinition>, AppError> {
+        let mut found = state.db.search_agents(query)?;
+        found.retain(|agent| has_all_tags(agent, tags));
+        Ok(found)
     }
 
     /// 新增或更新 Agent 定义，并将变更同步到对应工具文件
-    pub fn upsert(state: &AppState, agent: AgentDefinition) -> Result<(), AppError> {
+    ///
+    /// 新启用的工具中已有同名的非 cc-switch 文件时拒绝保存，`overwrite_unmanaged` 为 true 时覆盖并接管。
+    pub fn upsert(
+        state: &AppState,
+        mut agent: AgentDefinition,
+        overwrite_unmanaged: bool,
+    ) -> Result<(), AppError> {
+        Self::validate(&agent)?;
+        agent.tags = normalize_tags(&agent.tags);
+
         // 读取旧状态（按 id 查询，避免全表扫描）
-        let prev_apps = state
-            .db
-            .get_agent_by_id(&agent.id)?
-            .map(|a| a.apps.clone())
-            .unwrap_or_default();
+        let prev = state.db.get_agent_by_id(&agent.id)?;
+        let prev_apps = prev.as_ref().map(|a| a.apps.clone()).unwrap_or_default();
+        let newly_enabled: Vec<AppType> = agent
+            .apps
+            .enabled_apps()
+            .into_iter()
+            .filter(|app| !prev_apps.is_enabled_for(app))
+            .collect();
+        if !overwrite_unmanaged {
+            Self::ensure_no_unmanaged_files(state, &[(&agent, newly_enabled.as_slice())])?;
+        }
 
         // 保存到数据库
         state.db.save_agent(&agent)?;
 
-        // 处理禁用：旧版本启用但新版本取消时，从工具文件中移除
-        if prev_apps.claude && !agent.apps.claude {
-            agents::remove_agent_from_app(&agent.id, &AppType::Claude)?;
-        }
-        if prev_apps.codex && !agent.apps.codex {
-            agents::remove_agent_from_app(&agent.id, &AppType::Codex)?;
-        }
-        if prev_apps.gemini && !agent.apps.gemini {
-            agents::remove_agent_from_app(&agent.id, &AppType::Gemini)?;
-        }
-        if prev_apps.opencode && !agent.apps.opencode {
-            agents::remove_agent_from_app(&agent.id, &AppType::OpenCode)?;
-        }
+        sync_or_rollback(
+            "保存 Agent",
+            || {
+                // 处理禁用：旧版本启用但新版本取消时，从工具文件中移除
+                for app in prev_apps.enabled_apps() {
+                    if !agent.apps.is_enabled_for(&app) {
+                        agents::remove_agent_from_app(&agent.id, &app)?;
+                    }
+                }
 
-        // 同步到所有启用的工具（内容可能已更新）
-        Self::sync_agent_to_apps(&agent)?;
+                // 同步到所有启用的工具（内容可能已更新）
+                Self::sync_agent_to_apps(state, &agent)?;
 
-        Ok(())
+                // 新启用的工具中区块追加在文件末尾，按排序位置归位
+                Self::sync_block_order(state, &newly_enabled)
+            },
+            || Self::rollback_saves(state, &[(&agent, prev.as_ref())]),
+        )
+    }
+
+    /// 校验 Agent 定义，存在问题时返回 [`AppError::AgentValidation`]
+    pub fn validate(agent: &AgentDefinition) -> Result<(), AppError> {
+        let issues = agents::validate_agent(agent);
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::AgentValidation(issues))
+        }
     }
 
     /// 删除 Agent 定义，并从所有已启用工具中移除
@@ -69,33 +121,1008 @@ impl AgentsService {
     }
 
     /// 切换指定工具的启用状态（即时写入/删除文件）
+    ///
+    /// 启用时遇到同名的非托管文件默认拒绝，`overwrite_unmanaged` 为 true 时覆盖。
     pub fn toggle_app(
         state: &AppState,
         agent_id: &str,
         app: AppType,
         enabled: bool,
+        overwrite_unmanaged: bool,
     ) -> Result<(), AppError> {
         let agent = state.db.get_agent_by_id(agent_id)?;
 
-        if let Some(mut agent) = agent {
+        if let Some(prev) = agent {
+            let mut agent = prev.clone();
             agent.apps.set_enabled_for(&app, enabled);
+            if enabled && !prev.apps.is_enabled_for(&app) && !overwrite_unmanaged {
+                Self::ensure_no_unmanaged_files(state, &[(&agent, std::slice::from_ref(&app))])?;
+            }
             state.db.save_agent(&agent)?;
 
-            if enabled {
-                agents::sync_agent_to_app(&agent, &app)?;
+            sync_or_rollback(
+                "切换 Agent",
+                || {
+                    if enabled {
+                        agents::sync_agent_to_app(&agent, &app, &Self::template_vars(state, &app))?;
+                        Self::sync_block_order(state, std::slice::from_ref(&app))
+                    } else {
+                        agents::remove_agent_from_app(agent_id, &app)
+                    }
+                },
+                || Self::rollback_saves(state, &[(&agent, Some(&prev))]),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// 批量切换多个 Agent 在多个工具上的启用状态
+    ///
+    /// 所有数据库更新在同一个事务中完成；每个工具的文件变更合并后只应用一次，
+    /// 共享 marker 文件（AGENTS.md / GEMINI.md 等）只重写一次。
+    /// 返回状态实际发生变化的 agent id；不存在的 id 会被跳过。
+    /// 启用时任一 agent 与非托管文件冲突则整批不做改动（`overwrite_unmanaged` 为 true 时覆盖）。
+    pub fn bulk_toggle(
+        state: &AppState,
+        ids: &[String],
+        apps: AgentApps,
+        enabled: bool,
+        overwrite_unmanaged: bool,
+    ) -> Result<Vec<String>, AppError> {
+        let targets = apps.enabled_apps();
+        let mut changed: Vec<AgentDefinition> = Vec::new();
+        let mut previous: Vec<AgentDefinition> = Vec::new();
+        for id in ids {
+            let Some(mut agent) = state.db.get_agent_by_id(id)? else {
+                log::warn!("批量切换时跳过不存在的 agent: {id}");
+                continue;
+            };
+            if changed.iter().any(|a| a.id == agent.id) {
+                continue;
+            }
+            let before = agent.clone();
+            for app in &targets {
+                agent.apps.set_enabled_for(app, enabled);
+            }
+            if agent.apps != before.apps {
+                changed.push(agent);
+                previous.push(before);
+            }
+        }
+        if changed.is_empty() {
+            return Ok(Vec::new());
+        }
+        if enabled && !overwrite_unmanaged {
+            let newly_enabled: Vec<Vec<AppType>> = previous
+                .iter()
+                .map(|before| {
+                    targets
+                        .iter()
+                        .filter(|app| !before.apps.is_enabled_for(app))
+                        .cloned()
+                        .collect()
+                })
+                .collect();
+            let pending: Vec<(&AgentDefinition, &[AppType])> = changed
+                .iter()
+                .zip(newly_enabled.iter().map(Vec::as_slice))
+                .collect();
+            Self::ensure_no_unmanaged_files(state, &pending)?;
+        }
+
+        state.db.save_agents(&changed)?;
+
+        sync_or_rollback(
+            "批量切换 Agent",
+            || {
+                for app in &targets {
+                    let changes: Vec<AgentChange<'_>> = changed
+                        .iter()
+                        .map(|agent| {
+                            if enabled {
+                                AgentChange::Upsert(agent)
+                            } else {
+                                AgentChange::Remove(agent.id.as_str())
+                            }
+                        })
+                        .collect();
+                    agents::apply_agent_changes(app, &changes, &Self::template_vars(state, app))?;
+                }
+                if enabled {
+                    Self::sync_block_order(state, &targets)?;
+                }
+                Ok(())
+            },
+            || {
+                let saves: Vec<(&AgentDefinition, Option<&AgentDefinition>)> =
+                    changed.iter().zip(previous.iter().map(Some)).collect();
+                Self::rollback_saves(state, &saves)
+            },
+        )?;
+
+        log::info!(
+            "批量{}了 {} 个 agent（{} 个工具）",
+            if enabled { "启用" } else { "禁用" },
+            changed.len(),
+            targets.len()
+        );
+        Ok(changed.into_iter().map(|a| a.id).collect())
+    }
+
+    /// 按给定 id 顺序调整 Agent 排序，并同步共享 marker 文件中的区块顺序
+    pub fn reorder(state: &AppState, ids: &[String]) -> Result<(), AppError> {
+        state.db.reorder_agents(ids)?;
+        Self::sync_block_order(state, &[AppType::Codex, AppType::Gemini, AppType::OpenClaw])
+    }
+
+    /// 计算同步到指定工具时使用的模板变量（当前供应商名称、模型、日期、系统与工具）
+    ///
+    /// 提示词同步同样使用这组变量。
+    /// 查询当前供应商失败时只记录日志，对应变量留空，不阻塞同步。
+    pub fn template_vars(state: &AppState, app: &AppType) -> TemplateVars {
+        let provider = current_provider(state, app).unwrap_or_else(|e| {
+            log::warn!("读取 {} 当前供应商失败，模板变量留空: {e}", app.as_str());
+            None
+        });
+        TemplateVars {
+            today: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            provider_name: provider.as_ref().map(|p| p.name.clone()),
+            model: provider
+                .as_ref()
+                .and_then(|p| StreamCheckService::provider_model(app, p)),
+            os: agents::os_name().to_string(),
+            app: app.as_str().to_string(),
+        }
+    }
+
+    /// 重新写入指定工具中正文含模板变量的 agent（供应商切换后调用）
+    pub fn resync_templated(state: &AppState, app: &AppType) -> Result<(), AppError> {
+        let all = state.db.get_all_agents()?;
+        let changes: Vec<AgentChange<'_>> = all
+            .values()
+            .filter(|a| a.apps.is_enabled_for(app) && a.content.contains("{{"))
+            .map(AgentChange::Upsert)
+            .collect();
+        if changes.is_empty() {
+            return Ok(());
+        }
+        agents::apply_agent_changes(app, &changes, &Self::template_vars(state, app))
+    }
+
+    /// 切换 Gemini agent 的输出方式（GEMINI.md 区块 / 独立文件），并迁移已写入的 agent
+    ///
+    /// Gemini 同步已暂停时文件已被清理，只保存设置。返回迁移的 agent 数量。
+    pub fn set_gemini_output(
+        state: &AppState,
+        output: agents::GeminiAgentOutput,
+    ) -> Result<usize, AppError> {
+        let current = crate::settings::get_gemini_agent_output();
+        if current == output {
+            return Ok(0);
+        }
+        let app = AppType::Gemini;
+        let migrated = if crate::settings::is_app_sync_paused(&app) {
+            0
+        } else {
+            let enabled: Vec<AgentDefinition> = state
+                .db
+                .get_all_agents()?
+                .into_values()
+                .filter(|a| a.apps.is_enabled_for(&app))
+                .collect();
+            agents::migrate_gemini_output(
+                &enabled,
+                current,
+                output,
+                &Self::template_vars(state, &app),
+            )?
+        };
+        crate::settings::set_gemini_agent_output(output)?;
+        log::info!("Gemini agent 输出方式已切换为 {output:?}，迁移 {migrated} 个 agent");
+        Ok(migrated)
+    }
+
+    /// 检查即将新启用的工具中是否有同名但不是 cc-switch 写入的 agent 文件
+    ///
+    /// 每一项为 (agent, 新启用的工具)。此前已同步过的工具中的文件由 cc-switch 管理，不在检查范围内；
+    /// 存在冲突时列出全部文件路径并返回错误，不做任何改动。
+    fn ensure_no_unmanaged_files(
+        state: &AppState,
+        pending: &[(&AgentDefinition, &[AppType])],
+    ) -> Result<(), AppError> {
+        let mut conflicts = Vec::new();
+        for (agent, apps) in pending {
+            for app in apps.iter() {
+                let vars = Self::template_vars(state, app);
+                if let Some(path) = agents::find_unmanaged_file(agent, app, &vars)? {
+                    conflicts.push(path.display().to_string());
+                }
+            }
+        }
+        if conflicts.is_empty() {
+            return Ok(());
+        }
+        let paths = conflicts.join(", ");
+        Err(AppError::localized(
+            "agent.unmanaged_file_conflict",
+            format!("以下文件不是由 cc-switch 创建的，已拒绝覆盖: {paths}。可接管文件内容，或确认后覆盖"),
+            format!(
+                "Refusing to overwrite files not created by cc-switch: {paths}. Adopt their content, or confirm to overwrite"
+            ),
+        ))
+    }
+
+    /// 文件同步失败时的补偿：撤销数据库中的保存，并尽力把工具文件恢复到保存前的状态
+    ///
+    /// 每一项为 (本次保存的定义, 保存前的定义)；保存前不存在时为 None。
+    fn rollback_saves(
+        state: &AppState,
+        saves: &[(&AgentDefinition, Option<&AgentDefinition>)],
+    ) -> Result<(), AppError> {
+        let reverts: Vec<(&str, Option<&AgentDefinition>)> = saves
+            .iter()
+            .map(|(attempted, prev)| (attempted.id.as_str(), *prev))
+            .collect();
+        state.db.revert_agent_saves(&reverts)?;
+
+        for app in AppType::all() {
+            let vars = Self::template_vars(state, &app);
+            for (attempted, prev) in saves {
+                match prev.filter(|p| p.apps.is_enabled_for(&app)) {
+                    Some(prev) => best_effort(
+                        "恢复 agent 文件",
+                        agents::sync_agent_to_app(prev, &app, &vars),
+                    ),
+                    None if attempted.apps.is_enabled_for(&app) => best_effort(
+                        "移除 agent 文件",
+                        agents::remove_agent_from_app(&attempted.id, &app),
+                    ),
+                    None => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 让指定工具的共享 marker 文件中的区块顺序与数据库排序一致
+    fn sync_block_order(state: &AppState, apps: &[AppType]) -> Result<(), AppError> {
+        if apps.is_empty() {
+            return Ok(());
+        }
+        let all = state.db.get_all_agents()?;
+        for app in apps {
+            let ids: Vec<&str> = all
+                .values()
+                .filter(|a| a.apps.is_enabled_for(app))
+                .map(|a| a.id.as_str())
+                .collect();
+            agents::reorder_agent_blocks(app, &ids)?;
+        }
+        Ok(())
+    }
+
+    /// 扫描各工具 agent 目录，将尚未被管理的 agent 导入数据库
+    ///
+    /// 扫描 `~/.claude/agents/*.md` 与 `~/.config/opencode/agents/*.md`，
+    /// 以文件名作为 id；数据库中已存在的 id 视为已管理，跳过。
+    /// 先按 id 汇总各工具中的副本再逐个校验：任一副本未通过 [`Self::validate`] 时整个 id 不导入，
+    /// 并返回该副本的问题；全部通过且正文相同时合并为一条记录，并启用对应的全部工具；
+    /// 正文不同的 id 记为冲突、不导入。
+    /// 文件本身已存在，因此只写数据库，不重写文件。
+    pub fn scan_existing(state: &AppState) -> Result<AgentScanResult, AppError> {
+        let existing = state.db.get_all_agents()?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut copies: IndexMap<String, Vec<(AppType, AgentDefinition)>> = IndexMap::new();
+        let mut result = AgentScanResult::default();
+
+        for app in [AppType::Claude, AppType::OpenCode] {
+            for (id, raw) in agents::read_agent_files(&app)? {
+                if existing.contains_key(&id) {
+                    continue;
+                }
+
+                let (meta, body) = agents::parse_frontmatter_md(&raw);
+                if body.trim().is_empty() {
+                    log::debug!("跳过空 agent 文件: {id} ({})", app.as_str());
+                    continue;
+                }
+
+                let mut apps = AgentApps::default();
+                apps.set_enabled_for(&app, true);
+                let agent = AgentDefinition {
+                    name: meta
+                        .name
+                        .filter(|n| !n.trim().is_empty())
+                        .unwrap_or_else(|| id.clone()),
+                    id: id.clone(),
+                    content: body,
+                    description: meta.description.filter(|d| !d.trim().is_empty()),
+                    apps,
+                    tools: meta.tools.filter(|t| !t.trim().is_empty()),
+                    model: meta.model.filter(|m| !m.trim().is_empty()),
+                    color: meta.color.filter(|c| !c.trim().is_empty()),
+                    created_at: Some(now),
+                    updated_at: Some(now),
+                    ..Default::default()
+                };
+                copies.entry(id).or_default().push((app.clone(), agent));
+            }
+        }
+
+        let mut found: IndexMap<String, AgentDefinition> = IndexMap::new();
+        for (id, copies) in copies {
+            let mut valid = true;
+            for (app, agent) in &copies {
+                let issues = agents::validate_agent(agent);
+                if !issues.is_empty() {
+                    valid = false;
+                    result.invalid.push(AgentScanInvalid {
+                        agent_id: id.clone(),
+                        app: app.as_str().to_string(),
+                        issues,
+                    });
+                }
+            }
+            if !valid {
+                continue;
+            }
+
+            // 同名 agent 只有正文一致时才合并为一个定义，否则下次同步会覆盖其中一个文件
+            let mut copies = copies.into_iter();
+            let Some((_, mut agent)) = copies.next() else {
+                continue;
+            };
+            let mut conflict = false;
+            for (app, other) in copies {
+                if other.content.trim_end() == agent.content.trim_end() {
+                    agent.apps.set_enabled_for(&app, true);
+                } else {
+                    conflict = true;
+                }
+            }
+            if conflict {
+                log::warn!("agent {id} 在多个工具中的正文不同，跳过导入");
+                result.conflicts.push(id);
+                continue;
+            }
+            found.insert(id, agent);
+        }
+
+        for agent in found.values() {
+            state.db.save_agent(agent)?;
+        }
+
+        log::info!(
+            "从工具目录导入 {} 个 agent，{} 个内容冲突，{} 个未通过校验",
+            found.len(),
+            result.conflicts.len(),
+            result.invalid.len()
+        );
+        result.imported = found.into_values().collect();
+        Ok(result)
+    }
+
+    /// 获取内置 Agent 模板
+    pub fn templates() -> Vec<AgentTemplate> {
+        agents::all_templates().to_vec()
+    }
+
+    /// 基于内置模板新建 Agent，并同步到 `apps` 中启用的工具
+    ///
+    /// `id` 为空时使用模板 id；id 已存在时报错，避免覆盖用户修改过的 agent。
+    pub fn create_from_template(
+        state: &AppState,
+        template_id: &str,
+        id: Option<String>,
+        apps: AgentApps,
+    ) -> Result<AgentDefinition, AppError> {
+        let template = agents::find_template(template_id).ok_or_else(|| {
+            AppError::localized(
+                "agent.template_not_found",
+                format!("未找到 Agent 模板: {template_id}"),
+                format!("Agent template not found: {template_id}"),
+            )
+        })?;
+
+        let id = id
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| template.id.to_string());
+        if state.db.get_agent_by_id(&id)?.is_some() {
+            return Err(AppError::localized(
+                "agent.already_exists",
+                format!("Agent 已存在: {id}"),
+                format!("Agent already exists: {id}"),
+            ));
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let agent = AgentDefinition {
+            id,
+            name: template.name.to_string(),
+            content: template.content.to_string(),
+            description: Some(template.description.to_string()),
+            apps,
+            tools: template.tools.map(str::to_string),
+            created_at: Some(now),
+            updated_at: Some(now),
+            ..Default::default()
+        };
+        Self::upsert(state, agent.clone(), false)?;
+        Ok(agent)
+    }
+
+    /// 导出 Agent 为可移植的导出包
+    ///
+    /// `ids` 为空时导出全部；不存在的 id 会被忽略。
+    pub fn export_bundle(
+        state: &AppState,
+        ids: Option<&[String]>,
+    ) -> Result<AgentBundle, AppError> {
+        let all = state.db.get_all_agents()?;
+        let agents = match ids {
+            Some(ids) => ids.iter().filter_map(|id| all.get(id).cloned()).collect(),
+            None => all.into_values().collect(),
+        };
+        Ok(AgentBundle {
+            format: AGENT_BUNDLE_FORMAT.to_string(),
+            version: AGENT_BUNDLE_VERSION,
+            exported_at: chrono::Utc::now().timestamp_millis(),
+            agents,
+        })
+    }
+
+    /// 导出 Agent 到 JSON 文件，返回导出的数量
+    pub fn export_bundle_to_file(
+        state: &AppState,
+        path: &Path,
+        ids: Option<&[String]>,
+    ) -> Result<usize, AppError> {
+        let bundle = Self::export_bundle(state, ids)?;
+        let json = serde_json::to_string_pretty(&bundle)
+            .map_err(|e| AppError::JsonSerialize { source: e })?;
+        write_text_file(path, &json)?;
+        log::info!(
+            "已导出 {} 个 agent 到 {}",
+            bundle.agents.len(),
+            path.display()
+        );
+        Ok(bundle.agents.len())
+    }
+
+    /// 从 JSON 文件导入 Agent 导出包
+    pub fn import_bundle_from_file(
+        state: &AppState,
+        path: &Path,
+        strategy: DuplicateStrategy,
+    ) -> Result<AgentImportResult, AppError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+        let bundle: AgentBundle = serde_json::from_str(&raw).map_err(|e| {
+            AppError::localized(
+                "agent.bundle_invalid",
+                format!("无效的 Agent 导出包: {e}"),
+                format!("Invalid agent bundle: {e}"),
+            )
+        })?;
+        Self::import_bundle(state, bundle, strategy)
+    }
+
+    /// 导入 Agent 导出包，按 `strategy` 处理重复 id
+    ///
+    /// 导入包中的启用状态与排序位置被忽略：新 agent 不启用任何工具、排在最后，因此不会写入工具文件；
+    /// 覆盖时保留本地的启用状态、排序位置与创建时间，并重新同步到本地已启用的工具。
+    pub fn import_bundle(
+        state: &AppState,
+        bundle: AgentBundle,
+        strategy: DuplicateStrategy,
+    ) -> Result<AgentImportResult, AppError> {
+        if bundle.format != AGENT_BUNDLE_FORMAT || bundle.version > AGENT_BUNDLE_VERSION {
+            return Err(AppError::localized(
+                "agent.bundle_unsupported",
+                format!(
+                    "不支持的 Agent 导出包: {} v{}",
+                    bundle.format, bundle.version
+                ),
+                format!(
+                    "Unsupported agent bundle: {} v{}",
+                    bundle.format, bundle.version
+                ),
+            ));
+        }
+
+        let existing = state.db.get_all_agents()?;
+        let mut taken: HashSet<String> = existing.keys().cloned().collect();
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut result = AgentImportResult::default();
+
+        for mut agent in bundle.agents {
+            let id = agent.id.trim().to_string();
+            if id.is_empty() {
+                log::warn!("跳过缺少 id 的 agent: {}", agent.name);
+                continue;
+            }
+            agent.id = id.clone();
+            agent.apps = AgentApps::default();
+            agent.sort_index = None;
+            agent.created_at = agent.created_at.or(Some(now));
+            agent.updated_at = Some(now);
+
+            if taken.contains(&id) {
+                match strategy {
+                    DuplicateStrategy::Skip => {
+                        result.skipped.push(id);
+                        continue;
+                    }
+                    DuplicateStrategy::Overwrite => {
+                        if let Some(local) = existing.get(&id) {
+                            agent.apps = local.apps.clone();
+                            agent.sort_index = local.sort_index;
+                            agent.created_at = local.created_at.or(agent.created_at);
+                        }
+                        result.overwritten.push(id);
+                    }
+                    DuplicateStrategy::Rename => {
+                        let new_id = (2..)
+                            .map(|n| format!("{id}-{n}"))
+                            .find(|candidate| !taken.contains(candidate))
+                            .expect("unbounded id candidates");
+                        result.renamed.push(AgentRename {
+                            from: id,
+                            to: new_id.clone(),
+                        });
+                        agent.id = new_id;
+                    }
+                }
             } else {
-                agents::remove_agent_from_app(agent_id, &app)?;
+                result.imported.push(id);
             }
+
+            taken.insert(agent.id.clone());
+            Self::upsert(state, agent, false)?;
         }
 
+        log::info!(
+            "Agent 导入完成: 新增 {}，覆盖 {}，重命名 {}，跳过 {}",
+            result.imported.len(),
+            result.overwritten.len(),
+            result.renamed.len(),
+            result.skipped.len()
+        );
+        Ok(result)
+    }
+
+    /// 获取 Agent 的历史版本（最新在前）
+    pub fn history(state: &AppState, agent_id: &str) -> Result<Vec<AgentRevision>, AppError> {
+        state.db.get_agent_history(agent_id)
+    }
+
+    /// 对比同一 Agent 的两个历史版本
+    pub fn diff_versions(
+        state: &AppState,
+        agent_id: &str,
+        from_version: i64,
+        to_version: i64,
+    ) -> Result<AgentRevisionDiff, AppError> {
+        let from = Self::require_revision(state, agent_id, from_version)?.snapshot;
+        let to = Self::require_revision(state, agent_id, to_version)?.snapshot;
+
+        Ok(AgentRevisionDiff {
+            agent_id: agent_id.to_string(),
+            from_version,
+            to_version,
+            changed_fields: changed_fields(&from, &to),
+            content_diff: diff_lines(&from.content, &to.content),
+        })
+    }
+
+    /// 检查已启用工具中的 agent 文件是否被外部修改或删除
+    ///
+    /// 只返回存在偏差的条目；已暂停同步的工具会被跳过（其托管内容本就已清除）。
+    pub fn check_drift(state: &AppState) -> Result<Vec<AgentDrift>, AppError> {
+        let mut drifts = Vec::new();
+        let mut vars_by_app: Vec<(AppType, TemplateVars)> = Vec::new();
+        for agent in state.db.get_all_agents()?.values() {
+            for app in agent.apps.enabled_apps() {
+                if crate::settings::is_app_sync_paused(&app) {
+                    continue;
+                }
+                let vars = match vars_by_app.iter().find(|(a, _)| *a == app) {
+                    Some((_, vars)) => vars.clone(),
+                    None => {
+                        let vars = Self::template_vars(state, &app);
+                        vars_by_app.push((app.clone(), vars.clone()));
+                        vars
+                    }
+                };
+                let snapshot = agents::inspect_agent_file(agent, &app, &vars)?;
+                if !snapshot.is_drifted() {
+                    continue;
+                }
+
+                let (status, changed, content_diff) = match &snapshot.actual {
+                    Some(raw) => {
+                        // 与替换模板变量后的内容比较，避免把变量取值误报为修改
+                        let expected = agents::render_agent(agent, &vars);
+                        let on_disk = agents::parse_agent_file(&app, agent, raw);
+                        (
+                            AgentDriftStatus::Modified,
+                            changed_fields(&expected, &on_disk),
+                            diff_lines(&expected.content, &on_disk.content),
+                        )
+                    }
+                    None => (AgentDriftStatus::Missing, Vec::new(), Vec::new()),
+                };
+                drifts.push(AgentDrift {
+                    agent_id: agent.id.clone(),
+                    app: app.as_str().to_string(),
+                    status,
+                    path: snapshot.path.display().to_string(),
+                    changed_fields: changed,
+                    content_diff,
+                });
+            }
+        }
+        Ok(drifts)
+    }
+
+    /// 将某个工具文件中的外部修改拉回数据库
+    ///
+    /// 拉回后按新定义重新同步所有已启用的工具，使其他工具也获得这次修改。
+    pub fn pull_from_app(
+        state: &AppState,
+        agent_id: &str,
+        app: AppType,
+    ) -> Result<AgentDefinition, AppError> {
+        let agent = state.db.get_agent_by_id(agent_id)?.ok_or_else(|| {
+            AppError::localized(
+                "agent.not_found",
+                format!("Agent 不存在: {agent_id}"),
+                format!("Agent not found: {agent_id}"),
+            )
+        })?;
+        let vars = Self::template_vars(state, &app);
+        let snapshot = agents::inspect_agent_file(&agent, &app, &vars)?;
+        let Some(raw) = snapshot.actual else {
+            return Err(agent_file_missing(agent_id, &app, &snapshot.path));
+        };
+
+        let mut pulled = agents::parse_agent_file(&app, &agent, &raw);
+        // 未改动的模板行换回原模板，避免把变量（尤其是日期）固化成写入当时的取值
+        pulled.content = agents::restore_template_lines(&pulled.content, &agent.content, &vars);
+        pulled.updated_at = Some(chrono::Utc::now().timestamp_millis());
+        Self::upsert(state, pulled.clone(), false)?;
+        log::info!("已从 {} 拉回 Agent {agent_id} 的外部修改", app.as_str());
+        Ok(pulled)
+    }
+
+    /// 接管工具中同名的非 cc-switch 文件：把文件内容导入数据库并对该工具启用
+    ///
+    /// 与覆盖（`overwrite_unmanaged`）相反，保留文件中的内容。数据库中已有该 agent 时
+    /// 只用文件覆盖该工具文件格式携带的字段，没有时按文件新建；其他已启用的工具随后同步为新内容。
+    pub fn adopt_unmanaged(
+        state: &AppState,
+        agent_id: &str,
+        app: AppType,
+    ) -> Result<AgentDefinition, AppError> {
+        let base = state
+            .db
+            .get_agent_by_id(agent_id)?
+            .unwrap_or_else(|| AgentDefinition {
+                id: agent_id.to_string(),
+                name: agent_id.to_string(),
+                ..Default::default()
+            });
+        let vars = Self::template_vars(state, &app);
+        let snapshot = agents::inspect_agent_file(&base, &app, &vars)?;
+        let Some(raw) = snapshot.actual else {
+            return Err(agent_file_missing(agent_id, &app, &snapshot.path));
+        };
+
+        let mut adopted = agents::parse_agent_file(&app, &base, &raw);
+        adopted.content = agents::restore_template_lines(&adopted.content, &base.content, &vars);
+        adopted.apps.set_enabled_for(&app, true);
+        let now = chrono::Utc::now().timestamp_millis();
+        adopted.created_at = adopted.created_at.or(Some(now));
+        adopted.updated_at = Some(now);
+        // 文件内容已并入定义，按托管格式重写同名文件即完成接管
+        Self::upsert(state, adopted.clone(), true)?;
+        log::info!("已接管 {} 中的 Agent 文件 {agent_id}", app.as_str());
+        Ok(adopted)
+    }
+
+    /// 按数据库定义重写某个工具中的 agent 文件，丢弃外部修改或恢复被删除的文件
+    ///
+    /// 该工具未启用此 agent 时不做任何改动。
+    pub fn restore_file(state: &AppState, agent_id: &str, app: AppType) -> Result<(), AppError> {
+        let agent = state.db.get_agent_by_id(agent_id)?.ok_or_else(|| {
+            AppError::localized(
+                "agent.not_found",
+                format!("Agent 不存在: {agent_id}"),
+                format!("Agent not found: {agent_id}"),
+            )
+        })?;
+        if !agent.apps.is_enabled_for(&app) {
+            return Ok(());
+        }
+        // 恢复即用户明确要求丢弃外部修改，不受外部修改策略约束
+        let vars = Self::template_vars(state, &app);
+        crate::external_edits::overwrite_anyway(|| agents::sync_agent_to_app(&agent, &app, &vars))?;
+        Self::sync_block_order(state, std::slice::from_ref(&app))?;
+        log::info!("已恢复 Agent {agent_id} 在 {} 中的文件", app.as_str());
         Ok(())
     }
 
+    /// 将 Agent 回滚到指定历史版本，并重新同步工具文件
+    ///
+    /// 回滚本身也会产生一个新版本，因此可以再次撤销。
+    pub fn rollback(
+        state: &AppState,
+        agent_id: &str,
+        version: i64,
+    ) -> Result<AgentDefinition, AppError> {
+        let mut agent = Self::require_revision(state, agent_id, version)?.snapshot;
+        let current = state.db.get_agent_by_id(agent_id)?;
+
+        agent.id = agent_id.to_string();
+        // 排序位置不随版本回滚，沿用当前位置
+        agent.sort_index = None;
+        agent.created_at = current.and_then(|a| a.created_at).or(agent.created_at);
+        agent.updated_at = Some(chrono::Utc::now().timestamp_millis());
+
+        Self::upsert(state, agent.clone(), false)?;
+        log::info!("Agent {agent_id} 已回滚到版本 {version}");
+        Ok(agent)
+    }
+
+    fn require_revision(
+        state: &AppState,
+        agent_id: &str,
+        version: i64,
+    ) -> Result<AgentRevision, AppError> {
+        state
+            .db
+            .get_agent_revision(agent_id, version)?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "agent.revision_not_found",
+                    format!("Agent {agent_id} 不存在版本 {version}"),
+                    format!("Agent {agent_id} has no version {version}"),
+                )
+            })
+    }
+
     /// 将 Agent 同步到所有已启用的工具
-    fn sync_agent_to_apps(agent: &AgentDefinition) -> Result<(), AppError> {
+    fn sync_agent_to_apps(state: &AppState, agent: &AgentDefinition) -> Result<(), AppError> {
         for app in agent.apps.enabled_apps() {
-            agents::sync_agent_to_app(agent, &app)?;
+            agents::sync_agent_to_app(agent, &app, &Self::template_vars(state, &app))?;
         }
         Ok(())
     }
 }
+
+/// 指定工具当前生效的供应商
+fn current_provider(state: &AppState, app: &AppType) -> Result<Option<Provider>, AppError> {
+    match crate::settings::get_effective_current_provider(&state.db, app)? {
+        Some(id) => state.db.get_provider_by_id(&id, app.as_str()),
+        None => Ok(None),
+    }
+}
+
+/// 工具中找不到 agent 文件（或区块）时的错误
+fn agent_file_missing(agent_id: &str, app: &AppType, path: &std::path::Path) -> AppError {
+    AppError::localized(
+        "agent.file_missing",
+        format!(
+            "{} 中不存在 Agent {agent_id}: {}",
+            app.as_str(),
+            path.display()
+        ),
+        format!(
+            "Agent {agent_id} not found in {}: {}",
+            app.as_str(),
+            path.display()
+        ),
+    )
+}
+
+/// agent 是否带有 `tags` 中的全部标签（不区分大小写）；`tags` 为空时总是成立
+fn has_all_tags(agent: &AgentDefinition, tags: &[String]) -> bool {
+    tags.iter().all(|wanted| {
+        let wanted = wanted.trim().to_lowercase();
+        agent.tags.iter().any(|tag| tag.to_lowercase() == wanted)
+    })
+}
+
+/// 列出两个定义之间发生变化的字段（camelCase）
+fn changed_fields(from: &AgentDefinition, to: &AgentDefinition) -> Vec<String> {
+    [
+        ("name", from.name != to.name),
+        ("description", from.description != to.description),
+        ("content", from.content != to.content),
+        ("tools", from.tools != to.tools),
+        ("model", from.model != to.model),
+        ("color", from.color != to.color),
+        ("group", from.group != to.group),
+        ("tags", from.tags != to.tags),
+        ("apps", from.apps != to.apps),
+    ]
+    .into_iter()
+    .filter(|(_, changed)| *changed)
+    .map(|(field, _)| field.to_string())
+    .collect()
+}
+
+/// 逐行差异（Myers 算法的线性空间版本，耗时随差异行数增长，而不是两边行数之积）
+pub(crate) fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let mut out = Vec::with_capacity(a.len().max(b.len()));
+    diff_slices(&a, &b, &mut out);
+    out
+}
+
+fn push_lines(out: &mut Vec<DiffLine>, kind: DiffLineKind, lines: &[&str]) {
+    out.extend(lines.iter().map(|text| DiffLine {
+        kind,
+        text: text.to_string(),
+    }));
+}
+
+fn diff_slices(a: &[&str], b: &[&str], out: &mut Vec<DiffLine>) {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let (a_rest, b_rest) = (&a[prefix..], &b[prefix..]);
+    let suffix = a_rest
+        .iter()
+        .rev()
+        .zip(b_rest.iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (
+        &a_rest[..a_rest.len() - suffix],
+        &b_rest[..b_rest.len() - suffix],
+    );
+
+    push_lines(out, DiffLineKind::Equal, &a[..prefix]);
+    if a_mid.is_empty() {
+        push_lines(out, DiffLineKind::Added, b_mid);
+    } else if b_mid.is_empty() {
+        push_lines(out, DiffLineKind::Removed, a_mid);
+    } else if let Some((x, y)) = middle_split(a_mid, b_mid) {
+        diff_slices(&a_mid[..x], &b_mid[..y], out);
+        diff_slices(&a_mid[x..], &b_mid[y..], out);
+    } else {
+        push_lines(out, DiffLineKind::Removed, a_mid);
+        push_lines(out, DiffLineKind::Added, b_mid);
+    }
+    push_lines(out, DiffLineKind::Equal, &a_rest[a_rest.len() - suffix..]);
+}
+
+/// 单次查找分割点时最多推进的编辑步数；超过后把剩余部分整体视为删除加新增，保证耗时有上限
+const MAX_DIFF_STEPS: isize = 2048;
+
+/// 同时从两端推进编辑路径，返回两条路径相遇处的分割点；
+/// 两边没有公共行或差异过大（超过 [`MAX_DIFF_STEPS`]）时返回 None
+fn middle_split(a: &[&str], b: &[&str]) -> Option<(usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max_d = (n + m + 1) / 2;
+    let offset = max_d;
+    let len = (2 * max_d + 2) as usize;
+    let mut forward = vec![-1isize; len];
+    let mut backward = vec![-1isize; len];
+    forward[offset as usize + 1] = 0;
+    backward[offset as usize + 1] = 0;
+    let delta = n - m;
+    let odd = delta % 2 != 0;
+    let (mut k1_start, mut k1_end, mut k2_start, mut k2_end) = (0, 0, 0, 0);
+
+    for d in 0..max_d.min(MAX_DIFF_STEPS) {
+        let mut k1 = -d + k1_start;
+        while k1 <= d - k1_end {
+            let i = (offset + k1) as usize;
+            let mut x1 = if k1 == -d || (k1 != d && forward[i - 1] < forward[i + 1]) {
+                forward[i + 1]
+            } else {
+                forward[i - 1] + 1
+            };
+            let mut y1 = x1 - k1;
+            while x1 < n && y1 < m && a[x1 as usize] == b[y1 as usize] {
+                x1 += 1;
+                y1 += 1;
+            }
+            forward[i] = x1;
+            if x1 > n {
+                k1_end += 2;
+            } else if y1 > m {
+                k1_start += 2;
+            } else if odd {
+                let j = offset + delta - k1;
+                if (0..len as isize).contains(&j)
+                    && backward[j as usize] != -1
+                    && x1 >= n - backward[j as usize]
+                {
+                    return Some((x1 as usize, y1 as usize));
+                }
+            }
+            k1 += 2;
+        }
+
+        let mut k2 = -d + k2_start;
+        while k2 <= d - k2_end {
+            let i = (offset + k2) as usize;
+            let mut x2 = if k2 == -d || (k2 != d && backward[i - 1] < backward[i + 1]) {
+                backward[i + 1]
+            } else {
+                backward[i - 1] + 1
+            };
+            let mut y2 = x2 - k2;
+            while x2 < n && y2 < m && a[(n - x2 - 1) as usize] == b[(m - y2 - 1) as usize] {
+                x2 += 1;
+                y2 += 1;
+            }
+            backward[i] = x2;
+            if x2 > n {
+                k2_end += 2;
+            } else if y2 > m {
+                k2_start += 2;
+            } else if !odd {
+                let j = offset + delta - k2;
+                if (0..len as isize).contains(&j) && forward[j as usize] != -1 {
+                    let x1 = forward[j as usize];
+                    let y1 = offset + x1 - j;
+                    if x1 >= n - x2 {
+                        return Some((x1 as usize, y1 as usize));
+                    }
+                }
+            }
+            k2 += 2;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_lines_marks_added_and_removed() {
+        let diff = diff_lines("a\nb\nc", "a\nc\nd");
+        let kinds: Vec<_> = diff.iter().map(|l| (l.kind, l.text.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DiffLineKind::Equal, "a"),
+                (DiffLineKind::Removed, "b"),
+                (DiffLineKind::Equal, "c"),
+                (DiffLineKind::Added, "d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_lines_reconstructs_both_sides() {
+        let old = "fn a() {}\nlet x = 1;\nshared\nold only\nshared\ntail";
+        let new = "header\nfn a() {}\nshared\nnew one\nnew two\nshared\ntail\nextra";
+        let diff = diff_lines(old, new);
+        let side = |skip| {
+            diff.iter()
+                .filter(|l| l.kind != skip)
+                .map(|l| l.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        assert_eq!(side(DiffLineKind::Added), old);
+        assert_eq!(side(DiffLineKind::Removed), new);
+        let changed = diff
+            .iter()
+            .filter(|l| l.kind != DiffLineKind::Equal)
+            .count();
+        assert_eq!(changed, 6);
+
+        // 大文件整体替换也能很快完成，不会分配 N×M 的矩阵
+        let big_old: String = (0..20_000).map(|i| format!("old {i}\n")).collect();
+        let big_new: String = (0..20_000).map(|i| format!("new {i}\n")).collect();
+        assert_eq!(diff_lines(&big_old, &big_new).len(), 40_000);
+    }
+}
@@ -0,0 +1,239 @@
+//! 项目级 AGENTS.md 导出
+//!
+//! 将已启用的提示词与 Agent 汇总为符合 AGENTS.md 约定的 Markdown 文件，
+//! 写入任意项目目录，便于把 cc-switch 中的配置作为仓库内的 agent 说明提交。
+//!
+//! 生成内容位于 `<!-- cc-switch:agents-md -->` 区块内；目标文件已存在时只替换该区块，
+//! 区块外的用户内容保持不变，首次写入已有文件时区块追加在末尾。
+
+use std::path::{Path, PathBuf};
+
+use crate::agent::AgentDefinition;
+use crate::agents::{render_agent, render_template};
+use crate::app_config::AppType;
+use crate::config::write_text_file;
+use crate::error::AppError;
+use crate::prompt::Prompt;
+use crate::services::prompt_include::expand_includes;
+use crate::services::AgentsService;
+use crate::store::AppState;
+
+const REGION_START: &str = "<!-- cc-switch:agents-md -->";
+const REGION_END: &str = "<!-- /cc-switch:agents-md -->";
+
+/// AGENTS.md 导出服务
+pub struct AgentsMdService;
+
+impl AgentsMdService {
+    /// 渲染 AGENTS.md 内容
+    ///
+    /// `app` 为 `Some` 时只包含对该应用启用的条目，否则包含对任一应用启用的条目。
+    /// 与同步到工具文件时一致：提示词先展开 `{{> id}}` 引用，正文中的模板变量按
+    /// `app`（未指定时取条目启用的第一个工具）的当前状态替换。
+    pub fn render(state: &AppState, app: Option<&AppType>) -> Result<String, AppError> {
+        let all_prompts = state.db.get_prompts()?;
+        let vars_for = |enabled: Option<AppType>| {
+            app.cloned()
+                .or(enabled)
+                .map(|target| AgentsService::template_vars(state, &target))
+                .unwrap_or_default()
+        };
+
+        let prompts: Vec<Prompt> = all_prompts
+            .values()
+            .filter(|p| match app {
+                Some(app) => p.apps.is_enabled_for(app),
+                None => p.apps.any_enabled(),
+            })
+            .map(|p| {
+                let content = expand_includes(&p.content, &all_prompts).unwrap_or_else(|e| {
+                    log::warn!("展开提示词 {} 的引用失败，引用将原样导出: {e}", p.id);
+                    p.content.clone()
+                });
+                let vars = vars_for(AppType::all().find(|a| p.apps.is_enabled_for(a)));
+                Prompt {
+                    content: render_template(&content, &vars),
+                    ..p.clone()
+                }
+            })
+            .collect();
+        let agents: Vec<AgentDefinition> = state
+            .db
+            .get_all_agents()?
+            .into_values()
+            .filter(|a| match app {
+                Some(app) => a.apps.is_enabled_for(app),
+                None => !a.apps.is_empty(),
+            })
+            .map(|a| render_agent(&a, &vars_for(a.apps.enabled_apps().into_iter().next())))
+            .collect();
+
+        Ok(render_agents_md(&prompts, &agents))
+    }
+
+    /// 导出到 `{project_dir}/AGENTS.md`，返回写入的文件路径
+    pub fn export_to_dir(
+        state: &AppState,
+        project_dir: &Path,
+        app: Option<&AppType>,
+    ) -> Result<PathBuf, AppError> {
+        if !project_dir.is_dir() {
+            return Err(AppError::localized(
+                "agents_md.invalid_dir",
+                format!("项目目录不存在: {}", project_dir.display()),
+                format!("Project directory not found: {}", project_dir.display()),
+            ));
+        }
+
+        let path = project_dir.join("AGENTS.md");
+        let region = Self::render(state, app)?;
+        let existing = if path.exists() {
+            std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?
+        } else {
+            String::new()
+        };
+
+        write_text_file(&path, &upsert_region(&existing, &region))?;
+        log::info!("已导出 AGENTS.md: {}", path.display());
+        Ok(path)
+    }
+}
+
+/// 将提示词与 Agent 渲染为带区块标记的 Markdown
+fn render_agents_md(prompts: &[Prompt], agents: &[AgentDefinition]) -> String {
+    let mut out = String::new();
+    out.push_str(REGION_START);
+    out.push('\n');
+    out.push_str("<!-- Generated by CC Switch. Edit the source prompts/agents in CC Switch and re-export. -->\n");
+
+    if !prompts.is_empty() {
+        out.push_str("\n## Instructions\n");
+        for prompt in prompts {
+            if prompts.len() > 1 {
+                out.push_str(&format!("\n### {}\n", prompt.name));
+            }
+            out.push('\n');
+            push_body(&mut out, &prompt.content);
+        }
+    }
+
+    if !agents.is_empty() {
+        out.push_str("\n## Agents\n");
+        for agent in agents {
+            out.push_str(&format!("\n### {}\n", agent.name));
+            if let Some(desc) = agent.description.as_deref().filter(|d| !d.is_empty()) {
+                out.push_str(&format!("\n> {desc}\n"));
+            }
+            out.push('\n');
+            push_body(&mut out, &agent.content);
+        }
+    }
+
+    out.push('\n');
+    out.push_str(REGION_END);
+    out.push('\n');
+    out
+}
+
+fn push_body(out: &mut String, body: &str) {
+    out.push_str(body.trim_end());
+    out.push('\n');
+}
+
+/// 替换已有区块，或把区块追加到文件末尾（用户内容保持在前，标题不被挤到区块之后）
+fn upsert_region(content: &str, region: &str) -> String {
+    if let (Some(start), Some(end)) = (content.find(REGION_START), content.find(REGION_END)) {
+        if start < end {
+            let after = end + REGION_END.len();
+            let after = if content[after..].starts_with('\n') {
+                after + 1
+            } else {
+                after
+            };
+            return format!("{}{}{}", &content[..start], region, &content[after..]);
+        }
+    }
+
+    if content.trim().is_empty() {
+        return format!("# AGENTS.md\n\n{region}");
+    }
+
+    let mut result = content.to_string();
+    if !result.ends_with('\n') {
+        result.push('\n');
+    }
+    result.push('\n');
+    result.push_str(region);
+    result
+}
+
+/// 去掉 cc-switch 生成的 AGENTS.md 区块，只保留用户内容
+pub(crate) fn strip_region(content: &str) -> String {
+    let (Some(start), Some(end)) = (content.find(REGION_START), content.find(REGION_END)) else {
+        return content.to_string();
+    };
+    if start > end {
+        return content.to_string();
+    }
+    let after = end + REGION_END.len();
+    let after = if content[after..].starts_with('\n') {
+        after + 1
+    } else {
+        after
+    };
+    format!("{}{}", &content[..start], &content[after..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompt::PromptApps;
+
+    fn prompt(id: &str, content: &str) -> Prompt {
+        Prompt {
+            id: id.to_string(),
+            name: id.to_string(),
+            content: content.to_string(),
+            description: None,
+            tags: Vec::new(),
+            apps: PromptApps::default(),
+            sort_index: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn agent(id: &str, content: &str) -> AgentDefinition {
+        AgentDefinition {
+            id: id.to_string(),
+            name: format!("Agent {id}"),
+            content: content.to_string(),
+            description: Some("desc".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn render_includes_prompts_and_agents() {
+        let md = render_agents_md(&[prompt("p1", "Be concise.")], &[agent("a1", "Review.")]);
+        assert!(md.starts_with(REGION_START));
+        assert!(md.contains("## Instructions\n\nBe concise.\n"));
+        assert!(md.contains("### Agent a1\n\n> desc\n\nReview.\n"));
+        assert!(md.trim_end().ends_with(REGION_END));
+    }
+
+    #[test]
+    fn upsert_region_preserves_user_content() {
+        let region = render_agents_md(&[], &[agent("a1", "v1")]);
+        let initial = upsert_region("# My Project\n\nHand-written notes.\n", &region);
+        assert!(initial.starts_with("# My Project"));
+        assert!(initial.trim_end().ends_with(REGION_END));
+
+        let region2 = render_agents_md(&[], &[agent("a1", "v2")]);
+        let updated = upsert_region(&initial, &region2);
+        assert!(updated.contains("Hand-written notes."));
+        assert!(updated.contains("v2"));
+        assert!(!updated.contains("v1"));
+        assert_eq!(updated.matches(REGION_START).count(), 1);
+    }
+}
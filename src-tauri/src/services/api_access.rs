@@ -0,0 +1,269 @@
+//! 本地 API 的访问令牌与权限
+//!
+//! 自动化脚本通过代理服务器上的管理接口（`/cc-switch/v1/...`）访问 cc-switch，
+//! 每个请求都要携带一个 API 令牌（`Authorization: Bearer ccs_...`）。令牌创建时选择权限：
+//!
+//! - `read`：列出供应商，配置中的密钥字段被遮蔽
+//! - `switch`：切换当前供应商
+//! - `mutateConfig`：修改、删除数据，以及读取未遮蔽的密钥
+//!
+//! 权限检查在这里的服务函数中完成，HTTP 层只负责取出令牌与映射状态码，
+//! 之后接入的其他入口（如 MCP 工具）复用同一组函数即可获得相同的限制。
+//! 应用内的 Tauri 命令不经过这里，不受令牌限制。
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::secret_store;
+use crate::services::{ProviderAuditOrigin, ProviderService, SwitchResult};
+use crate::store::AppState;
+
+/// 令牌明文前缀，便于在日志和密钥扫描中识别
+const TOKEN_PREFIX: &str = "ccs_";
+
+/// 令牌权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiScope {
+    Read,
+    Switch,
+    MutateConfig,
+}
+
+impl ApiScope {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Switch => "switch",
+            Self::MutateConfig => "mutateConfig",
+        }
+    }
+}
+
+/// API 令牌（不含明文与摘要）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    /// Unix 秒
+    pub created_at: i64,
+    /// 最近一次通过认证的时间（Unix 秒）
+    pub last_used_at: Option<i64>,
+}
+
+/// 新创建的令牌，`secret` 只在此时返回一次
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedApiToken {
+    pub token: ApiToken,
+    pub secret: String,
+}
+
+/// 已通过认证的调用方
+#[derive(Debug, Clone)]
+pub struct ApiCaller {
+    token: ApiToken,
+}
+
+impl ApiCaller {
+    pub fn token(&self) -> &ApiToken {
+        &self.token
+    }
+
+    pub fn has(&self, scope: ApiScope) -> bool {
+        self.token.scopes.contains(&scope)
+    }
+
+    /// 缺少权限时返回 `api.forbidden` 错误
+    pub fn require(&self, scope: ApiScope) -> Result<(), AppError> {
+        if self.has(scope) {
+            return Ok(());
+        }
+        Err(AppError::localized(
+            "api.forbidden",
+            format!("令牌 {} 没有 {} 权限", self.token.name, scope.as_str()),
+            format!(
+                "Token {} lacks the {} scope",
+                self.token.name,
+                scope.as_str()
+            ),
+        ))
+    }
+}
+
+/// 通过 API 返回的供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiProvider {
+    pub id: String,
+    pub name: String,
+    pub category: Option<String>,
+    pub website_url: Option<String>,
+    pub is_current: bool,
+    /// 没有 `mutateConfig` 权限时密钥字段被替换为 `***`
+    pub settings_config: Value,
+}
+
+pub struct ApiAccessService;
+
+impl ApiAccessService {
+    /// 创建令牌，返回只显示一次的明文
+    pub fn create_token(
+        db: &Database,
+        name: &str,
+        scopes: Vec<ApiScope>,
+    ) -> Result<IssuedApiToken, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::InvalidInput("令牌名称不能为空".to_string()));
+        }
+        if scopes.is_empty() {
+            return Err(AppError::InvalidInput("至少选择一项权限".to_string()));
+        }
+        let mut unique = Vec::with_capacity(scopes.len());
+        for scope in scopes {
+            if !unique.contains(&scope) {
+                unique.push(scope);
+            }
+        }
+
+        let secret = format!(
+            "{TOKEN_PREFIX}{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let token = ApiToken {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            scopes: unique,
+            created_at: chrono::Utc::now().timestamp(),
+            last_used_at: None,
+        };
+        db.insert_api_token(&token, &hash_secret(&secret))?;
+        Ok(IssuedApiToken { token, secret })
+    }
+
+    /// 获取全部令牌
+    pub fn list_tokens(db: &Database) -> Result<Vec<ApiToken>, AppError> {
+        db.get_api_tokens()
+    }
+
+    /// 吊销令牌，返回是否存在
+    pub fn revoke_token(db: &Database, id: &str) -> Result<bool, AppError> {
+        db.delete_api_token(id)
+    }
+
+    /// 按令牌明文认证调用方；缺少或无效时返回 `api.unauthorized` 错误
+    pub fn authenticate(db: &Database, secret: Option<&str>) -> Result<ApiCaller, AppError> {
+        let token = match secret
+            .map(str::trim)
+            .filter(|s| s.starts_with(TOKEN_PREFIX))
+        {
+            Some(secret) => db.find_api_token_by_hash(&hash_secret(secret))?,
+            None => None,
+        };
+        let Some(mut token) = token else {
+            return Err(AppError::localized(
+                "api.unauthorized",
+                "缺少或无效的 API 令牌",
+                "Missing or invalid API token",
+            ));
+        };
+        let now = chrono::Utc::now().timestamp();
+        if let Err(e) = db.touch_api_token(&token.id, now) {
+            log::debug!("记录 API 令牌使用时间失败: {e}");
+        }
+        token.last_used_at = Some(now);
+        Ok(ApiCaller { token })
+    }
+
+    /// 列出应用的供应商（需要 `read`）
+    pub fn list_providers(
+        state: &AppState,
+        caller: &ApiCaller,
+        app: &str,
+    ) -> Result<Vec<ApiProvider>, AppError> {
+        caller.require(ApiScope::Read)?;
+        let app_type = AppType::from_str(app)?;
+        let current = state.db.get_current_provider(app_type.as_str())?;
+        let reveal = caller.has(ApiScope::MutateConfig);
+        Ok(state
+            .db
+            .get_all_providers(app_type.as_str())?
+            .into_values()
+            .map(|provider| {
+                let mut settings_config = provider.settings_config;
+                if !reveal {
+                    secret_store::redact_secrets(&app_type, &mut settings_config);
+                }
+                ApiProvider {
+                    is_current: current.as_deref() == Some(provider.id.as_str()),
+                    id: provider.id,
+                    name: provider.name,
+                    category: provider.category,
+                    website_url: provider.website_url,
+                    settings_config,
+                }
+            })
+            .collect())
+    }
+
+    /// 切换当前供应商（需要 `switch`）
+    pub fn switch_provider(
+        state: &AppState,
+        caller: &ApiCaller,
+        app: &str,
+        id: &str,
+    ) -> Result<SwitchResult, AppError> {
+        caller.require(ApiScope::Switch)?;
+        ProviderService::switch_from(state, AppType::from_str(app)?, id, ProviderAuditOrigin::Api)
+    }
+
+    /// 删除供应商（需要 `mutateConfig`）
+    pub fn delete_provider(
+        state: &AppState,
+        caller: &ApiCaller,
+        app: &str,
+        id: &str,
+    ) -> Result<(), AppError> {
+        caller.require(ApiScope::MutateConfig)?;
+        ProviderService::delete_from(state, AppType::from_str(app)?, id, ProviderAuditOrigin::Api)
+    }
+}
+
+fn hash_secret(secret: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(secret.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caller_requires_each_scope_explicitly() {
+        let caller = ApiCaller {
+            token: ApiToken {
+                id: "t1".to_string(),
+                name: "script".to_string(),
+                scopes: vec![ApiScope::Read, ApiScope::Switch],
+                created_at: 0,
+                last_used_at: None,
+            },
+        };
+        assert!(caller.require(ApiScope::Switch).is_ok());
+        match caller.require(ApiScope::MutateConfig) {
+            Err(AppError::Localized { key, .. }) => assert_eq!(key, "api.forbidden"),
+            other => panic!("expected forbidden, got {other:?}"),
+        }
+    }
+}
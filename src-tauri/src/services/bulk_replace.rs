@@ -0,0 +1,306 @@
+//! 提示词与 Agent 正文的批量查找替换
+//!
+//! 先以 `dry_run` 预览每个条目的匹配数与变更行，确认后在同一个事务中写入全部替换结果。
+//! 应用前会生成一份数据库备份（可在备份列表中恢复）；提示词各记录一条替换前的历史快照，
+//! Agent 各追加一条历史版本，均可单独回滚。
+
+use regex::{NoExpand, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::agent::{AgentDefinition, DiffLine, DiffLineKind};
+use crate::agents::{self, AgentChange};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::prompt::Prompt;
+use crate::services::agents::diff_lines;
+use crate::services::prompt::PROMPT_APPS;
+use crate::services::sync_guard::{best_effort, sync_or_rollback};
+use crate::services::{AgentsService, PromptProjectService, PromptService};
+use crate::store::AppState;
+
+/// 替换范围
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkReplaceKind {
+    Prompts,
+    Agents,
+    #[default]
+    All,
+}
+
+impl BulkReplaceKind {
+    fn includes_prompts(self) -> bool {
+        matches!(self, Self::Prompts | Self::All)
+    }
+
+    fn includes_agents(self) -> bool {
+        matches!(self, Self::Agents | Self::All)
+    }
+}
+
+/// 命中条目的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplaceItemKind {
+    Prompt,
+    Agent,
+}
+
+/// 单个条目的匹配情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceMatch {
+    pub kind: ReplaceItemKind,
+    pub id: String,
+    pub name: String,
+    /// 正文中的匹配次数
+    pub count: usize,
+    /// 替换前后发生变化的行（只含新增 / 删除行）
+    pub changes: Vec<DiffLine>,
+}
+
+/// 批量替换结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkReplaceResult {
+    pub matches: Vec<ReplaceMatch>,
+    /// 所有条目的匹配总数
+    pub total_matches: usize,
+    /// 是否已写入（dry run 或没有实际变化时为 false）
+    pub applied: bool,
+    /// 写入前生成的数据库备份 id；未写入或未生成备份时为 None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_id: Option<String>,
+}
+
+/// 批量查找替换服务
+pub struct BulkReplaceService;
+
+impl BulkReplaceService {
+    /// 在提示词和/或 Agent 正文中查找 `pattern` 并替换为 `replacement`
+    ///
+    /// `regex` 为 false 时按字面匹配（替换文本中的 `$` 不做展开）；为 true 时支持 `$1` / `${name}` 引用分组。
+    /// `dry_run` 为 true 时只返回预览，不写入数据库和工具文件。
+    pub fn bulk_replace(
+        state: &AppState,
+        kind: BulkReplaceKind,
+        pattern: &str,
+        replacement: &str,
+        regex: bool,
+        dry_run: bool,
+    ) -> Result<BulkReplaceResult, AppError> {
+        let matcher = Matcher::new(pattern, replacement, regex)?;
+        let mut result = BulkReplaceResult::default();
+
+        let mut prompt_edits: Vec<(Prompt, Prompt)> = Vec::new();
+        if kind.includes_prompts() {
+            for prompt in state.db.get_prompts()?.into_values() {
+                let Some((count, content)) = matcher.replace(&prompt.content) else {
+                    continue;
+                };
+                result.matches.push(ReplaceMatch {
+                    kind: ReplaceItemKind::Prompt,
+                    id: prompt.id.clone(),
+                    name: prompt.name.clone(),
+                    count,
+                    changes: changed_lines(&prompt.content, &content),
+                });
+                result.total_matches += count;
+                if content != prompt.content {
+                    let replaced = Prompt {
+                        content,
+                        updated_at: Some(chrono::Utc::now().timestamp()),
+                        ..prompt.clone()
+                    };
+                    prompt_edits.push((prompt, replaced));
+                }
+            }
+        }
+
+        let mut agent_edits: Vec<(AgentDefinition, AgentDefinition)> = Vec::new();
+        if kind.includes_agents() {
+            for agent in state.db.get_all_agents()?.into_values() {
+                let Some((count, content)) = matcher.replace(&agent.content) else {
+                    continue;
+                };
+                result.matches.push(ReplaceMatch {
+                    kind: ReplaceItemKind::Agent,
+                    id: agent.id.clone(),
+                    name: agent.name.clone(),
+                    count,
+                    changes: changed_lines(&agent.content, &content),
+                });
+                result.total_matches += count;
+                if content != agent.content {
+                    let replaced = AgentDefinition {
+                        content,
+                        updated_at: Some(chrono::Utc::now().timestamp_millis()),
+                        ..agent.clone()
+                    };
+                    agent_edits.push((agent, replaced));
+                }
+            }
+        }
+
+        if dry_run || (prompt_edits.is_empty() && agent_edits.is_empty()) {
+            return Ok(result);
+        }
+
+        result.backup_id = state
+            .db
+            .backup_database_file()?
+            .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()));
+
+        let new_prompts: Vec<Prompt> = prompt_edits.iter().map(|(_, new)| new.clone()).collect();
+        let new_agents: Vec<AgentDefinition> =
+            agent_edits.iter().map(|(_, new)| new.clone()).collect();
+        state.db.save_replaced_content(&prompt_edits, &new_agents)?;
+
+        sync_or_rollback(
+            "批量替换",
+            || Self::sync_files(state, &new_prompts, &new_agents),
+            || {
+                for (old, _) in &prompt_edits {
+                    state.db.save_prompt(old)?;
+                }
+                let reverts: Vec<(&str, Option<&AgentDefinition>)> = agent_edits
+                    .iter()
+                    .map(|(old, _)| (old.id.as_str(), Some(old)))
+                    .collect();
+                state.db.revert_agent_saves(&reverts)?;
+
+                let old_prompts: Vec<Prompt> =
+                    prompt_edits.iter().map(|(old, _)| old.clone()).collect();
+                let old_agents: Vec<AgentDefinition> =
+                    agent_edits.iter().map(|(old, _)| old.clone()).collect();
+                best_effort(
+                    "恢复工具文件",
+                    Self::sync_files(state, &old_prompts, &old_agents),
+                );
+                Ok(())
+            },
+        )?;
+
+        result.applied = true;
+        log::info!(
+            "批量替换已写入：{} 个提示词、{} 个 agent，共 {} 处",
+            prompt_edits.len(),
+            agent_edits.len(),
+            result.total_matches
+        );
+        Ok(result)
+    }
+
+    /// 把发生变化的条目重新写入启用它们的工具文件
+    ///
+    /// 提示词按 app 重新拼接写入当前启用的内容（数据库已是新内容），并重写挂载了提示词的项目文件；
+    /// Agent 按 app 合并为一次写入。
+    fn sync_files(
+        state: &AppState,
+        prompts: &[Prompt],
+        agent_defs: &[AgentDefinition],
+    ) -> Result<(), AppError> {
+        for app in &PROMPT_APPS {
+            if prompts.iter().any(|p| p.apps.is_enabled_for(app)) {
+                PromptService::sync_enabled_to_app(state, app)?;
+            }
+        }
+        if !prompts.is_empty() {
+            PromptProjectService::resync_all(state)?;
+        }
+        for app in AppType::all() {
+            let changes: Vec<AgentChange<'_>> = agent_defs
+                .iter()
+                .filter(|a| a.apps.is_enabled_for(&app))
+                .map(AgentChange::Upsert)
+                .collect();
+            if !changes.is_empty() {
+                agents::apply_agent_changes(
+                    &app,
+                    &changes,
+                    &AgentsService::template_vars(state, &app),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 编译后的查找规则
+struct Matcher<'a> {
+    re: Regex,
+    replacement: &'a str,
+    expand: bool,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(pattern: &str, replacement: &'a str, regex: bool) -> Result<Self, AppError> {
+        if pattern.is_empty() {
+            return Err(AppError::InvalidInput("查找内容不能为空".to_string()));
+        }
+        let source = if regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        let re = Regex::new(&source).map_err(|e| {
+            AppError::localized(
+                "bulk_replace.invalid_regex",
+                format!("无效的正则表达式: {e}"),
+                format!("Invalid regular expression: {e}"),
+            )
+        })?;
+        Ok(Self {
+            re,
+            replacement,
+            expand: regex,
+        })
+    }
+
+    /// 返回 (匹配次数, 替换后的文本)；没有匹配时返回 None
+    fn replace(&self, text: &str) -> Option<(usize, String)> {
+        let count = self.re.find_iter(text).count();
+        if count == 0 {
+            return None;
+        }
+        let replaced = if self.expand {
+            self.re.replace_all(text, self.replacement)
+        } else {
+            self.re.replace_all(text, NoExpand(self.replacement))
+        };
+        Some((count, replaced.into_owned()))
+    }
+}
+
+fn changed_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    diff_lines(old, new)
+        .into_iter()
+        .filter(|line| line.kind != DiffLineKind::Equal)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_mode_does_not_expand_dollar_references() {
+        let matcher = Matcher::new("a.b", "$1 x", false).unwrap();
+        assert_eq!(matcher.replace("axb"), None);
+        assert_eq!(
+            matcher.replace("a.b and a.b"),
+            Some((2, "$1 x and $1 x".to_string()))
+        );
+    }
+
+    #[test]
+    fn regex_mode_expands_groups_and_rejects_invalid_patterns() {
+        let matcher = Matcher::new(r"Project (\w+)", "Codename $1", true).unwrap();
+        assert_eq!(
+            matcher.replace("Project Falcon\nProject Owl"),
+            Some((2, "Codename Falcon\nCodename Owl".to_string()))
+        );
+        assert!(Matcher::new("(", "", true).is_err());
+        assert!(Matcher::new("", "x", false).is_err());
+    }
+}
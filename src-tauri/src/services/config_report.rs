@@ -0,0 +1,503 @@
+//! 配置报告导出
+//!
+//! 生成一份可打印、可审阅的 HTML / Markdown 文档，汇总供应商（凭据已遮蔽）、提示词、
+//! Agent、MCP 服务器、Skills 以及它们在各应用中的启用情况，便于审计或记录团队的标准配置。
+//! 报告只读数据库，不读写任何工具的配置文件。
+
+use std::path::Path;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::config::write_text_file;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::secret_store;
+use crate::services::prompt::PROMPT_APPS;
+use crate::services::stream_check::StreamCheckService;
+
+/// 报告格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+}
+
+impl FromStr for ReportFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "html" | "htm" => Ok(Self::Html),
+            "markdown" | "md" => Ok(Self::Markdown),
+            other => Err(AppError::localized(
+                "report.unsupported_format",
+                format!("不支持的报告格式: {other}"),
+                format!("Unsupported report format: {other}"),
+            )),
+        }
+    }
+}
+
+/// 报告中的一张表
+struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+/// 报告中的一个章节（可包含多张带小标题的表）
+struct Section {
+    title: String,
+    tables: Vec<(Option<String>, Table)>,
+}
+
+/// 配置报告服务
+pub struct ConfigReportService;
+
+impl ConfigReportService {
+    /// 生成报告并写入 `path`
+    pub fn write_to_file(db: &Database, path: &Path, format: ReportFormat) -> Result<(), AppError> {
+        let report = Self::generate(db, format)?;
+        write_text_file(path, &report)?;
+        log::info!("已生成配置报告: {}", path.display());
+        Ok(())
+    }
+
+    /// 生成报告内容
+    pub fn generate(db: &Database, format: ReportFormat) -> Result<String, AppError> {
+        let sections = vec![
+            Self::providers_section(db)?,
+            Self::prompts_section(db)?,
+            Self::agents_section(db)?,
+            Self::mcp_section(db)?,
+            Self::skills_section(db)?,
+            Self::matrix_section(db)?,
+        ];
+        let generated_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        Ok(match format {
+            ReportFormat::Markdown => render_markdown(&generated_at, &sections),
+            ReportFormat::Html => render_html(&generated_at, &sections),
+        })
+    }
+
+    fn providers_section(db: &Database) -> Result<Section, AppError> {
+        let mut tables = Vec::new();
+        for app in AppType::all() {
+            let providers = db.get_all_providers(app.as_str())?;
+            if providers.is_empty() {
+                continue;
+            }
+            let current = if app.is_additive_mode() {
+                None
+            } else {
+                crate::settings::get_effective_current_provider(db, &app)?
+            };
+            let rows = providers
+                .values()
+                .map(|p| {
+                    vec![
+                        p.name.clone(),
+                        p.id.clone(),
+                        yes_no(current.as_deref() == Some(p.id.as_str())),
+                        endpoints(p).join(", "),
+                        StreamCheckService::provider_model(&app, p).unwrap_or_default(),
+                        credentials(&app, &p.settings_config).join(", "),
+                    ]
+                })
+                .collect();
+            tables.push((
+                Some(app.as_str().to_string()),
+                Table {
+                    headers: vec!["Name", "ID", "Current", "Endpoint", "Model", "Credentials"],
+                    rows,
+                },
+            ));
+        }
+        Ok(Section {
+            title: "Providers".to_string(),
+            tables,
+        })
+    }
+
+    fn prompts_section(db: &Database) -> Result<Section, AppError> {
+        let rows = db
+            .get_prompts()?
+            .values()
+            .map(|p| {
+                let apps: Vec<AppType> = PROMPT_APPS
+                    .iter()
+                    .filter(|app| p.apps.is_enabled_for(app))
+                    .cloned()
+                    .collect();
+                vec![
+                    p.name.clone(),
+                    p.id.clone(),
+                    p.description.clone().unwrap_or_default(),
+                    app_list(&apps),
+                    p.content.chars().count().to_string(),
+                ]
+            })
+            .collect();
+        Ok(single_table(
+            "Prompts",
+            vec!["Name", "ID", "Description", "Apps", "Characters"],
+            rows,
+        ))
+    }
+
+    fn agents_section(db: &Database) -> Result<Section, AppError> {
+        let rows = db
+            .get_all_agents()?
+            .values()
+            .map(|a| {
+                vec![
+                    a.name.clone(),
+                    a.id.clone(),
+                    a.group.clone().unwrap_or_default(),
+                    a.model.clone().unwrap_or_default(),
+                    a.description.clone().unwrap_or_default(),
+                    app_list(&a.apps.enabled_apps()),
+                ]
+            })
+            .collect();
+        Ok(single_table(
+            "Agents",
+            vec!["Name", "ID", "Group", "Model", "Description", "Apps"],
+            rows,
+        ))
+    }
+
+    fn mcp_section(db: &Database) -> Result<Section, AppError> {
+        let rows = db
+            .get_all_mcp_servers()?
+            .values()
+            .map(|s| {
+                let transport = s
+                    .server
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .unwrap_or("stdio")
+                    .to_string();
+                // 只展示命令或 URL，env / headers 中可能含凭据，不写入报告
+                let target = s
+                    .server
+                    .get("command")
+                    .or_else(|| s.server.get("url"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                vec![
+                    s.name.clone(),
+                    s.id.clone(),
+                    transport,
+                    target,
+                    app_list(&s.apps.enabled_apps()),
+                ]
+            })
+            .collect();
+        Ok(single_table(
+            "MCP Servers",
+            vec!["Name", "ID", "Transport", "Command / URL", "Apps"],
+            rows,
+        ))
+    }
+
+    fn skills_section(db: &Database) -> Result<Section, AppError> {
+        let rows = db
+            .get_all_installed_skills()?
+            .values()
+            .map(|s| {
+                let source = match (&s.repo_owner, &s.repo_name) {
+                    (Some(owner), Some(name)) => format!("{owner}/{name}"),
+                    _ => "local".to_string(),
+                };
+                vec![
+                    s.name.clone(),
+                    s.directory.clone(),
+                    source,
+                    app_list(&s.apps.enabled_apps()),
+                ]
+            })
+            .collect();
+        Ok(single_table(
+            "Skills",
+            vec!["Name", "Directory", "Source", "Apps"],
+            rows,
+        ))
+    }
+
+    /// 启用矩阵：每行一个条目，每列一个应用（该应用不支持此类条目时为 n/a）
+    fn matrix_section(db: &Database) -> Result<Section, AppError> {
+        let mut rows = Vec::new();
+        let mut push = |kind: &str, name: &str, enabled: &dyn Fn(&AppType) -> Option<bool>| {
+            let mut row = vec![kind.to_string(), name.to_string()];
+            row.extend(AppType::all().map(|app| match enabled(&app) {
+                Some(true) => "✓".to_string(),
+                Some(false) => "".to_string(),
+                None => "n/a".to_string(),
+            }));
+            rows.push(row);
+        };
+
+        for p in db.get_prompts()?.values() {
+            push("Prompt", &p.name, &|app| {
+                PROMPT_APPS
+                    .contains(app)
+                    .then(|| p.apps.is_enabled_for(app))
+            });
+        }
+        for a in db.get_all_agents()?.values() {
+            push("Agent", &a.name, &|app| Some(a.apps.is_enabled_for(app)));
+        }
+        for s in db.get_all_mcp_servers()?.values() {
+            push("MCP", &s.name, &|app| {
+                (!matches!(app, AppType::OpenClaw)).then(|| s.apps.is_enabled_for(app))
+            });
+        }
+        for s in db.get_all_installed_skills()?.values() {
+            push("Skill", &s.name, &|app| {
+                (!matches!(app, AppType::OpenClaw)).then(|| s.apps.is_enabled_for(app))
+            });
+        }
+
+        Ok(single_table(
+            "Enablement Matrix",
+            vec![
+                "Type", "Name", "Claude", "Codex", "Gemini", "OpenCode", "OpenClaw",
+            ],
+            rows,
+        ))
+    }
+}
+
+fn single_table(title: &str, headers: Vec<&'static str>, rows: Vec<Vec<String>>) -> Section {
+    Section {
+        title: title.to_string(),
+        tables: vec![(None, Table { headers, rows })],
+    }
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "✓" } else { "" }.to_string()
+}
+
+fn app_list(apps: &[AppType]) -> String {
+    apps.iter()
+        .map(|app| app.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 遮蔽凭据：保留前 4 位和后 4 位，过短时整体隐藏
+fn mask_secret(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() > 12 {
+        let prefix: String = chars[..4].iter().collect();
+        let suffix: String = chars[chars.len() - 4..].iter().collect();
+        format!("{prefix}…{suffix}")
+    } else {
+        "***".to_string()
+    }
+}
+
+/// 递归收集配置中的字符串字段，返回 (字段名, 值)
+fn string_fields<'a>(value: &'a Value, out: &mut Vec<(&'a str, &'a str)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    Value::String(s) => out.push((key.as_str(), s.as_str())),
+                    other => string_fields(other, out),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                string_fields(item, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 供应商配置中的接口地址
+fn endpoints(provider: &Provider) -> Vec<String> {
+    let mut fields = Vec::new();
+    string_fields(&provider.settings_config, &mut fields);
+    let mut urls: Vec<String> = Vec::new();
+    for (key, value) in fields {
+        let key = key.to_lowercase();
+        if (key.contains("base_url") || key.contains("baseurl") || key == "url")
+            && !value.trim().is_empty()
+            && !urls.iter().any(|u| u == value)
+        {
+            urls.push(value.to_string());
+        }
+    }
+    urls
+}
+
+/// 供应商配置中的凭据字段（已遮蔽），形如 `ANTHROPIC_AUTH_TOKEN=sk-a…wxyz`
+fn credentials(app: &AppType, settings: &Value) -> Vec<String> {
+    let mut masked = settings.clone();
+    let mut found = Vec::new();
+    secret_store::mask_secrets(app, &mut masked, &mut |name, secret| {
+        found.push(format!("{name}={}", mask_secret(secret)));
+        String::new()
+    });
+    found
+}
+
+fn escape_markdown_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_markdown(generated_at: &str, sections: &[Section]) -> String {
+    let mut out = format!("# CC Switch Configuration Report\n\nGenerated at {generated_at}\n");
+    for section in sections {
+        out.push_str(&format!("\n## {}\n", section.title));
+        if section.tables.is_empty() {
+            out.push_str("\n_None_\n");
+        }
+        for (subtitle, table) in &section.tables {
+            if let Some(subtitle) = subtitle {
+                out.push_str(&format!("\n### {subtitle}\n"));
+            }
+            if table.rows.is_empty() {
+                out.push_str("\n_None_\n");
+                continue;
+            }
+            out.push_str(&format!("\n| {} |\n", table.headers.join(" | ")));
+            out.push_str(&format!("|{}\n", "---|".repeat(table.headers.len())));
+            for row in &table.rows {
+                let cells: Vec<String> = row.iter().map(|c| escape_markdown_cell(c)).collect();
+                out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+        }
+    }
+    out
+}
+
+fn render_html(generated_at: &str, sections: &[Section]) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>CC Switch Configuration Report</title>\n<style>\n\
+         body { font-family: -apple-system, 'Segoe UI', sans-serif; margin: 2rem; color: #222; }\n\
+         table { border-collapse: collapse; margin: 0.5rem 0 1.5rem; width: 100%; }\n\
+         th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }\n\
+         th { background: #f3f3f3; }\n\
+         @media print { h2 { page-break-before: auto; } tr { page-break-inside: avoid; } }\n\
+         </style>\n</head>\n<body>\n<h1>CC Switch Configuration Report</h1>\n",
+    );
+    out.push_str(&format!(
+        "<p>Generated at {}</p>\n",
+        escape_html(generated_at)
+    ));
+    for section in sections {
+        out.push_str(&format!("<h2>{}</h2>\n", escape_html(&section.title)));
+        if section.tables.is_empty() {
+            out.push_str("<p><em>None</em></p>\n");
+        }
+        for (subtitle, table) in &section.tables {
+            if let Some(subtitle) = subtitle {
+                out.push_str(&format!("<h3>{}</h3>\n", escape_html(subtitle)));
+            }
+            if table.rows.is_empty() {
+                out.push_str("<p><em>None</em></p>\n");
+                continue;
+            }
+            out.push_str("<table>\n<tr>");
+            for header in &table.headers {
+                out.push_str(&format!("<th>{}</th>", escape_html(header)));
+            }
+            out.push_str("</tr>\n");
+            for row in &table.rows {
+                out.push_str("<tr>");
+                for cell in row {
+                    out.push_str(&format!("<td>{}</td>", escape_html(cell)));
+                }
+                out.push_str("</tr>\n");
+            }
+            out.push_str("</table>\n");
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompt::{Prompt, PromptApps};
+    use serde_json::json;
+
+    #[test]
+    fn credentials_are_masked() {
+        let settings = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-ant-1234567890abcdef",
+                "ANTHROPIC_BASE_URL": "https://api.example.com",
+                "SHORT_KEY": "abc"
+            }
+        });
+        let creds = credentials(&AppType::Claude, &settings);
+        assert!(creds.contains(&"ANTHROPIC_AUTH_TOKEN=sk-a…cdef".to_string()));
+        assert!(creds.contains(&"SHORT_KEY=***".to_string()));
+        assert!(!creds.iter().any(|c| c.contains("1234567890")));
+    }
+
+    #[test]
+    fn renders_escaped_tables() {
+        let sections = vec![single_table(
+            "Agents",
+            vec!["Name", "Description"],
+            vec![vec!["a|b".to_string(), "<script>\nx".to_string()]],
+        )];
+        let md = render_markdown("now", &sections);
+        assert!(md.contains("| a\\|b | <script> x |"));
+        let html = render_html("now", &sections);
+        assert!(html.contains("<td>&lt;script&gt;\nx</td>"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn prompt_enabled_only_for_openclaw_is_reported() {
+        let db = Database::memory().expect("create memory db");
+        db.save_prompt(&Prompt {
+            id: "p1".to_string(),
+            name: "Claw only".to_string(),
+            content: "hello".to_string(),
+            description: None,
+            tags: Vec::new(),
+            apps: PromptApps {
+                openclaw: true,
+                ..Default::default()
+            },
+            sort_index: None,
+            created_at: None,
+            updated_at: None,
+        })
+        .expect("save prompt");
+
+        let prompts = ConfigReportService::prompts_section(&db).expect("prompts section");
+        assert_eq!(prompts.tables[0].1.rows[0][3], "openclaw");
+
+        let matrix = ConfigReportService::matrix_section(&db).expect("matrix section");
+        let row = &matrix.tables[0].1.rows[0];
+        assert_eq!(row[..2], ["Prompt", "Claw only"]);
+        assert_eq!(row[2..], ["", "", "", "", "✓"]);
+    }
+}
@@ -0,0 +1,302 @@
+//! 上下文预算估算
+//!
+//! 估算 cc-switch 为某个工具写入的内容（提示词、Agent、Skill 描述）在每次会话开始时占用的 token，
+//! 并与该工具的大致上下文窗口对比，占比过高时给出提示。
+//!
+//! 估算只用于量级判断：ASCII 字符按 4 个算 1 个 token，其余字符（中文等）按 1 个算 1 个。
+//! 各 agent 独立成文件的工具只在上下文中常驻 name / description，正文按需加载；
+//! 写入共享 marker 文件的工具则整段常驻。Skill 同理只计 name / description。
+//!
+//! [`ContextBudgetService::preview_composed_context`] 则给出同步后工具实际读到的文本，
+//! 用于在长会话前核对拼接顺序与重复内容。
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::AgentDefinition;
+use crate::agents;
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::prompt_files::prompt_file_path;
+use crate::services::prompt::PROMPT_APPS;
+use crate::services::{AgentsService, PromptService};
+use crate::store::AppState;
+
+/// 占比达到该值时提示偏高
+const WARNING_SHARE: f64 = 0.10;
+/// 占比达到该值时提示严重
+const CRITICAL_SHARE: f64 = 0.25;
+
+/// 各工具默认模型的大致上下文窗口（token）
+fn context_window(app: &AppType) -> u64 {
+    match app {
+        AppType::Claude => 200_000,
+        AppType::Codex => 272_000,
+        AppType::Gemini => 1_000_000,
+        AppType::OpenCode | AppType::OpenClaw => 128_000,
+    }
+}
+
+/// 粗略估算文本的 token 数
+fn estimate_tokens(text: &str) -> u64 {
+    let (ascii, other) = text.chars().fold((0u64, 0u64), |(ascii, other), c| {
+        if c.is_ascii() {
+            (ascii + 1, other)
+        } else {
+            (ascii, other + 1)
+        }
+    });
+    ascii.div_ceil(4) + other
+}
+
+/// 占用来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextItemKind {
+    Prompt,
+    Agent,
+    Skill,
+}
+
+/// 占比等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextBudgetLevel {
+    Ok,
+    Warning,
+    Critical,
+}
+
+/// 单个条目的估算占用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextBudgetItem {
+    pub kind: ContextItemKind,
+    pub id: String,
+    pub name: String,
+    pub tokens: u64,
+}
+
+/// 某个工具的上下文预算
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextBudget {
+    pub app: String,
+    /// 大致上下文窗口
+    pub context_window: u64,
+    /// 按占用从高到低排序
+    pub items: Vec<ContextBudgetItem>,
+    pub total_tokens: u64,
+    /// 占上下文窗口的比例（0 ~ 1）
+    pub share: f64,
+    pub level: ContextBudgetLevel,
+}
+
+/// 同步后工具会读取的一个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposedFile {
+    pub path: String,
+    pub content: String,
+}
+
+/// 按当前启用状态推演出的工具上下文
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposedContext {
+    pub app: String,
+    /// 指令文件在前（提示词区域与共享 agent 区块按写入顺序拼接，保留 cc-switch 标记），
+    /// 其后为独立成文件的 agent
+    pub files: Vec<ComposedFile>,
+}
+
+/// 上下文预算服务
+pub struct ContextBudgetService;
+
+impl ContextBudgetService {
+    /// 估算指定工具中由 cc-switch 管理的内容占用的上下文
+    pub fn get_context_budget(state: &AppState, app: &AppType) -> Result<ContextBudget, AppError> {
+        let mut items = Vec::new();
+
+        if PROMPT_APPS.contains(app) {
+            items.extend(
+                state
+                    .db
+                    .get_prompts()?
+                    .into_values()
+                    .filter(|p| p.apps.is_enabled_for(app))
+                    .map(|p| ContextBudgetItem {
+                        kind: ContextItemKind::Prompt,
+                        tokens: estimate_tokens(&p.content),
+                        id: p.id,
+                        name: p.name,
+                    }),
+            );
+        }
+
+        // 独立文件的工具只常驻 frontmatter 中的描述信息
+        let summary_only = agents::agent_files_dir(app).is_some();
+        items.extend(
+            state
+                .db
+                .get_all_agents()?
+                .into_values()
+                .filter(|a| a.apps.is_enabled_for(app))
+                .map(|a| ContextBudgetItem {
+                    kind: ContextItemKind::Agent,
+                    tokens: agent_tokens(&a, summary_only),
+                    id: a.id,
+                    name: a.name,
+                }),
+        );
+
+        items.extend(
+            state
+                .db
+                .get_all_installed_skills()?
+                .into_values()
+                .filter(|s| s.apps.is_enabled_for(app))
+                .map(|s| ContextBudgetItem {
+                    kind: ContextItemKind::Skill,
+                    tokens: estimate_tokens(&s.name)
+                        + estimate_tokens(s.description.as_deref().unwrap_or_default()),
+                    id: s.id,
+                    name: s.name,
+                }),
+        );
+
+        items.sort_by_key(|item| std::cmp::Reverse(item.tokens));
+        Ok(summarize(app, items))
+    }
+
+    /// 推演把已启用的提示词与 Agent 同步到指定工具后，该工具会读到的完整内容（不写入文件）
+    ///
+    /// 以各文件当前内容为基础，托管区域外的用户内容原样保留；模板变量与来源横幅按实际写入规则处理。
+    /// 提示词文件同时也是共享 agent 文件时（Codex、Gemini 标记模式），两者合并为同一个文件。
+    pub fn preview_composed_context(
+        state: &AppState,
+        app: &AppType,
+    ) -> Result<ComposedContext, AppError> {
+        let vars = AgentsService::template_vars(state, app);
+        let enabled: Vec<AgentDefinition> = state
+            .db
+            .get_all_agents()?
+            .into_values()
+            .filter(|a| a.apps.is_enabled_for(app))
+            .map(|a| agents::render_agent(&a, &vars))
+            .collect();
+
+        let mut files: Vec<(PathBuf, String)> = Vec::new();
+        if PROMPT_APPS.contains(app) {
+            let path = prompt_file_path(app)?;
+            let content = PromptService::compose_app_file(state, app, &read_existing(&path)?)?;
+            files.push((path, content));
+        }
+
+        if let Some(path) = agents::shared_agents_file(app) {
+            let slot = files.iter().position(|(p, _)| *p == path);
+            let original = match slot {
+                Some(i) => files[i].1.clone(),
+                None => read_existing(&path)?,
+            };
+            if let Some(content) = agents::compose_shared_file(app, &original, &enabled) {
+                match slot {
+                    Some(i) => files[i].1 = content,
+                    None => files.push((path, content)),
+                }
+            }
+        } else {
+            for agent in &enabled {
+                if let Some(content) = agents::agent_file_content(app, agent) {
+                    files.push((agents::agent_file_target(&agent.id, app).path, content));
+                }
+            }
+        }
+
+        Ok(ComposedContext {
+            app: app.as_str().to_string(),
+            files: files
+                .into_iter()
+                .map(|(path, content)| ComposedFile {
+                    path: path.to_string_lossy().to_string(),
+                    content,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// 读取文件当前内容，不存在时视为空
+fn read_existing(path: &Path) -> Result<String, AppError> {
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))
+}
+
+fn agent_tokens(agent: &AgentDefinition, summary_only: bool) -> u64 {
+    let summary = estimate_tokens(&agent.name)
+        + estimate_tokens(agent.description.as_deref().unwrap_or_default());
+    if summary_only {
+        summary
+    } else {
+        summary + estimate_tokens(&agent.content)
+    }
+}
+
+fn summarize(app: &AppType, items: Vec<ContextBudgetItem>) -> ContextBudget {
+    let context_window = context_window(app);
+    let total_tokens: u64 = items.iter().map(|i| i.tokens).sum();
+    let share = total_tokens as f64 / context_window as f64;
+    let level = if share >= CRITICAL_SHARE {
+        ContextBudgetLevel::Critical
+    } else if share >= WARNING_SHARE {
+        ContextBudgetLevel::Warning
+    } else {
+        ContextBudgetLevel::Ok
+    };
+    ContextBudget {
+        app: app.as_str().to_string(),
+        context_window,
+        items,
+        total_tokens,
+        share,
+        level,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_ascii_and_cjk_differently() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        assert_eq!(estimate_tokens("提示词"), 3);
+    }
+
+    #[test]
+    fn level_follows_share_of_context_window() {
+        let item = |tokens| ContextBudgetItem {
+            kind: ContextItemKind::Prompt,
+            id: "p".to_string(),
+            name: "p".to_string(),
+            tokens,
+        };
+        assert_eq!(
+            summarize(&AppType::Claude, vec![item(1_000)]).level,
+            ContextBudgetLevel::Ok
+        );
+        assert_eq!(
+            summarize(&AppType::Claude, vec![item(15_000), item(10_000)]).level,
+            ContextBudgetLevel::Warning
+        );
+        assert_eq!(
+            summarize(&AppType::Claude, vec![item(50_000)]).level,
+            ContextBudgetLevel::Critical
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! 启用状态矩阵的导出与导入
+//!
+//! 只包含“哪些提示词 / Agent / MCP 服务器在哪个 app 上启用”以及各 app 的当前供应商，
+//! 不含正文与配置内容，写成一个便于手改和代码评审的小 YAML 文件：
+//!
+//! ```yaml
+//! version: 1
+//! providers:
+//!   claude: packycode
+//! prompts:
+//!   claude: [base-rules, style]
+//! agents:
+//!   codex: [reviewer]
+//! mcp:
+//!   claude: [filesystem]
+//! ```
+//!
+//! 导入时按 app 对齐：YAML 中出现的 app，其列表即为完整的启用集合，未列出的会被关闭；
+//! 没出现的 app 保持不变；提示词的优先级顺序不随导入调整。本机不存在的 id 跳过并在结果中列出。
+//! 内容本身请用提示词 / Agent 导出包或完整备份迁移。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::config::write_text_file;
+use crate::error::AppError;
+use crate::services::prompt::PROMPT_APPS;
+use crate::services::{
+    AgentsService, McpService, PromptService, ProviderAuditOrigin, ProviderService,
+};
+use crate::store::AppState;
+
+const MATRIX_VERSION: u32 = 1;
+
+/// 启用状态矩阵；各段以 app 名为键
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnablementMatrix {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// 切换模式 app 的当前供应商 id（累加模式 app 没有当前供应商）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub providers: BTreeMap<String, String>,
+    /// 按优先级排列的启用提示词 id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub prompts: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub agents: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mcp: BTreeMap<String, Vec<String>>,
+}
+
+fn default_version() -> u32 {
+    MATRIX_VERSION
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnablementApplyResult {
+    /// 实际发生的变更，形如 `prompt claude +base-rules`、`provider codex -> packycode`
+    pub changes: Vec<String>,
+    /// 本机不存在而被跳过的条目，形如 `mcp claude filesystem`
+    pub missing: Vec<String>,
+}
+
+pub struct EnablementService;
+
+impl EnablementService {
+    /// 读取当前的启用状态矩阵
+    pub fn export(state: &AppState) -> Result<EnablementMatrix, AppError> {
+        let mut matrix = EnablementMatrix {
+            version: MATRIX_VERSION,
+            ..Default::default()
+        };
+
+        for app in AppType::all().filter(|app| !app.is_additive_mode()) {
+            let current = ProviderService::current(state, app.clone())?;
+            if !current.is_empty() {
+                matrix.providers.insert(app.as_str().to_string(), current);
+            }
+        }
+
+        let prompts = state.db.get_prompts()?;
+        for app in &PROMPT_APPS {
+            let ids: Vec<String> = prompts
+                .values()
+                .filter(|p| p.apps.is_enabled_for(app))
+                .map(|p| p.id.clone())
+                .collect();
+            if !ids.is_empty() {
+                matrix.prompts.insert(app.as_str().to_string(), ids);
+            }
+        }
+
+        let agents = state.db.get_all_agents()?;
+        let servers = state.db.get_all_mcp_servers()?;
+        for app in AppType::all() {
+            let ids: Vec<String> = agents
+                .values()
+                .filter(|a| a.apps.is_enabled_for(&app))
+                .map(|a| a.id.clone())
+                .collect();
+            if !ids.is_empty() {
+                matrix.agents.insert(app.as_str().to_string(), ids);
+            }
+            let ids: Vec<String> = servers
+                .values()
+                .filter(|s| s.apps.is_enabled_for(&app))
+                .map(|s| s.id.clone())
+                .collect();
+            if !ids.is_empty() {
+                matrix.mcp.insert(app.as_str().to_string(), ids);
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// 导出为 YAML 文本
+    pub fn export_yaml(state: &AppState) -> Result<String, AppError> {
+        let matrix = Self::export(state)?;
+        serde_yaml::to_string(&matrix)
+            .map_err(|e| AppError::Message(format!("序列化启用状态失败: {e}")))
+    }
+
+    /// 导出到文件
+    pub fn export_to_file(state: &AppState, path: &Path) -> Result<(), AppError> {
+        write_text_file(path, &Self::export_yaml(state)?)
+    }
+
+    /// 解析 YAML 文本
+    pub fn parse_yaml(text: &str) -> Result<EnablementMatrix, AppError> {
+        let matrix: EnablementMatrix = serde_yaml::from_str(text).map_err(|e| {
+            AppError::localized(
+                "enablement.invalid_yaml",
+                format!("启用状态文件格式错误: {e}"),
+                format!("Invalid enablement file: {e}"),
+            )
+        })?;
+        if matrix.version > MATRIX_VERSION {
+            return Err(AppError::localized(
+                "enablement.unsupported_version",
+                format!("不支持的启用状态文件版本: {}", matrix.version),
+                format!("Unsupported enablement file version: {}", matrix.version),
+            ));
+        }
+        Ok(matrix)
+    }
+
+    /// 从文件导入
+    pub fn import_from_file(
+        state: &AppState,
+        path: &Path,
+    ) -> Result<EnablementApplyResult, AppError> {
+        let text = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+        Self::apply(state, &Self::parse_yaml(&text)?)
+    }
+
+    /// 应用启用状态矩阵
+    ///
+    /// 依次处理供应商、提示词、Agent、MCP，每项变更都走与手动切换相同的同步路径；
+    /// 中途失败时已完成的变更保留。
+    pub fn apply(
+        state: &AppState,
+        matrix: &EnablementMatrix,
+    ) -> Result<EnablementApplyResult, AppError> {
+        let mut result = EnablementApplyResult::default();
+
+        for (app, id) in &matrix.providers {
+            let app_type = AppType::from_str(app)?;
+            if app_type.is_additive_mode() {
+                result.missing.push(format!("provider {app} {id}"));
+                continue;
+            }
+            if !state
+                .db
+                .get_all_providers(app_type.as_str())?
+                .contains_key(id)
+            {
+                result.missing.push(format!("provider {app} {id}"));
+                continue;
+            }
+            if ProviderService::current(state, app_type.clone())? != *id {
+                ProviderService::switch_from(state, app_type, id, ProviderAuditOrigin::Import)?;
+                result.changes.push(format!("provider {app} -> {id}"));
+            }
+        }
+
+        for (app, wanted) in &matrix.prompts {
+            let app_type = AppType::from_str(app)?;
+            if !PROMPT_APPS.contains(&app_type) {
+                result
+                    .missing
+                    .extend(wanted.iter().map(|id| format!("prompt {app} {id}")));
+                continue;
+            }
+            let prompts = state.db.get_prompts()?;
+            for prompt in prompts.values() {
+                let enabled = prompt.apps.is_enabled_for(&app_type);
+                let should = wanted.contains(&prompt.id);
+                if enabled != should {
+                    PromptService::toggle_prompt_app(
+                        state,
+                        &prompt.id,
+                        app_type.clone(),
+                        should,
+                        false,
+                    )?;
+                    result
+                        .changes
+                        .push(change_label("prompt", app, &prompt.id, should));
+                }
+            }
+            record_missing(&mut result, "prompt", app, wanted, |id| {
+                prompts.contains_key(id)
+            });
+        }
+
+        for (app, wanted) in &matrix.agents {
+            let app_type = AppType::from_str(app)?;
+            let agents = state.db.get_all_agents()?;
+            for agent in agents.values() {
+                let enabled = agent.apps.is_enabled_for(&app_type);
+                let should = wanted.contains(&agent.id);
+                if enabled != should {
+                    AgentsService::toggle_app(state, &agent.id, app_type.clone(), should, false)?;
+                    result
+                        .changes
+                        .push(change_label("agent", app, &agent.id, should));
+                }
+            }
+            record_missing(&mut result, "agent", app, wanted, |id| {
+                agents.contains_key(id)
+            });
+        }
+
+        for (app, wanted) in &matrix.mcp {
+            let app_type = AppType::from_str(app)?;
+            if app_type == AppType::OpenClaw {
+                // OpenClaw 不支持 MCP
+                result
+                    .missing
+                    .extend(wanted.iter().map(|id| format!("mcp {app} {id}")));
+                continue;
+            }
+            let servers = state.db.get_all_mcp_servers()?;
+            // 外部来源的只读条目不随导入切换
+            for server in servers.values().filter(|s| !s.is_read_only()) {
+                let enabled = server.apps.is_enabled_for(&app_type);
+                let should = wanted.contains(&server.id);
+                if enabled != should {
+                    McpService::toggle_app(state, &server.id, app_type.clone(), should)?;
+                    result
+                        .changes
+                        .push(change_label("mcp", app, &server.id, should));
+                }
+            }
+            record_missing(&mut result, "mcp", app, wanted, |id| {
+                servers.contains_key(id)
+            });
+        }
+
+        log::info!(
+            "已应用启用状态：{} 项变更，{} 项跳过",
+            result.changes.len(),
+            result.missing.len()
+        );
+        Ok(result)
+    }
+}
+
+fn change_label(kind: &str, app: &str, id: &str, enabled: bool) -> String {
+    format!("{kind} {app} {}{id}", if enabled { '+' } else { '-' })
+}
+
+fn record_missing(
+    result: &mut EnablementApplyResult,
+    kind: &str,
+    app: &str,
+    wanted: &[String],
+    exists: impl Fn(&str) -> bool,
+) {
+    result.missing.extend(
+        wanted
+            .iter()
+            .filter(|id| !exists(id))
+            .map(|id| format!("{kind} {app} {id}")),
+    );
+}
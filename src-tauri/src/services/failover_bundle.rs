@@ -0,0 +1,522 @@
+//! 故障转移配置的导入 / 导出
+//!
+//! SQL 备份会整体替换数据库，不适合把一台机器上调好的故障转移方案复制到另一台
+//! 已有供应商的机器。这里导出的 JSON 包含各应用的故障转移队列、熔断 / 重试 / 超时策略，
+//! 以及提示词定时切换规则；导入时按 id 匹配供应商和提示词，id 不存在则按名称匹配并
+//! 重映射到本机 id。整个导入在一个数据库事务中完成，失败时本机配置保持不变。
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::config::write_text_file;
+use crate::error::AppError;
+use crate::services::prompt::PROMPT_APPS;
+use crate::services::prompt_schedule::PromptSchedule;
+use crate::services::schedule::Schedule;
+use crate::store::AppState;
+
+/// 故障转移导出包格式标识
+pub const FAILOVER_BUNDLE_FORMAT: &str = "cc-switch-failover";
+/// 当前故障转移导出包版本
+///
+/// v2 增加了超时设置与提示词定时规则；v1 导出包仍可导入，缺失的部分保持本机设置。
+pub const FAILOVER_BUNDLE_VERSION: u32 = 2;
+
+/// 支持代理故障转移的应用（与 proxy_config 表的预置行一致）
+const FAILOVER_APPS: [&str; 3] = ["claude", "codex", "gemini"];
+
+/// 故障转移导出包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverBundle {
+    /// 固定为 [`FAILOVER_BUNDLE_FORMAT`]
+    pub format: String,
+    pub version: u32,
+    /// 导出时间（Unix 毫秒）
+    pub exported_at: i64,
+    pub apps: Vec<FailoverAppBundle>,
+    /// 提示词定时切换规则（v2 起）
+    #[serde(default)]
+    pub schedules: Vec<FailoverScheduleBundle>,
+}
+
+/// 单个应用的故障转移配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverAppBundle {
+    pub app_type: String,
+    pub auto_failover_enabled: bool,
+    pub max_retries: u32,
+    pub circuit_failure_threshold: u32,
+    pub circuit_success_threshold: u32,
+    pub circuit_timeout_seconds: u32,
+    pub circuit_error_rate_threshold: f64,
+    pub circuit_min_requests: u32,
+    /// 超时设置（秒，v2 起）；缺失时保留本机设置
+    #[serde(default)]
+    pub streaming_first_byte_timeout: Option<u32>,
+    #[serde(default)]
+    pub streaming_idle_timeout: Option<u32>,
+    #[serde(default)]
+    pub non_streaming_timeout: Option<u32>,
+    /// 队列中的供应商（按队列顺序）
+    pub queue: Vec<FailoverQueueEntry>,
+}
+
+/// 导出包中的提示词定时规则，同时记录提示词 id 与名称以便跨机器匹配
+///
+/// 执行状态（上次执行时间、错误）不导出，导入后从导入时刻开始计算。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverScheduleBundle {
+    pub id: String,
+    pub app: String,
+    pub prompt_id: String,
+    pub prompt_name: String,
+    pub schedule: Schedule,
+    pub enabled: bool,
+}
+
+/// 导出包中的队列条目，同时记录 id 与名称以便跨机器匹配
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverQueueEntry {
+    pub provider_id: String,
+    pub provider_name: String,
+}
+
+/// 按名称匹配后被重映射的供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverRemap {
+    pub app_type: String,
+    pub provider_name: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// 本机找不到对应供应商的队列条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverUnmatched {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+}
+
+/// 本机找不到对应提示词（或应用不支持提示词）的定时规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverUnmatchedSchedule {
+    pub schedule_id: String,
+    pub app: String,
+    pub prompt_id: String,
+    pub prompt_name: String,
+}
+
+/// 故障转移配置导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverImportResult {
+    /// 已导入的应用
+    pub apps: Vec<String>,
+    pub remapped: Vec<FailoverRemap>,
+    pub unmatched: Vec<FailoverUnmatched>,
+    /// 已导入的定时规则 id
+    pub schedules: Vec<String>,
+    pub unmatched_schedules: Vec<FailoverUnmatchedSchedule>,
+}
+
+/// 故障转移配置导入导出服务
+pub struct FailoverBundleService;
+
+impl FailoverBundleService {
+    /// 导出所有应用的故障转移队列、策略与提示词定时规则
+    pub async fn export_bundle(state: &AppState) -> Result<FailoverBundle, AppError> {
+        let mut apps = Vec::with_capacity(FAILOVER_APPS.len());
+        for app_type in FAILOVER_APPS {
+            let config = state.db.get_proxy_config_for_app(app_type).await?;
+            let queue = state
+                .db
+                .get_failover_queue(app_type)?
+                .into_iter()
+                .map(|item| FailoverQueueEntry {
+                    provider_id: item.provider_id,
+                    provider_name: item.provider_name,
+                })
+                .collect();
+            apps.push(FailoverAppBundle {
+                app_type: app_type.to_string(),
+                auto_failover_enabled: config.auto_failover_enabled,
+                max_retries: config.max_retries,
+                circuit_failure_threshold: config.circuit_failure_threshold,
+                circuit_success_threshold: config.circuit_success_threshold,
+                circuit_timeout_seconds: config.circuit_timeout_seconds,
+                circuit_error_rate_threshold: config.circuit_error_rate_threshold,
+                circuit_min_requests: config.circuit_min_requests,
+                streaming_first_byte_timeout: Some(config.streaming_first_byte_timeout),
+                streaming_idle_timeout: Some(config.streaming_idle_timeout),
+                non_streaming_timeout: Some(config.non_streaming_timeout),
+                queue,
+            });
+        }
+
+        let prompts = state.db.get_prompts()?;
+        let schedules = state
+            .db
+            .get_prompt_schedules()?
+            .into_iter()
+            .map(|s| FailoverScheduleBundle {
+                prompt_name: prompts
+                    .get(&s.prompt_id)
+                    .map(|p| p.name.clone())
+                    .unwrap_or_default(),
+                id: s.id,
+                app: s.app,
+                prompt_id: s.prompt_id,
+                schedule: s.schedule,
+                enabled: s.enabled,
+            })
+            .collect();
+
+        Ok(FailoverBundle {
+            format: FAILOVER_BUNDLE_FORMAT.to_string(),
+            version: FAILOVER_BUNDLE_VERSION,
+            exported_at: chrono::Utc::now().timestamp_millis(),
+            apps,
+            schedules,
+        })
+    }
+
+    /// 导出到 JSON 文件
+    pub async fn export_to_file(state: &AppState, path: &Path) -> Result<(), AppError> {
+        let bundle = Self::export_bundle(state).await?;
+        let json = serde_json::to_string_pretty(&bundle)
+            .map_err(|e| AppError::JsonSerialize { source: e })?;
+        write_text_file(path, &json)?;
+        log::info!("已导出故障转移配置到 {}", path.display());
+        Ok(())
+    }
+
+    /// 从 JSON 文件导入
+    pub async fn import_from_file(
+        state: &AppState,
+        path: &Path,
+    ) -> Result<FailoverImportResult, AppError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+        let bundle: FailoverBundle = serde_json::from_str(&raw).map_err(|e| {
+            AppError::localized(
+                "failover.bundle_invalid",
+                format!("无效的故障转移配置文件: {e}"),
+                format!("Invalid failover config file: {e}"),
+            )
+        })?;
+        Self::import_bundle(state, bundle).await
+    }
+
+    /// 导入故障转移导出包
+    ///
+    /// 导入的应用会先清空本机队列再按导出包重建；匹配不到供应商的条目会被跳过并在结果中列出。
+    /// 若某个应用最终队列为空，则不开启自动故障转移。代理开关本身不随导出包迁移，
+    /// 是否接管本机流量仍由用户在本机决定。
+    ///
+    /// 定时规则按 id 覆盖本机同 id 规则；提示词匹配不到的规则被跳过并在结果中列出。
+    pub async fn import_bundle(
+        state: &AppState,
+        bundle: FailoverBundle,
+    ) -> Result<FailoverImportResult, AppError> {
+        if bundle.format != FAILOVER_BUNDLE_FORMAT || bundle.version > FAILOVER_BUNDLE_VERSION {
+            return Err(AppError::localized(
+                "failover.bundle_unsupported",
+                format!(
+                    "不支持的故障转移配置文件: {} v{}",
+                    bundle.format, bundle.version
+                ),
+                format!(
+                    "Unsupported failover config file: {} v{}",
+                    bundle.format, bundle.version
+                ),
+            ));
+        }
+
+        let mut result = FailoverImportResult::default();
+        let mut setups = Vec::new();
+        for app in bundle.apps {
+            if !FAILOVER_APPS.contains(&app.app_type.as_str()) {
+                log::warn!("跳过不支持故障转移的应用: {}", app.app_type);
+                continue;
+            }
+
+            let local: Vec<(String, String)> = state
+                .db
+                .get_all_providers(&app.app_type)?
+                .into_values()
+                .map(|p| (p.id, p.name))
+                .collect();
+            let resolved = resolve_queue(&app.queue, &local);
+
+            let mut config = state.db.get_proxy_config_for_app(&app.app_type).await?;
+            config.auto_failover_enabled =
+                app.auto_failover_enabled && !resolved.provider_ids.is_empty();
+            config.max_retries = app.max_retries;
+            config.circuit_failure_threshold = app.circuit_failure_threshold;
+            config.circuit_success_threshold = app.circuit_success_threshold;
+            config.circuit_timeout_seconds = app.circuit_timeout_seconds;
+            config.circuit_error_rate_threshold = app.circuit_error_rate_threshold;
+            config.circuit_min_requests = app.circuit_min_requests;
+            if let Some(timeout) = app.streaming_first_byte_timeout {
+                config.streaming_first_byte_timeout = timeout;
+            }
+            if let Some(timeout) = app.streaming_idle_timeout {
+                config.streaming_idle_timeout = timeout;
+            }
+            if let Some(timeout) = app.non_streaming_timeout {
+                config.non_streaming_timeout = timeout;
+            }
+            setups.push((config, resolved.provider_ids));
+
+            for (entry, to) in resolved.remapped {
+                result.remapped.push(FailoverRemap {
+                    app_type: app.app_type.clone(),
+                    provider_name: entry.provider_name,
+                    from: entry.provider_id,
+                    to,
+                });
+            }
+            for entry in resolved.unmatched {
+                result.unmatched.push(FailoverUnmatched {
+                    app_type: app.app_type.clone(),
+                    provider_id: entry.provider_id,
+                    provider_name: entry.provider_name,
+                });
+            }
+            result.apps.push(app.app_type);
+        }
+
+        let prompts: Vec<(String, String)> = state
+            .db
+            .get_prompts()?
+            .into_values()
+            .map(|p| (p.id, p.name))
+            .collect();
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut schedules = Vec::new();
+        for entry in bundle.schedules {
+            let supported = AppType::from_str(&entry.app)
+                .map(|app| PROMPT_APPS.contains(&app))
+                .unwrap_or(false);
+            let prompt_id = supported
+                .then(|| resolve_by_id_or_name(&entry.prompt_id, &entry.prompt_name, &prompts))
+                .flatten();
+            let (Some(prompt_id), Ok(())) = (prompt_id, entry.schedule.validate()) else {
+                result.unmatched_schedules.push(FailoverUnmatchedSchedule {
+                    schedule_id: entry.id,
+                    app: entry.app,
+                    prompt_id: entry.prompt_id,
+                    prompt_name: entry.prompt_name,
+                });
+                continue;
+            };
+            let id = match entry.id.trim() {
+                "" => uuid::Uuid::new_v4().to_string(),
+                id => id.to_string(),
+            };
+            result.schedules.push(id.clone());
+            schedules.push(PromptSchedule {
+                id,
+                app: entry.app,
+                prompt_id,
+                schedule: entry.schedule,
+                enabled: entry.enabled,
+                last_run_at: None,
+                last_error: None,
+                created_at: now,
+                updated_at: now,
+            });
+        }
+
+        state.db.replace_failover_setup(&setups, &schedules)?;
+
+        log::info!(
+            "故障转移配置导入完成: 应用 {:?}，重映射 {}，未匹配 {}，定时规则 {}（未匹配 {}）",
+            result.apps,
+            result.remapped.len(),
+            result.unmatched.len(),
+            result.schedules.len(),
+            result.unmatched_schedules.len()
+        );
+        Ok(result)
+    }
+}
+
+/// 队列条目与本机供应商的匹配结果
+#[derive(Debug, Default)]
+struct ResolvedQueue {
+    /// 匹配到的本机供应商 id（按导出包顺序，已去重）
+    provider_ids: Vec<String>,
+    /// (原条目, 本机 id)
+    remapped: Vec<(FailoverQueueEntry, String)>,
+    unmatched: Vec<FailoverQueueEntry>,
+}
+
+/// 将导出包中的队列条目匹配到本机供应商
+///
+/// 优先按 id 匹配；id 不存在时按名称（忽略首尾空白与大小写）匹配，
+/// 名称对应多个本机供应商时视为无法匹配，避免误选。
+fn resolve_queue(entries: &[FailoverQueueEntry], local: &[(String, String)]) -> ResolvedQueue {
+    let mut resolved = ResolvedQueue::default();
+    let mut used = HashSet::new();
+
+    for entry in entries {
+        let target = resolve_by_id_or_name(&entry.provider_id, &entry.provider_name, local);
+        if let Some(id) = target.as_ref().filter(|id| **id != entry.provider_id) {
+            resolved.remapped.push((entry.clone(), id.clone()));
+        }
+
+        match target {
+            Some(id) if used.insert(id.clone()) => resolved.provider_ids.push(id),
+            Some(_) => {}
+            None => resolved.unmatched.push(entry.clone()),
+        }
+    }
+    resolved
+}
+
+/// 在本机 (id, 名称) 列表中查找对应项：优先按 id，其次按唯一名称（忽略首尾空白与大小写）
+fn resolve_by_id_or_name(id: &str, name: &str, local: &[(String, String)]) -> Option<String> {
+    if local.iter().any(|(local_id, _)| local_id == id) {
+        return Some(id.to_string());
+    }
+    let normalize = |name: &str| name.trim().to_lowercase();
+    let wanted = normalize(name);
+    let mut by_name = local.iter().filter(|(_, n)| normalize(n) == wanted);
+    match (by_name.next(), by_name.next()) {
+        (Some((local_id, _)), None) => Some(local_id.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::prompt::Prompt;
+    use crate::provider::Provider;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn entry(id: &str, name: &str) -> FailoverQueueEntry {
+        FailoverQueueEntry {
+            provider_id: id.to_string(),
+            provider_name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn resolve_queue_matches_by_id_then_unique_name() {
+        let local = vec![
+            ("p1".to_string(), "Primary".to_string()),
+            ("local-b".to_string(), "Backup".to_string()),
+            ("dup-1".to_string(), "Mirror".to_string()),
+            ("dup-2".to_string(), "mirror".to_string()),
+        ];
+        let entries = vec![
+            entry("p1", "Renamed Primary"),
+            entry("remote-b", " backup "),
+            entry("remote-m", "Mirror"),
+            entry("gone", "Gone"),
+        ];
+
+        let resolved = resolve_queue(&entries, &local);
+        assert_eq!(resolved.provider_ids, vec!["p1", "local-b"]);
+        assert_eq!(resolved.remapped.len(), 1);
+        assert_eq!(resolved.remapped[0].0.provider_id, "remote-b");
+        assert_eq!(resolved.remapped[0].1, "local-b");
+        let unmatched: Vec<_> = resolved
+            .unmatched
+            .iter()
+            .map(|e| e.provider_id.as_str())
+            .collect();
+        assert_eq!(unmatched, vec!["remote-m", "gone"]);
+    }
+
+    #[tokio::test]
+    async fn import_bundle_applies_timeouts_and_remaps_schedules() {
+        let db = Arc::new(Database::memory().expect("create memory db"));
+        let state = AppState::new(db.clone());
+        let provider = Provider::with_id("local-a".into(), "Alpha".into(), json!({}), None);
+        db.save_provider("claude", &provider)
+            .expect("save provider");
+        let prompt: Prompt = serde_json::from_value(json!({
+            "id": "local-work",
+            "name": "Work",
+            "content": "focus",
+        }))
+        .expect("prompt");
+        db.save_prompt(&prompt).expect("save prompt");
+
+        let mut bundle = FailoverBundleService::export_bundle(&state)
+            .await
+            .expect("export");
+        let claude = bundle
+            .apps
+            .iter_mut()
+            .find(|a| a.app_type == "claude")
+            .expect("claude bundle");
+        claude.auto_failover_enabled = true;
+        claude.non_streaming_timeout = Some(42);
+        claude.streaming_idle_timeout = None;
+        claude.queue = vec![entry("remote-a", "alpha")];
+        let schedule = Schedule {
+            time_zone: "UTC".into(),
+            at: "09:00".into(),
+            days: Vec::new(),
+        };
+        bundle.schedules = vec![
+            FailoverScheduleBundle {
+                id: "s1".into(),
+                app: "claude".into(),
+                prompt_id: "remote-work".into(),
+                prompt_name: "work".into(),
+                schedule: schedule.clone(),
+                enabled: true,
+            },
+            FailoverScheduleBundle {
+                id: "s2".into(),
+                app: "claude".into(),
+                prompt_id: "remote-home".into(),
+                prompt_name: "Home".into(),
+                schedule,
+                enabled: true,
+            },
+        ];
+        let before = db.get_proxy_config_for_app("claude").await.expect("config");
+
+        let result = FailoverBundleService::import_bundle(&state, bundle)
+            .await
+            .expect("import");
+
+        assert_eq!(result.remapped.len(), 1);
+        assert_eq!(result.schedules, vec!["s1"]);
+        assert_eq!(result.unmatched_schedules.len(), 1);
+        assert_eq!(result.unmatched_schedules[0].schedule_id, "s2");
+
+        let queue = db.get_failover_queue("claude").expect("queue");
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].provider_id, "local-a");
+        let config = db.get_proxy_config_for_app("claude").await.expect("config");
+        assert!(config.auto_failover_enabled);
+        assert_eq!(config.non_streaming_timeout, 42);
+        assert_eq!(config.streaming_idle_timeout, before.streaming_idle_timeout);
+        assert_eq!(config.enabled, before.enabled);
+
+        let saved = db.get_prompt_schedules().expect("schedules");
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].prompt_id, "local-work");
+        assert_eq!(saved[0].last_run_at, None);
+    }
+}
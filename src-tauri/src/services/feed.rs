@@ -0,0 +1,859 @@
+//! 远程订阅源（提示词 / Agent / MCP 服务器）
+//!
+//! 订阅一个 HTTPS 地址上的 JSON 清单（`format: "cc-switch-feed"`），cc-switch 定期拉取。
+//! 清单中的条目不会自动写入：新增或内容变化的条目作为“待处理更新”展示，
+//! 用户接受后才按 id 写入本地（保留本地的启用状态），忽略则记住当前版本、直到源再次更新。
+//! 本地已有同 id、但不是从该订阅接受的条目不会被覆盖；新增或改变启动命令的 MCP 服务器需要确认。
+
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::agent::{AgentDefinition, DiffLine};
+use crate::app_config::McpServer;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::prompt::Prompt;
+use crate::proxy::http_client;
+use crate::services::agents::diff_lines;
+use crate::services::{AgentsService, McpService, PromptService};
+use crate::store::AppState;
+
+/// 订阅清单格式标识
+pub const FEED_FORMAT: &str = "cc-switch-feed";
+/// 支持的最高清单版本
+pub const FEED_VERSION: u32 = 1;
+/// 自动拉取的间隔（秒）
+pub const FEED_POLL_INTERVAL_SECS: i64 = 6 * 3600;
+/// 单次拉取超时
+const FETCH_TIMEOUT_SECS: u64 = 30;
+
+/// 订阅源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedSubscription {
+    pub id: String,
+    pub url: String,
+    /// 显示名称；添加时未填写则取清单中的 name
+    pub name: String,
+    /// 关闭后不再自动拉取
+    pub enabled: bool,
+    /// 最近一次拉取时间（Unix 秒）
+    pub last_checked_at: Option<i64>,
+    /// 最近一次拉取失败的原因；成功时为 None
+    pub last_error: Option<String>,
+    /// 添加时间（Unix 秒）
+    pub created_at: i64,
+}
+
+/// 订阅条目类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedItemKind {
+    Prompt,
+    Agent,
+    Mcp,
+}
+
+impl FeedItemKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Prompt => "prompt",
+            Self::Agent => "agent",
+            Self::Mcp => "mcp",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "prompt" => Some(Self::Prompt),
+            "agent" => Some(Self::Agent),
+            "mcp" => Some(Self::Mcp),
+            _ => None,
+        }
+    }
+}
+
+/// 清单中的提示词
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedPrompt {
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// 清单中的 Agent（不含启用状态、排序等本地字段）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedAgent {
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// 清单中的 MCP 服务器（不含启用状态）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedMcpServer {
+    pub id: String,
+    pub name: String,
+    pub server: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// 订阅清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedManifest {
+    /// 固定为 [`FEED_FORMAT`]
+    pub format: String,
+    pub version: u32,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub prompts: Vec<FeedPrompt>,
+    #[serde(default)]
+    pub agents: Vec<FeedAgent>,
+    #[serde(default)]
+    pub mcp_servers: Vec<FeedMcpServer>,
+}
+
+/// `feed_items` 表中的一行（payload 为条目 JSON）
+#[derive(Debug, Clone)]
+pub struct FeedItemRecord {
+    pub subscription_id: String,
+    pub kind: FeedItemKind,
+    pub item_id: String,
+    pub name: String,
+    pub payload: String,
+    pub content_hash: String,
+    /// 用户最近一次接受或忽略时的内容摘要
+    pub accepted_hash: Option<String>,
+    /// 是否曾被接受写入本地
+    pub applied: bool,
+    /// 拉取时间（Unix 秒）
+    pub fetched_at: i64,
+}
+
+impl FeedItemRecord {
+    fn is_pending(&self) -> bool {
+        self.accepted_hash.as_deref() != Some(self.content_hash.as_str())
+    }
+}
+
+/// 待处理更新的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedUpdateStatus {
+    /// 本地还没有同 id 的条目
+    New,
+    /// 接受后会覆盖本地同 id 的条目
+    Updated,
+    /// 本地同 id 的条目不是从该订阅接受的，不会被覆盖
+    Conflict,
+}
+
+/// 待处理更新
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedUpdate {
+    pub subscription_id: String,
+    pub subscription_name: String,
+    pub kind: FeedItemKind,
+    pub item_id: String,
+    pub name: String,
+    pub status: FeedUpdateStatus,
+    /// 源中的条目内容
+    pub payload: serde_json::Value,
+    /// 本地内容到源中内容的逐行差异（MCP 为 server 配置的 JSON）；新条目全部为新增行
+    pub diff: Vec<DiffLine>,
+    /// 新增或改变启动命令的 MCP 服务器，接受时需要确认
+    pub requires_confirmation: bool,
+}
+
+/// 本地的提示词、Agent 与 MCP 服务器，用于与订阅条目对比
+struct LocalItems {
+    prompts: IndexMap<String, Prompt>,
+    agents: IndexMap<String, AgentDefinition>,
+    servers: IndexMap<String, McpServer>,
+}
+
+impl LocalItems {
+    fn load(db: &Database) -> Result<Self, AppError> {
+        Ok(Self {
+            prompts: db.get_prompts()?,
+            agents: db.get_all_agents()?,
+            servers: db.get_all_mcp_servers()?,
+        })
+    }
+
+    /// 对比订阅条目与本地同 id 的条目
+    fn compare(&self, item: &FeedItemRecord) -> Result<FeedUpdateCheck, AppError> {
+        let (local, remote, command_changed) = match item.kind {
+            FeedItemKind::Prompt => {
+                let feed: FeedPrompt = decode_payload(item)?;
+                let local = self.prompts.get(&feed.id).map(|p| p.content.clone());
+                (local, feed.content, false)
+            }
+            FeedItemKind::Agent => {
+                let feed: FeedAgent = decode_payload(item)?;
+                let local = self.agents.get(&feed.id).map(|a| a.content.clone());
+                (local, feed.content, false)
+            }
+            FeedItemKind::Mcp => {
+                let feed: FeedMcpServer = decode_payload(item)?;
+                let local = self.servers.get(&feed.id).map(|s| &s.server);
+                let command_changed = local
+                    .is_none_or(|server| launch_command(server) != launch_command(&feed.server));
+                (
+                    local.map(pretty_json),
+                    pretty_json(&feed.server),
+                    command_changed,
+                )
+            }
+        };
+        let status = match &local {
+            None => FeedUpdateStatus::New,
+            Some(_) if item.applied => FeedUpdateStatus::Updated,
+            Some(_) => FeedUpdateStatus::Conflict,
+        };
+        Ok(FeedUpdateCheck {
+            status,
+            diff: diff_lines(local.as_deref().unwrap_or_default(), &remote),
+            requires_confirmation: command_changed,
+        })
+    }
+}
+
+/// 订阅条目与本地条目的对比结果
+struct FeedUpdateCheck {
+    status: FeedUpdateStatus,
+    diff: Vec<DiffLine>,
+    requires_confirmation: bool,
+}
+
+/// 订阅源服务
+pub struct FeedService;
+
+impl FeedService {
+    /// 获取全部订阅
+    pub fn list_subscriptions(db: &Database) -> Result<Vec<FeedSubscription>, AppError> {
+        db.get_feed_subscriptions()
+    }
+
+    /// 添加订阅并立即拉取一次
+    ///
+    /// 只接受 https 地址；拉取失败不影响添加，失败原因记录在 `last_error` 中。
+    pub async fn add_subscription(
+        db: &Database,
+        url: &str,
+        name: Option<String>,
+    ) -> Result<FeedSubscription, AppError> {
+        let url = validate_feed_url(url)?;
+        if db.get_feed_subscriptions()?.iter().any(|s| s.url == url) {
+            return Err(AppError::localized(
+                "feed.duplicate_url",
+                format!("已订阅该地址: {url}"),
+                format!("Already subscribed to {url}"),
+            ));
+        }
+
+        let sub = FeedSubscription {
+            id: uuid::Uuid::new_v4().to_string(),
+            url,
+            name: name.map(|n| n.trim().to_string()).unwrap_or_default(),
+            enabled: true,
+            last_checked_at: None,
+            last_error: None,
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        db.save_feed_subscription(&sub)?;
+
+        if let Err(e) = Self::refresh(db, &sub.id).await {
+            log::warn!("首次拉取订阅 {} 失败: {e}", sub.url);
+        }
+        Ok(db.get_feed_subscription(&sub.id)?.unwrap_or(sub))
+    }
+
+    /// 删除订阅及其条目（已接受到本地的内容保留）
+    pub fn remove_subscription(db: &Database, id: &str) -> Result<bool, AppError> {
+        db.delete_feed_subscription(id)
+    }
+
+    /// 启用或暂停自动拉取
+    pub fn set_subscription_enabled(
+        db: &Database,
+        id: &str,
+        enabled: bool,
+    ) -> Result<(), AppError> {
+        let mut sub = Self::require_subscription(db, id)?;
+        sub.enabled = enabled;
+        db.save_feed_subscription(&sub)
+    }
+
+    /// 立即拉取指定订阅，返回该订阅的待处理更新数
+    pub async fn refresh(db: &Database, id: &str) -> Result<usize, AppError> {
+        let mut sub = Self::require_subscription(db, id)?;
+        let now = chrono::Utc::now().timestamp();
+
+        let manifest = match fetch_manifest(&sub.url).await {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                db.record_feed_check(id, now, Some(&e.to_string()))?;
+                return Err(e);
+            }
+        };
+
+        let items = manifest_items(&manifest, id, now)?;
+        db.replace_feed_items(id, &items)?;
+
+        if sub.name.is_empty() {
+            if let Some(name) = manifest.name.as_deref().map(str::trim) {
+                sub.name = name.to_string();
+            }
+        }
+        sub.last_checked_at = Some(now);
+        sub.last_error = None;
+        db.save_feed_subscription(&sub)?;
+
+        let pending = db
+            .get_feed_items(Some(id))?
+            .iter()
+            .filter(|item| item.is_pending())
+            .count();
+        log::info!(
+            "订阅 {} 已拉取：{} 个条目，{pending} 个待处理",
+            sub.url,
+            items.len()
+        );
+        Ok(pending)
+    }
+
+    /// 拉取所有到期（距上次拉取超过 [`FEED_POLL_INTERVAL_SECS`]）的已启用订阅，返回待处理更新总数
+    ///
+    /// 单个订阅失败只记录日志，不影响其他订阅。
+    pub async fn refresh_due(db: &Database) -> usize {
+        let subs = match db.get_feed_subscriptions() {
+            Ok(subs) => subs,
+            Err(e) => {
+                log::warn!("读取订阅列表失败: {e}");
+                return 0;
+            }
+        };
+        let now = chrono::Utc::now().timestamp();
+        let mut pending = 0;
+        for sub in subs.iter().filter(|s| s.enabled) {
+            let due = sub
+                .last_checked_at
+                .is_none_or(|at| now - at >= FEED_POLL_INTERVAL_SECS);
+            if !due {
+                continue;
+            }
+            match Self::refresh(db, &sub.id).await {
+                Ok(count) => pending += count,
+                Err(e) => log::warn!("拉取订阅 {} 失败: {e}", sub.url),
+            }
+        }
+        pending
+    }
+
+    /// 列出所有订阅中待处理的更新
+    pub fn get_pending_updates(state: &AppState) -> Result<Vec<FeedUpdate>, AppError> {
+        let subs = state.db.get_feed_subscriptions()?;
+        let local = LocalItems::load(&state.db)?;
+
+        let mut updates = Vec::new();
+        for item in state.db.get_feed_items(None)? {
+            if !item.is_pending() {
+                continue;
+            }
+            let check = local.compare(&item)?;
+            let subscription_name = subs
+                .iter()
+                .find(|s| s.id == item.subscription_id)
+                .map(|s| s.name.clone())
+                .unwrap_or_default();
+            updates.push(FeedUpdate {
+                payload: serde_json::from_str(&item.payload)
+                    .map_err(|e| AppError::JsonSerialize { source: e })?,
+                subscription_id: item.subscription_id,
+                subscription_name,
+                kind: item.kind,
+                item_id: item.item_id,
+                name: item.name,
+                status: check.status,
+                diff: check.diff,
+                requires_confirmation: check.requires_confirmation,
+            });
+        }
+        Ok(updates)
+    }
+
+    /// 接受一条更新：按 id 写入本地并同步到已启用的工具
+    ///
+    /// 本地已存在时只替换内容类字段，启用状态、排序、分组等保持不变；新条目默认不启用。
+    /// 本地同 id 的条目不是从该订阅接受的则拒绝；需要确认的 MCP 更新须传入 `confirmed`。
+    pub fn accept_update(
+        state: &AppState,
+        subscription_id: &str,
+        kind: FeedItemKind,
+        item_id: &str,
+        confirmed: bool,
+    ) -> Result<(), AppError> {
+        let item = Self::require_item(&state.db, subscription_id, kind, item_id)?;
+        let mut local = LocalItems::load(&state.db)?;
+        let check = local.compare(&item)?;
+        if check.status == FeedUpdateStatus::Conflict {
+            return Err(AppError::localized(
+                "feed.local_conflict",
+                format!("本地已有不是来自该订阅的同 id 条目，不会覆盖: {item_id}"),
+                format!("A local item with id {item_id} did not come from this feed and will not be overwritten"),
+            ));
+        }
+        if check.requires_confirmation && !confirmed {
+            return Err(AppError::localized(
+                "feed.confirmation_required",
+                format!("MCP 服务器 {item_id} 的启动命令有变化，需要确认后才能接受"),
+                format!("The launch command of MCP server {item_id} changed and must be confirmed"),
+            ));
+        }
+
+        match kind {
+            FeedItemKind::Prompt => {
+                let feed: FeedPrompt = decode_payload(&item)?;
+                let local = local.prompts.shift_remove(&feed.id);
+                let now = chrono::Utc::now().timestamp();
+                PromptService::upsert_prompt(
+                    state,
+                    Prompt {
+                        id: feed.id,
+                        name: feed.name,
+                        content: feed.content,
+                        description: feed.description,
+                        tags: local.as_ref().map(|p| p.tags.clone()).unwrap_or_default(),
+                        apps: local.as_ref().map(|p| p.apps.clone()).unwrap_or_default(),
+                        sort_index: local.as_ref().and_then(|p| p.sort_index),
+                        created_at: local.as_ref().and_then(|p| p.created_at).or(Some(now)),
+                        updated_at: Some(now),
+                    },
+                )?;
+            }
+            FeedItemKind::Agent => {
+                let feed: FeedAgent = decode_payload(&item)?;
+                let local = local.agents.shift_remove(&feed.id).unwrap_or_default();
+                let now = chrono::Utc::now().timestamp_millis();
+                AgentsService::upsert(
+                    state,
+                    AgentDefinition {
+                        id: feed.id,
+                        name: feed.name,
+                        content: feed.content,
+                        description: feed.description,
+                        tools: feed.tools,
+                        model: feed.model,
+                        color: feed.color,
+                        tags: feed.tags,
+                        created_at: local.created_at.or(Some(now)),
+                        updated_at: Some(now),
+                        ..local
+                    },
+                    false,
+                )?;
+            }
+            FeedItemKind::Mcp => {
+                let feed: FeedMcpServer = decode_payload(&item)?;
+                let local = local.servers.shift_remove(&feed.id);
+                McpService::upsert_server(
+                    state,
+                    McpServer {
+                        id: feed.id,
+                        name: feed.name,
+                        server: feed.server,
+                        apps: local.map(|s| s.apps).unwrap_or_default(),
+                        description: feed.description,
+                        homepage: feed.homepage,
+                        docs: feed.docs,
+                        tags: feed.tags,
+                        origin: None,
+                    },
+                )?;
+            }
+        }
+
+        state.db.set_feed_item_accepted(
+            subscription_id,
+            kind,
+            item_id,
+            &item.content_hash,
+            true,
+        )?;
+        log::info!("已接受订阅更新 {}:{item_id}", kind.as_str());
+        Ok(())
+    }
+
+    /// 忽略一条更新：记住当前版本，源中内容再次变化时重新提示
+    pub fn dismiss_update(
+        db: &Database,
+        subscription_id: &str,
+        kind: FeedItemKind,
+        item_id: &str,
+    ) -> Result<(), AppError> {
+        let item = Self::require_item(db, subscription_id, kind, item_id)?;
+        db.set_feed_item_accepted(subscription_id, kind, item_id, &item.content_hash, false)
+    }
+
+    fn require_subscription(db: &Database, id: &str) -> Result<FeedSubscription, AppError> {
+        db.get_feed_subscription(id)?.ok_or_else(|| {
+            AppError::localized(
+                "feed.not_found",
+                format!("订阅不存在: {id}"),
+                format!("Subscription not found: {id}"),
+            )
+        })
+    }
+
+    fn require_item(
+        db: &Database,
+        subscription_id: &str,
+        kind: FeedItemKind,
+        item_id: &str,
+    ) -> Result<FeedItemRecord, AppError> {
+        db.get_feed_item(subscription_id, kind, item_id)?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "feed.item_not_found",
+                    format!("订阅条目不存在: {}:{item_id}", kind.as_str()),
+                    format!("Feed item not found: {}:{item_id}", kind.as_str()),
+                )
+            })
+    }
+}
+
+fn validate_feed_url(raw: &str) -> Result<String, AppError> {
+    let parsed = url::Url::parse(raw.trim()).map_err(|e| {
+        AppError::localized(
+            "feed.invalid_url",
+            format!("无效的订阅地址: {e}"),
+            format!("Invalid feed URL: {e}"),
+        )
+    })?;
+    if parsed.scheme() != "https" {
+        return Err(AppError::localized(
+            "feed.https_required",
+            "订阅地址必须使用 https",
+            "Feed URL must use https",
+        ));
+    }
+    Ok(parsed.to_string())
+}
+
+async fn fetch_manifest(url: &str) -> Result<FeedManifest, AppError> {
+    let response = http_client::get()
+        .get(url)
+        .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+        .send()
+        .await
+        .map_err(|e| {
+            AppError::localized(
+                "feed.fetch_failed",
+                format!("拉取订阅失败: {e}"),
+                format!("Failed to fetch feed: {e}"),
+            )
+        })?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::localized(
+            "feed.fetch_failed",
+            format!("拉取订阅失败: HTTP {}", status.as_u16()),
+            format!("Failed to fetch feed: HTTP {}", status.as_u16()),
+        ));
+    }
+    let body = response.text().await.map_err(|e| {
+        AppError::localized(
+            "feed.fetch_failed",
+            format!("读取订阅内容失败: {e}"),
+            format!("Failed to read feed: {e}"),
+        )
+    })?;
+    parse_manifest(&body)
+}
+
+fn parse_manifest(body: &str) -> Result<FeedManifest, AppError> {
+    let manifest: FeedManifest = serde_json::from_str(body).map_err(|e| {
+        AppError::localized(
+            "feed.invalid_manifest",
+            format!("无效的订阅清单: {e}"),
+            format!("Invalid feed manifest: {e}"),
+        )
+    })?;
+    if manifest.format != FEED_FORMAT || manifest.version > FEED_VERSION {
+        return Err(AppError::localized(
+            "feed.unsupported_manifest",
+            format!(
+                "不支持的订阅清单: {} v{}",
+                manifest.format, manifest.version
+            ),
+            format!(
+                "Unsupported feed manifest: {} v{}",
+                manifest.format, manifest.version
+            ),
+        ));
+    }
+    Ok(manifest)
+}
+
+/// 把清单展开为条目行；id 去除首尾空白（payload 中同样），跳过缺少 id 的条目，
+/// 同一类型内重复的 id 以第一次出现为准
+fn manifest_items(
+    manifest: &FeedManifest,
+    subscription_id: &str,
+    fetched_at: i64,
+) -> Result<Vec<FeedItemRecord>, AppError> {
+    let mut items: Vec<FeedItemRecord> = Vec::new();
+    let mut push = |kind: FeedItemKind, id: &str, name: &str, payload: String| {
+        if id.is_empty() {
+            log::warn!("跳过缺少 id 的订阅条目: {name}");
+            return;
+        }
+        if items.iter().any(|i| i.kind == kind && i.item_id == id) {
+            log::warn!("跳过重复的订阅条目 {}:{id}", kind.as_str());
+            return;
+        }
+        items.push(FeedItemRecord {
+            subscription_id: subscription_id.to_string(),
+            kind,
+            item_id: id.to_string(),
+            name: name.to_string(),
+            content_hash: content_hash(&payload),
+            payload,
+            accepted_hash: None,
+            applied: false,
+            fetched_at,
+        });
+    };
+
+    for prompt in &manifest.prompts {
+        let prompt = FeedPrompt {
+            id: prompt.id.trim().to_string(),
+            ..prompt.clone()
+        };
+        push(
+            FeedItemKind::Prompt,
+            &prompt.id,
+            &prompt.name,
+            to_payload(&prompt)?,
+        );
+    }
+    for agent in &manifest.agents {
+        let agent = FeedAgent {
+            id: agent.id.trim().to_string(),
+            ..agent.clone()
+        };
+        push(
+            FeedItemKind::Agent,
+            &agent.id,
+            &agent.name,
+            to_payload(&agent)?,
+        );
+    }
+    for server in &manifest.mcp_servers {
+        if !server.server.is_object() {
+            log::warn!("跳过 server 字段不是对象的 MCP 条目: {}", server.id);
+            continue;
+        }
+        let server = FeedMcpServer {
+            id: server.id.trim().to_string(),
+            ..server.clone()
+        };
+        push(
+            FeedItemKind::Mcp,
+            &server.id,
+            &server.name,
+            to_payload(&server)?,
+        );
+    }
+    Ok(items)
+}
+
+/// MCP 服务器的启动命令（command 与 args）
+fn launch_command(
+    server: &serde_json::Value,
+) -> (Option<&serde_json::Value>, Option<&serde_json::Value>) {
+    (server.get("command"), server.get("args"))
+}
+
+fn pretty_json(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+fn to_payload<T: Serialize>(item: &T) -> Result<String, AppError> {
+    serde_json::to_string(item).map_err(|e| AppError::JsonSerialize { source: e })
+}
+
+fn content_hash(payload: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(payload.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn decode_payload<T: serde::de::DeserializeOwned>(item: &FeedItemRecord) -> Result<T, AppError> {
+    serde_json::from_str(&item.payload).map_err(|e| {
+        AppError::localized(
+            "feed.invalid_manifest",
+            format!("无法解析订阅条目 {}: {e}", item.item_id),
+            format!("Failed to parse feed item {}: {e}", item.item_id),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::agent::DiffLineKind;
+
+    #[test]
+    fn manifest_is_validated_and_expanded_into_items() {
+        assert!(parse_manifest(r#"{"format":"other","version":1}"#).is_err());
+        assert!(parse_manifest(r#"{"format":"cc-switch-feed","version":99}"#).is_err());
+
+        let manifest = parse_manifest(
+            r#"{
+                "format": "cc-switch-feed",
+                "version": 1,
+                "name": "Team",
+                "prompts": [{"id": " base ", "name": "Base", "content": "Be concise."}],
+                "agents": [
+                    {"id": "reviewer", "name": "Reviewer", "content": "Review."},
+                    {"id": "reviewer", "name": "Duplicate", "content": "Ignored."},
+                    {"id": " ", "name": "No id", "content": "Ignored."}
+                ],
+                "mcpServers": [
+                    {"id": "fetch", "name": "Fetch", "server": {"command": "uvx"}},
+                    {"id": "broken", "name": "Broken", "server": "uvx"}
+                ]
+            }"#,
+        )
+        .expect("valid manifest");
+
+        let items = manifest_items(&manifest, "sub", 0).unwrap();
+        let keys: Vec<(FeedItemKind, &str)> =
+            items.iter().map(|i| (i.kind, i.item_id.as_str())).collect();
+        assert_eq!(
+            keys,
+            vec![
+                (FeedItemKind::Prompt, "base"),
+                (FeedItemKind::Agent, "reviewer"),
+                (FeedItemKind::Mcp, "fetch"),
+            ]
+        );
+        assert!(items.iter().all(FeedItemRecord::is_pending));
+        let payload: FeedPrompt = decode_payload(&items[0]).unwrap();
+        assert_eq!(payload.id, "base");
+    }
+
+    #[test]
+    fn local_items_and_unconfirmed_mcp_commands_are_not_accepted() {
+        let db = Arc::new(Database::memory().expect("memory db"));
+        let state = AppState::new(db.clone());
+        db.save_feed_subscription(&FeedSubscription {
+            id: "team".to_string(),
+            url: "https://example.com/feed.json".to_string(),
+            name: "Team".to_string(),
+            enabled: true,
+            last_checked_at: None,
+            last_error: None,
+            created_at: 1,
+        })
+        .unwrap();
+        let manifest = parse_manifest(
+            r#"{
+                "format": "cc-switch-feed",
+                "version": 1,
+                "prompts": [{"id": "base", "name": "Base", "content": "Be concise.\nUse tests."}],
+                "mcpServers": [{"id": "fetch", "name": "Fetch", "server": {"command": "uvx"}}]
+            }"#,
+        )
+        .unwrap();
+        db.replace_feed_items("team", &manifest_items(&manifest, "team", 0).unwrap())
+            .unwrap();
+        db.save_prompt(&Prompt {
+            id: "base".to_string(),
+            name: "Mine".to_string(),
+            content: "Be concise.".to_string(),
+            description: None,
+            tags: Vec::new(),
+            apps: Default::default(),
+            sort_index: None,
+            created_at: None,
+            updated_at: None,
+        })
+        .unwrap();
+
+        let updates = FeedService::get_pending_updates(&state).unwrap();
+        let prompt = updates.iter().find(|u| u.item_id == "base").unwrap();
+        assert_eq!(prompt.status, FeedUpdateStatus::Conflict);
+        assert_eq!(prompt.payload["content"], "Be concise.\nUse tests.");
+        let added: Vec<&str> = prompt
+            .diff
+            .iter()
+            .filter(|l| l.kind == DiffLineKind::Added)
+            .map(|l| l.text.as_str())
+            .collect();
+        assert_eq!(added, ["Use tests."]);
+        assert!(!prompt.requires_confirmation);
+
+        let server = updates.iter().find(|u| u.item_id == "fetch").unwrap();
+        assert_eq!(server.status, FeedUpdateStatus::New);
+        assert!(server.requires_confirmation);
+
+        // 本地手写的同 id 提示词不会被覆盖
+        let err = FeedService::accept_update(&state, "team", FeedItemKind::Prompt, "base", true)
+            .unwrap_err();
+        assert!(err.to_string().contains("base"));
+        assert_eq!(db.get_prompts().unwrap()["base"].name, "Mine");
+
+        // 新的 MCP 启动命令未确认时拒绝
+        assert!(
+            FeedService::accept_update(&state, "team", FeedItemKind::Mcp, "fetch", false).is_err()
+        );
+        assert!(db.get_all_mcp_servers().unwrap().is_empty());
+
+        // 曾从该订阅接受过的条目视为更新
+        db.set_feed_item_accepted("team", FeedItemKind::Prompt, "base", "old", true)
+            .unwrap();
+        let updates = FeedService::get_pending_updates(&state).unwrap();
+        let prompt = updates.iter().find(|u| u.item_id == "base").unwrap();
+        assert_eq!(prompt.status, FeedUpdateStatus::Updated);
+    }
+
+    #[test]
+    fn only_https_urls_are_accepted() {
+        assert!(validate_feed_url("https://example.com/feed.json").is_ok());
+        assert!(validate_feed_url("http://example.com/feed.json").is_err());
+        assert!(validate_feed_url("not a url").is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! 统一的 id 与时间标签生成
+//!
+//! 导入、自动导入、深链接创建以及复制出的条目都通过 [`IdGenerator`] 生成 id：
+//! 按模式（可在设置中覆盖）渲染出可读的 id，再对照已有条目去重，冲突时追加 `-2`、`-3`……
+//!
+//! 模式中可用的占位符：
+//! - `{slug}`：由名称生成的可读 slug（小写，非字母数字字符折叠为 `-`）
+//! - `{app}`：所属应用（如 `claude`）
+//! - `{date}` / `{time}`：本地日期 `YYYYMMDD` / 时间 `HHMMSS`
+//! - `{ts}`：Unix 毫秒时间戳
+
+use chrono::{DateTime, Local};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+
+/// 冲突时最多尝试的后缀数量，超过后改用时间戳兜底
+const MAX_SUFFIX_ATTEMPTS: usize = 1000;
+
+/// 需要生成 id 的场景
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdKind {
+    /// 手动从提示词文件导入
+    ImportedPrompt,
+    /// 首次启动时自动导入提示词
+    AutoImportedPrompt,
+    /// 深链接导入提示词
+    DeeplinkPrompt,
+    /// 深链接导入供应商
+    DeeplinkProvider,
+    /// 复制供应商
+    DuplicateProvider,
+    /// 复制提示词
+    DuplicatePrompt,
+    /// 从内置预设创建供应商
+    PresetProvider,
+    /// 从导出文件导入供应商（仅在原 id 已被占用时使用）
+    ImportedProvider,
+}
+
+impl IdKind {
+    /// 设置 `idPatterns` 中对应的键
+    pub fn setting_key(&self) -> &'static str {
+        match self {
+            Self::ImportedPrompt => "importedPrompt",
+            Self::AutoImportedPrompt => "autoImportedPrompt",
+            Self::DeeplinkPrompt => "deeplinkPrompt",
+            Self::DeeplinkProvider => "deeplinkProvider",
+            Self::DuplicateProvider => "duplicateProvider",
+            Self::DuplicatePrompt => "duplicatePrompt",
+            Self::PresetProvider => "presetProvider",
+            Self::ImportedProvider => "importedProvider",
+        }
+    }
+
+    fn default_pattern(&self) -> &'static str {
+        match self {
+            Self::ImportedPrompt => "imported-{app}-{date}",
+            Self::AutoImportedPrompt => "auto-imported-{app}-{date}",
+            Self::DeeplinkPrompt
+            | Self::DeeplinkProvider
+            | Self::PresetProvider
+            | Self::ImportedProvider => "{slug}",
+            Self::DuplicateProvider | Self::DuplicatePrompt => "{slug}-copy",
+        }
+    }
+
+    /// 名称为空或 slug 为空时使用的占位 slug
+    fn fallback_slug(&self) -> &'static str {
+        match self {
+            Self::ImportedPrompt
+            | Self::AutoImportedPrompt
+            | Self::DeeplinkPrompt
+            | Self::DuplicatePrompt => "prompt",
+            Self::DeeplinkProvider
+            | Self::DuplicateProvider
+            | Self::PresetProvider
+            | Self::ImportedProvider => "provider",
+        }
+    }
+}
+
+/// id 生成器
+#[derive(Debug, Clone)]
+pub struct IdGenerator {
+    kind: IdKind,
+    pattern: String,
+    name: Option<String>,
+    app: Option<AppType>,
+}
+
+impl IdGenerator {
+    /// 使用设置中的模式（未配置时使用默认模式）
+    pub fn new(kind: IdKind) -> Self {
+        let pattern = crate::settings::get_id_pattern(kind.setting_key())
+            .filter(|p| !p.trim().is_empty())
+            .unwrap_or_else(|| kind.default_pattern().to_string());
+        Self::with_pattern(kind, pattern)
+    }
+
+    /// 使用指定模式
+    pub fn with_pattern(kind: IdKind, pattern: impl Into<String>) -> Self {
+        Self {
+            kind,
+            pattern: pattern.into(),
+            name: None,
+            app: None,
+        }
+    }
+
+    /// 提供 `{slug}` 的来源名称
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// 提供 `{app}` 的取值
+    pub fn app(mut self, app: &AppType) -> Self {
+        self.app = Some(app.clone());
+        self
+    }
+
+    /// 生成 id；`exists` 用于检查 id 是否已被占用（通常查询数据库）
+    pub fn generate(
+        &self,
+        mut exists: impl FnMut(&str) -> Result<bool, AppError>,
+    ) -> Result<String, AppError> {
+        let now = Local::now();
+        let base = self.render(&now);
+        if !exists(&base)? {
+            return Ok(base);
+        }
+        for n in 2..=MAX_SUFFIX_ATTEMPTS {
+            let candidate = format!("{base}-{n}");
+            if !exists(&candidate)? {
+                return Ok(candidate);
+            }
+        }
+        Ok(format!("{base}-{}", now.timestamp_millis()))
+    }
+
+    fn render(&self, now: &DateTime<Local>) -> String {
+        let slug = self
+            .name
+            .as_deref()
+            .map(slugify)
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| self.kind.fallback_slug().to_string());
+        let app = self.app.as_ref().map(AppType::as_str).unwrap_or("app");
+
+        let rendered = self
+            .pattern
+            .replace("{slug}", &slug)
+            .replace("{app}", app)
+            .replace("{date}", &now.format("%Y%m%d").to_string())
+            .replace("{time}", &now.format("%H%M%S").to_string())
+            .replace("{ts}", &now.timestamp_millis().to_string());
+        // 模式中的其他字符同样按 slug 规则规范化，保证 id 可安全用作键或文件名
+        let id = slugify(&rendered);
+        if id.is_empty() {
+            slug
+        } else {
+            id
+        }
+    }
+}
+
+/// 生成可读 slug：保留字母数字（含非 ASCII 文字）与 `_`，其余字符折叠为单个 `-`
+pub fn slugify(input: &str) -> String {
+    let mut slug = String::with_capacity(input.len());
+    for c in input.trim().chars() {
+        if c.is_alphanumeric() || c == '_' {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// 用于条目名称的本地时间标签（如 "导入的提示词 2026-01-02 15:04"）
+pub fn local_time_label() -> String {
+    Local::now().format("%Y-%m-%d %H:%M").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn slugify_collapses_separators() {
+        assert_eq!(slugify("  My Provider (Beta)! "), "my-provider-beta");
+        assert_eq!(slugify("代码 审查"), "代码-审查");
+        assert_eq!(slugify("--__--"), "__");
+        assert_eq!(slugify("!!!"), "");
+    }
+
+    #[test]
+    fn renders_pattern_and_avoids_collisions() {
+        let now = Local.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap();
+        let generator = IdGenerator::with_pattern(IdKind::ImportedPrompt, "imported-{app}-{date}")
+            .app(&AppType::Codex);
+        assert_eq!(generator.render(&now), "imported-codex-20260102");
+
+        let generator = IdGenerator::with_pattern(IdKind::DeeplinkProvider, "{slug}").name("  ");
+        assert_eq!(generator.render(&now), "provider");
+
+        let taken = ["acme", "acme-2"];
+        let id = IdGenerator::with_pattern(IdKind::DeeplinkProvider, "{slug}")
+            .name("Acme")
+            .generate(|id| Ok(taken.contains(&id)))
+            .unwrap();
+        assert_eq!(id, "acme-3");
+    }
+}
@@ -0,0 +1,248 @@
+//! 后台任务
+//!
+//! 耗时操作（技能仓库刷新、批量健康检查、数据库备份、全量重新同步）以任务形式在后台执行：
+//! 命令立即返回任务 id，执行过程通过事件通知前端：
+//! - `job-progress`：`{ jobId, kind, current, total, message }`
+//! - `job-completed`：`{ jobId, kind, result }`
+//! - `job-failed`：`{ jobId, kind, error, cancelled }`
+//!
+//! `cancel_job` 只设置取消标记，任务在步骤之间检查标记后提前结束（已开始的步骤会执行完毕）。
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AppError;
+
+/// 事件的发送方：应用内转发到前端，测试中可替换为记录事件的闭包
+pub(crate) type EventSink = Arc<dyn Fn(&str, Value) + Send + Sync>;
+
+/// 运行中任务的取消标记（任务结束后移除）
+fn running_jobs() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    static JOBS: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+    JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn lock_jobs() -> std::sync::MutexGuard<'static, HashMap<String, Arc<AtomicBool>>> {
+    running_jobs().lock().unwrap_or_else(|e| {
+        log::warn!("任务表锁已毒化，使用恢复值: {e}");
+        e.into_inner()
+    })
+}
+
+/// 任务执行上下文：上报进度、检查取消
+#[derive(Clone)]
+pub struct JobContext {
+    id: String,
+    kind: &'static str,
+    events: EventSink,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobContext {
+    /// 发送 `job-progress` 事件（`current` 为已完成的步骤数）
+    pub fn progress(&self, current: usize, total: usize, message: impl Into<String>) {
+        let payload = json!({
+            "jobId": self.id,
+            "kind": self.kind,
+            "current": current,
+            "total": total,
+            "message": message.into(),
+        });
+        (self.events)("job-progress", payload);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 已请求取消时返回错误，供任务在步骤之间用 `?` 提前结束
+    pub fn ensure_not_cancelled(&self) -> Result<(), AppError> {
+        if self.is_cancelled() {
+            return Err(AppError::localized(
+                "job.cancelled",
+                "任务已取消",
+                "Job was cancelled",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 后台任务服务
+pub struct JobService;
+
+impl JobService {
+    /// 在后台启动任务并立即返回任务 id
+    ///
+    /// 任务成功时发送 `job-completed`（携带序列化后的结果），失败或被取消时发送 `job-failed`。
+    pub fn spawn<T, F, Fut>(app: &AppHandle, kind: &'static str, task: F) -> String
+    where
+        T: Serialize,
+        F: FnOnce(JobContext) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, AppError>> + Send + 'static,
+    {
+        let app = app.clone();
+        let events: EventSink = Arc::new(move |event, payload| {
+            if let Err(e) = app.emit(event, payload) {
+                log::debug!("发送 {event} 事件失败: {e}");
+            }
+        });
+        Self::spawn_with_events(events, kind, task)
+    }
+
+    fn spawn_with_events<T, F, Fut>(events: EventSink, kind: &'static str, task: F) -> String
+    where
+        T: Serialize,
+        F: FnOnce(JobContext) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, AppError>> + Send + 'static,
+    {
+        let id = uuid::Uuid::new_v4().to_string();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        lock_jobs().insert(id.clone(), cancelled.clone());
+
+        let ctx = JobContext {
+            id: id.clone(),
+            kind,
+            events,
+            cancelled,
+        };
+        tauri::async_runtime::spawn(async move {
+            log::info!("[Job] 开始任务 {kind} ({})", ctx.id);
+            let result = task(ctx.clone()).await;
+            lock_jobs().remove(&ctx.id);
+
+            let (event, payload) = match result.and_then(|value| {
+                serde_json::to_value(value).map_err(|e| AppError::JsonSerialize { source: e })
+            }) {
+                Ok(value) => {
+                    log::info!("[Job] 任务 {kind} ({}) 已完成", ctx.id);
+                    (
+                        "job-completed",
+                        json!({ "jobId": ctx.id, "kind": kind, "result": value }),
+                    )
+                }
+                Err(e) => {
+                    let cancelled = ctx.is_cancelled();
+                    if cancelled {
+                        log::info!("[Job] 任务 {kind} ({}) 已取消", ctx.id);
+                    } else {
+                        log::warn!("[Job] 任务 {kind} ({}) 失败: {e}", ctx.id);
+                    }
+                    (
+                        "job-failed",
+                        json!({
+                            "jobId": ctx.id,
+                            "kind": kind,
+                            "error": e.to_string(),
+                            "cancelled": cancelled,
+                        }),
+                    )
+                }
+            };
+            (ctx.events)(event, payload);
+        });
+        id
+    }
+
+    /// 请求取消任务；任务不存在或已结束时返回 false
+    pub fn cancel(id: &str) -> bool {
+        match lock_jobs().get(id) {
+            Some(flag) => {
+                flag.store(true, Ordering::SeqCst);
+                log::info!("[Job] 已请求取消任务 {id}");
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 运行中的任务 id 列表
+    pub fn running() -> Vec<String> {
+        lock_jobs().keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    /// 把事件转发到 channel，返回事件发送方与接收端
+    fn recording_sink() -> (EventSink, mpsc::Receiver<(String, Value)>) {
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+        let sink: EventSink = Arc::new(move |event, payload| {
+            let _ = tx.lock().unwrap().send((event.to_string(), payload));
+        });
+        (sink, rx)
+    }
+
+    fn next_event(rx: &mpsc::Receiver<(String, Value)>) -> (String, Value) {
+        rx.recv_timeout(Duration::from_secs(5))
+            .expect("job event within timeout")
+    }
+
+    #[test]
+    fn completed_job_reports_progress_and_result() {
+        let (sink, rx) = recording_sink();
+        let id = JobService::spawn_with_events(sink, "test", |ctx| async move {
+            ctx.progress(1, 2, "halfway");
+            Ok(42)
+        });
+
+        let (event, payload) = next_event(&rx);
+        assert_eq!(event, "job-progress");
+        assert_eq!(payload["jobId"], id);
+        assert_eq!(payload["current"], 1);
+        assert_eq!(payload["message"], "halfway");
+
+        let (event, payload) = next_event(&rx);
+        assert_eq!(event, "job-completed");
+        assert_eq!(payload["kind"], "test");
+        assert_eq!(payload["result"], 42);
+        assert!(!JobService::running().contains(&id));
+        assert!(!JobService::cancel(&id));
+    }
+
+    #[test]
+    fn failed_job_reports_error_without_cancelled_flag() {
+        let (sink, rx) = recording_sink();
+        let id = JobService::spawn_with_events(sink, "test", |_ctx| async move {
+            Err::<(), _>(AppError::Message("boom".to_string()))
+        });
+
+        let (event, payload) = next_event(&rx);
+        assert_eq!(event, "job-failed");
+        assert_eq!(payload["jobId"], id);
+        assert_eq!(payload["cancelled"], false);
+        assert!(payload["error"].as_str().unwrap().contains("boom"));
+    }
+
+    #[test]
+    fn cancelled_job_stops_at_next_check() {
+        let (sink, rx) = recording_sink();
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+        let id = JobService::spawn_with_events(sink, "test", |ctx| async move {
+            let _ = release_rx.await;
+            ctx.ensure_not_cancelled()?;
+            Ok("finished")
+        });
+
+        assert!(JobService::running().contains(&id));
+        assert!(JobService::cancel(&id));
+        release_tx.send(()).unwrap();
+
+        let (event, payload) = next_event(&rx);
+        assert_eq!(event, "job-failed");
+        assert_eq!(payload["jobId"], id);
+        assert_eq!(payload["cancelled"], true);
+        assert!(!JobService::running().contains(&id));
+    }
+}
@@ -0,0 +1,527 @@
+//! 重新出现的旧版 config.json 的检测与选择性合并
+//!
+//! 首次启动时旧版 `config.json` 会被一次性迁移到 SQLite 并归档为 `config.json.migrated`。
+//! 但 dotfiles 同步、备份恢复等可能让一份 `config.json` 再次出现；之后每次启动都会检查：
+//! 文件比上次迁移（或上次处理）时更新，就提示用户，而不是像以前一样直接忽略。
+//!
+//! 用户可以先预览差异（[`LegacyConfigDiff`]：新增/变更的供应商、MCP 服务器和提示词），
+//! 再勾选要合并的条目；也可以忽略这一版文件。两种处理都会记下文件的修改时间，
+//! 同一份文件不会被重复提示。
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::app_config::{AppType, McpServer, MultiAppConfig};
+use crate::config::get_app_config_path;
+use crate::error::AppError;
+use crate::prompt::{Prompt, PromptApps};
+use crate::provider::Provider;
+use crate::services::{McpService, PromptService, ProviderAuditOrigin, ProviderService};
+use crate::store::AppState;
+
+/// 记录上次迁移/处理的 config.json 修改时间（Unix 毫秒）的设置键
+const MERGED_AT_KEY: &str = "legacy_config_merged_at";
+
+/// 参与差异比较的字段（序列化后的字段名）
+const PROVIDER_FIELDS: &[&str] = &[
+    "name",
+    "settingsConfig",
+    "websiteUrl",
+    "category",
+    "notes",
+    "icon",
+    "iconColor",
+];
+const MCP_FIELDS: &[&str] = &[
+    "name",
+    "server",
+    "apps",
+    "description",
+    "homepage",
+    "docs",
+    "tags",
+];
+const PROMPT_FIELDS: &[&str] = &["name", "content", "description"];
+
+/// 待处理的旧版配置文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyConfigStatus {
+    pub path: String,
+    /// 文件修改时间（Unix 毫秒）
+    pub modified_at: i64,
+    /// 上次迁移或处理时的文件修改时间；从未迁移过时为 None
+    pub last_merged_at: Option<i64>,
+}
+
+/// 差异条目的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LegacyEntryKind {
+    Provider,
+    Mcp,
+    Prompt,
+}
+
+/// 合并后对数据库的影响
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LegacyChangeAction {
+    Add,
+    Update,
+}
+
+/// 旧版配置与数据库之间的一处差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyConfigChange {
+    pub kind: LegacyEntryKind,
+    /// 供应商所属的应用；MCP 与提示词为 None
+    pub app: Option<String>,
+    pub id: String,
+    pub name: String,
+    pub action: LegacyChangeAction,
+    /// 值不同的字段（新增时为空）
+    pub changed_fields: Vec<String>,
+    /// 数据库中的当前值（只含比较的字段；新增时为 None）
+    pub current: Option<Value>,
+    /// 合并后的值（只含比较的字段）
+    pub incoming: Value,
+}
+
+/// 合并预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyConfigDiff {
+    pub status: LegacyConfigStatus,
+    pub changes: Vec<LegacyConfigChange>,
+}
+
+/// 选择合并的条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyMergeSelection {
+    pub kind: LegacyEntryKind,
+    #[serde(default)]
+    pub app: Option<String>,
+    pub id: String,
+}
+
+/// 合并结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyMergeResult {
+    pub merged: usize,
+    /// 没有合并的条目及原因
+    pub failed: Vec<String>,
+}
+
+/// 已解析的旧版配置内容（按类型分组）
+struct LegacyEntries {
+    providers: Vec<(AppType, Provider)>,
+    mcp_servers: Vec<McpServer>,
+    prompts: Vec<Prompt>,
+}
+
+pub struct LegacyConfigService;
+
+impl LegacyConfigService {
+    /// 检查是否有比上次迁移更新的 config.json；没有时返回 None
+    pub fn detect(state: &AppState) -> Result<Option<LegacyConfigStatus>, AppError> {
+        let path = get_app_config_path();
+        let Some(modified_at) = modified_millis(&path)? else {
+            return Ok(None);
+        };
+        let last_merged_at = Self::last_merged_at(state, &path)?;
+        if last_merged_at.is_some_and(|merged| modified_at <= merged) {
+            return Ok(None);
+        }
+        Ok(Some(LegacyConfigStatus {
+            path: path.display().to_string(),
+            modified_at,
+            last_merged_at,
+        }))
+    }
+
+    /// 预览合并 config.json 会对数据库做出的改动；文件不需要处理时返回 None
+    pub fn preview(state: &AppState) -> Result<Option<LegacyConfigDiff>, AppError> {
+        let Some(status) = Self::detect(state)? else {
+            return Ok(None);
+        };
+        let entries = load_entries(&get_app_config_path())?;
+        let mut changes = Vec::new();
+
+        for (app, provider) in &entries.providers {
+            let current = state.db.get_provider_by_id(&provider.id, app.as_str())?;
+            changes.extend(diff_entry(
+                LegacyEntryKind::Provider,
+                Some(app),
+                &provider.id,
+                &provider.name,
+                current.as_ref(),
+                provider,
+                PROVIDER_FIELDS,
+            )?);
+        }
+
+        let servers = state.db.get_all_mcp_servers()?;
+        for server in &entries.mcp_servers {
+            changes.extend(diff_entry(
+                LegacyEntryKind::Mcp,
+                None,
+                &server.id,
+                &server.name,
+                servers.get(&server.id),
+                server,
+                MCP_FIELDS,
+            )?);
+        }
+
+        let prompts = state.db.get_prompts()?;
+        for prompt in &entries.prompts {
+            changes.extend(diff_entry(
+                LegacyEntryKind::Prompt,
+                None,
+                &prompt.id,
+                &prompt.name,
+                prompts.get(&prompt.id),
+                prompt,
+                PROMPT_FIELDS,
+            )?);
+        }
+
+        Ok(Some(LegacyConfigDiff { status, changes }))
+    }
+
+    /// 合并选中的条目，并记下这一版文件已处理
+    ///
+    /// 已存在的条目只覆盖参与比较的字段，排序、启用状态等仍以数据库为准；新增的提示词默认不启用。
+    /// 单个条目失败不影响其余条目，原因随结果返回。
+    pub fn merge(
+        state: &AppState,
+        selections: &[LegacyMergeSelection],
+    ) -> Result<LegacyMergeResult, AppError> {
+        let path = get_app_config_path();
+        let Some(modified_at) = modified_millis(&path)? else {
+            return Err(AppError::localized(
+                "legacy_config.not_found",
+                "旧版配置文件不存在",
+                "The legacy config file no longer exists",
+            ));
+        };
+        let entries = load_entries(&path)?;
+        let mut result = LegacyMergeResult::default();
+
+        for selection in selections {
+            match Self::merge_one(state, &entries, selection) {
+                Ok(true) => result.merged += 1,
+                Ok(false) => result
+                    .failed
+                    .push(format!("{}: 旧版配置中不存在该条目", selection.id)),
+                Err(e) => result.failed.push(format!("{}: {e}", selection.id)),
+            }
+        }
+
+        Self::record_merged_at(state, modified_at)?;
+        Ok(result)
+    }
+
+    /// 忽略当前这一版 config.json，之后只有文件再次更新才会提示
+    pub fn dismiss(state: &AppState) -> Result<(), AppError> {
+        if let Some(modified_at) = modified_millis(&get_app_config_path())? {
+            Self::record_merged_at(state, modified_at)?;
+        }
+        Ok(())
+    }
+
+    fn merge_one(
+        state: &AppState,
+        entries: &LegacyEntries,
+        selection: &LegacyMergeSelection,
+    ) -> Result<bool, AppError> {
+        match selection.kind {
+            LegacyEntryKind::Provider => {
+                let app = selection
+                    .app
+                    .as_deref()
+                    .map(AppType::from_str)
+                    .transpose()?
+                    .ok_or_else(|| AppError::InvalidInput("合并供应商需要指定应用".to_string()))?;
+                let Some((_, incoming)) = entries
+                    .providers
+                    .iter()
+                    .find(|(a, p)| *a == app && p.id == selection.id)
+                else {
+                    return Ok(false);
+                };
+                match state.db.get_provider_by_id(&incoming.id, app.as_str())? {
+                    Some(current) => {
+                        let merged = overlay(&current, incoming, PROVIDER_FIELDS)?;
+                        ProviderService::update_from(
+                            state,
+                            app,
+                            merged,
+                            ProviderAuditOrigin::Import,
+                        )?;
+                    }
+                    None => {
+                        ProviderService::add_from(
+                            state,
+                            app,
+                            incoming.clone(),
+                            ProviderAuditOrigin::Import,
+                        )?;
+                    }
+                }
+            }
+            LegacyEntryKind::Mcp => {
+                let Some(incoming) = entries.mcp_servers.iter().find(|s| s.id == selection.id)
+                else {
+                    return Ok(false);
+                };
+                let merged = match state.db.get_all_mcp_servers()?.get(&incoming.id) {
+                    Some(current) => overlay(current, incoming, MCP_FIELDS)?,
+                    None => incoming.clone(),
+                };
+                McpService::upsert_server(state, merged)?;
+            }
+            LegacyEntryKind::Prompt => {
+                let Some(incoming) = entries.prompts.iter().find(|p| p.id == selection.id) else {
+                    return Ok(false);
+                };
+                match state.db.get_prompts()?.get(&incoming.id) {
+                    Some(current) => {
+                        let mut merged = overlay(current, incoming, PROMPT_FIELDS)?;
+                        merged.updated_at = Some(chrono::Utc::now().timestamp());
+                        PromptService::upsert_prompt(state, merged)?;
+                    }
+                    None => state.db.save_prompt(&Prompt {
+                        apps: PromptApps::default(),
+                        sort_index: None,
+                        ..incoming.clone()
+                    })?,
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// 上次处理时的文件修改时间；没有记录时退回到首次迁移归档文件的修改时间
+    fn last_merged_at(state: &AppState, path: &Path) -> Result<Option<i64>, AppError> {
+        if let Some(value) = state.db.get_setting(MERGED_AT_KEY)? {
+            if let Ok(millis) = value.parse() {
+                return Ok(Some(millis));
+            }
+            log::warn!("忽略无效的 {MERGED_AT_KEY} 设置值: {value}");
+        }
+        modified_millis(&path.with_extension("json.migrated"))
+    }
+
+    fn record_merged_at(state: &AppState, modified_at: i64) -> Result<(), AppError> {
+        state
+            .db
+            .set_setting(MERGED_AT_KEY, &modified_at.to_string())
+    }
+}
+
+/// 文件修改时间（Unix 毫秒），文件不存在时返回 None
+fn modified_millis(path: &Path) -> Result<Option<i64>, AppError> {
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(AppError::io(path, e)),
+    };
+    let modified = metadata.modified().map_err(|e| AppError::io(path, e))?;
+    Ok(Some(
+        modified
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0),
+    ))
+}
+
+/// 只读解析 config.json（不做 `MultiAppConfig::load` 中的落盘与自动导入）
+fn load_entries(path: &Path) -> Result<LegacyEntries, AppError> {
+    let content = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+    let mut config: MultiAppConfig =
+        serde_json::from_str(&content).map_err(|e| AppError::json(path, e))?;
+    config.migrate_mcp_to_unified()?;
+
+    let mut providers = Vec::new();
+    for app in AppType::all() {
+        if let Some(manager) = config.apps.get(app.as_str()) {
+            let mut items: Vec<Provider> = manager.providers.values().cloned().collect();
+            items.sort_by(|a, b| a.id.cmp(&b.id));
+            providers.extend(items.into_iter().map(|p| (app.clone(), p)));
+        }
+    }
+
+    let mut mcp_servers: Vec<McpServer> = config
+        .mcp
+        .servers
+        .take()
+        .unwrap_or_default()
+        .into_values()
+        .collect();
+    mcp_servers.sort_by(|a, b| a.id.cmp(&b.id));
+
+    // 与首次迁移一致：同一 id 出现在多个应用中时保留第一个
+    let roots = &config.prompts;
+    let mut seen = HashSet::new();
+    let mut prompts = Vec::new();
+    for map in [
+        &roots.claude.prompts,
+        &roots.codex.prompts,
+        &roots.gemini.prompts,
+        &roots.opencode.prompts,
+        &roots.openclaw.prompts,
+    ] {
+        let mut items: Vec<&Prompt> = map.values().collect();
+        items.sort_by(|a, b| a.id.cmp(&b.id));
+        for prompt in items {
+            if seen.insert(prompt.id.clone()) {
+                prompts.push(prompt.clone());
+            }
+        }
+    }
+
+    Ok(LegacyEntries {
+        providers,
+        mcp_servers,
+        prompts,
+    })
+}
+
+/// 取出参与比较的字段
+fn pick_fields<T: Serialize>(value: &T, fields: &[&str]) -> Result<Map<String, Value>, AppError> {
+    let value = serde_json::to_value(value).map_err(|source| AppError::JsonSerialize { source })?;
+    Ok(fields
+        .iter()
+        .map(|field| {
+            let v = value.get(*field).cloned().unwrap_or(Value::Null);
+            (field.to_string(), v)
+        })
+        .collect())
+}
+
+/// 比较一个条目，没有差异时返回 None
+fn diff_entry<T: Serialize>(
+    kind: LegacyEntryKind,
+    app: Option<&AppType>,
+    id: &str,
+    name: &str,
+    current: Option<&T>,
+    incoming: &T,
+    fields: &[&str],
+) -> Result<Option<LegacyConfigChange>, AppError> {
+    let incoming = pick_fields(incoming, fields)?;
+    let (action, changed_fields, current) = match current {
+        Some(current) => {
+            let current = pick_fields(current, fields)?;
+            let changed: Vec<String> = fields
+                .iter()
+                .filter(|field| current.get(**field) != incoming.get(**field))
+                .map(|field| field.to_string())
+                .collect();
+            if changed.is_empty() {
+                return Ok(None);
+            }
+            (LegacyChangeAction::Update, changed, Some(current))
+        }
+        None => (LegacyChangeAction::Add, Vec::new(), None),
+    };
+    Ok(Some(LegacyConfigChange {
+        kind,
+        app: app.map(|a| a.as_str().to_string()),
+        id: id.to_string(),
+        name: name.to_string(),
+        action,
+        changed_fields,
+        current: current.map(Value::Object),
+        incoming: Value::Object(incoming),
+    }))
+}
+
+/// 用 `incoming` 中参与比较的字段覆盖 `current`，其余字段保持不变
+fn overlay<T>(current: &T, incoming: &T, fields: &[&str]) -> Result<T, AppError>
+where
+    T: Serialize + serde::de::DeserializeOwned,
+{
+    let mut merged =
+        serde_json::to_value(current).map_err(|source| AppError::JsonSerialize { source })?;
+    let incoming = pick_fields(incoming, fields)?;
+    if let Some(object) = merged.as_object_mut() {
+        for (field, value) in incoming {
+            if value.is_null() {
+                object.remove(&field);
+            } else {
+                object.insert(field, value);
+            }
+        }
+    }
+    serde_json::from_value(merged).map_err(|source| AppError::JsonSerialize { source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn provider(name: &str, config: Value) -> Provider {
+        Provider::with_id("p1".to_string(), name.to_string(), config, None)
+    }
+
+    #[test]
+    fn diff_lists_only_changed_fields() {
+        let current = provider("Relay", json!({"env": {"ANTHROPIC_BASE_URL": "https://a"}}));
+        let same = diff_entry(
+            LegacyEntryKind::Provider,
+            Some(&AppType::Claude),
+            "p1",
+            "Relay",
+            Some(&current),
+            &current,
+            PROVIDER_FIELDS,
+        )
+        .unwrap();
+        assert!(same.is_none());
+
+        let incoming = provider("Relay", json!({"env": {"ANTHROPIC_BASE_URL": "https://b"}}));
+        let change = diff_entry(
+            LegacyEntryKind::Provider,
+            Some(&AppType::Claude),
+            "p1",
+            "Relay",
+            Some(&current),
+            &incoming,
+            PROVIDER_FIELDS,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(change.action, LegacyChangeAction::Update);
+        assert_eq!(change.changed_fields, ["settingsConfig"]);
+        assert_eq!(
+            change.incoming["settingsConfig"]["env"]["ANTHROPIC_BASE_URL"],
+            "https://b"
+        );
+    }
+
+    #[test]
+    fn overlay_keeps_fields_outside_the_comparison() {
+        let mut current = provider("Old", json!({}));
+        current.sort_index = Some(3);
+        current.notes = Some("old note".to_string());
+        let incoming = provider("New", json!({"env": {}}));
+
+        let merged = overlay(&current, &incoming, PROVIDER_FIELDS).unwrap();
+        assert_eq!(merged.name, "New");
+        assert_eq!(merged.settings_config, json!({"env": {}}));
+        assert_eq!(merged.sort_index, Some(3));
+        assert_eq!(merged.notes, None);
+    }
+}
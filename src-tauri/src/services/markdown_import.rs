@@ -0,0 +1,603 @@
+//! 从笔记软件导出的 Markdown 导入提示词 / Agent
+//!
+//! 面向 Obsidian vault 与 Notion 的 Markdown 导出：既可以是单个 `.md` 文件，
+//! 也可以是整个文件夹（递归扫描，跳过 `.obsidian`、`.trash` 等隐藏目录）。
+//! 每篇笔记按以下顺序推断字段：
+//! - 名称：frontmatter 的 `title` / `name` → 开头的一级标题 → 文件名（去掉 Notion 追加的 32 位 id）
+//! - 描述：frontmatter 的 `description` / `summary` → Notion 属性 → 正文第一段
+//! - 正文：去掉 frontmatter、Notion 属性与作为名称的一级标题，Obsidian 的 `[[链接|别名]]` 转为纯文本
+//!
+//! 同时读取 frontmatter 的 `tags`；导入为 Agent 时另以笔记所在子文件夹作为分组。
+//! 按文件夹批量导入提示词时，名称直接取文件名。
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::{
+    AgentBundle, AgentDefinition, AgentRename, DuplicateStrategy, AGENT_BUNDLE_FORMAT,
+    AGENT_BUNDLE_VERSION,
+};
+use crate::error::AppError;
+use crate::prompt::{
+    Prompt, PromptApps, PromptBundle, PROMPT_BUNDLE_FORMAT, PROMPT_BUNDLE_VERSION,
+};
+use crate::services::id_generator::slugify;
+use crate::services::{AgentsService, PromptBundleService};
+use crate::store::AppState;
+use crate::tags::normalize_tags;
+
+/// 从正文第一段推断描述时保留的最大字符数
+const MAX_DESCRIPTION_CHARS: usize = 160;
+
+static WIKI_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"!?\[\[([^\]|#]*)(?:#[^\]|]*)?(?:\|([^\]]+))?\]\]").expect("valid wiki link regex")
+});
+
+static NOTION_ID_SUFFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s+[0-9a-f]{32}$").expect("valid notion id regex"));
+
+/// 导入为哪种条目
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkdownImportTarget {
+    Prompt,
+    Agent,
+}
+
+/// 解析出的单篇笔记
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownNote {
+    /// 由文件名生成的 id（同一批次内已去重，与已有条目的冲突按导入策略处理）
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub content: String,
+    pub tags: Vec<String>,
+    /// 相对导入根目录的子文件夹（`/` 分隔），位于根目录时为 None
+    pub folder: Option<String>,
+    /// 笔记文件路径
+    pub source_path: String,
+}
+
+/// Markdown 导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownImportResult {
+    /// 新增的 id
+    pub imported: Vec<String>,
+    /// 被覆盖的 id
+    pub overwritten: Vec<String>,
+    /// 因 id 已存在而跳过的 id
+    pub skipped: Vec<String>,
+    pub renamed: Vec<AgentRename>,
+    /// 正文为空或无法读取而忽略的文件
+    pub ignored_files: Vec<String>,
+}
+
+/// Markdown 笔记导入服务
+pub struct MarkdownImportService;
+
+impl MarkdownImportService {
+    /// 解析 `path`（文件或文件夹）下的所有笔记，只预览不写入
+    pub fn preview(path: &Path) -> Result<Vec<MarkdownNote>, AppError> {
+        Ok(scan_notes(path, false)?.0)
+    }
+
+    /// 将 `path` 下的笔记导入为提示词或 Agent
+    ///
+    /// 导入的条目不对任何工具启用；`Overwrite` 覆盖已有条目时保留其本地启用状态与排序。
+    pub fn import(
+        state: &AppState,
+        path: &Path,
+        target: MarkdownImportTarget,
+        strategy: DuplicateStrategy,
+    ) -> Result<MarkdownImportResult, AppError> {
+        Self::import_notes(state, path, scan_notes(path, false)?, target, strategy)
+    }
+
+    /// 把文件夹中的每个 `.md` 文件导入为一条提示词
+    ///
+    /// 名称取文件名（去掉 Notion 追加的 id），描述与标签取自 frontmatter，其余规则与 [`Self::import`] 相同。
+    pub fn import_prompts_from_dir(
+        state: &AppState,
+        dir: &Path,
+        strategy: DuplicateStrategy,
+    ) -> Result<MarkdownImportResult, AppError> {
+        if !dir.is_dir() {
+            return Err(AppError::localized(
+                "markdown_import.not_a_directory",
+                format!("不是文件夹: {}", dir.display()),
+                format!("Not a directory: {}", dir.display()),
+            ));
+        }
+        let scanned = scan_notes(dir, true)?;
+        Self::import_notes(state, dir, scanned, MarkdownImportTarget::Prompt, strategy)
+    }
+
+    fn import_notes(
+        state: &AppState,
+        path: &Path,
+        (notes, ignored_files): (Vec<MarkdownNote>, Vec<String>),
+        target: MarkdownImportTarget,
+        strategy: DuplicateStrategy,
+    ) -> Result<MarkdownImportResult, AppError> {
+        let mut result = match target {
+            MarkdownImportTarget::Agent => import_agents(state, notes, strategy)?,
+            MarkdownImportTarget::Prompt => import_prompts(state, notes, strategy)?,
+        };
+        result.ignored_files = ignored_files;
+        log::info!(
+            "Markdown 导入完成（{}）: 新增 {}，覆盖 {}，重命名 {}，跳过 {}，忽略文件 {}",
+            path.display(),
+            result.imported.len(),
+            result.overwritten.len(),
+            result.renamed.len(),
+            result.skipped.len(),
+            result.ignored_files.len()
+        );
+        Ok(result)
+    }
+}
+
+fn import_agents(
+    state: &AppState,
+    notes: Vec<MarkdownNote>,
+    strategy: DuplicateStrategy,
+) -> Result<MarkdownImportResult, AppError> {
+    let existing = state.db.get_all_agents()?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let agents = notes
+        .into_iter()
+        .map(|note| {
+            // 只有覆盖时才沿用本地启用状态；重命名出的新条目与本地条目无关
+            let apps = match strategy {
+                DuplicateStrategy::Overwrite => existing
+                    .get(&note.id)
+                    .map(|local| local.apps.clone())
+                    .unwrap_or_default(),
+                DuplicateStrategy::Skip | DuplicateStrategy::Rename => Default::default(),
+            };
+            AgentDefinition {
+                id: note.id,
+                name: note.name,
+                content: note.content,
+                description: note.description,
+                apps,
+                group: note.folder,
+                tags: note.tags,
+                created_at: Some(now),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let bundle = AgentBundle {
+        format: AGENT_BUNDLE_FORMAT.to_string(),
+        version: AGENT_BUNDLE_VERSION,
+        exported_at: now,
+        agents,
+    };
+    let result = AgentsService::import_bundle(state, bundle, strategy)?;
+    Ok(MarkdownImportResult {
+        imported: result.imported,
+        overwritten: result.overwritten,
+        skipped: result.skipped,
+        renamed: result.renamed,
+        ignored_files: Vec::new(),
+    })
+}
+
+fn import_prompts(
+    state: &AppState,
+    notes: Vec<MarkdownNote>,
+    strategy: DuplicateStrategy,
+) -> Result<MarkdownImportResult, AppError> {
+    let now = chrono::Utc::now().timestamp();
+    let prompts = notes
+        .into_iter()
+        .map(|note| Prompt {
+            id: note.id,
+            name: note.name,
+            content: note.content,
+            description: note.description,
+            tags: note.tags,
+            apps: PromptApps::default(),
+            sort_index: None,
+            created_at: Some(now),
+            updated_at: Some(now),
+        })
+        .collect();
+
+    let bundle = PromptBundle {
+        format: PROMPT_BUNDLE_FORMAT.to_string(),
+        version: PROMPT_BUNDLE_VERSION,
+        exported_at: now,
+        prompts,
+    };
+    let result = PromptBundleService::import_bundle(state, bundle, strategy)?;
+    Ok(MarkdownImportResult {
+        imported: result.imported,
+        overwritten: result.overwritten,
+        skipped: result.skipped,
+        renamed: result.renamed,
+        ignored_files: Vec::new(),
+    })
+}
+
+/// 解析 `path` 下的所有笔记，返回 (笔记, 被忽略的文件)；`name_from_file` 时名称取文件名
+fn scan_notes(
+    path: &Path,
+    name_from_file: bool,
+) -> Result<(Vec<MarkdownNote>, Vec<String>), AppError> {
+    let files = collect_markdown_files(path)?;
+    let root = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+
+    let mut notes = Vec::new();
+    let mut ignored = Vec::new();
+    let mut seen_ids = HashSet::new();
+    for file in files {
+        let raw = match std::fs::read_to_string(&file) {
+            Ok(raw) => raw,
+            Err(e) => {
+                log::warn!("读取笔记失败，已忽略: {}: {e}", file.display());
+                ignored.push(file.to_string_lossy().to_string());
+                continue;
+            }
+        };
+        let mut note = parse_note(root, &file, &raw, name_from_file);
+        if note.content.trim().is_empty() {
+            log::debug!("跳过空笔记: {}", file.display());
+            ignored.push(note.source_path);
+            continue;
+        }
+        // 不同文件夹中的同名笔记在同一批次内先行去重，避免互相覆盖
+        if !seen_ids.insert(note.id.clone()) {
+            let base = note.id.clone();
+            note.id = (2..)
+                .map(|n| format!("{base}-{n}"))
+                .find(|candidate| !seen_ids.contains(candidate))
+                .expect("unbounded id candidates");
+            seen_ids.insert(note.id.clone());
+        }
+        notes.push(note);
+    }
+    Ok((notes, ignored))
+}
+
+/// 收集 `path` 下的 `.md` 文件（按路径排序）；`path` 为文件时只返回它自己
+fn collect_markdown_files(path: &Path) -> Result<Vec<PathBuf>, AppError> {
+    if path.is_file() {
+        if !is_markdown(path) {
+            return Err(AppError::localized(
+                "markdown_import.not_markdown",
+                format!("不是 Markdown 文件: {}", path.display()),
+                format!("Not a Markdown file: {}", path.display()),
+            ));
+        }
+        return Ok(vec![path.to_path_buf()]);
+    }
+    if !path.is_dir() {
+        return Err(AppError::localized(
+            "markdown_import.not_found",
+            format!("路径不存在: {}", path.display()),
+            format!("Path not found: {}", path.display()),
+        ));
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|e| AppError::io(&dir, e))?;
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if hidden {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            // 不跟随目录符号链接，避免链接成环时无限遍历
+            if file_type.is_dir() {
+                pending.push(entry_path);
+            } else if (file_type.is_file() || (file_type.is_symlink() && entry_path.is_file()))
+                && is_markdown(&entry_path)
+            {
+                files.push(entry_path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("md") || e.eq_ignore_ascii_case("markdown"))
+}
+
+/// 笔记中可映射到条目字段的元数据（来自 frontmatter 或 Notion 属性）
+#[derive(Debug, Default)]
+struct NoteMeta {
+    title: Option<String>,
+    description: Option<String>,
+    tags: Vec<String>,
+}
+
+/// 将一篇笔记解析为导入条目，`root` 用于计算所在子文件夹
+///
+/// `name_from_file` 时名称直接取文件名，与文件名相同的一级标题仍会从正文中去掉。
+fn parse_note(root: &Path, file: &Path, raw: &str, name_from_file: bool) -> MarkdownNote {
+    let text = raw.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let (mut meta, body) = split_frontmatter(&text);
+
+    let stem = file
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let notion_title = NOTION_ID_SUFFIX
+        .is_match(&stem)
+        .then(|| NOTION_ID_SUFFIX.replace(&stem, "").to_string());
+    let file_title = notion_title.clone().unwrap_or_else(|| stem.clone());
+
+    let mut body = body.trim_start_matches('\n');
+    let heading = body.strip_prefix("# ").map(|rest| {
+        let (heading, after) = rest.split_once('\n').unwrap_or((rest, ""));
+        (heading.trim().to_string(), after)
+    });
+
+    let name = meta
+        .title
+        .clone()
+        .or_else(|| heading.as_ref().map(|(h, _)| h.clone()))
+        .filter(|n| !n.is_empty() && !name_from_file)
+        .unwrap_or_else(|| file_title.clone());
+    if let Some((heading, after)) = &heading {
+        if heading.eq_ignore_ascii_case(&name) {
+            body = after.trim_start_matches('\n');
+        }
+    }
+
+    // Notion 导出的数据库页面在标题后紧跟 `属性: 值` 行
+    if notion_title.is_some() {
+        if let Some((props, rest)) = split_notion_properties(body) {
+            meta.description = meta.description.or(props.description);
+            if meta.tags.is_empty() {
+                meta.tags = props.tags;
+            }
+            body = rest;
+        }
+    }
+
+    let mut content = WIKI_LINK
+        .replace_all(body, |caps: &regex::Captures<'_>| {
+            caps.get(2)
+                .or_else(|| caps.get(1))
+                .map(|m| m.as_str().trim().to_string())
+                .unwrap_or_default()
+        })
+        .trim_matches('\n')
+        .to_string();
+    if !content.is_empty() {
+        content.push('\n');
+    }
+
+    let description = meta.description.or_else(|| first_paragraph(&content));
+    let id = [slugify(&file_title), slugify(&name)]
+        .into_iter()
+        .find(|s| !s.is_empty())
+        .unwrap_or_else(|| "note".to_string());
+    let folder = file
+        .parent()
+        .and_then(|parent| parent.strip_prefix(root).ok())
+        .map(|rel| {
+            rel.components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .filter(|folder| !folder.is_empty());
+
+    MarkdownNote {
+        id,
+        name,
+        description,
+        content,
+        tags: meta.tags,
+        folder,
+        source_path: file.to_string_lossy().to_string(),
+    }
+}
+
+/// 拆出 YAML frontmatter；没有或无法解析时返回空元数据与原文
+fn split_frontmatter(text: &str) -> (NoteMeta, &str) {
+    let Some(after_open) = text.strip_prefix("---\n") else {
+        return (NoteMeta::default(), text);
+    };
+    let Some(close) = after_open.find("\n---") else {
+        return (NoteMeta::default(), text);
+    };
+    let yaml = &after_open[..close];
+    let after_close = &after_open[close + "\n---".len()..];
+    let body = after_close.split_once('\n').map(|(_, b)| b).unwrap_or("");
+
+    let value: serde_yaml::Value = match serde_yaml::from_str(yaml) {
+        Ok(value) => value,
+        Err(e) => {
+            log::warn!("解析笔记 frontmatter 失败，忽略元数据: {e}");
+            return (NoteMeta::default(), body);
+        }
+    };
+    let text_field = |keys: &[&str]| {
+        keys.iter()
+            .filter_map(|key| value.get(*key))
+            .filter_map(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .find(|s| !s.is_empty())
+    };
+    let tags = match value.get("tags") {
+        Some(serde_yaml::Value::Sequence(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(str::to_string)
+            .collect(),
+        Some(serde_yaml::Value::String(s)) => split_tags(s),
+        _ => Vec::new(),
+    };
+
+    let meta = NoteMeta {
+        title: text_field(&["title", "name"]),
+        description: text_field(&["description", "summary"]),
+        tags: normalize_note_tags(tags),
+    };
+    (meta, body)
+}
+
+/// 识别 Notion 页面开头的属性段落（每行都是 `属性: 值`），返回 (元数据, 剩余正文)
+fn split_notion_properties(body: &str) -> Option<(NoteMeta, &str)> {
+    let (block, rest) = body.split_once("\n\n").unwrap_or((body, ""));
+    let mut meta = NoteMeta::default();
+    for line in block.lines() {
+        let (key, value) = line.split_once(": ")?;
+        let key = key.trim();
+        if key.is_empty() || key.len() > 40 || key.starts_with(['#', '-', '*', '>']) {
+            return None;
+        }
+        match key.to_lowercase().as_str() {
+            "description" | "summary" => meta.description = Some(value.trim().to_string()),
+            "tags" => meta.tags = normalize_note_tags(split_tags(value)),
+            _ => {}
+        }
+    }
+    Some((meta, rest.trim_start_matches('\n')))
+}
+
+fn split_tags(raw: &str) -> Vec<String> {
+    raw.split([',', ' ']).map(str::to_string).collect()
+}
+
+/// 去掉 `#` 前缀后按 [`normalize_tags`] 规范化
+fn normalize_note_tags(tags: Vec<String>) -> Vec<String> {
+    normalize_tags(tags.iter().map(|t| t.trim().trim_start_matches('#')))
+}
+
+/// 正文第一个文字段落（跳过标题；列表、代码块、引用等结构化段落不作为描述）
+fn first_paragraph(content: &str) -> Option<String> {
+    let paragraph = content
+        .split("\n\n")
+        .map(str::trim)
+        .find(|p| !p.is_empty() && !p.starts_with('#'))?;
+    if is_structured(paragraph) {
+        return None;
+    }
+
+    let text = paragraph.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= MAX_DESCRIPTION_CHARS {
+        return Some(text);
+    }
+    let truncated: String = text.chars().take(MAX_DESCRIPTION_CHARS).collect();
+    Some(format!("{}…", truncated.trim_end()))
+}
+
+fn is_structured(paragraph: &str) -> bool {
+    const PREFIXES: [&str; 7] = ["```", "- ", "* ", "> ", "|", "<", "!["];
+    let numbered = paragraph
+        .split_once(". ")
+        .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    numbered || PREFIXES.iter().any(|prefix| paragraph.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn obsidian_note_uses_frontmatter_and_flattens_wiki_links() {
+        let root = Path::new("/vault");
+        let raw = "---\ntitle: Code Reviewer\ntags: [review, \"#rust\"]\n---\n# Code Reviewer\n\nReview diffs like [[Senior Engineer|a senior engineer]].\n\nSee [[Checklists#Rust]].\n";
+        let note = parse_note(
+            root,
+            Path::new("/vault/Agents/code-reviewer.md"),
+            raw,
+            false,
+        );
+
+        assert_eq!(note.id, "code-reviewer");
+        assert_eq!(note.name, "Code Reviewer");
+        assert_eq!(note.tags, vec!["review", "rust"]);
+        assert_eq!(note.folder.as_deref(), Some("Agents"));
+        assert_eq!(
+            note.content,
+            "Review diffs like a senior engineer.\n\nSee Checklists.\n"
+        );
+        assert_eq!(
+            note.description.as_deref(),
+            Some("Review diffs like a senior engineer.")
+        );
+    }
+
+    #[test]
+    fn notion_export_strips_page_id_and_properties() {
+        let root = Path::new("/export");
+        let raw = "# Release Notes Writer\n\nTags: writing, docs\nDescription: Drafts release notes\n\nYou write release notes.\n";
+        let note = parse_note(
+            root,
+            Path::new("/export/Release Notes Writer 0123456789abcdef0123456789abcdef.md"),
+            raw,
+            false,
+        );
+
+        assert_eq!(note.id, "release-notes-writer");
+        assert_eq!(note.name, "Release Notes Writer");
+        assert_eq!(note.description.as_deref(), Some("Drafts release notes"));
+        assert_eq!(note.tags, vec!["writing", "docs"]);
+        assert_eq!(note.folder, None);
+        assert_eq!(note.content, "You write release notes.\n");
+    }
+
+    #[test]
+    fn plain_note_falls_back_to_file_name_and_keeps_unrelated_heading() {
+        let root = Path::new("/notes");
+        let raw = "---\nname: Terse\n---\n# Style\n\n- be brief\n";
+        let note = parse_note(root, Path::new("/notes/terse.md"), raw, false);
+        assert_eq!(note.name, "Terse");
+        assert_eq!(note.content, "# Style\n\n- be brief\n");
+        assert_eq!(note.description, None);
+
+        let note = parse_note(
+            root,
+            Path::new("/notes/My Prompt.md"),
+            "Just text.\n",
+            false,
+        );
+        assert_eq!(note.id, "my-prompt");
+        assert_eq!(note.name, "My Prompt");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn directory_symlinks_are_not_followed() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("nested");
+        std::fs::create_dir(&nested).unwrap();
+        std::fs::write(nested.join("rules.md"), "Rules\n").unwrap();
+        std::os::unix::fs::symlink(dir.path(), nested.join("loop")).unwrap();
+        std::os::unix::fs::symlink(nested.join("rules.md"), dir.path().join("alias.md")).unwrap();
+
+        let files = collect_markdown_files(dir.path()).unwrap();
+        assert_eq!(
+            files,
+            vec![dir.path().join("alias.md"), nested.join("rules.md")]
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use indexmap::IndexMap;
+use serde_json::Value;
 use std::collections::HashMap;
 
-use crate::app_config::{AppType, McpServer};
+use crate::app_config::{AppType, McpServer, MCP_ORIGIN_GEMINI_EXTENSION};
 use crate::error::AppError;
+use crate::external_edits::{self, ManagedKind, ManagedUnit};
 use crate::mcp;
+use crate::services::sync_guard::{best_effort, sync_or_rollback};
 use crate::store::AppState;
 
 /// MCP 相关业务逻辑（v3.7.0 统一结构）
@@ -18,33 +21,36 @@ impl McpService {
     /// 添加或更新 MCP 服务器
     pub fn upsert_server(state: &AppState, server: McpServer) -> Result<(), AppError> {
         // 读取旧状态：用于处理“编辑时取消勾选某个应用”的场景（需要从对应 live 配置中移除）
-        let prev_apps = state
-            .db
-            .get_all_mcp_servers()?
-            .get(&server.id)
-            .map(|s| s.apps.clone())
-            .unwrap_or_default();
+        let prev = state.db.get_all_mcp_servers()?.shift_remove(&server.id);
+        if let Some(read_only) = prev.iter().chain([&server]).find(|s| s.is_read_only()) {
+            return Err(read_only_error(read_only));
+        }
+        let prev_apps = prev.as_ref().map(|s| s.apps.clone()).unwrap_or_default();
 
         state.db.save_mcp_server(&server)?;
 
-        // 处理禁用：若旧版本启用但新版本取消，则需要从该应用的 live 配置移除
-        if prev_apps.claude && !server.apps.claude {
-            Self::remove_server_from_app(state, &server.id, &AppType::Claude)?;
-        }
-        if prev_apps.codex && !server.apps.codex {
-            Self::remove_server_from_app(state, &server.id, &AppType::Codex)?;
-        }
-        if prev_apps.gemini && !server.apps.gemini {
-            Self::remove_server_from_app(state, &server.id, &AppType::Gemini)?;
-        }
-        if prev_apps.opencode && !server.apps.opencode {
-            Self::remove_server_from_app(state, &server.id, &AppType::OpenCode)?;
-        }
-
-        // 同步到各个启用的应用
-        Self::sync_server_to_apps(state, &server)?;
+        sync_or_rollback(
+            "保存 MCP 服务器",
+            || {
+                // 处理禁用：若旧版本启用但新版本取消，则需要从该应用的 live 配置移除
+                if prev_apps.claude && !server.apps.claude {
+                    Self::remove_server_from_app(state, &server.id, &AppType::Claude)?;
+                }
+                if prev_apps.codex && !server.apps.codex {
+                    Self::remove_server_from_app(state, &server.id, &AppType::Codex)?;
+                }
+                if prev_apps.gemini && !server.apps.gemini {
+                    Self::remove_server_from_app(state, &server.id, &AppType::Gemini)?;
+                }
+                if prev_apps.opencode && !server.apps.opencode {
+                    Self::remove_server_from_app(state, &server.id, &AppType::OpenCode)?;
+                }
 
-        Ok(())
+                // 同步到各个启用的应用
+                Self::sync_server_to_apps(state, &server)
+            },
+            || Self::rollback_save(state, &server, prev.as_ref()),
+        )
     }
 
     /// 删除 MCP 服务器
@@ -52,6 +58,9 @@ impl McpService {
         let server = state.db.get_all_mcp_servers()?.shift_remove(id);
 
         if let Some(server) = server {
+            if server.is_read_only() {
+                return Err(read_only_error(&server));
+            }
             state.db.delete_mcp_server(id)?;
 
             // 从所有应用的 live 配置中移除
@@ -71,21 +80,58 @@ impl McpService {
     ) -> Result<(), AppError> {
         let mut servers = state.db.get_all_mcp_servers()?;
 
-        if let Some(server) = servers.get_mut(server_id) {
+        if let Some(prev) = servers.shift_remove(server_id) {
+            if prev.is_read_only() {
+                return Err(read_only_error(&prev));
+            }
+            let mut server = prev.clone();
             server.apps.set_enabled_for(&app, enabled);
-            state.db.save_mcp_server(server)?;
+            state.db.save_mcp_server(&server)?;
 
             // 同步到对应应用
-            if enabled {
-                Self::sync_server_to_app(state, server, &app)?;
-            } else {
-                Self::remove_server_from_app(state, server_id, &app)?;
-            }
+            sync_or_rollback(
+                "切换 MCP 服务器",
+                || {
+                    if enabled {
+                        Self::sync_server_to_app(state, &server, &app)
+                    } else {
+                        Self::remove_server_from_app(state, server_id, &app)
+                    }
+                },
+                || Self::rollback_save(state, &server, Some(&prev)),
+            )?;
         }
 
         Ok(())
     }
 
+    /// 文件同步失败时的补偿：恢复数据库中的旧记录（新建时删除），并尽力恢复各应用的 live 配置
+    fn rollback_save(
+        state: &AppState,
+        attempted: &McpServer,
+        prev: Option<&McpServer>,
+    ) -> Result<(), AppError> {
+        match prev {
+            Some(prev) => state.db.save_mcp_server(prev)?,
+            None => state.db.delete_mcp_server(&attempted.id)?,
+        }
+
+        for app in AppType::all() {
+            match prev.filter(|p| p.apps.is_enabled_for(&app)) {
+                Some(prev) => best_effort(
+                    "恢复 MCP 配置",
+                    Self::sync_server_to_app_no_config(prev, &app),
+                ),
+                None if attempted.apps.is_enabled_for(&app) => best_effort(
+                    "移除 MCP 配置",
+                    Self::remove_server_from_app(state, &attempted.id, &app),
+                ),
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
     /// 将 MCP 服务器同步到所有启用的应用
     fn sync_server_to_apps(_state: &AppState, server: &McpServer) -> Result<(), AppError> {
         for app in server.apps.enabled_apps() {
@@ -105,6 +151,20 @@ impl McpService {
     }
 
     fn sync_server_to_app_no_config(server: &McpServer, app: &AppType) -> Result<(), AppError> {
+        if server.is_read_only() {
+            // 外部来源的条目由其来源直接加载，不写入 live 配置
+            return Ok(());
+        }
+        if crate::settings::is_app_sync_paused(app) {
+            log::debug!("{} 同步已暂停，跳过 MCP 写入", app.as_str());
+            return Ok(());
+        }
+        Self::write_live_entry(app, &server.id, Some(&server.server), || {
+            Self::write_server_to_app(server, app)
+        })
+    }
+
+    fn write_server_to_app(server: &McpServer, app: &AppType) -> Result<(), AppError> {
         match app {
             AppType::Claude => {
                 mcp::sync_single_server_to_claude(&Default::default(), &server.id, &server.server)?;
@@ -146,6 +206,14 @@ impl McpService {
     }
 
     fn remove_server_from_app(_state: &AppState, id: &str, app: &AppType) -> Result<(), AppError> {
+        if crate::settings::is_app_sync_paused(app) {
+            log::debug!("{} 同步已暂停，跳过 MCP 移除", app.as_str());
+            return Ok(());
+        }
+        Self::write_live_entry(app, id, None, || Self::remove_live_entry(id, app))
+    }
+
+    fn remove_live_entry(id: &str, app: &AppType) -> Result<(), AppError> {
         match app {
             AppType::Claude => mcp::remove_server_from_claude(id)?,
             AppType::Codex => mcp::remove_server_from_codex(id)?,
@@ -161,6 +229,34 @@ impl McpService {
         Ok(())
     }
 
+    /// 按外部修改策略写入 live 配置中的单个条目（`next` 为 None 表示移除）
+    ///
+    /// 条目在 cc-switch 之外被改过时由 [`external_edits::before_write`] 决定是否继续，写入后记录新内容。
+    fn write_live_entry(
+        app: &AppType,
+        id: &str,
+        next: Option<&Value>,
+        write: impl FnOnce() -> Result<(), AppError>,
+    ) -> Result<(), AppError> {
+        let Some(path) = mcp::live_config_path(app) else {
+            return write();
+        };
+        let unit = ManagedUnit {
+            app,
+            kind: ManagedKind::Mcp,
+            id,
+            path: &path,
+        };
+        let current = mcp::read_live_server(app, id)?;
+        let next = next.map(Value::to_string);
+        if !external_edits::before_write(&unit, current.as_deref(), next.as_deref())? {
+            return Ok(());
+        }
+        write()?;
+        external_edits::after_write(&unit, mcp::read_live_server(app, id)?.as_deref());
+        Ok(())
+    }
+
     /// 手动同步所有启用的 MCP 服务器到对应的应用
     pub fn sync_all_enabled(state: &AppState) -> Result<(), AppError> {
         let servers = Self::get_all_servers(state)?;
@@ -172,6 +268,26 @@ impl McpService {
         Ok(())
     }
 
+    /// 将启用的 MCP 服务器写入指定应用（用于恢复同步）
+    pub fn sync_enabled_to_app(state: &AppState, app: &AppType) -> Result<(), AppError> {
+        for server in Self::get_all_servers(state)?.values() {
+            if server.apps.is_enabled_for(app) {
+                Self::sync_server_to_app_no_config(server, app)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 从指定应用的 live 配置中移除所有启用的 MCP 服务器（数据库中的启用状态保持不变）
+    pub fn remove_enabled_from_app(state: &AppState, app: &AppType) -> Result<(), AppError> {
+        for server in Self::get_all_servers(state)?.values() {
+            if server.apps.is_enabled_for(app) && !server.is_read_only() {
+                Self::remove_server_from_app(state, &server.id, app)?;
+            }
+        }
+        Ok(())
+    }
+
     // ========================================================================
     // 兼容层：支持旧的 v3.6.x 命令（已废弃，将在 v4.0 移除）
     // ========================================================================
@@ -334,6 +450,47 @@ impl McpService {
         Ok(new_count)
     }
 
+    /// 扫描 Gemini CLI 扩展自带的 MCP 服务器并刷新对应的只读条目，返回新增数量
+    ///
+    /// 已卸载扩展留下的条目一并删除；这些条目从不写入 live 配置，刷新只改数据库。
+    /// 与自建服务器 id 冲突的扩展服务器跳过，自建服务器保持不变。
+    pub fn import_from_gemini_extensions(state: &AppState) -> Result<usize, AppError> {
+        let found = mcp::scan_gemini_extensions()?;
+        let existing = state.db.get_all_mcp_servers()?;
+
+        let mut new_count = 0;
+        for server in &found {
+            match existing.get(&server.id) {
+                Some(prev) if !prev.is_read_only() => {
+                    log::warn!(
+                        "跳过 Gemini 扩展的 MCP 服务器 '{}'：已存在同 id 的服务器",
+                        server.id
+                    );
+                    continue;
+                }
+                Some(prev) if prev.server == server.server && prev.origin == server.origin => {
+                    continue;
+                }
+                Some(_) => {}
+                None => new_count += 1,
+            }
+            state.db.save_mcp_server(server)?;
+        }
+
+        for (id, server) in &existing {
+            let from_extension = server
+                .origin
+                .as_ref()
+                .is_some_and(|origin| origin.kind == MCP_ORIGIN_GEMINI_EXTENSION);
+            if from_extension && !found.iter().any(|f| &f.id == id) {
+                state.db.delete_mcp_server(id)?;
+                log::info!("Gemini 扩展已不再提供 MCP 服务器 '{id}'，已移除");
+            }
+        }
+
+        Ok(new_count)
+    }
+
     /// 从 OpenCode 导入 MCP（v3.9.2+ 新增）
     pub fn import_from_opencode(state: &AppState) -> Result<usize, AppError> {
         // 创建临时 MultiAppConfig 用于导入
@@ -372,3 +529,23 @@ impl McpService {
         Ok(new_count)
     }
 }
+
+/// 只读条目（外部来源）拒绝修改时的错误
+pub(crate) fn read_only_error(server: &McpServer) -> AppError {
+    let source = server
+        .origin
+        .as_ref()
+        .map(|origin| origin.name.as_str())
+        .unwrap_or_default();
+    AppError::localized(
+        "mcp.read_only",
+        format!(
+            "MCP 服务器 {} 由 Gemini 扩展 {source} 提供，只读；请在 Gemini CLI 中管理该扩展",
+            server.id
+        ),
+        format!(
+            "MCP server {} is provided by the Gemini extension {source} and is read-only; manage the extension in Gemini CLI",
+            server.id
+        ),
+    )
+}
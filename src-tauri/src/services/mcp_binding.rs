@@ -0,0 +1,218 @@
+//! MCP 服务器与供应商凭据的绑定
+//!
+//! 部分 MCP 服务器需要与某个供应商相同的 API Key。绑定后，服务器 `env` 中的指定变量
+//! 始终取自供应商配置：切换供应商或修改供应商配置时自动改写服务器 env 并同步到各应用，
+//! 避免供应商与依赖它的 MCP 服务器之间的密钥不一致。
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::McpService;
+use crate::store::AppState;
+
+/// MCP 服务器的供应商绑定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpProviderBinding {
+    pub server_id: String,
+    /// 供应商所属应用
+    pub app_type: String,
+    /// 绑定的供应商；为空时跟随该应用的当前供应商
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    /// 服务器 env 变量名 -> 供应商配置中的字段路径（以 `.` 分隔，如 `env.ANTHROPIC_AUTH_TOKEN`）
+    pub env_map: BTreeMap<String, String>,
+}
+
+/// MCP 供应商绑定服务
+pub struct McpBindingService;
+
+impl McpBindingService {
+    /// 获取所有绑定
+    pub fn list(state: &AppState) -> Result<Vec<McpProviderBinding>, AppError> {
+        state.db.get_mcp_provider_bindings()
+    }
+
+    /// 新增或替换 MCP 服务器的绑定，并立即按绑定改写服务器 env
+    pub fn set(state: &AppState, binding: McpProviderBinding) -> Result<(), AppError> {
+        let app = AppType::from_str(&binding.app_type)?;
+        let Some(server) = state
+            .db
+            .get_all_mcp_servers()?
+            .shift_remove(&binding.server_id)
+        else {
+            return Err(AppError::localized(
+                "mcp.server_not_found",
+                format!("MCP 服务器不存在: {}", binding.server_id),
+                format!("MCP server not found: {}", binding.server_id),
+            ));
+        };
+        if server.is_read_only() {
+            return Err(crate::services::mcp::read_only_error(&server));
+        }
+        if binding.env_map.is_empty() {
+            return Err(AppError::localized(
+                "mcp.binding_empty",
+                "绑定至少需要一个环境变量映射",
+                "A binding needs at least one env variable mapping",
+            ));
+        }
+        if let Some(provider_id) = &binding.provider_id {
+            if state
+                .db
+                .get_provider_by_id(provider_id, app.as_str())?
+                .is_none()
+            {
+                return Err(AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {provider_id}"),
+                    format!("Provider not found: {provider_id}"),
+                ));
+            }
+        }
+
+        state.db.save_mcp_provider_binding(&binding)?;
+        Self::apply(state, &binding)?;
+        Ok(())
+    }
+
+    /// 删除绑定（不改动服务器当前的 env）
+    pub fn remove(state: &AppState, server_id: &str) -> Result<bool, AppError> {
+        state.db.delete_mcp_provider_binding(server_id)
+    }
+
+    /// 供应商切换或配置变化后，刷新依赖它的 MCP 服务器
+    ///
+    /// 固定绑定到 `provider_id` 的服务器，以及跟随该应用当前供应商且当前供应商正是
+    /// `provider_id` 的服务器都会被刷新。返回 env 实际发生变化的服务器 id。
+    pub fn refresh_for_provider(
+        state: &AppState,
+        app: &AppType,
+        provider_id: &str,
+    ) -> Result<Vec<String>, AppError> {
+        let bindings: Vec<McpProviderBinding> = Self::list(state)?
+            .into_iter()
+            .filter(|b| b.app_type == app.as_str())
+            .collect();
+        if bindings.is_empty() {
+            return Ok(Vec::new());
+        }
+        let current = crate::settings::get_effective_current_provider(&state.db, app)?;
+
+        let mut updated = Vec::new();
+        for binding in bindings {
+            let affected = match &binding.provider_id {
+                Some(pinned) => pinned == provider_id,
+                None => current.as_deref() == Some(provider_id),
+            };
+            if affected && Self::apply(state, &binding)? {
+                updated.push(binding.server_id);
+            }
+        }
+        if !updated.is_empty() {
+            log::info!(
+                "已按供应商 {provider_id} ({}) 刷新 MCP 服务器 env: {updated:?}",
+                app.as_str()
+            );
+        }
+        Ok(updated)
+    }
+
+    /// 按绑定改写服务器 env 并同步到已启用的应用；env 未变化时不写入，返回是否变化
+    ///
+    /// 供应商缺失或配置中找不到对应字段时跳过该变量并记录日志，不清空已有值。
+    fn apply(state: &AppState, binding: &McpProviderBinding) -> Result<bool, AppError> {
+        let app = AppType::from_str(&binding.app_type)?;
+        let provider_id = match &binding.provider_id {
+            Some(id) => Some(id.clone()),
+            None => crate::settings::get_effective_current_provider(&state.db, &app)?,
+        };
+        let Some(provider_id) = provider_id else {
+            return Ok(false);
+        };
+        let Some(provider) = state.db.get_provider_by_id(&provider_id, app.as_str())? else {
+            log::warn!(
+                "MCP 服务器 {} 绑定的供应商 {provider_id} 不存在，跳过",
+                binding.server_id
+            );
+            return Ok(false);
+        };
+        let Some(mut server) = state
+            .db
+            .get_all_mcp_servers()?
+            .shift_remove(&binding.server_id)
+        else {
+            return Ok(false);
+        };
+
+        let mut changed = false;
+        for (var, path) in &binding.env_map {
+            let Some(value) = lookup_path(&provider.settings_config, path) else {
+                log::warn!(
+                    "供应商 {provider_id} 配置中不存在字段 {path}，跳过 MCP 服务器 {} 的 {var}",
+                    binding.server_id
+                );
+                continue;
+            };
+            changed |= set_env(&mut server.server, var, value);
+        }
+
+        if changed {
+            McpService::upsert_server(state, server)?;
+        }
+        Ok(changed)
+    }
+}
+
+/// 按 `.` 分隔的路径读取字符串字段
+fn lookup_path<'a>(value: &'a Value, path: &str) -> Option<&'a str> {
+    path.split('.')
+        .try_fold(value, |node, key| node.get(key))
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+}
+
+/// 写入服务器 env 变量，返回是否变化
+fn set_env(server: &mut Value, var: &str, value: &str) -> bool {
+    let Some(obj) = server.as_object_mut() else {
+        return false;
+    };
+    let env = obj
+        .entry("env")
+        .or_insert_with(|| Value::Object(Default::default()));
+    let Some(env) = env.as_object_mut() else {
+        return false;
+    };
+    if env.get(var).and_then(Value::as_str) == Some(value) {
+        return false;
+    }
+    env.insert(var.to_string(), Value::String(value.to_string()));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn copies_provider_field_into_server_env() {
+        let settings = json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-new" } });
+        let mut server = json!({ "command": "npx", "env": { "API_KEY": "sk-old", "OTHER": "1" } });
+
+        let value = lookup_path(&settings, "env.ANTHROPIC_AUTH_TOKEN").expect("field");
+        assert!(set_env(&mut server, "API_KEY", value));
+        assert!(!set_env(&mut server, "API_KEY", value));
+        assert_eq!(server["env"], json!({ "API_KEY": "sk-new", "OTHER": "1" }));
+
+        assert_eq!(lookup_path(&settings, "env.MISSING"), None);
+        let mut bare = json!({ "command": "npx" });
+        assert!(set_env(&mut bare, "API_KEY", "sk"));
+        assert_eq!(bare["env"]["API_KEY"], "sk");
+    }
+}
@@ -0,0 +1,244 @@
+//! 导出 MCP 服务器到 devcontainer / CI 环境
+//!
+//! 把选中的 MCP 服务器生成为可以直接放进仓库的片段：项目级 `.mcp.json`、
+//! `devcontainer.json` 与 `docker-compose.yml` 的相关部分。
+//! 密钥不会写入片段：`env` 中的值与 HTTP 请求头替换为 `${VAR}` 引用，
+//! 由 devcontainer 从宿主机环境变量（`${localEnv:VAR}`）或 CI 的环境变量注入。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::app_config::McpServer;
+use crate::error::AppError;
+use crate::services::McpService;
+use crate::store::AppState;
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpDevcontainerExport {
+    /// 项目级 `.mcp.json`
+    pub mcp_json: String,
+    /// `devcontainer.json` 片段（features 与 remoteEnv）
+    pub devcontainer_json: String,
+    /// `docker-compose.yml` 片段（服务的 environment）
+    pub docker_compose: String,
+    /// 需要在宿主机或 CI 中提供的环境变量
+    pub env_vars: Vec<String>,
+}
+
+/// 启动命令 -> 容器中需要的 devcontainer feature
+const COMMAND_FEATURES: &[(&[&str], &str)] = &[
+    (
+        &["npx", "node", "npm", "pnpm", "bunx", "yarn"],
+        "ghcr.io/devcontainers/features/node:1",
+    ),
+    (
+        &["uvx", "uv", "python", "python3", "pip", "pipx"],
+        "ghcr.io/devcontainers/features/python:1",
+    ),
+    (
+        &["docker"],
+        "ghcr.io/devcontainers/features/docker-in-docker:2",
+    ),
+];
+
+impl McpService {
+    /// 将选中的 MCP 服务器导出为 devcontainer / docker-compose 片段
+    pub fn export_as_devcontainer(
+        state: &AppState,
+        ids: &[String],
+    ) -> Result<McpDevcontainerExport, AppError> {
+        if ids.is_empty() {
+            return Err(AppError::localized(
+                "mcp.export_empty",
+                "请至少选择一个 MCP 服务器",
+                "Select at least one MCP server to export",
+            ));
+        }
+        let mut all = state.db.get_all_mcp_servers()?;
+        let servers = ids
+            .iter()
+            .map(|id| {
+                all.shift_remove(id).ok_or_else(|| {
+                    AppError::localized(
+                        "mcp.server_not_found",
+                        format!("MCP 服务器不存在: {id}"),
+                        format!("MCP server not found: {id}"),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        build_export(&servers)
+    }
+}
+
+fn build_export(servers: &[McpServer]) -> Result<McpDevcontainerExport, AppError> {
+    let mut env_vars = BTreeSet::new();
+    let mut features = BTreeSet::new();
+    let mut mcp_servers = Map::new();
+
+    for server in servers {
+        let mut spec = server.server.clone();
+        externalize_secrets(&server.id, &mut spec, &mut env_vars);
+        if let Some(command) = spec.get("command").and_then(Value::as_str) {
+            let program = command.rsplit(['/', '\\']).next().unwrap_or(command);
+            let program = program.strip_suffix(".exe").unwrap_or(program);
+            features.extend(
+                COMMAND_FEATURES
+                    .iter()
+                    .filter(|(commands, _)| commands.contains(&program))
+                    .map(|(_, feature)| *feature),
+            );
+        }
+        mcp_servers.insert(server.id.clone(), spec);
+    }
+
+    let mcp_json = json!({ "mcpServers": mcp_servers });
+    let devcontainer = json!({
+        "features": features
+            .iter()
+            .map(|feature| (feature.to_string(), json!({})))
+            .collect::<Map<_, _>>(),
+        "remoteEnv": env_vars
+            .iter()
+            .map(|name| (name.clone(), json!(format!("${{localEnv:{name}}}"))))
+            .collect::<Map<_, _>>(),
+    });
+    let compose = json!({
+        "services": {
+            "devcontainer": {
+                "environment": env_vars
+                    .iter()
+                    .map(|name| (name.clone(), format!("${{{name}}}")))
+                    .collect::<BTreeMap<_, _>>(),
+            }
+        }
+    });
+
+    Ok(McpDevcontainerExport {
+        mcp_json: to_pretty_json(&mcp_json)?,
+        devcontainer_json: to_pretty_json(&devcontainer)?,
+        docker_compose: serde_yaml::to_string(&compose)
+            .map_err(|e| AppError::Message(format!("生成 docker-compose 片段失败: {e}")))?,
+        env_vars: env_vars.into_iter().collect(),
+    })
+}
+
+/// 把 `env` 的值与 `headers` 替换为 `${VAR}` 引用，并收集引用的变量名
+///
+/// 已经是 `${...}` 引用的值保持不变；`Bearer` 令牌保留前缀。
+fn externalize_secrets(server_id: &str, spec: &mut Value, env_vars: &mut BTreeSet<String>) {
+    if let Some(env) = spec.get_mut("env").and_then(Value::as_object_mut) {
+        for (name, value) in env.iter_mut() {
+            if value.as_str().is_some_and(|v| v.contains("${")) {
+                continue;
+            }
+            *value = json!(format!("${{{name}}}"));
+            env_vars.insert(name.clone());
+        }
+    }
+    if let Some(headers) = spec.get_mut("headers").and_then(Value::as_object_mut) {
+        for (header, value) in headers.iter_mut() {
+            let Some(current) = value.as_str().filter(|v| !v.contains("${")) else {
+                continue;
+            };
+            let name = env_var_name(&format!("{server_id}_{header}"));
+            let prefix = if current.starts_with("Bearer ") {
+                "Bearer "
+            } else {
+                ""
+            };
+            *value = json!(format!("{prefix}${{{name}}}"));
+            env_vars.insert(name);
+        }
+    }
+}
+
+/// 转换为大写、只含字母数字与下划线的环境变量名
+fn env_var_name(raw: &str) -> String {
+    raw.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn to_pretty_json(value: &Value) -> Result<String, AppError> {
+    serde_json::to_string_pretty(value).map_err(|source| AppError::JsonSerialize { source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str, spec: Value) -> McpServer {
+        McpServer {
+            id: id.to_string(),
+            name: id.to_string(),
+            server: spec,
+            apps: Default::default(),
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+            origin: None,
+        }
+    }
+
+    #[test]
+    fn export_replaces_secrets_with_env_references() {
+        let export = build_export(&[
+            server(
+                "github",
+                json!({
+                    "command": "npx",
+                    "args": ["-y", "@modelcontextprotocol/server-github"],
+                    "env": { "GITHUB_TOKEN": "ghp_secret", "LOG_LEVEL": "${LOG_LEVEL}" }
+                }),
+            ),
+            server(
+                "remote-docs",
+                json!({
+                    "type": "http",
+                    "url": "https://mcp.example.com",
+                    "headers": { "Authorization": "Bearer sk-secret" }
+                }),
+            ),
+        ])
+        .unwrap();
+
+        assert!(!export.mcp_json.contains("secret"));
+        let mcp: Value = serde_json::from_str(&export.mcp_json).unwrap();
+        assert_eq!(
+            mcp["mcpServers"]["github"]["env"]["GITHUB_TOKEN"],
+            "${GITHUB_TOKEN}"
+        );
+        assert_eq!(
+            mcp["mcpServers"]["remote-docs"]["headers"]["Authorization"],
+            "Bearer ${REMOTE_DOCS_AUTHORIZATION}"
+        );
+        assert_eq!(
+            export.env_vars,
+            vec!["GITHUB_TOKEN", "REMOTE_DOCS_AUTHORIZATION"]
+        );
+
+        let devcontainer: Value = serde_json::from_str(&export.devcontainer_json).unwrap();
+        assert!(devcontainer["features"]
+            .get("ghcr.io/devcontainers/features/node:1")
+            .is_some());
+        assert_eq!(
+            devcontainer["remoteEnv"]["GITHUB_TOKEN"],
+            "${localEnv:GITHUB_TOKEN}"
+        );
+        assert!(export
+            .docker_compose
+            .contains("GITHUB_TOKEN: ${GITHUB_TOKEN}"));
+    }
+}
@@ -1,30 +1,88 @@
+pub mod agent_watcher;
 pub mod agents;
+pub mod agents_md;
+pub mod api_access;
+pub mod bulk_replace;
 pub mod config;
+pub mod config_report;
+pub mod context_budget;
+pub mod enablement;
 pub mod env_checker;
 pub mod env_manager;
+pub mod failover_bundle;
+pub mod feed;
+pub mod id_generator;
+pub mod jobs;
+pub mod legacy_config;
+pub mod markdown_import;
 pub mod mcp;
+pub mod mcp_binding;
+pub mod mcp_export;
 pub mod omo;
 pub mod prompt;
+pub mod prompt_backup;
+pub mod prompt_bundle;
+pub mod prompt_drift;
+pub mod prompt_include;
+pub mod prompt_project;
+pub mod prompt_schedule;
+pub mod prompt_validation;
 pub mod provider;
 pub mod proxy;
+pub mod remote_cache;
+pub mod schedule;
+pub mod share_link;
 pub mod skill;
+pub mod skill_integrity;
 pub mod speedtest;
 pub mod stream_check;
+pub mod sync_guard;
+pub mod sync_pause;
+pub mod sync_targets;
 pub mod usage_stats;
 pub mod webdav;
 pub mod webdav_auto_sync;
 pub mod webdav_sync;
 
 pub use agents::AgentsService;
+pub use agents_md::AgentsMdService;
+pub use api_access::{ApiAccessService, ApiScope, ApiToken, IssuedApiToken};
+pub use bulk_replace::{BulkReplaceKind, BulkReplaceResult, BulkReplaceService};
 pub use config::ConfigService;
+pub use context_budget::{ComposedContext, ContextBudget, ContextBudgetService};
+pub use enablement::{EnablementApplyResult, EnablementService};
+pub use feed::FeedService;
+pub use jobs::{JobContext, JobService};
+pub use legacy_config::{
+    LegacyChangeAction, LegacyConfigDiff, LegacyConfigService, LegacyConfigStatus, LegacyEntryKind,
+    LegacyMergeResult, LegacyMergeSelection,
+};
+pub use markdown_import::{
+    MarkdownImportResult, MarkdownImportService, MarkdownImportTarget, MarkdownNote,
+};
 pub use mcp::McpService;
+pub use mcp_binding::{McpBindingService, McpProviderBinding};
+pub use mcp_export::McpDevcontainerExport;
 pub use omo::OmoService;
 pub use prompt::PromptService;
-pub use provider::{ProviderService, ProviderSortUpdate, SwitchResult};
+pub use prompt_backup::{PromptBackupService, PromptFileBackup};
+pub use prompt_bundle::PromptBundleService;
+pub use prompt_drift::{DriftResolution, PromptDrift, PromptDriftService};
+pub use prompt_project::{PromptProject, PromptProjectService};
+pub use prompt_schedule::{PromptSchedule, PromptScheduleService};
+pub use provider::{
+    ProviderAuditOrigin, ProviderProfile, ProviderService, ProviderSortUpdate, SwitchResult,
+};
 pub use proxy::ProxyService;
+pub use remote_cache::RemoteCacheService;
+pub use schedule::Schedule;
+pub use share_link::{ShareKind, ShareLink, ShareLinkService};
 #[allow(unused_imports)]
 pub use skill::{DiscoverableSkill, Skill, SkillRepo, SkillService};
+pub use skill_integrity::{SkillIntegrityIssue, SkillRepairResult};
 pub use speedtest::{EndpointLatency, SpeedtestService};
+pub use sync_pause::SyncPauseService;
+pub use sync_targets::{SyncTarget, SyncTargetKind, SyncTargetMode, SyncTargetsService};
 #[allow(unused_imports)]
 pub use usage_stats::{
     DailyStats, LogFilters, ModelStats, PaginatedLogs, ProviderLimitStatus, ProviderStats,
@@ -2,6 +2,7 @@ use crate::config::write_json_file;
 use crate::database::OmoGlobalConfig;
 use crate::error::AppError;
 use crate::opencode_config::get_opencode_dir;
+use crate::services::id_generator::local_time_label;
 use crate::store::AppState;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -323,11 +324,7 @@ impl OmoService {
         state.db.save_omo_global_config(v.config_key, &global)?;
 
         let provider_id = format!("{}{}", v.provider_prefix, uuid::Uuid::new_v4());
-        let name = format!(
-            "{} {}",
-            v.import_label,
-            chrono::Local::now().format("%Y-%m-%d %H:%M")
-        );
+        let name = format!("{} {}", v.import_label, local_time_label());
         let settings_config =
             serde_json::to_value(&settings).unwrap_or_else(|_| serde_json::json!({}));
 
@@ -344,6 +341,8 @@ impl OmoService {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            archived: false,
+            tags: Vec::new(),
         };
 
         state.db.save_provider("opencode", &provider)?;
@@ -1,10 +1,27 @@
+use std::path::Path;
+
 use indexmap::IndexMap;
 
+use crate::agents::render_template;
 use crate::app_config::AppType;
-use crate::config::write_text_file;
+use crate::config::{delete_file, write_text_file};
+use crate::database::Database;
 use crate::error::AppError;
-use crate::prompt::{Prompt, PromptApps};
-use crate::prompt_files::prompt_file_path;
+use crate::external_edits::{self, ManagedKind, ManagedUnit};
+use crate::managed_banner::banner_for;
+use crate::prompt::{
+    Prompt, PromptApps, PromptFilePreview, PromptIssue, PromptRevision, PromptRevisionReason,
+    TrashedPrompt,
+};
+use crate::prompt_files::{
+    normalize_imported_prompt, opencode_prompt_path, prompt_file_path, prompt_region_body,
+    replace_prompt_region, OpenCodePromptTarget,
+};
+use crate::services::id_generator::{local_time_label, IdGenerator, IdKind};
+use crate::services::prompt_include::{dependent_ids, expand_includes, included_ids};
+use crate::services::prompt_validation::errors_only;
+use crate::services::sync_guard::{best_effort, sync_or_rollback};
+use crate::services::{AgentsService, PromptBackupService, PromptProjectService};
 use crate::store::AppState;
 
 /// 安全地获取当前 Unix 时间戳
@@ -15,31 +32,206 @@ fn get_unix_timestamp() -> Result<i64, AppError> {
         .map_err(|e| AppError::Message(format!("Failed to get system time: {e}")))
 }
 
+/// 拥有提示词文件的 app
+pub(crate) const PROMPT_APPS: [AppType; 5] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+    AppType::OpenClaw,
+];
+
 /// 将 AppType 映射到数据库列名
 fn app_to_col(app: &AppType) -> &'static str {
     match app {
         AppType::Claude => "claude_enabled",
         AppType::Codex => "codex_enabled",
         AppType::Gemini => "gemini_enabled",
-        AppType::OpenCode | AppType::OpenClaw => "opencode_enabled",
+        AppType::OpenCode => "opencode_enabled",
+        AppType::OpenClaw => "openclaw_enabled",
     }
 }
 
 /// 读取 prompt 的 app 启用状态
 fn app_enabled(apps: &PromptApps, app: &AppType) -> bool {
-    match app {
-        AppType::Claude => apps.claude,
-        AppType::Codex => apps.codex,
-        AppType::Gemini => apps.gemini,
-        AppType::OpenCode | AppType::OpenClaw => apps.opencode,
+    apps.is_enabled_for(app)
+}
+
+/// 同一 app 启用多个提示词时，相邻两段之间的分隔
+const PROMPT_SEPARATOR: &str = "\n\n---\n\n";
+
+/// 按优先级顺序拼接提示词
+///
+/// 只有一个时原样返回；多个时去掉各段末尾空白后以 [`PROMPT_SEPARATOR`] 连接，并以换行结尾。
+fn concat_prompts(prompts: &[&Prompt]) -> String {
+    match prompts {
+        [] => String::new(),
+        [only] => only.content.clone(),
+        _ => {
+            let mut text = prompts
+                .iter()
+                .map(|p| p.content.trim_end())
+                .collect::<Vec<_>>()
+                .join(PROMPT_SEPARATOR);
+            text.push('\n');
+            text
+        }
     }
 }
 
-/// 写入 app 的提示词文件，若内容为空则清空文件
-fn sync_app_file(app: &AppType, content: Option<&str>) -> Result<(), AppError> {
+/// 把 `text` 写入 app 提示词文件的托管区域（`text` 为空时移除区域），区域外的用户内容保持不变
+///
+/// `legacy` 为可能由旧版本整文件写入的提示词内容，见 [`replace_prompt_region`]。
+/// 内容未变化时不触碰文件；同步已暂停时跳过。
+fn write_region(app: &AppType, text: &str, legacy: &[&str]) -> Result<(), AppError> {
+    if crate::settings::is_app_sync_paused(app) {
+        log::debug!("{} 同步已暂停，跳过提示词写入", app.as_str());
+        return Ok(());
+    }
     let path = prompt_file_path(app)?;
-    let text = content.unwrap_or("");
-    write_text_file(&path, text)
+    write_region_at(app, &path, text, legacy)?;
+    if *app == AppType::OpenCode
+        && crate::settings::get_opencode_prompt_target() == OpenCodePromptTarget::Instructions
+    {
+        sync_opencode_instruction(&path)?;
+    }
+    Ok(())
+}
+
+/// OpenCode 独立提示词文件有内容时登记到 `opencode.json` 的 `instructions`；
+/// 托管区域移除后文件为空，删除文件并撤销登记
+fn sync_opencode_instruction(path: &Path) -> Result<(), AppError> {
+    let has_content = std::fs::read_to_string(path).is_ok_and(|c| !c.trim().is_empty());
+    if !has_content {
+        delete_file(path)?;
+    }
+    crate::opencode_config::set_instruction(&path.to_string_lossy(), has_content)
+}
+
+/// 把 `text` 写入指定文件的托管区域，不检查同步暂停状态（项目文件也经由这里写入）
+///
+/// 区域在 cc-switch 之外被改过时按 `app` 的外部修改策略处理，见 [`crate::external_edits`]。
+pub(crate) fn write_region_at(
+    app: &AppType,
+    path: &Path,
+    text: &str,
+    legacy: &[&str],
+) -> Result<(), AppError> {
+    let original = if path.exists() {
+        std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?
+    } else {
+        String::new()
+    };
+    let unit = ManagedUnit {
+        app,
+        kind: ManagedKind::Prompt,
+        id: "region",
+        path,
+    };
+    let next = Some(text).filter(|t| !t.trim().is_empty());
+    if !external_edits::before_write(&unit, prompt_region_body(&original), next)? {
+        return Ok(());
+    }
+
+    let content = replace_prompt_region(&original, text, legacy);
+    if content != original && (path.exists() || !content.is_empty()) {
+        PromptBackupService::backup_before_first_write(app, path, &original)?;
+        write_text_file(path, &content)?;
+    }
+    external_edits::after_write(&unit, prompt_region_body(&content));
+    Ok(())
+}
+
+/// `prompts` 中对 app 启用的提示词（保持传入的优先级顺序）
+pub(crate) fn enabled_for<'a>(
+    prompts: &'a IndexMap<String, Prompt>,
+    app: &AppType,
+) -> Vec<&'a Prompt> {
+    prompts
+        .values()
+        .filter(|p| app_enabled(&p.apps, app))
+        .collect()
+}
+
+/// 渲染 app 中启用的提示词并按优先级拼接，得到写入托管区域的正文（不含横幅）
+pub(crate) fn render_enabled(
+    state: &AppState,
+    app: &AppType,
+    prompts: &IndexMap<String, Prompt>,
+) -> String {
+    render_prompts(state, app, &enabled_for(prompts, app), prompts)
+}
+
+/// 按 app 的模板变量渲染给定提示词并按传入顺序拼接（不含横幅）
+///
+/// 渲染前先展开 `{{> id}}` 引用（见 [`crate::services::prompt_include`]），`all` 为可供引用的全部提示词；
+/// 无法展开的引用原样保留。
+pub(crate) fn render_prompts(
+    state: &AppState,
+    app: &AppType,
+    prompts: &[&Prompt],
+    all: &IndexMap<String, Prompt>,
+) -> String {
+    if !prompts.iter().any(|p| p.content.contains("{{")) {
+        return concat_prompts(prompts);
+    }
+    let vars = AgentsService::template_vars(state, app);
+    let rendered: Vec<Prompt> = prompts
+        .iter()
+        .map(|p| {
+            let content = expand_includes(&p.content, all).unwrap_or_else(|e| {
+                log::warn!("展开提示词 {} 的引用失败，引用将原样写入: {e}", p.id);
+                p.content.clone()
+            });
+            Prompt {
+                content: render_template(&content, &vars),
+                ..(*p).clone()
+            }
+        })
+        .collect();
+    concat_prompts(&rendered.iter().collect::<Vec<_>>())
+}
+
+/// 按 `prompts` 中的启用状态重写 app 文件的托管区域；没有启用的提示词时移除区域
+///
+/// 正文中的模板变量（见 [`crate::agents::render_template`]）在写入时按该 app 的当前状态替换；
+/// 开启来源横幅时，区域第一行注明写入的提示词 id。
+pub(crate) fn rewrite_app_file(
+    state: &AppState,
+    app: &AppType,
+    prompts: &IndexMap<String, Prompt>,
+) -> Result<(), AppError> {
+    let legacy: Vec<&str> = prompts.values().map(|p| p.content.as_str()).collect();
+    write_region(app, &region_text(state, app, prompts), &legacy)
+}
+
+/// 写入 app 托管区域的完整文本：渲染后的正文，开启来源横幅时首行为横幅
+fn region_text(state: &AppState, app: &AppType, prompts: &IndexMap<String, Prompt>) -> String {
+    let mut text = render_enabled(state, app, prompts);
+    if !text.trim().is_empty() {
+        let ids: Vec<&str> = enabled_for(prompts, app)
+            .iter()
+            .map(|p| p.id.as_str())
+            .collect();
+        text.insert_str(0, &banner_for("prompt", &ids.join(", ")));
+    }
+    text
+}
+
+/// 按 `prompts` 的启用状态推演 app 提示词文件同步后的内容，`original` 为文件当前内容
+fn compose_file(
+    state: &AppState,
+    app: &AppType,
+    prompts: &IndexMap<String, Prompt>,
+    original: &str,
+) -> String {
+    let legacy: Vec<&str> = prompts.values().map(|p| p.content.as_str()).collect();
+    replace_prompt_region(original, &region_text(state, app, prompts), &legacy)
+}
+
+/// 移除 app 提示词文件中的托管区域（文件不存在或同步已暂停时不做任何事）
+fn clear_app_file(app: &AppType) -> Result<(), AppError> {
+    write_region(app, "", &[])
 }
 
 pub struct PromptService;
@@ -50,106 +242,469 @@ impl PromptService {
         state.db.get_prompts()
     }
 
-    /// 新增或更新提示词
+    /// 获取对 app 启用的提示词（按优先级排序）
+    pub fn get_prompts_for_app(
+        state: &AppState,
+        app: &AppType,
+    ) -> Result<IndexMap<String, Prompt>, AppError> {
+        state.db.get_prompts_for_app(app_to_col(app))
+    }
+
+    /// 获取对 app 启用的优先级最高的提示词
+    pub fn get_enabled_prompt(state: &AppState, app: &AppType) -> Result<Option<Prompt>, AppError> {
+        state.db.get_enabled_prompt(app_to_col(app))
+    }
+
+    /// 按关键词搜索提示词（name / description / content 全文匹配）
+    pub fn search_prompts(state: &AppState, query: &str) -> Result<Vec<Prompt>, AppError> {
+        state.db.search_prompts(query)
+    }
+
+    /// 新增或更新提示词，返回校验警告（见 [`Self::validate_prompt`]，有错误级别的问题时拒绝保存）
     ///
-    /// 保存后，对每个 app 检查新数据中的 enabled 标志：
-    /// - 若 enabled=true，写入对应 app 文件
-    /// - 若 enabled=false，且该 app 现在没有任何启用提示词，清空文件
-    pub fn upsert_prompt(state: &AppState, prompt: Prompt) -> Result<(), AppError> {
-        let new_apps = prompt.apps.clone();
+    /// 保存后，对每个 app：
+    /// - 该提示词保存前或保存后对其启用，或引用了它的提示词对其启用时，按所有已启用提示词重新拼接写入文件
+    /// - 该 app 没有任何启用的提示词时，移除文件中的托管区域
+    pub fn upsert_prompt(state: &AppState, prompt: Prompt) -> Result<Vec<PromptIssue>, AppError> {
+        let issues = Self::validate_prompt(state, &prompt)?;
+        let errors = errors_only(&issues);
+        if !errors.is_empty() {
+            return Err(AppError::PromptValidation(errors));
+        }
+
+        let before = state.db.get_prompts()?;
+        let was_enabled = before.get(&prompt.id).map(|p| p.apps.clone());
         state.db.save_prompt(&prompt)?;
 
         let all_prompts = state.db.get_prompts()?;
-        let apps = [
-            AppType::Claude,
-            AppType::Codex,
-            AppType::Gemini,
-            AppType::OpenCode,
-        ];
-        for app in &apps {
-            if app_enabled(&new_apps, app) {
-                sync_app_file(app, Some(&prompt.content))?;
-            } else {
-                // 检查是否还有其他启用的提示词
-                let still_enabled = all_prompts
-                    .values()
-                    .any(|p| p.id != prompt.id && app_enabled(&p.apps, app));
-                if !still_enabled {
-                    // 若刚保存的也已禁用，确认再清空
-                    let just_saved_enabled = all_prompts
-                        .get(&prompt.id)
-                        .map(|p| app_enabled(&p.apps, app))
-                        .unwrap_or(false);
-                    if !just_saved_enabled {
-                        let path = prompt_file_path(app)?;
-                        if path.exists() {
-                            let _ = write_text_file(&path, "");
-                        }
+        // 引用了该提示词的提示词正文也随之变化
+        let dependents = dependent_ids(&prompt.id, &all_prompts);
+        sync_or_rollback(
+            "保存提示词",
+            || {
+                for app in &PROMPT_APPS {
+                    let affected = app_enabled(&prompt.apps, app)
+                        || was_enabled
+                            .as_ref()
+                            .is_some_and(|apps| app_enabled(apps, app))
+                        || dependents
+                            .iter()
+                            .filter_map(|id| all_prompts.get(id))
+                            .any(|p| app_enabled(&p.apps, app));
+                    let any_enabled = all_prompts.values().any(|p| app_enabled(&p.apps, app));
+                    if affected || !any_enabled {
+                        rewrite_app_file(state, app, &all_prompts)?;
                     }
                 }
+                Ok(())
+            },
+            || Self::rollback_to(state, &before),
+        )?;
+
+        let mut projects = PromptProjectService::linked_project_ids(&state.db, &prompt.id)?;
+        for id in &dependents {
+            for project in PromptProjectService::linked_project_ids(&state.db, id)? {
+                if !projects.contains(&project) {
+                    projects.push(project);
+                }
             }
         }
-        Ok(())
+        best_effort(
+            "同步项目提示词",
+            PromptProjectService::resync_projects(state, &projects),
+        );
+        Ok(issues)
     }
 
-    /// 删除提示词
+    /// 删除提示词（移入回收站，可通过 [`Self::restore_prompt`] 恢复）
     ///
-    /// 若该提示词在某个 app 中处于启用状态，删除后清空对应 app 文件。
+    /// 若该提示词在某个 app 中处于启用状态，删除后按剩余的启用提示词重写文件（没有剩余时移除托管区域）。
+    /// 挂载了该提示词的项目文件同样重写。顺带清理超过保留期的回收站记录。
+    /// 仍被其他提示词以 `{{> id}}` 引用时拒绝删除。
     pub fn delete_prompt(state: &AppState, id: &str) -> Result<(), AppError> {
         // 先读出当前状态，以便删除后清理文件
-        let prompts = state.db.get_prompts()?;
-        let target = prompts.get(id).cloned();
+        let mut prompts = state.db.get_prompts()?;
+        let target = prompts.shift_remove(id);
+        let includers: Vec<&str> = prompts
+            .values()
+            .filter(|p| included_ids(&p.content).contains(&id))
+            .map(|p| p.id.as_str())
+            .collect();
+        if !includers.is_empty() {
+            let list = includers.join(", ");
+            return Err(AppError::localized(
+                "prompt.still_included",
+                format!("提示词 {id} 仍被以下提示词引用，请先移除引用: {list}"),
+                format!("Prompt {id} is still included by: {list}. Remove the includes first."),
+            ));
+        }
+        let projects = PromptProjectService::linked_project_ids(&state.db, id)?;
 
-        state.db.delete_prompt(id)?;
+        state.db.trash_prompt(id, get_unix_timestamp()?)?;
+        if let Err(e) = Self::purge_expired_trash(&state.db) {
+            log::warn!("清理提示词回收站失败: {e}");
+        }
+        best_effort(
+            "同步项目提示词",
+            PromptProjectService::resync_projects(state, &projects),
+        );
 
         if let Some(prompt) = target {
-            let apps = [
-                AppType::Claude,
-                AppType::Codex,
-                AppType::Gemini,
-                AppType::OpenCode,
-            ];
-            for app in &apps {
+            for app in &PROMPT_APPS {
                 if app_enabled(&prompt.apps, app) {
-                    // 被删除的是该 app 的活跃提示词，清空文件
-                    let path = prompt_file_path(app)?;
-                    if path.exists() {
-                        let _ = write_text_file(&path, "");
-                    }
+                    rewrite_app_file(state, app, &prompts)?;
                 }
             }
         }
         Ok(())
     }
 
-    /// 切换提示词对指定 app 的启用状态（互斥）
+    /// 获取提示词的历史快照（最新在前）
+    pub fn history(state: &AppState, id: &str) -> Result<Vec<PromptRevision>, AppError> {
+        state.db.get_prompt_history(id)
+    }
+
+    /// 将提示词回滚到指定历史快照，返回回滚后的提示词
+    ///
+    /// 只恢复名称、描述与正文，各 app 的启用状态与排序保持当前值。
+    /// 回滚前的内容另记一条快照，回滚本身也可以撤销。
+    pub fn rollback(state: &AppState, id: &str, version: i64) -> Result<Prompt, AppError> {
+        let revision = state.db.get_prompt_revision(id, version)?.ok_or_else(|| {
+            AppError::localized(
+                "prompt.revision_not_found",
+                format!("提示词 {id} 不存在版本 {version}"),
+                format!("Prompt {id} has no version {version}"),
+            )
+        })?;
+        let current = state.db.get_prompts()?.shift_remove(id).ok_or_else(|| {
+            AppError::localized(
+                "prompt.not_found",
+                format!("提示词不存在: {id}"),
+                format!("Prompt not found: {id}"),
+            )
+        })?;
+
+        let restored = Prompt {
+            name: revision.snapshot.name,
+            description: revision.snapshot.description,
+            content: revision.snapshot.content,
+            updated_at: Some(get_unix_timestamp()?),
+            ..current.clone()
+        };
+        state
+            .db
+            .save_prompt_revision(&current, PromptRevisionReason::Rollback)?;
+        Self::upsert_prompt(state, restored.clone())?;
+        log::info!("提示词 {id} 已回滚到版本 {version}");
+        Ok(restored)
+    }
+
+    /// 复制提示词，返回新提示词
+    ///
+    /// 副本在名称后追加 " copy"，排在现有提示词之后，且不对任何 app 启用、不挂载到项目，
+    /// 因此不会改动任何文件。
+    pub fn duplicate_prompt(state: &AppState, id: &str) -> Result<Prompt, AppError> {
+        let prompts = state.db.get_prompts()?;
+        let source = prompts.get(id).ok_or_else(|| {
+            AppError::localized(
+                "prompt.not_found",
+                format!("提示词不存在: {id}"),
+                format!("Prompt not found: {id}"),
+            )
+        })?;
+
+        let new_id = IdGenerator::new(IdKind::DuplicatePrompt)
+            .name(&source.name)
+            .generate(|candidate| Ok(prompts.contains_key(candidate)))?;
+        let now = get_unix_timestamp()?;
+        let copy = Prompt {
+            id: new_id,
+            name: format!("{} copy", source.name),
+            apps: PromptApps::default(),
+            sort_index: None,
+            created_at: Some(now),
+            updated_at: Some(now),
+            ..source.clone()
+        };
+        state.db.save_prompt(&copy)?;
+        Ok(copy)
+    }
+
+    /// 回收站中的提示词（最近删除的在前）
+    pub fn get_trash(state: &AppState) -> Result<Vec<TrashedPrompt>, AppError> {
+        state.db.get_trashed_prompts()
+    }
+
+    /// 从回收站恢复提示词：按删除前的启用状态重写 app 文件，并恢复仍登记着的项目挂载
+    ///
+    /// 恢复的提示词排在现有提示词之后；文件同步失败时重新移回回收站。
+    pub fn restore_prompt(state: &AppState, id: &str) -> Result<Prompt, AppError> {
+        let Some(trashed) = state.db.restore_trashed_prompt(id)? else {
+            return Err(AppError::localized(
+                "prompt.not_in_trash",
+                format!("回收站中没有该提示词: {id}"),
+                format!("Prompt not found in trash: {id}"),
+            ));
+        };
+
+        let prompts = state.db.get_prompts()?;
+        sync_or_rollback(
+            "恢复提示词",
+            || {
+                for app in &PROMPT_APPS {
+                    if app_enabled(&trashed.prompt.apps, app) {
+                        rewrite_app_file(state, app, &prompts)?;
+                    }
+                }
+                Ok(())
+            },
+            || {
+                state.db.trash_prompt(id, trashed.deleted_at)?;
+                let remaining = state.db.get_prompts()?;
+                for app in &PROMPT_APPS {
+                    if app_enabled(&trashed.prompt.apps, app) {
+                        best_effort("恢复提示词文件", rewrite_app_file(state, app, &remaining));
+                    }
+                }
+                Ok(())
+            },
+        )?;
+
+        let projects = PromptProjectService::linked_project_ids(&state.db, id)?;
+        best_effort(
+            "同步项目提示词",
+            PromptProjectService::resync_projects(state, &projects),
+        );
+        prompts
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::Database(format!("恢复后未找到提示词: {id}")))
+    }
+
+    /// 永久删除回收站中的记录：`id` 为 None 时清空回收站，返回删除的数量
+    pub fn purge_trash(state: &AppState, id: Option<&str>) -> Result<usize, AppError> {
+        match id {
+            Some(id) => Ok(usize::from(state.db.delete_trashed_prompt(id)?)),
+            None => state.db.purge_prompt_trash(i64::MAX),
+        }
+    }
+
+    /// 清理超过保留期（设置 `promptTrashRetentionDays`）的回收站记录，返回删除的数量
+    pub fn purge_expired_trash(db: &Database) -> Result<usize, AppError> {
+        let days = crate::settings::effective_prompt_trash_retention_days();
+        if days == 0 {
+            return Ok(0);
+        }
+        let cutoff = get_unix_timestamp()? - i64::from(days) * 86_400;
+        let purged = db.purge_prompt_trash(cutoff)?;
+        if purged > 0 {
+            log::info!("已清理 {purged} 个超过 {days} 天的已删除提示词");
+        }
+        Ok(purged)
+    }
+
+    /// 切换提示词对指定 app 的启用状态
+    ///
+    /// `exclusive` 为 true 时启用会取消同 app 的其他提示词；为 false 时与已启用的提示词并存，
+    /// 文件内容按优先级拼接所有启用的提示词。
     pub fn toggle_prompt_app(
         state: &AppState,
         id: &str,
         app: AppType,
         enabled: bool,
+        exclusive: bool,
     ) -> Result<(), AppError> {
         let col = app_to_col(&app);
-        state.db.toggle_prompt_app(id, col, enabled)?;
+        let before = state.db.get_prompts()?;
+        state.db.toggle_prompt_app(id, col, enabled, exclusive)?;
 
         // 同步文件
-        if enabled {
-            // 写入被启用提示词的内容
-            let prompts = state.db.get_prompts()?;
-            if let Some(prompt) = prompts.get(id) {
-                sync_app_file(&app, Some(&prompt.content))?;
-            }
-        } else {
-            // 检查是否还有其他启用的提示词
-            let prompts = state.db.get_prompts()?;
-            let any_enabled = prompts.values().any(|p| app_enabled(&p.apps, &app));
-            if !any_enabled {
-                let path = prompt_file_path(&app)?;
-                if path.exists() {
-                    let _ = write_text_file(&path, "");
+        let prompts = state.db.get_prompts()?;
+        sync_or_rollback(
+            "切换提示词",
+            || rewrite_app_file(state, &app, &prompts),
+            || Self::rollback_to(state, &before),
+        )
+    }
+
+    /// 按给定 id 顺序调整提示词优先级，并重写启用了多个提示词的 app 文件
+    pub fn reorder_prompts(state: &AppState, ids: &[String]) -> Result<(), AppError> {
+        let before = state.db.get_prompts()?;
+        state.db.reorder_prompts(ids)?;
+
+        let prompts = state.db.get_prompts()?;
+        sync_or_rollback(
+            "调整提示词顺序",
+            || {
+                for app in &PROMPT_APPS {
+                    if enabled_for(&prompts, app).len() > 1 {
+                        rewrite_app_file(state, app, &prompts)?;
+                    }
                 }
+                PromptProjectService::resync_all(state)
+            },
+            || Self::rollback_to(state, &before),
+        )
+    }
+
+    /// 文件同步失败时的补偿：把提示词表恢复为 `before`，并尽力按恢复后的启用状态重写各 app 文件
+    ///
+    /// 互斥启用会取消同 app 的其他提示词，因此恢复整张表而不只是目标行。
+    fn rollback_to(state: &AppState, before: &IndexMap<String, Prompt>) -> Result<(), AppError> {
+        for id in state.db.get_prompts()?.keys() {
+            if !before.contains_key(id) {
+                state.db.delete_prompt(id)?;
+            }
+        }
+        for prompt in before.values() {
+            state.db.save_prompt(prompt)?;
+        }
+
+        for app in &PROMPT_APPS {
+            best_effort("恢复提示词文件", rewrite_app_file(state, app, before));
+        }
+        Ok(())
+    }
+
+    /// 将当前启用的提示词（按优先级拼接）写入指定 app 文件（用于恢复同步）
+    pub fn sync_enabled_to_app(state: &AppState, app: &AppType) -> Result<(), AppError> {
+        if Self::get_enabled_prompt(state, app)?.is_some() {
+            rewrite_app_file(state, app, &state.db.get_prompts()?)?;
+        }
+        Ok(())
+    }
+
+    /// 重新写入正文含模板变量的提示词（供应商切换后调用），没有时不触碰文件
+    pub fn resync_templated(state: &AppState, app: &AppType) -> Result<(), AppError> {
+        if !PROMPT_APPS.contains(app) {
+            return Ok(());
+        }
+        let prompts = state.db.get_prompts()?;
+        if enabled_for(&prompts, app)
+            .iter()
+            .any(|p| p.content.contains("{{"))
+        {
+            rewrite_app_file(state, app, &prompts)?;
+        }
+        Ok(())
+    }
+
+    /// 日期变化后重新写入含模板变量的提示词与 agent，使文件中的 `{{today}}` 等于当天日期
+    ///
+    /// 由后台任务在跨过本地零点后调用；单个 app 失败只记录日志。
+    pub fn resync_templates_for_new_day(state: &AppState) {
+        for app in AppType::all() {
+            if let Err(e) = Self::resync_templated(state, &app) {
+                log::warn!("日期变化后刷新 {} 提示词模板变量失败: {e}", app.as_str());
+            }
+            if let Err(e) = AgentsService::resync_templated(state, &app) {
+                log::warn!("日期变化后刷新 {} agent 模板变量失败: {e}", app.as_str());
             }
         }
+    }
+
+    /// 预览编辑中的正文展开引用、替换模板变量后的结果
+    ///
+    /// 整个 app 文件的预览见 [`Self::preview_app_file`]。
+    pub fn render_preview(
+        state: &AppState,
+        app: &AppType,
+        content: &str,
+    ) -> Result<String, AppError> {
+        let prompts = state.db.get_prompts()?;
+        Ok(render_template(
+            &expand_includes(content, &prompts)?,
+            &AgentsService::template_vars(state, app),
+        ))
+    }
+
+    /// 推演按当前启用状态同步后 app 提示词文件的完整内容（不写入）
+    ///
+    /// `original` 为文件当前内容，托管区域外的用户内容按实际写入规则保留。
+    pub fn compose_app_file(
+        state: &AppState,
+        app: &AppType,
+        original: &str,
+    ) -> Result<String, AppError> {
+        Ok(compose_file(state, app, &state.db.get_prompts()?, original))
+    }
+
+    /// 预览同步后 app 提示词文件的完整内容（拼接、展开引用、替换模板变量、横幅均与实际写入一致）
+    ///
+    /// `enabled_ids` 为 Some 时按这组 id 模拟该 app 的启用状态（拼接顺序仍按优先级），
+    /// 用于在切换开关前查看结果；None 时使用当前启用状态。不写入任何文件。
+    pub fn preview_app_file(
+        state: &AppState,
+        app: &AppType,
+        enabled_ids: Option<&[String]>,
+    ) -> Result<PromptFilePreview, AppError> {
+        let mut prompts = state.db.get_prompts()?;
+        if let Some(ids) = enabled_ids {
+            for prompt in prompts.values_mut() {
+                prompt.apps.set_enabled_for(app, ids.contains(&prompt.id));
+            }
+        }
+        let path = prompt_file_path(app)?;
+        let original = if path.exists() {
+            std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?
+        } else {
+            String::new()
+        };
+        let content = compose_file(state, app, &prompts, &original);
+        Ok(PromptFilePreview {
+            app: app.as_str().to_string(),
+            path: path.display().to_string(),
+            rendered: render_enabled(state, app, &prompts),
+            changed: content != original,
+            content,
+        })
+    }
+
+    /// 切换 OpenCode 全局提示词的写入位置（`AGENTS.md` 托管区域 / 独立 instructions 文件）
+    ///
+    /// 先移除旧位置的托管区域（独立文件同时撤销 `instructions` 登记），再按启用状态写入新位置；
+    /// 写入失败时恢复原设置与文件。OpenCode 同步已暂停时只保存设置。
+    pub fn set_opencode_target(
+        state: &AppState,
+        target: OpenCodePromptTarget,
+    ) -> Result<(), AppError> {
+        let current = crate::settings::get_opencode_prompt_target();
+        if current == target {
+            return Ok(());
+        }
+        let app = AppType::OpenCode;
+        if crate::settings::is_app_sync_paused(&app) {
+            return crate::settings::set_opencode_prompt_target(target);
+        }
+
+        let old_path = opencode_prompt_path(current);
+        write_region_at(&app, &old_path, "", &[])?;
+        if current == OpenCodePromptTarget::Instructions {
+            sync_opencode_instruction(&old_path)?;
+        }
+        crate::settings::set_opencode_prompt_target(target)?;
+        sync_or_rollback(
+            "切换 OpenCode 提示词位置",
+            || Self::sync_enabled_to_app(state, &app),
+            || {
+                best_effort("清理 OpenCode 提示词文件", clear_app_file(&app));
+                crate::settings::set_opencode_prompt_target(current)?;
+                best_effort(
+                    "恢复 OpenCode 提示词文件",
+                    Self::sync_enabled_to_app(state, &app),
+                );
+                Ok(())
+            },
+        )?;
+        log::info!("OpenCode 提示词写入位置已切换为 {target:?}");
+        Ok(())
+    }
+
+    /// 若指定 app 有启用的提示词，移除其提示词文件中的托管区域（数据库中的启用状态保持不变）
+    pub fn clear_enabled_from_app(state: &AppState, app: &AppType) -> Result<(), AppError> {
+        if Self::get_enabled_prompt(state, app)?.is_some() {
+            clear_app_file(app)?;
+        }
         Ok(())
     }
 
@@ -163,18 +718,24 @@ impl PromptService {
 
         let content =
             std::fs::read_to_string(&file_path).map_err(|e| AppError::io(&file_path, e))?;
+        let content = normalize_imported_prompt(
+            &content,
+            &crate::settings::get_prompt_import_normalization(),
+        );
         let timestamp = get_unix_timestamp()?;
 
-        let id = format!("imported-{timestamp}");
+        let existing = state.db.get_prompts()?;
+        let id = IdGenerator::new(IdKind::ImportedPrompt)
+            .app(&app)
+            .generate(|id| Ok(existing.contains_key(id)))?;
         let prompt = Prompt {
             id: id.clone(),
-            name: format!(
-                "导入的提示词 {}",
-                chrono::Local::now().format("%Y-%m-%d %H:%M")
-            ),
+            name: format!("导入的提示词 {}", local_time_label()),
             content,
             description: Some("从现有配置文件导入".to_string()),
+            tags: Vec::new(),
             apps: PromptApps::default(),
+            sort_index: None,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
@@ -200,11 +761,10 @@ impl PromptService {
         app: AppType,
     ) -> Result<usize, AppError> {
         // 幂等性保护：该 app 已有启用的提示词则跳过
-        let existing = state.db.get_prompts()?;
-        let already_enabled = existing.values().any(|p| app_enabled(&p.apps, &app));
-        if already_enabled {
+        if Self::get_enabled_prompt(state, &app)?.is_some() {
             return Ok(0);
         }
+        let existing = state.db.get_prompts()?;
 
         let file_path = prompt_file_path(&app)?;
         if !file_path.exists() {
@@ -212,7 +772,9 @@ impl PromptService {
         }
 
         let content = match std::fs::read_to_string(&file_path) {
-            Ok(c) => c,
+            Ok(c) => {
+                normalize_imported_prompt(&c, &crate::settings::get_prompt_import_normalization())
+            }
             Err(e) => {
                 log::warn!("读取提示词文件失败: {file_path:?}, 错误: {e}");
                 return Ok(0);
@@ -226,26 +788,22 @@ impl PromptService {
         log::info!("发现提示词文件，自动导入: {file_path:?}");
 
         let timestamp = get_unix_timestamp()?;
-        let id = format!("auto-imported-{timestamp}");
+        let id = IdGenerator::new(IdKind::AutoImportedPrompt)
+            .app(&app)
+            .generate(|id| Ok(existing.contains_key(id)))?;
 
         // 构建 apps，只启用当前 app
         let mut apps = PromptApps::default();
-        match app {
-            AppType::Claude => apps.claude = true,
-            AppType::Codex => apps.codex = true,
-            AppType::Gemini => apps.gemini = true,
-            AppType::OpenCode | AppType::OpenClaw => apps.opencode = true,
-        }
+        apps.set_enabled_for(&app, true);
 
         let prompt = Prompt {
             id: id.clone(),
-            name: format!(
-                "Auto-imported Prompt {}",
-                chrono::Local::now().format("%Y-%m-%d %H:%M")
-            ),
+            name: format!("Auto-imported Prompt {}", local_time_label()),
             content,
             description: Some("Automatically imported on first launch".to_string()),
+            tags: Vec::new(),
             apps,
+            sort_index: None,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
@@ -0,0 +1,140 @@
+//! 提示词文件首次改写前的备份
+//!
+//! cc-switch 第一次往某个提示词文件（CLAUDE.md、GEMINI.md、AGENTS.md 等）写入托管区域时，
+//! 文件里通常只有用户自己的内容。写入前先把原文件复制到 `~/.cc-switch/backups/prompts/`，
+//! 并在同目录的 `manifest.json` 中登记来源路径，之后可以列出并一键恢复。
+//!
+//! “首次”按文件内容判断：已包含托管区域的文件说明 cc-switch 写过，不再备份；
+//! 不存在或只有空白的文件没有可丢失的内容，也不备份。
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::config::{get_app_config_dir, read_json_file, write_json_file, write_text_file};
+use crate::error::AppError;
+use crate::prompt_files::prompt_region_body;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// 一份提示词文件备份
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFileBackup {
+    /// 备份文件名，同时作为恢复时的标识
+    pub id: String,
+    pub app: String,
+    /// 被备份文件的原路径
+    pub original_path: String,
+    pub backup_path: String,
+    pub size_bytes: u64,
+    /// Unix 秒
+    pub created_at: i64,
+}
+
+pub struct PromptBackupService;
+
+impl PromptBackupService {
+    /// 备份目录：`~/.cc-switch/backups/prompts`
+    pub fn backup_dir() -> PathBuf {
+        get_app_config_dir().join("backups").join("prompts")
+    }
+
+    /// 在 cc-switch 首次改写 `path` 之前备份其原内容，返回新建的备份
+    ///
+    /// `original` 为文件当前内容（不存在时为空串）。
+    pub(crate) fn backup_before_first_write(
+        app: &AppType,
+        path: &Path,
+        original: &str,
+    ) -> Result<Option<PromptFileBackup>, AppError> {
+        if original.trim().is_empty() || prompt_region_body(original).is_some() {
+            return Ok(None);
+        }
+
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "prompt.md".to_string());
+        let now = chrono::Local::now();
+        let dir = Self::backup_dir();
+        let mut id = format!(
+            "{}-{file_name}.{}.bak",
+            app.as_str(),
+            now.format("%Y%m%d_%H%M%S")
+        );
+        let mut suffix = 1;
+        while dir.join(&id).exists() {
+            suffix += 1;
+            id = format!(
+                "{}-{file_name}.{}-{suffix}.bak",
+                app.as_str(),
+                now.format("%Y%m%d_%H%M%S")
+            );
+        }
+
+        let backup_path = dir.join(&id);
+        write_text_file(&backup_path, original)?;
+        let backup = PromptFileBackup {
+            id,
+            app: app.as_str().to_string(),
+            original_path: path.display().to_string(),
+            backup_path: backup_path.display().to_string(),
+            size_bytes: original.len() as u64,
+            created_at: now.timestamp(),
+        };
+        let mut manifest = load_manifest()?;
+        manifest.push(backup.clone());
+        write_json_file(&dir.join(MANIFEST_FILE), &manifest)?;
+        log::info!(
+            "首次写入 {} 前已备份原文件到 {}",
+            path.display(),
+            backup_path.display()
+        );
+        Ok(Some(backup))
+    }
+
+    /// 列出仍存在的备份，最新的在前
+    pub fn list() -> Result<Vec<PromptFileBackup>, AppError> {
+        let mut backups: Vec<PromptFileBackup> = load_manifest()?
+            .into_iter()
+            .filter(|backup| Path::new(&backup.backup_path).exists())
+            .collect();
+        backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        Ok(backups)
+    }
+
+    /// 用备份覆盖原文件，返回被恢复的备份
+    ///
+    /// 恢复后的文件不含托管区域，下次同步时 cc-switch 会重新追加自己的区域。
+    pub fn restore(id: &str) -> Result<PromptFileBackup, AppError> {
+        let backup = load_manifest()?
+            .into_iter()
+            .find(|backup| backup.id == id)
+            .ok_or_else(|| {
+                AppError::localized(
+                    "prompt_backup.not_found",
+                    format!("提示词备份不存在: {id}"),
+                    format!("Prompt backup not found: {id}"),
+                )
+            })?;
+        let source = Path::new(&backup.backup_path);
+        let content = std::fs::read_to_string(source).map_err(|e| AppError::io(source, e))?;
+        write_text_file(Path::new(&backup.original_path), &content)?;
+        log::info!(
+            "已从 {} 恢复提示词文件 {}",
+            backup.backup_path,
+            backup.original_path
+        );
+        Ok(backup)
+    }
+}
+
+fn load_manifest() -> Result<Vec<PromptFileBackup>, AppError> {
+    let path = PromptBackupService::backup_dir().join(MANIFEST_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    read_json_file(&path)
+}
@@ -0,0 +1,343 @@
+//! 提示词导出包
+//!
+//! 把选中的提示词导出为可移植的文件，便于分享给同事或迁移到另一台机器。支持两种格式，按文件扩展名区分：
+//!
+//! - `.json`：[`PromptBundle`]，与 Agent 导出包结构一致
+//! - `.zip`：`manifest.json` 加上 `prompts/` 下每个提示词一个 Markdown 文件，
+//!   文件开头的 YAML frontmatter 保存 id、名称、描述与时间戳，正文即提示词内容，可直接阅读或编辑
+//!
+//! 启用状态与本机相关，导入时不会生效：新提示词不对任何应用启用，覆盖时保留本地的启用状态与优先级。
+
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+
+use crate::agent::{AgentRename, DuplicateStrategy};
+use crate::config::write_text_file;
+use crate::error::AppError;
+use crate::prompt::{
+    Prompt, PromptApps, PromptBundle, PromptImportResult, PROMPT_BUNDLE_FORMAT,
+    PROMPT_BUNDLE_VERSION,
+};
+use crate::services::PromptService;
+use crate::store::AppState;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const PROMPTS_DIR: &str = "prompts";
+
+/// zip 导出包的清单
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ZipManifest {
+    format: String,
+    version: u32,
+    exported_at: i64,
+    /// 按优先级排列的提示词文件（相对 zip 根目录）
+    files: Vec<String>,
+}
+
+/// 提示词 Markdown 文件的 frontmatter
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptFileMeta {
+    id: String,
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<i64>,
+}
+
+pub struct PromptBundleService;
+
+impl PromptBundleService {
+    /// 导出提示词（按优先级排序）；`ids` 为空时导出全部，不存在的 id 会被忽略
+    pub fn export_bundle(
+        state: &AppState,
+        ids: Option<&[String]>,
+    ) -> Result<PromptBundle, AppError> {
+        let prompts = state
+            .db
+            .get_prompts()?
+            .into_values()
+            .filter(|p| ids.is_none_or(|ids| ids.contains(&p.id)))
+            .collect();
+        Ok(PromptBundle {
+            format: PROMPT_BUNDLE_FORMAT.to_string(),
+            version: PROMPT_BUNDLE_VERSION,
+            exported_at: chrono::Utc::now().timestamp_millis(),
+            prompts,
+        })
+    }
+
+    /// 导出提示词到文件（`.zip` 为 Markdown 压缩包，其余为 JSON），返回导出的数量
+    pub fn export_to_file(
+        state: &AppState,
+        path: &Path,
+        ids: Option<&[String]>,
+    ) -> Result<usize, AppError> {
+        let bundle = Self::export_bundle(state, ids)?;
+        if is_zip(path) {
+            write_zip(path, &bundle)?;
+        } else {
+            let json = serde_json::to_string_pretty(&bundle)
+                .map_err(|e| AppError::JsonSerialize { source: e })?;
+            write_text_file(path, &json)?;
+        }
+        log::info!(
+            "已导出 {} 个提示词到 {}",
+            bundle.prompts.len(),
+            path.display()
+        );
+        Ok(bundle.prompts.len())
+    }
+
+    /// 从文件导入提示词导出包（格式同 [`Self::export_to_file`]）
+    pub fn import_from_file(
+        state: &AppState,
+        path: &Path,
+        strategy: DuplicateStrategy,
+    ) -> Result<PromptImportResult, AppError> {
+        let bundle = if is_zip(path) {
+            read_zip(path)?
+        } else {
+            let raw = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+            serde_json::from_str(&raw).map_err(|e| invalid_bundle(e.to_string()))?
+        };
+        Self::import_bundle(state, bundle, strategy)
+    }
+
+    /// 导入提示词导出包，按 `strategy` 处理重复 id
+    ///
+    /// 导入包中的启用状态与优先级被忽略：新提示词不启用、排在最后；覆盖时保留本地的启用状态、优先级与创建时间。
+    pub fn import_bundle(
+        state: &AppState,
+        bundle: PromptBundle,
+        strategy: DuplicateStrategy,
+    ) -> Result<PromptImportResult, AppError> {
+        if bundle.format != PROMPT_BUNDLE_FORMAT || bundle.version > PROMPT_BUNDLE_VERSION {
+            return Err(AppError::localized(
+                "prompt.bundle_unsupported",
+                format!(
+                    "不支持的提示词导出包: {} v{}",
+                    bundle.format, bundle.version
+                ),
+                format!(
+                    "Unsupported prompt bundle: {} v{}",
+                    bundle.format, bundle.version
+                ),
+            ));
+        }
+
+        let existing = state.db.get_prompts()?;
+        let mut taken: HashSet<String> = existing.keys().cloned().collect();
+        let now = chrono::Utc::now().timestamp();
+        let mut result = PromptImportResult::default();
+
+        for mut prompt in bundle.prompts {
+            let id = prompt.id.trim().to_string();
+            if id.is_empty() {
+                log::warn!("跳过缺少 id 的提示词: {}", prompt.name);
+                continue;
+            }
+            prompt.id = id.clone();
+            prompt.apps = PromptApps::default();
+            prompt.sort_index = None;
+            prompt.created_at = prompt.created_at.or(Some(now));
+            prompt.updated_at = Some(now);
+
+            if taken.contains(&id) {
+                match strategy {
+                    DuplicateStrategy::Skip => {
+                        result.skipped.push(id);
+                        continue;
+                    }
+                    DuplicateStrategy::Overwrite => {
+                        if let Some(local) = existing.get(&id) {
+                            prompt.apps = local.apps.clone();
+                            prompt.sort_index = local.sort_index;
+                            prompt.created_at = local.created_at.or(prompt.created_at);
+                        }
+                        result.overwritten.push(id);
+                    }
+                    DuplicateStrategy::Rename => {
+                        let new_id = (2..)
+                            .map(|n| format!("{id}-{n}"))
+                            .find(|candidate| !taken.contains(candidate))
+                            .expect("unbounded id candidates");
+                        result.renamed.push(AgentRename {
+                            from: id,
+                            to: new_id.clone(),
+                        });
+                        prompt.id = new_id;
+                    }
+                }
+            } else {
+                result.imported.push(id);
+            }
+
+            taken.insert(prompt.id.clone());
+            PromptService::upsert_prompt(state, prompt)?;
+        }
+
+        log::info!(
+            "提示词导入完成: 新增 {}，覆盖 {}，重命名 {}，跳过 {}",
+            result.imported.len(),
+            result.overwritten.len(),
+            result.renamed.len(),
+            result.skipped.len()
+        );
+        Ok(result)
+    }
+}
+
+fn is_zip(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+fn invalid_bundle(detail: String) -> AppError {
+    AppError::localized(
+        "prompt.bundle_invalid",
+        format!("无效的提示词导出包: {detail}"),
+        format!("Invalid prompt bundle: {detail}"),
+    )
+}
+
+/// 由 id 生成 zip 内的文件名（只保留安全字符，冲突时追加序号）
+fn file_name_for(id: &str, used: &mut HashSet<String>) -> String {
+    let stem: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = std::iter::once(format!("{PROMPTS_DIR}/{stem}.md"))
+        .chain((2..).map(|n| format!("{PROMPTS_DIR}/{stem}-{n}.md")))
+        .find(|candidate| !used.contains(candidate))
+        .expect("unbounded file name candidates");
+    used.insert(name.clone());
+    name
+}
+
+/// 生成带 frontmatter 的提示词 Markdown 文件内容
+fn prompt_to_markdown(prompt: &Prompt) -> Result<String, AppError> {
+    let meta = PromptFileMeta {
+        id: prompt.id.clone(),
+        name: prompt.name.clone(),
+        description: prompt.description.clone(),
+        tags: prompt.tags.clone(),
+        created_at: prompt.created_at,
+        updated_at: prompt.updated_at,
+    };
+    let yaml = serde_yaml::to_string(&meta)
+        .map_err(|e| AppError::Message(format!("序列化提示词元数据失败: {e}")))?;
+    Ok(format!("---\n{yaml}---\n\n{}", prompt.content))
+}
+
+/// 解析 [`prompt_to_markdown`] 生成的文件
+fn prompt_from_markdown(file: &str, text: &str) -> Result<Prompt, AppError> {
+    // 导出包可能在 Windows 上被编辑过
+    let text = text.replace("\r\n", "\n");
+    let parsed = text.strip_prefix("---\n").and_then(|rest| {
+        let close = rest.find("\n---\n")?;
+        Some((&rest[..close], &rest[close + "\n---\n".len()..]))
+    });
+    let Some((yaml, body)) = parsed else {
+        return Err(invalid_bundle(format!("{file} 缺少 frontmatter")));
+    };
+    let meta: PromptFileMeta =
+        serde_yaml::from_str(yaml).map_err(|e| invalid_bundle(format!("{file}: {e}")))?;
+    Ok(Prompt {
+        id: meta.id,
+        name: meta.name,
+        content: body.strip_prefix('\n').unwrap_or(body).to_string(),
+        description: meta.description,
+        tags: meta.tags,
+        apps: PromptApps::default(),
+        sort_index: None,
+        created_at: meta.created_at,
+        updated_at: meta.updated_at,
+    })
+}
+
+fn write_zip(path: &Path, bundle: &PromptBundle) -> Result<(), AppError> {
+    let mut used = HashSet::new();
+    let mut entries = Vec::with_capacity(bundle.prompts.len());
+    for prompt in &bundle.prompts {
+        entries.push((
+            file_name_for(&prompt.id, &mut used),
+            prompt_to_markdown(prompt)?,
+        ));
+    }
+    let manifest = ZipManifest {
+        format: bundle.format.clone(),
+        version: bundle.version,
+        exported_at: bundle.exported_at,
+        files: entries.iter().map(|(name, _)| name.clone()).collect(),
+    };
+    let manifest = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| AppError::JsonSerialize { source: e })?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    let file = std::fs::File::create(path).map_err(|e| AppError::io(path, e))?;
+    let mut writer = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let zip_err =
+        |e: zip::result::ZipError| AppError::Message(format!("写入提示词压缩包失败: {e}"));
+
+    writer.start_file(MANIFEST_FILE, options).map_err(zip_err)?;
+    writer
+        .write_all(manifest.as_bytes())
+        .map_err(|e| AppError::io(path, e))?;
+    for (name, text) in &entries {
+        writer.start_file(name.as_str(), options).map_err(zip_err)?;
+        writer
+            .write_all(text.as_bytes())
+            .map_err(|e| AppError::io(path, e))?;
+    }
+    writer.finish().map_err(zip_err)?;
+    Ok(())
+}
+
+fn read_zip(path: &Path) -> Result<PromptBundle, AppError> {
+    let file = std::fs::File::open(path).map_err(|e| AppError::io(path, e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| invalid_bundle(e.to_string()))?;
+    let mut read_entry = |name: &str| -> Result<String, AppError> {
+        let mut entry = archive
+            .by_name(name)
+            .map_err(|e| invalid_bundle(format!("{name}: {e}")))?;
+        let mut text = String::new();
+        entry
+            .read_to_string(&mut text)
+            .map_err(|e| invalid_bundle(format!("{name}: {e}")))?;
+        Ok(text)
+    };
+
+    let manifest: ZipManifest = serde_json::from_str(&read_entry(MANIFEST_FILE)?)
+        .map_err(|e| invalid_bundle(format!("{MANIFEST_FILE}: {e}")))?;
+    let prompts = manifest
+        .files
+        .iter()
+        .map(|name| prompt_from_markdown(name, &read_entry(name)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(PromptBundle {
+        format: manifest.format,
+        version: manifest.version,
+        exported_at: manifest.exported_at,
+        prompts,
+    })
+}
@@ -0,0 +1,205 @@
+//! 提示词文件的外部修改对账
+//!
+//! 每次写入提示词托管区域后，[`crate::external_edits`] 都记下了写入内容的摘要。
+//! 这里把各 app 提示词文件的当前区域与记录比对，列出在 cc-switch 之外被改过的文件，
+//! 并提供三种处理方式（[`DriftResolution`]）：
+//!
+//! - `import`：把文件中的内容导入为新提示词，并改为只对该 app 启用它
+//! - `merge`：把文件中的内容写回当前唯一启用的提示词（多个提示词拼接、或该提示词引用了其他提示词时
+//!   无法拆分，只能导入）
+//! - `discard`：丢弃外部修改，按数据库重新写入
+//!
+//! 三种方式都会让文件与数据库重新一致，写入时不受外部修改策略影响。
+
+use serde::{Deserialize, Serialize};
+
+use crate::agents::restore_template_lines;
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::external_edits::{self, ManagedKind, ManagedUnit};
+use crate::managed_banner::strip_banners;
+use crate::prompt::{Prompt, PromptApps};
+use crate::prompt_files::{prompt_file_path, prompt_region_body};
+use crate::services::id_generator::{local_time_label, IdGenerator, IdKind};
+use crate::services::prompt::{enabled_for, render_enabled, rewrite_app_file, PROMPT_APPS};
+use crate::services::prompt_include::included_ids;
+use crate::services::{AgentsService, PromptService};
+use crate::store::AppState;
+
+/// 一个被外部修改过的提示词文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptDrift {
+    pub app: String,
+    pub path: String,
+    /// 托管区域的当前正文（已去掉来源横幅）；区域被整段删除时为 None
+    pub current: Option<String>,
+    /// 按数据库中启用的提示词应写入的正文
+    pub expected: String,
+    /// 当前对该 app 启用的提示词 id（按优先级）
+    pub enabled_prompt_ids: Vec<String>,
+}
+
+/// 外部修改的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DriftResolution {
+    Import,
+    Merge,
+    Discard,
+}
+
+pub struct PromptDriftService;
+
+impl PromptDriftService {
+    /// 列出托管区域偏离了上次写入内容的 app 提示词文件（同步已暂停的 app 不检查）
+    pub fn detect(state: &AppState) -> Result<Vec<PromptDrift>, AppError> {
+        let prompts = state.db.get_prompts()?;
+        let mut drifts = Vec::new();
+        for app in PROMPT_APPS {
+            if crate::settings::is_app_sync_paused(&app) {
+                continue;
+            }
+            let Some(current) = Self::drifted_body(&app)? else {
+                continue;
+            };
+            drifts.push(PromptDrift {
+                app: app.as_str().to_string(),
+                path: prompt_file_path(&app)?.display().to_string(),
+                current,
+                expected: render_enabled(state, &app, &prompts),
+                enabled_prompt_ids: enabled_for(&prompts, &app)
+                    .iter()
+                    .map(|p| p.id.clone())
+                    .collect(),
+            });
+        }
+        Ok(drifts)
+    }
+
+    /// 按选择的方式处理 app 提示词文件的外部修改，返回导入或写回的提示词 id（`discard` 时为 None）
+    ///
+    /// 文件没有偏离时不做任何事并返回 None。
+    pub fn resolve(
+        state: &AppState,
+        app: AppType,
+        resolution: DriftResolution,
+    ) -> Result<Option<String>, AppError> {
+        let Some(current) = Self::drifted_body(&app)? else {
+            return Ok(None);
+        };
+        let body = current.filter(|c| !c.trim().is_empty());
+
+        external_edits::overwrite_anyway(|| match resolution {
+            DriftResolution::Import => {
+                let content = body.ok_or_else(Self::nothing_to_keep)?;
+                Self::import(state, app, content).map(Some)
+            }
+            DriftResolution::Merge => {
+                let content = body.ok_or_else(Self::nothing_to_keep)?;
+                Self::merge(state, &app, content).map(Some)
+            }
+            DriftResolution::Discard => {
+                rewrite_app_file(state, &app, &state.db.get_prompts()?)?;
+                Ok(None)
+            }
+        })
+    }
+
+    /// 偏离时返回 Some(区域当前正文)，区域被删除时为 Some(None)；未偏离或无从比较时返回 None
+    fn drifted_body(app: &AppType) -> Result<Option<Option<String>>, AppError> {
+        let path = prompt_file_path(app)?;
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(AppError::io(&path, e)),
+        };
+        let body = content.as_deref().and_then(prompt_region_body);
+        let unit = ManagedUnit {
+            app,
+            kind: ManagedKind::Prompt,
+            id: "region",
+            path: &path,
+        };
+        if !external_edits::has_drifted(&unit, body) {
+            return Ok(None);
+        }
+        Ok(Some(body.map(strip_banners)))
+    }
+
+    /// 新建提示词保存文件中的内容，并改为只对该 app 启用它
+    fn import(state: &AppState, app: AppType, content: String) -> Result<String, AppError> {
+        let existing = state.db.get_prompts()?;
+        let id = IdGenerator::new(IdKind::ImportedPrompt)
+            .app(&app)
+            .generate(|id| Ok(existing.contains_key(id)))?;
+        let now = chrono::Utc::now().timestamp();
+        state.db.save_prompt(&Prompt {
+            id: id.clone(),
+            name: format!("导入的提示词 {}", local_time_label()),
+            content,
+            description: Some("从外部修改的提示词文件导入".to_string()),
+            tags: Vec::new(),
+            apps: PromptApps::default(),
+            sort_index: None,
+            created_at: Some(now),
+            updated_at: Some(now),
+        })?;
+        PromptService::toggle_prompt_app(state, &id, app, true, true)?;
+        Ok(id)
+    }
+
+    /// 把文件中的内容写回该 app 唯一启用的提示词
+    fn merge(state: &AppState, app: &AppType, content: String) -> Result<String, AppError> {
+        let prompts = state.db.get_prompts()?;
+        let enabled = enabled_for(&prompts, app);
+        let [target] = enabled.as_slice() else {
+            return Err(AppError::localized(
+                "prompt_drift.merge_needs_single_prompt",
+                format!(
+                    "{} 启用的提示词不止一个（或没有），无法写回，请改为导入",
+                    app.as_str()
+                ),
+                format!(
+                    "{} does not have exactly one enabled prompt to merge into; import it instead",
+                    app.as_str()
+                ),
+            ));
+        };
+        // 文件中是引用展开后的正文，写回会把被引用提示词的内容固化进来
+        if !included_ids(&target.content).is_empty() {
+            return Err(AppError::localized(
+                "prompt_drift.merge_has_includes",
+                format!(
+                    "提示词 {} 引用了其他提示词，写回会把引用展开进正文，请改为导入",
+                    target.id
+                ),
+                format!(
+                    "Prompt {} includes other prompts; merging would inline them, import it instead",
+                    target.id
+                ),
+            ));
+        }
+        let id = target.id.clone();
+        // 未改动的模板行换回原模板，避免把变量（尤其是日期）固化成写入当时的取值
+        let vars = AgentsService::template_vars(state, app);
+        let content = restore_template_lines(&content, &target.content, &vars);
+        PromptService::upsert_prompt(
+            state,
+            Prompt {
+                content,
+                updated_at: Some(chrono::Utc::now().timestamp()),
+                ..(*target).clone()
+            },
+        )?;
+        Ok(id)
+    }
+
+    fn nothing_to_keep() -> AppError {
+        AppError::localized(
+            "prompt_drift.region_removed",
+            "托管区域已被删除，没有可以保留的内容",
+            "The managed region was removed; there is nothing to keep",
+        )
+    }
+}
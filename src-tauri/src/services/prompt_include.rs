@@ -0,0 +1,190 @@
+//! 提示词引用（include）
+//!
+//! 正文中的 `{{> prompt-id}}` 在同步时替换为被引用提示词的正文，被引用的提示词无需启用，
+//! 可以只作为公共片段存在。例如各工具的提示词都以 `{{> common-rules}}` 开头，再追加各自的内容。
+//!
+//! 引用可以嵌套；出现循环或引用不存在的提示词时，保存会被校验拒绝，仍被引用的提示词也不能删除。
+//! 保存被引用的提示词时，引用了它的提示词所启用的 app 文件会一并重写。
+//! 同步时若仍遇到这类问题（例如旧数据中残留的失效引用），该引用原样保留并记录日志。
+
+use indexmap::IndexMap;
+
+use crate::error::AppError;
+use crate::prompt::Prompt;
+
+const INCLUDE_OPEN: &str = "{{>";
+const INCLUDE_CLOSE: &str = "}}";
+/// 最大嵌套层数，防止过深的引用链
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// 展开正文中的全部引用
+pub(crate) fn expand_includes(
+    content: &str,
+    prompts: &IndexMap<String, Prompt>,
+) -> Result<String, AppError> {
+    expand(content, prompts, &mut Vec::new())
+}
+
+fn expand<'a>(
+    content: &str,
+    prompts: &'a IndexMap<String, Prompt>,
+    stack: &mut Vec<&'a str>,
+) -> Result<String, AppError> {
+    if !content.contains(INCLUDE_OPEN) {
+        return Ok(content.to_string());
+    }
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find(INCLUDE_OPEN) {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + INCLUDE_OPEN.len()..];
+        let Some(end) = after.find(INCLUDE_CLOSE) else {
+            out.push_str(&rest[start..]);
+            return Ok(out);
+        };
+        let id = after[..end].trim();
+        let Some((key, included)) = prompts.get_key_value(id) else {
+            return Err(AppError::localized(
+                "prompt.include_missing",
+                format!("引用的提示词不存在: {id}"),
+                format!("Included prompt not found: {id}"),
+            ));
+        };
+        if stack.contains(&key.as_str()) {
+            let mut chain: Vec<&str> = stack.clone();
+            chain.push(key);
+            return Err(AppError::localized(
+                "prompt.include_cycle",
+                format!("提示词引用形成循环: {}", chain.join(" -> ")),
+                format!("Prompt includes form a cycle: {}", chain.join(" -> ")),
+            ));
+        }
+        if stack.len() >= MAX_INCLUDE_DEPTH {
+            return Err(AppError::localized(
+                "prompt.include_too_deep",
+                format!("提示词引用嵌套超过 {MAX_INCLUDE_DEPTH} 层"),
+                format!("Prompt includes are nested deeper than {MAX_INCLUDE_DEPTH} levels"),
+            ));
+        }
+        stack.push(key);
+        let expanded = expand(&included.content, prompts, stack)?;
+        stack.pop();
+        // 被引用正文末尾的换行由引用处的上下文决定，避免多出空行
+        out.push_str(expanded.trim_end_matches('\n'));
+        rest = &after[end + INCLUDE_CLOSE.len()..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// 正文直接引用的提示词 id（按出现顺序，不展开嵌套引用）
+pub(crate) fn included_ids(content: &str) -> Vec<&str> {
+    let mut ids = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find(INCLUDE_OPEN) {
+        let after = &rest[start + INCLUDE_OPEN.len()..];
+        let Some(end) = after.find(INCLUDE_CLOSE) else {
+            break;
+        };
+        ids.push(after[..end].trim());
+        rest = &after[end + INCLUDE_CLOSE.len()..];
+    }
+    ids
+}
+
+/// 直接或间接引用了 `id` 的提示词 id（不含 `id` 本身）
+pub(crate) fn dependent_ids(id: &str, prompts: &IndexMap<String, Prompt>) -> Vec<String> {
+    let mut found = vec![id.to_string()];
+    loop {
+        let before = found.len();
+        for (key, prompt) in prompts {
+            if !found.contains(key)
+                && included_ids(&prompt.content)
+                    .iter()
+                    .any(|included| found.iter().any(|f| f == included))
+            {
+                found.push(key.clone());
+            }
+        }
+        if found.len() == before {
+            break;
+        }
+    }
+    found.remove(0);
+    found
+}
+
+/// 检查 `prompt` 保存后引用是否可以展开（`prompts` 为包含该提示词新版本的全部提示词）
+pub(crate) fn check_includes(
+    prompt: &Prompt,
+    prompts: &IndexMap<String, Prompt>,
+) -> Result<(), AppError> {
+    let mut stack = vec![prompt.id.as_str()];
+    expand(&prompt.content, prompts, &mut stack).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompts(entries: &[(&str, &str)]) -> IndexMap<String, Prompt> {
+        entries
+            .iter()
+            .map(|(id, content)| {
+                (
+                    id.to_string(),
+                    Prompt {
+                        id: id.to_string(),
+                        name: id.to_string(),
+                        content: content.to_string(),
+                        description: None,
+                        tags: Vec::new(),
+                        apps: Default::default(),
+                        sort_index: None,
+                        created_at: None,
+                        updated_at: None,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nested_includes_are_expanded_in_place() {
+        let all = prompts(&[
+            ("common", "Be concise.\n{{> tone}}\n"),
+            ("tone", "Use a friendly tone.\n"),
+        ]);
+        assert_eq!(
+            expand_includes("{{> common }}\nClaude only.\n", &all).unwrap(),
+            "Be concise.\nUse a friendly tone.\nClaude only.\n"
+        );
+        assert_eq!(expand_includes("{{model}}", &all).unwrap(), "{{model}}");
+    }
+
+    #[test]
+    fn cycles_and_missing_includes_are_rejected() {
+        let all = prompts(&[("a", "{{> b}}"), ("b", "{{> a}}")]);
+        match check_includes(&all["a"], &all) {
+            Err(AppError::Localized { key, .. }) => assert_eq!(key, "prompt.include_cycle"),
+            other => panic!("expected cycle error, got {other:?}"),
+        }
+        let all = prompts(&[("a", "{{> ghost}}")]);
+        match expand_includes(&all["a"].content, &all) {
+            Err(AppError::Localized { key, .. }) => assert_eq!(key, "prompt.include_missing"),
+            other => panic!("expected missing error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn dependents_include_nested_includers() {
+        let all = prompts(&[
+            ("claude", "{{> common}}\nClaude only.\n"),
+            ("common", "{{> tone}}\n"),
+            ("tone", "Friendly.\n"),
+            ("other", "Unrelated.\n"),
+        ]);
+        assert_eq!(dependent_ids("tone", &all), vec!["common", "claude"]);
+        assert!(dependent_ids("other", &all).is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+//! 项目级提示词
+//!
+//! 提示词除了按 app 写入用户目录下的全局文件（`~/.claude/CLAUDE.md` 等），还可以挂载到登记过的项目目录，
+//! 写入 `<project>/CLAUDE.md`、`<project>/AGENTS.md`、`<project>/GEMINI.md` 的托管区域。
+//! 项目挂载与全局启用状态相互独立：只挂到项目、不在全局启用的提示词不会出现在用户目录的文件里。
+//!
+//! Codex 与 OpenCode 在项目中共用 `AGENTS.md`，两者挂载的提示词按优先级合并写入同一个区域。
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::managed_banner::banner_for;
+use crate::prompt::Prompt;
+use crate::prompt_files::prompt_file_name;
+use crate::services::prompt::{render_prompts, write_region_at, PROMPT_APPS};
+use crate::services::sync_guard::{best_effort, sync_or_rollback};
+use crate::store::AppState;
+
+/// 登记的项目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptProject {
+    pub id: String,
+    /// 项目根目录（绝对路径）
+    pub path: String,
+    /// 显示名称；添加时未填写则取目录名
+    pub name: String,
+    /// 登记时间（Unix 秒）
+    pub created_at: i64,
+    /// 挂载到该项目的提示词
+    #[serde(default)]
+    pub links: Vec<PromptProjectLink>,
+}
+
+/// 提示词在项目中的一次挂载（对某个 app 生效）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptProjectLink {
+    pub prompt_id: String,
+    pub app: String,
+}
+
+impl PromptProject {
+    fn links_app(&self, prompt_id: &str, app: &AppType) -> bool {
+        self.links
+            .iter()
+            .any(|l| l.prompt_id == prompt_id && l.app == app.as_str())
+    }
+}
+
+/// 项目中的提示词文件及写入它的 app（按文件名分组，顺序与 [`PROMPT_APPS`] 一致）
+fn project_files() -> Vec<(&'static str, Vec<AppType>)> {
+    let mut files: Vec<(&'static str, Vec<AppType>)> = Vec::new();
+    for app in PROMPT_APPS {
+        let name = prompt_file_name(&app);
+        match files.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, apps)) => apps.push(app),
+            None => files.push((name, vec![app])),
+        }
+    }
+    files
+}
+
+pub struct PromptProjectService;
+
+impl PromptProjectService {
+    /// 获取全部项目（含挂载）
+    pub fn list_projects(db: &Database) -> Result<Vec<PromptProject>, AppError> {
+        db.get_prompt_projects()
+    }
+
+    /// 登记项目目录；目录必须存在，同一路径只能登记一次
+    pub fn add_project(
+        db: &Database,
+        path: &str,
+        name: Option<String>,
+    ) -> Result<PromptProject, AppError> {
+        let dir = PathBuf::from(path.trim());
+        if !dir.is_dir() {
+            return Err(AppError::localized(
+                "prompt_project.not_a_directory",
+                format!("项目目录不存在: {}", dir.display()),
+                format!("Project directory does not exist: {}", dir.display()),
+            ));
+        }
+        let dir = dir.canonicalize().map_err(|e| AppError::io(&dir, e))?;
+        let path = dir.to_string_lossy().to_string();
+        if db.get_prompt_projects()?.iter().any(|p| p.path == path) {
+            return Err(AppError::localized(
+                "prompt_project.duplicate_path",
+                format!("该项目已登记: {path}"),
+                format!("Project already registered: {path}"),
+            ));
+        }
+
+        let name = name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .or_else(|| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| path.clone());
+        let project = PromptProject {
+            id: uuid::Uuid::new_v4().to_string(),
+            path,
+            name,
+            created_at: chrono::Utc::now().timestamp(),
+            links: Vec::new(),
+        };
+        db.save_prompt_project(&project)?;
+        Ok(project)
+    }
+
+    /// 取消登记项目：先移除项目文件中的托管区域，再删除登记与挂载
+    pub fn remove_project(state: &AppState, id: &str) -> Result<bool, AppError> {
+        let Some(project) = state.db.get_prompt_project(id)? else {
+            return Ok(false);
+        };
+        Self::clear_project_files(&project)?;
+        state.db.delete_prompt_project(id)
+    }
+
+    /// 切换提示词在项目中对某个 app 的挂载，并重写该项目的提示词文件
+    pub fn toggle_project_prompt(
+        state: &AppState,
+        project_id: &str,
+        prompt_id: &str,
+        app: AppType,
+        enabled: bool,
+    ) -> Result<(), AppError> {
+        if !PROMPT_APPS.contains(&app) {
+            return Err(AppError::localized(
+                "prompt_project.unsupported_app",
+                format!("{} 不支持项目级提示词", app.as_str()),
+                format!("{} does not support project prompts", app.as_str()),
+            ));
+        }
+        let before = Self::require_project(&state.db, project_id)?;
+        if !state.db.get_prompts()?.contains_key(prompt_id) {
+            return Err(AppError::localized(
+                "prompt.not_found",
+                format!("提示词不存在: {prompt_id}"),
+                format!("Prompt not found: {prompt_id}"),
+            ));
+        }
+        let was_enabled = before.links_app(prompt_id, &app);
+        if was_enabled == enabled {
+            return Ok(());
+        }
+
+        state
+            .db
+            .set_prompt_project_link(project_id, prompt_id, app.as_str(), enabled)?;
+        let project = Self::require_project(&state.db, project_id)?;
+        sync_or_rollback(
+            "切换项目提示词",
+            || Self::sync_project(state, &project),
+            || {
+                state.db.set_prompt_project_link(
+                    project_id,
+                    prompt_id,
+                    app.as_str(),
+                    was_enabled,
+                )?;
+                best_effort("恢复项目提示词文件", Self::sync_project(state, &before));
+                Ok(())
+            },
+        )
+    }
+
+    /// 挂载了指定提示词的项目 id
+    pub fn linked_project_ids(db: &Database, prompt_id: &str) -> Result<Vec<String>, AppError> {
+        Ok(db
+            .get_prompt_projects()?
+            .into_iter()
+            .filter(|p| p.links.iter().any(|l| l.prompt_id == prompt_id))
+            .map(|p| p.id)
+            .collect())
+    }
+
+    /// 按当前挂载重写给定项目的提示词文件（已不存在的项目忽略）
+    pub fn resync_projects(state: &AppState, ids: &[String]) -> Result<(), AppError> {
+        for project in state.db.get_prompt_projects()? {
+            if ids.contains(&project.id) {
+                Self::sync_project(state, &project)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 重写所有挂载了提示词的项目文件（提示词内容或优先级变化后调用）
+    pub fn resync_all(state: &AppState) -> Result<(), AppError> {
+        for project in state.db.get_prompt_projects()? {
+            if !project.links.is_empty() {
+                Self::sync_project(state, &project)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 按挂载状态重写项目中的每个提示词文件；没有挂载的文件移除托管区域
+    ///
+    /// 文件对应的 app 全部暂停同步时跳过；项目目录已被删除或移走时只记录警告。
+    pub fn sync_project(state: &AppState, project: &PromptProject) -> Result<(), AppError> {
+        let root = Path::new(&project.path);
+        if !root.is_dir() {
+            log::warn!("项目目录不存在，跳过提示词同步: {}", project.path);
+            return Ok(());
+        }
+
+        let prompts = state.db.get_prompts()?;
+        for (file_name, apps) in project_files() {
+            if apps.iter().all(crate::settings::is_app_sync_paused) {
+                log::debug!("{file_name} 对应的 app 同步已暂停，跳过项目提示词写入");
+                continue;
+            }
+            let linked: Vec<&Prompt> = prompts
+                .values()
+                .filter(|p| apps.iter().any(|app| project.links_app(&p.id, app)))
+                .collect();
+            // 模板变量按第一个有挂载的 app 渲染
+            let Some(app) = apps
+                .iter()
+                .find(|app| project.links.iter().any(|l| l.app == app.as_str()))
+            else {
+                write_region_at(&apps[0], &root.join(file_name), "", &[])?;
+                continue;
+            };
+            let mut text = render_prompts(state, app, &linked, &prompts);
+            if !text.trim().is_empty() {
+                let ids: Vec<&str> = linked.iter().map(|p| p.id.as_str()).collect();
+                text.insert_str(0, &banner_for("prompt", &ids.join(", ")));
+            }
+            write_region_at(app, &root.join(file_name), &text, &[])?;
+        }
+        Ok(())
+    }
+
+    fn clear_project_files(project: &PromptProject) -> Result<(), AppError> {
+        let root = Path::new(&project.path);
+        if !root.is_dir() {
+            return Ok(());
+        }
+        for (file_name, apps) in project_files() {
+            write_region_at(&apps[0], &root.join(file_name), "", &[])?;
+        }
+        Ok(())
+    }
+
+    fn require_project(db: &Database, id: &str) -> Result<PromptProject, AppError> {
+        db.get_prompt_project(id)?.ok_or_else(|| {
+            AppError::localized(
+                "prompt_project.not_found",
+                format!("项目不存在: {id}"),
+                format!("Project not found: {id}"),
+            )
+        })
+    }
+}
@@ -0,0 +1,200 @@
+//! 定时切换提示词
+//!
+//! 每条规则表示“到点后把某个 app 的启用提示词换成指定的提示词”，时间按 [`Schedule`]
+//! 描述。例如工作日 09:00 切到“工作”提示词、工作日 18:00 切回“个人”提示词，
+//! 两条规则配合即可实现“工作时间用 A，其余时间用 B”。
+//!
+//! 后台任务每分钟调用 [`PromptScheduleService::run_due`]，切换复用
+//! [`PromptService::toggle_prompt_app`]（独占启用），与手动切换走同一条同步路径。
+//! 应用关闭期间错过的执行在下次检查时补上：同一 app 只应用最晚到期的那条规则。
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::prompt::PROMPT_APPS;
+use crate::services::schedule::Schedule;
+use crate::services::PromptService;
+use crate::store::AppState;
+
+/// 后台任务的检查间隔（秒）
+pub const PROMPT_SCHEDULE_TICK_SECS: u64 = 60;
+
+/// 定时切换规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptSchedule {
+    /// 新建时留空，由后端生成
+    #[serde(default)]
+    pub id: String,
+    pub app: String,
+    /// 到点后独占启用的提示词
+    pub prompt_id: String,
+    pub schedule: Schedule,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 最近一次已处理的计划执行时间（Unix 毫秒）
+    #[serde(default)]
+    pub last_run_at: Option<i64>,
+    /// 最近一次执行失败的原因；成功时为 None
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Unix 毫秒
+    #[serde(default)]
+    pub created_at: i64,
+    /// Unix 毫秒；修改规则后只处理此后的执行
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 一次生效的定时切换
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedPromptSchedule {
+    pub schedule_id: String,
+    pub app: String,
+    pub prompt_id: String,
+    /// 计划执行时间（Unix 毫秒）
+    pub run_at: i64,
+}
+
+pub struct PromptScheduleService;
+
+impl PromptScheduleService {
+    /// 获取全部规则
+    pub fn list(state: &AppState) -> Result<Vec<PromptSchedule>, AppError> {
+        state.db.get_prompt_schedules()
+    }
+
+    /// 新增（`id` 为空）或更新规则，返回保存后的规则
+    ///
+    /// 保存时间之前的计划执行不会再被补上。
+    pub fn save(state: &AppState, schedule: PromptSchedule) -> Result<PromptSchedule, AppError> {
+        let app = AppType::from_str(&schedule.app)?;
+        if !PROMPT_APPS.contains(&app) {
+            return Err(AppError::localized(
+                "prompt_schedule.unsupported_app",
+                format!("{} 不支持提示词", app.as_str()),
+                format!("{} does not support prompts", app.as_str()),
+            ));
+        }
+        if !state.db.get_prompts()?.contains_key(&schedule.prompt_id) {
+            return Err(Self::prompt_not_found(&schedule.prompt_id));
+        }
+        schedule.schedule.validate()?;
+
+        let now = Utc::now().timestamp_millis();
+        let existing = match schedule.id.trim() {
+            "" => None,
+            id => state.db.get_prompt_schedule(id)?,
+        };
+        let saved = PromptSchedule {
+            id: existing
+                .as_ref()
+                .map(|s| s.id.clone())
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            app: app.as_str().to_string(),
+            last_run_at: existing.as_ref().and_then(|s| s.last_run_at),
+            last_error: None,
+            created_at: existing.as_ref().map_or(now, |s| s.created_at),
+            updated_at: now,
+            ..schedule
+        };
+        state.db.save_prompt_schedule(&saved)?;
+        Ok(saved)
+    }
+
+    /// 删除规则，返回是否存在
+    pub fn delete(state: &AppState, id: &str) -> Result<bool, AppError> {
+        state.db.delete_prompt_schedule(id)
+    }
+
+    /// 执行到 `now` 为止到期的规则，返回实际生效的切换
+    ///
+    /// 单条规则失败只记录在该规则的 `last_error` 中，不影响其他规则。
+    pub fn run_due(
+        state: &AppState,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<AppliedPromptSchedule>, AppError> {
+        let mut due: Vec<(PromptSchedule, DateTime<Utc>)> = Vec::new();
+        for schedule in state.db.get_prompt_schedules()? {
+            if !schedule.enabled {
+                continue;
+            }
+            let since = schedule.last_run_at.unwrap_or(0).max(schedule.updated_at);
+            let Some(since) = Utc.timestamp_millis_opt(since).single() else {
+                continue;
+            };
+            match schedule.schedule.latest_run_between(since, now) {
+                Ok(Some(run)) => due.push((schedule, run)),
+                Ok(None) => {}
+                Err(e) => log::warn!("提示词定时规则 {} 无效: {e}", schedule.id),
+            }
+        }
+
+        // 同一 app 只应用最晚到期的规则（同时到期时取后创建的），其余视为已被覆盖
+        due.sort_by_key(|(_, run)| *run);
+        let winners: HashMap<&str, usize> = due
+            .iter()
+            .enumerate()
+            .map(|(index, (schedule, _))| (schedule.app.as_str(), index))
+            .collect();
+
+        let mut applied = Vec::new();
+        for (index, (schedule, run)) in due.iter().enumerate() {
+            let run_at = run.timestamp_millis();
+            let error = if winners.get(schedule.app.as_str()) == Some(&index) {
+                match Self::apply(state, schedule) {
+                    Ok(()) => {
+                        log::info!(
+                            "定时切换提示词：{} 已切换到 {}",
+                            schedule.app,
+                            schedule.prompt_id
+                        );
+                        applied.push(AppliedPromptSchedule {
+                            schedule_id: schedule.id.clone(),
+                            app: schedule.app.clone(),
+                            prompt_id: schedule.prompt_id.clone(),
+                            run_at,
+                        });
+                        None
+                    }
+                    Err(e) => {
+                        log::warn!("执行提示词定时规则 {} 失败: {e}", schedule.id);
+                        Some(e.to_string())
+                    }
+                }
+            } else {
+                None
+            };
+            state
+                .db
+                .record_prompt_schedule_run(&schedule.id, run_at, error.as_deref())?;
+        }
+        Ok(applied)
+    }
+
+    fn apply(state: &AppState, schedule: &PromptSchedule) -> Result<(), AppError> {
+        let app = AppType::from_str(&schedule.app)?;
+        if !state.db.get_prompts()?.contains_key(&schedule.prompt_id) {
+            return Err(Self::prompt_not_found(&schedule.prompt_id));
+        }
+        PromptService::toggle_prompt_app(state, &schedule.prompt_id, app, true, true)
+    }
+
+    fn prompt_not_found(id: &str) -> AppError {
+        AppError::localized(
+            "prompt.not_found",
+            format!("提示词不存在: {id}"),
+            format!("Prompt not found: {id}"),
+        )
+    }
+}
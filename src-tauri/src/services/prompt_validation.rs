@@ -0,0 +1,166 @@
+//! 提示词保存前的校验
+//!
+//! 检查三类问题：
+//!
+//! - 大小：按提示词启用的每个应用，把它与该应用其余启用的提示词拼接渲染后，与该应用的大小限制
+//!   （[`crate::prompt::PromptSizeLimit`]，可在设置中覆盖）比较
+//! - 已知会让工具出错的写法：正文中出现 cc-switch 托管区域标记（会打乱区域边界）、
+//!   写入 `GEMINI.md` 的正文以 YAML frontmatter 开头（Gemini CLI 会把它当作普通文本读入）
+//! - 引用：`{{> id}}` 引用了不存在的提示词，或引用链形成循环
+//!
+//! 错误级别的问题会阻止保存，警告随保存结果返回给前端展示。
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::prompt::{Prompt, PromptIssue, PromptIssueLevel};
+use crate::prompt_files::{PROMPT_REGION_BEGIN, PROMPT_REGION_END};
+use crate::services::prompt::{render_enabled, PROMPT_APPS};
+use crate::services::prompt_include::check_includes;
+use crate::services::PromptService;
+use crate::store::AppState;
+
+impl PromptService {
+    /// 校验提示词（按保存后的启用状态），返回全部问题；不写入任何内容
+    pub fn validate_prompt(
+        state: &AppState,
+        prompt: &Prompt,
+    ) -> Result<Vec<PromptIssue>, AppError> {
+        let mut issues = content_issues(prompt);
+
+        let mut prompts = state.db.get_prompts()?;
+        if let Some(existing) = prompts.get_mut(&prompt.id) {
+            *existing = prompt.clone();
+        } else {
+            prompts.insert(prompt.id.clone(), prompt.clone());
+        }
+        if let Err(e) = check_includes(prompt, &prompts) {
+            issues.push(issue(
+                None,
+                PromptIssueLevel::Error,
+                "include",
+                e.to_string(),
+            ));
+        }
+        for app in PROMPT_APPS {
+            if prompt.apps.is_enabled_for(&app) {
+                issues.extend(size_issue(&app, &render_enabled(state, &app, &prompts)));
+            }
+        }
+        Ok(issues)
+    }
+}
+
+/// 与其他提示词无关的正文问题
+fn content_issues(prompt: &Prompt) -> Vec<PromptIssue> {
+    let mut issues = Vec::new();
+    if prompt.content.contains(PROMPT_REGION_BEGIN) || prompt.content.contains(PROMPT_REGION_END) {
+        issues.push(issue(
+            None,
+            PromptIssueLevel::Error,
+            "region_marker",
+            "正文包含 cc-switch 托管区域标记，写入后会打乱区域边界".to_string(),
+        ));
+    }
+    if prompt.apps.is_enabled_for(&AppType::Gemini) && starts_with_frontmatter(&prompt.content) {
+        issues.push(issue(
+            Some(&AppType::Gemini),
+            PromptIssueLevel::Warning,
+            "frontmatter",
+            "正文以 YAML frontmatter 开头，GEMINI.md 不解析 frontmatter，会被当作普通文本读入"
+                .to_string(),
+        ));
+    }
+    issues
+}
+
+fn size_issue(app: &AppType, rendered: &str) -> Option<PromptIssue> {
+    let size = rendered.len() as u64;
+    let limit = crate::settings::get_prompt_size_limit(app);
+    if let Some(max) = limit.max_bytes.filter(|max| size > *max) {
+        return Some(issue(
+            Some(app),
+            PromptIssueLevel::Error,
+            "too_large",
+            format!(
+                "{} 的提示词合计 {size} 字节，超过上限 {max} 字节，超出部分不会生效",
+                app.as_str()
+            ),
+        ));
+    }
+    limit.warn_bytes.filter(|warn| size > *warn).map(|warn| {
+        issue(
+            Some(app),
+            PromptIssueLevel::Warning,
+            "large",
+            format!(
+                "{} 的提示词合计 {size} 字节，超过建议的 {warn} 字节，可能影响响应速度与上下文占用",
+                app.as_str()
+            ),
+        )
+    })
+}
+
+/// 正文开头是否是一段 `---` 包围的 frontmatter
+fn starts_with_frontmatter(content: &str) -> bool {
+    let Some(rest) = content.trim_start().strip_prefix("---") else {
+        return false;
+    };
+    let Some(rest) = rest
+        .strip_prefix('\n')
+        .or_else(|| rest.strip_prefix("\r\n"))
+    else {
+        return false;
+    };
+    rest.lines().any(|line| line.trim_end() == "---")
+}
+
+fn issue(
+    app: Option<&AppType>,
+    level: PromptIssueLevel,
+    code: &str,
+    message: String,
+) -> PromptIssue {
+    PromptIssue {
+        app: app.map(|a| a.as_str().to_string()),
+        level,
+        code: code.to_string(),
+        message,
+    }
+}
+
+/// 只保留错误级别的问题
+pub(crate) fn errors_only(issues: &[PromptIssue]) -> Vec<PromptIssue> {
+    issues
+        .iter()
+        .filter(|i| i.level == PromptIssueLevel::Error)
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_frontmatter_only_at_the_start() {
+        assert!(starts_with_frontmatter("---\ntitle: x\n---\nBody"));
+        assert!(starts_with_frontmatter("\n---\r\ntitle: x\r\n---\r\n"));
+        assert!(!starts_with_frontmatter("Intro\n---\ntitle: x\n---\n"));
+        assert!(!starts_with_frontmatter("--- not frontmatter\n"));
+        assert!(!starts_with_frontmatter("---\nunterminated"));
+    }
+
+    #[test]
+    fn codex_size_limit_is_an_error_above_32_kib() {
+        let within = size_issue(&AppType::Codex, &"a".repeat(20_000));
+        assert!(within.is_none());
+
+        let large = size_issue(&AppType::Codex, &"a".repeat(30_000)).unwrap();
+        assert_eq!(large.level, PromptIssueLevel::Warning);
+        assert_eq!(large.code, "large");
+
+        let too_large = size_issue(&AppType::Codex, &"a".repeat(40_000)).unwrap();
+        assert_eq!(too_large.level, PromptIssueLevel::Error);
+        assert_eq!(too_large.app.as_deref(), Some("codex"));
+    }
+}
@@ -0,0 +1,87 @@
+//! 供应商归档
+//!
+//! 暂时不用的供应商可以归档而不是删除：归档后不出现在切换列表（托盘菜单）与故障转移队列中，
+//! 也不能被切换为当前供应商，但配置完整保留，取消归档即可恢复使用。
+//! 累加模式应用（OpenCode、OpenClaw）归档时会从 live 配置中移除该供应商。
+
+use super::{ProviderAuditOrigin, ProviderService};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+impl ProviderService {
+    /// 设置供应商的归档状态，返回更新后的供应商
+    ///
+    /// 当前供应商不能归档；归档时同时移出故障转移队列。
+    pub fn set_archived(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        archived: bool,
+    ) -> Result<Provider, AppError> {
+        let mut provider = state
+            .db
+            .get_provider_by_id(id, app_type.as_str())?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {id}"),
+                    format!("Provider not found: {id}"),
+                )
+            })?;
+        if provider.archived == archived {
+            return Ok(provider);
+        }
+        let previous = provider.clone();
+
+        if archived {
+            if app_type.is_additive_mode() {
+                Self::remove_from_live_config(state, app_type.clone(), id)?;
+            } else {
+                let current =
+                    crate::settings::get_effective_current_provider(&state.db, &app_type)?;
+                if current.as_deref() == Some(id) {
+                    return Err(AppError::localized(
+                        "provider.archive_current",
+                        "不能归档当前正在使用的供应商，请先切换到其他供应商",
+                        "The provider in use cannot be archived; switch to another provider first",
+                    ));
+                }
+            }
+        }
+
+        state
+            .db
+            .set_provider_archived(app_type.as_str(), id, archived)?;
+        provider.archived = archived;
+        if archived {
+            provider.in_failover_queue = false;
+        }
+        Self::record_update_audit(
+            state,
+            &app_type,
+            Some(&previous),
+            &provider,
+            ProviderAuditOrigin::Ui,
+        );
+        log::info!(
+            "[{}] 供应商 {id} 已{}",
+            app_type.as_str(),
+            if archived { "归档" } else { "取消归档" }
+        );
+        Ok(provider)
+    }
+
+    /// 已归档的供应商不能被切换或加入故障转移队列
+    pub(super) fn reject_archived(provider: &Provider) -> Result<(), AppError> {
+        if !provider.archived {
+            return Ok(());
+        }
+        Err(AppError::localized(
+            "provider.archived",
+            format!("供应商 {} 已归档，请先取消归档", provider.name),
+            format!("Provider {} is archived; unarchive it first", provider.name),
+        ))
+    }
+}
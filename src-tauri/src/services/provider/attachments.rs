@@ -0,0 +1,172 @@
+//! 供应商备注附件
+//!
+//! 供应商备注（`notes`）按 Markdown 渲染，可以附带文件（如发票、厂商的使用说明）。
+//! 附件复制到 `~/.cc-switch/attachments/<附件 id>/<原文件名>`，数据库只记录元数据；
+//! 删除供应商时附件一并删除。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderService;
+use crate::app_config::AppType;
+use crate::config::get_app_config_dir;
+use crate::error::AppError;
+use crate::store::AppState;
+
+/// 单个附件的大小上限
+pub const MAX_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
+
+/// 供应商备注附件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderAttachment {
+    pub id: String,
+    pub provider_id: String,
+    /// 原文件名
+    pub file_name: String,
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 添加时间（Unix 秒）
+    pub added_at: i64,
+}
+
+fn attachments_dir() -> PathBuf {
+    get_app_config_dir().join("attachments")
+}
+
+fn attachment_file(attachment: &ProviderAttachment) -> PathBuf {
+    attachments_dir()
+        .join(&attachment.id)
+        .join(&attachment.file_name)
+}
+
+/// 删除附件在磁盘上的文件（失败只记录日志）
+pub(crate) fn remove_attachment_files(id: &str) {
+    let dir = attachments_dir().join(id);
+    if dir.exists() {
+        if let Err(e) = fs::remove_dir_all(&dir) {
+            log::warn!("删除附件文件 {} 失败: {e}", dir.display());
+        }
+    }
+}
+
+fn attachment_not_found(id: &str) -> AppError {
+    AppError::localized(
+        "provider.attachment_not_found",
+        format!("附件不存在: {id}"),
+        format!("Attachment not found: {id}"),
+    )
+}
+
+impl ProviderService {
+    /// 将文件复制为供应商的附件
+    pub fn add_attachment(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        source: &Path,
+    ) -> Result<ProviderAttachment, AppError> {
+        if state
+            .db
+            .get_provider_by_id(provider_id, app_type.as_str())?
+            .is_none()
+        {
+            return Err(AppError::localized(
+                "provider.not_found",
+                format!("供应商不存在: {provider_id}"),
+                format!("Provider not found: {provider_id}"),
+            ));
+        }
+
+        let metadata = fs::metadata(source).map_err(|e| AppError::io(source, e))?;
+        let file_name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .filter(|_| metadata.is_file())
+            .ok_or_else(|| {
+                AppError::InvalidInput(format!("不是有效的文件: {}", source.display()))
+            })?;
+        if metadata.len() > MAX_ATTACHMENT_BYTES {
+            return Err(AppError::localized(
+                "provider.attachment_too_large",
+                format!(
+                    "附件 {file_name} 超过 {} MB 上限",
+                    MAX_ATTACHMENT_BYTES / 1024 / 1024
+                ),
+                format!(
+                    "Attachment {file_name} exceeds the {} MB limit",
+                    MAX_ATTACHMENT_BYTES / 1024 / 1024
+                ),
+            ));
+        }
+
+        let attachment = ProviderAttachment {
+            id: uuid::Uuid::new_v4().to_string(),
+            provider_id: provider_id.to_string(),
+            file_name,
+            size: metadata.len(),
+            added_at: chrono::Utc::now().timestamp(),
+        };
+        let target = attachment_file(&attachment);
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir).map_err(|e| AppError::io(dir, e))?;
+        }
+        fs::copy(source, &target).map_err(|e| AppError::io(&target, e))?;
+        if let Err(e) = state
+            .db
+            .insert_provider_attachment(app_type.as_str(), &attachment)
+        {
+            remove_attachment_files(&attachment.id);
+            return Err(e);
+        }
+        Ok(attachment)
+    }
+
+    /// 获取供应商的附件
+    pub fn list_attachments(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+    ) -> Result<Vec<ProviderAttachment>, AppError> {
+        state
+            .db
+            .list_provider_attachments(app_type.as_str(), provider_id)
+    }
+
+    /// 获取附件文件的路径（用于打开）
+    pub fn attachment_path(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        id: &str,
+    ) -> Result<PathBuf, AppError> {
+        let attachment = Self::list_attachments(state, app_type, provider_id)?
+            .into_iter()
+            .find(|attachment| attachment.id == id)
+            .ok_or_else(|| attachment_not_found(id))?;
+        let path = attachment_file(&attachment);
+        if !path.is_file() {
+            return Err(attachment_not_found(&attachment.file_name));
+        }
+        Ok(path)
+    }
+
+    /// 删除附件
+    pub fn remove_attachment(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        id: &str,
+    ) -> Result<(), AppError> {
+        if !state
+            .db
+            .delete_provider_attachment(app_type.as_str(), provider_id, id)?
+        {
+            return Err(attachment_not_found(id));
+        }
+        remove_attachment_files(id);
+        Ok(())
+    }
+}
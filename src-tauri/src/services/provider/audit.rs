@@ -0,0 +1,402 @@
+//! 供应商变更审计日志
+//!
+//! 记录每次供应商的创建、修改、删除、恢复、永久删除与切换：时间、变更前后的值与来源（界面、深链接、
+//! 故障转移、定时任务等），用于回答“配置是什么时候、因为什么改的”。
+//!
+//! 变更前后的值是整个供应商的 JSON，脱敏规则与本地 API、配置报告相同（见 [`secret_store::redact_secrets`]）：
+//! 应用的密钥字段、名称像凭据的字段与请求头，以及 TOML、请求头文本中内嵌的凭据都替换为 `***`，
+//! 元数据（用量查询脚本、代理密码、环境变量）同样处理。切换记录的是切换前后的当前供应商 id。
+//! 写审计日志失败只记日志，不影响变更本身。
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::{reference, ProviderService, SwitchResult};
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::secret_store;
+use crate::store::AppState;
+
+/// 默认返回的审计记录条数
+pub const DEFAULT_AUDIT_LIMIT: u32 = 200;
+
+/// 变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderAuditAction {
+    Create,
+    Update,
+    Delete,
+    /// 从回收站恢复
+    Restore,
+    /// 从回收站永久删除（变更前的值已记录在移入回收站时的删除记录中）
+    Purge,
+    Switch,
+}
+
+impl ProviderAuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Restore => "restore",
+            Self::Purge => "purge",
+            Self::Switch => "switch",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "create" => Some(Self::Create),
+            "update" => Some(Self::Update),
+            "delete" => Some(Self::Delete),
+            "restore" => Some(Self::Restore),
+            "purge" => Some(Self::Purge),
+            "switch" => Some(Self::Switch),
+            _ => None,
+        }
+    }
+}
+
+/// 变更来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderAuditOrigin {
+    /// 应用界面（包括托盘菜单）
+    Ui,
+    /// `ccswitch://` 深链接导入
+    Deeplink,
+    /// 代理故障转移自动切换
+    Failover,
+    /// 定时任务（如按保留期清理回收站）
+    Schedule,
+    /// 本地 API（自动化脚本）
+    Api,
+    /// 从旧版配置或启用状态矩阵导入
+    Import,
+}
+
+impl ProviderAuditOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ui => "ui",
+            Self::Deeplink => "deeplink",
+            Self::Failover => "failover",
+            Self::Schedule => "schedule",
+            Self::Api => "api",
+            Self::Import => "import",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ui" => Some(Self::Ui),
+            "deeplink" => Some(Self::Deeplink),
+            "failover" => Some(Self::Failover),
+            "schedule" => Some(Self::Schedule),
+            "api" => Some(Self::Api),
+            "import" => Some(Self::Import),
+            _ => None,
+        }
+    }
+}
+
+/// 一条审计记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderAuditEntry {
+    pub id: i64,
+    pub app_type: String,
+    pub provider_id: String,
+    pub action: ProviderAuditAction,
+    pub origin: ProviderAuditOrigin,
+    /// 变更前的值（创建、恢复与永久删除时为空）
+    pub old_value: Option<Value>,
+    /// 变更后的值（删除与永久删除时为空）
+    pub new_value: Option<Value>,
+    /// 变更时间（Unix 毫秒）
+    pub created_at: i64,
+}
+
+/// 脱敏后的供应商 JSON
+fn redacted(app_type: &AppType, provider: &Provider) -> Option<Value> {
+    let mut value = serde_json::to_value(provider).ok()?;
+    for field in ["settingsConfig", "meta"] {
+        if let Some(field) = value.get_mut(field) {
+            secret_store::redact_secrets(app_type, field);
+        }
+    }
+    Some(value)
+}
+
+impl ProviderService {
+    /// 写入一条审计记录
+    pub(crate) fn record_audit(
+        db: &Database,
+        app_type: &str,
+        provider_id: &str,
+        action: ProviderAuditAction,
+        origin: ProviderAuditOrigin,
+        old_value: Option<Value>,
+        new_value: Option<Value>,
+    ) {
+        let entry = ProviderAuditEntry {
+            id: 0,
+            app_type: app_type.to_string(),
+            provider_id: provider_id.to_string(),
+            action,
+            origin,
+            old_value,
+            new_value,
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        if let Err(e) = db.insert_provider_audit(&entry) {
+            log::warn!("[{app_type}] 写入供应商 {provider_id} 的审计记录失败: {e}");
+        }
+    }
+
+    /// 记录一次切换：变更前后的值为当前供应商 id
+    pub(crate) fn record_switch_audit(
+        db: &Database,
+        app_type: &str,
+        previous_id: Option<&str>,
+        provider_id: &str,
+        origin: ProviderAuditOrigin,
+    ) {
+        Self::record_audit(
+            db,
+            app_type,
+            provider_id,
+            ProviderAuditAction::Switch,
+            origin,
+            previous_id.map(|id| json!({ "providerId": id })),
+            Some(json!({ "providerId": provider_id })),
+        );
+    }
+
+    /// 记录一次修改，变更前后的值脱敏后保存
+    pub(crate) fn record_update_audit(
+        state: &AppState,
+        app_type: &AppType,
+        previous: Option<&Provider>,
+        updated: &Provider,
+        origin: ProviderAuditOrigin,
+    ) {
+        Self::record_audit(
+            &state.db,
+            app_type.as_str(),
+            &updated.id,
+            ProviderAuditAction::Update,
+            origin,
+            previous.and_then(|p| redacted(app_type, p)),
+            redacted(app_type, updated),
+        );
+    }
+
+    /// 记录一次恢复或创建，变更后的值脱敏后保存
+    pub(crate) fn record_create_audit(
+        state: &AppState,
+        app_type: &AppType,
+        provider: &Provider,
+        action: ProviderAuditAction,
+        origin: ProviderAuditOrigin,
+    ) {
+        Self::record_audit(
+            &state.db,
+            app_type.as_str(),
+            &provider.id,
+            action,
+            origin,
+            None,
+            redacted(app_type, provider),
+        );
+    }
+
+    /// 获取审计记录（按时间倒序）；`provider_id` 为 None 时返回该应用的全部记录
+    pub fn get_audit_log(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<Vec<ProviderAuditEntry>, AppError> {
+        state.db.get_provider_audit_log(
+            app_type.as_str(),
+            provider_id,
+            limit.unwrap_or(DEFAULT_AUDIT_LIMIT),
+        )
+    }
+
+    /// 添加供应商，审计记录的来源为 `origin`
+    ///
+    /// 深链接、导入文件、旧配置合并等外部来源都经由这里新增，配置中的钥匙串引用一律清空。
+    pub fn add_from(
+        state: &AppState,
+        app_type: AppType,
+        provider: Provider,
+        origin: ProviderAuditOrigin,
+    ) -> Result<bool, AppError> {
+        reference::reject_reference_origin(&provider)?;
+        let mut provider = provider;
+        let stripped = crate::secret_store::strip_references(&mut provider.settings_config);
+        if stripped > 0 {
+            log::warn!(
+                "新增供应商 {} 时清除了 {stripped} 个钥匙串引用",
+                provider.id
+            );
+        }
+        let created = provider.clone();
+        let result = Self::add_unchecked(state, app_type.clone(), provider)?;
+        Self::record_create_audit(
+            state,
+            &app_type,
+            &created,
+            ProviderAuditAction::Create,
+            origin,
+        );
+        Ok(result)
+    }
+
+    /// 修改供应商，审计记录的来源为 `origin`
+    pub fn update_from(
+        state: &AppState,
+        app_type: AppType,
+        provider: Provider,
+        origin: ProviderAuditOrigin,
+    ) -> Result<bool, AppError> {
+        reference::reject_reference_origin(&provider)?;
+        reference::ensure_editable(state, &app_type, &provider.id)?;
+        let previous = state
+            .db
+            .get_provider_by_id(&provider.id, app_type.as_str())?;
+        let updated = provider.clone();
+        let result = Self::update_unchecked(state, app_type.clone(), provider)?;
+        Self::record_update_audit(state, &app_type, previous.as_ref(), &updated, origin);
+        Ok(result)
+    }
+
+    /// 删除供应商，审计记录的来源为 `origin`
+    pub fn delete_from(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        origin: ProviderAuditOrigin,
+    ) -> Result<(), AppError> {
+        let previous = state.db.get_provider_by_id(id, app_type.as_str())?;
+        Self::delete_inner(state, app_type.clone(), id, origin)?;
+        Self::record_audit(
+            &state.db,
+            app_type.as_str(),
+            id,
+            ProviderAuditAction::Delete,
+            origin,
+            previous.as_ref().and_then(|p| redacted(&app_type, p)),
+            None,
+        );
+        Ok(())
+    }
+
+    /// 切换供应商，审计记录的来源为 `origin`
+    pub fn switch_from(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        origin: ProviderAuditOrigin,
+    ) -> Result<SwitchResult, AppError> {
+        let previous = if app_type.is_additive_mode() {
+            None
+        } else {
+            crate::settings::get_effective_current_provider(&state.db, &app_type)?
+        };
+        let result = Self::switch_inner(state, app_type.clone(), id, origin)?;
+        Self::record_switch_audit(
+            &state.db,
+            app_type.as_str(),
+            previous.as_deref(),
+            id,
+            origin,
+        );
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{ProviderMeta, ProviderProxyConfig, UsageScript};
+
+    #[test]
+    fn redacts_claude_env_and_headers() {
+        let provider = Provider::with_id(
+            "relay".to_string(),
+            "Relay".to_string(),
+            json!({
+                "env": {
+                    "ANTHROPIC_AUTH_TOKEN": "sk-token",
+                    "ANTHROPIC_BASE_URL": "https://relay.example.com",
+                    "ANTHROPIC_CUSTOM_HEADERS": "X-Team: core\nAuthorization: Bearer sk-header",
+                    "CLAUDE_CODE_MAX_OUTPUT_TOKENS": "32000"
+                }
+            }),
+            None,
+        );
+        let value = redacted(&AppType::Claude, &provider).unwrap();
+        let env = &value["settingsConfig"]["env"];
+        assert_eq!(env["ANTHROPIC_AUTH_TOKEN"], "***");
+        assert_eq!(env["ANTHROPIC_BASE_URL"], "https://relay.example.com");
+        assert_eq!(
+            env["ANTHROPIC_CUSTOM_HEADERS"],
+            "X-Team: core\nAuthorization: Bearer ***"
+        );
+        assert_eq!(env["CLAUDE_CODE_MAX_OUTPUT_TOKENS"], "32000");
+    }
+
+    #[test]
+    fn redacts_codex_toml_and_meta_secrets() {
+        let mut provider = Provider::with_id(
+            "relay".to_string(),
+            "Relay".to_string(),
+            json!({
+                "auth": { "OPENAI_API_KEY": "sk-auth" },
+                "config": "base_url = \"https://relay.example.com/v1\"\nexperimental_bearer_token = \"sk-toml\"\n"
+            }),
+            None,
+        );
+        provider.meta = Some(ProviderMeta {
+            usage_script: Some(UsageScript {
+                enabled: true,
+                language: "javascript".to_string(),
+                code: String::new(),
+                timeout: None,
+                api_key: Some("sk-usage".to_string()),
+                base_url: None,
+                access_token: Some("at-usage".to_string()),
+                user_id: None,
+                template_type: None,
+                auto_query_interval: None,
+            }),
+            proxy_config: Some(ProviderProxyConfig {
+                proxy_password: Some("hunter2".to_string()),
+                ..Default::default()
+            }),
+            env: [("RELAY_TOKEN".to_string(), "rt-env".to_string())].into(),
+            ..Default::default()
+        });
+
+        let value = redacted(&AppType::Codex, &provider).unwrap();
+        let text = value.to_string();
+        for secret in [
+            "sk-auth", "sk-toml", "sk-usage", "at-usage", "hunter2", "rt-env",
+        ] {
+            assert!(!text.contains(secret), "{secret} leaked: {text}");
+        }
+        assert!(value["settingsConfig"]["config"]
+            .as_str()
+            .unwrap()
+            .contains("base_url = \"https://relay.example.com/v1\""));
+        assert_eq!(value["meta"]["usage_script"]["apiKey"], "***");
+        assert_eq!(value["meta"]["env"]["RELAY_TOKEN"], "***");
+    }
+}
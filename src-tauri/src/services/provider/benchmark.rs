@@ -0,0 +1,209 @@
+//! 供应商延迟测速
+//!
+//! 对应用下所有供应商的接口地址并发发起一次轻量的 GET 请求，分别测量 TCP 连接、TLS 握手
+//! 与首字节耗时，结果写入 `provider_benchmarks` 表并按首字节耗时排序返回，方便切换前挑选最快的中转。
+//!
+//! - TCP 连接：直接对目标主机建立一次连接（含 DNS 解析）
+//! - 首字节：在已建立的连接上再发一次请求，从发出到收到响应头的耗时
+//! - TLS 握手：新建连接的完整请求耗时减去 TCP 连接与首字节耗时，属于估算值
+//!
+//! 经代理访问时连接建在代理上，TCP 与 TLS 两项没有意义，只记录首字节耗时。
+//! 只要收到 HTTP 响应（包括 401 / 404）就视为可达，测速不关心状态码。
+
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+
+use super::ProviderService;
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::proxy::providers::get_adapter;
+use crate::store::AppState;
+
+/// 单个请求的默认超时（秒）
+const DEFAULT_TIMEOUT_SECS: u64 = 8;
+const MIN_TIMEOUT_SECS: u64 = 2;
+const MAX_TIMEOUT_SECS: u64 = 30;
+/// 每个供应商保留的测速记录条数
+const KEEP_PER_PROVIDER: u32 = 20;
+
+/// 一个供应商的测速结果（耗时单位为毫秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderBenchmark {
+    pub provider_id: String,
+    pub provider_name: String,
+    pub url: String,
+    pub connect_ms: Option<u64>,
+    pub tls_ms: Option<u64>,
+    pub first_byte_ms: Option<u64>,
+    pub http_status: Option<u16>,
+    pub error: Option<String>,
+    /// Unix 秒
+    pub measured_at: i64,
+}
+
+impl ProviderService {
+    /// 并发测速应用下的全部供应商，保存结果并返回排序后的列表
+    pub async fn benchmark_providers(
+        state: &AppState,
+        app_type: AppType,
+        timeout_secs: Option<u64>,
+    ) -> Result<Vec<ProviderBenchmark>, AppError> {
+        let providers = state.db.get_all_providers(app_type.as_str())?;
+        let timeout = Duration::from_secs(
+            timeout_secs
+                .unwrap_or(DEFAULT_TIMEOUT_SECS)
+                .clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS),
+        );
+        let measured_at = chrono::Utc::now().timestamp();
+
+        let mut results = join_all(
+            providers
+                .values()
+                .map(|provider| measure(&app_type, provider, timeout, measured_at)),
+        )
+        .await;
+        state
+            .db
+            .save_provider_benchmarks(app_type.as_str(), &results, KEEP_PER_PROVIDER)?;
+
+        rank(&mut results);
+        Ok(results)
+    }
+
+    /// 每个现存供应商最近一次的测速结果（已排序）
+    pub fn get_provider_benchmarks(
+        state: &AppState,
+        app_type: AppType,
+    ) -> Result<Vec<ProviderBenchmark>, AppError> {
+        let providers = state.db.get_all_providers(app_type.as_str())?;
+        let mut results: Vec<ProviderBenchmark> = state
+            .db
+            .get_latest_provider_benchmarks(app_type.as_str())?
+            .into_iter()
+            .filter(|result| providers.contains_key(&result.provider_id))
+            .collect();
+        rank(&mut results);
+        Ok(results)
+    }
+}
+
+/// 成功的按首字节耗时升序，失败的排在最后
+fn rank(results: &mut [ProviderBenchmark]) {
+    results.sort_by_key(|r| (r.error.is_some(), r.first_byte_ms.unwrap_or(u64::MAX)));
+}
+
+async fn measure(
+    app_type: &AppType,
+    provider: &Provider,
+    timeout: Duration,
+    measured_at: i64,
+) -> ProviderBenchmark {
+    let mut result = ProviderBenchmark {
+        provider_id: provider.id.clone(),
+        provider_name: provider.name.clone(),
+        url: String::new(),
+        connect_ms: None,
+        tls_ms: None,
+        first_byte_ms: None,
+        http_status: None,
+        error: None,
+        measured_at,
+    };
+    if let Err(e) = run(app_type, provider, timeout, &mut result).await {
+        result.error = Some(e);
+    }
+    result
+}
+
+async fn run(
+    app_type: &AppType,
+    provider: &Provider,
+    timeout: Duration,
+    result: &mut ProviderBenchmark,
+) -> Result<(), String> {
+    result.url = get_adapter(app_type)
+        .extract_base_url(provider)
+        .map_err(|e| format!("无法获取接口地址: {e}"))?;
+    let url = Url::parse(&result.url).map_err(|e| format!("URL 无效: {e}"))?;
+
+    let proxy_config = provider.meta.as_ref().and_then(|m| m.proxy_config.as_ref());
+    let proxied = proxy_config.is_some_and(|c| c.enabled)
+        || crate::proxy::http_client::get_current_proxy_url().is_some();
+
+    if !proxied {
+        let host = url.host_str().ok_or("URL 缺少主机名")?;
+        let port = url.port_or_known_default().ok_or("URL 缺少端口")?;
+        let start = Instant::now();
+        tokio::time::timeout(timeout, TcpStream::connect((host, port)))
+            .await
+            .map_err(|_| "TCP 连接超时".to_string())?
+            .map_err(|e| format!("TCP 连接失败: {e}"))?;
+        result.connect_ms = Some(start.elapsed().as_millis() as u64);
+    }
+
+    // 新建客户端保证第一次请求从建立连接开始，第二次请求复用同一连接
+    let client = crate::proxy::http_client::build_fresh_for_provider(proxy_config)?;
+    let start = Instant::now();
+    client
+        .get(url.clone())
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| format!("请求失败: {e}"))?;
+    let cold_ms = start.elapsed().as_millis() as u64;
+
+    let start = Instant::now();
+    let response = client
+        .get(url.clone())
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| format!("请求失败: {e}"))?;
+    let first_byte_ms = start.elapsed().as_millis() as u64;
+    result.first_byte_ms = Some(first_byte_ms);
+    result.http_status = Some(response.status().as_u16());
+
+    if url.scheme() == "https" {
+        result.tls_ms = result
+            .connect_ms
+            .map(|connect_ms| cold_ms.saturating_sub(connect_ms + first_byte_ms));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn benchmark(id: &str, first_byte_ms: Option<u64>, error: Option<&str>) -> ProviderBenchmark {
+        ProviderBenchmark {
+            provider_id: id.to_string(),
+            provider_name: id.to_string(),
+            url: String::new(),
+            connect_ms: None,
+            tls_ms: None,
+            first_byte_ms,
+            http_status: None,
+            error: error.map(str::to_string),
+            measured_at: 0,
+        }
+    }
+
+    #[test]
+    fn rank_puts_fastest_first_and_failures_last() {
+        let mut results = vec![
+            benchmark("down", None, Some("TCP 连接超时")),
+            benchmark("slow", Some(900), None),
+            benchmark("fast", Some(120), None),
+        ];
+        rank(&mut results);
+        let order: Vec<_> = results.iter().map(|r| r.provider_id.as_str()).collect();
+        assert_eq!(order, vec!["fast", "slow", "down"]);
+    }
+}
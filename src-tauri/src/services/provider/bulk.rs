@@ -0,0 +1,121 @@
+//! 供应商批量编辑
+//!
+//! 对选中的多个供应商的 `settingsConfig` 应用同一个 JSON Merge Patch（RFC 7396：`null` 删除字段，
+//! 对象递归合并，其余直接覆盖），例如统一修改 `env.ANTHROPIC_MODEL`。
+//! 全部供应商校验通过后才在同一个事务中写入；之后再为受影响的当前供应商（累加模式应用为全部）
+//! 同步 live 配置，同步失败只作为警告返回。每个配置发生变化的供应商各记一条修改审计。
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::live::json_merge_patch;
+use super::{reference, ProviderAuditOrigin, ProviderService};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::store::AppState;
+
+/// 批量编辑结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkUpdateResult {
+    /// 配置发生变化的供应商
+    pub updated: Vec<String>,
+    /// 应用补丁后配置没有变化的供应商
+    pub unchanged: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ProviderService {
+    /// 对多个供应商的 `settingsConfig` 应用同一个 JSON Merge Patch
+    pub fn bulk_update(
+        state: &AppState,
+        app_type: AppType,
+        ids: &[String],
+        patch: &Value,
+    ) -> Result<BulkUpdateResult, AppError> {
+        if !patch.is_object() || patch.as_object().is_some_and(|p| p.is_empty()) {
+            return Err(AppError::localized(
+                "provider.bulk_patch_invalid",
+                "批量编辑的补丁必须是非空的 JSON 对象",
+                "The bulk edit patch must be a non-empty JSON object",
+            ));
+        }
+
+        let mut providers = state.db.get_all_providers(app_type.as_str())?;
+        let mut result = BulkUpdateResult::default();
+        let mut previous = Vec::new();
+        let mut changed = Vec::new();
+        for id in ids {
+            if result.updated.contains(id) || result.unchanged.contains(id) {
+                continue;
+            }
+            let mut provider = providers.shift_remove(id).ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {id}"),
+                    format!("Provider not found: {id}"),
+                )
+            })?;
+            reference::ensure_editable(state, &app_type, id)?;
+
+            let before = provider.clone();
+            json_merge_patch(&mut provider.settings_config, patch);
+            Self::normalize_provider_if_claude(&app_type, &mut provider);
+            if provider.settings_config == before.settings_config {
+                result.unchanged.push(id.clone());
+                continue;
+            }
+            Self::validate_provider_settings(&app_type, &provider)?;
+            result.updated.push(id.clone());
+            previous.push(before);
+            changed.push(provider);
+        }
+        if changed.is_empty() {
+            return Ok(result);
+        }
+
+        let updates: Vec<_> = changed
+            .iter()
+            .map(|p| (p.id.clone(), p.settings_config.clone()))
+            .collect();
+        state
+            .db
+            .update_providers_settings(app_type.as_str(), &updates)?;
+        log::info!(
+            "[{}] 批量编辑了 {} 个供应商",
+            app_type.as_str(),
+            changed.len()
+        );
+
+        for (before, after) in previous.iter().zip(&changed) {
+            Self::record_update_audit(
+                state,
+                &app_type,
+                Some(before),
+                after,
+                ProviderAuditOrigin::Ui,
+            );
+        }
+
+        let current = crate::settings::get_effective_current_provider(&state.db, &app_type)?;
+        for provider in changed {
+            state
+                .db
+                .clear_credential_issue(&provider.id, app_type.as_str())?;
+            if let Err(e) =
+                super::McpBindingService::refresh_for_provider(state, &app_type, &provider.id)
+            {
+                log::warn!("刷新依赖供应商 {} 的 MCP 服务器失败: {e}", provider.id);
+            }
+            // 配置已在上面的事务中写入数据库，这里只同步 live 配置
+            if app_type.is_additive_mode() || current.as_deref() == Some(provider.id.as_str()) {
+                if let Err(e) = Self::sync_saved_to_live(state, &app_type, &provider) {
+                    result
+                        .warnings
+                        .push(format!("同步供应商 {} 的 live 配置失败: {e}", provider.id));
+                }
+            }
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,253 @@
+//! 供应商凭据问题识别
+//!
+//! 健康检查与代理转发都会拿到上游的认证错误（401 / 402 / 403，以及部分返回 429 的额度错误）。
+//! 这里根据状态码与错误正文区分「凭据过期」「凭据无效」「额度耗尽」三类问题，
+//! 记录到供应商上，并给出对应的处理建议（重新登录 / 充值 / 更换密钥）。
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderService;
+use crate::database::Database;
+use crate::error::AppError;
+
+/// 错误正文保存的最大字符数
+const MAX_MESSAGE_CHARS: usize = 500;
+
+const QUOTA_KEYWORDS: &[&str] = &[
+    "insufficient_quota",
+    "quota",
+    "balance",
+    "credit",
+    "billing",
+    "余额",
+    "额度",
+    "欠费",
+];
+const EXPIRED_KEYWORDS: &[&str] = &["expire", "过期"];
+const AUTH_KEYWORDS: &[&str] = &[
+    "api key",
+    "api_key",
+    "apikey",
+    "token",
+    "unauthorized",
+    "authentication",
+    "invalid",
+    "密钥",
+];
+
+/// 凭据问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialIssueKind {
+    /// 令牌 / 登录态已过期
+    Expired,
+    /// 密钥错误、被吊销或无权限
+    Invalid,
+    /// 余额或额度耗尽
+    QuotaExhausted,
+}
+
+impl CredentialIssueKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Expired => "expired",
+            Self::Invalid => "invalid",
+            Self::QuotaExhausted => "quota_exhausted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "expired" => Some(Self::Expired),
+            "invalid" => Some(Self::Invalid),
+            "quota_exhausted" => Some(Self::QuotaExhausted),
+            _ => None,
+        }
+    }
+
+    /// 对应的处理建议
+    pub fn remediation(&self) -> Remediation {
+        match self {
+            Self::Expired => Remediation::Relogin,
+            Self::Invalid => Remediation::RotateKey,
+            Self::QuotaExhausted => Remediation::TopUp,
+        }
+    }
+}
+
+/// 问题来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSource {
+    /// 流式健康检查
+    HealthCheck,
+    /// 代理转发的真实请求
+    Proxy,
+}
+
+impl IssueSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::HealthCheck => "health_check",
+            Self::Proxy => "proxy",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "health_check" => Some(Self::HealthCheck),
+            "proxy" => Some(Self::Proxy),
+            _ => None,
+        }
+    }
+}
+
+/// 处理建议
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Remediation {
+    /// 重新登录以刷新令牌
+    Relogin,
+    /// 充值或提升额度
+    TopUp,
+    /// 更换 API Key
+    RotateKey,
+}
+
+/// 记录在供应商上的凭据问题（对应 `provider_credential_issues` 表）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialIssue {
+    pub provider_id: String,
+    pub app_type: String,
+    pub kind: CredentialIssueKind,
+    pub http_status: u16,
+    /// 上游错误正文（截断）
+    pub message: String,
+    pub source: IssueSource,
+    /// 识别时间（Unix 秒）
+    pub detected_at: i64,
+}
+
+/// 面向前端的供应商问题，附带处理建议
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderIssue {
+    #[serde(flatten)]
+    pub issue: CredentialIssue,
+    pub remediation: Remediation,
+}
+
+/// 根据状态码与错误正文识别凭据问题；与凭据无关的错误返回 None
+///
+/// - 402 一律视为额度耗尽
+/// - 401 按正文区分额度 / 过期，其余视为凭据无效
+/// - 403 只有正文明确指向额度或凭据时才识别（避免把地区限制等误判为密钥问题）
+/// - 429 只有正文提到额度 / 余额时才识别（普通限流不算）
+pub fn classify_auth_error(status: u16, body: &str) -> Option<CredentialIssueKind> {
+    let text = body.to_lowercase();
+    let mentions = |keywords: &[&str]| keywords.iter().any(|k| text.contains(k));
+
+    match status {
+        402 => Some(CredentialIssueKind::QuotaExhausted),
+        401 | 403 if mentions(QUOTA_KEYWORDS) => Some(CredentialIssueKind::QuotaExhausted),
+        401 | 403 if mentions(EXPIRED_KEYWORDS) => Some(CredentialIssueKind::Expired),
+        401 => Some(CredentialIssueKind::Invalid),
+        403 if mentions(AUTH_KEYWORDS) => Some(CredentialIssueKind::Invalid),
+        429 if mentions(QUOTA_KEYWORDS) => Some(CredentialIssueKind::QuotaExhausted),
+        _ => None,
+    }
+}
+
+impl ProviderService {
+    /// 根据一次请求结果更新供应商的凭据问题
+    ///
+    /// 成功时清除已记录的问题；失败且能识别为凭据问题时记录（覆盖旧记录）；
+    /// 其他失败（超时、5xx 等）不改变已有记录。
+    pub fn record_credential_outcome(
+        db: &Database,
+        app_type: &str,
+        provider_id: &str,
+        success: bool,
+        http_status: Option<u16>,
+        body: &str,
+        source: IssueSource,
+    ) -> Result<(), AppError> {
+        if success {
+            if db.has_credential_issue(provider_id, app_type)? {
+                db.clear_credential_issue(provider_id, app_type)?;
+            }
+            return Ok(());
+        }
+        let Some(status) = http_status else {
+            return Ok(());
+        };
+        let Some(kind) = classify_auth_error(status, body) else {
+            return Ok(());
+        };
+
+        log::warn!(
+            "[{app_type}] 供应商 {provider_id} 凭据问题: {} (HTTP {status})",
+            kind.as_str()
+        );
+        db.save_credential_issue(&CredentialIssue {
+            provider_id: provider_id.to_string(),
+            app_type: app_type.to_string(),
+            kind,
+            http_status: status,
+            message: body.chars().take(MAX_MESSAGE_CHARS).collect(),
+            source,
+            detected_at: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    /// 获取供应商当前的问题及处理建议
+    pub fn get_issues(
+        db: &Database,
+        app_type: &str,
+        provider_id: &str,
+    ) -> Result<Vec<ProviderIssue>, AppError> {
+        Ok(db
+            .get_credential_issue(provider_id, app_type)?
+            .into_iter()
+            .map(|issue| ProviderIssue {
+                remediation: issue.kind.remediation(),
+                issue,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_auth_errors() {
+        use CredentialIssueKind::*;
+
+        let cases = [
+            (401, "Invalid API key", Some(Invalid)),
+            (401, "OAuth token has expired", Some(Expired)),
+            (401, "令牌已过期，请重新登录", Some(Expired)),
+            (401, "账户余额不足", Some(QuotaExhausted)),
+            (402, "", Some(QuotaExhausted)),
+            (403, "Request not allowed in your region", None),
+            (403, "invalid x-api-key", Some(Invalid)),
+            (
+                429,
+                r#"{"type":"insufficient_quota"}"#,
+                Some(QuotaExhausted),
+            ),
+            (429, "Rate limit reached, retry later", None),
+            (500, "expired", None),
+        ];
+        for (status, body, expected) in cases {
+            assert_eq!(
+                classify_auth_error(status, body),
+                expected,
+                "status {status}, body {body:?}"
+            );
+        }
+    }
+}
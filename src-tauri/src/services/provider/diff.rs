@@ -0,0 +1,221 @@
+//! 比较两个供应商的配置
+//!
+//! 逐字段比较两个供应商的 `settingsConfig`，按 JSON 路径（如 `$.env.ANTHROPIC_BASE_URL`）列出差异，
+//! 方便在切换或合并两个相似的中转配置前确认具体区别。
+//! Codex 的 `config` 是 TOML 文本，能解析时按 TOML 的结构比较，而不是整段文本。
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::ProviderService;
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 差异类型（相对左侧供应商）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    /// 只有右侧有
+    Added,
+    /// 只有左侧有
+    Removed,
+    /// 两侧都有但值不同
+    Changed,
+}
+
+/// 一处差异
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderDiffEntry {
+    pub path: String,
+    pub kind: DiffKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right: Option<Value>,
+}
+
+/// 两个供应商配置的比较结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderDiff {
+    pub left_id: String,
+    pub right_id: String,
+    /// 按路径排序的差异；为空表示配置相同
+    pub entries: Vec<ProviderDiffEntry>,
+}
+
+/// 对象键的路径片段：标识符用 `.key`，其他用 `["key"]`
+fn key_segment(key: &str) -> String {
+    let is_ident = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_ident {
+        format!(".{key}")
+    } else {
+        format!("[{}]", Value::String(key.to_string()))
+    }
+}
+
+fn diff_values(path: &str, left: &Value, right: &Value, out: &mut Vec<ProviderDiffEntry>) {
+    match (left, right) {
+        (Value::Object(l), Value::Object(r)) => {
+            let keys: BTreeSet<&String> = l.keys().chain(r.keys()).collect();
+            for key in keys {
+                let child = format!("{path}{}", key_segment(key));
+                match (l.get(key), r.get(key)) {
+                    (Some(lv), Some(rv)) => diff_values(&child, lv, rv, out),
+                    (Some(lv), None) => out.push(ProviderDiffEntry {
+                        path: child,
+                        kind: DiffKind::Removed,
+                        left: Some(lv.clone()),
+                        right: None,
+                    }),
+                    (None, Some(rv)) => out.push(ProviderDiffEntry {
+                        path: child,
+                        kind: DiffKind::Added,
+                        left: None,
+                        right: Some(rv.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(l), Value::Array(r)) => {
+            for i in 0..l.len().max(r.len()) {
+                let child = format!("{path}[{i}]");
+                match (l.get(i), r.get(i)) {
+                    (Some(lv), Some(rv)) => diff_values(&child, lv, rv, out),
+                    (lv, rv) => out.push(ProviderDiffEntry {
+                        path: child,
+                        kind: if lv.is_some() {
+                            DiffKind::Removed
+                        } else {
+                            DiffKind::Added
+                        },
+                        left: lv.cloned(),
+                        right: rv.cloned(),
+                    }),
+                }
+            }
+        }
+        _ if left != right => out.push(ProviderDiffEntry {
+            path: path.to_string(),
+            kind: DiffKind::Changed,
+            left: Some(left.clone()),
+            right: Some(right.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// 参与比较的配置：Codex 的 TOML 文本解析为结构
+fn comparable_config(app_type: &AppType, provider: &Provider) -> Value {
+    let mut config = provider.settings_config.clone();
+    if matches!(app_type, AppType::Codex) {
+        if let Some(parsed) = config
+            .get("config")
+            .and_then(Value::as_str)
+            .and_then(|text| toml::from_str::<toml::Value>(text).ok())
+            .and_then(|table| serde_json::to_value(table).ok())
+        {
+            config["config"] = parsed;
+        }
+    }
+    config
+}
+
+impl ProviderService {
+    /// 比较两个供应商的 `settingsConfig`
+    pub fn diff(
+        state: &AppState,
+        app_type: AppType,
+        left_id: &str,
+        right_id: &str,
+    ) -> Result<ProviderDiff, AppError> {
+        let load = |id: &str| {
+            state
+                .db
+                .get_provider_by_id(id, app_type.as_str())?
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {id}"),
+                        format!("Provider not found: {id}"),
+                    )
+                })
+        };
+        let left = comparable_config(&app_type, &load(left_id)?);
+        let right = comparable_config(&app_type, &load(right_id)?);
+
+        let mut entries = Vec::new();
+        diff_values("$", &left, &right, &mut entries);
+        Ok(ProviderDiff {
+            left_id: left_id.to_string(),
+            right_id: right_id.to_string(),
+            entries,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn diff(left: Value, right: Value) -> Vec<ProviderDiffEntry> {
+        let mut out = Vec::new();
+        diff_values("$", &left, &right, &mut out);
+        out
+    }
+
+    #[test]
+    fn lists_added_removed_and_changed_paths() {
+        let entries = diff(
+            json!({"env": {"ANTHROPIC_BASE_URL": "https://a", "OLD": "1"}, "models": ["x", "y"]}),
+            json!({"env": {"ANTHROPIC_BASE_URL": "https://b", "api-mode": "fast"}, "models": ["x"]}),
+        );
+        let summary: Vec<(&str, DiffKind)> =
+            entries.iter().map(|e| (e.path.as_str(), e.kind)).collect();
+        assert_eq!(
+            summary,
+            [
+                ("$.env.ANTHROPIC_BASE_URL", DiffKind::Changed),
+                ("$.env.OLD", DiffKind::Removed),
+                ("$.env[\"api-mode\"]", DiffKind::Added),
+                ("$.models[1]", DiffKind::Removed),
+            ]
+        );
+        assert_eq!(entries[0].right, Some(json!("https://b")));
+        assert!(diff(json!({"a": 1}), json!({"a": 1})).is_empty());
+    }
+
+    #[test]
+    fn codex_toml_config_is_compared_structurally() {
+        let provider = |config: &str| {
+            Provider::with_id(
+                "p".to_string(),
+                "p".to_string(),
+                json!({"auth": {}, "config": config}),
+                None,
+            )
+        };
+        let left = comparable_config(
+            &AppType::Codex,
+            &provider("model = \"gpt-5\"\nmodel_provider = \"relay\"\n"),
+        );
+        let right = comparable_config(
+            &AppType::Codex,
+            &provider("model_provider = \"relay\"\nmodel = \"gpt-5-codex\"\n"),
+        );
+        let entries = diff(left, right);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "$.config.model");
+    }
+}
@@ -0,0 +1,215 @@
+//! 端点健康检查与自动选择
+//!
+//! 供应商可以保存多个自定义端点（`provider_endpoints`）。每次测速都会记录端点的延迟与连续失败次数：
+//! 最近一次测速成功（连续失败为 0）且有延迟记录的端点视为健康。
+//!
+//! 开启了自动选择（`endpointAutoSelect`）的供应商在切换时，会把配置中的 Base URL 改为当前最快的
+//! 健康端点后再写入 live 配置；没有健康端点时保持原配置。改写后的配置在切换成功后才保存并记入审计，
+//! 只读引用不参与改写。后台定时探测这些供应商的端点，只有一个端点的供应商无需选择，不参与探测。
+
+use serde_json::{json, Value};
+
+use super::{ProviderAuditOrigin, ProviderService};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::proxy::providers::get_adapter;
+use crate::services::{ProxyService, SpeedtestService};
+use crate::settings::CustomEndpoint;
+use crate::store::AppState;
+
+/// 后台探测端点的间隔（秒）
+pub const ENDPOINT_PROBE_INTERVAL_SECS: u64 = 30 * 60;
+
+/// 支持自动选择端点的应用
+const AUTO_SELECT_APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+fn auto_select_enabled(provider: &Provider) -> bool {
+    provider
+        .meta
+        .as_ref()
+        .is_some_and(|meta| meta.endpoint_auto_select == Some(true))
+}
+
+/// 延迟最低的健康端点；延迟相同时按 URL 排序，保证结果稳定
+fn fastest_healthy_endpoint(provider: &Provider) -> Option<&CustomEndpoint> {
+    provider
+        .meta
+        .as_ref()?
+        .custom_endpoints
+        .values()
+        .filter(|endpoint| endpoint.consecutive_failures == 0)
+        .filter_map(|endpoint| endpoint.last_latency_ms.map(|ms| (ms, endpoint)))
+        .min_by(|(a_ms, a), (b_ms, b)| a_ms.cmp(b_ms).then_with(|| a.url.cmp(&b.url)))
+        .map(|(_, endpoint)| endpoint)
+}
+
+/// 把配置中的 Base URL 改为 `url`，返回是否支持该应用
+fn set_base_url(app_type: &AppType, settings: &mut Value, url: &str) -> bool {
+    let env_key = match app_type {
+        AppType::Claude => "ANTHROPIC_BASE_URL",
+        AppType::Gemini => "GOOGLE_GEMINI_BASE_URL",
+        AppType::Codex => {
+            let Some(config) = settings.get("config").and_then(Value::as_str) else {
+                return false;
+            };
+            settings["config"] = json!(ProxyService::update_toml_base_url(config, url));
+            return true;
+        }
+        _ => return false,
+    };
+    if !settings.get("env").is_some_and(Value::is_object) {
+        settings["env"] = json!({});
+    }
+    settings["env"][env_key] = json!(url);
+    true
+}
+
+impl ProviderService {
+    /// 开启了自动选择的供应商：返回改用最快健康端点的配置（不写数据库）；无需改写时返回 None
+    pub(super) fn fastest_endpoint_settings(
+        app_type: &AppType,
+        provider: &Provider,
+    ) -> Option<(CustomEndpoint, Value)> {
+        if provider.is_reference() || !auto_select_enabled(provider) {
+            return None;
+        }
+        let endpoint = fastest_healthy_endpoint(provider)?;
+        let current = get_adapter(app_type).extract_base_url(provider).ok();
+        if current.as_deref().map(|url| url.trim_end_matches('/')) == Some(endpoint.url.as_str()) {
+            return None;
+        }
+
+        let mut settings = provider.settings_config.clone();
+        if !set_base_url(app_type, &mut settings, &endpoint.url) {
+            return None;
+        }
+        Some((endpoint.clone(), settings))
+    }
+
+    /// 切换成功后保存自动选择端点改写的配置，审计记录的来源为 `origin`
+    pub(super) fn save_fastest_endpoint(
+        state: &AppState,
+        app_type: &AppType,
+        previous: &Provider,
+        endpoint: &CustomEndpoint,
+        settings: &Value,
+        origin: ProviderAuditOrigin,
+    ) -> Result<(), AppError> {
+        state
+            .db
+            .update_provider_settings_config(app_type.as_str(), &previous.id, settings)?;
+        state.db.touch_custom_endpoint(
+            app_type.as_str(),
+            &previous.id,
+            &endpoint.url,
+            chrono::Utc::now().timestamp_millis(),
+        )?;
+        let mut updated = previous.clone();
+        updated.settings_config = settings.clone();
+        Self::record_update_audit(state, app_type, Some(previous), &updated, origin);
+        log::info!(
+            "[{}] 供应商 {} 自动选择端点 {}（{}ms）",
+            app_type.as_str(),
+            previous.id,
+            endpoint.url,
+            endpoint.last_latency_ms.unwrap_or_default()
+        );
+        Ok(())
+    }
+
+    /// 探测所有开启了自动选择的供应商的端点并记录结果，返回探测的端点数
+    pub async fn probe_auto_select_endpoints(state: &AppState) -> Result<usize, AppError> {
+        let mut probed = 0;
+        for app_type in AUTO_SELECT_APPS {
+            let providers = state.db.get_all_providers(app_type.as_str())?;
+            for provider in providers.values() {
+                if provider.archived || !auto_select_enabled(provider) {
+                    continue;
+                }
+                let urls: Vec<String> = provider
+                    .meta
+                    .as_ref()
+                    .map(|meta| meta.custom_endpoints.keys().cloned().collect())
+                    .unwrap_or_default();
+                if urls.len() < 2 {
+                    continue;
+                }
+                probed += urls.len();
+                let results = SpeedtestService::test_endpoints(urls, None).await?;
+                Self::record_endpoint_benchmarks(state, app_type.clone(), &provider.id, &results)?;
+            }
+        }
+        Ok(probed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(url: &str, latency: Option<u64>, failures: u32) -> (String, CustomEndpoint) {
+        (
+            url.to_string(),
+            CustomEndpoint {
+                url: url.to_string(),
+                added_at: 0,
+                last_used: None,
+                last_benchmarked: Some(1),
+                consecutive_failures: failures,
+                last_latency_ms: latency,
+            },
+        )
+    }
+
+    #[test]
+    fn picks_fastest_healthy_endpoint_and_rewrites_base_url() {
+        let mut provider = Provider::with_id(
+            "relay".to_string(),
+            "Relay".to_string(),
+            json!({"env": {"ANTHROPIC_BASE_URL": "https://a.example.com"}}),
+            None,
+        );
+        provider.meta = Some(crate::provider::ProviderMeta {
+            custom_endpoints: [
+                endpoint("https://a.example.com", Some(300), 0),
+                endpoint("https://b.example.com", Some(80), 0),
+                // 曾经最快，但最近一次测速失败
+                endpoint("https://c.example.com", Some(20), 1),
+                endpoint("https://d.example.com", None, 0),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        });
+
+        let fastest = fastest_healthy_endpoint(&provider).expect("healthy endpoint");
+        assert_eq!(fastest.url, "https://b.example.com");
+
+        let mut settings = provider.settings_config.clone();
+        assert!(set_base_url(&AppType::Claude, &mut settings, &fastest.url));
+        assert_eq!(
+            settings["env"]["ANTHROPIC_BASE_URL"],
+            "https://b.example.com"
+        );
+
+        let mut codex = json!({
+            "auth": {},
+            "config": "model_provider = \"relay\"\n\n[model_providers.relay]\nbase_url = \"https://a.example.com/v1\"\n"
+        });
+        assert!(set_base_url(
+            &AppType::Codex,
+            &mut codex,
+            "https://b.example.com/v1"
+        ));
+        assert!(codex["config"]
+            .as_str()
+            .unwrap()
+            .contains("base_url = \"https://b.example.com/v1\""));
+        assert!(!set_base_url(
+            &AppType::OpenCode,
+            &mut json!({}),
+            "https://b.example.com"
+        ));
+    }
+}
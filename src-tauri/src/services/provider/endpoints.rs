@@ -4,11 +4,28 @@
 
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use serde::{Deserialize, Serialize};
+
 use crate::app_config::AppType;
 use crate::error::AppError;
+use crate::services::speedtest::EndpointLatency;
 use crate::settings::CustomEndpoint;
 use crate::store::AppState;
 
+/// 连续测速失败达到该次数的端点视为失效
+pub const DEFAULT_STALE_FAILURES: u32 = 3;
+
+/// 连续测速失败、建议移除的端点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleEndpoint {
+    pub provider_id: String,
+    pub url: String,
+    pub consecutive_failures: u32,
+    pub last_benchmarked_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+}
+
 /// Get custom endpoints list for a provider
 pub fn get_custom_endpoints(
     state: &AppState,
@@ -27,7 +44,7 @@ pub fn get_custom_endpoints(
     }
 
     let mut result: Vec<_> = meta.custom_endpoints.values().cloned().collect();
-    result.sort_by(|a, b| b.added_at.cmp(&a.added_at));
+    result.sort_by_key(|endpoint| std::cmp::Reverse(endpoint.added_at));
     Ok(result)
 }
 
@@ -75,20 +92,76 @@ pub fn update_endpoint_last_used(
     url: String,
 ) -> Result<(), AppError> {
     let normalized = url.trim().trim_end_matches('/').to_string();
+    state
+        .db
+        .touch_custom_endpoint(app_type.as_str(), provider_id, &normalized, now_millis())?;
+    Ok(())
+}
 
-    // Get provider, update last_used, save back
-    let mut providers = state.db.get_all_providers(app_type.as_str())?;
-    if let Some(provider) = providers.get_mut(provider_id) {
-        if let Some(meta) = provider.meta.as_mut() {
-            if let Some(endpoint) = meta.custom_endpoints.get_mut(&normalized) {
-                endpoint.last_used = Some(now_millis());
-                state.db.save_provider(app_type.as_str(), provider)?;
-            }
-        }
+/// Record speed-test results for a provider's custom endpoints
+///
+/// Results for URLs that are not saved endpoints of the provider are ignored.
+pub fn record_endpoint_benchmarks(
+    state: &AppState,
+    app_type: AppType,
+    provider_id: &str,
+    results: &[EndpointLatency],
+) -> Result<(), AppError> {
+    let now = now_millis();
+    for result in results {
+        let normalized = result.url.trim().trim_end_matches('/');
+        let latency_ms = result
+            .latency
+            .filter(|_| result.error.is_none())
+            .map(|ms| ms as u64);
+        state.db.record_endpoint_benchmark(
+            app_type.as_str(),
+            provider_id,
+            normalized,
+            latency_ms,
+            now,
+        )?;
     }
     Ok(())
 }
 
+/// List endpoints that failed `min_failures` benchmarks in a row, removing them unless `dry_run`
+///
+/// Endpoints of read-only reference providers are reported but never removed.
+pub fn prune_stale_endpoints(
+    state: &AppState,
+    app_type: AppType,
+    provider_id: Option<&str>,
+    min_failures: u32,
+    dry_run: bool,
+) -> Result<Vec<StaleEndpoint>, AppError> {
+    let stale =
+        state
+            .db
+            .get_stale_endpoints(app_type.as_str(), provider_id, min_failures.max(1))?;
+    if dry_run {
+        return Ok(stale);
+    }
+
+    let mut removed = Vec::with_capacity(stale.len());
+    for endpoint in stale {
+        if super::reference::ensure_editable(state, &app_type, &endpoint.provider_id).is_err() {
+            continue;
+        }
+        state
+            .db
+            .remove_custom_endpoint(app_type.as_str(), &endpoint.provider_id, &endpoint.url)?;
+        log::info!(
+            "移除连续 {} 次测速失败的端点 {} ({})",
+            endpoint.consecutive_failures,
+            endpoint.url,
+            endpoint.provider_id
+        );
+        removed.push(endpoint);
+    }
+    Ok(removed)
+}
+
 /// Get current timestamp in milliseconds
 fn now_millis() -> i64 {
     SystemTime::now()
@@ -0,0 +1,204 @@
+//! 供应商环境变量注入
+//!
+//! 有些工具只读取环境变量（如 `ANTHROPIC_BASE_URL`）而不读配置文件。供应商元数据可以声明一组
+//! 环境变量（`env`），切换供应商后这些变量会写入 `~/.cc-switch/env/<app>.{sh,fish,ps1}`：
+//! 在 shell 启动文件中 source 对应脚本即可在新终端中生效。其他供应商声明过、当前供应商没有的变量
+//! 会在脚本中取消设置，避免切换后残留上一个供应商的值。
+//!
+//! 脚本可能包含密钥，在 Unix 上以 600 权限写入。累加模式应用（OpenCode、OpenClaw）没有
+//! “当前供应商”，不生成脚本。
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::ProviderService;
+use crate::app_config::AppType;
+use crate::config::{get_app_config_dir, write_text_file};
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 环境变量脚本
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderEnvScript {
+    /// `sh`（bash / zsh）、`fish` 或 `powershell`
+    pub shell: String,
+    pub path: String,
+    /// 需要加入 shell 启动文件的一行
+    pub source_line: String,
+}
+
+/// 支持的 shell：名称、扩展名
+const SHELLS: [(&str, &str); 3] = [("sh", "sh"), ("fish", "fish"), ("powershell", "ps1")];
+
+fn env_script_path(app_type: &AppType, ext: &str) -> PathBuf {
+    get_app_config_dir()
+        .join("env")
+        .join(format!("{}.{ext}", app_type.as_str()))
+}
+
+fn is_valid_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 校验供应商声明的环境变量名
+pub(super) fn validate_env(provider: &Provider) -> Result<(), AppError> {
+    let Some(meta) = &provider.meta else {
+        return Ok(());
+    };
+    if let Some(name) = meta.env.keys().find(|name| !is_valid_env_name(name)) {
+        return Err(AppError::localized(
+            "provider.env_invalid_name",
+            format!("环境变量名无效: {name}（只能包含字母、数字与下划线，且不能以数字开头）"),
+            format!(
+                "Invalid environment variable name: {name} (letters, digits and underscores only, not starting with a digit)"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// 生成脚本内容：`set` 为要导出的变量，`unset` 为要取消的变量
+fn render_script(shell: &str, set: &BTreeMap<String, String>, unset: &[&String]) -> String {
+    let comment = "# Generated by CC Switch. Do not edit; changes are overwritten on switch.\n";
+    let mut out = String::from(comment);
+    for name in unset {
+        out.push_str(&match shell {
+            "fish" => format!("set -e {name}\n"),
+            "powershell" => format!("Remove-Item Env:{name} -ErrorAction SilentlyContinue\n"),
+            _ => format!("unset {name}\n"),
+        });
+    }
+    for (name, value) in set {
+        out.push_str(&match shell {
+            "fish" => format!(
+                "set -gx {name} '{}'\n",
+                value.replace('\\', "\\\\").replace('\'', "\\'")
+            ),
+            "powershell" => format!("$env:{name} = '{}'\n", value.replace('\'', "''")),
+            _ => format!("export {name}='{}'\n", value.replace('\'', "'\\''")),
+        });
+    }
+    out
+}
+
+fn source_line(shell: &str, path: &str) -> String {
+    match shell {
+        "powershell" => format!(". \"{path}\""),
+        "fish" => format!("source \"{path}\""),
+        _ => format!("[ -f \"{path}\" ] && . \"{path}\""),
+    }
+}
+
+impl ProviderService {
+    /// 按当前供应商重新生成环境变量脚本，返回各 shell 的脚本
+    pub fn write_env_scripts(
+        state: &AppState,
+        app_type: &AppType,
+    ) -> Result<Vec<ProviderEnvScript>, AppError> {
+        if app_type.is_additive_mode() {
+            return Err(AppError::localized(
+                "provider.env_additive_app",
+                format!(
+                    "{} 的供应商同时生效，不支持按当前供应商注入环境变量",
+                    app_type.as_str()
+                ),
+                format!(
+                    "{} uses all providers at once and has no current provider to export variables for",
+                    app_type.as_str()
+                ),
+            ));
+        }
+        let providers = state.db.get_all_providers(app_type.as_str())?;
+        let current = crate::settings::get_effective_current_provider(&state.db, app_type)?;
+        let set: BTreeMap<String, String> = current
+            .and_then(|id| providers.get(&id))
+            .and_then(|p| p.meta.as_ref())
+            .map(|meta| meta.env.clone().into_iter().collect())
+            .unwrap_or_default();
+        let declared: BTreeSet<&String> = providers
+            .values()
+            .filter_map(|p| p.meta.as_ref())
+            .flat_map(|meta| meta.env.keys())
+            .collect();
+        let unset: Vec<&String> = declared
+            .into_iter()
+            .filter(|name| !set.contains_key(*name))
+            .collect();
+
+        let mut scripts = Vec::new();
+        for (shell, ext) in SHELLS {
+            let path = env_script_path(app_type, ext);
+            write_text_file(&path, &render_script(shell, &set, &unset))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+                    .map_err(|e| AppError::io(&path, e))?;
+            }
+            let path = path.to_string_lossy().to_string();
+            scripts.push(ProviderEnvScript {
+                shell: shell.to_string(),
+                source_line: source_line(shell, &path),
+                path,
+            });
+        }
+        Ok(scripts)
+    }
+
+    /// 切换或编辑当前供应商后刷新环境变量脚本
+    ///
+    /// 只在有供应商声明了环境变量、或脚本已经生成过时写入，未使用该功能时不产生文件。
+    pub(super) fn refresh_env_scripts(state: &AppState, app_type: &AppType) {
+        if app_type.is_additive_mode() {
+            return;
+        }
+        let in_use = env_script_path(app_type, "sh").exists()
+            || state
+                .db
+                .get_all_providers(app_type.as_str())
+                .is_ok_and(|providers| {
+                    providers
+                        .values()
+                        .any(|p| p.meta.as_ref().is_some_and(|m| !m.env.is_empty()))
+                });
+        if !in_use {
+            return;
+        }
+        if let Err(e) = Self::write_env_scripts(state, app_type) {
+            log::warn!("[{}] 刷新环境变量脚本失败: {e}", app_type.as_str());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_quoted_exports_and_unsets_per_shell() {
+        let set = BTreeMap::from([("ANTHROPIC_BASE_URL".to_string(), "it's".to_string())]);
+        let stale = "OLD_KEY".to_string();
+        let unset = vec![&stale];
+
+        let sh = render_script("sh", &set, &unset);
+        assert!(sh.contains("unset OLD_KEY\n"));
+        assert!(sh.contains("export ANTHROPIC_BASE_URL='it'\\''s'\n"));
+        assert!(render_script("fish", &set, &unset).contains("set -gx ANTHROPIC_BASE_URL 'it\\'s'"));
+        assert!(
+            render_script("powershell", &set, &unset).contains("$env:ANTHROPIC_BASE_URL = 'it''s'")
+        );
+
+        assert!(is_valid_env_name("_MY_VAR1"));
+        assert!(!is_valid_env_name("1VAR"));
+        assert!(!is_valid_env_name("MY-VAR"));
+        assert!(!is_valid_env_name(""));
+    }
+}
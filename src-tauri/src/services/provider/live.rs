@@ -18,6 +18,7 @@ use super::gemini_auth::{
     detect_gemini_auth_type, ensure_google_oauth_security_flag, GeminiAuthType,
 };
 use super::normalize_claude_models_in_value;
+use super::{ProviderAuditAction, ProviderAuditOrigin, ProviderService};
 
 pub(crate) fn sanitize_claude_settings_for_live(settings: &Value) -> Value {
     let mut v = settings.clone();
@@ -344,7 +345,7 @@ pub fn patch_claude_live(patch: Value) -> Result<(), AppError> {
 }
 
 /// RFC 7396 JSON Merge Patch: null deletes, objects merge recursively, rest overwrites.
-fn json_merge_patch(target: &mut Value, patch: &Value) {
+pub(super) fn json_merge_patch(target: &mut Value, patch: &Value) {
     if let Some(patch_obj) = patch.as_object() {
         if !target.is_object() {
             *target = json!({});
@@ -357,7 +358,7 @@ fn json_merge_patch(target: &mut Value, patch: &Value) {
                 let entry = target_obj.entry(key.clone()).or_insert(json!({}));
                 json_merge_patch(entry, value);
                 // Clean up empty container objects
-                if entry.as_object().map_or(false, |o| o.is_empty()) {
+                if entry.as_object().is_some_and(|o| o.is_empty()) {
                     target_obj.remove(key);
                 }
             } else {
@@ -674,7 +675,10 @@ fn backfill_gemini_key_fields(live: &Value) -> Value {
 ///
 /// Writes all providers from the database to the live configuration file.
 /// Used for OpenCode and other additive mode applications.
-fn sync_all_providers_to_live(state: &AppState, app_type: &AppType) -> Result<(), AppError> {
+pub(super) fn sync_all_providers_to_live(
+    state: &AppState,
+    app_type: &AppType,
+) -> Result<(), AppError> {
     let providers = state.db.get_all_providers(app_type.as_str())?;
 
     for provider in providers.values() {
@@ -926,13 +930,55 @@ pub fn import_default_config(state: &AppState, app_type: AppType) -> Result<bool
     provider.category = Some("custom".to_string());
 
     state.db.save_provider(app_type.as_str(), &provider)?;
+    ProviderService::record_create_audit(
+        state,
+        &app_type,
+        &provider,
+        ProviderAuditAction::Create,
+        ProviderAuditOrigin::Import,
+    );
     state
         .db
         .set_current_provider(app_type.as_str(), &provider.id)?;
+    ProviderService::record_switch_audit(
+        &state.db,
+        app_type.as_str(),
+        None,
+        &provider.id,
+        ProviderAuditOrigin::Import,
+    );
 
     Ok(true) // 真正导入了
 }
 
+/// 首次启动时从现有 live 配置自动识别供应商
+///
+/// 供应商表为空（包括回收站）时视为首次启动：Claude、Codex、Gemini 中存在 live 配置的应用各导入一个
+/// 供应商并设为当前供应商，避免用户面对空的供应商列表。单个应用导入失败只记录日志。
+/// 返回导入了供应商的应用。
+pub fn import_live_providers_on_first_launch(state: &AppState) -> Result<Vec<AppType>, AppError> {
+    if !state.db.is_providers_table_empty()? {
+        return Ok(Vec::new());
+    }
+
+    let mut imported = Vec::new();
+    for (app_type, live_path) in [
+        (AppType::Claude, get_claude_settings_path()),
+        (AppType::Codex, get_codex_auth_path()),
+        (AppType::Gemini, crate::gemini_config::get_gemini_env_path()),
+    ] {
+        if !live_path.exists() {
+            continue;
+        }
+        match import_default_config(state, app_type.clone()) {
+            Ok(true) => imported.push(app_type),
+            Ok(false) => {}
+            Err(e) => log::warn!("[{}] 自动识别现有配置失败: {e}", app_type.as_str()),
+        }
+    }
+    Ok(imported)
+}
+
 /// Write Gemini live configuration with authentication handling
 pub(crate) fn write_gemini_live(provider: &Provider) -> Result<(), AppError> {
     use crate::gemini_config::{
@@ -1084,6 +1130,13 @@ pub fn import_opencode_providers_from_live(state: &AppState) -> Result<usize, Ap
             log::warn!("Failed to import OpenCode provider '{id}': {e}");
             continue;
         }
+        ProviderService::record_create_audit(
+            state,
+            &AppType::OpenCode,
+            &provider,
+            ProviderAuditAction::Create,
+            ProviderAuditOrigin::Import,
+        );
 
         imported += 1;
         log::info!("Imported OpenCode provider '{id}' from live config");
@@ -1149,6 +1202,13 @@ pub fn import_openclaw_providers_from_live(state: &AppState) -> Result<usize, Ap
             log::warn!("Failed to import OpenClaw provider '{id}': {e}");
             continue;
         }
+        ProviderService::record_create_audit(
+            state,
+            &AppType::OpenClaw,
+            &provider,
+            ProviderAuditAction::Create,
+            ProviderAuditOrigin::Import,
+        );
 
         imported += 1;
         log::info!("Imported OpenClaw provider '{id}' from live config");
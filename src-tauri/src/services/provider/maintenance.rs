@@ -0,0 +1,91 @@
+//! 供应商维护窗口
+//!
+//! 供应商临时不可用（上游维护、额度重置前等）时，可以标记“维护至某个时间”：
+//! 窗口内批量健康检查跳过它，故障转移优先使用队列中的其他供应商，
+//! 代理与健康检查也不再为它记录凭据问题。供应商仍留在故障转移队列中，窗口到期后自动恢复。
+
+use super::{ProviderAuditOrigin, ProviderService};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+impl ProviderService {
+    /// 将供应商标记为维护中直到 `until`（Unix 秒），返回更新后的供应商
+    pub fn set_maintenance(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        until: i64,
+    ) -> Result<Provider, AppError> {
+        if until <= chrono::Utc::now().timestamp() {
+            return Err(AppError::localized(
+                "provider.maintenance_in_past",
+                "维护结束时间必须晚于当前时间",
+                "The maintenance window must end in the future",
+            ));
+        }
+        let mut provider = Self::load_for_maintenance(state, &app_type, id)?;
+        let previous = provider.clone();
+        provider
+            .meta
+            .get_or_insert_with(Default::default)
+            .maintenance_until = Some(until);
+        state.db.save_provider(app_type.as_str(), &provider)?;
+        Self::record_update_audit(
+            state,
+            &app_type,
+            Some(&previous),
+            &provider,
+            ProviderAuditOrigin::Ui,
+        );
+        log::info!(
+            "[{}] 供应商 {id} 进入维护，持续到 {until}",
+            app_type.as_str()
+        );
+        Ok(provider)
+    }
+
+    /// 提前结束维护窗口，返回之前是否处于维护中
+    pub fn clear_maintenance(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+    ) -> Result<bool, AppError> {
+        let mut provider = Self::load_for_maintenance(state, &app_type, id)?;
+        let previous = provider.clone();
+        let was_in_maintenance = provider.in_maintenance(chrono::Utc::now().timestamp());
+        let Some(meta) = provider.meta.as_mut() else {
+            return Ok(false);
+        };
+        if meta.maintenance_until.take().is_none() {
+            return Ok(false);
+        }
+        state.db.save_provider(app_type.as_str(), &provider)?;
+        Self::record_update_audit(
+            state,
+            &app_type,
+            Some(&previous),
+            &provider,
+            ProviderAuditOrigin::Ui,
+        );
+        Ok(was_in_maintenance)
+    }
+
+    fn load_for_maintenance(
+        state: &AppState,
+        app_type: &AppType,
+        id: &str,
+    ) -> Result<Provider, AppError> {
+        state
+            .db
+            .get_provider_by_id(id, app_type.as_str())?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {id}"),
+                    format!("Provider not found: {id}"),
+                )
+            })
+    }
+}
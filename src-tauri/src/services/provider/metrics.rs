@@ -0,0 +1,147 @@
+//! 按运行指标排序供应商
+//!
+//! 除手动排序（`sort_index`）外，供应商列表还可以按最近记录的延迟、流式检查成功率或最近使用时间排序，
+//! 让切换列表把状态最好的供应商排在前面。没有对应指标的供应商排在后面，彼此之间保持手动顺序。
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use super::ProviderService;
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 计算成功率时使用的最近流式检查次数
+const SUCCESS_RATE_WINDOW: u32 = 20;
+
+/// 供应商列表的排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderSortMode {
+    /// 手动排序
+    #[default]
+    Manual,
+    /// 延迟从低到高
+    Latency,
+    /// 成功率从高到低
+    SuccessRate,
+    /// 最近使用的在前
+    LastUsed,
+}
+
+/// 供应商的运行指标
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderMetrics {
+    /// 最近一次成功的流式检查延迟；没有检查记录时取最近一次测速的首字节耗时（毫秒）
+    pub latency_ms: Option<u64>,
+    /// 最近若干次流式检查的成功率（0 ~ 1）
+    pub success_rate: Option<f64>,
+    /// 最近一次经代理请求的时间（Unix 秒）
+    pub last_used_at: Option<i64>,
+}
+
+/// 按指标对供应商排序；没有指标的供应商排在后面并保持原有顺序
+fn sort_by_metrics(
+    providers: IndexMap<String, Provider>,
+    metrics: &HashMap<String, ProviderMetrics>,
+    mode: ProviderSortMode,
+) -> IndexMap<String, Provider> {
+    let key = |id: &String| -> Option<f64> {
+        let m = metrics.get(id)?;
+        match mode {
+            ProviderSortMode::Manual => None,
+            ProviderSortMode::Latency => m.latency_ms.map(|ms| ms as f64),
+            ProviderSortMode::SuccessRate => m.success_rate.map(|rate| -rate),
+            ProviderSortMode::LastUsed => m.last_used_at.map(|at| -(at as f64)),
+        }
+    };
+    let mut entries: Vec<_> = providers.into_iter().collect();
+    // sort_by 是稳定排序，指标相同的供应商保持手动顺序
+    entries.sort_by(|(a, _), (b, _)| match (key(a), key(b)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    entries.into_iter().collect()
+}
+
+impl ProviderService {
+    /// 按指定方式排序列出供应商
+    pub fn list_sorted(
+        state: &AppState,
+        app_type: AppType,
+        mode: ProviderSortMode,
+    ) -> Result<IndexMap<String, Provider>, AppError> {
+        let providers = state.db.get_all_providers(app_type.as_str())?;
+        if mode == ProviderSortMode::Manual {
+            return Ok(providers);
+        }
+        let metrics = state
+            .db
+            .get_provider_metrics(app_type.as_str(), SUCCESS_RATE_WINDOW)?;
+        Ok(sort_by_metrics(providers, &metrics, mode))
+    }
+
+    /// 获取各供应商的运行指标
+    pub fn get_metrics(
+        state: &AppState,
+        app_type: AppType,
+    ) -> Result<HashMap<String, ProviderMetrics>, AppError> {
+        state
+            .db
+            .get_provider_metrics(app_type.as_str(), SUCCESS_RATE_WINDOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn providers(ids: &[&str]) -> IndexMap<String, Provider> {
+        ids.iter()
+            .map(|id| {
+                let provider = Provider::with_id(id.to_string(), id.to_string(), json!({}), None);
+                (id.to_string(), provider)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sorts_by_metric_and_keeps_manual_order_for_missing() {
+        let metrics = HashMap::from([
+            (
+                "b".to_string(),
+                ProviderMetrics {
+                    latency_ms: Some(300),
+                    success_rate: Some(0.5),
+                    last_used_at: Some(100),
+                },
+            ),
+            (
+                "d".to_string(),
+                ProviderMetrics {
+                    latency_ms: Some(120),
+                    success_rate: Some(1.0),
+                    last_used_at: None,
+                },
+            ),
+        ]);
+        let order = |mode| -> Vec<String> {
+            sort_by_metrics(providers(&["a", "b", "c", "d"]), &metrics, mode)
+                .into_keys()
+                .collect()
+        };
+
+        assert_eq!(order(ProviderSortMode::Manual), ["a", "b", "c", "d"]);
+        assert_eq!(order(ProviderSortMode::Latency), ["d", "b", "a", "c"]);
+        assert_eq!(order(ProviderSortMode::SuccessRate), ["d", "b", "a", "c"]);
+        assert_eq!(order(ProviderSortMode::LastUsed), ["b", "a", "c", "d"]);
+    }
+}
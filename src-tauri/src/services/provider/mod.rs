@@ -2,9 +2,33 @@
 //!
 //! Handles provider CRUD operations, switching, and configuration management.
 
+mod archive;
+mod attachments;
+mod audit;
+mod benchmark;
+mod bulk;
+mod credential;
+mod diff;
+mod endpoint_health;
 mod endpoints;
+mod env;
 mod gemini_auth;
 mod live;
+mod maintenance;
+mod metrics;
+mod preflight;
+mod presets;
+mod profiles;
+mod quota;
+mod recovery;
+mod reference;
+mod secrets;
+mod setup_guide;
+mod tags;
+mod transfer;
+mod trash;
+mod trust;
+mod universal;
 mod usage;
 
 use indexmap::IndexMap;
@@ -16,16 +40,41 @@ use crate::app_config::AppType;
 use crate::error::AppError;
 use crate::provider::{Provider, UsageResult};
 use crate::services::mcp::McpService;
+use crate::services::mcp_binding::McpBindingService;
 use crate::settings::CustomEndpoint;
 use crate::store::AppState;
 
 // Re-export sub-module functions for external access
 pub use live::{
-    import_default_config, import_openclaw_providers_from_live,
-    import_opencode_providers_from_live, read_live_settings, sync_current_to_live,
+    import_default_config, import_live_providers_on_first_launch,
+    import_openclaw_providers_from_live, import_opencode_providers_from_live, read_live_settings,
+    sync_current_to_live,
 };
 
+pub use attachments::ProviderAttachment;
+pub use audit::{ProviderAuditAction, ProviderAuditEntry, ProviderAuditOrigin};
+pub use benchmark::ProviderBenchmark;
+pub use bulk::BulkUpdateResult;
+pub use credential::{CredentialIssue, CredentialIssueKind, IssueSource, ProviderIssue};
+pub use diff::ProviderDiff;
+pub use endpoint_health::ENDPOINT_PROBE_INTERVAL_SECS;
+pub use endpoints::{StaleEndpoint, DEFAULT_STALE_FAILURES};
+pub use env::ProviderEnvScript;
+pub use metrics::{ProviderMetrics, ProviderSortMode};
+pub use presets::ProviderPreset;
+pub use profiles::{ProfileApplyResult, ProviderProfile};
+pub use quota::ProviderQuota;
+pub use recovery::LiveConfigRecovery;
+pub use secrets::{MissingSecret, SecretUpdateResult};
+pub use setup_guide::ProviderSetupGuide;
+pub use tags::ProviderTag;
+pub use transfer::ProviderImportResult;
+pub use trash::TrashedProvider;
+pub use trust::FastestProviderPick;
+pub use universal::UniversalApplyResult;
+
 // Internal re-exports (pub(crate))
+pub(crate) use attachments::remove_attachment_files;
 pub(crate) use live::sanitize_claude_settings_for_live;
 pub(crate) use live::write_live_partial;
 
@@ -44,6 +93,12 @@ pub struct ProviderService;
 #[serde(rename_all = "camelCase")]
 pub struct SwitchResult {
     pub warnings: Vec<String>,
+    /// Malformed live config files that were quarantined and regenerated
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recovered: Vec<LiveConfigRecovery>,
+    /// Pre-switch check result, present when validation is enabled and ran
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preflight: Option<crate::services::stream_check::StreamCheckResult>,
 }
 
 #[cfg(test)]
@@ -122,7 +177,17 @@ impl ProviderService {
     }
 
     /// Add a new provider
+    ///
+    /// 只读引用只能通过 [`Self::sync_references`] 创建。
     pub fn add(state: &AppState, app_type: AppType, provider: Provider) -> Result<bool, AppError> {
+        Self::add_from(state, app_type, provider, ProviderAuditOrigin::Ui)
+    }
+
+    fn add_unchecked(
+        state: &AppState,
+        app_type: AppType,
+        provider: Provider,
+    ) -> Result<bool, AppError> {
         let mut provider = provider;
         // Normalize Claude model keys
         Self::normalize_provider_if_claude(&app_type, &mut provider);
@@ -159,19 +224,57 @@ impl ProviderService {
     }
 
     /// Update a provider
+    ///
+    /// 只读引用不能在本地修改，只能复制后编辑。
     pub fn update(
         state: &AppState,
         app_type: AppType,
         provider: Provider,
+    ) -> Result<bool, AppError> {
+        Self::update_from(state, app_type, provider, ProviderAuditOrigin::Ui)
+    }
+
+    fn update_unchecked(
+        state: &AppState,
+        app_type: AppType,
+        provider: Provider,
     ) -> Result<bool, AppError> {
         let mut provider = provider;
         // Normalize Claude model keys
         Self::normalize_provider_if_claude(&app_type, &mut provider);
         Self::validate_provider_settings(&app_type, &provider)?;
 
+        // 配置变化（例如更换了密钥）后，旧的凭据问题不再可信
+        let settings_changed = state
+            .db
+            .get_provider_by_id(&provider.id, app_type.as_str())?
+            .is_none_or(|prev| prev.settings_config != provider.settings_config);
+
         // Save to database
         state.db.save_provider(app_type.as_str(), &provider)?;
+        if settings_changed {
+            state
+                .db
+                .clear_credential_issue(&provider.id, app_type.as_str())?;
+            if let Err(e) = McpBindingService::refresh_for_provider(state, &app_type, &provider.id)
+            {
+                log::warn!("刷新依赖供应商 {} 的 MCP 服务器失败: {e}", provider.id);
+            }
+        }
 
+        Self::sync_saved_to_live(state, &app_type, &provider)?;
+        Ok(true)
+    }
+
+    /// 把已保存到数据库的供应商同步到 live 配置
+    ///
+    /// 累加模式应用总是写入（OMO 类别仅在其为当前 OMO 供应商时重写 OMO 配置）；
+    /// 其他应用仅在其为当前供应商时写入，代理接管期间只更新 Live 备份。
+    pub(super) fn sync_saved_to_live(
+        state: &AppState,
+        app_type: &AppType,
+        provider: &Provider,
+    ) -> Result<(), AppError> {
         // Additive mode apps (OpenCode, OpenClaw) - always update in live config
         if app_type.is_additive_mode() {
             if matches!(app_type, AppType::OpenCode) && provider.category.as_deref() == Some("omo")
@@ -186,7 +289,7 @@ impl ProviderService {
                         &crate::services::omo::STANDARD,
                     )?;
                 }
-                return Ok(true);
+                return Ok(());
             }
             if matches!(app_type, AppType::OpenCode)
                 && provider.category.as_deref() == Some("omo-slim")
@@ -202,18 +305,19 @@ impl ProviderService {
                         &crate::services::omo::SLIM,
                     )?;
                 }
-                return Ok(true);
+                return Ok(());
             }
-            write_live_snapshot(&app_type, &provider)?;
-            return Ok(true);
+            write_live_snapshot(app_type, provider)?;
+            return Ok(());
         }
 
         // For other apps: Check if this is current provider (use effective current, not just DB)
         let effective_current =
-            crate::settings::get_effective_current_provider(&state.db, &app_type)?;
+            crate::settings::get_effective_current_provider(&state.db, app_type)?;
         let is_current = effective_current.as_deref() == Some(provider.id.as_str());
 
         if is_current {
+            Self::refresh_env_scripts(state, app_type);
             // 如果代理接管模式处于激活状态，并且代理服务正在运行：
             // - 不写 Live 配置（否则会破坏接管）
             // - 仅更新 Live 备份（保证关闭代理时能恢复到最新配置）
@@ -229,24 +333,35 @@ impl ProviderService {
                 futures::executor::block_on(
                     state
                         .proxy_service
-                        .update_live_backup_from_provider(app_type.as_str(), &provider),
+                        .update_live_backup_from_provider(app_type.as_str(), provider),
                 )
                 .map_err(|e| AppError::Message(format!("更新 Live 备份失败: {e}")))?;
             } else {
-                write_live_partial(&app_type, &provider)?;
+                write_live_partial(app_type, provider)?;
                 // Sync MCP
                 McpService::sync_all_enabled(state)?;
             }
         }
 
-        Ok(true)
+        Ok(())
     }
 
-    /// Delete a provider
+    /// Delete a provider (moved to the trash, see [`Self::restore`])
     ///
-    /// 同时检查本地 settings 和数据库的当前供应商，防止删除任一端正在使用的供应商。
+    /// 同时检查本地 settings 和数据库的当前供应商：删除任一端正在使用的供应商时，先切换到备用供应商，
+    /// 没有可切换的供应商时拒绝删除。
     /// 对于累加模式应用（OpenCode, OpenClaw），可以随时删除任意供应商，同时从 live 配置中移除。
     pub fn delete(state: &AppState, app_type: AppType, id: &str) -> Result<(), AppError> {
+        Self::delete_from(state, app_type, id, ProviderAuditOrigin::Ui)
+    }
+
+    /// 删除供应商；删除当前供应商时自动切换到备用供应商，该切换的审计来源同为 `origin`
+    fn delete_inner(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        origin: ProviderAuditOrigin,
+    ) -> Result<(), AppError> {
         // Additive mode apps - no current provider concept
         if app_type.is_additive_mode() {
             if matches!(app_type, AppType::OpenCode) {
@@ -273,7 +388,7 @@ impl ProviderService {
                         ));
                     }
 
-                    state.db.delete_provider(app_type.as_str(), id)?;
+                    Self::trash(state, &app_type, id)?;
                     if was_current {
                         crate::services::OmoService::delete_config_file(
                             &crate::services::omo::STANDARD,
@@ -300,7 +415,7 @@ impl ProviderService {
                         ));
                     }
 
-                    state.db.delete_provider(app_type.as_str(), id)?;
+                    Self::trash(state, &app_type, id)?;
                     if was_current {
                         crate::services::OmoService::delete_config_file(
                             &crate::services::omo::SLIM,
@@ -310,7 +425,7 @@ impl ProviderService {
                 }
             }
             // Remove from database
-            state.db.delete_provider(app_type.as_str(), id)?;
+            Self::trash(state, &app_type, id)?;
             // Also remove from live config
             match app_type {
                 AppType::OpenCode => remove_opencode_provider_from_live(id)?,
@@ -325,12 +440,19 @@ impl ProviderService {
         let db_current = state.db.get_current_provider(app_type.as_str())?;
 
         if local_current.as_deref() == Some(id) || db_current.as_deref() == Some(id) {
-            return Err(AppError::Message(
-                "无法删除当前正在使用的供应商".to_string(),
-            ));
+            let Some(fallback) = Self::fallback_for_delete(state, &app_type, id)? else {
+                return Err(AppError::Message(
+                    "无法删除当前正在使用的供应商：没有可以自动切换的其他供应商（未验证的供应商需先允许自动化使用）".to_string(),
+                ));
+            };
+            log::info!(
+                "[{}] 删除当前供应商 {id}，先切换到备用供应商 {fallback}",
+                app_type.as_str()
+            );
+            Self::switch_from(state, app_type.clone(), &fallback, origin)?;
         }
 
-        state.db.delete_provider(app_type.as_str(), id)
+        Self::trash(state, &app_type, id)
     }
 
     /// Remove provider from live config only (for additive mode apps like OpenCode, OpenClaw)
@@ -408,30 +530,94 @@ impl ProviderService {
     /// Switch flow:
     /// 1. Validate target provider exists
     /// 2. Check if proxy takeover mode is active AND proxy server is running
-    /// 3. If takeover mode active: hot-switch proxy target only (no Live config write)
+    /// 3. If takeover mode active: hot-switch proxy target only (no Live config write);
+    ///    new requests go to the new provider right away, and Live cleanup waits until
+    ///    in-flight requests on the previous provider have finished
     /// 4. If normal mode:
     ///    a. **Backfill mechanism**: Backfill current live config to current provider
     ///    b. Update local settings current_provider_xxx (device-level)
     ///    c. Update database is_current (as default for new devices)
     ///    d. Write target provider config to live files
     ///    e. Sync MCP configuration
+    ///
+    /// After a successful switch, agents and prompts whose content uses template
+    /// variables (`{{provider_name}}`, `{{model}}`, ...) are rewritten for the new provider, and
+    /// MCP servers bound to the app's current provider get the new credentials.
     pub fn switch(state: &AppState, app_type: AppType, id: &str) -> Result<SwitchResult, AppError> {
+        Self::switch_from(state, app_type, id, ProviderAuditOrigin::Ui)
+    }
+
+    fn switch_inner(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        origin: ProviderAuditOrigin,
+    ) -> Result<SwitchResult, AppError> {
+        let result = Self::switch_provider_target(state, app_type.clone(), id, origin)?;
+        if let Err(e) = crate::services::AgentsService::resync_templated(state, &app_type) {
+            log::warn!("切换供应商后刷新 agent 模板变量失败（不影响切换结果）: {e}");
+        }
+        if let Err(e) = crate::services::PromptService::resync_templated(state, &app_type) {
+            log::warn!("切换供应商后刷新提示词模板变量失败（不影响切换结果）: {e}");
+        }
+        if let Err(e) = McpBindingService::refresh_for_provider(state, &app_type, id) {
+            log::warn!("切换供应商后刷新绑定的 MCP 服务器失败（不影响切换结果）: {e}");
+        }
+        Self::refresh_env_scripts(state, &app_type);
+        Ok(result)
+    }
+
+    fn switch_provider_target(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        origin: ProviderAuditOrigin,
+    ) -> Result<SwitchResult, AppError> {
         // Check if provider exists
-        let providers = state.db.get_all_providers(app_type.as_str())?;
-        let _provider = providers
-            .get(id)
-            .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))?;
+        let mut providers = state.db.get_all_providers(app_type.as_str())?;
+        let provider = providers.get_mut(id).ok_or_else(|| {
+            AppError::localized(
+                "provider.not_found",
+                format!("供应商 {id} 不存在"),
+                format!("Provider not found: {id}"),
+            )
+        })?;
+        Self::reject_archived(provider)?;
+
+        // 自动选择端点：写入 live 的是改用最快健康端点的配置，切换成功后才保存到数据库
+        let rewrite =
+            Self::fastest_endpoint_settings(&app_type, provider).map(|(endpoint, settings)| {
+                let previous = provider.clone();
+                provider.settings_config = settings.clone();
+                (previous, endpoint, settings)
+            });
+
+        let result = Self::switch_to_provider(state, app_type.clone(), id, &providers, origin)?;
+        if let Some((previous, endpoint, settings)) = rewrite {
+            Self::save_fastest_endpoint(state, &app_type, &previous, &endpoint, &settings, origin)?;
+        }
+        Ok(result)
+    }
+
+    fn switch_to_provider(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        providers: &indexmap::IndexMap<String, Provider>,
+        origin: ProviderAuditOrigin,
+    ) -> Result<SwitchResult, AppError> {
+        let _provider = &providers[id];
 
         // OMO providers are switched through their own exclusive path.
         if matches!(app_type, AppType::OpenCode) && _provider.category.as_deref() == Some("omo") {
-            return Self::switch_normal(state, app_type, id, &providers);
+            return Self::switch_normal(state, app_type, id, providers, origin);
         }
 
         // OMO Slim providers are switched through their own exclusive path.
         if matches!(app_type, AppType::OpenCode)
             && _provider.category.as_deref() == Some("omo-slim")
         {
-            return Self::switch_normal(state, app_type, id, &providers);
+            return Self::switch_normal(state, app_type, id, providers, origin);
         }
 
         // Check if proxy takeover mode is active AND proxy server is actually running
@@ -459,9 +645,16 @@ impl ProviderService {
             );
 
             // 获取新供应商的完整配置（用于更新备份）
-            let provider = providers
-                .get(id)
-                .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))?;
+            let provider = providers.get(id).ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商 {id} 不存在"),
+                    format!("Provider not found: {id}"),
+                )
+            })?;
+            let previous_id =
+                crate::settings::get_effective_current_provider(&state.db, &app_type)?
+                    .filter(|previous| previous != id);
 
             // Update database is_current
             state.db.set_current_provider(app_type.as_str(), id)?;
@@ -479,10 +672,17 @@ impl ProviderService {
 
             // 关键修复：接管模式下切换供应商不会写回 Live 配置，
             // 需要主动清理 Claude Live 中的“模型覆盖”字段，避免仍以旧模型名发起请求。
+            // 等旧供应商上的流式响应结束后再改写，避免正在进行的会话中途重新加载配置。
             if matches!(app_type, AppType::Claude) {
-                if let Err(e) = state.proxy_service.cleanup_claude_model_overrides_in_live() {
-                    log::warn!("清理 Claude Live 模型字段失败（不影响切换结果）: {e}");
-                }
+                state.proxy_service.after_provider_drained(
+                    app_type.as_str(),
+                    previous_id.as_deref().unwrap_or_default(),
+                    |proxy| {
+                        if let Err(e) = proxy.cleanup_claude_model_overrides_in_live() {
+                            log::warn!("清理 Claude Live 模型字段失败（不影响切换结果）: {e}");
+                        }
+                    },
+                );
             }
 
             // Note: No Live config write, no MCP sync
@@ -491,7 +691,7 @@ impl ProviderService {
         }
 
         // Normal mode: full switch with Live config write
-        Self::switch_normal(state, app_type, id, &providers)
+        Self::switch_normal(state, app_type, id, providers, origin)
     }
 
     /// Normal switch flow (non-proxy mode)
@@ -500,10 +700,15 @@ impl ProviderService {
         app_type: AppType,
         id: &str,
         providers: &indexmap::IndexMap<String, Provider>,
+        origin: ProviderAuditOrigin,
     ) -> Result<SwitchResult, AppError> {
-        let provider = providers
-            .get(id)
-            .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))?;
+        let provider = providers.get(id).ok_or_else(|| {
+            AppError::localized(
+                "provider.not_found",
+                format!("供应商 {id} 不存在"),
+                format!("Provider not found: {id}"),
+            )
+        })?;
 
         if matches!(app_type, AppType::OpenCode) && provider.category.as_deref() == Some("omo") {
             state
@@ -538,17 +743,30 @@ impl ProviderService {
                 if !app_type.is_additive_mode() {
                     // Only backfill when switching to a different provider
                     if let Ok(live_config) = read_live_settings(app_type.clone()) {
-                        if let Some(mut current_provider) = providers.get(&current_id).cloned() {
+                        // 只读引用不回填 live 中的本地改动
+                        if let Some(mut current_provider) = providers
+                            .get(&current_id)
+                            .filter(|p| !p.is_reference())
+                            .cloned()
+                        {
+                            let previous = current_provider.clone();
                             // Only extract key fields from live config for backfill
                             current_provider.settings_config =
                                 backfill_key_fields(&app_type, &live_config);
-                            if let Err(e) =
-                                state.db.save_provider(app_type.as_str(), &current_provider)
-                            {
-                                log::warn!("Backfill failed: {e}");
-                                result
-                                    .warnings
-                                    .push(format!("backfill_failed:{current_id}"));
+                            match state.db.save_provider(app_type.as_str(), &current_provider) {
+                                Ok(()) => Self::record_update_audit(
+                                    state,
+                                    &app_type,
+                                    Some(&previous),
+                                    &current_provider,
+                                    origin,
+                                ),
+                                Err(e) => {
+                                    log::warn!("Backfill failed: {e}");
+                                    result
+                                        .warnings
+                                        .push(format!("backfill_failed:{current_id}"));
+                                }
                             }
                         }
                     }
@@ -556,6 +774,10 @@ impl ProviderService {
             }
         }
 
+        // Quarantine unparsable live files so they can be rebuilt from the database.
+        // 必须在修改当前供应商之前完成，否则失败时会留下半切换状态
+        let recovered = recovery::quarantine_malformed_live_configs(&app_type)?;
+
         // Additive mode apps skip setting is_current (no such concept)
         if !app_type.is_additive_mode() {
             // Update local settings (device-level, takes priority)
@@ -568,6 +790,16 @@ impl ProviderService {
         // Sync to live (partial merge: only key fields, preserving user settings)
         write_live_partial(&app_type, provider)?;
 
+        if !recovered.is_empty() {
+            recovery::regenerate_after_quarantine(state, &app_type)?;
+            for item in &recovered {
+                result
+                    .warnings
+                    .push(format!("live_config_recovered:{}", item.original_path));
+            }
+            result.recovered = recovered;
+        }
+
         Ok(result)
     }
 
@@ -583,6 +815,13 @@ impl ProviderService {
         import_default_config(state, app_type)
     }
 
+    /// 首次启动时从现有 live 配置自动识别供应商 (re-export)
+    pub fn import_live_providers_on_first_launch(
+        state: &AppState,
+    ) -> Result<Vec<AppType>, AppError> {
+        import_live_providers_on_first_launch(state)
+    }
+
     /// Read current live settings (re-export)
     pub fn read_live_settings(app_type: AppType) -> Result<Value, AppError> {
         read_live_settings(app_type)
@@ -609,6 +848,7 @@ impl ProviderService {
         provider_id: &str,
         url: String,
     ) -> Result<(), AppError> {
+        reference::ensure_editable(state, &app_type, provider_id)?;
         endpoints::add_custom_endpoint(state, app_type, provider_id, url)
     }
 
@@ -619,6 +859,7 @@ impl ProviderService {
         provider_id: &str,
         url: String,
     ) -> Result<(), AppError> {
+        reference::ensure_editable(state, &app_type, provider_id)?;
         endpoints::remove_custom_endpoint(state, app_type, provider_id, url)
     }
 
@@ -632,6 +873,27 @@ impl ProviderService {
         endpoints::update_endpoint_last_used(state, app_type, provider_id, url)
     }
 
+    /// Record speed-test results for custom endpoints (re-export)
+    pub fn record_endpoint_benchmarks(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        results: &[crate::services::EndpointLatency],
+    ) -> Result<(), AppError> {
+        endpoints::record_endpoint_benchmarks(state, app_type, provider_id, results)
+    }
+
+    /// Flag or remove endpoints failing consecutive benchmarks (re-export)
+    pub fn prune_stale_endpoints(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: Option<&str>,
+        min_failures: u32,
+        dry_run: bool,
+    ) -> Result<Vec<StaleEndpoint>, AppError> {
+        endpoints::prune_stale_endpoints(state, app_type, provider_id, min_failures, dry_run)
+    }
+
     /// Update provider sort order
     pub fn update_sort_order(
         state: &AppState,
@@ -773,6 +1035,7 @@ impl ProviderService {
                 validate_usage_script(usage_script)?;
             }
         }
+        env::validate_env(provider)?;
 
         Ok(())
     }
@@ -1079,83 +1342,50 @@ impl ProviderService {
         Ok(true)
     }
 
-    /// 删除统一供应商
+    /// 删除统一供应商及其生成的子供应商
+    ///
+    /// 子供应商与普通供应商一样移入回收站；正在使用的子供应商先切换到备用供应商，无法切换时
+    /// 拒绝删除，统一供应商保持不变。
     pub fn delete_universal(state: &AppState, id: &str) -> Result<bool, AppError> {
         // 获取统一供应商（用于删除生成的子供应商）
         let provider = state.db.get_universal_provider(id)?;
 
-        // 删除统一供应商
-        state.db.delete_universal_provider(id)?;
-
-        // 删除生成的子供应商
+        // 先删除生成的子供应商
         if let Some(p) = provider {
-            if p.apps.claude {
-                let claude_id = format!("universal-claude-{id}");
-                let _ = state.db.delete_provider("claude", &claude_id);
-            }
-            if p.apps.codex {
-                let codex_id = format!("universal-codex-{id}");
-                let _ = state.db.delete_provider("codex", &codex_id);
-            }
-            if p.apps.gemini {
-                let gemini_id = format!("universal-gemini-{id}");
-                let _ = state.db.delete_provider("gemini", &gemini_id);
+            for (app_type, enabled) in [
+                (AppType::Claude, p.apps.claude),
+                (AppType::Codex, p.apps.codex),
+                (AppType::Gemini, p.apps.gemini),
+            ] {
+                let child_id = universal::universal_child_id(&app_type, id);
+                if enabled
+                    && state
+                        .db
+                        .get_provider_by_id(&child_id, app_type.as_str())?
+                        .is_some()
+                {
+                    Self::delete_from(state, app_type, &child_id, ProviderAuditOrigin::Ui)?;
+                }
             }
         }
 
+        // 删除统一供应商
+        state.db.delete_universal_provider(id)?;
+
         Ok(true)
     }
 
-    /// 同步统一供应商到各应用
+    /// 同步统一供应商到各应用（按统一供应商的应用启用状态创建、更新或删除子供应商）
     pub fn sync_universal_to_apps(state: &AppState, id: &str) -> Result<bool, AppError> {
-        let provider = state
-            .db
-            .get_universal_provider(id)?
-            .ok_or_else(|| AppError::Message(format!("统一供应商 {id} 不存在")))?;
-
-        // 同步到 Claude
-        if let Some(mut claude_provider) = provider.to_claude_provider() {
-            // 合并已有配置
-            if let Some(existing) = state.db.get_provider_by_id(&claude_provider.id, "claude")? {
-                let mut merged = existing.settings_config.clone();
-                Self::merge_json(&mut merged, &claude_provider.settings_config);
-                claude_provider.settings_config = merged;
-            }
-            state.db.save_provider("claude", &claude_provider)?;
-        } else {
-            // 如果禁用了 Claude，删除对应的子供应商
-            let claude_id = format!("universal-claude-{id}");
-            let _ = state.db.delete_provider("claude", &claude_id);
-        }
-
-        // 同步到 Codex
-        if let Some(mut codex_provider) = provider.to_codex_provider() {
-            // 合并已有配置
-            if let Some(existing) = state.db.get_provider_by_id(&codex_provider.id, "codex")? {
-                let mut merged = existing.settings_config.clone();
-                Self::merge_json(&mut merged, &codex_provider.settings_config);
-                codex_provider.settings_config = merged;
-            }
-            state.db.save_provider("codex", &codex_provider)?;
-        } else {
-            let codex_id = format!("universal-codex-{id}");
-            let _ = state.db.delete_provider("codex", &codex_id);
-        }
-
-        // 同步到 Gemini
-        if let Some(mut gemini_provider) = provider.to_gemini_provider() {
-            // 合并已有配置
-            if let Some(existing) = state.db.get_provider_by_id(&gemini_provider.id, "gemini")? {
-                let mut merged = existing.settings_config.clone();
-                Self::merge_json(&mut merged, &gemini_provider.settings_config);
-                gemini_provider.settings_config = merged;
-            }
-            state.db.save_provider("gemini", &gemini_provider)?;
-        } else {
-            let gemini_id = format!("universal-gemini-{id}");
-            let _ = state.db.delete_provider("gemini", &gemini_id);
-        }
+        let provider = state.db.get_universal_provider(id)?.ok_or_else(|| {
+            AppError::localized(
+                "provider.universal_not_found",
+                format!("统一供应商 {id} 不存在"),
+                format!("Universal provider {id} not found"),
+            )
+        })?;
 
+        Self::materialize_universal(state, &provider)?;
         Ok(true)
     }
 
@@ -0,0 +1,64 @@
+//! 切换前预检
+//!
+//! 开启预检（[`SwitchValidation`]）后，标记为当前供应商之前先对它发送一次最小请求
+//! （与健康检查相同的 1 token 流式请求）。`block` 模式下预检失败会拒绝切换并返回原始的
+//! HTTP 错误，`warn` 模式下照常切换，预检结果附在切换结果中由前端提示。
+
+use super::{ProviderService, SwitchResult};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::stream_check::{StreamCheckService, SwitchValidation};
+use crate::store::AppState;
+
+impl ProviderService {
+    /// 预检后切换供应商；`force` 为 true 时跳过预检
+    pub async fn switch_with_preflight(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        force: bool,
+    ) -> Result<SwitchResult, AppError> {
+        let config = state.db.get_stream_check_config()?;
+        let mut preflight = None;
+        if !force && config.validate_on_switch != SwitchValidation::Off {
+            let provider = state
+                .db
+                .get_provider_by_id(id, app_type.as_str())?
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {id}"),
+                        format!("Provider not found: {id}"),
+                    )
+                })?;
+            Self::reject_archived(&provider)?;
+
+            if let Some(result) =
+                StreamCheckService::preflight(&state.db, &app_type, &provider, &config).await?
+            {
+                if !result.success {
+                    log::warn!(
+                        "[{}] 供应商 {id} 切换前预检失败: {}",
+                        app_type.as_str(),
+                        result.message
+                    );
+                    if config.validate_on_switch == SwitchValidation::Block {
+                        return Err(AppError::localized(
+                            "provider.preflight_failed",
+                            format!("{} 预检失败，已取消切换: {}", provider.name, result.message),
+                            format!(
+                                "Pre-switch check of {} failed, switch cancelled: {}",
+                                provider.name, result.message
+                            ),
+                        ));
+                    }
+                }
+                preflight = Some(result);
+            }
+        }
+
+        let mut result = Self::switch(state, app_type, id)?;
+        result.preflight = preflight;
+        Ok(result)
+    }
+}
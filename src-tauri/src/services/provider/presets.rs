@@ -0,0 +1,193 @@
+//! 内置供应商预设目录
+//!
+//! 收录常见厂商（官方、开源模型厂商、聚合平台与中转站）在各应用下的 Base URL、模型与
+//! 密钥字段，数据随程序打包（`provider_presets.json`）。从预设创建供应商时只需要填写 API Key。
+
+use std::collections::BTreeMap;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::secrets::set_value_at;
+use super::{ProviderAuditOrigin, ProviderService};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::id_generator::{IdGenerator, IdKind};
+use crate::store::AppState;
+
+/// 预设在某个应用下的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetAppConfig {
+    /// 不含密钥的 `settingsConfig`
+    pub settings_config: Value,
+    /// 密钥字段路径（`settingsConfig` 中以 `.` 分隔）；为空表示使用官方登录，不需要 API Key
+    #[serde(default)]
+    pub api_key_path: Option<String>,
+}
+
+/// 供应商预设
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderPreset {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub website_url: String,
+    /// 获取 API Key 的页面
+    #[serde(default)]
+    pub api_key_url: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub icon_color: Option<String>,
+    /// 应用（如 `claude`）-> 配置
+    pub apps: BTreeMap<String, PresetAppConfig>,
+}
+
+static PROVIDER_PRESETS: Lazy<Vec<ProviderPreset>> = Lazy::new(|| {
+    serde_json::from_str(include_str!("provider_presets.json")).expect("内置供应商预设格式错误")
+});
+
+/// 按预设生成供应商（尚未保存）
+fn build_from_preset(
+    preset: &ProviderPreset,
+    app_type: &AppType,
+    api_key: &str,
+    name: Option<&str>,
+) -> Result<Provider, AppError> {
+    let config = preset.apps.get(app_type.as_str()).ok_or_else(|| {
+        AppError::localized(
+            "provider.preset_app_unsupported",
+            format!("预设 {} 不支持 {}", preset.name, app_type.as_str()),
+            format!(
+                "Preset {} is not available for {}",
+                preset.name,
+                app_type.as_str()
+            ),
+        )
+    })?;
+
+    let mut settings_config = config.settings_config.clone();
+    if let Some(path) = &config.api_key_path {
+        let api_key = api_key.trim();
+        if api_key.is_empty() {
+            return Err(AppError::localized(
+                "provider.preset_api_key_required",
+                format!("使用预设 {} 需要填写 API Key", preset.name),
+                format!("Preset {} needs an API key", preset.name),
+            ));
+        }
+        set_value_at(&mut settings_config, path, api_key)?;
+    }
+
+    let name = name
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unwrap_or(&preset.name);
+    let mut provider = Provider::with_id(
+        String::new(),
+        name.to_string(),
+        settings_config,
+        Some(preset.website_url.clone()),
+    );
+    provider.category = Some(preset.category.clone());
+    provider.icon = preset.icon.clone();
+    provider.icon_color = preset.icon_color.clone();
+    provider.created_at = Some(chrono::Utc::now().timestamp_millis());
+    Ok(provider)
+}
+
+impl ProviderService {
+    /// 获取内置预设；指定应用时只返回支持该应用的预设
+    pub fn list_presets(app_type: Option<&AppType>) -> Vec<ProviderPreset> {
+        PROVIDER_PRESETS
+            .iter()
+            .filter(|preset| app_type.is_none_or(|app| preset.apps.contains_key(app.as_str())))
+            .cloned()
+            .collect()
+    }
+
+    /// 从预设创建供应商，返回保存后的供应商
+    ///
+    /// `name` 为空时使用预设名称；需要密钥的预设必须提供 `api_key`。审计记录的来源为 `origin`。
+    pub fn create_from_preset(
+        state: &AppState,
+        app_type: AppType,
+        preset_id: &str,
+        api_key: &str,
+        name: Option<&str>,
+        origin: ProviderAuditOrigin,
+    ) -> Result<Provider, AppError> {
+        let preset = PROVIDER_PRESETS
+            .iter()
+            .find(|preset| preset.id == preset_id)
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.preset_not_found",
+                    format!("供应商预设不存在: {preset_id}"),
+                    format!("Provider preset not found: {preset_id}"),
+                )
+            })?;
+
+        let mut provider = build_from_preset(preset, &app_type, api_key, name)?;
+        provider.id = IdGenerator::new(IdKind::PresetProvider)
+            .name(&provider.name)
+            .app(&app_type)
+            .generate(|candidate| {
+                Ok(state
+                    .db
+                    .get_provider_by_id(candidate, app_type.as_str())?
+                    .is_some())
+            })?;
+
+        Self::add_from(state, app_type, provider.clone(), origin)?;
+        Ok(provider)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret_store::secret_paths;
+
+    #[test]
+    fn bundled_presets_use_known_apps_and_key_fields() {
+        for preset in PROVIDER_PRESETS.iter() {
+            assert!(!preset.apps.is_empty(), "{} has no apps", preset.id);
+            for (app, config) in &preset.apps {
+                let app_type: AppType = app.parse().expect("known app");
+                if let Some(path) = &config.api_key_path {
+                    assert!(
+                        secret_paths(&app_type).contains(&path.as_str()),
+                        "{}/{app} uses unknown key field {path}",
+                        preset.id
+                    );
+                } else {
+                    assert_eq!(preset.category, "official", "{} needs a key", preset.id);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn build_from_preset_fills_api_key() {
+        let preset = ProviderService::list_presets(Some(&AppType::Codex))
+            .into_iter()
+            .find(|preset| preset.id == "openrouter")
+            .expect("openrouter preset");
+
+        let provider = build_from_preset(&preset, &AppType::Codex, " sk-or-1 ", None).unwrap();
+        assert_eq!(provider.name, "OpenRouter");
+        assert_eq!(
+            provider.settings_config["auth"]["OPENAI_API_KEY"],
+            "sk-or-1"
+        );
+        assert_eq!(provider.category.as_deref(), Some("aggregator"));
+
+        assert!(build_from_preset(&preset, &AppType::Codex, "  ", None).is_err());
+        assert!(build_from_preset(&preset, &AppType::Gemini, "sk-or-1", None).is_err());
+    }
+}
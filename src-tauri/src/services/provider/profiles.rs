@@ -0,0 +1,207 @@
+//! 供应商组合
+//!
+//! 组合是一组“应用 -> 供应商”的对应关系，例如“工作”= Claude 用 A、Codex 用 B、Gemini 用 C。
+//! 应用组合时依次切换各应用；任何一个应用切换失败，已经切换的应用会切回原来的供应商，
+//! 保证所有应用要么都切到组合中的供应商，要么都保持原样。
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ProviderAuditOrigin, ProviderService};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::store::AppState;
+
+/// 供应商组合
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderProfile {
+    pub name: String,
+    /// 应用（如 `claude`）-> 供应商 id
+    pub providers: BTreeMap<String, String>,
+    /// Unix 秒
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+/// 应用组合的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileApplyResult {
+    /// 实际切换了供应商的应用（已是目标供应商的应用不计入）
+    pub switched: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ProviderService {
+    /// 获取全部供应商组合
+    pub fn list_profiles(state: &AppState) -> Result<Vec<ProviderProfile>, AppError> {
+        state.db.get_provider_profiles()
+    }
+
+    /// 新增或更新供应商组合，返回保存后的组合
+    pub fn save_profile(
+        state: &AppState,
+        mut profile: ProviderProfile,
+    ) -> Result<ProviderProfile, AppError> {
+        profile.name = profile.name.trim().to_string();
+        if profile.name.is_empty() {
+            return Err(AppError::localized(
+                "provider.profile_name_empty",
+                "组合名称不能为空",
+                "Profile name cannot be empty",
+            ));
+        }
+        Self::check_profile_targets(state, &profile)?;
+
+        let now = chrono::Utc::now().timestamp();
+        profile.created_at = state
+            .db
+            .get_provider_profile(&profile.name)?
+            .map_or(now, |existing| existing.created_at);
+        profile.updated_at = now;
+        state.db.save_provider_profile(&profile)?;
+        Ok(profile)
+    }
+
+    /// 删除供应商组合
+    pub fn delete_profile(state: &AppState, name: &str) -> Result<bool, AppError> {
+        state.db.delete_provider_profile(name)
+    }
+
+    /// 应用供应商组合：切换组合中的每个应用，任何一个失败时回滚已切换的应用
+    ///
+    /// 切换与回滚的审计记录来源均为 `origin`。
+    pub fn apply_profile(
+        state: &AppState,
+        name: &str,
+        origin: ProviderAuditOrigin,
+    ) -> Result<ProfileApplyResult, AppError> {
+        let profile = state.db.get_provider_profile(name)?.ok_or_else(|| {
+            AppError::localized(
+                "provider.profile_not_found",
+                format!("供应商组合不存在: {name}"),
+                format!("Provider profile not found: {name}"),
+            )
+        })?;
+        // 先整体检查，避免切到一半才发现供应商已被删除
+        let targets = Self::check_profile_targets(state, &profile)?;
+
+        let mut result = ProfileApplyResult::default();
+        // 已切换的应用及其原供应商，用于失败时回滚
+        let mut applied: Vec<(AppType, Option<String>)> = Vec::new();
+        for (app_type, provider_id) in targets {
+            let previous = crate::settings::get_effective_current_provider(&state.db, &app_type)?;
+            if previous.as_deref() == Some(provider_id.as_str()) {
+                continue;
+            }
+            match Self::switch_from(state, app_type.clone(), &provider_id, origin) {
+                Ok(switch) => {
+                    result.warnings.extend(switch.warnings);
+                    result.switched.push(app_type.as_str().to_string());
+                    applied.push((app_type, previous));
+                }
+                Err(e) => {
+                    let rollback_failures = Self::rollback_profile(state, applied, origin);
+                    let (zh_rollback, en_rollback) = if rollback_failures.is_empty() {
+                        (
+                            "已切换的应用均已恢复".to_string(),
+                            "all switched apps were restored".to_string(),
+                        )
+                    } else {
+                        let apps = rollback_failures.join(", ");
+                        (
+                            format!("以下应用未能恢复: {apps}"),
+                            format!("failed to restore: {apps}"),
+                        )
+                    };
+                    return Err(AppError::localized(
+                        "provider.profile_apply_failed",
+                        format!(
+                            "应用供应商组合 {name} 失败：{} 切换失败: {e}；{zh_rollback}",
+                            app_type.as_str()
+                        ),
+                        format!(
+                            "Failed to apply provider profile {name}: switching {} failed: {e}; {en_rollback}",
+                            app_type.as_str()
+                        ),
+                    ));
+                }
+            }
+        }
+
+        log::info!("已应用供应商组合 {name}，切换的应用: {:?}", result.switched);
+        Ok(result)
+    }
+
+    /// 按相反顺序切回原供应商，返回未能恢复的应用
+    fn rollback_profile(
+        state: &AppState,
+        applied: Vec<(AppType, Option<String>)>,
+        origin: ProviderAuditOrigin,
+    ) -> Vec<String> {
+        let mut failures = Vec::new();
+        for (app_type, previous) in applied.into_iter().rev() {
+            let Some(previous) = previous else {
+                // 切换前没有当前供应商，无法恢复到“无”
+                failures.push(app_type.as_str().to_string());
+                continue;
+            };
+            if let Err(e) = Self::switch_from(state, app_type.clone(), &previous, origin) {
+                log::error!(
+                    "回滚供应商组合时恢复 {} 的供应商 {previous} 失败: {e}",
+                    app_type.as_str()
+                );
+                failures.push(app_type.as_str().to_string());
+            }
+        }
+        failures
+    }
+
+    /// 校验组合中的应用与供应商，返回按应用顺序排列的切换目标
+    fn check_profile_targets(
+        state: &AppState,
+        profile: &ProviderProfile,
+    ) -> Result<Vec<(AppType, String)>, AppError> {
+        if profile.providers.is_empty() {
+            return Err(AppError::localized(
+                "provider.profile_empty",
+                "组合中至少需要一个应用的供应商",
+                "A profile needs a provider for at least one app",
+            ));
+        }
+
+        let mut targets = Vec::new();
+        for (app, provider_id) in &profile.providers {
+            let app_type = AppType::from_str(app)?;
+            if app_type.is_additive_mode() {
+                return Err(AppError::localized(
+                    "provider.profile_additive_app",
+                    format!("{app} 的供应商同时生效，没有“当前供应商”，不能加入组合"),
+                    format!(
+                        "{app} uses all providers at once and has no current provider to switch"
+                    ),
+                ));
+            }
+            if state
+                .db
+                .get_provider_by_id(provider_id, app_type.as_str())?
+                .is_none()
+            {
+                return Err(AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {app}/{provider_id}"),
+                    format!("Provider not found: {app}/{provider_id}"),
+                ));
+            }
+            targets.push((app_type, provider_id.clone()));
+        }
+        let order: Vec<AppType> = AppType::all().collect();
+        targets.sort_by_key(|(app_type, _)| order.iter().position(|a| a == app_type));
+        Ok(targets)
+    }
+}
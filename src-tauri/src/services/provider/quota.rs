@@ -0,0 +1,342 @@
+//! 供应商余额查询
+//!
+//! 不少厂商与中转站提供余额接口。供应商元数据可以指定接口类型（`quotaType`）与地址（`quotaUrl`）：
+//! 未指定类型时按 Base URL 的域名识别，未指定地址时使用该类型的默认地址。
+//! OpenAI 兼容的 billing 接口（One API / New API 等中转站）的地址是 `.../v1/dashboard/billing`
+//! 前缀，默认由供应商的 Base URL 推出，额度与用量分两次请求。
+//!
+//! 与用量查询脚本不同，这里不执行用户脚本，只按接口类型解析固定格式的响应。
+
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::ProviderService;
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::QuotaType;
+use crate::proxy::http_client;
+use crate::proxy::providers::get_adapter;
+use crate::secret_store::is_placeholder;
+use crate::store::AppState;
+
+/// 余额接口请求超时
+const QUOTA_TIMEOUT_SECS: u64 = 15;
+
+/// 余额查询结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderQuota {
+    pub provider_id: String,
+    pub quota_type: QuotaType,
+    pub total: Option<f64>,
+    pub used: Option<f64>,
+    pub remaining: Option<f64>,
+    /// 金额单位（如 `USD`、`CNY`）
+    pub unit: String,
+    /// 查询时间（Unix 秒）
+    pub checked_at: i64,
+}
+
+/// 解析出的额度
+#[derive(Debug, Clone, PartialEq)]
+struct QuotaFigures {
+    total: Option<f64>,
+    used: Option<f64>,
+    remaining: Option<f64>,
+    unit: String,
+}
+
+/// 按 Base URL 的域名识别余额接口类型
+fn detect_quota_type(base_url: &str) -> Option<QuotaType> {
+    let host = url::Url::parse(base_url)
+        .ok()?
+        .host_str()?
+        .to_ascii_lowercase();
+    let matches = |domain: &str| host == domain || host.ends_with(&format!(".{domain}"));
+    if matches("openrouter.ai") {
+        Some(QuotaType::Openrouter)
+    } else if matches("deepseek.com") {
+        Some(QuotaType::Deepseek)
+    } else if matches("siliconflow.cn") {
+        Some(QuotaType::Siliconflow)
+    } else if matches("moonshot.cn") {
+        Some(QuotaType::Moonshot)
+    } else {
+        None
+    }
+}
+
+/// 余额接口类型的默认地址；OpenAI billing 需要 Base URL
+fn default_quota_url(quota_type: QuotaType, base_url: Option<&str>) -> Option<String> {
+    let url = match quota_type {
+        QuotaType::Openrouter => "https://openrouter.ai/api/v1/credits",
+        QuotaType::Deepseek => "https://api.deepseek.com/user/balance",
+        QuotaType::Siliconflow => "https://api.siliconflow.cn/v1/user/info",
+        QuotaType::Moonshot => "https://api.moonshot.cn/v1/users/me/balance",
+        QuotaType::OpenaiBilling => {
+            let base = base_url?.trim_end_matches('/');
+            let base = base.strip_suffix("/v1").unwrap_or(base);
+            return Some(format!("{base}/v1/dashboard/billing"));
+        }
+    };
+    Some(url.to_string())
+}
+
+/// 数字或数字字符串
+fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn parse_openrouter(body: &Value) -> Option<QuotaFigures> {
+    let data = body.get("data")?;
+    let total = number(data.get("total_credits"))?;
+    let used = number(data.get("total_usage"))?;
+    Some(QuotaFigures {
+        total: Some(total),
+        used: Some(used),
+        remaining: Some(total - used),
+        unit: "USD".to_string(),
+    })
+}
+
+/// DeepSeek 按币种返回余额，取第一项
+fn parse_deepseek(body: &Value) -> Option<QuotaFigures> {
+    let info = body.get("balance_infos")?.as_array()?.first()?;
+    Some(QuotaFigures {
+        total: None,
+        used: None,
+        remaining: Some(number(info.get("total_balance"))?),
+        unit: info
+            .get("currency")
+            .and_then(Value::as_str)
+            .unwrap_or("CNY")
+            .to_string(),
+    })
+}
+
+fn parse_siliconflow(body: &Value) -> Option<QuotaFigures> {
+    let data = body.get("data")?;
+    Some(QuotaFigures {
+        total: None,
+        used: None,
+        remaining: Some(number(data.get("totalBalance"))?),
+        unit: "CNY".to_string(),
+    })
+}
+
+fn parse_moonshot(body: &Value) -> Option<QuotaFigures> {
+    let data = body.get("data")?;
+    Some(QuotaFigures {
+        total: None,
+        used: None,
+        remaining: Some(number(data.get("available_balance"))?),
+        unit: "CNY".to_string(),
+    })
+}
+
+/// OpenAI billing：额度来自 subscription 的 `hard_limit_usd`，用量来自 usage 的 `total_usage`（美分）
+fn parse_openai_billing(subscription: &Value, usage: &Value) -> Option<QuotaFigures> {
+    let total = number(subscription.get("hard_limit_usd"))?;
+    let used = number(usage.get("total_usage"))? / 100.0;
+    Some(QuotaFigures {
+        total: Some(total),
+        used: Some(used),
+        remaining: Some(total - used),
+        unit: "USD".to_string(),
+    })
+}
+
+async fn fetch_json(client: &reqwest::Client, url: &str, api_key: &str) -> Result<Value, AppError> {
+    let response = client
+        .get(url)
+        .bearer_auth(api_key)
+        .timeout(Duration::from_secs(QUOTA_TIMEOUT_SECS))
+        .send()
+        .await
+        .map_err(quota_request_failed)?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(quota_request_failed(format!("HTTP {}", status.as_u16())));
+    }
+    response.json().await.map_err(quota_request_failed)
+}
+
+fn quota_request_failed(e: impl std::fmt::Display) -> AppError {
+    AppError::localized(
+        "provider.quota_request_failed",
+        format!("查询余额失败: {e}"),
+        format!("Balance query failed: {e}"),
+    )
+}
+
+impl ProviderService {
+    /// 查询供应商的余额
+    pub async fn query_quota(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+    ) -> Result<ProviderQuota, AppError> {
+        let provider = state
+            .db
+            .get_provider_by_id(id, app_type.as_str())?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {id}"),
+                    format!("Provider not found: {id}"),
+                )
+            })?;
+        let adapter = get_adapter(&app_type);
+        let base_url = adapter.extract_base_url(&provider).ok();
+        let meta = provider.meta.as_ref();
+
+        let quota_type = meta
+            .and_then(|m| m.quota_type)
+            .or_else(|| base_url.as_deref().and_then(detect_quota_type))
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.quota_unsupported",
+                    format!(
+                        "无法识别 {} 的余额接口，请在供应商设置中指定类型",
+                        provider.name
+                    ),
+                    format!(
+                        "Cannot tell the balance API of {}; set its type in the provider settings",
+                        provider.name
+                    ),
+                )
+            })?;
+        let url = meta
+            .and_then(|m| m.quota_url.as_deref())
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+            .map(|u| u.trim_end_matches('/').to_string())
+            .or_else(|| default_quota_url(quota_type, base_url.as_deref()))
+            .ok_or_else(|| quota_request_failed("missing base URL"))?;
+        let parsed = url::Url::parse(&url).map_err(quota_request_failed)?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(quota_request_failed(format!("unsupported URL {url}")));
+        }
+
+        let api_key = adapter
+            .extract_auth(&provider)
+            .map(|auth| auth.api_key)
+            .filter(|key| !is_placeholder(key))
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.quota_missing_key",
+                    format!("{} 没有 API Key，无法查询余额", provider.name),
+                    format!("{} has no API key to query its balance", provider.name),
+                )
+            })?;
+
+        let client = http_client::get_for_provider(meta.and_then(|m| m.proxy_config.as_ref()));
+        let figures = match quota_type {
+            QuotaType::OpenaiBilling => {
+                let today = chrono::Utc::now().date_naive();
+                let start = today - chrono::Duration::days(99);
+                let end = today + chrono::Duration::days(1);
+                let subscription =
+                    fetch_json(&client, &format!("{url}/subscription"), &api_key).await?;
+                let usage = fetch_json(
+                    &client,
+                    &format!("{url}/usage?start_date={start}&end_date={end}"),
+                    &api_key,
+                )
+                .await?;
+                parse_openai_billing(&subscription, &usage)
+            }
+            QuotaType::Openrouter => parse_openrouter(&fetch_json(&client, &url, &api_key).await?),
+            QuotaType::Deepseek => parse_deepseek(&fetch_json(&client, &url, &api_key).await?),
+            QuotaType::Siliconflow => {
+                parse_siliconflow(&fetch_json(&client, &url, &api_key).await?)
+            }
+            QuotaType::Moonshot => parse_moonshot(&fetch_json(&client, &url, &api_key).await?),
+        }
+        .ok_or_else(|| {
+            AppError::localized(
+                "provider.quota_parse_failed",
+                "余额接口返回的数据格式无法识别",
+                "The balance API returned an unrecognized response",
+            )
+        })?;
+
+        Ok(ProviderQuota {
+            provider_id: provider.id,
+            quota_type,
+            total: figures.total,
+            used: figures.used,
+            remaining: figures.remaining,
+            unit: figures.unit,
+            checked_at: chrono::Utc::now().timestamp(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detects_quota_type_and_default_url_from_base_url() {
+        assert_eq!(
+            detect_quota_type("https://openrouter.ai/api"),
+            Some(QuotaType::Openrouter)
+        );
+        assert_eq!(
+            detect_quota_type("https://api.deepseek.com/anthropic"),
+            Some(QuotaType::Deepseek)
+        );
+        assert_eq!(detect_quota_type("https://notdeepseek.com"), None);
+        assert_eq!(
+            default_quota_url(
+                QuotaType::OpenaiBilling,
+                Some("https://relay.example.com/v1/")
+            ),
+            Some("https://relay.example.com/v1/dashboard/billing".to_string())
+        );
+        assert_eq!(default_quota_url(QuotaType::OpenaiBilling, None), None);
+    }
+
+    #[test]
+    fn parses_vendor_balance_responses() {
+        let openrouter = parse_openrouter(&json!({
+            "data": {"total_credits": 20.0, "total_usage": 5.5}
+        }))
+        .unwrap();
+        assert_eq!(openrouter.remaining, Some(14.5));
+
+        let deepseek = parse_deepseek(&json!({
+            "is_available": true,
+            "balance_infos": [{"currency": "CNY", "total_balance": "110.00"}]
+        }))
+        .unwrap();
+        assert_eq!(deepseek.remaining, Some(110.0));
+        assert_eq!(deepseek.unit, "CNY");
+
+        let siliconflow =
+            parse_siliconflow(&json!({"code": 20000, "data": {"totalBalance": "88.88"}})).unwrap();
+        assert_eq!(siliconflow.remaining, Some(88.88));
+
+        let moonshot =
+            parse_moonshot(&json!({"code": 0, "data": {"available_balance": 49.5}})).unwrap();
+        assert_eq!(moonshot.remaining, Some(49.5));
+
+        let billing = parse_openai_billing(
+            &json!({"hard_limit_usd": 100.0}),
+            &json!({"total_usage": 2550.0}),
+        )
+        .unwrap();
+        assert_eq!(billing.used, Some(25.5));
+        assert_eq!(billing.remaining, Some(74.5));
+
+        assert!(parse_moonshot(&json!({"error": "unauthorized"})).is_none());
+    }
+}
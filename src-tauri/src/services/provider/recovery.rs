@@ -0,0 +1,194 @@
+//! Malformed live config recovery
+//!
+//! When a tool's live config file (settings.json / config.toml / ...) can no
+//! longer be parsed, switching providers would otherwise either fail outright
+//! or silently start from an empty document. This module quarantines the broken
+//! file into `~/.cc-switch/backups/malformed/` so the switch flow can regenerate
+//! a minimal valid config from the database, and reports what was recovered.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::codex_config::{get_codex_auth_path, get_codex_config_path};
+use crate::config::{copy_file, delete_file, get_app_config_dir, get_claude_settings_path};
+use crate::error::AppError;
+use crate::gemini_config::get_gemini_settings_path;
+use crate::openclaw_config::get_openclaw_config_path;
+use crate::opencode_config::get_opencode_config_path;
+use crate::services::mcp::McpService;
+use crate::store::AppState;
+
+use super::live::sync_all_providers_to_live;
+
+/// Record of a single quarantined live config file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveConfigRecovery {
+    pub app: String,
+    pub original_path: String,
+    pub quarantine_path: String,
+    pub error: String,
+}
+
+/// Parser used to validate a live config file
+#[derive(Debug, Clone, Copy)]
+enum LiveFileFormat {
+    Json,
+    Json5,
+    Toml,
+}
+
+/// Live config files owned by an app that cc-switch parses and rewrites
+fn live_files(app_type: &AppType) -> Vec<(PathBuf, LiveFileFormat)> {
+    match app_type {
+        AppType::Claude => vec![(get_claude_settings_path(), LiveFileFormat::Json)],
+        AppType::Codex => vec![
+            (get_codex_auth_path(), LiveFileFormat::Json),
+            (get_codex_config_path(), LiveFileFormat::Toml),
+        ],
+        AppType::Gemini => vec![(get_gemini_settings_path(), LiveFileFormat::Json)],
+        AppType::OpenCode => vec![(get_opencode_config_path(), LiveFileFormat::Json)],
+        AppType::OpenClaw => vec![(get_openclaw_config_path(), LiveFileFormat::Json5)],
+    }
+}
+
+/// Return the parse error message if `content` is not a valid document.
+///
+/// Blank files are treated as valid (they are rewritten from scratch anyway).
+fn detect_parse_error(format: LiveFileFormat, content: &str) -> Option<String> {
+    if content.trim().is_empty() {
+        return None;
+    }
+    match format {
+        LiveFileFormat::Json => match serde_json::from_str::<Value>(content) {
+            Ok(v) if v.is_object() => None,
+            Ok(_) => Some("top-level value is not a JSON object".to_string()),
+            Err(e) => Some(e.to_string()),
+        },
+        LiveFileFormat::Json5 => match json5::from_str::<Value>(content) {
+            Ok(v) if v.is_object() => None,
+            Ok(_) => Some("top-level value is not a JSON object".to_string()),
+            Err(e) => Some(e.to_string()),
+        },
+        LiveFileFormat::Toml => content
+            .parse::<toml_edit::DocumentMut>()
+            .err()
+            .map(|e| e.to_string()),
+    }
+}
+
+fn quarantine_dir() -> PathBuf {
+    get_app_config_dir().join("backups").join("malformed")
+}
+
+/// Move a broken file into the quarantine directory, returning the new path
+fn quarantine_file(app_type: &AppType, path: &Path) -> Result<PathBuf, AppError> {
+    let dir = quarantine_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "config".to_string());
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let target = dir.join(format!("{}-{file_name}.{timestamp}.bak", app_type.as_str()));
+
+    // rename 可能因跨设备失败，回退到复制 + 删除
+    if std::fs::rename(path, &target).is_err() {
+        copy_file(path, &target)?;
+        delete_file(path)?;
+    }
+    Ok(target)
+}
+
+/// Quarantine every malformed live config file of `app_type`.
+///
+/// Returns an empty list when all files parse. When recovery is disabled via
+/// settings, a malformed file aborts the operation with an error instead.
+pub(crate) fn quarantine_malformed_live_configs(
+    app_type: &AppType,
+) -> Result<Vec<LiveConfigRecovery>, AppError> {
+    let mut recovered = Vec::new();
+
+    for (path, format) in live_files(app_type) {
+        if !path.exists() {
+            continue;
+        }
+        let content = match std::fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) => {
+                log::warn!("读取 live 配置失败，跳过损坏检测: {}: {e}", path.display());
+                continue;
+            }
+        };
+        let Some(error) = detect_parse_error(format, &content) else {
+            continue;
+        };
+
+        if !crate::settings::auto_recover_malformed_config_enabled() {
+            return Err(AppError::localized(
+                "live_config.malformed",
+                format!("配置文件已损坏，无法解析: {}: {error}", path.display()),
+                format!("Config file is malformed: {}: {error}", path.display()),
+            ));
+        }
+
+        log::warn!(
+            "{} live 配置损坏，将隔离并从数据库重建: {}: {error}",
+            app_type.as_str(),
+            path.display()
+        );
+        let target = quarantine_file(app_type, &path)?;
+        log::info!(
+            "已隔离损坏的配置文件: {} -> {}",
+            path.display(),
+            target.display()
+        );
+
+        recovered.push(LiveConfigRecovery {
+            app: app_type.as_str().to_string(),
+            original_path: path.display().to_string(),
+            quarantine_path: target.display().to_string(),
+            error,
+        });
+    }
+
+    Ok(recovered)
+}
+
+/// Rebuild the parts of a quarantined config that live outside the provider's
+/// own key fields: all providers for additive apps, and MCP servers.
+pub(crate) fn regenerate_after_quarantine(
+    state: &AppState,
+    app_type: &AppType,
+) -> Result<(), AppError> {
+    if app_type.is_additive_mode() {
+        sync_all_providers_to_live(state, app_type)?;
+    }
+    McpService::sync_all_enabled(state)?;
+    log::info!("{} live 配置已从数据库重建", app_type.as_str());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_parse_error_accepts_valid_documents() {
+        assert!(detect_parse_error(LiveFileFormat::Json, r#"{"env":{}}"#).is_none());
+        assert!(detect_parse_error(LiveFileFormat::Json5, "{ a: 1, // c\n }").is_none());
+        assert!(detect_parse_error(LiveFileFormat::Toml, "model = \"gpt-5\"\n").is_none());
+        assert!(detect_parse_error(LiveFileFormat::Json, "  \n").is_none());
+    }
+
+    #[test]
+    fn detect_parse_error_flags_broken_documents() {
+        assert!(detect_parse_error(LiveFileFormat::Json, r#"{"env": "#).is_some());
+        assert!(detect_parse_error(LiveFileFormat::Json, "[1, 2]").is_some());
+        assert!(detect_parse_error(LiveFileFormat::Toml, "model = ").is_some());
+    }
+}
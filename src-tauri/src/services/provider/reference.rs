@@ -0,0 +1,162 @@
+//! 只读引用供应商
+//!
+//! 团队模板仓库下发的供应商以只读引用（`meta.origin.kind = reference`）保存：
+//! 本地不能修改，只能复制出一份普通供应商再编辑；下次模板同步时按来源覆盖更新，
+//! 因此不会出现「本地改过又被模板同步覆盖」的情况。
+
+use super::ProviderService;
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::{Provider, ProviderOrigin, ProviderOriginKind};
+use crate::services::id_generator::{IdGenerator, IdKind};
+use crate::store::AppState;
+
+/// 只读引用只能由模板同步写入，拒绝外部传入的引用来源
+pub(super) fn reject_reference_origin(provider: &Provider) -> Result<(), AppError> {
+    if provider.is_reference() {
+        return Err(AppError::localized(
+            "provider.reference_origin_forbidden",
+            "只读引用供应商只能通过模板同步创建",
+            "Reference providers can only be created by template sync",
+        ));
+    }
+    Ok(())
+}
+
+/// 已保存的供应商为只读引用时拒绝修改
+pub(super) fn ensure_editable(
+    state: &AppState,
+    app_type: &AppType,
+    id: &str,
+) -> Result<(), AppError> {
+    let is_reference = state
+        .db
+        .get_provider_by_id(id, app_type.as_str())?
+        .is_some_and(|p| p.is_reference());
+    if is_reference {
+        return Err(AppError::localized(
+            "provider.read_only",
+            format!("供应商 {id} 是团队模板的只读引用，请复制后再编辑"),
+            format!("Provider {id} is a read-only team template reference; duplicate it to edit"),
+        ));
+    }
+    Ok(())
+}
+
+impl ProviderService {
+    /// 复制供应商为一份可编辑的本地供应商，返回新供应商
+    ///
+    /// 配置、元数据与自定义端点都会复制；副本不继承来源、排序位置、故障转移队列与归档状态
+    /// 以及端点的使用与测速记录。
+    pub fn duplicate(state: &AppState, app_type: AppType, id: &str) -> Result<Provider, AppError> {
+        // 按 id 读取时不带自定义端点，这里从完整列表中取
+        let source = state
+            .db
+            .get_all_providers(app_type.as_str())?
+            .shift_remove(id)
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {id}"),
+                    format!("Provider not found: {id}"),
+                )
+            })?;
+
+        let new_id = IdGenerator::new(IdKind::DuplicateProvider)
+            .name(&source.name)
+            .app(&app_type)
+            .generate(|candidate| {
+                Ok(state
+                    .db
+                    .get_provider_by_id(candidate, app_type.as_str())?
+                    .is_some())
+            })?;
+
+        let mut copy = source.clone();
+        copy.id = new_id;
+        copy.name = format!("{} copy", source.name);
+        copy.created_at = Some(chrono::Utc::now().timestamp_millis());
+        copy.sort_index = None;
+        copy.in_failover_queue = false;
+        copy.archived = false;
+        if let Some(meta) = copy.meta.as_mut() {
+            meta.origin = None;
+            for endpoint in meta.custom_endpoints.values_mut() {
+                endpoint.last_used = None;
+                endpoint.last_benchmarked = None;
+                endpoint.consecutive_failures = 0;
+                endpoint.last_latency_ms = None;
+            }
+        }
+
+        Self::add_unchecked(state, app_type, copy.clone())?;
+        Ok(copy)
+    }
+
+    /// 写入团队模板仓库中的供应商，作为只读引用保存
+    ///
+    /// 已存在的同来源引用按模板内容覆盖；与本地供应商或其他来源的引用 id 冲突时跳过该条，
+    /// 不覆盖它们的数据。
+    /// 返回实际写入的供应商 id。
+    pub fn sync_references(
+        state: &AppState,
+        app_type: AppType,
+        source: &str,
+        providers: Vec<Provider>,
+    ) -> Result<Vec<String>, AppError> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut written = Vec::new();
+
+        for mut provider in providers {
+            let existing = state
+                .db
+                .get_provider_by_id(&provider.id, app_type.as_str())?;
+            if existing.as_ref().is_some_and(|p| !p.is_reference()) {
+                log::warn!(
+                    "模板供应商 {} 与本地供应商 id 冲突，跳过同步（来源: {source}）",
+                    provider.id
+                );
+                continue;
+            }
+            if let Some(other) = existing
+                .as_ref()
+                .and_then(|p| p.meta.as_ref()?.origin.as_ref()?.source.as_deref())
+                .filter(|other| *other != source)
+            {
+                log::warn!(
+                    "模板供应商 {} 已由其他来源 {other} 同步，跳过同步（来源: {source}）",
+                    provider.id
+                );
+                continue;
+            }
+
+            provider.meta.get_or_insert_with(Default::default).origin = Some(ProviderOrigin {
+                kind: ProviderOriginKind::Reference,
+                source: Some(source.to_string()),
+                synced_at: Some(now),
+            });
+            let id = provider.id.clone();
+            match existing {
+                Some(prev) => {
+                    // 保留本地的排序与故障转移设置
+                    provider.sort_index = prev.sort_index;
+                    provider.in_failover_queue = prev.in_failover_queue;
+                    Self::update_unchecked(state, app_type.clone(), provider)?;
+                }
+                None => {
+                    Self::add_unchecked(state, app_type.clone(), provider)?;
+                }
+            }
+            written.push(id);
+        }
+
+        if !written.is_empty() {
+            log::info!(
+                "已从 {source} 同步 {} 个只读引用供应商 ({})",
+                written.len(),
+                app_type.as_str()
+            );
+        }
+        Ok(written)
+    }
+}
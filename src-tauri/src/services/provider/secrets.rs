@@ -0,0 +1,298 @@
+//! 供应商密钥补录
+//!
+//! 从深链接、SQL 备份或他人分享的配置导入供应商后，密钥常常是空的或被替换成了占位符
+//! （`<redacted>`、`****` 等）。这里列出缺少密钥的供应商，并提供按字段路径补录密钥的接口，
+//! 补录后可以立即做一次流式健康检查，便于在导入大量供应商后逐个快速填好。
+//!
+//! 官方登录（OAuth）与 OMO 类供应商不使用 API Key，不在检查范围内。
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::gemini_auth::is_google_official_gemini;
+use super::{IssueSource, ProviderService};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::secret_store::{is_placeholder, secret_paths};
+use crate::services::stream_check::{StreamCheckResult, StreamCheckService};
+use crate::store::AppState;
+
+/// 缺少密钥的供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingSecret {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    /// 建议填写的字段路径（`settingsConfig` 中以 `.` 分隔）
+    pub key_path: String,
+    /// 该应用可接受的全部密钥字段路径（首个为默认）
+    pub key_paths: Vec<String>,
+}
+
+/// 补录密钥的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretUpdateResult {
+    pub provider_id: String,
+    /// 补录后的健康检查结果（未要求检查时为 None）
+    pub probe: Option<StreamCheckResult>,
+}
+
+fn value_at<'a>(config: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(config, |node, key| node.get(key))
+}
+
+/// 不使用 API Key 的供应商（官方登录、OMO 配置）
+fn uses_no_api_key(app_type: &AppType, provider: &Provider) -> bool {
+    matches!(
+        provider.category.as_deref(),
+        Some("official" | "omo" | "omo-slim")
+    ) || (*app_type == AppType::Gemini && is_google_official_gemini(provider))
+}
+
+/// 供应商缺少密钥时返回建议填写的字段路径
+///
+/// 优先沿用配置中已经出现的（空或占位符）字段，都没有时取该应用的默认字段。
+fn missing_key_path(app_type: &AppType, provider: &Provider) -> Option<&'static str> {
+    if uses_no_api_key(app_type, provider) {
+        return None;
+    }
+    let paths = secret_paths(app_type);
+    let present: Vec<(&'static str, &str)> = paths
+        .iter()
+        .filter_map(|path| {
+            value_at(&provider.settings_config, path)
+                .and_then(Value::as_str)
+                .map(|v| (*path, v))
+        })
+        .collect();
+    if present.iter().any(|(_, value)| !is_placeholder(value)) {
+        return None;
+    }
+    Some(present.first().map(|(path, _)| *path).unwrap_or(paths[0]))
+}
+
+/// 供应商缺少密钥时返回待补录的条目
+pub(super) fn missing_secret(app_type: &AppType, provider: &Provider) -> Option<MissingSecret> {
+    let key_path = missing_key_path(app_type, provider)?;
+    Some(MissingSecret {
+        app_type: app_type.as_str().to_string(),
+        provider_id: provider.id.clone(),
+        provider_name: provider.name.clone(),
+        key_path: key_path.to_string(),
+        key_paths: secret_paths(app_type)
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+    })
+}
+
+/// 把 `value` 写到 `path`，途中缺少的对象按需创建
+pub(super) fn set_value_at(config: &mut Value, path: &str, value: &str) -> Result<(), AppError> {
+    let mut node = config;
+    let mut keys = path.split('.').peekable();
+    while let Some(key) = keys.next() {
+        if !node.is_object() {
+            if !node.is_null() {
+                return Err(AppError::InvalidInput(format!(
+                    "字段路径 {path} 经过的 {key} 不是对象"
+                )));
+            }
+            *node = Value::Object(Map::new());
+        }
+        let map = node.as_object_mut().expect("checked object above");
+        if keys.peek().is_none() {
+            map.insert(key.to_string(), Value::String(value.to_string()));
+            return Ok(());
+        }
+        node = map.entry(key.to_string()).or_insert(Value::Null);
+    }
+    Ok(())
+}
+
+impl ProviderService {
+    /// 列出缺少密钥的供应商；`app_type` 为 None 时检查全部应用
+    pub fn get_providers_missing_secrets(
+        state: &AppState,
+        app_type: Option<AppType>,
+    ) -> Result<Vec<MissingSecret>, AppError> {
+        let apps: Vec<AppType> = match app_type {
+            Some(app) => vec![app],
+            None => AppType::all().collect(),
+        };
+        let mut missing = Vec::new();
+        for app in apps {
+            missing.extend(
+                state
+                    .db
+                    .get_all_providers(app.as_str())?
+                    .values()
+                    .filter_map(|provider| missing_secret(&app, provider)),
+            );
+        }
+        Ok(missing)
+    }
+
+    /// 为供应商补录密钥，`probe` 为 true 时随后做一次流式健康检查并记录凭据问题
+    ///
+    /// `key_path` 必须是该应用的密钥字段之一（见 [`MissingSecret::key_paths`]）。
+    /// 保存走常规的供应商更新流程，当前供应商的 live 配置会同步更新。
+    pub async fn set_provider_secret(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        key_path: &str,
+        value: &str,
+        probe: bool,
+    ) -> Result<SecretUpdateResult, AppError> {
+        if !secret_paths(&app_type).contains(&key_path) {
+            return Err(AppError::localized(
+                "provider.secret_path_invalid",
+                format!("{} 不支持的密钥字段: {key_path}", app_type.as_str()),
+                format!(
+                    "Unsupported secret field for {}: {key_path}",
+                    app_type.as_str()
+                ),
+            ));
+        }
+        if is_placeholder(value) {
+            return Err(AppError::localized(
+                "provider.secret_empty",
+                "密钥不能为空或占位符",
+                "The secret must not be empty or a placeholder",
+            ));
+        }
+        let mut provider = state
+            .db
+            .get_provider_by_id(provider_id, app_type.as_str())?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {provider_id}"),
+                    format!("Provider not found: {provider_id}"),
+                )
+            })?;
+
+        set_value_at(&mut provider.settings_config, key_path, value.trim())?;
+        Self::update(state, app_type.clone(), provider.clone())?;
+
+        let probe = if probe {
+            let config = state.db.get_stream_check_config()?;
+            let result =
+                StreamCheckService::check_with_retry(&app_type, &provider, &config).await?;
+            if let Err(e) = Self::record_credential_outcome(
+                &state.db,
+                app_type.as_str(),
+                provider_id,
+                result.success,
+                result.http_status,
+                &result.message,
+                IssueSource::HealthCheck,
+            ) {
+                log::warn!("记录供应商 {provider_id} 凭据问题失败: {e}");
+            }
+            Some(result)
+        } else {
+            None
+        };
+        Ok(SecretUpdateResult {
+            provider_id: provider_id.to_string(),
+            probe,
+        })
+    }
+
+    /// 切换供应商密钥的存储位置（系统钥匙串 / 数据库明文），返回重新保存的供应商数量
+    ///
+    /// 开启前先确认钥匙串可用。读取供应商时引用已解析为真实密钥，按新设置逐个重新保存，
+    /// 由数据库层完成移入；改回明文时随后删除钥匙串中的条目（仍有无法解析的引用时保留条目）。
+    pub fn set_keychain_storage(state: &AppState, enabled: bool) -> Result<usize, AppError> {
+        if crate::settings::store_secrets_in_keychain() == enabled {
+            return Ok(0);
+        }
+        if enabled {
+            crate::secret_store::check_available()?;
+        }
+        crate::settings::set_store_secrets_in_keychain(enabled)?;
+
+        let mut migrated = 0;
+        for app in AppType::all() {
+            for provider in state.db.get_all_providers(app.as_str())?.into_values() {
+                state.db.update_provider_settings_config(
+                    app.as_str(),
+                    &provider.id,
+                    &provider.settings_config,
+                )?;
+                if !enabled {
+                    if crate::secret_store::contains_reference(&provider.settings_config) {
+                        log::warn!(
+                            "供应商 {} 仍有无法解析的钥匙串引用，保留钥匙串条目",
+                            provider.id
+                        );
+                    } else {
+                        crate::secret_store::forget(&app, &provider.id)?;
+                    }
+                }
+                migrated += 1;
+            }
+        }
+        log::info!(
+            "供应商密钥已改为存储在{}，重新保存了 {migrated} 个供应商",
+            if enabled {
+                "系统钥匙串"
+            } else {
+                "数据库"
+            }
+        );
+        Ok(migrated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn provider(config: Value) -> Provider {
+        Provider::with_id("p1".to_string(), "P1".to_string(), config, None)
+    }
+
+    #[test]
+    fn detects_blank_and_redacted_keys_only() {
+        let claude = AppType::Claude;
+        let cases = [
+            (json!({"env": {"ANTHROPIC_AUTH_TOKEN": "sk-live"}}), None),
+            (
+                json!({"env": {"ANTHROPIC_API_KEY": "<redacted>"}}),
+                Some("env.ANTHROPIC_API_KEY"),
+            ),
+            (
+                json!({"env": {"ANTHROPIC_AUTH_TOKEN": "****"}}),
+                Some("env.ANTHROPIC_AUTH_TOKEN"),
+            ),
+            (json!({"env": {}}), Some("env.ANTHROPIC_AUTH_TOKEN")),
+        ];
+        for (config, expected) in cases {
+            assert_eq!(
+                missing_key_path(&claude, &provider(config.clone())),
+                expected,
+                "{config}"
+            );
+        }
+
+        let mut official = provider(json!({"env": {}}));
+        official.category = Some("official".to_string());
+        assert_eq!(missing_key_path(&claude, &official), None);
+    }
+
+    #[test]
+    fn set_value_creates_missing_objects() {
+        let mut config = json!({"auth": null, "config": "model = \"x\""});
+        set_value_at(&mut config, "auth.OPENAI_API_KEY", "sk-new").unwrap();
+        assert_eq!(config["auth"]["OPENAI_API_KEY"], "sk-new");
+        assert_eq!(config["config"], "model = \"x\"");
+
+        assert!(set_value_at(&mut config, "config.key", "v").is_err());
+    }
+}
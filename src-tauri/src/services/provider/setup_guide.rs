@@ -0,0 +1,94 @@
+//! 供应商配置指南
+//!
+//! 按供应商分类（`category`）给出需要填写的字段、Base URL 的常见写法与易错点，
+//! 供新增供应商表单展示上下文帮助。数据随程序打包（`setup_guides.json`），不依赖网络。
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use super::ProviderService;
+
+/// 中英双语文本，由前端按界面语言选择
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuideText {
+    pub zh: String,
+    pub en: String,
+}
+
+/// 需要填写的配置字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuideField {
+    /// 环境变量名或配置键
+    pub key: String,
+    /// 适用的 app；为空表示全部
+    #[serde(default)]
+    pub apps: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+    pub label: GuideText,
+    pub help: GuideText,
+    #[serde(default)]
+    pub example: Option<String>,
+}
+
+/// Base URL 的常见写法
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseUrlPattern {
+    pub pattern: String,
+    pub note: GuideText,
+}
+
+/// 某一供应商分类的配置指南
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderSetupGuide {
+    pub category: String,
+    pub title: GuideText,
+    pub summary: GuideText,
+    pub fields: Vec<GuideField>,
+    pub base_url_patterns: Vec<BaseUrlPattern>,
+    pub pitfalls: Vec<GuideText>,
+}
+
+static SETUP_GUIDES: Lazy<Vec<ProviderSetupGuide>> = Lazy::new(|| {
+    serde_json::from_str(include_str!("setup_guides.json")).expect("内置配置指南格式错误")
+});
+
+impl ProviderService {
+    /// 获取分类的配置指南，未收录的分类返回 None
+    pub fn get_setup_guide(category: &str) -> Option<ProviderSetupGuide> {
+        SETUP_GUIDES
+            .iter()
+            .find(|guide| guide.category == category)
+            .cloned()
+    }
+
+    /// 获取全部内置配置指南
+    pub fn list_setup_guides() -> Vec<ProviderSetupGuide> {
+        SETUP_GUIDES.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_guides_cover_every_form_category() {
+        for category in [
+            "official",
+            "cn_official",
+            "cloud_provider",
+            "aggregator",
+            "third_party",
+            "custom",
+        ] {
+            let guide = ProviderService::get_setup_guide(category)
+                .unwrap_or_else(|| panic!("missing guide for {category}"));
+            assert!(!guide.summary.zh.is_empty() && !guide.summary.en.is_empty());
+        }
+        assert!(ProviderService::get_setup_guide("unknown").is_none());
+    }
+}
@@ -0,0 +1,42 @@
+//! 供应商标签
+//!
+//! 供应商可以打上任意标签（如厂商、地区、价格档位），标签以 JSON 数组存放在 `providers.tags` 列中，
+//! 筛选在数据库中完成，供应商很多时前端不必拉取全部再过滤。标签比较不区分大小写。
+
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use super::ProviderService;
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 标签及使用该标签的供应商数量
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderTag {
+    pub tag: String,
+    pub count: usize,
+}
+
+impl ProviderService {
+    /// 列出带有指定标签的供应商
+    pub fn list_by_tag(
+        state: &AppState,
+        app_type: AppType,
+        tag: &str,
+    ) -> Result<IndexMap<String, Provider>, AppError> {
+        state.db.get_providers_by_tag(app_type.as_str(), tag)
+    }
+
+    /// 列出应用下使用过的标签
+    pub fn list_tags(state: &AppState, app_type: AppType) -> Result<Vec<ProviderTag>, AppError> {
+        Ok(state
+            .db
+            .get_provider_tags(app_type.as_str())?
+            .into_iter()
+            .map(|(tag, count)| ProviderTag { tag, count })
+            .collect())
+    }
+}
@@ -0,0 +1,456 @@
+//! 供应商导出 / 导入
+//!
+//! 把某个应用的供应商导出为 JSON，便于分享配置而不泄露密钥：密钥字段（见 [`secret_paths`]）
+//! 以及用量查询脚本中的 API Key、访问令牌在导出时被清空；提供口令时这些密钥改为加密后
+//! 随供应商一起保存。口令经 PBKDF2-HMAC-SHA256 派生出 AES-256-GCM 密钥，盐与迭代次数写在文件中。
+//!
+//! 导入时先解密全部供应商再写入，口令错误不会写入任何供应商。之后逐个添加，单个供应商添加失败时
+//! 记入结果的 `failed` 并继续导入其余供应商，已导入的供应商不回滚。被清空的密钥在结果中列出，
+//! 由用户按 [`MissingSecret`] 逐个补录。
+//!
+//! 导入的供应商一律记为未验证（见 [`ProviderTrust`]），不沿用导出文件中的信任级别或官方分类。
+
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::pbkdf2;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::secrets::{missing_secret, set_value_at};
+use super::{MissingSecret, ProviderAuditOrigin, ProviderService};
+use crate::app_config::AppType;
+use crate::config::write_text_file;
+use crate::crypto::{self, random_bytes, KEY_LEN};
+use crate::error::AppError;
+use crate::provider::{Provider, ProviderTrust};
+use crate::secret_store::{is_placeholder, secret_paths};
+use crate::services::id_generator::{IdGenerator, IdKind};
+use crate::store::AppState;
+
+/// 供应商导出文件格式标识
+pub const PROVIDER_EXPORT_FORMAT: &str = "cc-switch-providers";
+/// 当前供应商导出文件版本
+pub const PROVIDER_EXPORT_VERSION: u32 = 1;
+/// 导出时使用的 PBKDF2 迭代次数
+const PBKDF2_ITERATIONS: u32 = 600_000;
+/// 导入时接受的迭代次数范围，避免构造的文件削弱加密或让导入卡住
+const PBKDF2_ITERATIONS_RANGE: std::ops::RangeInclusive<u32> = 100_000..=10_000_000;
+const SALT_LEN: usize = 16;
+/// 绑定到密文上的附加数据，防止把其他用途的密文当作供应商密钥解密
+const SECRETS_AAD: &[u8] = b"cc-switch-provider-secrets-v1";
+/// 用量查询脚本密钥在密钥表中的键（与 `settingsConfig` 字段路径不会重名）
+const USAGE_API_KEY: &str = "usageScript.apiKey";
+const USAGE_ACCESS_TOKEN: &str = "usageScript.accessToken";
+
+/// 供应商导出文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderExport {
+    /// 固定为 [`PROVIDER_EXPORT_FORMAT`]
+    pub format: String,
+    pub version: u32,
+    /// 导出时间（Unix 毫秒）
+    pub exported_at: i64,
+    pub app_type: String,
+    /// 密钥加密参数；为空表示密钥已被清空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<ExportEncryption>,
+    pub providers: Vec<ExportedProvider>,
+}
+
+/// 口令加密参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportEncryption {
+    /// base64 编码的盐
+    pub salt: String,
+    pub iterations: u32,
+}
+
+/// 导出文件中的供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedProvider {
+    /// 密钥已清空的供应商
+    pub provider: Provider,
+    /// 加密后的密钥表（base64 编码的 nonce + 密文 + tag）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_secrets: Option<String>,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderImportResult {
+    pub app_type: String,
+    /// 导入后的供应商 id（原 id 已被占用时为新生成的 id）
+    pub imported: Vec<String>,
+    /// 仍缺少密钥、需要用户补录的供应商
+    pub missing_secrets: Vec<MissingSecret>,
+    /// 没有导入的供应商及原因
+    pub failed: Vec<String>,
+}
+
+/// 取出供应商中的密钥并在原处留空，返回 字段路径 -> 密钥
+fn take_secrets(app_type: &AppType, provider: &mut Provider) -> BTreeMap<String, String> {
+    let mut secrets = BTreeMap::new();
+    for path in secret_paths(app_type) {
+        let Some(slot) = path
+            .split('.')
+            .try_fold(&mut provider.settings_config, |node, key| node.get_mut(key))
+        else {
+            continue;
+        };
+        let Some(secret) = slot.as_str() else {
+            continue;
+        };
+        if !is_placeholder(secret) {
+            secrets.insert(path.to_string(), secret.to_string());
+        }
+        *slot = Value::String(String::new());
+    }
+
+    if let Some(script) = provider
+        .meta
+        .as_mut()
+        .and_then(|meta| meta.usage_script.as_mut())
+    {
+        for (key, slot) in [
+            (USAGE_API_KEY, &mut script.api_key),
+            (USAGE_ACCESS_TOKEN, &mut script.access_token),
+        ] {
+            if let Some(secret) = slot.take().filter(|s| !is_placeholder(s)) {
+                secrets.insert(key.to_string(), secret);
+            }
+        }
+    }
+    secrets
+}
+
+/// 把密钥写回供应商；不认识的字段忽略
+fn restore_secrets(
+    app_type: &AppType,
+    provider: &mut Provider,
+    secrets: BTreeMap<String, String>,
+) -> Result<(), AppError> {
+    for (path, secret) in secrets {
+        let script = provider
+            .meta
+            .as_mut()
+            .and_then(|meta| meta.usage_script.as_mut());
+        match (path.as_str(), script) {
+            (USAGE_API_KEY, Some(script)) => script.api_key = Some(secret),
+            (USAGE_ACCESS_TOKEN, Some(script)) => script.access_token = Some(secret),
+            (path, _) if secret_paths(app_type).contains(&path) => {
+                set_value_at(&mut provider.settings_config, path, &secret)?
+            }
+            (path, _) => log::warn!("导入供应商 {} 时忽略未知的密钥字段 {path}", provider.id),
+        }
+    }
+    Ok(())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; KEY_LEN] {
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        NonZeroU32::new(iterations).unwrap_or(NonZeroU32::MIN),
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    key
+}
+
+fn wrong_passphrase() -> AppError {
+    AppError::localized(
+        "provider.export_passphrase_wrong",
+        "口令错误或导出文件已损坏，无法解密供应商密钥",
+        "Wrong passphrase or corrupted export file; provider secrets cannot be decrypted",
+    )
+}
+
+impl ProviderService {
+    /// 导出应用的供应商；`ids` 为空时导出全部
+    ///
+    /// 提供 `passphrase` 时密钥加密保存，否则密钥被清空。
+    pub fn export_providers(
+        state: &AppState,
+        app_type: AppType,
+        ids: Option<&[String]>,
+        passphrase: Option<&str>,
+    ) -> Result<ProviderExport, AppError> {
+        let mut all = state.db.get_all_providers(app_type.as_str())?;
+        let providers: Vec<Provider> = match ids {
+            Some(ids) => ids
+                .iter()
+                .map(|id| {
+                    all.shift_remove(id).ok_or_else(|| {
+                        AppError::localized(
+                            "provider.not_found",
+                            format!("供应商不存在: {id}"),
+                            format!("Provider not found: {id}"),
+                        )
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+            None => all.into_values().collect(),
+        };
+
+        let passphrase = passphrase.filter(|p| !p.is_empty());
+        let mut encryption = None;
+        let mut key = None;
+        if let Some(passphrase) = passphrase {
+            let salt = random_bytes::<SALT_LEN>()?;
+            key = Some(derive_key(passphrase, &salt, PBKDF2_ITERATIONS));
+            encryption = Some(ExportEncryption {
+                salt: STANDARD.encode(salt),
+                iterations: PBKDF2_ITERATIONS,
+            });
+        }
+
+        let mut exported = Vec::with_capacity(providers.len());
+        for mut provider in providers {
+            let secrets = take_secrets(&app_type, &mut provider);
+            let encrypted_secrets = match &key {
+                Some(key) if !secrets.is_empty() => {
+                    let plaintext = serde_json::to_vec(&secrets)
+                        .map_err(|source| AppError::JsonSerialize { source })?;
+                    Some(STANDARD.encode(crypto::seal(key, SECRETS_AAD, &plaintext)?))
+                }
+                _ => None,
+            };
+            exported.push(ExportedProvider {
+                provider,
+                encrypted_secrets,
+            });
+        }
+
+        Ok(ProviderExport {
+            format: PROVIDER_EXPORT_FORMAT.to_string(),
+            version: PROVIDER_EXPORT_VERSION,
+            exported_at: chrono::Utc::now().timestamp_millis(),
+            app_type: app_type.as_str().to_string(),
+            encryption,
+            providers: exported,
+        })
+    }
+
+    /// 导出到 JSON 文件，返回导出的供应商数量
+    pub fn export_providers_to_file(
+        state: &AppState,
+        app_type: AppType,
+        path: &Path,
+        ids: Option<&[String]>,
+        passphrase: Option<&str>,
+    ) -> Result<usize, AppError> {
+        let export = Self::export_providers(state, app_type, ids, passphrase)?;
+        let json = serde_json::to_string_pretty(&export)
+            .map_err(|source| AppError::JsonSerialize { source })?;
+        write_text_file(path, &json)?;
+        log::info!(
+            "已导出 {} 个 {} 供应商到 {}",
+            export.providers.len(),
+            export.app_type,
+            path.display()
+        );
+        Ok(export.providers.len())
+    }
+
+    /// 从 JSON 文件导入供应商
+    pub fn import_providers_from_file(
+        state: &AppState,
+        path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<ProviderImportResult, AppError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+        let export: ProviderExport = serde_json::from_str(&raw).map_err(|e| {
+            AppError::localized(
+                "provider.export_invalid",
+                format!("无效的供应商导出文件: {e}"),
+                format!("Invalid provider export file: {e}"),
+            )
+        })?;
+        Self::import_providers(state, export, passphrase)
+    }
+
+    /// 导入供应商导出文件
+    ///
+    /// 密钥已加密时需要口令；原 id 已被占用的供应商按名称生成新 id。
+    /// 单个供应商添加失败时记入 `failed`，其余供应商照常导入。
+    /// 本机相关的状态（故障转移队列、排序、自动化授权、模板来源）不随导入保留。
+    pub fn import_providers(
+        state: &AppState,
+        export: ProviderExport,
+        passphrase: Option<&str>,
+    ) -> Result<ProviderImportResult, AppError> {
+        if export.format != PROVIDER_EXPORT_FORMAT || export.version > PROVIDER_EXPORT_VERSION {
+            return Err(AppError::localized(
+                "provider.export_unsupported",
+                format!(
+                    "不支持的供应商导出文件: {} v{}",
+                    export.format, export.version
+                ),
+                format!(
+                    "Unsupported provider export file: {} v{}",
+                    export.format, export.version
+                ),
+            ));
+        }
+        let app_type: AppType = export.app_type.parse()?;
+
+        let key =
+            match (&export.encryption, passphrase.filter(|p| !p.is_empty())) {
+                (None, _) => None,
+                (Some(_), None) => return Err(AppError::localized(
+                    "provider.export_passphrase_required",
+                    "导出文件中的密钥已加密，请输入导出时设置的口令",
+                    "Secrets in this export are encrypted; enter the passphrase used for export",
+                )),
+                (Some(encryption), Some(passphrase)) => {
+                    if !PBKDF2_ITERATIONS_RANGE.contains(&encryption.iterations) {
+                        return Err(wrong_passphrase());
+                    }
+                    let salt = STANDARD
+                        .decode(&encryption.salt)
+                        .map_err(|_| wrong_passphrase())?;
+                    Some(derive_key(passphrase, &salt, encryption.iterations))
+                }
+            };
+
+        // 先全部解密，口令错误时不写入任何供应商
+        let mut providers = Vec::with_capacity(export.providers.len());
+        for entry in export.providers {
+            let mut provider = entry.provider;
+            if let (Some(key), Some(data)) = (&key, &entry.encrypted_secrets) {
+                let sealed = STANDARD
+                    .decode(data.trim())
+                    .map_err(|_| wrong_passphrase())?;
+                let plaintext =
+                    crypto::open(key, SECRETS_AAD, &sealed).ok_or_else(wrong_passphrase)?;
+                let secrets: BTreeMap<String, String> =
+                    serde_json::from_slice(&plaintext).map_err(|_| wrong_passphrase())?;
+                restore_secrets(&app_type, &mut provider, secrets)?;
+            }
+            providers.push(provider);
+        }
+
+        let mut result = ProviderImportResult {
+            app_type: app_type.as_str().to_string(),
+            ..Default::default()
+        };
+        for mut provider in providers {
+            let taken = provider.id.trim().is_empty()
+                || state
+                    .db
+                    .get_provider_by_id(&provider.id, app_type.as_str())?
+                    .is_some();
+            if taken {
+                provider.id = IdGenerator::new(IdKind::ImportedProvider)
+                    .name(&provider.name)
+                    .app(&app_type)
+                    .generate(|candidate| {
+                        Ok(state
+                            .db
+                            .get_provider_by_id(candidate, app_type.as_str())?
+                            .is_some())
+                    })?;
+            }
+            provider.sort_index = None;
+            provider.in_failover_queue = false;
+            let meta = provider.meta.get_or_insert_with(Default::default);
+            meta.origin = None;
+            meta.automation_allowed = None;
+            // 显式记为未验证，官方分类也不能绕过信任确认
+            meta.trust = Some(ProviderTrust::Unverified);
+
+            if let Err(e) = Self::add_from(
+                state,
+                app_type.clone(),
+                provider.clone(),
+                ProviderAuditOrigin::Import,
+            ) {
+                log::warn!("导入供应商 {} 失败: {e}", provider.name);
+                result.failed.push(format!("{}: {e}", provider.name));
+                continue;
+            }
+            result
+                .missing_secrets
+                .extend(missing_secret(&app_type, &provider));
+            result.imported.push(provider.id);
+        }
+        log::info!(
+            "已导入 {} 个 {} 供应商，{} 个需要补录密钥，{} 个失败",
+            result.imported.len(),
+            result.app_type,
+            result.missing_secrets.len(),
+            result.failed.len()
+        );
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{ProviderMeta, UsageScript};
+    use serde_json::json;
+
+    #[test]
+    fn secrets_are_taken_and_restored() {
+        let mut provider = Provider::with_id(
+            "relay".to_string(),
+            "Relay".to_string(),
+            json!({"env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-relay",
+                "ANTHROPIC_BASE_URL": "https://relay.example.com"
+            }}),
+            None,
+        );
+        provider.meta = Some(ProviderMeta {
+            usage_script: Some(UsageScript {
+                enabled: true,
+                language: "javascript".to_string(),
+                code: String::new(),
+                timeout: None,
+                api_key: Some("usage-key".to_string()),
+                base_url: None,
+                access_token: None,
+                user_id: None,
+                template_type: None,
+                auto_query_interval: None,
+            }),
+            ..Default::default()
+        });
+        let original = serde_json::to_value(&provider).unwrap();
+
+        let secrets = take_secrets(&AppType::Claude, &mut provider);
+        assert_eq!(secrets.len(), 2);
+        assert_eq!(provider.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"], "");
+        assert!(!serde_json::to_string(&provider)
+            .unwrap()
+            .contains("usage-key"));
+
+        restore_secrets(&AppType::Claude, &mut provider, secrets).unwrap();
+        assert_eq!(serde_json::to_value(&provider).unwrap(), original);
+    }
+
+    #[test]
+    fn sealed_secrets_open_only_with_the_same_passphrase() {
+        let salt = random_bytes::<SALT_LEN>().unwrap();
+        let key = derive_key("correct horse", &salt, 1_000);
+        let sealed =
+            crypto::seal(&key, SECRETS_AAD, b"{\"env.ANTHROPIC_AUTH_TOKEN\":\"sk\"}").unwrap();
+
+        assert_eq!(
+            crypto::open(&key, SECRETS_AAD, &sealed).unwrap(),
+            b"{\"env.ANTHROPIC_AUTH_TOKEN\":\"sk\"}"
+        );
+        let wrong = derive_key("battery staple", &salt, 1_000);
+        assert!(crypto::open(&wrong, SECRETS_AAD, &sealed).is_none());
+    }
+}
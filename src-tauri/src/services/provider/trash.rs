@@ -0,0 +1,162 @@
+//! 供应商回收站
+//!
+//! 删除供应商只是软删除（`providers.deleted_at`）：记录保留在回收站中，可以恢复；
+//! 超过保留期（设置 `providerTrashRetentionDays`，默认 30 天）后永久删除。恢复与永久删除都记入审计，
+//! 按保留期自动清理的来源为定时任务。
+//! 删除当前供应商时先切换到备用供应商（故障转移队列中的第一个，否则为排序最前的可用供应商），
+//! 不会让应用处于没有 live 配置的状态。
+
+use serde::{Deserialize, Serialize};
+
+use super::live::write_live_snapshot;
+use super::{ProviderAuditAction, ProviderAuditOrigin, ProviderService};
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 回收站中的供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedProvider {
+    pub provider: Provider,
+    /// 删除时间（Unix 秒）
+    pub deleted_at: i64,
+}
+
+impl ProviderService {
+    /// 把供应商移入回收站，顺带清理超过保留期的记录
+    pub(super) fn trash(state: &AppState, app_type: &AppType, id: &str) -> Result<(), AppError> {
+        state
+            .db
+            .trash_provider(app_type.as_str(), id, chrono::Utc::now().timestamp())?;
+        if let Err(e) = Self::purge_expired_trash(&state.db) {
+            log::warn!("清理供应商回收站失败: {e}");
+        }
+        Ok(())
+    }
+
+    /// 删除当前供应商前选出的备用供应商：故障转移队列优先，其次按排序取第一个可用的
+    ///
+    /// 自动切换不会选中未验证且未获允许的供应商（见 [`Provider::allowed_in_automation`]）。
+    pub(super) fn fallback_for_delete(
+        state: &AppState,
+        app_type: &AppType,
+        id: &str,
+    ) -> Result<Option<String>, AppError> {
+        let providers = state.db.get_all_providers(app_type.as_str())?;
+        let now = chrono::Utc::now().timestamp();
+        let usable = |candidate: &str| {
+            candidate != id
+                && providers.get(candidate).is_some_and(|p| {
+                    !p.archived && !p.in_maintenance(now) && p.allowed_in_automation()
+                })
+        };
+
+        let queued = state
+            .db
+            .get_failover_queue(app_type.as_str())?
+            .into_iter()
+            .map(|item| item.provider_id)
+            .find(|candidate| usable(candidate));
+        Ok(queued.or_else(|| {
+            providers
+                .keys()
+                .find(|candidate| usable(candidate))
+                .cloned()
+        }))
+    }
+
+    /// 回收站中的供应商（最近删除的在前）
+    pub fn get_trash(
+        state: &AppState,
+        app_type: AppType,
+    ) -> Result<Vec<TrashedProvider>, AppError> {
+        state.db.get_trashed_providers(app_type.as_str())
+    }
+
+    /// 从回收站恢复供应商
+    ///
+    /// 恢复的供应商不会成为当前供应商；累加模式应用会重新写入 live 配置。
+    pub fn restore(state: &AppState, app_type: AppType, id: &str) -> Result<Provider, AppError> {
+        let provider = state
+            .db
+            .restore_trashed_provider(app_type.as_str(), id)?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_in_trash",
+                    format!("回收站中没有该供应商: {id}"),
+                    format!("Provider not found in trash: {id}"),
+                )
+            })?;
+
+        let omo = matches!(provider.category.as_deref(), Some("omo" | "omo-slim"));
+        if app_type.is_additive_mode() && !omo && !provider.archived {
+            if let Err(e) = write_live_snapshot(&app_type, &provider) {
+                state
+                    .db
+                    .trash_provider(app_type.as_str(), id, chrono::Utc::now().timestamp())?;
+                return Err(e);
+            }
+        }
+        Self::record_create_audit(
+            state,
+            &app_type,
+            &provider,
+            ProviderAuditAction::Restore,
+            ProviderAuditOrigin::Ui,
+        );
+        log::info!("[{}] 已从回收站恢复供应商 {id}", app_type.as_str());
+        Ok(provider)
+    }
+
+    /// 永久删除回收站中的供应商：`id` 为 None 时清空该应用的回收站，返回删除的数量
+    pub fn purge_trash(
+        state: &AppState,
+        app_type: AppType,
+        id: Option<&str>,
+    ) -> Result<usize, AppError> {
+        let purged = match id {
+            Some(id) => {
+                let trashed = state
+                    .db
+                    .get_trashed_providers(app_type.as_str())?
+                    .iter()
+                    .any(|t| t.provider.id == id);
+                if trashed {
+                    state.db.delete_provider(app_type.as_str(), id)?;
+                    vec![(app_type.as_str().to_string(), id.to_string())]
+                } else {
+                    Vec::new()
+                }
+            }
+            None => state
+                .db
+                .purge_provider_trash(Some(app_type.as_str()), i64::MAX)?,
+        };
+        Self::record_purge_audit(&state.db, &purged, ProviderAuditOrigin::Ui);
+        Ok(purged.len())
+    }
+
+    /// 清理超过保留期（设置 `providerTrashRetentionDays`）的回收站记录，返回删除的数量
+    pub fn purge_expired_trash(db: &Database) -> Result<usize, AppError> {
+        let days = crate::settings::effective_provider_trash_retention_days();
+        if days == 0 {
+            return Ok(0);
+        }
+        let cutoff = chrono::Utc::now().timestamp() - i64::from(days) * 86_400;
+        let purged = db.purge_provider_trash(None, cutoff)?;
+        Self::record_purge_audit(db, &purged, ProviderAuditOrigin::Schedule);
+        if !purged.is_empty() {
+            log::info!("已清理 {} 个超过 {days} 天的已删除供应商", purged.len());
+        }
+        Ok(purged.len())
+    }
+
+    fn record_purge_audit(db: &Database, purged: &[(String, String)], origin: ProviderAuditOrigin) {
+        for (app, id) in purged {
+            Self::record_audit(db, app, id, ProviderAuditAction::Purge, origin, None, None);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! 供应商信任级别
+//!
+//! 供应商分为官方、已验证中转与未验证中转三级（见 [`ProviderTrust`]）。
+//! 未验证的供应商加入故障转移队列前需要用户确认，确认结果记录为“允许自动化使用”；
+//! 故障转移、自动选择最快供应商以及删除当前供应商时的自动切换都跳过未验证且未获允许的供应商。
+//! 降级为未验证时撤销此前的允许，并移出故障转移队列。
+
+use serde::Serialize;
+
+use super::{ProviderAuditOrigin, ProviderService};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::{Provider, ProviderTrust};
+use crate::store::AppState;
+
+/// 自动选择最快供应商的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FastestProviderPick {
+    /// 选中的供应商；没有可用的测速结果时为空
+    pub provider_id: Option<String>,
+    /// 是否发生了切换（选中的已是当前供应商时为 false）
+    pub switched: bool,
+    /// 比选中者更快、但因未验证而被跳过的供应商名称
+    pub skipped_untrusted: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ProviderService {
+    /// 设置供应商的信任级别，返回更新后的供应商
+    ///
+    /// 从官方或已验证降为未验证时，清除“允许自动化使用”并移出故障转移队列，需重新确认。
+    pub fn set_trust(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        trust: ProviderTrust,
+    ) -> Result<Provider, AppError> {
+        let mut provider = Self::load_for_trust(state, &app_type, id)?;
+        let previous = provider.clone();
+        let downgraded = trust == ProviderTrust::Unverified
+            && provider.trust_level() != ProviderTrust::Unverified;
+        let meta = provider.meta.get_or_insert_with(Default::default);
+        meta.trust = Some(trust);
+        if downgraded {
+            meta.automation_allowed = None;
+        }
+        state.db.save_provider(app_type.as_str(), &provider)?;
+        if downgraded && provider.in_failover_queue {
+            state.db.remove_from_failover_queue(app_type.as_str(), id)?;
+            provider.in_failover_queue = false;
+            log::info!(
+                "[{}] 供应商 {id} 降为未验证，已移出故障转移队列",
+                app_type.as_str()
+            );
+        }
+        Self::record_update_audit(
+            state,
+            &app_type,
+            Some(&previous),
+            &provider,
+            ProviderAuditOrigin::Ui,
+        );
+        Ok(provider)
+    }
+
+    /// 设置未验证供应商是否允许被自动化功能使用，返回更新后的供应商
+    pub fn set_automation_allowed(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        allowed: bool,
+    ) -> Result<Provider, AppError> {
+        let mut provider = Self::load_for_trust(state, &app_type, id)?;
+        let previous = provider.clone();
+        provider
+            .meta
+            .get_or_insert_with(Default::default)
+            .automation_allowed = Some(allowed);
+        state.db.save_provider(app_type.as_str(), &provider)?;
+        Self::record_update_audit(
+            state,
+            &app_type,
+            Some(&previous),
+            &provider,
+            ProviderAuditOrigin::Ui,
+        );
+        Ok(provider)
+    }
+
+    /// 将供应商加入故障转移队列
+    ///
+    /// 未验证且未获允许的供应商需要 `confirmed` 为 true，确认后记录为允许自动化使用。
+    pub fn add_to_failover_queue(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        confirmed: bool,
+    ) -> Result<(), AppError> {
+        let provider = Self::load_for_trust(state, &app_type, id)?;
+        Self::reject_archived(&provider)?;
+        if !provider.allowed_in_automation() {
+            if !confirmed {
+                return Err(AppError::localized(
+                    "provider.untrusted_needs_confirmation",
+                    format!(
+                        "{} 是未验证的供应商，故障转移时请求（包括代码与密钥）会自动发往该供应商，请确认后再加入",
+                        provider.name
+                    ),
+                    format!(
+                        "{} is an unverified provider; failover would send requests (including code and keys) to it automatically. Confirm before adding it",
+                        provider.name
+                    ),
+                ));
+            }
+            Self::set_automation_allowed(state, app_type.clone(), id, true)?;
+            log::info!(
+                "[{}] 用户确认将未验证的供应商 {id} 用于故障转移",
+                app_type.as_str()
+            );
+        }
+        state.db.add_to_failover_queue(app_type.as_str(), id)
+    }
+
+    /// 按最近一次测速结果切换到最快的供应商
+    ///
+    /// 跳过测速失败、已归档、维护中以及未验证且未获允许的供应商。切换的审计记录来源为 `origin`。
+    pub fn switch_to_fastest(
+        state: &AppState,
+        app_type: AppType,
+        origin: ProviderAuditOrigin,
+    ) -> Result<FastestProviderPick, AppError> {
+        if app_type.is_additive_mode() {
+            return Err(AppError::localized(
+                "provider.fastest_additive_app",
+                format!(
+                    "{} 的供应商同时生效，没有“当前供应商”可以切换",
+                    app_type.as_str()
+                ),
+                format!(
+                    "{} uses all providers at once and has no current provider to switch",
+                    app_type.as_str()
+                ),
+            ));
+        }
+        let providers = state.db.get_all_providers(app_type.as_str())?;
+        let now = chrono::Utc::now().timestamp();
+
+        let mut pick = FastestProviderPick::default();
+        for result in Self::get_provider_benchmarks(state, app_type.clone())? {
+            if result.error.is_some() || result.first_byte_ms.is_none() {
+                continue;
+            }
+            let Some(provider) = providers.get(&result.provider_id) else {
+                continue;
+            };
+            if provider.archived || provider.in_maintenance(now) {
+                continue;
+            }
+            if !provider.allowed_in_automation() {
+                pick.skipped_untrusted.push(provider.name.clone());
+                continue;
+            }
+            pick.provider_id = Some(provider.id.clone());
+            break;
+        }
+
+        let Some(id) = pick.provider_id.clone() else {
+            return Ok(pick);
+        };
+        let current = crate::settings::get_effective_current_provider(&state.db, &app_type)?;
+        if current.as_deref() != Some(id.as_str()) {
+            pick.warnings = Self::switch_from(state, app_type, &id, origin)?.warnings;
+            pick.switched = true;
+        }
+        Ok(pick)
+    }
+
+    fn load_for_trust(
+        state: &AppState,
+        app_type: &AppType,
+        id: &str,
+    ) -> Result<Provider, AppError> {
+        state
+            .db
+            .get_provider_by_id(id, app_type.as_str())?
+            .ok_or_else(|| {
+                AppError::localized(
+                    "provider.not_found",
+                    format!("供应商不存在: {id}"),
+                    format!("Provider not found: {id}"),
+                )
+            })
+    }
+}
@@ -0,0 +1,155 @@
+//! 统一供应商应用到多个应用
+//!
+//! 一个统一供应商可以同时生成 Claude、Codex、Gemini 三种形态的供应商（id 为
+//! `universal-<应用>-<统一供应商 id>`）。生成的供应商以 `meta.origin = { kind: "universal", source: <统一供应商 id> }`
+//! 记录关联：之后编辑统一供应商并同步时更新同一批供应商（只覆盖名称、网址、分类与配置，保留本地的
+//! 备注、图标、排序、标签与元数据），删除统一供应商时一并移入回收站。生成、更新与删除都经过普通
+//! 供应商的校验、live 配置写入、当前供应商切换与审计。
+
+use serde::Serialize;
+
+use super::{ProviderAuditOrigin, ProviderService};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::{
+    Provider, ProviderOrigin, ProviderOriginKind, UniversalProvider, UniversalProviderApps,
+};
+use crate::store::AppState;
+
+/// 统一供应商支持的应用
+const UNIVERSAL_APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+/// 统一供应商在某个应用下生成的供应商
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedUniversalProvider {
+    pub app: String,
+    pub provider_id: String,
+    /// 是否为新建（否则为更新已关联的供应商）
+    pub created: bool,
+}
+
+/// 应用统一供应商的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UniversalApplyResult {
+    pub applied: Vec<AppliedUniversalProvider>,
+    /// 因应用被取消而删除的供应商 id
+    pub removed: Vec<String>,
+}
+
+pub(super) fn universal_child_id(app_type: &AppType, universal_id: &str) -> String {
+    format!("universal-{}-{universal_id}", app_type.as_str())
+}
+
+impl ProviderService {
+    /// 把统一供应商应用到指定的应用：为每个应用创建或更新关联的供应商，未列出的应用删除已生成的供应商
+    pub fn apply_universal(
+        state: &AppState,
+        id: &str,
+        apps: &[AppType],
+    ) -> Result<UniversalApplyResult, AppError> {
+        if let Some(app) = apps.iter().find(|app| !UNIVERSAL_APPS.contains(app)) {
+            return Err(AppError::localized(
+                "provider.universal_app_unsupported",
+                format!("统一供应商不支持应用到 {}", app.as_str()),
+                format!("Universal providers cannot be applied to {}", app.as_str()),
+            ));
+        }
+        let mut universal = state.db.get_universal_provider(id)?.ok_or_else(|| {
+            AppError::localized(
+                "provider.universal_not_found",
+                format!("统一供应商 {id} 不存在"),
+                format!("Universal provider {id} not found"),
+            )
+        })?;
+
+        universal.apps = UniversalProviderApps {
+            claude: apps.contains(&AppType::Claude),
+            codex: apps.contains(&AppType::Codex),
+            gemini: apps.contains(&AppType::Gemini),
+        };
+        state.db.save_universal_provider(&universal)?;
+        Self::materialize_universal(state, &universal)
+    }
+
+    /// 按统一供应商当前的应用启用状态生成或删除各应用的供应商
+    pub(super) fn materialize_universal(
+        state: &AppState,
+        universal: &UniversalProvider,
+    ) -> Result<UniversalApplyResult, AppError> {
+        let mut result = UniversalApplyResult::default();
+        for app_type in UNIVERSAL_APPS {
+            let generated = match app_type {
+                AppType::Claude => universal.to_claude_provider(),
+                AppType::Codex => universal.to_codex_provider(),
+                _ => universal.to_gemini_provider(),
+            };
+            match generated {
+                Some(provider) => {
+                    let applied =
+                        Self::sync_universal_child(state, &app_type, universal, provider)?;
+                    result.applied.push(applied);
+                }
+                None => {
+                    let child_id = universal_child_id(&app_type, &universal.id);
+                    if state
+                        .db
+                        .get_provider_by_id(&child_id, app_type.as_str())?
+                        .is_some()
+                    {
+                        Self::delete_from(
+                            state,
+                            app_type.clone(),
+                            &child_id,
+                            ProviderAuditOrigin::Ui,
+                        )?;
+                        result.removed.push(child_id);
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn sync_universal_child(
+        state: &AppState,
+        app_type: &AppType,
+        universal: &UniversalProvider,
+        generated: Provider,
+    ) -> Result<AppliedUniversalProvider, AppError> {
+        let origin = ProviderOrigin {
+            kind: ProviderOriginKind::Universal,
+            source: Some(universal.id.clone()),
+            synced_at: Some(chrono::Utc::now().timestamp_millis()),
+        };
+        let provider_id = generated.id.clone();
+
+        let existing = state
+            .db
+            .get_provider_by_id(&provider_id, app_type.as_str())?;
+        let created = existing.is_none();
+        match existing {
+            Some(mut provider) => {
+                // 只覆盖统一供应商生成的字段，本地的备注、图标、排序、标签与元数据保持不变
+                Self::merge_json(&mut provider.settings_config, &generated.settings_config);
+                provider.name = generated.name;
+                provider.website_url = generated.website_url;
+                provider.category = generated.category;
+                provider.meta.get_or_insert_with(Default::default).origin = Some(origin);
+                Self::update_from(state, app_type.clone(), provider, ProviderAuditOrigin::Ui)?;
+            }
+            None => {
+                let mut provider = generated;
+                provider.meta.get_or_insert_with(Default::default).origin = Some(origin);
+                Self::add_from(state, app_type.clone(), provider, ProviderAuditOrigin::Ui)?;
+            }
+        }
+
+        Ok(AppliedUniversalProvider {
+            app: app_type.as_str().to_string(),
+            provider_id,
+            created,
+        })
+    }
+}
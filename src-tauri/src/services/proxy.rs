@@ -8,7 +8,7 @@ use crate::database::Database;
 use crate::provider::Provider;
 use crate::proxy::server::ProxyServer;
 use crate::proxy::types::*;
-use crate::services::provider::write_live_partial;
+use crate::services::provider::{write_live_partial, ProviderAuditOrigin, ProviderService};
 use serde_json::{json, Value};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -31,6 +31,9 @@ const CLAUDE_MODEL_OVERRIDE_ENV_KEYS: [&str; 6] = [
     "ANTHROPIC_SMALL_FAST_MODEL",
 ];
 
+/// 热切换时等待旧供应商在途请求结束的上限（与默认的请求超时一致）
+const SWITCH_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+
 #[derive(Clone)]
 pub struct ProxyService {
     db: Arc<Database>,
@@ -1575,9 +1578,21 @@ impl ProxyService {
         let app_type_enum =
             AppType::from_str(app_type).map_err(|_| format!("无效的应用类型: {app_type}"))?;
 
+        let previous_id = self
+            .db
+            .get_current_provider(app_type_enum.as_str())
+            .ok()
+            .flatten();
         self.db
             .set_current_provider(app_type_enum.as_str(), provider_id)
             .map_err(|e| format!("更新当前供应商失败: {e}"))?;
+        ProviderService::record_switch_audit(
+            &self.db,
+            app_type_enum.as_str(),
+            previous_id.as_deref(),
+            provider_id,
+            ProviderAuditOrigin::Ui,
+        );
 
         // 同步本地 settings（设备级优先）
         crate::settings::set_current_provider(&app_type_enum, Some(provider_id))
@@ -1615,7 +1630,7 @@ impl ProxyService {
     // ==================== Live 配置读写辅助方法 ====================
 
     /// 更新 TOML 字符串中的 base_url
-    fn update_toml_base_url(toml_str: &str, new_url: &str) -> String {
+    pub(crate) fn update_toml_base_url(toml_str: &str, new_url: &str) -> String {
         use toml_edit::DocumentMut;
 
         let mut doc = match toml_str.parse::<DocumentMut>() {
@@ -1877,6 +1892,43 @@ impl ProxyService {
         self.server.read().await.is_some()
     }
 
+    /// 热切换后，等旧供应商上的在途请求（含流式响应）结束再执行 `apply`
+    ///
+    /// 新请求此时已经路由到新供应商；`apply` 用于改写磁盘上的配置，推迟执行可避免
+    /// 客户端在长时间的流式会话中途重新加载配置。旧供应商没有在途请求时立即执行，
+    /// 否则在后台等待，超过 [`SWITCH_DRAIN_TIMEOUT`] 仍未结束也会执行。
+    pub fn after_provider_drained<F>(&self, app_type: &str, provider_id: &str, apply: F)
+    where
+        F: FnOnce(&ProxyService) + Send + 'static,
+    {
+        let in_flight = futures::executor::block_on(self.server.read())
+            .as_ref()
+            .map(|server| server.in_flight());
+        let pending = in_flight
+            .as_ref()
+            .map_or(0, |in_flight| in_flight.count(app_type, provider_id));
+        let Some(in_flight) = in_flight.filter(|_| pending > 0) else {
+            apply(self);
+            return;
+        };
+
+        log::info!(
+            "[{app_type}] 旧供应商 {provider_id} 还有 {pending} 个在途请求，等待结束后再更新配置"
+        );
+        let service = self.clone();
+        let app_type = app_type.to_string();
+        let provider_id = provider_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            if !in_flight
+                .wait_drained(&app_type, &provider_id, SWITCH_DRAIN_TIMEOUT)
+                .await
+            {
+                log::warn!("[{app_type}] 等待旧供应商 {provider_id} 的在途请求超时，继续更新配置");
+            }
+            apply(&service);
+        });
+    }
+
     /// 热更新熔断器配置
     ///
     /// 如果代理服务器正在运行，将新配置应用到所有已创建的熔断器实例
@@ -0,0 +1,186 @@
+//! 远程数据的本地缓存
+//!
+//! 技能仓库索引、工具最新版本等都需要联网获取。结果按 key 序列化到 `remote_cache` 表，
+//! 在 TTL 内直接复用；过期后重新请求，请求失败（例如离线）时退回旧数据并标记为 stale，
+//! 保证离线时仍能浏览上一次拿到的内容。
+
+use std::fmt::Display;
+use std::future::Future;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::database::Database;
+use crate::error::AppError;
+
+/// 缓存表中的一条记录（payload 为 JSON）
+#[derive(Debug, Clone)]
+pub struct RemoteCacheEntry {
+    pub payload: String,
+    /// 获取时间（Unix 秒）
+    pub fetched_at: i64,
+    /// 过期时间（Unix 秒）
+    pub expires_at: i64,
+}
+
+/// 带来源信息的数据
+#[derive(Debug, Clone)]
+pub struct Cached<T> {
+    pub data: T,
+    /// 刷新失败、返回的是已过期的缓存
+    pub stale: bool,
+}
+
+/// 远程数据缓存服务
+pub struct RemoteCacheService;
+
+impl RemoteCacheService {
+    /// 优先返回未过期的缓存，否则调用 `fetch` 刷新并写回缓存
+    ///
+    /// `fetch` 失败时若存在旧缓存（无论是否过期）则返回旧数据并标记 `stale`，
+    /// 没有任何缓存时返回 `fetch` 的错误。缓存读写失败只记录日志，不影响结果。
+    pub async fn get_or_fetch<T, E, F, Fut>(
+        db: &Database,
+        key: &str,
+        ttl_secs: i64,
+        fetch: F,
+    ) -> Result<Cached<T>, E>
+    where
+        T: Serialize + DeserializeOwned,
+        E: Display,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let now = chrono::Utc::now().timestamp();
+        let cached = match db.get_remote_cache(key) {
+            Ok(entry) => entry.and_then(|entry| decode::<T>(key, entry)),
+            Err(e) => {
+                log::warn!("读取远程缓存 {key} 失败: {e}");
+                None
+            }
+        };
+
+        let cached = match cached {
+            Some((data, entry)) if entry.expires_at > now => {
+                return Ok(Cached { data, stale: false });
+            }
+            other => other,
+        };
+
+        match fetch().await {
+            Ok(data) => {
+                Self::store(db, key, &data, now, ttl_secs);
+                Ok(Cached { data, stale: false })
+            }
+            Err(e) => match cached {
+                Some((data, _)) => {
+                    log::warn!("刷新 {key} 失败，使用过期缓存: {e}");
+                    Ok(Cached { data, stale: true })
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// 忽略 TTL 强制刷新：成功时写回缓存，失败时保留原有缓存并返回错误
+    pub async fn refresh<T, E, F, Fut>(
+        db: &Database,
+        key: &str,
+        ttl_secs: i64,
+        fetch: F,
+    ) -> Result<T, E>
+    where
+        T: Serialize,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let data = fetch().await?;
+        Self::store(db, key, &data, chrono::Utc::now().timestamp(), ttl_secs);
+        Ok(data)
+    }
+
+    /// 清空全部远程缓存，返回删除的条目数
+    pub fn clear(db: &Database) -> Result<usize, AppError> {
+        let removed = db.clear_remote_cache()?;
+        log::info!("已清空远程缓存（{removed} 条）");
+        Ok(removed)
+    }
+
+    fn store<T: Serialize>(db: &Database, key: &str, data: &T, now: i64, ttl_secs: i64) {
+        let payload = match serde_json::to_string(data) {
+            Ok(payload) => payload,
+            Err(e) => {
+                log::warn!("序列化远程缓存 {key} 失败: {e}");
+                return;
+            }
+        };
+        let entry = RemoteCacheEntry {
+            payload,
+            fetched_at: now,
+            expires_at: now + ttl_secs,
+        };
+        if let Err(e) = db.put_remote_cache(key, &entry) {
+            log::warn!("写入远程缓存 {key} 失败: {e}");
+        }
+    }
+}
+
+/// 反序列化缓存内容；格式不兼容（例如结构体升级）时视为没有缓存
+fn decode<T: DeserializeOwned>(
+    key: &str,
+    entry: RemoteCacheEntry,
+) -> Option<(T, RemoteCacheEntry)> {
+    match serde_json::from_str(&entry.payload) {
+        Ok(data) => Some((data, entry)),
+        Err(e) => {
+            log::debug!("忽略无法解析的远程缓存 {key}: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn serves_fresh_cache_then_stale_on_failure() {
+        let db = Database::memory().expect("create memory db");
+
+        let first = RemoteCacheService::get_or_fetch(&db, "k", 3600, || async {
+            Ok::<_, String>(vec![1, 2])
+        })
+        .await
+        .expect("initial fetch");
+        assert!(!first.stale);
+
+        // TTL 内不再调用 fetch
+        let cached = RemoteCacheService::get_or_fetch(&db, "k", 3600, || async {
+            Err::<Vec<i32>, _>("should not be called".to_string())
+        })
+        .await
+        .expect("fresh cache");
+        assert_eq!(cached.data, vec![1, 2]);
+        assert!(!cached.stale);
+
+        // 过期后刷新失败，退回旧数据
+        let mut entry = db.get_remote_cache("k").unwrap().unwrap();
+        entry.expires_at = 0;
+        db.put_remote_cache("k", &entry).unwrap();
+        let stale = RemoteCacheService::get_or_fetch(&db, "k", 3600, || async {
+            Err::<Vec<i32>, _>("offline".to_string())
+        })
+        .await
+        .expect("stale cache");
+        assert_eq!(stale.data, vec![1, 2]);
+        assert!(stale.stale);
+
+        // 清空后没有可退回的数据
+        assert_eq!(RemoteCacheService::clear(&db).unwrap(), 1);
+        let err = RemoteCacheService::get_or_fetch(&db, "k", 3600, || async {
+            Err::<Vec<i32>, _>("offline".to_string())
+        })
+        .await;
+        assert!(err.is_err());
+    }
+}
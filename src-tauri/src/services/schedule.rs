@@ -0,0 +1,257 @@
+//! 定时规则与下次执行时间
+//!
+//! 规则按“星期几 + 当天时刻”描述，并显式携带 IANA 时区（如 `Asia/Shanghai`）。
+//! 执行时间总是先在规则所在时区中确定当地时刻，再换算为 UTC，因此机器时区变化或
+//! 夏令时切换都不会让规则漂移。夏令时切换当天的处理：
+//!
+//! - 时钟拨快（当地时刻不存在）：按切换前的偏移换算，即顺延一个跳过的时长（02:30 → 03:30）
+//! - 时钟拨慢（当地时刻出现两次）：只在第一次出现时执行一次，不会重复执行
+
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// 单次最多计算的执行次数
+pub const MAX_NEXT_RUNS: usize = 100;
+
+/// 每周定时规则
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    /// IANA 时区名称
+    pub time_zone: String,
+    /// 当地时刻，`HH:MM`
+    pub at: String,
+    /// 执行的星期（`Mon`..`Sun`），为空表示每天
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+}
+
+/// 一次计划中的执行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledRun {
+    /// Unix 毫秒
+    pub at: i64,
+    /// 规则时区中的当地时间（RFC 3339，含当时的偏移）
+    pub local: String,
+}
+
+impl Schedule {
+    /// 解析时区，未知名称时报错
+    pub fn zone(&self) -> Result<Tz, AppError> {
+        Tz::from_str(self.time_zone.trim()).map_err(|_| {
+            AppError::localized(
+                "schedule.invalid_time_zone",
+                format!("未知的时区: {}", self.time_zone),
+                format!("Unknown time zone: {}", self.time_zone),
+            )
+        })
+    }
+
+    /// 解析当地时刻，格式不是 `HH:MM` 时报错
+    pub fn time_of_day(&self) -> Result<NaiveTime, AppError> {
+        NaiveTime::parse_from_str(self.at.trim(), "%H:%M").map_err(|_| {
+            AppError::localized(
+                "schedule.invalid_time",
+                format!("时刻格式应为 HH:MM: {}", self.at),
+                format!("Time must be formatted as HH:MM: {}", self.at),
+            )
+        })
+    }
+
+    /// 校验时区与时刻
+    pub fn validate(&self) -> Result<(), AppError> {
+        self.zone()?;
+        self.time_of_day()?;
+        Ok(())
+    }
+
+    /// `after` 之后（不含）的下一次执行时间
+    pub fn next_run_after(&self, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, AppError> {
+        Ok(self.next_runs_after(after, 1)?.into_iter().next())
+    }
+
+    /// `after` 之后（不含）的前 `count` 次执行时间（最多 [`MAX_NEXT_RUNS`] 次）
+    pub fn next_runs_after(
+        &self,
+        after: DateTime<Utc>,
+        count: usize,
+    ) -> Result<Vec<DateTime<Utc>>, AppError> {
+        let zone = self.zone()?;
+        let time = self.time_of_day()?;
+        let count = count.min(MAX_NEXT_RUNS);
+
+        // 从前一天开始，覆盖 `after` 在当地仍是前一天的情况
+        let mut date = after.with_timezone(&zone).date_naive() - Duration::days(1);
+        let mut runs = Vec::with_capacity(count);
+        while runs.len() < count {
+            if self.runs_on(date) {
+                if let Some(run) = resolve_local(&zone, date.and_time(time)) {
+                    if run > after {
+                        runs.push(run);
+                    }
+                }
+            }
+            date += Duration::days(1);
+        }
+        Ok(runs)
+    }
+
+    /// `(after, until]` 区间内最后一次执行时间，用于补上错过的执行（如应用关闭期间）
+    ///
+    /// 规则至少每周执行一次，区间较长时只需要查看 `until` 之前的八天。
+    pub fn latest_run_between(
+        &self,
+        after: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        let mut cursor = after.max(until - Duration::days(8));
+        let mut latest = None;
+        while let Some(run) = self.next_run_after(cursor)? {
+            if run > until {
+                break;
+            }
+            latest = Some(run);
+            cursor = run;
+        }
+        Ok(latest)
+    }
+
+    /// 计划中的执行时间，附带规则时区中的当地时间，供界面预览
+    pub fn next_runs(
+        &self,
+        after: DateTime<Utc>,
+        count: usize,
+    ) -> Result<Vec<ScheduledRun>, AppError> {
+        let zone = self.zone()?;
+        Ok(self
+            .next_runs_after(after, count)?
+            .into_iter()
+            .map(|run| ScheduledRun {
+                at: run.timestamp_millis(),
+                local: run.with_timezone(&zone).to_rfc3339(),
+            })
+            .collect())
+    }
+
+    fn runs_on(&self, date: NaiveDate) -> bool {
+        self.days.is_empty() || self.days.contains(&date.weekday())
+    }
+}
+
+/// 把时区中的当地时间换算为 UTC（夏令时规则见模块文档）
+fn resolve_local<Z: TimeZone>(zone: &Z, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match zone.from_local_datetime(&local) {
+        LocalResult::Single(t) => Some(t.with_timezone(&Utc)),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest.with_timezone(&Utc)),
+        LocalResult::None => {
+            // 跳过的时段不超过几个小时，往前取一个确定存在的时刻拿到切换前的偏移
+            let before = zone
+                .from_local_datetime(&(local - Duration::hours(6)))
+                .earliest()?;
+            let offset = i64::from(before.offset().fix().local_minus_utc());
+            Some(Utc.from_utc_datetime(&(local - Duration::seconds(offset))))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(time_zone: &str, at: &str, days: &[Weekday]) -> Schedule {
+        Schedule {
+            time_zone: time_zone.to_string(),
+            at: at.to_string(),
+            days: days.to_vec(),
+        }
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn keeps_local_time_across_dst_changes() {
+        let rule = schedule("America/New_York", "09:00", &[]);
+        let runs = rule
+            .next_runs_after(utc("2026-03-07T00:00:00Z"), 3)
+            .unwrap();
+        assert_eq!(
+            runs,
+            [
+                utc("2026-03-07T14:00:00Z"),
+                utc("2026-03-08T13:00:00Z"),
+                utc("2026-03-09T13:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn skipped_time_is_shifted_and_repeated_time_runs_once() {
+        // 2026-03-08 02:00 拨快到 03:00，02:30 不存在
+        let rule = schedule("America/New_York", "02:30", &[]);
+        let runs = rule
+            .next_runs_after(utc("2026-03-07T12:00:00Z"), 2)
+            .unwrap();
+        assert_eq!(
+            runs,
+            [utc("2026-03-08T07:30:00Z"), utc("2026-03-09T06:30:00Z")]
+        );
+
+        // 2026-11-01 02:00 拨慢到 01:00，01:30 出现两次，只执行第一次
+        let rule = schedule("America/New_York", "01:30", &[]);
+        let runs = rule
+            .next_runs_after(utc("2026-10-31T12:00:00Z"), 2)
+            .unwrap();
+        assert_eq!(
+            runs,
+            [utc("2026-11-01T05:30:00Z"), utc("2026-11-02T06:30:00Z")]
+        );
+        assert_eq!(
+            rule.next_run_after(utc("2026-11-01T05:30:00Z")).unwrap(),
+            Some(utc("2026-11-02T06:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn filters_weekdays_in_the_rule_time_zone() {
+        // 上海周一 07:00 在 UTC 仍是周日
+        let rule = schedule("Asia/Shanghai", "07:00", &[Weekday::Mon]);
+        let next = rule.next_run_after(utc("2026-10-18T12:00:00Z")).unwrap();
+        assert_eq!(next, Some(utc("2026-10-18T23:00:00Z")));
+
+        let runs = rule.next_runs(utc("2026-10-18T12:00:00Z"), 1).unwrap();
+        assert_eq!(runs[0].local, "2026-10-19T07:00:00+08:00");
+    }
+
+    #[test]
+    fn latest_run_between_picks_the_last_missed_run() {
+        let rule = schedule("UTC", "09:00", &[Weekday::Mon, Weekday::Fri]);
+        // 2026-10-16 是周五，一直关闭到下周二
+        let latest = rule
+            .latest_run_between(utc("2026-10-15T00:00:00Z"), utc("2026-10-20T08:00:00Z"))
+            .unwrap();
+        assert_eq!(latest, Some(utc("2026-10-19T09:00:00Z")));
+
+        let none = rule
+            .latest_run_between(utc("2026-10-19T09:00:00Z"), utc("2026-10-20T08:00:00Z"))
+            .unwrap();
+        assert_eq!(none, None);
+    }
+
+    #[test]
+    fn rejects_unknown_zone_and_bad_time() {
+        assert!(schedule("Mars/Olympus", "09:00", &[]).validate().is_err());
+        assert!(schedule("UTC", "9am", &[]).validate().is_err());
+        assert!(schedule("UTC", "23:59", &[]).validate().is_ok());
+    }
+}
@@ -0,0 +1,380 @@
+//! 分享链接
+//!
+//! 较大的提示词或 Agent 放进 base64 深链接后往往超出聊天工具能完整传递的长度。这里把单个条目
+//! 加密后上传到用户配置的中转服务，只把一条短的 `ccswitch://v1/share` 链接交给对方：
+//!
+//! ```text
+//! ccswitch://v1/share?relay={中转服务地址}&id={条目 id}&key={base64url 密钥}
+//! ```
+//!
+//! 密钥只出现在链接中，中转服务只能看到密文。中转服务需要实现两个接口：
+//!
+//! - `POST {relay}`，请求体 `{"data": "...", "ttlSeconds": 3600}`，返回 `{"id": "..."}`
+//! - `GET {relay}/{id}`，返回 `{"data": "..."}`，过期或不存在时返回 404 / 410
+//!
+//! 过期时间同时写在密文中，中转服务没有按时删除时接收方同样会拒绝导入。
+//! 导入沿用提示词 / Agent 导出包的流程：重复 id 时重命名，导入的条目不对任何应用启用。
+
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::{AgentBundle, AgentImportResult, DuplicateStrategy};
+use crate::crypto::{self, random_bytes, KEY_LEN};
+use crate::error::AppError;
+use crate::prompt::{PromptBundle, PromptImportResult};
+use crate::proxy::http_client;
+use crate::services::{AgentsService, PromptBundleService};
+use crate::store::AppState;
+
+/// 分享链接最短有效期（秒）
+pub const MIN_SHARE_TTL_SECS: u64 = 60;
+/// 分享链接最长有效期（秒）
+pub const MAX_SHARE_TTL_SECS: u64 = 30 * 24 * 3600;
+/// 与中转服务通信的超时
+const RELAY_TIMEOUT_SECS: u64 = 30;
+/// 绑定到密文上的附加数据，防止把其他用途的密文当作分享内容解密
+const SHARE_AAD: &[u8] = b"cc-switch-share-v1";
+
+/// 可分享的条目类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareKind {
+    Prompt,
+    Agent,
+}
+
+/// 创建的分享链接
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLink {
+    pub url: String,
+    /// 过期时间（Unix 秒）
+    pub expires_at: i64,
+}
+
+/// 深链接中指向中转服务上某个分享的引用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareRef {
+    pub relay: String,
+    pub id: String,
+    /// base64url 编码的密钥
+    pub key: String,
+}
+
+/// 导入分享链接的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "result", rename_all = "lowercase")]
+pub enum ShareImportResult {
+    Prompt(PromptImportResult),
+    Agent(AgentImportResult),
+}
+
+/// 加密前的分享内容
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SharePayload {
+    expires_at: i64,
+    #[serde(flatten)]
+    bundle: SharedBundle,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "bundle", rename_all = "lowercase")]
+enum SharedBundle {
+    Prompt(PromptBundle),
+    Agent(AgentBundle),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RelayUpload<'a> {
+    data: &'a str,
+    ttl_seconds: u64,
+}
+
+#[derive(Deserialize)]
+struct RelayCreated {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct RelayDownload {
+    data: String,
+}
+
+pub struct ShareLinkService;
+
+impl ShareLinkService {
+    /// 加密上传单个提示词或 Agent，返回有效期为 `ttl_secs` 秒的分享链接
+    pub async fn create_share_link(
+        state: &AppState,
+        kind: ShareKind,
+        id: &str,
+        ttl_secs: u64,
+    ) -> Result<ShareLink, AppError> {
+        let relay = crate::settings::get_share_relay_url().ok_or_else(|| {
+            AppError::localized(
+                "share.relay_not_configured",
+                "尚未配置分享中转服务地址",
+                "No share relay URL is configured",
+            )
+        })?;
+        let relay = validate_relay_url(&relay)?;
+        if !(MIN_SHARE_TTL_SECS..=MAX_SHARE_TTL_SECS).contains(&ttl_secs) {
+            return Err(AppError::localized(
+                "share.invalid_ttl",
+                format!(
+                    "有效期应在 {MIN_SHARE_TTL_SECS} 到 {MAX_SHARE_TTL_SECS} 秒之间: {ttl_secs}"
+                ),
+                format!(
+                    "TTL must be between {MIN_SHARE_TTL_SECS} and {MAX_SHARE_TTL_SECS} seconds: {ttl_secs}"
+                ),
+            ));
+        }
+
+        let ids = [id.to_string()];
+        let bundle = match kind {
+            ShareKind::Prompt => SharedBundle::Prompt(PromptBundleService::export_bundle(
+                state,
+                Some(ids.as_slice()),
+            )?),
+            ShareKind::Agent => {
+                SharedBundle::Agent(AgentsService::export_bundle(state, Some(ids.as_slice()))?)
+            }
+        };
+        let found = match &bundle {
+            SharedBundle::Prompt(b) => !b.prompts.is_empty(),
+            SharedBundle::Agent(b) => !b.agents.is_empty(),
+        };
+        if !found {
+            return Err(AppError::localized(
+                "share.item_not_found",
+                format!("要分享的条目不存在: {id}"),
+                format!("Item to share not found: {id}"),
+            ));
+        }
+
+        let expires_at = chrono::Utc::now().timestamp() + ttl_secs as i64;
+        let plaintext = serde_json::to_vec(&SharePayload { expires_at, bundle })
+            .map_err(|e| AppError::JsonSerialize { source: e })?;
+        let key = random_bytes::<KEY_LEN>()?;
+        let data = URL_SAFE_NO_PAD.encode(crypto::seal(&key, SHARE_AAD, &plaintext)?);
+
+        let remote_id = upload(&relay, &data, ttl_secs).await?;
+        Ok(ShareLink {
+            url: share_url(&ShareRef {
+                relay,
+                id: remote_id,
+                key: URL_SAFE_NO_PAD.encode(key),
+            }),
+            expires_at,
+        })
+    }
+
+    /// 下载并解密分享内容，按导出包流程导入（重复 id 时重命名）
+    pub async fn import_share(
+        state: &AppState,
+        share: &ShareRef,
+    ) -> Result<ShareImportResult, AppError> {
+        let relay = validate_relay_url(&share.relay)?;
+        let key = URL_SAFE_NO_PAD
+            .decode(share.key.trim())
+            .map_err(|_| invalid_share())?;
+        let data = download(&relay, &share.id).await?;
+        let payload = open_payload(&key, &data, chrono::Utc::now().timestamp())?;
+
+        match payload.bundle {
+            SharedBundle::Prompt(bundle) => {
+                PromptBundleService::import_bundle(state, bundle, DuplicateStrategy::Rename)
+                    .map(ShareImportResult::Prompt)
+            }
+            SharedBundle::Agent(bundle) => {
+                AgentsService::import_bundle(state, bundle, DuplicateStrategy::Rename)
+                    .map(ShareImportResult::Agent)
+            }
+        }
+    }
+}
+
+/// 生成分享链接
+fn share_url(share: &ShareRef) -> String {
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("relay", &share.relay)
+        .append_pair("id", &share.id)
+        .append_pair("key", &share.key)
+        .finish();
+    format!("ccswitch://v1/share?{query}")
+}
+
+/// 中转服务只接受 https 地址（本机调试地址除外），返回去掉末尾 `/` 的地址
+pub fn validate_relay_url(raw: &str) -> Result<String, AppError> {
+    let parsed = url::Url::parse(raw.trim()).map_err(|e| {
+        AppError::localized(
+            "share.invalid_relay",
+            format!("无效的中转服务地址: {e}"),
+            format!("Invalid relay URL: {e}"),
+        )
+    })?;
+    let local = matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    if parsed.scheme() != "https" && !(parsed.scheme() == "http" && local) {
+        return Err(AppError::localized(
+            "share.https_required",
+            "中转服务地址必须使用 https",
+            "Relay URL must use https",
+        ));
+    }
+    Ok(parsed.as_str().trim_end_matches('/').to_string())
+}
+
+/// 解密并校验有效期；密钥错误、内容被篡改或已过期时报错
+fn open_payload(key: &[u8], data: &str, now: i64) -> Result<SharePayload, AppError> {
+    let sealed = URL_SAFE_NO_PAD
+        .decode(data.trim())
+        .map_err(|_| invalid_share())?;
+    let plaintext = crypto::open(key, SHARE_AAD, &sealed).ok_or_else(invalid_share)?;
+    let payload: SharePayload = serde_json::from_slice(&plaintext).map_err(|_| invalid_share())?;
+    if payload.expires_at <= now {
+        return Err(expired_share());
+    }
+    Ok(payload)
+}
+
+async fn upload(relay: &str, data: &str, ttl_seconds: u64) -> Result<String, AppError> {
+    let response = http_client::get()
+        .post(relay)
+        .timeout(Duration::from_secs(RELAY_TIMEOUT_SECS))
+        .json(&RelayUpload { data, ttl_seconds })
+        .send()
+        .await
+        .map_err(relay_failed)?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(relay_failed(format!("HTTP {}", status.as_u16())));
+    }
+    let created: RelayCreated = response.json().await.map_err(relay_failed)?;
+    if created.id.trim().is_empty() {
+        return Err(relay_failed("empty id"));
+    }
+    Ok(created.id)
+}
+
+async fn download(relay: &str, id: &str) -> Result<String, AppError> {
+    let mut url = url::Url::parse(relay).map_err(relay_failed)?;
+    url.path_segments_mut()
+        .map_err(|_| relay_failed("relay URL cannot be a base"))?
+        .push(id);
+    let response = http_client::get()
+        .get(url)
+        .timeout(Duration::from_secs(RELAY_TIMEOUT_SECS))
+        .send()
+        .await
+        .map_err(relay_failed)?;
+    let status = response.status();
+    if status.as_u16() == 404 || status.as_u16() == 410 {
+        return Err(expired_share());
+    }
+    if !status.is_success() {
+        return Err(relay_failed(format!("HTTP {}", status.as_u16())));
+    }
+    let body: RelayDownload = response.json().await.map_err(relay_failed)?;
+    Ok(body.data)
+}
+
+fn relay_failed(e: impl std::fmt::Display) -> AppError {
+    AppError::localized(
+        "share.relay_failed",
+        format!("访问分享中转服务失败: {e}"),
+        format!("Share relay request failed: {e}"),
+    )
+}
+
+fn invalid_share() -> AppError {
+    AppError::localized(
+        "share.invalid",
+        "分享内容无法解密，链接可能不完整或已损坏",
+        "The shared content cannot be decrypted; the link may be incomplete or corrupted",
+    )
+}
+
+fn expired_share() -> AppError {
+    AppError::localized(
+        "share.expired",
+        "分享链接已过期",
+        "The share link has expired",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompt::{Prompt, PROMPT_BUNDLE_FORMAT, PROMPT_BUNDLE_VERSION};
+
+    fn payload(expires_at: i64) -> Vec<u8> {
+        let bundle = PromptBundle {
+            format: PROMPT_BUNDLE_FORMAT.to_string(),
+            version: PROMPT_BUNDLE_VERSION,
+            exported_at: 0,
+            prompts: vec![Prompt {
+                id: "base".to_string(),
+                name: "Base".to_string(),
+                content: "Base rules\n".to_string(),
+                description: None,
+                tags: Vec::new(),
+                apps: Default::default(),
+                sort_index: None,
+                created_at: None,
+                updated_at: None,
+            }],
+        };
+        serde_json::to_vec(&SharePayload {
+            expires_at,
+            bundle: SharedBundle::Prompt(bundle),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn sealed_payload_opens_only_with_its_key_before_expiry() {
+        let key = random_bytes::<KEY_LEN>().unwrap();
+        let data = URL_SAFE_NO_PAD.encode(crypto::seal(&key, SHARE_AAD, &payload(1_000)).unwrap());
+
+        let opened = open_payload(&key, &data, 999).unwrap();
+        match opened.bundle {
+            SharedBundle::Prompt(bundle) => assert_eq!(bundle.prompts[0].id, "base"),
+            SharedBundle::Agent(_) => panic!("expected a prompt bundle"),
+        }
+
+        let other = random_bytes::<KEY_LEN>().unwrap();
+        assert!(open_payload(&other, &data, 999).is_err());
+        assert!(open_payload(&key, &data, 1_000).is_err());
+        assert!(open_payload(&key, "AAAA", 999).is_err());
+    }
+
+    #[test]
+    fn share_url_escapes_the_relay_address() {
+        let url = share_url(&ShareRef {
+            relay: "https://relay.example.com/p?x=1".to_string(),
+            id: "abc".to_string(),
+            key: "k-y_".to_string(),
+        });
+        assert_eq!(
+            url,
+            "ccswitch://v1/share?relay=https%3A%2F%2Frelay.example.com%2Fp%3Fx%3D1&id=abc&key=k-y_"
+        );
+    }
+
+    #[test]
+    fn relay_must_use_https_except_on_localhost() {
+        assert_eq!(
+            validate_relay_url("https://relay.example.com/api/").unwrap(),
+            "https://relay.example.com/api"
+        );
+        assert!(validate_relay_url("http://127.0.0.1:8080").is_ok());
+        assert!(validate_relay_url("http://relay.example.com").is_err());
+        assert!(validate_relay_url("not a url").is_err());
+    }
+}
@@ -18,6 +18,15 @@ use crate::app_config::{AppType, InstalledSkill, SkillApps, UnmanagedSkill};
 use crate::config::get_app_config_dir;
 use crate::database::Database;
 use crate::error::format_skill_error;
+use crate::services::remote_cache::RemoteCacheService;
+
+/// 技能仓库索引缓存有效期（6 小时）
+const SKILL_INDEX_CACHE_TTL_SECS: i64 = 6 * 60 * 60;
+
+/// 仓库索引在远程缓存中的 key
+fn repo_cache_key(repo: &SkillRepo) -> String {
+    format!("skills:repo:{}/{}@{}", repo.owner, repo.name, repo.branch)
+}
 
 // ========== 数据结构 ==========
 
@@ -57,6 +66,9 @@ pub struct DiscoverableSkill {
     /// 分支名称
     #[serde(rename = "repoBranch")]
     pub repo_branch: String,
+    /// 仓库刷新失败，该条目来自过期的本地缓存
+    #[serde(default)]
+    pub stale: bool,
 }
 
 /// 技能对象（兼容旧 API，内部使用 DiscoverableSkill）
@@ -490,6 +502,8 @@ impl SkillService {
         let dest = ssot_dir.join(&install_name);
 
         let mut repo_branch = skill.repo_branch.clone();
+        // 本次下载到的提交（None 表示复用了已存在的目录）
+        let mut source_sha = None;
 
         // 如果已存在则跳过下载
         if !dest.exists() {
@@ -501,7 +515,7 @@ impl SkillService {
             };
 
             // 下载仓库
-            let (temp_dir, used_branch) = timeout(
+            let (temp_dir, used_branch, sha) = timeout(
                 std::time::Duration::from_secs(60),
                 self.download_repo(&repo),
             )
@@ -518,6 +532,7 @@ impl SkillService {
                 ))
             })??;
             repo_branch = used_branch;
+            source_sha = Some(sha);
 
             // 复制到 SSOT
             let source = temp_dir.join(&source_rel);
@@ -603,6 +618,13 @@ impl SkillService {
         // 保存到数据库
         db.save_skill(&installed_skill)?;
 
+        // 记录文件校验值，用于启动时发现被截断或丢失的文件
+        if let Some(sha) = source_sha {
+            if let Err(e) = Self::record_integrity(db, &installed_skill.id, &dest, sha) {
+                log::warn!("记录 Skill {} 的文件校验值失败: {e}", installed_skill.name);
+            }
+        }
+
         // 同步到当前应用目录
         Self::sync_to_app_dir(&install_name, current_app)?;
 
@@ -641,6 +663,7 @@ impl SkillService {
 
         // 从数据库删除
         db.delete_skill(id)?;
+        db.delete_skill_integrity(id)?;
 
         log::info!("Skill {} 卸载成功", skill.name);
 
@@ -964,9 +987,13 @@ impl SkillService {
     // ========== 发现功能（保留原有逻辑）==========
 
     /// 列出所有可发现的技能（从仓库获取）
+    ///
+    /// 每个仓库的扫描结果缓存 [`SKILL_INDEX_CACHE_TTL_SECS`] 秒；
+    /// 刷新失败时使用过期缓存，对应条目标记为 `stale`。
     pub async fn discover_available(
         &self,
         repos: Vec<SkillRepo>,
+        db: &Arc<Database>,
     ) -> Result<Vec<DiscoverableSkill>> {
         let mut skills = Vec::new();
 
@@ -975,12 +1002,12 @@ impl SkillService {
 
         let fetch_tasks = enabled_repos
             .iter()
-            .map(|repo| self.fetch_repo_skills(repo));
+            .map(|repo| self.fetch_repo_skills_cached(repo, db));
 
         let results: Vec<Result<Vec<DiscoverableSkill>>> =
             futures::future::join_all(fetch_tasks).await;
 
-        for (repo, result) in enabled_repos.into_iter().zip(results.into_iter()) {
+        for (repo, result) in enabled_repos.into_iter().zip(results) {
             match result {
                 Ok(repo_skills) => skills.extend(repo_skills),
                 Err(e) => log::warn!("获取仓库 {}/{} 技能失败: {}", repo.owner, repo.name, e),
@@ -989,7 +1016,7 @@ impl SkillService {
 
         // 去重并排序
         Self::deduplicate_discoverable_skills(&mut skills);
-        skills.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        skills.sort_by_key(|skill| skill.name.to_lowercase());
 
         Ok(skills)
     }
@@ -1001,7 +1028,7 @@ impl SkillService {
         db: &Arc<Database>,
     ) -> Result<Vec<Skill>> {
         // 获取可发现的技能
-        let discoverable = self.discover_available(repos).await?;
+        let discoverable = self.discover_available(repos, db).await?;
 
         // 获取已安装的技能
         let installed = db.get_all_installed_skills()?;
@@ -1056,14 +1083,47 @@ impl SkillService {
             }
         }
 
-        skills.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        skills.sort_by_key(|skill| skill.name.to_lowercase());
+
+        Ok(skills)
+    }
+
+    /// 从缓存或仓库获取技能列表
+    async fn fetch_repo_skills_cached(
+        &self,
+        repo: &SkillRepo,
+        db: &Database,
+    ) -> Result<Vec<DiscoverableSkill>> {
+        let key = repo_cache_key(repo);
+        let cached = RemoteCacheService::get_or_fetch(db, &key, SKILL_INDEX_CACHE_TTL_SECS, || {
+            self.fetch_repo_skills(repo)
+        })
+        .await?;
 
+        let mut skills = cached.data;
+        if cached.stale {
+            for skill in &mut skills {
+                skill.stale = true;
+            }
+        }
         Ok(skills)
     }
 
+    /// 忽略缓存重新下载仓库索引，返回仓库中的技能数量（失败时保留旧缓存）
+    pub async fn refresh_repo_index(&self, repo: &SkillRepo, db: &Database) -> Result<usize> {
+        let skills = RemoteCacheService::refresh(
+            db,
+            &repo_cache_key(repo),
+            SKILL_INDEX_CACHE_TTL_SECS,
+            || self.fetch_repo_skills(repo),
+        )
+        .await?;
+        Ok(skills.len())
+    }
+
     /// 从仓库获取技能列表
     async fn fetch_repo_skills(&self, repo: &SkillRepo) -> Result<Vec<DiscoverableSkill>> {
-        let (temp_dir, resolved_branch) =
+        let (temp_dir, resolved_branch, _) =
             timeout(std::time::Duration::from_secs(60), self.download_repo(repo))
                 .await
                 .map_err(|_| {
@@ -1161,6 +1221,7 @@ impl SkillService {
             repo_owner: repo.owner.clone(),
             repo_name: repo.name.clone(),
             repo_branch: repo.branch.clone(),
+            stale: false,
         })
     }
 
@@ -1207,7 +1268,7 @@ impl SkillService {
     }
 
     /// 校验并规范化技能源路径（允许多级目录），拒绝路径穿越和绝对路径
-    fn sanitize_skill_source_path(raw: &str) -> Option<PathBuf> {
+    pub(crate) fn sanitize_skill_source_path(raw: &str) -> Option<PathBuf> {
         let trimmed = raw.trim();
         if trimmed.is_empty() {
             return None;
@@ -1280,8 +1341,11 @@ impl SkillService {
         });
     }
 
-    /// 下载仓库
-    async fn download_repo(&self, repo: &SkillRepo) -> Result<(PathBuf, String)> {
+    /// 下载仓库，返回解压目录、实际使用的分支与归档对应的提交
+    pub(crate) async fn download_repo(
+        &self,
+        repo: &SkillRepo,
+    ) -> Result<(PathBuf, String, Option<String>)> {
         let temp_dir = tempfile::tempdir()?;
         let temp_path = temp_dir.path().to_path_buf();
         let _ = temp_dir.keep();
@@ -1305,8 +1369,8 @@ impl SkillService {
             );
 
             match self.download_and_extract(&url, &temp_path).await {
-                Ok(_) => {
-                    return Ok((temp_path, branch.to_string()));
+                Ok(sha) => {
+                    return Ok((temp_path, branch.to_string(), sha));
                 }
                 Err(e) => {
                     last_error = Some(e);
@@ -1318,8 +1382,14 @@ impl SkillService {
         Err(last_error.unwrap_or_else(|| anyhow::anyhow!("所有分支下载失败")))
     }
 
-    /// 下载并解压 ZIP
-    async fn download_and_extract(&self, url: &str, dest: &Path) -> Result<()> {
+    /// 下载并解压 ZIP，返回归档对应的提交 SHA
+    ///
+    /// GitHub 生成的归档会把提交 SHA 写在 ZIP 注释中，无法识别时返回 None。
+    pub(crate) async fn download_and_extract(
+        &self,
+        url: &str,
+        dest: &Path,
+    ) -> Result<Option<String>> {
         let client = crate::proxy::http_client::get();
         let response = client.get(url).send().await?;
         if !response.status().is_success() {
@@ -1339,6 +1409,11 @@ impl SkillService {
         let bytes = response.bytes().await?;
         let cursor = std::io::Cursor::new(bytes);
         let mut archive = zip::ZipArchive::new(cursor)?;
+        let commit_sha = std::str::from_utf8(archive.comment())
+            .ok()
+            .map(str::trim)
+            .filter(|c| c.len() == 40 && c.chars().all(|ch| ch.is_ascii_hexdigit()))
+            .map(str::to_string);
 
         let root_name = if !archive.is_empty() {
             let first_file = archive.by_index(0)?;
@@ -1391,11 +1466,11 @@ impl SkillService {
         // 第二遍：解析 symlink，将目标内容复制到 symlink 位置
         Self::resolve_symlinks_in_dir(dest, &symlinks)?;
 
-        Ok(())
+        Ok(commit_sha)
     }
 
     /// 递归复制目录
-    fn copy_dir_recursive(src: &Path, dest: &Path) -> Result<()> {
+    pub(crate) fn copy_dir_recursive(src: &Path, dest: &Path) -> Result<()> {
         fs::create_dir_all(dest)?;
 
         for entry in fs::read_dir(src)? {
@@ -0,0 +1,329 @@
+//! Skills SSOT 目录的完整性校验与修复
+//!
+//! SSOT 目录可能放在云盘同步目录中，同步不完整时 Skill 文件会被悄悄截断或丢失。
+//! 从仓库安装 Skill 时记录每个文件的 SHA-256、大小以及下载到的提交；启动时逐个比对，
+//! 发现文件缺失或被改动后，可按记录的仓库与提交重新下载并恢复 SSOT 中的 Skill。
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::time::timeout;
+
+use crate::app_config::{AppType, InstalledSkill};
+use crate::database::Database;
+use crate::services::skill::{SkillRepo, SkillService};
+
+/// 单个文件的校验值
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileChecksum {
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Skill 的校验记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillIntegrityRecord {
+    pub skill_id: String,
+    /// 安装时下载到的提交 SHA（无法识别时为空，修复时退回按分支下载）
+    pub source_sha: Option<String>,
+    /// 相对路径（以 `/` 分隔）-> 校验值
+    pub files: BTreeMap<String, FileChecksum>,
+    pub recorded_at: i64,
+}
+
+/// 校验发现的问题
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillIntegrityIssue {
+    pub skill_id: String,
+    pub name: String,
+    pub directory: String,
+    /// 缺失的文件（整个目录缺失时包含全部文件）
+    pub missing: Vec<String>,
+    /// 内容或大小与记录不一致的文件
+    pub modified: Vec<String>,
+    /// 是否记录了来源仓库，可以重新下载
+    pub repairable: bool,
+}
+
+/// 修复失败的 Skill
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillRepairFailure {
+    pub skill_id: String,
+    pub error: String,
+}
+
+/// 修复结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillRepairResult {
+    pub repaired: Vec<String>,
+    pub failed: Vec<SkillRepairFailure>,
+}
+
+/// 计算目录下所有文件的校验值
+pub fn checksum_dir(dir: &Path) -> Result<BTreeMap<String, FileChecksum>> {
+    let mut files = BTreeMap::new();
+    checksum_dir_recursive(dir, dir, &mut files)?;
+    Ok(files)
+}
+
+fn checksum_dir_recursive(
+    base: &Path,
+    current: &Path,
+    files: &mut BTreeMap<String, FileChecksum>,
+) -> Result<()> {
+    for entry in fs::read_dir(current)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            checksum_dir_recursive(base, &path, files)?;
+        } else if let Ok(rel) = path.strip_prefix(base) {
+            let rel = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.insert(rel, checksum_file(&path)?);
+        }
+    }
+    Ok(())
+}
+
+fn checksum_file(path: &Path) -> Result<FileChecksum> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 8192];
+    let mut size = 0u64;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+    }
+    Ok(FileChecksum {
+        sha256: format!("{:x}", hasher.finalize()),
+        size,
+    })
+}
+
+/// 与记录比对，返回 (缺失文件, 被改动文件)；新增的文件不视为问题
+fn compare(recorded: &BTreeMap<String, FileChecksum>, dir: &Path) -> (Vec<String>, Vec<String>) {
+    let mut missing = Vec::new();
+    let mut modified = Vec::new();
+    for (rel, expected) in recorded {
+        let path = dir.join(rel);
+        if !path.is_file() {
+            missing.push(rel.clone());
+            continue;
+        }
+        // 大小不同即可判定被截断，无需再计算哈希
+        let size_matches = fs::metadata(&path).is_ok_and(|m| m.len() == expected.size);
+        if !size_matches || checksum_file(&path).map_or(true, |actual| actual != *expected) {
+            modified.push(rel.clone());
+        }
+    }
+    (missing, modified)
+}
+
+impl SkillService {
+    /// 记录 Skill 在 SSOT 中的文件校验值
+    pub(crate) fn record_integrity(
+        db: &Arc<Database>,
+        skill_id: &str,
+        dir: &Path,
+        source_sha: Option<String>,
+    ) -> Result<()> {
+        let record = SkillIntegrityRecord {
+            skill_id: skill_id.to_string(),
+            source_sha,
+            files: checksum_dir(dir)?,
+            recorded_at: chrono::Utc::now().timestamp(),
+        };
+        db.save_skill_integrity(&record)?;
+        Ok(())
+    }
+
+    /// 校验所有有记录的 Skill，返回文件缺失或被改动的 Skill
+    pub fn verify_integrity(db: &Arc<Database>) -> Result<Vec<SkillIntegrityIssue>> {
+        let ssot_dir = Self::get_ssot_dir()?;
+        let skills = db.get_all_installed_skills()?;
+
+        let mut issues = Vec::new();
+        for record in db.get_skill_integrity_records()? {
+            let Some(skill) = skills.get(&record.skill_id) else {
+                continue;
+            };
+            let (missing, modified) = compare(&record.files, &ssot_dir.join(&skill.directory));
+            if missing.is_empty() && modified.is_empty() {
+                continue;
+            }
+            log::warn!(
+                "Skill {} 的文件不完整：缺失 {:?}，被改动 {:?}",
+                skill.name,
+                missing,
+                modified
+            );
+            issues.push(SkillIntegrityIssue {
+                skill_id: skill.id.clone(),
+                name: skill.name.clone(),
+                directory: skill.directory.clone(),
+                missing,
+                modified,
+                repairable: skill.repo_owner.is_some() && skill.repo_name.is_some(),
+            });
+        }
+        Ok(issues)
+    }
+
+    /// 按记录的来源仓库与提交重新下载 Skill，恢复 SSOT 目录并重新同步到已启用的应用
+    ///
+    /// `ids` 为空时修复校验发现问题的全部 Skill。
+    pub async fn repair(&self, db: &Arc<Database>, ids: &[String]) -> Result<SkillRepairResult> {
+        let targets: Vec<String> = if ids.is_empty() {
+            Self::verify_integrity(db)?
+                .into_iter()
+                .map(|issue| issue.skill_id)
+                .collect()
+        } else {
+            ids.to_vec()
+        };
+
+        let mut result = SkillRepairResult::default();
+        for id in targets {
+            match self.repair_one(db, &id).await {
+                Ok(()) => result.repaired.push(id),
+                Err(e) => {
+                    log::warn!("修复 Skill {id} 失败: {e}");
+                    result.failed.push(SkillRepairFailure {
+                        skill_id: id,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    async fn repair_one(&self, db: &Arc<Database>, id: &str) -> Result<()> {
+        let skill = db
+            .get_installed_skill(id)?
+            .ok_or_else(|| anyhow!("Skill not found: {id}"))?;
+        let (Some(owner), Some(name)) = (skill.repo_owner.clone(), skill.repo_name.clone()) else {
+            return Err(anyhow!("Skill {} 没有来源仓库，无法重新下载", skill.name));
+        };
+        // 仓库内的路径记录在 id（"owner/name:directory"）中
+        let source_rel = id
+            .split_once(':')
+            .and_then(|(_, path)| Self::sanitize_skill_source_path(path))
+            .ok_or_else(|| anyhow!("无法从 Skill id 解析仓库内路径: {id}"))?;
+        let recorded_sha = db.get_skill_integrity(id)?.and_then(|r| r.source_sha);
+
+        let download = async {
+            if let Some(sha) = &recorded_sha {
+                let temp_dir = tempfile::tempdir()?;
+                let url = format!("https://github.com/{owner}/{name}/archive/{sha}.zip");
+                match self.download_and_extract(&url, temp_dir.path()).await {
+                    Ok(_) => return Ok((temp_dir.keep(), Some(sha.clone()))),
+                    Err(e) => log::warn!(
+                        "按提交 {sha} 下载 Skill {} 失败，改为按分支下载: {e}",
+                        skill.name
+                    ),
+                }
+            }
+            let repo = SkillRepo {
+                owner: owner.clone(),
+                name: name.clone(),
+                branch: skill.repo_branch.clone().unwrap_or_default(),
+                enabled: true,
+            };
+            let (path, _, sha) = self.download_repo(&repo).await?;
+            Ok::<_, anyhow::Error>((path, sha))
+        };
+        let (temp_dir, source_sha) = timeout(std::time::Duration::from_secs(60), download)
+            .await
+            .map_err(|_| anyhow!("下载 {owner}/{name} 超时"))??;
+        let restored = Self::restore_from_download(db, &skill, &temp_dir, &source_rel, source_sha);
+        let _ = fs::remove_dir_all(&temp_dir);
+        restored?;
+
+        log::info!("Skill {} 已从 {owner}/{name} 恢复", skill.name);
+        Ok(())
+    }
+
+    /// 用下载到的仓库内容替换 SSOT 中的 Skill，并更新校验记录
+    fn restore_from_download(
+        db: &Arc<Database>,
+        skill: &InstalledSkill,
+        temp_dir: &Path,
+        source_rel: &Path,
+        source_sha: Option<String>,
+    ) -> Result<()> {
+        let source = temp_dir.join(source_rel);
+        if !source.is_dir() {
+            return Err(anyhow!("下载的仓库中不存在 {}", source_rel.display()));
+        }
+
+        // 先复制到临时目录再替换，避免复制中途失败留下半个目录
+        let ssot_dir = Self::get_ssot_dir()?;
+        let dest = ssot_dir.join(&skill.directory);
+        let staging = ssot_dir.join(format!(".{}.repair", skill.directory));
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        Self::copy_dir_recursive(&source, &staging)?;
+        if dest.exists() {
+            fs::remove_dir_all(&dest)?;
+        }
+        fs::rename(&staging, &dest)?;
+
+        Self::record_integrity(db, &skill.id, &dest, source_sha)?;
+        resync_enabled_apps(skill)
+    }
+}
+
+/// 复制模式下应用目录中的副本同样可能不完整，修复后重新同步
+fn resync_enabled_apps(skill: &InstalledSkill) -> Result<()> {
+    for app in AppType::all() {
+        if skill.apps.is_enabled_for(&app) {
+            SkillService::sync_to_app_dir(&skill.directory, &app)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_reports_truncated_and_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("scripts")).unwrap();
+        fs::write(dir.path().join("SKILL.md"), "# Skill\n\nUse carefully.\n").unwrap();
+        fs::write(dir.path().join("scripts/run.sh"), "echo hi\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+
+        let recorded = checksum_dir(dir.path()).unwrap();
+        assert!(recorded.contains_key("scripts/run.sh"));
+        assert_eq!(compare(&recorded, dir.path()), (vec![], vec![]));
+
+        // 模拟云盘同步截断与丢失；新增的文件不算问题
+        fs::write(dir.path().join("SKILL.md"), "# Ski").unwrap();
+        fs::remove_file(dir.path().join("scripts/run.sh")).unwrap();
+        fs::write(dir.path().join("extra.md"), "extra").unwrap();
+
+        let (missing, modified) = compare(&recorded, dir.path());
+        assert_eq!(missing, vec!["scripts/run.sh"]);
+        assert_eq!(modified, vec!["SKILL.md"]);
+    }
+}
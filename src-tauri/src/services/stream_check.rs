@@ -8,11 +8,14 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
 
 use crate::app_config::AppType;
+use crate::database::Database;
 use crate::error::AppError;
 use crate::provider::Provider;
 use crate::proxy::providers::{get_adapter, AuthInfo, AuthStrategy};
+use crate::store::AppState;
 
 /// 健康状态枚举
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -39,12 +42,52 @@ pub struct StreamCheckConfig {
     /// 检查提示词
     #[serde(default = "default_test_prompt")]
     pub test_prompt: String,
+    /// 延迟回归告警倍数：当前供应商延迟超过基线的该倍数时告警（<= 1 表示关闭）
+    #[serde(default = "default_regression_factor")]
+    pub regression_factor: f64,
+    /// 计算延迟基线使用的最近成功检查次数
+    #[serde(default = "default_baseline_window")]
+    pub baseline_window: u32,
+    /// 切换（含故障转移）后立即发送一次预热请求，提前完成 TLS 握手与中转冷启动
+    #[serde(default)]
+    pub warm_up_on_switch: bool,
+    /// 切换前预检：标记为当前供应商之前先发送一次最小请求验证密钥与端点
+    #[serde(default)]
+    pub validate_on_switch: SwitchValidation,
+}
+
+/// 切换前预检模式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SwitchValidation {
+    /// 不预检
+    #[default]
+    Off,
+    /// 预检失败时仍然切换，并在切换结果中附带预检结果
+    Warn,
+    /// 预检失败时拒绝切换（可强制切换）
+    Block,
 }
 
 fn default_test_prompt() -> String {
     "Who are you?".to_string()
 }
 
+fn default_regression_factor() -> f64 {
+    2.0
+}
+
+fn default_baseline_window() -> u32 {
+    20
+}
+
+/// 计算基线所需的最少历史样本数
+const MIN_BASELINE_SAMPLES: usize = 3;
+/// 推荐候选供应商时参考的最近检查时间范围（秒）
+const CANDIDATE_WINDOW_SECS: i64 = 24 * 60 * 60;
+/// 推荐候选供应商的最大数量
+const MAX_CANDIDATES: usize = 3;
+
 impl Default for StreamCheckConfig {
     fn default() -> Self {
         Self {
@@ -55,6 +98,10 @@ impl Default for StreamCheckConfig {
             codex_model: "gpt-5.1-codex@low".to_string(),
             gemini_model: "gemini-3-pro-preview".to_string(),
             test_prompt: default_test_prompt(),
+            regression_factor: default_regression_factor(),
+            baseline_window: default_baseline_window(),
+            warm_up_on_switch: false,
+            validate_on_switch: SwitchValidation::Off,
         }
     }
 }
@@ -73,10 +120,112 @@ pub struct StreamCheckResult {
     pub retry_count: u32,
 }
 
+/// 延迟更低的候选供应商（来自最近的检查结果）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyCandidate {
+    pub provider_id: String,
+    pub provider_name: String,
+    pub latency_ms: u64,
+}
+
+/// 当前供应商的延迟回归告警
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyRegression {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub latency_ms: u64,
+    /// 最近成功检查延迟的中位数
+    pub baseline_ms: u64,
+    /// 当前延迟 / 基线
+    pub ratio: f64,
+    pub candidates: Vec<LatencyCandidate>,
+}
+
+/// 切换后的预热结果（`provider-warmed-up` 事件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderWarmUp {
+    pub app_type: String,
+    pub provider_id: String,
+    /// 触发来源：`switch` 或 `failover`
+    pub source: String,
+    pub result: StreamCheckResult,
+}
+
 /// 流式健康检查服务
 pub struct StreamCheckService;
 
 impl StreamCheckService {
+    /// 检测当前供应商的延迟是否相对滚动基线明显变慢
+    ///
+    /// `log_id` 为本次检查写入的日志 id，基线只取它之前的成功记录。
+    /// 历史样本不足或告警关闭时返回 None。
+    pub fn detect_latency_regression(
+        db: &Database,
+        app_type: &AppType,
+        provider: &Provider,
+        result: &StreamCheckResult,
+        log_id: i64,
+        config: &StreamCheckConfig,
+    ) -> Result<Option<LatencyRegression>, AppError> {
+        if !result.success || config.regression_factor <= 1.0 {
+            return Ok(None);
+        }
+        let Some(latency_ms) = result.response_time_ms else {
+            return Ok(None);
+        };
+
+        let history = db.get_recent_stream_check_latencies(
+            app_type.as_str(),
+            &provider.id,
+            log_id,
+            config.baseline_window.max(1),
+        )?;
+        let Some(baseline_ms) =
+            Self::regressed_baseline(latency_ms, &history, config.regression_factor)
+        else {
+            return Ok(None);
+        };
+
+        let since = chrono::Utc::now().timestamp() - CANDIDATE_WINDOW_SECS;
+        let mut candidates: Vec<LatencyCandidate> = db
+            .get_latest_stream_check_latencies(app_type.as_str(), since)?
+            .into_iter()
+            .filter(|c| c.provider_id != provider.id && c.latency_ms < latency_ms)
+            .collect();
+        candidates.sort_by_key(|c| c.latency_ms);
+        candidates.truncate(MAX_CANDIDATES);
+
+        log::warn!(
+            "[{}] 供应商 {} 延迟回归: {latency_ms}ms（基线 {baseline_ms}ms）",
+            app_type.as_str(),
+            provider.name
+        );
+        Ok(Some(LatencyRegression {
+            app_type: app_type.as_str().to_string(),
+            provider_id: provider.id.clone(),
+            provider_name: provider.name.clone(),
+            latency_ms,
+            baseline_ms,
+            ratio: latency_ms as f64 / baseline_ms.max(1) as f64,
+            candidates,
+        }))
+    }
+
+    /// 若 `latency_ms` 超过历史中位数的 `factor` 倍，返回该中位数
+    fn regressed_baseline(latency_ms: u64, history: &[u64], factor: f64) -> Option<u64> {
+        if history.len() < MIN_BASELINE_SAMPLES {
+            return None;
+        }
+        let mut sorted = history.to_vec();
+        sorted.sort_unstable();
+        let baseline = sorted[sorted.len() / 2];
+        (latency_ms as f64 > baseline as f64 * factor).then_some(baseline)
+    }
+
     /// 执行流式健康检查（带重试）
     ///
     /// 如果 Provider 配置了单独的测试配置（meta.testConfig），则使用该配置覆盖全局配置
@@ -132,6 +281,97 @@ impl StreamCheckService {
         }))
     }
 
+    /// 切换后的预热请求
+    ///
+    /// 只发送一次（不重试），结果写入检查日志，作为新供应商的第一条健康数据。
+    /// 未开启 `warm_up_on_switch` 或供应商不存在时返回 `None`。
+    pub async fn warm_up(
+        db: &Database,
+        app_type: &AppType,
+        provider_id: &str,
+    ) -> Result<Option<StreamCheckResult>, AppError> {
+        let config = db.get_stream_check_config()?;
+        if !config.warm_up_on_switch {
+            return Ok(None);
+        }
+        let Some(provider) = db.get_provider_by_id(provider_id, app_type.as_str())? else {
+            return Ok(None);
+        };
+
+        let config = Self::merge_provider_config(&provider, &config);
+        let result = Self::check_once(app_type, &provider, &config)
+            .await
+            .unwrap_or_else(|e| Self::failed_result(e.to_string()));
+        db.save_stream_check_log(provider_id, &provider.name, app_type.as_str(), &result)?;
+        Ok(Some(result))
+    }
+
+    /// 切换前预检：对目标供应商发送一次最小请求（不重试），结果写入检查日志
+    ///
+    /// 官方登录的供应商与不支持健康检查的应用（OpenCode、OpenClaw）不预检，返回 `None`。
+    pub async fn preflight(
+        db: &Database,
+        app_type: &AppType,
+        provider: &Provider,
+        config: &StreamCheckConfig,
+    ) -> Result<Option<StreamCheckResult>, AppError> {
+        if matches!(app_type, AppType::OpenCode | AppType::OpenClaw)
+            || provider.category.as_deref() == Some("official")
+        {
+            return Ok(None);
+        }
+
+        let config = Self::merge_provider_config(provider, config);
+        let result = Self::check_once(app_type, provider, &config)
+            .await
+            .unwrap_or_else(|e| Self::failed_result(e.to_string()));
+        db.save_stream_check_log(&provider.id, &provider.name, app_type.as_str(), &result)?;
+        Ok(Some(result))
+    }
+
+    /// 请求未能发出（如缺少密钥、Base URL 无效）时的检查结果
+    fn failed_result(message: String) -> StreamCheckResult {
+        StreamCheckResult {
+            status: HealthStatus::Failed,
+            success: false,
+            http_status: Self::http_status_from_error(&message),
+            message,
+            response_time_ms: None,
+            model_used: String::new(),
+            tested_at: chrono::Utc::now().timestamp(),
+            retry_count: 0,
+        }
+    }
+
+    /// 在后台执行预热，完成后发射 `provider-warmed-up` 事件；不阻塞切换本身
+    pub fn spawn_warm_up(app: &AppHandle, app_type: AppType, provider_id: String, source: &str) {
+        let app = app.clone();
+        let source = source.to_string();
+        tauri::async_runtime::spawn(async move {
+            let db = app.state::<AppState>().db.clone();
+            match Self::warm_up(&db, &app_type, &provider_id).await {
+                Ok(Some(result)) => {
+                    log::info!(
+                        "预热 {} 供应商 {provider_id}: {}",
+                        app_type.as_str(),
+                        result.message
+                    );
+                    let event = ProviderWarmUp {
+                        app_type: app_type.as_str().to_string(),
+                        provider_id,
+                        source,
+                        result,
+                    };
+                    if let Err(e) = app.emit("provider-warmed-up", &event) {
+                        log::warn!("发射预热事件失败: {e}");
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("预热供应商 {provider_id} 失败: {e}"),
+            }
+        });
+    }
+
     /// 合并供应商单独配置和全局配置
     ///
     /// 如果供应商配置了 meta.testConfig 且 enabled 为 true，则使用供应商配置覆盖全局配置
@@ -168,6 +408,10 @@ impl StreamCheckService {
                     .test_prompt
                     .clone()
                     .unwrap_or_else(|| global_config.test_prompt.clone()),
+                regression_factor: global_config.regression_factor,
+                baseline_window: global_config.baseline_window,
+                warm_up_on_switch: global_config.warm_up_on_switch,
+                validate_on_switch: global_config.validate_on_switch,
             },
             None => global_config.clone(),
         }
@@ -273,7 +517,7 @@ impl StreamCheckService {
                 success: false,
                 message: e.to_string(),
                 response_time_ms: Some(response_time),
-                http_status: None,
+                http_status: Self::http_status_from_error(&e.to_string()),
                 model_used: String::new(),
                 tested_at,
                 retry_count: 0,
@@ -521,6 +765,13 @@ impl StreamCheckService {
         }
     }
 
+    /// 从 `HTTP 401 Unauthorized: ...` 形式的错误信息中提取状态码
+    fn http_status_from_error(message: &str) -> Option<u16> {
+        let rest = message.strip_prefix("HTTP ")?;
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok()
+    }
+
     fn determine_status(latency_ms: u64, threshold: u64) -> HealthStatus {
         if latency_ms <= threshold {
             HealthStatus::Operational
@@ -562,24 +813,26 @@ impl StreamCheckService {
         provider: &Provider,
         config: &StreamCheckConfig,
     ) -> String {
+        Self::provider_model(app_type, provider).unwrap_or_else(|| match app_type {
+            AppType::Claude => config.claude_model.clone(),
+            AppType::Codex => config.codex_model.clone(),
+            AppType::Gemini => config.gemini_model.clone(),
+            AppType::OpenCode | AppType::OpenClaw => "gpt-4o".to_string(),
+        })
+    }
+
+    /// 读取供应商配置中指定的模型（未配置时返回 None）
+    pub(crate) fn provider_model(app_type: &AppType, provider: &Provider) -> Option<String> {
         match app_type {
-            AppType::Claude => Self::extract_env_model(provider, "ANTHROPIC_MODEL")
-                .unwrap_or_else(|| config.claude_model.clone()),
-            AppType::Codex => {
-                Self::extract_codex_model(provider).unwrap_or_else(|| config.codex_model.clone())
-            }
-            AppType::Gemini => Self::extract_env_model(provider, "GEMINI_MODEL")
-                .unwrap_or_else(|| config.gemini_model.clone()),
-            AppType::OpenCode => {
-                // OpenCode uses models map in settings_config
-                // Try to extract first model from the models object
-                Self::extract_opencode_model(provider).unwrap_or_else(|| "gpt-4o".to_string())
-            }
-            AppType::OpenClaw => {
-                // OpenClaw uses models array in settings_config
-                // Try to extract first model from the models array
-                Self::extract_openclaw_model(provider).unwrap_or_else(|| "gpt-4o".to_string())
-            }
+            AppType::Claude => Self::extract_env_model(provider, "ANTHROPIC_MODEL"),
+            AppType::Codex => Self::extract_codex_model(provider),
+            AppType::Gemini => Self::extract_env_model(provider, "GEMINI_MODEL"),
+            // OpenCode uses models map in settings_config
+            // Try to extract first model from the models object
+            AppType::OpenCode => Self::extract_opencode_model(provider),
+            // OpenClaw uses models array in settings_config
+            // Try to extract first model from the models array
+            AppType::OpenClaw => Self::extract_openclaw_model(provider),
         }
     }
 
@@ -675,6 +928,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_http_status_from_error() {
+        assert_eq!(
+            StreamCheckService::http_status_from_error("HTTP 401 Unauthorized: bad key"),
+            Some(401)
+        );
+        assert_eq!(
+            StreamCheckService::http_status_from_error("Stream read failed: eof"),
+            None
+        );
+    }
+
     #[test]
     fn test_should_retry() {
         assert!(StreamCheckService::should_retry("Request timeout"));
@@ -683,12 +948,82 @@ mod tests {
         assert!(!StreamCheckService::should_retry("API Key invalid"));
     }
 
+    #[test]
+    fn test_regressed_baseline() {
+        let history = [900, 1000, 1100, 1000];
+        assert_eq!(
+            StreamCheckService::regressed_baseline(2500, &history, 2.0),
+            Some(1000)
+        );
+        assert_eq!(
+            StreamCheckService::regressed_baseline(1800, &history, 2.0),
+            None
+        );
+        // 样本不足时不告警
+        assert_eq!(
+            StreamCheckService::regressed_baseline(9000, &[1000, 1000], 2.0),
+            None
+        );
+    }
+
     #[test]
     fn test_default_config() {
         let config = StreamCheckConfig::default();
         assert_eq!(config.timeout_secs, 45);
         assert_eq!(config.max_retries, 2);
         assert_eq!(config.degraded_threshold_ms, 6000);
+        assert!(!config.warm_up_on_switch);
+        assert_eq!(config.validate_on_switch, SwitchValidation::Off);
+    }
+
+    #[tokio::test]
+    async fn preflight_skips_official_and_reports_missing_key() {
+        let db = Database::memory().expect("create memory db");
+        let config = StreamCheckConfig::default();
+
+        let mut official = Provider::with_id(
+            "official".to_string(),
+            "Claude Official".to_string(),
+            json!({"env": {}}),
+            None,
+        );
+        official.category = Some("official".to_string());
+        let skipped = StreamCheckService::preflight(&db, &AppType::Claude, &official, &config)
+            .await
+            .expect("preflight");
+        assert!(skipped.is_none());
+
+        let relay = Provider::with_id(
+            "relay".to_string(),
+            "Relay".to_string(),
+            json!({"env": {"ANTHROPIC_BASE_URL": "https://relay.example.com"}}),
+            None,
+        );
+        let result = StreamCheckService::preflight(&db, &AppType::Claude, &relay, &config)
+            .await
+            .expect("preflight")
+            .expect("checked");
+        assert!(!result.success);
+        assert_eq!(result.status, HealthStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn warm_up_requires_opt_in_and_an_existing_provider() {
+        let db = Database::memory().expect("create memory db");
+        let skipped = StreamCheckService::warm_up(&db, &AppType::Claude, "missing")
+            .await
+            .expect("warm up");
+        assert!(skipped.is_none());
+
+        db.save_stream_check_config(&StreamCheckConfig {
+            warm_up_on_switch: true,
+            ..StreamCheckConfig::default()
+        })
+        .expect("save config");
+        let missing = StreamCheckService::warm_up(&db, &AppType::Claude, "missing")
+            .await
+            .expect("warm up");
+        assert!(missing.is_none());
     }
 
     #[test]
@@ -0,0 +1,67 @@
+//! 数据库与工具文件的一致性保护
+//!
+//! MCP、Agent 与提示词的保存和开关都是先写数据库、再同步工具文件。
+//! 文件同步失败时数据库已经是新状态，直接返回错误会让两者长期不一致。
+//! [`sync_or_rollback`] 以补偿方式处理：同步失败后恢复数据库中的旧状态，
+//! 并尽力把工具文件也恢复到旧状态，最后返回原始错误。
+
+use crate::error::AppError;
+
+/// 执行文件同步，失败时调用 `rollback` 补偿数据库变更并返回同步错误
+///
+/// 补偿本身失败只记录日志（此时数据库与文件可能仍不一致），返回的始终是同步错误。
+pub(crate) fn sync_or_rollback<T>(
+    action: &str,
+    sync: impl FnOnce() -> Result<T, AppError>,
+    rollback: impl FnOnce() -> Result<(), AppError>,
+) -> Result<T, AppError> {
+    match sync() {
+        Ok(value) => Ok(value),
+        Err(e) => {
+            log::warn!("{action}：同步工具文件失败，回滚数据库变更: {e}");
+            if let Err(rollback_err) = rollback() {
+                log::error!("{action}：回滚失败，数据库与工具文件可能不一致: {rollback_err}");
+            }
+            Err(e)
+        }
+    }
+}
+
+/// 补偿过程中恢复文件的单个步骤：失败只记录日志，不中断其余步骤
+pub(crate) fn best_effort(step: &str, result: Result<(), AppError>) {
+    if let Err(e) = result {
+        log::warn!("回滚时{step}失败: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn rolls_back_only_when_sync_fails() {
+        let rolled_back = Cell::new(false);
+        let ok = sync_or_rollback(
+            "test",
+            || Ok(1),
+            || {
+                rolled_back.set(true);
+                Ok(())
+            },
+        );
+        assert_eq!(ok.unwrap(), 1);
+        assert!(!rolled_back.get());
+
+        let err = sync_or_rollback::<()>(
+            "test",
+            || Err(AppError::Message("disk full".to_string())),
+            || {
+                rolled_back.set(true);
+                Err(AppError::Message("rollback failed".to_string()))
+            },
+        );
+        assert!(rolled_back.get());
+        assert!(err.unwrap_err().to_string().contains("disk full"));
+    }
+}
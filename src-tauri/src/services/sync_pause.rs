@@ -0,0 +1,69 @@
+//! 按应用暂停 / 恢复同步
+//!
+//! 暂停时移除 cc-switch 写入该应用的 agent、提示词与 MCP 配置，但保留数据库中的
+//! 启用状态；暂停期间的开关操作只更新数据库。恢复时按数据库当前状态重新写入，
+//! 从而还原暂停前（以及暂停期间修改后）的完整状态。
+
+use crate::agents::{self, AgentChange, TemplateVars};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::{AgentsService, McpService, PromptService};
+use crate::settings;
+use crate::store::AppState;
+
+/// 同步暂停业务逻辑
+pub struct SyncPauseService;
+
+impl SyncPauseService {
+    /// 设置指定应用的暂停状态（状态未变化时直接返回）
+    pub fn set_paused(state: &AppState, app: &AppType, paused: bool) -> Result<(), AppError> {
+        if settings::is_app_sync_paused(app) == paused {
+            return Ok(());
+        }
+        if paused {
+            Self::pause(state, app)
+        } else {
+            Self::resume(state, app)
+        }
+    }
+
+    /// 先清理文件再记录暂停标记（标记生效后写入路径会跳过该应用）
+    fn pause(state: &AppState, app: &AppType) -> Result<(), AppError> {
+        let agent_list = state.db.get_all_agents()?;
+        let removals: Vec<AgentChange<'_>> = agent_list
+            .values()
+            .filter(|a| a.apps.is_enabled_for(app))
+            .map(|a| AgentChange::Remove(&a.id))
+            .collect();
+        agents::apply_agent_changes(app, &removals, &TemplateVars::default())?;
+
+        McpService::remove_enabled_from_app(state, app)?;
+        // 提示词文件可能与 agent 区块共用（AGENTS.md / GEMINI.md），两者各自只移除自己的区域
+        PromptService::clear_enabled_from_app(state, app)?;
+
+        settings::set_app_sync_paused(app, true)?;
+        log::info!("已暂停 {} 的同步", app.as_str());
+        Ok(())
+    }
+
+    /// 先清除暂停标记再按数据库状态重新写入
+    fn resume(state: &AppState, app: &AppType) -> Result<(), AppError> {
+        settings::set_app_sync_paused(app, false)?;
+
+        // 提示词写入文件开头的托管区域，agent 区块随后按各自的位置写入
+        PromptService::sync_enabled_to_app(state, app)?;
+
+        let agent_list = state.db.get_all_agents()?;
+        let upserts: Vec<AgentChange<'_>> = agent_list
+            .values()
+            .filter(|a| a.apps.is_enabled_for(app))
+            .map(AgentChange::Upsert)
+            .collect();
+        let vars = AgentsService::template_vars(state, app);
+        agents::apply_agent_changes(app, &upserts, &vars)?;
+
+        McpService::sync_enabled_to_app(state, app)?;
+        log::info!("已恢复 {} 的同步", app.as_str());
+        Ok(())
+    }
+}
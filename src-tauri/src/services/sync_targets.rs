@@ -0,0 +1,159 @@
+//! 同步目标预览
+//!
+//! 在用户切换启用状态之前，告诉前端某个提示词 / agent 会写到哪个文件、
+//! 以整文件覆盖还是 cc-switch 管理的区块形式写入。
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agents;
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::prompt_files::{prompt_file_path, PROMPT_REGION_BEGIN, PROMPT_REGION_END};
+use crate::services::prompt::PROMPT_APPS;
+use crate::store::AppState;
+
+/// Agent 会写入的工具
+const AGENT_APPS: [AppType; 5] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+    AppType::OpenClaw,
+];
+
+/// 预览对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncTargetKind {
+    Prompt,
+    Agent,
+}
+
+impl FromStr for SyncTargetKind {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "prompt" => Ok(Self::Prompt),
+            "agent" => Ok(Self::Agent),
+            other => Err(AppError::localized(
+                "sync_target.unsupported_kind",
+                format!("不支持的同步对象类型: {other}"),
+                format!("Unsupported sync target kind: {other}"),
+            )),
+        }
+    }
+}
+
+/// 写入方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncTargetMode {
+    /// 整个文件由该对象独占（覆盖写入）
+    WholeFile,
+    /// 写入共享文件中 cc-switch 管理的区块，不影响区块外的内容
+    ManagedBlock,
+}
+
+/// 单个工具的同步目标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncTarget {
+    pub app: String,
+    /// 绝对路径
+    pub path: String,
+    pub mode: SyncTargetMode,
+    /// 区块起始标记（仅 `ManagedBlock`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker_start: Option<String>,
+    /// 区块结束标记（仅 `ManagedBlock`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker_end: Option<String>,
+    /// 当前是否已对该工具启用
+    pub enabled: bool,
+    /// 目标文件当前是否存在
+    pub exists: bool,
+    /// 该工具的同步已暂停（切换后不会立即写入）
+    pub paused: bool,
+}
+
+/// 同步目标预览服务
+pub struct SyncTargetsService;
+
+impl SyncTargetsService {
+    /// 返回提示词 / agent 在每个支持的工具中对应的写入位置
+    ///
+    /// 未启用的工具同样列出（`enabled = false`），便于在启用前预览。
+    pub fn get_targets(
+        state: &AppState,
+        kind: SyncTargetKind,
+        id: &str,
+    ) -> Result<Vec<SyncTarget>, AppError> {
+        match kind {
+            SyncTargetKind::Prompt => Self::prompt_targets(state, id),
+            SyncTargetKind::Agent => Self::agent_targets(state, id),
+        }
+    }
+
+    fn prompt_targets(state: &AppState, id: &str) -> Result<Vec<SyncTarget>, AppError> {
+        let prompts = state.db.get_prompts()?;
+        let prompt = prompts.get(id).ok_or_else(|| {
+            AppError::localized(
+                "prompt.not_found",
+                format!("提示词不存在: {id}"),
+                format!("Prompt not found: {id}"),
+            )
+        })?;
+
+        PROMPT_APPS
+            .iter()
+            .map(|app| {
+                let path = prompt_file_path(app)?;
+                Ok(SyncTarget {
+                    app: app.as_str().to_string(),
+                    exists: path.exists(),
+                    path: path.to_string_lossy().to_string(),
+                    mode: SyncTargetMode::ManagedBlock,
+                    marker_start: Some(PROMPT_REGION_BEGIN.to_string()),
+                    marker_end: Some(PROMPT_REGION_END.to_string()),
+                    enabled: prompt.apps.is_enabled_for(app),
+                    paused: crate::settings::is_app_sync_paused(app),
+                })
+            })
+            .collect()
+    }
+
+    fn agent_targets(state: &AppState, id: &str) -> Result<Vec<SyncTarget>, AppError> {
+        let agent = state.db.get_agent_by_id(id)?.ok_or_else(|| {
+            AppError::localized(
+                "agent.not_found",
+                format!("Agent 不存在: {id}"),
+                format!("Agent not found: {id}"),
+            )
+        })?;
+
+        Ok(AGENT_APPS
+            .iter()
+            .map(|app| {
+                let target = agents::agent_file_target(&agent.id, app);
+                let (mode, markers) = match target.markers {
+                    Some(markers) => (SyncTargetMode::ManagedBlock, Some(markers)),
+                    None => (SyncTargetMode::WholeFile, None),
+                };
+                let (marker_start, marker_end) = markers.unzip();
+                SyncTarget {
+                    app: app.as_str().to_string(),
+                    exists: target.path.exists(),
+                    path: target.path.to_string_lossy().to_string(),
+                    mode,
+                    marker_start,
+                    marker_end,
+                    enabled: agent.apps.is_enabled_for(app),
+                    paused: crate::settings::is_app_sync_paused(app),
+                }
+            })
+            .collect())
+    }
+}
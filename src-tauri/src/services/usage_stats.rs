@@ -4,7 +4,7 @@
 
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
-use chrono::{Local, TimeZone};
+use chrono::{Local, TimeZone, Timelike};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -648,6 +648,93 @@ impl Database {
             monthly_exceeded,
         })
     }
+
+    /// 按一天中的小时（本地时间）汇总供应商的延迟，用于绘制热力图
+    ///
+    /// 样本来自最近 `days` 天内成功的流式健康检查与成功（2xx）的代理请求；代理请求优先取首 token 延迟。
+    /// 始终返回 24 个小时桶，没有样本的小时延迟字段为空。
+    pub fn get_latency_heatmap(
+        &self,
+        provider_id: &str,
+        app_type: Option<&str>,
+        days: u32,
+    ) -> Result<LatencyHeatmap, AppError> {
+        let since = Local::now().timestamp() - i64::from(days) * 24 * 60 * 60;
+        let conn = lock_conn!(self.conn);
+
+        let sql = "SELECT tested_at, response_time_ms FROM stream_check_logs
+             WHERE provider_id = ?1 AND (?2 IS NULL OR app_type = ?2)
+               AND success = 1 AND response_time_ms IS NOT NULL AND tested_at >= ?3
+             UNION ALL
+             SELECT created_at, COALESCE(first_token_ms, latency_ms) FROM proxy_request_logs
+             WHERE provider_id = ?1 AND (?2 IS NULL OR app_type = ?2)
+               AND status_code >= 200 AND status_code < 300 AND created_at >= ?3";
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![provider_id, app_type, since], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        let mut samples: Vec<Vec<u64>> = vec![Vec::new(); 24];
+        for row in rows {
+            let (ts, latency) = row?;
+            let Some(at) = Local.timestamp_opt(ts, 0).single() else {
+                continue;
+            };
+            samples[at.hour() as usize].push(latency.max(0) as u64);
+        }
+
+        Ok(LatencyHeatmap {
+            provider_id: provider_id.to_string(),
+            app_type: app_type.map(str::to_string),
+            days,
+            buckets: samples
+                .into_iter()
+                .enumerate()
+                .map(|(hour, latencies)| LatencyHourBucket::from_samples(hour as u8, latencies))
+                .collect(),
+        })
+    }
+}
+
+/// 延迟热力图中的一个小时桶
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyHourBucket {
+    /// 本地时间的小时（0-23）
+    pub hour: u8,
+    pub sample_count: u64,
+    pub avg_latency_ms: Option<u64>,
+    pub p50_latency_ms: Option<u64>,
+    pub p90_latency_ms: Option<u64>,
+}
+
+impl LatencyHourBucket {
+    fn from_samples(hour: u8, mut latencies: Vec<u64>) -> Self {
+        latencies.sort_unstable();
+        let percentile =
+            |p: usize| (!latencies.is_empty()).then(|| latencies[(latencies.len() - 1) * p / 100]);
+        Self {
+            hour,
+            sample_count: latencies.len() as u64,
+            avg_latency_ms: (!latencies.is_empty())
+                .then(|| latencies.iter().sum::<u64>() / latencies.len() as u64),
+            p50_latency_ms: percentile(50),
+            p90_latency_ms: percentile(90),
+        }
+    }
+}
+
+/// 供应商按小时的延迟分布
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyHeatmap {
+    pub provider_id: String,
+    /// 为空时包含该供应商在所有应用下的样本
+    pub app_type: Option<String>,
+    /// 统计的天数
+    pub days: u32,
+    /// 固定 24 个，按小时排列
+    pub buckets: Vec<LatencyHourBucket>,
 }
 
 /// Provider 限额状态
@@ -981,4 +1068,60 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_latency_heatmap_buckets_by_local_hour() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let at_hour = |hour: u32| {
+            Local::now()
+                .date_naive()
+                .and_hms_opt(hour, 30, 0)
+                .and_then(|t| Local.from_local_datetime(&t).single())
+                .map(|t| t.timestamp())
+                .expect("local timestamp")
+                - 24 * 60 * 60
+        };
+
+        {
+            let conn = lock_conn!(db.conn);
+            for (id, latency, status, hour) in [
+                ("req1", 100, 200, 9),
+                ("req2", 300, 200, 9),
+                ("req3", 9000, 502, 9),
+                ("req4", 50, 200, 22),
+            ] {
+                conn.execute(
+                    "INSERT INTO proxy_request_logs (
+                        request_id, provider_id, app_type, model,
+                        latency_ms, status_code, created_at
+                    ) VALUES (?, 'p1', 'claude', 'claude-3', ?, ?, ?)",
+                    params![id, latency, status, at_hour(hour)],
+                )?;
+            }
+            conn.execute(
+                "INSERT INTO stream_check_logs (
+                    provider_id, provider_name, app_type, status, success, message,
+                    response_time_ms, tested_at
+                ) VALUES ('p1', 'P1', 'claude', 'operational', 1, 'ok', 200, ?)",
+                params![at_hour(9)],
+            )?;
+        }
+
+        let heatmap = db.get_latency_heatmap("p1", Some("claude"), 7)?;
+        assert_eq!(heatmap.buckets.len(), 24);
+        let nine = &heatmap.buckets[9];
+        assert_eq!(nine.sample_count, 3);
+        assert_eq!(nine.avg_latency_ms, Some(200));
+        assert_eq!(nine.p50_latency_ms, Some(200));
+        assert_eq!(heatmap.buckets[22].sample_count, 1);
+        assert_eq!(heatmap.buckets[0].avg_latency_ms, None);
+
+        assert!(db
+            .get_latency_heatmap("p1", Some("codex"), 7)?
+            .buckets
+            .iter()
+            .all(|b| b.sample_count == 0));
+
+        Ok(())
+    }
 }
@@ -1,11 +1,16 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 use std::sync::{OnceLock, RwLock};
 
+use crate::agents::{GeminiAgentOutput, SectionPlacement};
 use crate::app_config::AppType;
 use crate::error::AppError;
+use crate::external_edits::ExternalEditPolicy;
+use crate::prompt::PromptSizeLimit;
+use crate::prompt_files::OpenCodePromptTarget;
 use crate::services::skill::SyncMethod;
 
 /// 自定义端点配置（历史兼容，实际存储在 provider.meta.custom_endpoints）
@@ -16,6 +21,15 @@ pub struct CustomEndpoint {
     pub added_at: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_used: Option<i64>,
+    /// 最近一次测速时间（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_benchmarked: Option<i64>,
+    /// 连续测速失败次数，成功一次即清零
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    /// 最近一次成功测速的延迟（毫秒），失败后清空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_latency_ms: Option<u64>,
 }
 
 fn default_true() -> bool {
@@ -163,6 +177,23 @@ impl WebDavSyncSettings {
     }
 }
 
+/// 导入提示词文件后的规范化选项
+///
+/// 默认全部关闭，导入内容与文件保持一致；需要时由用户在设置中开启。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptImportNormalization {
+    /// 将 CRLF / CR 换行统一为 LF
+    #[serde(default)]
+    pub normalize_line_endings: bool,
+    /// 去掉 cc-switch 自己写入的提示词区域、agent 区块与 AGENTS.md 导出区块
+    #[serde(default)]
+    pub strip_managed_sections: bool,
+    /// 去掉行尾空白与文件末尾多余的空行
+    #[serde(default)]
+    pub trim_trailing_whitespace: bool,
+}
+
 /// 应用设置结构
 ///
 /// 存储设备级别设置，保存在本地 `~/.cc-switch/settings.json`，不随数据库同步。
@@ -187,6 +218,9 @@ pub struct AppSettings {
     /// 静默启动（程序启动时不显示主窗口，仅托盘运行）
     #[serde(default)]
     pub silent_startup: bool,
+    /// 开机自启时以后台模式运行：只启动托盘与后台服务，首次打开时才创建主窗口
+    #[serde(default)]
+    pub autostart_minimized: bool,
     /// 是否在主页面启用本地代理功能（默认关闭）
     #[serde(default)]
     pub enable_local_proxy: bool,
@@ -260,6 +294,81 @@ pub struct AppSettings {
     /// - Linux: "gnome-terminal" | "konsole" | "xfce4-terminal" | "alacritty" | "kitty" | "ghostty"
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub preferred_terminal: Option<String>,
+
+    // ===== 提示词导入设置 =====
+    /// 导入提示词文件（首次启动、手动、深链接）后的规范化选项
+    #[serde(default)]
+    pub prompt_import_normalization: PromptImportNormalization,
+    /// OpenCode 全局提示词的写入位置：agents（`AGENTS.md` 托管区域，默认）/ instructions（独立文件）
+    ///
+    /// 切换需要迁移已写入的内容，只能通过 `set_opencode_prompt_target` 修改
+    #[serde(default)]
+    pub opencode_prompt_target: OpenCodePromptTarget,
+
+    // ===== 提示词回收站设置 =====
+    /// 删除的提示词在回收站中保留的天数（默认 30，0 表示不自动清理）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_trash_retention_days: Option<u32>,
+
+    // ===== 供应商回收站设置 =====
+    /// 删除的供应商在回收站中保留的天数（默认 30，0 表示不自动清理）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_trash_retention_days: Option<u32>,
+
+    // ===== 提示词大小限制设置 =====
+    /// 各应用提示词文件的大小限制覆盖（键为应用 id，未设置的字段使用默认值）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub prompt_size_limits: BTreeMap<String, PromptSizeLimit>,
+
+    // ===== Agent 同步设置 =====
+    /// Codex AGENTS.md 中 cc-switch 托管区域的位置：top / bottom（默认）
+    #[serde(default)]
+    pub codex_agents_placement: SectionPlacement,
+    /// Gemini agent 的输出方式：markers（GEMINI.md 区块，默认）/ files（`~/.gemini/agents/{id}.md`）
+    ///
+    /// 切换需要迁移已写入的内容，只能通过 `set_gemini_agent_output` 修改
+    #[serde(default)]
+    pub gemini_agent_output: GeminiAgentOutput,
+
+    // ===== 配置恢复设置 =====
+    /// 切换供应商时若 live 配置文件损坏，是否自动隔离并从数据库重建（默认开启）
+    #[serde(default = "default_true")]
+    pub auto_recover_malformed_config: bool,
+    /// 托管的 agent 文件被外部删除时是否自动恢复（默认开启；关闭后仅在界面中提示）
+    #[serde(default = "default_true")]
+    pub auto_restore_agent_files: bool,
+
+    // ===== 托管文件横幅设置 =====
+    /// 是否在 cc-switch 写入的 agent 文件、marker 区块与提示词区域顶部加一行来源说明注释（默认关闭）
+    #[serde(default)]
+    pub managed_banner: bool,
+
+    // ===== id 生成设置 =====
+    /// 各场景的 id 模式覆盖（键见 `IdKind::setting_key`，占位符见 `services::id_generator`）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub id_patterns: BTreeMap<String, String>,
+
+    // ===== 外部修改处理设置 =====
+    /// 各应用检测到托管内容被外部修改时的处理方式（键为应用 id，缺省为 overwrite）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub external_edit_policies: BTreeMap<String, ExternalEditPolicy>,
+
+    // ===== 同步暂停设置 =====
+    /// 已暂停同步的应用（暂停期间不写入该应用的 agent / 提示词 / MCP 配置）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paused_sync_apps: Vec<String>,
+
+    // ===== 分享设置 =====
+    /// 分享链接使用的中转服务地址（未配置时无法创建分享链接，协议见 `services::share_link`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share_relay_url: Option<String>,
+
+    // ===== 密钥存储设置 =====
+    /// 供应商 API Key 存入系统钥匙串，数据库只保留引用（见 `crate::secret_store`）
+    ///
+    /// 切换需要迁移已保存的密钥，只能通过 `set_keychain_storage` 修改
+    #[serde(default)]
+    pub store_secrets_in_keychain: bool,
 }
 
 fn default_show_in_tray() -> bool {
@@ -279,6 +388,7 @@ impl Default for AppSettings {
             skip_claude_onboarding: false,
             launch_on_startup: false,
             silent_startup: false,
+            autostart_minimized: false,
             enable_local_proxy: false,
             proxy_confirmed: None,
             usage_confirmed: None,
@@ -300,6 +410,21 @@ impl Default for AppSettings {
             backup_interval_hours: None,
             backup_retain_count: None,
             preferred_terminal: None,
+            prompt_import_normalization: PromptImportNormalization::default(),
+            opencode_prompt_target: OpenCodePromptTarget::default(),
+            prompt_trash_retention_days: None,
+            provider_trash_retention_days: None,
+            prompt_size_limits: BTreeMap::new(),
+            codex_agents_placement: SectionPlacement::default(),
+            gemini_agent_output: GeminiAgentOutput::default(),
+            auto_recover_malformed_config: true,
+            auto_restore_agent_files: true,
+            managed_banner: false,
+            id_patterns: BTreeMap::new(),
+            external_edit_policies: BTreeMap::new(),
+            paused_sync_apps: Vec::new(),
+            share_relay_url: None,
+            store_secrets_in_keychain: false,
         }
     }
 }
@@ -660,6 +785,85 @@ pub fn effective_backup_retain_count() -> usize {
         .unwrap_or(10)
 }
 
+// ===== 提示词回收站管理函数 =====
+
+/// 删除的提示词在回收站中保留的天数（默认 30，0 表示不自动清理）
+pub fn effective_prompt_trash_retention_days() -> u32 {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .prompt_trash_retention_days
+        .unwrap_or(30)
+}
+
+// ===== 供应商回收站管理函数 =====
+
+/// 删除的供应商在回收站中保留的天数（默认 30，0 表示不自动清理）
+pub fn effective_provider_trash_retention_days() -> u32 {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .provider_trash_retention_days
+        .unwrap_or(30)
+}
+
+// ===== 提示词大小限制管理函数 =====
+
+/// 应用提示词文件的大小限制（用户覆盖优先，其余取默认值）
+pub fn get_prompt_size_limit(app: &AppType) -> PromptSizeLimit {
+    let default = PromptSizeLimit::default_for(app);
+    let store = settings_store().read().unwrap_or_else(|e| {
+        log::warn!("设置锁已毒化，使用恢复值: {e}");
+        e.into_inner()
+    });
+    match store.prompt_size_limits.get(app.as_str()) {
+        Some(custom) => PromptSizeLimit {
+            warn_bytes: custom.warn_bytes.or(default.warn_bytes),
+            max_bytes: custom.max_bytes.or(default.max_bytes),
+        },
+        None => default,
+    }
+}
+
+// ===== 分享设置管理函数 =====
+
+/// 分享中转服务地址（空字符串视为未配置）
+pub fn get_share_relay_url() -> Option<String> {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .share_relay_url
+        .clone()
+        .filter(|url| !url.trim().is_empty())
+}
+
+// ===== 密钥存储设置管理函数 =====
+
+/// 供应商密钥是否存入系统钥匙串
+pub fn store_secrets_in_keychain() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .store_secrets_in_keychain
+}
+
+/// 保存密钥存储位置（不迁移已保存的密钥，由调用方负责）
+pub fn set_store_secrets_in_keychain(enabled: bool) -> Result<(), AppError> {
+    mutate_settings(|current| current.store_secrets_in_keychain = enabled)
+}
+
 // ===== 终端设置管理函数 =====
 
 /// 获取首选终端应用
@@ -674,6 +878,179 @@ pub fn get_preferred_terminal() -> Option<String> {
         .clone()
 }
 
+// ===== 配置恢复设置管理函数 =====
+
+/// 是否在 live 配置损坏时自动隔离并重建
+pub fn auto_recover_malformed_config_enabled() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .auto_recover_malformed_config
+}
+
+/// 导入提示词后的规范化选项
+pub fn get_prompt_import_normalization() -> PromptImportNormalization {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .prompt_import_normalization
+}
+
+/// OpenCode 全局提示词的写入位置
+pub fn get_opencode_prompt_target() -> OpenCodePromptTarget {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .opencode_prompt_target
+}
+
+/// 保存 OpenCode 提示词写入位置（不迁移文件，由调用方负责）
+pub fn set_opencode_prompt_target(target: OpenCodePromptTarget) -> Result<(), AppError> {
+    mutate_settings(|current| current.opencode_prompt_target = target)
+}
+
+/// Codex AGENTS.md 中托管区域的位置
+pub fn get_codex_agents_placement() -> SectionPlacement {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .codex_agents_placement
+}
+
+/// Gemini agent 的输出方式
+pub fn get_gemini_agent_output() -> GeminiAgentOutput {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .gemini_agent_output
+}
+
+/// 保存 Gemini agent 的输出方式（不迁移文件，由调用方负责）
+pub fn set_gemini_agent_output(output: GeminiAgentOutput) -> Result<(), AppError> {
+    mutate_settings(|current| current.gemini_agent_output = output)
+}
+
+/// 托管的 agent 文件被删除时是否自动恢复
+pub fn auto_restore_agent_files_enabled() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .auto_restore_agent_files
+}
+
+/// 是否在托管内容顶部写入来源横幅
+pub fn managed_banner_enabled() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .managed_banner
+}
+
+/// 开启或关闭来源横幅（只修改设置，不改写已有文件）
+pub fn set_managed_banner(enabled: bool) -> Result<(), AppError> {
+    mutate_settings(|current| current.managed_banner = enabled)
+}
+
+/// 获取指定场景的 id 模式覆盖
+pub fn get_id_pattern(key: &str) -> Option<String> {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .id_patterns
+        .get(key)
+        .cloned()
+}
+
+// ===== 外部修改处理函数 =====
+
+/// 指定应用检测到外部修改时的处理方式
+pub fn get_external_edit_policy(app: &AppType) -> ExternalEditPolicy {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .external_edit_policies
+        .get(app.as_str())
+        .copied()
+        .unwrap_or_default()
+}
+
+/// 设置指定应用检测到外部修改时的处理方式（overwrite 即恢复默认）
+pub fn set_external_edit_policy(app: &AppType, policy: ExternalEditPolicy) -> Result<(), AppError> {
+    mutate_settings(|current| {
+        if policy == ExternalEditPolicy::default() {
+            current.external_edit_policies.remove(app.as_str());
+        } else {
+            current
+                .external_edit_policies
+                .insert(app.as_str().to_string(), policy);
+        }
+    })
+}
+
+// ===== 同步暂停管理函数 =====
+
+/// 指定应用是否已暂停同步
+pub fn is_app_sync_paused(app: &AppType) -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .paused_sync_apps
+        .iter()
+        .any(|a| a == app.as_str())
+}
+
+/// 获取已暂停同步的应用列表
+pub fn get_paused_sync_apps() -> Vec<String> {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .paused_sync_apps
+        .clone()
+}
+
+/// 设置指定应用的同步暂停状态
+pub fn set_app_sync_paused(app: &AppType, paused: bool) -> Result<(), AppError> {
+    mutate_settings(|current| {
+        current.paused_sync_apps.retain(|a| a != app.as_str());
+        if paused {
+            current.paused_sync_apps.push(app.as_str().to_string());
+        }
+    })
+}
+
 // ===== WebDAV 同步设置管理函数 =====
 
 /// 获取 WebDAV 同步设置
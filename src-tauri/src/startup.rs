@@ -0,0 +1,115 @@
+//! 启动方式与主窗口的延迟创建
+//!
+//! 主窗口在 `tauri.conf.json` 中配置为 `create: false`，由这里按需创建：
+//! 正常启动时在 setup 中立即创建；后台启动时只初始化托盘与后台服务
+//! （文件监听、定时任务、代理、故障转移），直到用户从托盘或再次启动应用时才创建窗口，
+//! 避免开机时加载 WebView。
+
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::settings::AppSettings;
+
+/// 主窗口标签
+pub const MAIN_WINDOW_LABEL: &str = "main";
+/// 显式要求后台启动的命令行参数
+pub const BACKGROUND_ARG: &str = "--background";
+/// 开机自启注册时附带的参数，用于区分系统登录启动与手动启动
+///
+/// macOS 登录项不支持启动参数，开机自启时不会带上它，届时仍按静默启动设置处理。
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+/// 本次启动是否以后台模式运行（不创建主窗口）
+pub fn is_background_launch(args: &[String], settings: &AppSettings) -> bool {
+    let has_arg = |flag: &str| args.iter().skip(1).any(|arg| arg == flag);
+    has_arg(BACKGROUND_ARG) || (settings.autostart_minimized && has_arg(AUTOSTART_ARG))
+}
+
+/// 获取主窗口，不存在时按配置创建
+pub fn ensure_main_window(app: &AppHandle) -> Option<WebviewWindow> {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        return Some(window);
+    }
+
+    let Some(config) = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == MAIN_WINDOW_LABEL)
+        .cloned()
+    else {
+        log::error!("tauri.conf.json 中缺少主窗口配置");
+        return None;
+    };
+
+    match tauri::WebviewWindowBuilder::from_config(app, &config).and_then(|b| b.build()) {
+        Ok(window) => {
+            #[cfg(target_os = "linux")]
+            disable_webkit_hardware_acceleration(&window);
+            log::info!("已创建主窗口");
+            Some(window)
+        }
+        Err(e) => {
+            log::error!("创建主窗口失败: {e}");
+            None
+        }
+    }
+}
+
+/// 显示并聚焦主窗口（后台启动后首次调用时创建窗口）
+pub fn show_main_window(app: &AppHandle) {
+    let Some(window) = ensure_main_window(app) else {
+        return;
+    };
+    #[cfg(target_os = "windows")]
+    {
+        let _ = window.set_skip_taskbar(false);
+    }
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+    #[cfg(target_os = "macos")]
+    {
+        crate::tray::apply_tray_policy(app, true);
+    }
+}
+
+/// Linux: 禁用 WebKitGTK 硬件加速，防止 EGL 初始化失败导致白屏
+#[cfg(target_os = "linux")]
+fn disable_webkit_hardware_acceleration(window: &WebviewWindow) {
+    let _ = window.with_webview(|webview| {
+        use webkit2gtk::{HardwareAccelerationPolicy, SettingsExt, WebViewExt};
+        let wk_webview = webview.inner();
+        if let Some(settings) = WebViewExt::settings(&wk_webview) {
+            SettingsExt::set_hardware_acceleration_policy(
+                &settings,
+                HardwareAccelerationPolicy::Never,
+            );
+            log::info!("已禁用 WebKitGTK 硬件加速");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("cc-switch")
+            .chain(list.iter().copied())
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn background_launch_requires_flag_or_autostart_with_setting() {
+        let mut settings = AppSettings::default();
+        assert!(!is_background_launch(&args(&[]), &settings));
+        assert!(is_background_launch(&args(&[BACKGROUND_ARG]), &settings));
+        assert!(!is_background_launch(&args(&[AUTOSTART_ARG]), &settings));
+
+        settings.autostart_minimized = true;
+        assert!(is_background_launch(&args(&[AUTOSTART_ARG]), &settings));
+        assert!(!is_background_launch(&args(&[]), &settings));
+    }
+}
@@ -0,0 +1,32 @@
+//! 标签规范化
+//!
+//! 供应商、提示词与 Agent 的标签都按同一规则保存：去掉首尾空白与空标签，
+//! 不区分大小写去重，保留先出现的写法。
+
+use std::collections::HashSet;
+
+/// 规范化一组标签
+pub(crate) fn normalize_tags<I>(tags: I) -> Vec<String>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut seen = HashSet::new();
+    tags.into_iter()
+        .map(|tag| tag.as_ref().trim().to_string())
+        .filter(|tag| !tag.is_empty() && seen.insert(tag.to_lowercase()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_first_spelling_and_drops_blanks() {
+        assert_eq!(
+            normalize_tags([" Rust ", "", "rust", "CLI", "  ", "cli"]),
+            vec!["Rust", "CLI"]
+        );
+    }
+}
@@ -296,13 +296,15 @@ fn handle_provider_click(
             .db
             .set_proxy_flags_sync(app_type_str, proxy_enabled, false)?;
 
-        // 切换供应商
-        crate::commands::switch_provider(
+        // 切换供应商（在阻塞线程中执行，可以等待切换前预检）
+        tauri::async_runtime::block_on(crate::commands::switch_provider(
+            app.clone(),
             app_state.clone(),
             app_type_str.to_string(),
             provider_id.to_string(),
-        )
-        .map_err(AppError::Message)?;
+            None,
+        ))
+        .map_err(|e| AppError::Message(e.message))?;
 
         // 更新托盘菜单
         if let Ok(new_menu) = create_tray_menu(app, app_state.inner()) {
@@ -357,7 +359,8 @@ pub fn create_tray_menu(
         }
 
         let app_type_str = section.app_type.as_str();
-        let providers = app_state.db.get_all_providers(app_type_str)?;
+        let mut providers = app_state.db.get_all_providers(app_type_str)?;
+        providers.retain(|_, provider| !provider.archived);
 
         // 使用有效的当前供应商 ID（验证存在性，自动清理失效 ID）
         let current_id =
@@ -417,21 +420,7 @@ pub fn handle_tray_menu_event(app: &tauri::AppHandle, event_id: &str) {
     log::info!("处理托盘菜单事件: {event_id}");
 
     match event_id {
-        "show_main" => {
-            if let Some(window) = app.get_webview_window("main") {
-                #[cfg(target_os = "windows")]
-                {
-                    let _ = window.set_skip_taskbar(false);
-                }
-                let _ = window.unminimize();
-                let _ = window.show();
-                let _ = window.set_focus();
-                #[cfg(target_os = "macos")]
-                {
-                    apply_tray_policy(app, true);
-                }
-            }
-        }
+        "show_main" => crate::startup::show_main_window(app),
         "quit" => {
             log::info!("退出应用");
             app.exit(0);
@@ -0,0 +1,493 @@
+use std::fs;
+
+use cc_switch_lib::{
+    update_settings, AgentApps, AgentDefinition, AgentDriftStatus, AgentsService, AppError,
+    AppSettings, AppType, DiffLineKind, DuplicateStrategy, ExternalEditPolicy, SyncTargetKind,
+    SyncTargetMode, SyncTargetsService,
+};
+
+#[path = "support.rs"]
+mod support;
+use support::{create_test_state, ensure_test_home, reset_test_fs, test_mutex};
+
+fn reviewer(content: &str) -> AgentDefinition {
+    AgentDefinition {
+        id: "reviewer".to_string(),
+        name: "Reviewer".to_string(),
+        content: content.to_string(),
+        apps: AgentApps {
+            claude: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn enabling_agent_refuses_to_clobber_unmanaged_file() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let path = home.join(".claude").join("agents").join("reviewer.md");
+    fs::create_dir_all(path.parent().unwrap()).expect("create agents dir");
+    fs::write(&path, "hand-written reviewer\n").expect("seed unmanaged file");
+
+    let state = create_test_state().expect("create test state");
+
+    let err = AgentsService::upsert(&state, reviewer("Review the diff."), false)
+        .expect_err("unmanaged file should block the write");
+    assert!(err.to_string().contains("reviewer.md"), "{err}");
+    assert!(state.db.get_agent_by_id("reviewer").unwrap().is_none());
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "hand-written reviewer\n"
+    );
+
+    // 保存时先不启用，再通过切换启用：同样拒绝，数据库保持禁用
+    let mut disabled = reviewer("Review the diff.");
+    disabled.apps = AgentApps::default();
+    AgentsService::upsert(&state, disabled, false).expect("save without apps");
+    AgentsService::toggle_app(&state, "reviewer", AppType::Claude, true, false)
+        .expect_err("toggle should be refused as well");
+    let stored = state.db.get_agent_by_id("reviewer").unwrap().unwrap();
+    assert!(!stored.apps.claude);
+
+    // 显式覆盖后文件由 cc-switch 接管，之后的更新不再需要确认
+    AgentsService::toggle_app(&state, "reviewer", AppType::Claude, true, true)
+        .expect("overwrite when confirmed");
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("Review the diff."));
+
+    AgentsService::upsert(&state, reviewer("Review carefully."), false)
+        .expect("managed file can be updated");
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("Review carefully."));
+}
+
+#[test]
+fn unmanaged_agent_file_can_be_adopted_into_the_database() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let path = home.join(".claude").join("agents").join("reviewer.md");
+    fs::create_dir_all(path.parent().unwrap()).expect("create agents dir");
+    fs::write(
+        &path,
+        "---\nname: Reviewer\ndescription: Reviews diffs\n---\n\nhand-written reviewer\n",
+    )
+    .expect("seed unmanaged file");
+
+    let state = create_test_state().expect("create test state");
+    let mut disabled = reviewer("Review the diff.");
+    disabled.apps = AgentApps::default();
+    disabled.apps.codex = true;
+    AgentsService::upsert(&state, disabled, false).expect("save without claude");
+
+    // 接管：保留文件内容，并对 Claude 启用；其他已启用的工具同步为文件内容
+    let adopted =
+        AgentsService::adopt_unmanaged(&state, "reviewer", AppType::Claude).expect("adopt file");
+    assert_eq!(adopted.content.trim_end(), "hand-written reviewer");
+    assert_eq!(adopted.description.as_deref(), Some("Reviews diffs"));
+    let stored = state.db.get_agent_by_id("reviewer").unwrap().unwrap();
+    assert!(stored.apps.claude && stored.apps.codex);
+    assert_eq!(stored.content.trim_end(), "hand-written reviewer");
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("hand-written reviewer"));
+    assert!(fs::read_to_string(home.join(".codex").join("AGENTS.md"))
+        .unwrap()
+        .contains("hand-written reviewer"));
+    assert!(AgentsService::check_drift(&state).unwrap().is_empty());
+
+    // 之后的更新不再需要确认
+    AgentsService::upsert(&state, reviewer("Review carefully."), false)
+        .expect("adopted file is managed");
+}
+
+#[test]
+fn scan_existing_skips_conflicting_and_invalid_agent_files() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let claude_dir = home.join(".claude").join("agents");
+    let opencode_dir = home.join(".config").join("opencode").join("agents");
+    fs::create_dir_all(&claude_dir).expect("create claude agents dir");
+    fs::create_dir_all(&opencode_dir).expect("create opencode agents dir");
+
+    // 两个工具中正文相同：合并为一条并启用两个工具
+    fs::write(claude_dir.join("shared.md"), "Shared body\n").unwrap();
+    fs::write(opencode_dir.join("shared.md"), "Shared body\n").unwrap();
+    // 正文不同：不导入，避免之后的同步覆盖其中一个文件
+    fs::write(claude_dir.join("reviewer.md"), "Claude reviewer\n").unwrap();
+    fs::write(opencode_dir.join("reviewer.md"), "OpenCode reviewer\n").unwrap();
+    // 名称无法原样写回 frontmatter：未通过校验
+    fs::write(
+        claude_dir.join("broken.md"),
+        "---\nname: 'Broken: reviewer'\n---\nBody\n",
+    )
+    .unwrap();
+    // 只有一个工具中的副本未通过校验：整个 id 都不导入
+    fs::write(claude_dir.join("half.md"), "Half body\n").unwrap();
+    fs::write(
+        opencode_dir.join("half.md"),
+        "---\nname: 'Half: reviewer'\n---\nHalf body\n",
+    )
+    .unwrap();
+
+    let state = create_test_state().expect("create test state");
+    let result = AgentsService::scan_existing(&state).expect("scan agents");
+
+    let ids: Vec<_> = result.imported.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec!["shared"]);
+    let shared = state.db.get_agent_by_id("shared").unwrap().unwrap();
+    assert!(shared.apps.claude && shared.apps.opencode);
+
+    assert_eq!(result.conflicts, vec!["reviewer".to_string()]);
+    assert!(state.db.get_agent_by_id("reviewer").unwrap().is_none());
+    assert_eq!(
+        fs::read_to_string(opencode_dir.join("reviewer.md")).unwrap(),
+        "OpenCode reviewer\n"
+    );
+
+    let invalid: Vec<_> = result
+        .invalid
+        .iter()
+        .map(|i| (i.agent_id.as_str(), i.app.as_str()))
+        .collect();
+    assert_eq!(invalid, vec![("broken", "claude"), ("half", "opencode")]);
+    assert!(state.db.get_agent_by_id("broken").unwrap().is_none());
+    assert!(state.db.get_agent_by_id("half").unwrap().is_none());
+}
+
+#[test]
+fn external_edits_are_reported_as_drift_and_can_be_pulled_back() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let state = create_test_state().expect("create test state");
+
+    let mut agent = reviewer("Review the diff.");
+    agent.apps.codex = true;
+    AgentsService::upsert(&state, agent, false).expect("save agent");
+    assert!(AgentsService::check_drift(&state).unwrap().is_empty());
+
+    // 在 Claude 中手动修改正文：报告为 Modified，并给出正文差异
+    let claude_path = home.join(".claude").join("agents").join("reviewer.md");
+    let original = fs::read_to_string(&claude_path).unwrap();
+    fs::write(
+        &claude_path,
+        original.replace("Review the diff.", "Review the diff twice."),
+    )
+    .unwrap();
+
+    let drifts = AgentsService::check_drift(&state).expect("check drift");
+    assert_eq!(drifts.len(), 1);
+    assert_eq!(drifts[0].agent_id, "reviewer");
+    assert_eq!(drifts[0].app, "claude");
+    assert_eq!(drifts[0].status, AgentDriftStatus::Modified);
+    assert_eq!(drifts[0].changed_fields, vec!["content".to_string()]);
+    assert!(drifts[0]
+        .content_diff
+        .iter()
+        .any(|line| line.kind == DiffLineKind::Added && line.text == "Review the diff twice."));
+
+    // 拉回后数据库更新，并同步到其他已启用的工具
+    let pulled =
+        AgentsService::pull_from_app(&state, "reviewer", AppType::Claude).expect("pull edit back");
+    assert_eq!(pulled.content.trim_end(), "Review the diff twice.");
+    let stored = state.db.get_agent_by_id("reviewer").unwrap().unwrap();
+    assert_eq!(stored.content.trim_end(), "Review the diff twice.");
+    let codex_file = fs::read_to_string(home.join(".codex").join("AGENTS.md")).unwrap();
+    assert!(codex_file.contains("Review the diff twice."));
+    assert!(AgentsService::check_drift(&state).unwrap().is_empty());
+
+    // 文件被删除：报告为 Missing，无法拉回，但可以按数据库恢复
+    fs::remove_file(&claude_path).unwrap();
+    let drifts = AgentsService::check_drift(&state).expect("check drift");
+    assert_eq!(drifts.len(), 1);
+    assert_eq!(drifts[0].status, AgentDriftStatus::Missing);
+    AgentsService::pull_from_app(&state, "reviewer", AppType::Claude)
+        .expect_err("missing file cannot be pulled");
+    AgentsService::restore_file(&state, "reviewer", AppType::Claude).expect("restore file");
+    assert!(fs::read_to_string(&claude_path)
+        .unwrap()
+        .contains("Review the diff twice."));
+    assert!(AgentsService::check_drift(&state).unwrap().is_empty());
+}
+
+#[test]
+fn overwritten_external_edits_are_kept_in_the_agent_history() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let state = create_test_state().expect("create test state");
+    let mut settings = AppSettings::default();
+    settings
+        .external_edit_policies
+        .insert("claude".to_string(), ExternalEditPolicy::Versions);
+    update_settings(settings).expect("update settings");
+
+    AgentsService::upsert(&state, reviewer("Review the diff."), false).expect("save agent");
+    let claude_path = home.join(".claude").join("agents").join("reviewer.md");
+    let original = fs::read_to_string(&claude_path).unwrap();
+    fs::write(
+        &claude_path,
+        original.replace("Review the diff.", "Review the diff by hand."),
+    )
+    .unwrap();
+
+    AgentsService::upsert(&state, reviewer("Review the diff carefully."), false)
+        .expect("save agent again");
+    assert!(fs::read_to_string(&claude_path)
+        .unwrap()
+        .contains("Review the diff carefully."));
+
+    // 被覆盖的外部修改是最新的历史版本，冲突记录指向它
+    let history = state.db.get_agent_history("reviewer").unwrap();
+    assert_eq!(
+        history[0].snapshot.content.trim_end(),
+        "Review the diff by hand."
+    );
+    let conflicts = state.db.get_external_edit_conflicts().unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].id, "reviewer");
+    assert_eq!(conflicts[0].revision, Some(history[0].version));
+    assert!(!home.join(".cc-switch").join("external-edits").exists());
+
+    assert!(AgentsService::check_drift(&state).unwrap().is_empty());
+}
+
+#[test]
+fn sync_targets_distinguish_agent_files_from_shared_instruction_files() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let state = create_test_state().expect("create test state");
+    AgentsService::upsert(&state, reviewer("Review the diff."), false).expect("save agent");
+
+    let targets = SyncTargetsService::get_targets(&state, SyncTargetKind::Agent, "reviewer")
+        .expect("agent targets");
+    let claude = targets.iter().find(|t| t.app == "claude").expect("claude");
+    assert_eq!(claude.mode, SyncTargetMode::WholeFile);
+    assert!(claude.marker_start.is_none() && claude.marker_end.is_none());
+    assert!(claude.enabled && claude.exists);
+    assert_eq!(
+        claude.path,
+        home.join(".claude")
+            .join("agents")
+            .join("reviewer.md")
+            .to_string_lossy()
+    );
+
+    let codex = targets.iter().find(|t| t.app == "codex").expect("codex");
+    assert_eq!(codex.mode, SyncTargetMode::ManagedBlock);
+    assert!(codex.marker_start.is_some() && codex.marker_end.is_some());
+    assert!(!codex.enabled);
+
+    assert!(SyncTargetsService::get_targets(&state, SyncTargetKind::Agent, "missing").is_err());
+}
+
+#[test]
+fn date_variables_do_not_cause_drift_or_get_frozen_by_pull() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let state = create_test_state().expect("create test state");
+
+    AgentsService::upsert(
+        &state,
+        reviewer("Written on {{today}}.\nReview the diff.\n"),
+        false,
+    )
+    .expect("save agent");
+
+    // 模拟前一天写入的文件：只有日期不同，不算外部修改
+    let path = home.join(".claude").join("agents").join("reviewer.md");
+    let written = fs::read_to_string(&path).unwrap();
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    assert!(written.contains(&format!("Written on {today}.")));
+    let yesterday = written.replace(&today, "2000-01-01");
+    fs::write(&path, &yesterday).unwrap();
+    assert!(AgentsService::check_drift(&state).unwrap().is_empty());
+
+    // 改动其他行后拉回：日期行仍保留模板变量
+    fs::write(
+        &path,
+        yesterday.replace("Review the diff.", "Review the diff twice."),
+    )
+    .unwrap();
+    assert_eq!(AgentsService::check_drift(&state).unwrap().len(), 1);
+    let pulled =
+        AgentsService::pull_from_app(&state, "reviewer", AppType::Claude).expect("pull edit back");
+    assert_eq!(
+        pulled.content.trim_end(),
+        "Written on {{today}}.\nReview the diff twice."
+    );
+}
+
+#[test]
+fn imported_agents_are_not_enabled_or_written_to_tool_files() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    // 发送方启用了 Claude；分享链接与导出文件都经由 import_bundle 导入
+    let sender = create_test_state().expect("create sender state");
+    AgentsService::upsert(&sender, reviewer("Review the diff.\n"), false).expect("save agent");
+    let bundle = AgentsService::export_bundle(&sender, None).expect("export bundle");
+    assert!(bundle.agents[0].apps.claude);
+    drop(sender);
+
+    reset_test_fs();
+    let state = create_test_state().expect("create receiver state");
+    let result = AgentsService::import_bundle(&state, bundle, DuplicateStrategy::Rename)
+        .expect("import bundle");
+    assert_eq!(result.imported, vec!["reviewer".to_string()]);
+
+    let imported = state.db.get_agent_by_id("reviewer").unwrap().unwrap();
+    assert!(imported.apps.is_empty());
+    assert!(!home
+        .join(".claude")
+        .join("agents")
+        .join("reviewer.md")
+        .exists());
+}
+
+#[test]
+fn agent_bundle_import_handles_duplicates_and_ignores_bundle_state() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let state = create_test_state().expect("create test state");
+
+    AgentsService::upsert(&state, reviewer("Review v1.\n"), false).expect("save reviewer");
+    AgentsService::upsert(
+        &state,
+        AgentDefinition {
+            id: "helper".to_string(),
+            name: "Helper".to_string(),
+            content: "Help v1.\n".to_string(),
+            ..Default::default()
+        },
+        false,
+    )
+    .expect("save helper");
+    AgentsService::reorder(&state, &["helper".to_string(), "reviewer".to_string()])
+        .expect("reorder agents");
+    let local = state.db.get_agent_by_id("reviewer").unwrap().unwrap();
+
+    // 导入包中的启用状态与排序位置来自导出方，不应生效
+    let mut bundle = AgentsService::export_bundle(&state, None).expect("export bundle");
+    for agent in &mut bundle.agents {
+        agent.content = agent.content.replace("v1", "v2");
+        agent.apps = AgentApps {
+            opencode: true,
+            ..Default::default()
+        };
+        agent.sort_index = Some(99);
+    }
+
+    let skipped = AgentsService::import_bundle(&state, bundle.clone(), DuplicateStrategy::Skip)
+        .expect("import with skip");
+    assert_eq!(skipped.skipped, vec!["helper", "reviewer"]);
+    assert_eq!(
+        state
+            .db
+            .get_agent_by_id("reviewer")
+            .unwrap()
+            .unwrap()
+            .content,
+        "Review v1.\n"
+    );
+
+    let overwritten =
+        AgentsService::import_bundle(&state, bundle.clone(), DuplicateStrategy::Overwrite)
+            .expect("import with overwrite");
+    assert_eq!(overwritten.overwritten, vec!["helper", "reviewer"]);
+    let reviewer_after = state.db.get_agent_by_id("reviewer").unwrap().unwrap();
+    assert_eq!(reviewer_after.content, "Review v2.\n");
+    assert_eq!(reviewer_after.apps, local.apps);
+    assert_eq!(reviewer_after.sort_index, local.sort_index);
+    let written =
+        fs::read_to_string(home.join(".claude").join("agents").join("reviewer.md")).unwrap();
+    assert!(written.contains("Review v2."));
+    assert!(!home
+        .join(".config")
+        .join("opencode")
+        .join("agents")
+        .join("reviewer.md")
+        .exists());
+
+    let renamed = AgentsService::import_bundle(&state, bundle.clone(), DuplicateStrategy::Rename)
+        .expect("import with rename");
+    let pairs: Vec<_> = renamed
+        .renamed
+        .iter()
+        .map(|r| (r.from.as_str(), r.to.as_str()))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![("helper", "helper-2"), ("reviewer", "reviewer-2")]
+    );
+    let copy = state.db.get_agent_by_id("reviewer-2").unwrap().unwrap();
+    assert!(copy.apps.is_empty());
+    assert_ne!(copy.sort_index, Some(99));
+
+    let mut unknown_format = bundle.clone();
+    unknown_format.format = "something-else".to_string();
+    let mut newer_version = bundle;
+    newer_version.version += 1;
+    for bad in [unknown_format, newer_version] {
+        let err = AgentsService::import_bundle(&state, bad, DuplicateStrategy::Skip)
+            .expect_err("unsupported bundle is rejected");
+        assert!(matches!(
+            err,
+            AppError::Localized {
+                key: "agent.bundle_unsupported",
+                ..
+            }
+        ));
+    }
+}
+
+#[test]
+fn openclaw_agents_are_written_to_the_workspace_agents_md() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let _ = fs::remove_dir_all(home.join(".openclaw"));
+    let workspace_md = home.join(".openclaw").join("workspace").join("AGENTS.md");
+    fs::create_dir_all(workspace_md.parent().unwrap()).unwrap();
+    fs::write(&workspace_md, "# My notes\n").unwrap();
+
+    let state = create_test_state().expect("create test state");
+    let mut agent = reviewer("Review the diff.\n");
+    agent.apps = AgentApps {
+        openclaw: true,
+        ..Default::default()
+    };
+    AgentsService::upsert(&state, agent.clone(), false).expect("enable for openclaw");
+
+    let content = fs::read_to_string(&workspace_md).unwrap();
+    assert!(
+        content.contains("<!-- cc-switch:agent:reviewer -->"),
+        "{content}"
+    );
+    assert!(content.contains("Review the diff."), "{content}");
+    assert!(content.contains("# My notes"), "{content}");
+    assert!(!home.join(".openclaw").join("AGENTS.md").exists());
+
+    agent.apps = AgentApps::default();
+    AgentsService::upsert(&state, agent, false).expect("disable for openclaw");
+
+    let content = fs::read_to_string(&workspace_md).unwrap();
+    assert!(!content.contains("cc-switch:agent:reviewer"), "{content}");
+    assert!(!content.contains("Review the diff."), "{content}");
+    assert!(content.contains("# My notes"), "{content}");
+}
@@ -105,3 +105,92 @@ fn load_valid_v2_config_succeeds() {
         .is_some());
     assert!(loaded.get_manager(&cc_switch_lib::AppType::Codex).is_some());
 }
+
+#[test]
+fn reappearing_legacy_config_is_previewed_and_merged_selectively() {
+    use cc_switch_lib::{
+        LegacyChangeAction, LegacyConfigService, LegacyEntryKind, LegacyMergeSelection,
+    };
+
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    let state = support::create_test_state().expect("create test state");
+    assert!(LegacyConfigService::detect(&state).unwrap().is_none());
+
+    // 例如被 dotfiles 同步恢复的一份旧配置
+    let path = cfg_path();
+    fs::create_dir_all(path.parent().unwrap()).expect("create cfg dir");
+    let legacy = serde_json::json!({
+        "version": 2,
+        "claude": {
+            "current": "legacy-relay",
+            "providers": {
+                "legacy-relay": {
+                    "id": "legacy-relay",
+                    "name": "Legacy Relay",
+                    "settingsConfig": {
+                        "env": {
+                            "ANTHROPIC_AUTH_TOKEN": "sk-legacy",
+                            "ANTHROPIC_BASE_URL": "https://relay.example.com"
+                        }
+                    }
+                }
+            }
+        },
+        "prompts": {
+            "claude": {
+                "prompts": {
+                    "legacy-prompt": {
+                        "id": "legacy-prompt",
+                        "name": "Legacy Prompt",
+                        "content": "Be brief."
+                    }
+                }
+            }
+        }
+    });
+    fs::write(&path, serde_json::to_string_pretty(&legacy).unwrap()).expect("write legacy");
+
+    let status = LegacyConfigService::detect(&state)
+        .unwrap()
+        .expect("newer config.json should be detected");
+    assert_eq!(status.last_merged_at, None);
+
+    let diff = LegacyConfigService::preview(&state).unwrap().unwrap();
+    let kinds: Vec<_> = diff.changes.iter().map(|c| (c.kind, c.action)).collect();
+    assert_eq!(
+        kinds,
+        [
+            (LegacyEntryKind::Provider, LegacyChangeAction::Add),
+            (LegacyEntryKind::Prompt, LegacyChangeAction::Add),
+        ]
+    );
+
+    // 只合并供应商
+    let result = LegacyConfigService::merge(
+        &state,
+        &[LegacyMergeSelection {
+            kind: LegacyEntryKind::Provider,
+            app: Some("claude".to_string()),
+            id: "legacy-relay".to_string(),
+        }],
+    )
+    .unwrap();
+    assert_eq!(result.merged, 1);
+    assert!(result.failed.is_empty());
+    assert!(state
+        .db
+        .get_provider_by_id("legacy-relay", "claude")
+        .unwrap()
+        .is_some());
+    assert!(!state
+        .db
+        .get_prompts()
+        .unwrap()
+        .contains_key("legacy-prompt"));
+
+    // 同一版文件不再提示，文件也保持原样
+    assert!(LegacyConfigService::detect(&state).unwrap().is_none());
+    assert!(path.exists());
+}
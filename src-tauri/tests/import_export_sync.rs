@@ -390,6 +390,7 @@ command = "echo"
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            origin: None,
         },
     );
 
@@ -417,6 +418,71 @@ command = "echo"
     );
 }
 
+#[test]
+fn codex_mcp_round_trip_keeps_env_args_and_the_rest_of_the_file() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let path = cc_switch_lib::get_codex_config_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("create codex dir");
+    }
+    fs::write(
+        &path,
+        r#"model = "gpt-5"
+
+# 本地文件系统服务
+[mcp_servers.fs]
+command = "npx"
+args = ["-y", "@modelcontextprotocol/server-filesystem", 3000]
+startup_timeout_sec = 20 # 慢启动
+
+[mcp_servers.fs.env]
+ROOT = "/srv"
+PORT = 8080
+
+[mcp_servers.docs]
+url = "https://docs.example.com/mcp"
+bearer_token_env_var = "DOCS_TOKEN"
+"#,
+    )
+    .expect("write codex config");
+
+    let mut config = MultiAppConfig::default();
+    cc_switch_lib::import_from_codex(&mut config).expect("import codex");
+    let servers = config.mcp.servers.as_ref().expect("unified servers");
+
+    // 未写 type 的条目按字段推断；数字形式的 args / env 转为字符串保留
+    let fs_spec = &servers.get("fs").expect("fs server").server;
+    assert_eq!(fs_spec["type"], "stdio");
+    assert_eq!(
+        fs_spec["args"],
+        json!(["-y", "@modelcontextprotocol/server-filesystem", "3000"])
+    );
+    assert_eq!(fs_spec["env"], json!({ "ROOT": "/srv", "PORT": "8080" }));
+    assert_eq!(fs_spec["startup_timeout_sec"], 20);
+    let docs_spec = &servers.get("docs").expect("docs server").server;
+    assert_eq!(docs_spec["type"], "http");
+    assert_eq!(docs_spec["bearer_token_env_var"], "DOCS_TOKEN");
+
+    // 只修改 env 中的一个值：其余字段、注释与其他条目保持原样
+    let mut updated = fs_spec.clone();
+    updated["env"]["ROOT"] = json!("/data");
+    cc_switch_lib::sync_single_server_to_codex(&MultiAppConfig::default(), "fs", &updated)
+        .expect("sync fs");
+    let text = fs::read_to_string(&path).expect("read codex config");
+    assert!(text.starts_with("model = \"gpt-5\"\n"));
+    assert!(text.contains("# 本地文件系统服务"));
+    assert!(text.contains("startup_timeout_sec = 20 # 慢启动"));
+    assert!(text.contains("ROOT = \"/data\""));
+    assert!(text.contains("bearer_token_env_var = \"DOCS_TOKEN\""));
+
+    let root: toml::Table = toml::from_str(&text).expect("valid toml");
+    let env = root["mcp_servers"]["fs"]["env"]
+        .as_table()
+        .expect("env table");
+    assert_eq!(env["PORT"].as_str(), Some("8080"));
+}
+
 #[test]
 fn sync_claude_enabled_mcp_projects_to_user_config() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
@@ -518,6 +584,7 @@ fn import_from_claude_merges_into_config() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            origin: None,
         },
     );
 
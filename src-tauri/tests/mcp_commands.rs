@@ -4,8 +4,9 @@ use std::fs;
 use serde_json::json;
 
 use cc_switch_lib::{
-    get_claude_mcp_path, get_claude_settings_path, import_default_config_test_hook, AppError,
-    AppType, McpApps, McpServer, McpService, MultiAppConfig,
+    get_claude_mcp_path, get_claude_settings_path, import_default_config_test_hook,
+    write_codex_live_atomic, AppError, AppType, McpApps, McpServer, McpService, MultiAppConfig,
+    ProviderService,
 };
 
 #[path = "support.rs"]
@@ -65,6 +66,56 @@ fn import_default_config_claude_persists_provider() {
     );
 }
 
+#[test]
+fn first_launch_detects_providers_from_existing_live_configs() {
+    use support::create_test_state;
+
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let settings_path = get_claude_settings_path();
+    fs::create_dir_all(settings_path.parent().unwrap()).expect("create claude settings dir");
+    let settings = json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "claude-key" } });
+    fs::write(&settings_path, settings.to_string()).expect("seed claude settings.json");
+    write_codex_live_atomic(
+        &json!({ "OPENAI_API_KEY": "codex-key" }),
+        Some("model = \"gpt-5\"\n"),
+    )
+    .expect("seed codex live config");
+
+    let state = create_test_state().expect("create test state");
+    let detected =
+        ProviderService::import_live_providers_on_first_launch(&state).expect("detect providers");
+    assert_eq!(detected, vec![AppType::Claude, AppType::Codex]);
+
+    for app in [AppType::Claude, AppType::Codex] {
+        assert_eq!(
+            state
+                .db
+                .get_current_provider(app.as_str())
+                .unwrap()
+                .as_deref(),
+            Some("default"),
+            "{} should get a current provider",
+            app.as_str()
+        );
+    }
+    assert!(state
+        .db
+        .get_all_providers(AppType::Gemini.as_str())
+        .unwrap()
+        .is_empty());
+
+    // 供应商表不为空时不再自动识别
+    state.db.delete_provider("claude", "default").unwrap();
+    assert!(
+        ProviderService::import_live_providers_on_first_launch(&state)
+            .unwrap()
+            .is_empty()
+    );
+}
+
 #[test]
 fn import_default_config_without_live_file_returns_error() {
     use support::create_test_state;
@@ -220,6 +271,7 @@ fn set_mcp_enabled_for_codex_writes_live_config() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            origin: None,
         },
     );
 
@@ -284,6 +336,7 @@ fn enabling_codex_mcp_skips_when_codex_dir_missing() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            origin: None,
         },
     )
     .expect("insert server without syncing");
@@ -328,6 +381,7 @@ fn upsert_mcp_server_disabling_app_removes_from_claude_live_config() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            origin: None,
         },
     )
     .expect("upsert should sync to Claude live config");
@@ -361,6 +415,7 @@ fn upsert_mcp_server_disabling_app_removes_from_claude_live_config() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            origin: None,
         },
     )
     .expect("upsert disabling app should remove from Claude live config");
@@ -493,6 +548,7 @@ fn enabling_gemini_mcp_skips_when_gemini_dir_missing() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            origin: None,
         },
     )
     .expect("insert server without syncing");
@@ -547,6 +603,7 @@ fn enabling_claude_mcp_skips_when_claude_config_absent() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            origin: None,
         },
     )
     .expect("insert server without syncing");
@@ -560,3 +617,121 @@ fn enabling_claude_mcp_skips_when_claude_config_absent() {
         "~/.claude.json should still not exist after skipped sync"
     );
 }
+
+#[test]
+fn upsert_mcp_server_rolls_back_db_when_live_sync_fails() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    fs::create_dir_all(home.join(".claude")).expect("create ~/.claude dir");
+
+    let state = support::create_test_state().expect("create test state");
+    let make_server = |name: &str, claude: bool| McpServer {
+        id: "echo".to_string(),
+        name: name.to_string(),
+        server: json!({
+            "type": "stdio",
+            "command": "echo"
+        }),
+        apps: McpApps {
+            claude,
+            codex: false,
+            gemini: false,
+            opencode: false,
+        },
+        description: None,
+        homepage: None,
+        docs: None,
+        tags: Vec::new(),
+        origin: None,
+    };
+
+    // 损坏的 ~/.claude.json 让写入 Claude MCP 配置失败
+    fs::write(get_claude_mcp_path(), "{ not json").expect("seed broken ~/.claude.json");
+
+    // 新建失败：数据库中不应残留记录
+    McpService::upsert_server(&state, make_server("echo", true))
+        .expect_err("sync to broken Claude config should fail");
+    assert!(
+        !state
+            .db
+            .get_all_mcp_servers()
+            .expect("load servers")
+            .contains_key("echo"),
+        "failed create should be rolled back"
+    );
+
+    // 更新失败：数据库恢复为更新前的定义
+    McpService::upsert_server(&state, make_server("echo", false)).expect("save without sync");
+    McpService::upsert_server(&state, make_server("renamed", true))
+        .expect_err("sync to broken Claude config should fail");
+    let servers = state.db.get_all_mcp_servers().expect("load servers");
+    let saved = servers.get("echo").expect("server kept");
+    assert_eq!(saved.name, "echo");
+    assert!(!saved.apps.claude, "failed update should be rolled back");
+}
+
+#[test]
+fn gemini_extension_servers_are_imported_read_only_and_pruned() {
+    use support::create_test_state;
+
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let ext_dir = home.join(".gemini").join("extensions").join("search-tools");
+    fs::create_dir_all(&ext_dir).expect("create extension dir");
+    fs::write(
+        ext_dir.join("gemini-extension.json"),
+        serde_json::to_string_pretty(&json!({
+            "name": "search-tools",
+            "version": "1.0.0",
+            "mcpServers": {
+                "search": {
+                    "command": "node",
+                    "args": ["${extensionPath}${/}server.js"]
+                }
+            }
+        }))
+        .unwrap(),
+    )
+    .expect("write extension manifest");
+
+    let state = create_test_state().expect("create test state");
+    let added = McpService::import_from_gemini_extensions(&state).expect("scan extensions");
+    assert_eq!(added, 1);
+    let servers = state.db.get_all_mcp_servers().expect("load servers");
+    let server = servers
+        .get("search-tools/search")
+        .expect("extension server");
+    assert!(server.is_read_only() && server.apps.gemini && !server.apps.claude);
+    let arg = server.server["args"][0].as_str().unwrap();
+    assert!(arg.starts_with(ext_dir.to_string_lossy().as_ref()) && arg.ends_with("server.js"));
+
+    // 只读条目不能切换或删除，也不会写入 Gemini settings.json
+    let err = McpService::toggle_app(&state, "search-tools/search", AppType::Claude, true)
+        .expect_err("read-only server cannot be toggled");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "mcp.read_only",
+            ..
+        }
+    ));
+    assert!(McpService::delete_server(&state, "search-tools/search").is_err());
+    McpService::sync_all_enabled(&state).expect("sync all");
+    assert!(!home.join(".gemini").join("settings.json").exists());
+
+    // 再次扫描不重复新增；扩展卸载后条目被移除
+    assert_eq!(
+        McpService::import_from_gemini_extensions(&state).expect("rescan"),
+        0
+    );
+    fs::remove_dir_all(&ext_dir).expect("remove extension");
+    McpService::import_from_gemini_extensions(&state).expect("scan after uninstall");
+    assert!(!state
+        .db
+        .get_all_mcp_servers()
+        .unwrap()
+        .contains_key("search-tools/search"));
+}
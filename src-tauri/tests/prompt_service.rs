@@ -0,0 +1,1174 @@
+use std::fs;
+
+use cc_switch_lib::{
+    update_settings, AgentApps, AgentDefinition, AgentsMdService, AgentsService, AppSettings,
+    AppType, BulkReplaceKind, BulkReplaceService, DriftResolution, DuplicateStrategy,
+    EnablementService, ExternalEditPolicy, MarkdownImportService, MarkdownImportTarget,
+    OpenCodePromptTarget, Prompt, PromptApps, PromptBackupService, PromptBundleService,
+    PromptDriftService, PromptIssueLevel, PromptProjectService, PromptRevisionReason,
+    PromptSchedule, PromptScheduleService, PromptService, PromptSizeLimit, Schedule,
+    SyncTargetKind, SyncTargetMode, SyncTargetsService,
+};
+
+#[path = "support.rs"]
+mod support;
+use support::{create_test_state, ensure_test_home, reset_test_fs, test_mutex};
+
+fn prompt(id: &str, content: &str) -> Prompt {
+    Prompt {
+        id: id.to_string(),
+        name: id.to_string(),
+        content: content.to_string(),
+        description: None,
+        tags: Vec::new(),
+        apps: Default::default(),
+        sort_index: None,
+        created_at: None,
+        updated_at: None,
+    }
+}
+
+/// 提示词托管区域的完整内容
+fn region(text: &str) -> String {
+    format!("<!-- cc-switch:prompt:begin -->\n{text}<!-- cc-switch:prompt:end -->\n")
+}
+
+#[test]
+fn non_exclusive_prompts_are_concatenated_in_priority_order() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let path = home.join(".claude").join("CLAUDE.md");
+
+    let state = create_test_state().expect("create test state");
+    PromptService::upsert_prompt(&state, prompt("base", "Base rules\n")).expect("save base");
+    PromptService::upsert_prompt(&state, prompt("style", "Style guide\n")).expect("save style");
+
+    PromptService::toggle_prompt_app(&state, "base", AppType::Claude, true, false)
+        .expect("enable base");
+    PromptService::toggle_prompt_app(&state, "style", AppType::Claude, true, false)
+        .expect("enable style");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region("Base rules\n\n---\n\nStyle guide\n")
+    );
+
+    PromptService::reorder_prompts(&state, &["style".to_string(), "base".to_string()])
+        .expect("reorder");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region("Style guide\n\n---\n\nBase rules\n")
+    );
+
+    // 关闭其中一个后只剩单段，内容原样写入
+    PromptService::toggle_prompt_app(&state, "style", AppType::Claude, false, false)
+        .expect("disable style");
+    assert_eq!(fs::read_to_string(&path).unwrap(), region("Base rules\n"));
+
+    // 默认的互斥启用会取消其他提示词
+    PromptService::toggle_prompt_app(&state, "style", AppType::Claude, true, false)
+        .expect("enable style again");
+    PromptService::toggle_prompt_app(&state, "base", AppType::Claude, true, true)
+        .expect("exclusive enable");
+    assert_eq!(fs::read_to_string(&path).unwrap(), region("Base rules\n"));
+    let prompts = state.db.get_prompts().unwrap();
+    assert!(!prompts["style"].apps.claude);
+}
+
+#[test]
+fn user_content_outside_the_region_survives_toggles_and_deletion() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let path = home.join(".claude").join("CLAUDE.md");
+    fs::create_dir_all(path.parent().unwrap()).expect("create claude dir");
+
+    let state = create_test_state().expect("create test state");
+
+    // 旧版本整文件写入的提示词：启用后被托管区域取代，不会重复出现
+    fs::write(&path, "Base rules\n").expect("seed legacy prompt file");
+    let mut base = prompt("base", "Base rules\n");
+    base.apps = PromptApps {
+        claude: true,
+        ..Default::default()
+    };
+    PromptService::upsert_prompt(&state, base).expect("save enabled base");
+    assert_eq!(fs::read_to_string(&path).unwrap(), region("Base rules\n"));
+
+    // 用户手写的内容位于区域之后，切换与删除都不影响
+    let manual = format!("{}\n# My notes\n", region("Base rules\n"));
+    fs::write(&path, &manual).expect("append manual notes");
+
+    PromptService::upsert_prompt(&state, prompt("style", "Style guide\n")).expect("save style");
+    PromptService::toggle_prompt_app(&state, "style", AppType::Claude, true, true)
+        .expect("switch to style");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        format!("{}\n# My notes\n", region("Style guide\n"))
+    );
+
+    PromptService::delete_prompt(&state, "style").expect("delete style");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "# My notes\n");
+}
+
+#[test]
+fn hand_written_prompt_files_are_backed_up_once_and_can_be_restored() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let path = home.join(".claude").join("CLAUDE.md");
+    fs::create_dir_all(path.parent().unwrap()).expect("create claude dir");
+    fs::write(&path, "# My own rules\n").expect("seed hand-written file");
+
+    let state = create_test_state().expect("create test state");
+    PromptService::upsert_prompt(&state, prompt("base", "Base rules\n")).expect("save base");
+    PromptService::toggle_prompt_app(&state, "base", AppType::Claude, true, false)
+        .expect("enable base");
+
+    let backups = PromptBackupService::list().expect("list backups");
+    assert_eq!(backups.len(), 1);
+    assert_eq!(backups[0].app, "claude");
+    assert_eq!(backups[0].original_path, path.display().to_string());
+    assert_eq!(
+        fs::read_to_string(&backups[0].backup_path).unwrap(),
+        "# My own rules\n"
+    );
+
+    // 文件已含托管区域，之后的改写不再备份
+    PromptService::upsert_prompt(&state, prompt("style", "Style guide\n")).expect("save style");
+    PromptService::toggle_prompt_app(&state, "style", AppType::Claude, true, true)
+        .expect("switch to style");
+    assert_eq!(PromptBackupService::list().unwrap().len(), 1);
+
+    PromptBackupService::restore(&backups[0].id).expect("restore backup");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "# My own rules\n");
+    assert!(PromptBackupService::restore("missing.bak").is_err());
+}
+
+#[test]
+fn markdown_folder_import_skips_hidden_dirs_and_keeps_local_state_on_overwrite() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let vault = home.join("vault");
+    fs::create_dir_all(vault.join("Writing")).expect("create vault");
+    fs::create_dir_all(vault.join(".obsidian")).expect("create obsidian dir");
+    fs::write(vault.join("terse.md"), "# Terse\n\nAnswer briefly.\n").expect("write terse");
+    fs::write(
+        vault.join("Writing").join("editor.md"),
+        "---\ntitle: Editor\ndescription: Tightens prose\n---\nEdit the text.\n",
+    )
+    .expect("write editor");
+    fs::write(
+        vault.join("Writing").join("empty.md"),
+        "---\ntitle: Empty\n---\n",
+    )
+    .expect("write empty");
+    fs::write(vault.join(".obsidian").join("hidden.md"), "Not a note\n").expect("write hidden");
+
+    let state = create_test_state().expect("create test state");
+    let result = MarkdownImportService::import(
+        &state,
+        &vault,
+        MarkdownImportTarget::Prompt,
+        DuplicateStrategy::Skip,
+    )
+    .expect("import vault");
+    assert_eq!(result.imported, vec!["editor", "terse"]);
+    assert_eq!(result.ignored_files.len(), 1, "empty note is ignored");
+
+    let prompts = PromptService::get_prompts(&state).expect("load prompts");
+    assert_eq!(prompts.len(), 2, "hidden directories are not scanned");
+    let editor = &prompts["editor"];
+    assert_eq!(editor.name, "Editor");
+    assert_eq!(editor.description.as_deref(), Some("Tightens prose"));
+    assert_eq!(editor.content, "Edit the text.\n");
+    assert_eq!(prompts["terse"].content, "Answer briefly.\n");
+
+    PromptService::toggle_prompt_app(&state, "terse", AppType::Claude, true, true)
+        .expect("enable terse");
+    fs::write(vault.join("terse.md"), "# Terse\n\nAnswer in one line.\n").expect("edit terse");
+
+    let result = MarkdownImportService::import(
+        &state,
+        &vault.join("terse.md"),
+        MarkdownImportTarget::Prompt,
+        DuplicateStrategy::Overwrite,
+    )
+    .expect("re-import terse");
+    assert_eq!(result.overwritten, vec!["terse"]);
+
+    let terse = &PromptService::get_prompts(&state).expect("reload")["terse"];
+    assert_eq!(terse.content, "Answer in one line.\n");
+    assert!(terse.apps.claude, "overwrite keeps the prompt enabled");
+    let live = fs::read_to_string(home.join(".claude").join("CLAUDE.md")).expect("read CLAUDE.md");
+    assert!(live.contains("Answer in one line."));
+}
+
+#[test]
+fn prompts_imported_from_a_folder_are_named_after_their_files() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let dir = home.join("prompt-library");
+    fs::create_dir_all(&dir).expect("create library");
+    fs::write(
+        dir.join("code-review.md"),
+        "---
+title: Reviewer
+description: Reviews diffs
+tags: [review, \"#Rust\", Review]
+---
+# Checklist
+
+Look for bugs.
+",
+    )
+    .expect("write code-review");
+    fs::write(
+        dir.join("Terse.md"),
+        "# Terse
+
+Answer briefly.
+",
+    )
+    .expect("write terse");
+
+    let state = create_test_state().expect("create test state");
+    let result =
+        MarkdownImportService::import_prompts_from_dir(&state, &dir, DuplicateStrategy::Skip)
+            .expect("import folder");
+    assert_eq!(result.imported, vec!["terse", "code-review"]);
+
+    let prompts = PromptService::get_prompts(&state).expect("load prompts");
+    let review = &prompts["code-review"];
+    assert_eq!(
+        review.name, "code-review",
+        "file stem wins over frontmatter title"
+    );
+    assert_eq!(review.description.as_deref(), Some("Reviews diffs"));
+    assert_eq!(review.tags, vec!["review", "Rust"]);
+    assert_eq!(review.content, "# Checklist\n\nLook for bugs.\n");
+    let terse = &prompts["terse"];
+    assert_eq!(terse.name, "Terse");
+    assert_eq!(
+        terse.content, "Answer briefly.\n",
+        "heading matching the file name is dropped"
+    );
+    assert!(terse.tags.is_empty());
+
+    assert!(MarkdownImportService::import_prompts_from_dir(
+        &state,
+        &dir.join("Terse.md"),
+        DuplicateStrategy::Skip
+    )
+    .is_err());
+}
+
+#[test]
+fn template_variables_are_rendered_at_write_time_only() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let path = home.join(".codex").join("AGENTS.md");
+
+    let state = create_test_state().expect("create test state");
+    PromptService::upsert_prompt(&state, prompt("env", "Target: {{app}} ({{ unknown }})\n"))
+        .expect("save prompt");
+    PromptService::toggle_prompt_app(&state, "env", AppType::Codex, true, true)
+        .expect("enable for codex");
+
+    assert_eq!(
+        fs::read_to_string(&path).expect("read AGENTS.md"),
+        region("Target: codex ({{ unknown }})\n")
+    );
+    let stored = &PromptService::get_prompts(&state).expect("load prompts")["env"];
+    assert_eq!(stored.content, "Target: {{app}} ({{ unknown }})\n");
+
+    let preview = PromptService::render_preview(&state, &AppType::Claude, "{{app}}")
+        .expect("preview content");
+    assert_eq!(preview, "claude");
+    let preview =
+        PromptService::preview_app_file(&state, &AppType::Codex, None).expect("preview enabled");
+    assert_eq!(preview.rendered, "Target: codex ({{ unknown }})\n");
+}
+
+#[test]
+fn project_prompts_are_written_to_the_project_and_not_the_home_file() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let project_dir = home.join("work").join("demo");
+    fs::create_dir_all(&project_dir).expect("create project dir");
+    fs::write(project_dir.join("AGENTS.md"), "Project notes\n").expect("seed AGENTS.md");
+
+    let state = create_test_state().expect("create test state");
+    PromptService::upsert_prompt(&state, prompt("base", "Base rules\n")).expect("save base");
+    PromptService::upsert_prompt(&state, prompt("style", "Style guide\n")).expect("save style");
+
+    let project = PromptProjectService::add_project(&state.db, project_dir.to_str().unwrap(), None)
+        .expect("register project");
+    assert_eq!(project.name, "demo");
+    assert!(
+        PromptProjectService::add_project(&state.db, project_dir.to_str().unwrap(), None).is_err(),
+        "same directory must not be registered twice"
+    );
+
+    for (id, app) in [("base", AppType::Claude), ("style", AppType::Codex)] {
+        PromptProjectService::toggle_project_prompt(&state, &project.id, id, app, true)
+            .expect("attach prompt");
+    }
+    let project_dir = std::path::PathBuf::from(&project.path);
+    assert_eq!(
+        fs::read_to_string(project_dir.join("CLAUDE.md")).expect("read project CLAUDE.md"),
+        region("Base rules\n")
+    );
+    assert_eq!(
+        fs::read_to_string(project_dir.join("AGENTS.md")).expect("read project AGENTS.md"),
+        format!("{}\nProject notes\n", region("Style guide\n"))
+    );
+    assert!(!project_dir.join("GEMINI.md").exists());
+    assert!(!home.join(".claude").join("CLAUDE.md").exists());
+
+    // 编辑提示词会同步到挂载它的项目；保存不应丢失挂载
+    PromptService::upsert_prompt(&state, prompt("base", "Base rules v2\n")).expect("edit base");
+    assert_eq!(
+        fs::read_to_string(project_dir.join("CLAUDE.md")).expect("read edited CLAUDE.md"),
+        region("Base rules v2\n")
+    );
+
+    PromptService::delete_prompt(&state, "style").expect("delete style");
+    assert_eq!(
+        fs::read_to_string(project_dir.join("AGENTS.md")).expect("read AGENTS.md after delete"),
+        "Project notes\n"
+    );
+
+    assert!(PromptProjectService::remove_project(&state, &project.id).expect("remove project"));
+    assert_eq!(
+        fs::read_to_string(project_dir.join("CLAUDE.md")).expect("read CLAUDE.md after removal"),
+        ""
+    );
+    assert!(PromptProjectService::list_projects(&state.db)
+        .expect("list projects")
+        .is_empty());
+}
+
+#[test]
+fn external_edits_follow_the_per_app_policy() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let path = home.join(".claude").join("CLAUDE.md");
+
+    let state = create_test_state().expect("create test state");
+    let claude_prompt = |content: &str| {
+        let mut p = prompt("base", content);
+        p.apps.claude = true;
+        p
+    };
+    let set_policy = |policy: ExternalEditPolicy| {
+        let mut settings = AppSettings::default();
+        settings
+            .external_edit_policies
+            .insert("claude".to_string(), policy);
+        update_settings(settings).expect("update settings");
+    };
+
+    PromptService::upsert_prompt(&state, claude_prompt("Base rules\n")).expect("save base");
+    assert_eq!(fs::read_to_string(&path).unwrap(), region("Base rules\n"));
+
+    // preserve：外部修改保留，本次写入跳过
+    set_policy(ExternalEditPolicy::Preserve);
+    fs::write(&path, region("Edited by hand\n")).expect("edit region externally");
+    PromptService::upsert_prompt(&state, claude_prompt("Base rules v2\n")).expect("edit base");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region("Edited by hand\n")
+    );
+    let conflicts = state.db.get_external_edit_conflicts().unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].policy, ExternalEditPolicy::Preserve);
+    assert!(conflicts[0].snapshot.is_none());
+
+    // versions：外部修改记入提示词历史后覆盖
+    set_policy(ExternalEditPolicy::Versions);
+    PromptService::upsert_prompt(&state, claude_prompt("Base rules v3\n")).expect("edit base");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region("Base rules v3\n")
+    );
+    let history = PromptService::history(&state, "base").unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].reason, PromptRevisionReason::ExternalEdit);
+    assert!(history[0].snapshot.content.contains("Edited by hand"));
+    let conflicts = state.db.get_external_edit_conflicts().unwrap();
+    assert_eq!(conflicts.len(), 2);
+    assert_eq!(conflicts[1].policy, ExternalEditPolicy::Versions);
+    assert_eq!(conflicts[1].revision, Some(history[0].version));
+    assert!(!home.join(".cc-switch").join("external-edits").exists());
+
+    // 内容与记录一致后不再视为外部修改
+    PromptService::upsert_prompt(&state, claude_prompt("Base rules v4\n")).expect("edit base");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region("Base rules v4\n")
+    );
+    assert_eq!(PromptService::history(&state, "base").unwrap().len(), 1);
+    assert_eq!(state.db.get_external_edit_conflicts().unwrap().len(), 2);
+}
+
+#[test]
+fn openclaw_prompts_are_toggled_independently_of_opencode() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let opencode_md = home.join(".config").join("opencode").join("AGENTS.md");
+    let openclaw_md = home.join(".openclaw").join("workspace").join("AGENTS.md");
+    let _ = fs::remove_dir_all(home.join(".config").join("opencode"));
+    let _ = fs::remove_dir_all(home.join(".openclaw"));
+
+    let state = create_test_state().expect("create test state");
+    let mut base = prompt("base", "Base rules\n");
+    base.apps.opencode = true;
+    PromptService::upsert_prompt(&state, base).expect("save base");
+    assert_eq!(
+        fs::read_to_string(&opencode_md).unwrap(),
+        region("Base rules\n")
+    );
+    assert!(!openclaw_md.exists());
+
+    PromptService::toggle_prompt_app(&state, "base", AppType::OpenClaw, true, false)
+        .expect("enable for openclaw");
+    assert_eq!(
+        fs::read_to_string(&openclaw_md).unwrap(),
+        region("Base rules\n")
+    );
+
+    // 关闭 OpenCode 不影响 OpenClaw
+    PromptService::toggle_prompt_app(&state, "base", AppType::OpenCode, false, false)
+        .expect("disable for opencode");
+    let apps = state.db.get_prompts().unwrap()["base"].apps.clone();
+    assert!(!apps.opencode && apps.openclaw);
+    assert_eq!(
+        fs::read_to_string(&openclaw_md).unwrap(),
+        region("Base rules\n")
+    );
+    assert!(!home.join(".openclaw").join("AGENTS.md").exists());
+
+    // 与 OpenClaw 的 agent 区块共用工作区 AGENTS.md，互不覆盖
+    AgentsService::upsert(
+        &state,
+        AgentDefinition {
+            id: "reviewer".to_string(),
+            name: "Reviewer".to_string(),
+            content: "Review the diff.\n".to_string(),
+            apps: AgentApps {
+                openclaw: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        false,
+    )
+    .expect("enable agent for openclaw");
+    PromptService::upsert_prompt(&state, {
+        let mut base = prompt("base", "Base rules v2\n");
+        base.apps.openclaw = true;
+        base
+    })
+    .expect("edit base");
+    let content = fs::read_to_string(&openclaw_md).unwrap();
+    assert!(content.contains(&region("Base rules v2\n")), "{content}");
+    assert!(content.contains("Review the diff."), "{content}");
+}
+
+#[test]
+fn opencode_prompts_move_between_agents_md_and_a_registered_instructions_file() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let dir = home.join(".config").join("opencode");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create opencode dir");
+    let agents_md = dir.join("AGENTS.md");
+    let instructions = dir.join("cc-switch-prompts.md");
+    let config_path = dir.join("opencode.json");
+    fs::write(&agents_md, "# House rules\n").expect("seed user AGENTS.md");
+    fs::write(
+        &config_path,
+        r#"{"instructions": ["docs/style.md"], "model": "x/y"}"#,
+    )
+    .expect("seed opencode.json");
+    let listed = || -> Vec<String> {
+        let config: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        config
+            .get("instructions")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .map(|v| v.as_str().unwrap().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let state = create_test_state().expect("create test state");
+    let mut base = prompt("base", "Base rules\n");
+    base.apps.opencode = true;
+    PromptService::upsert_prompt(&state, base).expect("save base");
+    assert_eq!(
+        fs::read_to_string(&agents_md).unwrap(),
+        format!("{}\n# House rules\n", region("Base rules\n"))
+    );
+
+    // 切到独立文件：AGENTS.md 只剩用户内容，独立文件登记在用户已有的 instructions 之后
+    PromptService::set_opencode_target(&state, OpenCodePromptTarget::Instructions)
+        .expect("switch to instructions");
+    assert_eq!(fs::read_to_string(&agents_md).unwrap(), "# House rules\n");
+    assert_eq!(
+        fs::read_to_string(&instructions).unwrap(),
+        region("Base rules\n")
+    );
+    let registered = instructions.to_string_lossy().to_string();
+    assert_eq!(listed(), vec!["docs/style.md".to_string(), registered]);
+
+    // 关闭后删除独立文件并撤销登记，用户条目与其他配置保持不变
+    PromptService::toggle_prompt_app(&state, "base", AppType::OpenCode, false, false)
+        .expect("disable base");
+    assert!(!instructions.exists());
+    assert_eq!(listed(), vec!["docs/style.md".to_string()]);
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(config["model"], "x/y");
+
+    // 切回 AGENTS.md 后重新写入托管区域
+    PromptService::toggle_prompt_app(&state, "base", AppType::OpenCode, true, false)
+        .expect("enable base");
+    PromptService::set_opencode_target(&state, OpenCodePromptTarget::Agents).expect("switch back");
+    assert!(!instructions.exists());
+    assert_eq!(listed(), vec!["docs/style.md".to_string()]);
+    assert_eq!(
+        fs::read_to_string(&agents_md).unwrap(),
+        format!("{}\n# House rules\n", region("Base rules\n"))
+    );
+}
+
+#[test]
+fn deleted_prompts_go_to_the_trash_and_restore_their_files_and_links() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let path = home.join(".claude").join("CLAUDE.md");
+    let project_dir = home.join("trash-project");
+    let _ = fs::remove_dir_all(&project_dir);
+    fs::create_dir_all(&project_dir).expect("create project dir");
+
+    let state = create_test_state().expect("create test state");
+    let mut base = prompt("base", "Base rules\n");
+    base.apps.claude = true;
+    PromptService::upsert_prompt(&state, base).expect("save base");
+    let project = PromptProjectService::add_project(&state.db, project_dir.to_str().unwrap(), None)
+        .expect("add project");
+    PromptProjectService::toggle_project_prompt(&state, &project.id, "base", AppType::Claude, true)
+        .expect("link base");
+
+    PromptService::delete_prompt(&state, "base").expect("delete base");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "");
+    assert!(!state.db.get_prompts().unwrap().contains_key("base"));
+    let trash = PromptService::get_trash(&state).expect("list trash");
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].prompt.id, "base");
+    assert_eq!(trash[0].project_links.len(), 1);
+
+    // 恢复后按删除前的状态重写全局文件与项目文件
+    let restored = PromptService::restore_prompt(&state, "base").expect("restore base");
+    assert!(restored.apps.claude);
+    assert_eq!(fs::read_to_string(&path).unwrap(), region("Base rules\n"));
+    assert!(fs::read_to_string(project_dir.join("CLAUDE.md"))
+        .unwrap()
+        .contains("Base rules"));
+    assert!(PromptService::get_trash(&state).unwrap().is_empty());
+
+    // 已有同 id 的提示词时拒绝恢复，回收站记录保留
+    PromptService::delete_prompt(&state, "base").expect("delete base again");
+    PromptService::upsert_prompt(&state, prompt("base", "Replacement\n")).expect("recreate");
+    assert!(PromptService::restore_prompt(&state, "base").is_err());
+    assert_eq!(PromptService::get_trash(&state).unwrap().len(), 1);
+
+    // 未超过保留期的记录不会被自动清理；手动清空后无法再恢复
+    assert_eq!(PromptService::purge_expired_trash(&state.db).unwrap(), 0);
+    assert_eq!(PromptService::purge_trash(&state, None).unwrap(), 1);
+    PromptService::delete_prompt(&state, "base").expect("delete replacement");
+    assert_eq!(PromptService::purge_trash(&state, Some("base")).unwrap(), 1);
+    assert!(PromptService::restore_prompt(&state, "base").is_err());
+}
+
+#[test]
+fn duplicated_prompts_start_disabled_with_a_fresh_id() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let path = home.join(".claude").join("CLAUDE.md");
+
+    let state = create_test_state().expect("create test state");
+    let mut base = prompt("base", "Base rules\n");
+    base.name = "Base Rules".to_string();
+    base.description = Some("shared".to_string());
+    base.apps.claude = true;
+    PromptService::upsert_prompt(&state, base).expect("save base");
+
+    let first = PromptService::duplicate_prompt(&state, "base").expect("duplicate base");
+    let second = PromptService::duplicate_prompt(&state, "base").expect("duplicate again");
+    assert_eq!(first.id, "base-rules-copy");
+    assert_eq!(second.id, "base-rules-copy-2");
+    assert_eq!(first.name, "Base Rules copy");
+    assert_eq!(first.content, "Base rules\n");
+    assert_eq!(first.description.as_deref(), Some("shared"));
+    assert!(!first.apps.claude);
+
+    // 副本未启用，文件只包含原提示词
+    assert_eq!(fs::read_to_string(&path).unwrap(), region("Base rules\n"));
+    let ids: Vec<String> = state.db.get_prompts().unwrap().into_keys().collect();
+    assert_eq!(ids, ["base", "base-rules-copy", "base-rules-copy-2"]);
+
+    assert!(PromptService::duplicate_prompt(&state, "missing").is_err());
+}
+
+#[test]
+fn bulk_replaced_prompts_can_be_rolled_back_to_their_snapshot() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let path = home.join(".claude").join("CLAUDE.md");
+
+    let state = create_test_state().expect("create test state");
+    let mut rules = prompt("rules", "Use old-api everywhere\n");
+    rules.apps.claude = true;
+    PromptService::upsert_prompt(&state, rules).expect("save rules");
+    PromptService::upsert_prompt(&state, prompt("other", "Unrelated\n")).expect("save other");
+
+    BulkReplaceService::bulk_replace(
+        &state,
+        BulkReplaceKind::Prompts,
+        "old-api",
+        "new-api",
+        false,
+        false,
+    )
+    .expect("bulk replace");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region("Use new-api everywhere\n")
+    );
+
+    // 只有被改写的提示词留下快照
+    let history = PromptService::history(&state, "rules").expect("history");
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].reason, PromptRevisionReason::BulkReplace);
+    assert_eq!(history[0].snapshot.content, "Use old-api everywhere\n");
+    assert!(PromptService::history(&state, "other").unwrap().is_empty());
+
+    let restored = PromptService::rollback(&state, "rules", history[0].version).expect("rollback");
+    assert_eq!(restored.content, "Use old-api everywhere\n");
+    assert!(restored.apps.claude);
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region("Use old-api everywhere\n")
+    );
+
+    // 回滚前的内容也有快照，可以再撤销
+    let history = PromptService::history(&state, "rules").unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].reason, PromptRevisionReason::Rollback);
+    assert_eq!(history[0].snapshot.content, "Use new-api everywhere\n");
+
+    assert!(PromptService::rollback(&state, "rules", 99).is_err());
+}
+
+#[test]
+fn oversized_or_malformed_prompts_are_rejected_or_flagged() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let agents_md = home.join(".codex").join("AGENTS.md");
+
+    let state = create_test_state().expect("create test state");
+    let mut base = prompt("base", "Base rules\n");
+    base.apps.codex = true;
+    PromptService::upsert_prompt(&state, base).expect("save base");
+
+    // Codex 只读取 AGENTS.md 的前 32 KiB，超出时拒绝保存，文件保持不变
+    let mut huge = prompt("huge", &"x".repeat(40 * 1024));
+    huge.apps.codex = true;
+    let err = PromptService::upsert_prompt(&state, huge.clone()).expect_err("too large for codex");
+    assert!(err.to_string().contains("codex"));
+    assert!(!state.db.get_prompts().unwrap().contains_key("huge"));
+    assert_eq!(
+        fs::read_to_string(&agents_md).unwrap(),
+        region("Base rules\n")
+    );
+
+    // 未启用时只是普通数据，可以保存
+    huge.apps.codex = false;
+    assert!(PromptService::upsert_prompt(&state, huge)
+        .expect("save disabled")
+        .is_empty());
+
+    // 设置中的覆盖生效：调低警告阈值后返回警告但仍然保存
+    let mut settings = AppSettings::default();
+    settings.prompt_size_limits.insert(
+        "codex".to_string(),
+        PromptSizeLimit {
+            warn_bytes: Some(8),
+            max_bytes: None,
+        },
+    );
+    update_settings(settings).expect("update settings");
+    let mut style = prompt("style", "Style guide\n");
+    style.apps.codex = true;
+    let warnings = PromptService::upsert_prompt(&state, style).expect("save with warning");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, "large");
+    assert_eq!(warnings[0].level, PromptIssueLevel::Warning);
+    update_settings(AppSettings::default()).expect("reset settings");
+
+    // GEMINI.md 中的 frontmatter 只给出警告；托管区域标记直接拒绝
+    let mut fm = prompt("fm", "---\ntitle: rules\n---\nBody\n");
+    fm.apps.gemini = true;
+    let warnings = PromptService::upsert_prompt(&state, fm).expect("save frontmatter prompt");
+    assert_eq!(warnings[0].code, "frontmatter");
+    let marker = prompt("marker", "<!-- cc-switch:prompt:end -->\n");
+    assert!(PromptService::upsert_prompt(&state, marker).is_err());
+}
+
+#[test]
+fn drifted_prompt_files_can_be_merged_imported_or_discarded() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let path = home.join(".claude").join("CLAUDE.md");
+
+    let state = create_test_state().expect("create test state");
+    let mut base = prompt("base", "Base rules\n");
+    base.apps.claude = true;
+    PromptService::upsert_prompt(&state, base).expect("save base");
+    assert!(PromptDriftService::detect(&state).unwrap().is_empty());
+
+    // 只改区域外的内容不算偏离
+    fs::write(&path, format!("{}\n# Notes\n", region("Base rules\n"))).expect("append notes");
+    assert!(PromptDriftService::detect(&state).unwrap().is_empty());
+
+    // merge：写回唯一启用的提示词
+    fs::write(&path, region("Base rules, edited\n")).expect("edit region");
+    let drifts = PromptDriftService::detect(&state).unwrap();
+    assert_eq!(drifts.len(), 1);
+    assert_eq!(drifts[0].app, "claude");
+    assert_eq!(drifts[0].current.as_deref(), Some("Base rules, edited\n"));
+    assert_eq!(drifts[0].expected, "Base rules\n");
+    assert_eq!(drifts[0].enabled_prompt_ids, vec!["base".to_string()]);
+    let merged = PromptDriftService::resolve(&state, AppType::Claude, DriftResolution::Merge)
+        .expect("merge drift");
+    assert_eq!(merged.as_deref(), Some("base"));
+    assert_eq!(
+        state.db.get_prompts().unwrap()["base"].content,
+        "Base rules, edited\n"
+    );
+    assert!(PromptDriftService::detect(&state).unwrap().is_empty());
+
+    // import：另存为新提示词并只对该 app 启用
+    fs::write(&path, region("Experimental rules\n")).expect("edit region again");
+    let imported = PromptDriftService::resolve(&state, AppType::Claude, DriftResolution::Import)
+        .expect("import drift")
+        .expect("imported id");
+    let prompts = state.db.get_prompts().unwrap();
+    assert_eq!(prompts[&imported].content, "Experimental rules\n");
+    assert!(prompts[&imported].apps.claude);
+    assert!(!prompts["base"].apps.claude);
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region("Experimental rules\n")
+    );
+
+    // discard：按数据库重新写入，即使策略为 preserve
+    let mut settings = AppSettings::default();
+    settings
+        .external_edit_policies
+        .insert("claude".to_string(), ExternalEditPolicy::Preserve);
+    update_settings(settings).expect("update settings");
+    fs::write(&path, region("Scratch\n")).expect("scribble");
+    assert_eq!(
+        PromptDriftService::resolve(&state, AppType::Claude, DriftResolution::Discard).unwrap(),
+        None
+    );
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region("Experimental rules\n")
+    );
+    assert!(PromptDriftService::detect(&state).unwrap().is_empty());
+}
+
+#[test]
+fn merging_a_templated_prompt_keeps_the_date_variable() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let path = home.join(".claude").join("CLAUDE.md");
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+    let state = create_test_state().expect("create test state");
+    let mut dated = prompt("dated", "Date: {{today}}\nBase rules\n");
+    dated.apps.claude = true;
+    PromptService::upsert_prompt(&state, dated).expect("save dated");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region(&format!("Date: {today}\nBase rules\n"))
+    );
+
+    // 前一天写入的文件被外部修改后写回：日期行仍保留模板变量
+    fs::write(&path, region("Date: 2000-01-01\nBase rules, edited\n")).expect("edit region");
+    PromptDriftService::resolve(&state, AppType::Claude, DriftResolution::Merge)
+        .expect("merge drift");
+    assert_eq!(
+        state.db.get_prompts().unwrap()["dated"].content,
+        "Date: {{today}}\nBase rules, edited\n"
+    );
+
+    // 日期变化后的刷新按当天日期重写文件，且不算外部修改
+    PromptService::resync_templates_for_new_day(&state);
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region(&format!("Date: {today}\nBase rules, edited\n"))
+    );
+    assert!(PromptDriftService::detect(&state).unwrap().is_empty());
+}
+
+#[test]
+fn prompts_with_includes_cannot_be_merged_from_the_file() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let path = home.join(".claude").join("CLAUDE.md");
+
+    let state = create_test_state().expect("create test state");
+    PromptService::upsert_prompt(&state, prompt("common", "Be concise.\n")).expect("save common");
+    let mut claude = prompt("claude", "{{> common}}\nClaude only.\n");
+    claude.apps.claude = true;
+    PromptService::upsert_prompt(&state, claude).expect("save claude");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        region("Be concise.\nClaude only.\n")
+    );
+
+    // 文件中是展开后的正文，写回会丢掉引用
+    fs::write(&path, region("Be concise.\nClaude only, edited.\n")).expect("edit region");
+    assert!(PromptDriftService::resolve(&state, AppType::Claude, DriftResolution::Merge).is_err());
+    assert_eq!(
+        state.db.get_prompts().unwrap()["claude"].content,
+        "{{> common}}\nClaude only.\n"
+    );
+
+    let imported = PromptDriftService::resolve(&state, AppType::Claude, DriftResolution::Import)
+        .expect("import drift")
+        .expect("imported id");
+    assert_eq!(
+        state.db.get_prompts().unwrap()[&imported].content,
+        "Be concise.\nClaude only, edited.\n"
+    );
+}
+
+#[test]
+fn saving_an_included_prompt_rewrites_the_apps_of_its_includers() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let claude_path = home.join(".claude").join("CLAUDE.md");
+    let codex_path = home.join(".codex").join("AGENTS.md");
+
+    let state = create_test_state().expect("create test state");
+    PromptService::upsert_prompt(&state, prompt("tone", "Be friendly.\n")).expect("save tone");
+    PromptService::upsert_prompt(&state, prompt("common", "{{> tone}}\nBe concise.\n"))
+        .expect("save common");
+    let mut claude = prompt("claude", "{{> common}}\nClaude only.\n");
+    claude.apps.claude = true;
+    PromptService::upsert_prompt(&state, claude).expect("save claude");
+    let mut codex = prompt("codex", "Codex only.\n");
+    codex.apps.codex = true;
+    PromptService::upsert_prompt(&state, codex).expect("save codex");
+    let codex_before = fs::read_to_string(&codex_path).expect("read AGENTS.md");
+
+    // 嵌套引用的片段改动后，启用了引用方的 app 文件随之更新
+    PromptService::upsert_prompt(&state, prompt("tone", "Be warm.\n")).expect("edit tone");
+    assert_eq!(
+        fs::read_to_string(&claude_path).unwrap(),
+        region("Be warm.\nBe concise.\nClaude only.\n")
+    );
+    assert_eq!(fs::read_to_string(&codex_path).unwrap(), codex_before);
+
+    // 仍被引用的片段不能删除
+    assert!(PromptService::delete_prompt(&state, "tone").is_err());
+    assert!(state.db.get_prompts().unwrap().contains_key("tone"));
+    PromptService::upsert_prompt(&state, prompt("common", "Be concise.\n")).expect("drop include");
+    PromptService::delete_prompt(&state, "tone").expect("delete unused tone");
+    assert_eq!(
+        fs::read_to_string(&claude_path).unwrap(),
+        region("Be concise.\nClaude only.\n")
+    );
+}
+
+#[test]
+fn prompt_bundles_round_trip_through_zip_and_json() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let export_dir = home.join("exports");
+
+    let state = create_test_state().expect("create test state");
+    let mut base = prompt("base", "Base rules\n\n- keep diffs small\n");
+    base.description = Some("团队约定".to_string());
+    base.apps.claude = true;
+    PromptService::upsert_prompt(&state, base).expect("save base");
+    PromptService::upsert_prompt(&state, prompt("team/style", "Style guide\n"))
+        .expect("save style");
+
+    let zip_path = export_dir.join("prompts.zip");
+    let json_path = export_dir.join("prompts.json");
+    let exported =
+        PromptBundleService::export_to_file(&state, &zip_path, None).expect("export zip");
+    assert_eq!(exported, 2);
+    PromptBundleService::export_to_file(&state, &json_path, Some(&["base".to_string()]))
+        .expect("export json");
+
+    // 清空本地后导入，模拟另一台机器：内容与元数据保留，启用状态不随导出包生效
+    for id in ["base", "team/style"] {
+        PromptService::delete_prompt(&state, id).expect("delete prompt");
+    }
+    let result = PromptBundleService::import_from_file(&state, &zip_path, DuplicateStrategy::Skip)
+        .expect("import zip");
+    assert_eq!(result.imported, vec!["base", "team/style"]);
+    let prompts = state.db.get_prompts().expect("load prompts");
+    assert_eq!(
+        prompts["base"].content,
+        "Base rules\n\n- keep diffs small\n"
+    );
+    assert_eq!(prompts["base"].description.as_deref(), Some("团队约定"));
+    assert!(!prompts["base"].apps.claude);
+    assert_eq!(prompts["team/style"].content, "Style guide\n");
+
+    // 重复导入按策略处理
+    let result =
+        PromptBundleService::import_from_file(&state, &json_path, DuplicateStrategy::Rename)
+            .expect("import json");
+    assert_eq!(result.renamed.len(), 1);
+    assert_eq!(result.renamed[0].to, "base-2");
+    let result = PromptBundleService::import_from_file(&state, &zip_path, DuplicateStrategy::Skip)
+        .expect("import zip again");
+    assert_eq!(result.skipped, vec!["base", "team/style"]);
+}
+
+#[test]
+fn scheduled_rules_swap_the_enabled_prompt_and_catch_up_once() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let state = create_test_state().expect("create test state");
+    PromptService::upsert_prompt(&state, prompt("work", "Work rules\n")).expect("save work");
+    PromptService::upsert_prompt(&state, prompt("personal", "Personal\n")).expect("save personal");
+
+    let rule = |prompt_id: &str, at: &str| PromptSchedule {
+        id: String::new(),
+        app: "claude".to_string(),
+        prompt_id: prompt_id.to_string(),
+        schedule: Schedule {
+            time_zone: "UTC".to_string(),
+            at: at.to_string(),
+            days: Vec::new(),
+        },
+        enabled: true,
+        last_run_at: None,
+        last_error: None,
+        created_at: 0,
+        updated_at: 0,
+    };
+    let work = PromptScheduleService::save(&state, rule("work", "09:00")).expect("save work rule");
+    PromptScheduleService::save(&state, rule("personal", "18:00")).expect("save personal rule");
+    assert!(!work.id.is_empty());
+
+    let missing = PromptScheduleService::save(&state, rule("nope", "09:00"));
+    assert!(missing.is_err(), "rules must point to an existing prompt");
+
+    let enabled = |id: &str| state.db.get_prompts().unwrap()[id].apps.claude;
+    let day = (chrono::Utc::now() + chrono::Duration::days(2)).date_naive();
+    let at = |h: u32| day.and_hms_opt(h, 0, 0).unwrap().and_utc();
+
+    // 两天没有运行：10:00 时最晚到期的是当天 09:00 的规则
+    let applied = PromptScheduleService::run_due(&state, at(10)).expect("run at 10:00");
+    assert_eq!(applied.len(), 1);
+    assert_eq!(applied[0].prompt_id, "work");
+    assert!(enabled("work") && !enabled("personal"));
+
+    let applied = PromptScheduleService::run_due(&state, at(19)).expect("run at 19:00");
+    assert_eq!(applied[0].prompt_id, "personal");
+    assert!(!enabled("work") && enabled("personal"));
+
+    // 已处理过的执行不会重复
+    assert!(PromptScheduleService::run_due(&state, at(19))
+        .expect("run again")
+        .is_empty());
+}
+
+#[test]
+fn enablement_matrix_round_trips_and_aligns_listed_apps_only() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    ensure_test_home();
+
+    let state = create_test_state().expect("create test state");
+    PromptService::upsert_prompt(&state, prompt("base", "Base rules\n")).expect("save base");
+    PromptService::upsert_prompt(&state, prompt("style", "Style guide\n")).expect("save style");
+    PromptService::toggle_prompt_app(&state, "base", AppType::Claude, true, false)
+        .expect("enable base");
+    PromptService::toggle_prompt_app(&state, "base", AppType::Gemini, true, false)
+        .expect("enable base for gemini");
+    let reviewer = AgentDefinition {
+        id: "reviewer".to_string(),
+        name: "Reviewer".to_string(),
+        content: "Review carefully\n".to_string(),
+        apps: AgentApps {
+            claude: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    AgentsService::upsert(&state, reviewer, false).expect("save agent");
+
+    let yaml = EnablementService::export_yaml(&state).expect("export matrix");
+    let exported = EnablementService::parse_yaml(&yaml).expect("parse exported matrix");
+    assert_eq!(exported.prompts["claude"], vec!["base".to_string()]);
+    assert_eq!(exported.agents["claude"], vec!["reviewer".to_string()]);
+    assert!(!exported.prompts.contains_key("codex"));
+
+    let matrix = EnablementService::parse_yaml(
+        "prompts:\n  claude: [style]\n  codex: [base, ghost]\nagents:\n  claude: []\n",
+    )
+    .expect("parse edited matrix");
+    let result = EnablementService::apply(&state, &matrix).expect("apply matrix");
+    assert_eq!(result.missing, vec!["prompt codex ghost".to_string()]);
+
+    let prompts = state.db.get_prompts().unwrap();
+    assert!(!prompts["base"].apps.claude && prompts["style"].apps.claude);
+    assert!(prompts["base"].apps.codex);
+    // 未出现在文件中的 app 保持原状
+    assert!(prompts["base"].apps.gemini);
+    let agents = state.db.get_all_agents().unwrap();
+    assert!(!agents["reviewer"].apps.claude);
+
+    // 再次应用同一份矩阵不产生变更
+    let again = EnablementService::apply(&state, &matrix).expect("apply again");
+    assert!(again.changes.is_empty());
+}
+
+#[test]
+fn prompt_file_preview_matches_sync_and_simulates_toggles() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let state = create_test_state().expect("create test state");
+    PromptService::upsert_prompt(&state, prompt("base", "Base rules\n")).expect("save base");
+    PromptService::upsert_prompt(&state, prompt("style", "Style guide\n")).expect("save style");
+    PromptService::toggle_prompt_app(&state, "base", AppType::Claude, true, false)
+        .expect("enable base");
+
+    let path = home.join(".claude").join("CLAUDE.md");
+    let on_disk = fs::read_to_string(&path).expect("read synced file");
+    let current =
+        PromptService::preview_app_file(&state, &AppType::Claude, None).expect("preview current");
+    assert_eq!(current.content, on_disk);
+    assert!(!current.changed);
+
+    let ids = vec!["base".to_string(), "style".to_string()];
+    let toggled = PromptService::preview_app_file(&state, &AppType::Claude, Some(&ids))
+        .expect("preview toggled");
+    assert!(toggled.changed);
+    assert!(toggled.content.contains("Base rules") && toggled.content.contains("Style guide"));
+    assert_eq!(toggled.rendered, "Base rules\n\n---\n\nStyle guide\n");
+    // 预览不写入文件，也不改变启用状态
+    assert_eq!(fs::read_to_string(&path).unwrap(), on_disk);
+    assert!(!state.db.get_prompts().unwrap()["style"].apps.claude);
+}
+
+#[test]
+fn sync_targets_list_every_prompt_app_with_the_managed_region() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let state = create_test_state().expect("create test state");
+    PromptService::upsert_prompt(&state, prompt("base", "Base rules\n")).expect("save base");
+    PromptService::toggle_prompt_app(&state, "base", AppType::Claude, true, false)
+        .expect("enable base");
+
+    let targets = SyncTargetsService::get_targets(&state, SyncTargetKind::Prompt, "base")
+        .expect("prompt targets");
+    let apps: Vec<_> = targets.iter().map(|t| t.app.as_str()).collect();
+    assert_eq!(
+        apps,
+        vec!["claude", "codex", "gemini", "opencode", "openclaw"]
+    );
+    assert!(targets
+        .iter()
+        .all(|t| t.mode == SyncTargetMode::ManagedBlock && t.marker_start.is_some()));
+
+    let claude = &targets[0];
+    assert!(claude.enabled && claude.exists && !claude.paused);
+    assert_eq!(
+        claude.path,
+        home.join(".claude").join("CLAUDE.md").to_string_lossy()
+    );
+    let codex = &targets[1];
+    assert!(!codex.enabled && !codex.exists);
+
+    assert!(SyncTargetsService::get_targets(&state, SyncTargetKind::Prompt, "missing").is_err());
+}
+
+#[test]
+fn agents_md_export_expands_includes_and_template_variables() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let project = home.join("agents-md-project");
+    fs::create_dir_all(&project).expect("create project dir");
+
+    let state = create_test_state().expect("create test state");
+    PromptService::upsert_prompt(&state, prompt("common", "Be concise.\n")).expect("save common");
+    let mut claude = prompt("claude", "{{> common}}\nWritten for {{app}}.\n");
+    claude.apps.claude = true;
+    PromptService::upsert_prompt(&state, claude).expect("save claude");
+    AgentsService::upsert(
+        &state,
+        AgentDefinition {
+            id: "reviewer".to_string(),
+            name: "Reviewer".to_string(),
+            content: "Review for {{app}}.\n".to_string(),
+            apps: AgentApps {
+                claude: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        false,
+    )
+    .expect("save agent");
+
+    let path = AgentsMdService::export_to_dir(&state, &project, Some(&AppType::Claude))
+        .expect("export AGENTS.md");
+    let exported = fs::read_to_string(path).expect("read AGENTS.md");
+    assert!(
+        exported.contains("Be concise.\nWritten for claude.\n"),
+        "{exported}"
+    );
+    assert!(exported.contains("Review for claude.\n"), "{exported}");
+    assert!(!exported.contains("{{"), "{exported}");
+}
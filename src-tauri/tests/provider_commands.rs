@@ -80,6 +80,7 @@ command = "say"
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            origin: None,
         },
     );
 
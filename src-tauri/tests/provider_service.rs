@@ -1,8 +1,10 @@
 use serde_json::json;
 
 use cc_switch_lib::{
-    get_claude_settings_path, read_json_file, write_codex_live_atomic, AppError, AppType, McpApps,
-    McpServer, MultiAppConfig, Provider, ProviderMeta, ProviderService,
+    get_claude_settings_path, read_json_file, update_settings, write_codex_live_atomic,
+    ApiAccessService, ApiScope, AppError, AppSettings, AppType, McpApps, McpServer, MultiAppConfig,
+    Provider, ProviderAuditOrigin, ProviderMeta, ProviderProfile, ProviderService, ProviderTrust,
+    UniversalProvider,
 };
 
 #[path = "support.rs"]
@@ -94,6 +96,7 @@ command = "say"
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            origin: None,
         },
     );
 
@@ -439,14 +442,72 @@ fn provider_service_switch_missing_provider_returns_error() {
     let err = ProviderService::switch(&state, AppType::Claude, "missing")
         .expect_err("switching missing provider should fail");
     match err {
-        AppError::Message(msg) => {
-            assert!(
-                msg.contains("不存在") || msg.contains("not found"),
-                "expected provider not found message, got {msg}"
+        AppError::Localized { key, .. } => assert_eq!(key, "provider.not_found"),
+        other => panic!("expected provider.not_found error, got {other:?}"),
+    }
+}
+
+#[test]
+fn switch_with_malformed_live_config_keeps_current_when_recovery_disabled() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "old-provider".to_string();
+        for (id, token) in [("old-provider", "old-key"), ("new-provider", "new-key")] {
+            manager.providers.insert(
+                id.to_string(),
+                Provider::with_id(
+                    id.to_string(),
+                    id.to_string(),
+                    json!({ "env": { "ANTHROPIC_AUTH_TOKEN": token } }),
+                    None,
+                ),
             );
         }
-        other => panic!("expected Message error for provider not found, got {other:?}"),
     }
+    let state = create_test_state_with_config(&config).expect("create test state");
+
+    update_settings(AppSettings {
+        auto_recover_malformed_config: false,
+        ..Default::default()
+    })
+    .expect("disable auto recovery");
+
+    let settings_path = get_claude_settings_path();
+    std::fs::create_dir_all(settings_path.parent().expect("settings dir"))
+        .expect("create claude settings dir");
+    std::fs::write(&settings_path, "{ not json").expect("seed malformed live config");
+
+    let err = ProviderService::switch(&state, AppType::Claude, "new-provider")
+        .expect_err("malformed live config aborts the switch");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "live_config.malformed",
+            ..
+        }
+    ));
+
+    let current_id = state
+        .db
+        .get_current_provider(AppType::Claude.as_str())
+        .expect("get current provider");
+    assert_eq!(
+        current_id.as_deref(),
+        Some("old-provider"),
+        "current provider must not change when the switch fails"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&settings_path).expect("read live config"),
+        "{ not json",
+        "malformed live file is left untouched"
+    );
 }
 
 #[test]
@@ -657,3 +718,1269 @@ fn provider_service_delete_current_provider_returns_error() {
         other => panic!("expected Config/Message error, got {other:?}"),
     }
 }
+
+#[test]
+fn reference_providers_are_read_only_until_duplicated() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    let template = Provider::with_id(
+        "team-gateway".to_string(),
+        "Team Gateway".to_string(),
+        json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "team-key",
+                "ANTHROPIC_BASE_URL": "https://gateway.example.com"
+            }
+        }),
+        None,
+    );
+
+    let written = ProviderService::sync_references(
+        &app_state,
+        AppType::Claude,
+        "https://git.example.com/team/templates",
+        vec![template.clone()],
+    )
+    .expect("sync references");
+    assert_eq!(written, vec!["team-gateway".to_string()]);
+
+    let stored = app_state
+        .db
+        .get_provider_by_id("team-gateway", "claude")
+        .expect("read provider")
+        .expect("reference stored");
+    assert!(stored.is_reference());
+
+    // 其他模板仓库的同 id 供应商不会覆盖已有引用
+    let mut other = template.clone();
+    other.name = "Other Gateway".to_string();
+    let written = ProviderService::sync_references(
+        &app_state,
+        AppType::Claude,
+        "https://git.example.com/other/templates",
+        vec![other],
+    )
+    .expect("sync other source");
+    assert!(written.is_empty());
+    let kept = app_state
+        .db
+        .get_provider_by_id("team-gateway", "claude")
+        .expect("read provider")
+        .expect("reference kept");
+    assert_eq!(kept.name, "Team Gateway");
+
+    let mut edited = stored.clone();
+    edited.name = "Edited".to_string();
+    if let Some(meta) = edited.meta.as_mut() {
+        meta.origin = None;
+    }
+    let err = ProviderService::update(&app_state, AppType::Claude, edited)
+        .expect_err("reference providers cannot be edited");
+    assert!(
+        matches!(
+            err,
+            AppError::Localized {
+                key: "provider.read_only",
+                ..
+            }
+        ),
+        "unexpected error: {err:?}"
+    );
+
+    let err = ProviderService::add(&app_state, AppType::Claude, {
+        let mut forged = stored.clone();
+        forged.id = "forged".to_string();
+        forged
+    })
+    .expect_err("reference origin cannot be set from outside template sync");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "provider.reference_origin_forbidden",
+            ..
+        }
+    ));
+
+    let copy = ProviderService::duplicate(&app_state, AppType::Claude, "team-gateway")
+        .expect("duplicate reference");
+    assert_eq!(copy.id, "team-gateway-copy");
+    assert!(!copy.is_reference());
+
+    let mut edited_copy = copy.clone();
+    edited_copy.name = "My Gateway".to_string();
+    ProviderService::update(&app_state, AppType::Claude, edited_copy)
+        .expect("duplicated provider is editable");
+}
+
+#[test]
+fn duplicated_provider_copies_settings_meta_and_endpoints() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    let mut provider = Provider::with_id(
+        "relay".to_string(),
+        "Relay".to_string(),
+        json!({ "env": {
+            "ANTHROPIC_AUTH_TOKEN": "relay-key",
+            "ANTHROPIC_MODEL": "claude-sonnet-4-5"
+        } }),
+        None,
+    );
+    provider.meta = Some(ProviderMeta {
+        api_format: Some("anthropic".to_string()),
+        ..Default::default()
+    });
+    ProviderService::add(&app_state, AppType::Claude, provider).expect("add provider");
+    app_state
+        .db
+        .add_custom_endpoint("claude", "relay", "https://backup.example.com")
+        .expect("add endpoint");
+    app_state
+        .db
+        .record_endpoint_benchmark("claude", "relay", "https://backup.example.com", None, 1)
+        .expect("record benchmark");
+
+    let copy = ProviderService::duplicate(&app_state, AppType::Claude, "relay").expect("duplicate");
+    assert_eq!(copy.id, "relay-copy");
+    assert_eq!(copy.name, "Relay copy");
+
+    let stored = app_state
+        .db
+        .get_all_providers("claude")
+        .expect("read providers")
+        .shift_remove("relay-copy")
+        .expect("copy exists");
+    assert_eq!(
+        stored.settings_config["env"]["ANTHROPIC_MODEL"],
+        "claude-sonnet-4-5"
+    );
+    let meta = stored.meta.expect("meta copied");
+    assert_eq!(meta.api_format.as_deref(), Some("anthropic"));
+    let endpoint = meta
+        .custom_endpoints
+        .get("https://backup.example.com")
+        .expect("endpoint copied");
+    assert_eq!(endpoint.consecutive_failures, 0);
+    assert!(endpoint.last_benchmarked.is_none());
+}
+
+#[test]
+fn api_tokens_are_limited_to_their_scopes() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    for (id, key) in [("relay-a", "sk-aaaa"), ("relay-b", "sk-bbbb")] {
+        let provider = Provider::with_id(
+            id.to_string(),
+            id.to_string(),
+            json!({
+                "env": {
+                    "ANTHROPIC_AUTH_TOKEN": key,
+                    "ANTHROPIC_BASE_URL": "https://relay.example.com"
+                }
+            }),
+            None,
+        );
+        ProviderService::add(&app_state, AppType::Claude, provider).expect("add provider");
+    }
+
+    // 自动化脚本：可以列出和切换，但看不到密钥、不能删除
+    let issued = ApiAccessService::create_token(
+        &app_state.db,
+        "switcher",
+        vec![ApiScope::Read, ApiScope::Switch],
+    )
+    .expect("create token");
+    assert!(issued.secret.starts_with("ccs_"));
+    let caller =
+        ApiAccessService::authenticate(&app_state.db, Some(&issued.secret)).expect("authenticate");
+
+    let providers =
+        ApiAccessService::list_providers(&app_state, &caller, "claude").expect("list providers");
+    assert_eq!(providers.len(), 2);
+    for provider in &providers {
+        assert_eq!(
+            provider.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+            "***"
+        );
+    }
+
+    ApiAccessService::switch_provider(&app_state, &caller, "claude", "relay-b")
+        .expect("switch with switch scope");
+    assert_eq!(
+        app_state
+            .db
+            .get_current_provider("claude")
+            .unwrap()
+            .as_deref(),
+        Some("relay-b")
+    );
+
+    let err = ApiAccessService::delete_provider(&app_state, &caller, "claude", "relay-a")
+        .expect_err("delete needs mutateConfig");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "api.forbidden",
+            ..
+        }
+    ));
+    assert!(app_state
+        .db
+        .get_provider_by_id("relay-a", "claude")
+        .unwrap()
+        .is_some());
+
+    // 吊销后令牌立即失效
+    assert!(ApiAccessService::revoke_token(&app_state.db, &issued.token.id).unwrap());
+    for secret in [Some(issued.secret.as_str()), None] {
+        let err = ApiAccessService::authenticate(&app_state.db, secret)
+            .expect_err("revoked or missing token");
+        assert!(matches!(
+            err,
+            AppError::Localized {
+                key: "api.unauthorized",
+                ..
+            }
+        ));
+    }
+}
+
+#[test]
+fn maintenance_window_can_be_set_and_cleared() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    ProviderService::add(
+        &app_state,
+        AppType::Claude,
+        Provider::with_id(
+            "backup".to_string(),
+            "Backup".to_string(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "backup-key" } }),
+            None,
+        ),
+    )
+    .expect("add provider");
+
+    let now = chrono::Utc::now().timestamp();
+    let err = ProviderService::set_maintenance(&app_state, AppType::Claude, "backup", now - 60)
+        .expect_err("window in the past is rejected");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "provider.maintenance_in_past",
+            ..
+        }
+    ));
+
+    let updated =
+        ProviderService::set_maintenance(&app_state, AppType::Claude, "backup", now + 3600)
+            .expect("set maintenance");
+    assert!(updated.in_maintenance(now));
+    assert!(!updated.in_maintenance(now + 7200));
+    let stored = app_state
+        .db
+        .get_provider_by_id("backup", "claude")
+        .expect("read provider")
+        .expect("provider exists");
+    assert_eq!(
+        stored.meta.and_then(|meta| meta.maintenance_until),
+        Some(now + 3600)
+    );
+
+    assert!(
+        ProviderService::clear_maintenance(&app_state, AppType::Claude, "backup")
+            .expect("clear maintenance")
+    );
+    assert!(
+        !ProviderService::clear_maintenance(&app_state, AppType::Claude, "backup")
+            .expect("clear again")
+    );
+}
+
+#[test]
+fn unverified_provider_needs_confirmation_before_failover() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    ProviderService::add(
+        &app_state,
+        AppType::Claude,
+        Provider::with_id(
+            "relay".to_string(),
+            "Relay".to_string(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "relay-key" } }),
+            None,
+        ),
+    )
+    .expect("add provider");
+
+    let err = ProviderService::add_to_failover_queue(&app_state, AppType::Claude, "relay", false)
+        .expect_err("unverified provider needs confirmation");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "provider.untrusted_needs_confirmation",
+            ..
+        }
+    ));
+    assert!(app_state
+        .db
+        .get_failover_queue("claude")
+        .unwrap()
+        .is_empty());
+
+    ProviderService::add_to_failover_queue(&app_state, AppType::Claude, "relay", true)
+        .expect("confirmed provider is added");
+    assert_eq!(app_state.db.get_failover_queue("claude").unwrap().len(), 1);
+    let stored = app_state
+        .db
+        .get_provider_by_id("relay", "claude")
+        .expect("read provider")
+        .expect("provider exists");
+    assert!(stored.allowed_in_automation());
+
+    // 确认过的供应商再次加入不需要确认
+    app_state
+        .db
+        .remove_from_failover_queue("claude", "relay")
+        .expect("remove from queue");
+    ProviderService::add_to_failover_queue(&app_state, AppType::Claude, "relay", false)
+        .expect("consent is remembered");
+
+    // 降为未验证会撤销允许并移出队列
+    ProviderService::set_trust(
+        &app_state,
+        AppType::Claude,
+        "relay",
+        ProviderTrust::VerifiedRelay,
+    )
+    .expect("mark verified");
+    let downgraded = ProviderService::set_trust(
+        &app_state,
+        AppType::Claude,
+        "relay",
+        ProviderTrust::Unverified,
+    )
+    .expect("mark unverified");
+    assert!(!downgraded.allowed_in_automation());
+    assert!(app_state
+        .db
+        .get_failover_queue("claude")
+        .unwrap()
+        .is_empty());
+
+    // 删除当前供应商时不会自动切换到未获允许的供应商
+    ProviderService::add(
+        &app_state,
+        AppType::Claude,
+        Provider::with_id(
+            "spare".to_string(),
+            "Spare".to_string(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "spare-key" } }),
+            None,
+        ),
+    )
+    .expect("add spare");
+    ProviderService::switch(&app_state, AppType::Claude, "relay").expect("switch to relay");
+    assert!(ProviderService::delete(&app_state, AppType::Claude, "relay").is_err());
+    ProviderService::set_automation_allowed(&app_state, AppType::Claude, "spare", true)
+        .expect("allow spare");
+    ProviderService::delete(&app_state, AppType::Claude, "relay").expect("delete relay");
+    assert_eq!(
+        ProviderService::current(&app_state, AppType::Claude).expect("current"),
+        "spare"
+    );
+}
+
+#[test]
+fn exported_providers_keep_secrets_only_with_passphrase() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    ProviderService::add(
+        &app_state,
+        AppType::Claude,
+        Provider::with_id(
+            "relay".to_string(),
+            "Relay".to_string(),
+            json!({ "env": {
+                "ANTHROPIC_AUTH_TOKEN": "relay-key",
+                "ANTHROPIC_BASE_URL": "https://relay.example.com"
+            } }),
+            None,
+        ),
+    )
+    .expect("add provider");
+
+    // 不提供口令时密钥被清空，导入后需要补录
+    let mut stripped = ProviderService::export_providers(&app_state, AppType::Claude, None, None)
+        .expect("export without passphrase");
+    // 导出文件中声明的信任级别与官方分类不生效
+    let exported = &mut stripped.providers[0].provider;
+    exported.category = Some("official".to_string());
+    exported.meta.get_or_insert_with(Default::default).trust = Some(ProviderTrust::Official);
+    assert!(!serde_json::to_string(&stripped)
+        .unwrap()
+        .contains("relay-key"));
+    let result = ProviderService::import_providers(&app_state, stripped, None)
+        .expect("import stripped export");
+    assert_eq!(result.imported, vec!["relay-2".to_string()]);
+    assert_eq!(result.missing_secrets.len(), 1);
+    assert_eq!(result.missing_secrets[0].provider_id, "relay-2");
+    assert!(result.failed.is_empty());
+    let imported = app_state
+        .db
+        .get_provider_by_id("relay-2", "claude")
+        .expect("read provider")
+        .expect("provider exists");
+    assert_eq!(imported.trust_level(), ProviderTrust::Unverified);
+
+    // 提供口令时密钥加密保存，口令错误不会导入任何供应商
+    let encrypted =
+        ProviderService::export_providers(&app_state, AppType::Claude, None, Some("s3cret"))
+            .expect("export with passphrase");
+    assert!(!serde_json::to_string(&encrypted)
+        .unwrap()
+        .contains("relay-key"));
+    let before = app_state.db.get_all_providers("claude").unwrap().len();
+    assert!(ProviderService::import_providers(&app_state, encrypted.clone(), None).is_err());
+    assert!(
+        ProviderService::import_providers(&app_state, encrypted.clone(), Some("wrong")).is_err()
+    );
+    assert_eq!(
+        app_state.db.get_all_providers("claude").unwrap().len(),
+        before
+    );
+
+    let result = ProviderService::import_providers(&app_state, encrypted, Some("s3cret"))
+        .expect("import with passphrase");
+    let restored = app_state
+        .db
+        .get_provider_by_id(&result.imported[0], "claude")
+        .expect("read provider")
+        .expect("provider exists");
+    assert_eq!(
+        restored.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+        "relay-key"
+    );
+}
+
+#[test]
+fn archived_provider_leaves_failover_queue_and_cannot_be_switched_to() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    for id in ["main", "stale"] {
+        ProviderService::add(
+            &app_state,
+            AppType::Claude,
+            Provider::with_id(
+                id.to_string(),
+                id.to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": format!("{id}-key") } }),
+                None,
+            ),
+        )
+        .expect("add provider");
+    }
+    ProviderService::switch(&app_state, AppType::Claude, "main").expect("switch to main");
+    ProviderService::add_to_failover_queue(&app_state, AppType::Claude, "stale", true)
+        .expect("add to failover queue");
+
+    let err = ProviderService::set_archived(&app_state, AppType::Claude, "main", true)
+        .expect_err("current provider cannot be archived");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "provider.archive_current",
+            ..
+        }
+    ));
+
+    let archived = ProviderService::set_archived(&app_state, AppType::Claude, "stale", true)
+        .expect("archive provider");
+    assert!(archived.archived);
+    assert!(app_state
+        .db
+        .get_failover_queue("claude")
+        .unwrap()
+        .is_empty());
+    assert!(ProviderService::switch(&app_state, AppType::Claude, "stale").is_err());
+    assert!(
+        ProviderService::add_to_failover_queue(&app_state, AppType::Claude, "stale", true).is_err()
+    );
+
+    // 编辑已归档的供应商不会改变归档状态，配置保留
+    let mut edited = archived.clone();
+    edited.archived = false;
+    edited.name = "Stale relay".to_string();
+    ProviderService::update(&app_state, AppType::Claude, edited).expect("edit archived");
+    let stored = app_state
+        .db
+        .get_provider_by_id("stale", "claude")
+        .unwrap()
+        .expect("provider kept");
+    assert!(stored.archived);
+    assert_eq!(
+        stored.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+        "stale-key"
+    );
+
+    ProviderService::set_archived(&app_state, AppType::Claude, "stale", false)
+        .expect("unarchive provider");
+    ProviderService::switch(&app_state, AppType::Claude, "stale").expect("switch after unarchive");
+}
+
+#[test]
+fn bulk_update_patches_selected_providers_in_one_step() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    for id in ["relay-a", "relay-b", "relay-c"] {
+        ProviderService::add(
+            &app_state,
+            AppType::Claude,
+            Provider::with_id(
+                id.to_string(),
+                id.to_string(),
+                json!({ "env": {
+                    "ANTHROPIC_AUTH_TOKEN": format!("{id}-key"),
+                    "ANTHROPIC_MODEL": "old-model",
+                    "ANTHROPIC_SMALL_FAST_MODEL": "old-haiku"
+                } }),
+                None,
+            ),
+        )
+        .expect("add provider");
+    }
+    ProviderService::switch(&app_state, AppType::Claude, "relay-a").expect("switch to relay-a");
+
+    let ids = vec!["relay-a".to_string(), "relay-b".to_string()];
+    let result = ProviderService::bulk_update(
+        &app_state,
+        AppType::Claude,
+        &ids,
+        &json!({ "env": { "ANTHROPIC_MODEL": "new-model", "ANTHROPIC_SMALL_FAST_MODEL": null } }),
+    )
+    .expect("bulk update");
+    assert_eq!(result.updated, ids);
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+
+    for id in ["relay-a", "relay-b"] {
+        let stored = app_state
+            .db
+            .get_provider_by_id(id, "claude")
+            .unwrap()
+            .expect("provider kept");
+        assert_eq!(
+            stored.settings_config["env"]["ANTHROPIC_MODEL"],
+            "new-model"
+        );
+        assert!(stored.settings_config["env"]
+            .get("ANTHROPIC_SMALL_FAST_MODEL")
+            .is_none());
+        assert_eq!(
+            stored.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+            format!("{id}-key")
+        );
+    }
+    let untouched = app_state
+        .db
+        .get_provider_by_id("relay-c", "claude")
+        .unwrap()
+        .expect("provider kept");
+    assert_eq!(
+        untouched.settings_config["env"]["ANTHROPIC_MODEL"],
+        "old-model"
+    );
+
+    // 当前供应商的 live 配置同步更新
+    let live: serde_json::Value =
+        read_json_file(&get_claude_settings_path()).expect("read live settings");
+    assert_eq!(live["env"]["ANTHROPIC_MODEL"], "new-model");
+
+    // 任一供应商校验失败时全部不写入
+    let err = ProviderService::bulk_update(
+        &app_state,
+        AppType::Claude,
+        &["relay-c".to_string(), "missing".to_string()],
+        &json!({ "env": { "ANTHROPIC_MODEL": "newer-model" } }),
+    )
+    .expect_err("unknown provider rejects the whole batch");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "provider.not_found",
+            ..
+        }
+    ));
+    let untouched = app_state
+        .db
+        .get_provider_by_id("relay-c", "claude")
+        .unwrap()
+        .expect("provider kept");
+    assert_eq!(
+        untouched.settings_config["env"]["ANTHROPIC_MODEL"],
+        "old-model"
+    );
+
+    assert!(ProviderService::bulk_update(&app_state, AppType::Claude, &ids, &json!([])).is_err());
+}
+
+#[test]
+fn provider_attachments_are_copied_listed_and_removed_with_provider() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    for id in ["current", "relay"] {
+        ProviderService::add(
+            &app_state,
+            AppType::Claude,
+            Provider::with_id(
+                id.to_string(),
+                id.to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": format!("{id}-key") } }),
+                None,
+            ),
+        )
+        .expect("add provider");
+    }
+    ProviderService::switch(&app_state, AppType::Claude, "current").expect("switch");
+
+    let source = home.join("invoice-2026-10.pdf");
+    std::fs::write(&source, b"%PDF-1.7 invoice").expect("write source file");
+    let attachment = ProviderService::add_attachment(&app_state, AppType::Claude, "relay", &source)
+        .expect("add attachment");
+    assert_eq!(attachment.file_name, "invoice-2026-10.pdf");
+    assert_eq!(attachment.size, 16);
+    assert!(
+        ProviderService::add_attachment(&app_state, AppType::Claude, "missing", &source).is_err()
+    );
+
+    let listed = ProviderService::list_attachments(&app_state, AppType::Claude, "relay")
+        .expect("list attachments");
+    assert_eq!(listed.len(), 1);
+    let path =
+        ProviderService::attachment_path(&app_state, AppType::Claude, "relay", &attachment.id)
+            .expect("attachment path");
+    assert_eq!(
+        std::fs::read(&path).expect("read copy"),
+        b"%PDF-1.7 invoice"
+    );
+
+    // 删除的供应商在回收站中保留附件，永久删除时一并删除
+    ProviderService::delete(&app_state, AppType::Claude, "relay").expect("delete provider");
+    assert!(
+        path.exists(),
+        "attachments kept while the provider is in the trash"
+    );
+    ProviderService::purge_trash(&app_state, AppType::Claude, Some("relay")).expect("purge");
+    assert!(!path.exists(), "attachment files removed with provider");
+    assert!(
+        ProviderService::list_attachments(&app_state, AppType::Claude, "relay")
+            .expect("list attachments")
+            .is_empty()
+    );
+}
+
+#[test]
+fn deleted_providers_go_to_trash_and_current_falls_back() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    for id in ["main", "spare", "backup"] {
+        ProviderService::add(
+            &app_state,
+            AppType::Claude,
+            Provider::with_id(
+                id.to_string(),
+                id.to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": format!("{id}-key") } }),
+                None,
+            ),
+        )
+        .expect("add provider");
+    }
+    ProviderService::switch(&app_state, AppType::Claude, "main").expect("switch to main");
+    ProviderService::add_to_failover_queue(&app_state, AppType::Claude, "backup", true)
+        .expect("add to failover queue");
+
+    // 删除当前供应商：优先切换到故障转移队列中的供应商
+    ProviderService::delete(&app_state, AppType::Claude, "main").expect("delete current");
+    assert_eq!(
+        ProviderService::current(&app_state, AppType::Claude).expect("current"),
+        "backup"
+    );
+    let live: serde_json::Value =
+        read_json_file(&get_claude_settings_path()).expect("read live settings");
+    assert_eq!(live["env"]["ANTHROPIC_AUTH_TOKEN"], "backup-key");
+    assert!(!app_state
+        .db
+        .get_all_providers("claude")
+        .unwrap()
+        .contains_key("main"));
+    let trash = ProviderService::get_trash(&app_state, AppType::Claude).expect("trash");
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].provider.id, "main");
+
+    let restored =
+        ProviderService::restore(&app_state, AppType::Claude, "main").expect("restore provider");
+    assert_eq!(
+        restored.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+        "main-key"
+    );
+    assert_eq!(
+        ProviderService::current(&app_state, AppType::Claude).expect("current"),
+        "backup"
+    );
+    assert!(ProviderService::restore(&app_state, AppType::Claude, "main").is_err());
+
+    ProviderService::delete(&app_state, AppType::Claude, "spare").expect("delete spare");
+    assert_eq!(
+        ProviderService::purge_trash(&app_state, AppType::Claude, Some("spare")).expect("purge"),
+        1
+    );
+    assert!(ProviderService::restore(&app_state, AppType::Claude, "spare").is_err());
+
+    // 新供应商替换回收站中同 id 的记录
+    ProviderService::delete(&app_state, AppType::Claude, "main").expect("delete main again");
+    ProviderService::add(
+        &app_state,
+        AppType::Claude,
+        Provider::with_id(
+            "main".to_string(),
+            "Main v2".to_string(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "main-key-2" } }),
+            None,
+        ),
+    )
+    .expect("add provider with trashed id");
+    assert!(ProviderService::get_trash(&app_state, AppType::Claude)
+        .expect("trash")
+        .is_empty());
+    let replaced = app_state
+        .db
+        .get_provider_by_id("main", "claude")
+        .unwrap()
+        .expect("new provider");
+    assert_eq!(replaced.name, "Main v2");
+}
+
+#[test]
+fn provider_profile_rolls_back_when_one_app_fails_to_switch() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "home".to_string();
+        for (id, token) in [("home", "home-key"), ("work", "work-key")] {
+            manager.providers.insert(
+                id.to_string(),
+                Provider::with_id(
+                    id.to_string(),
+                    id.to_string(),
+                    json!({ "env": { "ANTHROPIC_AUTH_TOKEN": token } }),
+                    None,
+                ),
+            );
+        }
+        let manager = config
+            .get_manager_mut(&AppType::Codex)
+            .expect("codex manager");
+        manager.providers.insert(
+            "broken".to_string(),
+            Provider::with_id(
+                "broken".to_string(),
+                "Broken Codex".to_string(),
+                json!({ "config": "model = \"gpt-5\"" }),
+                None,
+            ),
+        );
+    }
+    let state = create_test_state_with_config(&config).expect("create test state");
+    ProviderService::switch(&state, AppType::Claude, "home").expect("start on home");
+
+    let profile = |name: &str, providers: &[(&str, &str)]| ProviderProfile {
+        name: name.to_string(),
+        providers: providers
+            .iter()
+            .map(|(app, id)| (app.to_string(), id.to_string()))
+            .collect(),
+        created_at: 0,
+        updated_at: 0,
+    };
+    ProviderService::save_profile(
+        &state,
+        profile("Work", &[("claude", "work"), ("codex", "broken")]),
+    )
+    .expect("save profile");
+    let err = ProviderService::save_profile(&state, profile("Bad", &[("claude", "missing")]))
+        .expect_err("unknown provider is rejected");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "provider.not_found",
+            ..
+        }
+    ));
+
+    let err = ProviderService::apply_profile(&state, "Work", ProviderAuditOrigin::Ui)
+        .expect_err("codex switch fails");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "provider.profile_apply_failed",
+            ..
+        }
+    ));
+    let claude_current = state
+        .db
+        .get_current_provider("claude")
+        .expect("read current provider");
+    assert_eq!(
+        claude_current.as_deref(),
+        Some("home"),
+        "claude is rolled back"
+    );
+    let live: serde_json::Value =
+        read_json_file(&get_claude_settings_path()).expect("read claude live");
+    assert_eq!(live["env"]["ANTHROPIC_AUTH_TOKEN"], "home-key");
+
+    ProviderService::save_profile(&state, profile("Work", &[("claude", "work")]))
+        .expect("update profile");
+    let result = ProviderService::apply_profile(&state, "Work", ProviderAuditOrigin::Ui)
+        .expect("apply profile");
+    assert_eq!(result.switched, vec!["claude"]);
+    let live: serde_json::Value =
+        read_json_file(&get_claude_settings_path()).expect("read claude live");
+    assert_eq!(live["env"]["ANTHROPIC_AUTH_TOKEN"], "work-key");
+    assert_eq!(
+        ProviderService::list_profiles(&state)
+            .expect("list profiles")
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn universal_provider_applies_to_selected_apps_and_stays_linked() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    ProviderService::add(
+        &app_state,
+        AppType::Claude,
+        Provider::with_id(
+            "direct".to_string(),
+            "Direct".to_string(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-direct" } }),
+            None,
+        ),
+    )
+    .expect("add current claude provider");
+    let mut universal = UniversalProvider::new(
+        "relay".to_string(),
+        "Relay".to_string(),
+        "custom".to_string(),
+        "https://relay.example.com".to_string(),
+        "sk-relay".to_string(),
+    );
+    ProviderService::upsert_universal(&app_state, universal.clone()).expect("save universal");
+
+    let result =
+        ProviderService::apply_universal(&app_state, "relay", &[AppType::Claude, AppType::Codex])
+            .expect("apply to claude and codex");
+    assert_eq!(result.applied.len(), 2);
+    assert!(result.applied.iter().all(|applied| applied.created));
+    let claude = app_state
+        .db
+        .get_provider_by_id("universal-claude-relay", "claude")
+        .unwrap()
+        .expect("claude provider created");
+    let origin = serde_json::to_value(&claude.meta.unwrap().origin).unwrap();
+    assert_eq!(origin["kind"], "universal");
+    assert_eq!(origin["source"], "relay");
+
+    // 本地对子供应商的备注、图标与标签修改在重新应用后保留
+    let mut codex = app_state
+        .db
+        .get_provider_by_id("universal-codex-relay", "codex")
+        .unwrap()
+        .expect("codex provider created");
+    codex.notes = Some("team relay".to_string());
+    codex.icon = Some("relay".to_string());
+    codex.tags = vec!["shared".to_string()];
+    ProviderService::update(&app_state, AppType::Codex, codex).expect("customize codex child");
+
+    // 编辑统一供应商后重新应用：更新已关联的 Codex 供应商，取消的 Claude 供应商被删除
+    universal.base_url = "https://relay2.example.com".to_string();
+    ProviderService::upsert_universal(&app_state, universal).expect("update universal");
+    let result = ProviderService::apply_universal(&app_state, "relay", &[AppType::Codex])
+        .expect("apply to codex only");
+    assert_eq!(result.applied.len(), 1);
+    assert!(!result.applied[0].created);
+    assert_eq!(result.removed, ["universal-claude-relay"]);
+    let codex = app_state
+        .db
+        .get_provider_by_id("universal-codex-relay", "codex")
+        .unwrap()
+        .expect("codex provider kept");
+    assert!(codex.settings_config["config"]
+        .as_str()
+        .unwrap()
+        .contains("https://relay2.example.com"));
+    assert_eq!(codex.notes.as_deref(), Some("team relay"));
+    assert_eq!(codex.icon.as_deref(), Some("relay"));
+    assert_eq!(codex.tags, ["shared"]);
+    assert!(app_state
+        .db
+        .get_provider_by_id("universal-claude-relay", "claude")
+        .unwrap()
+        .is_none());
+    let trash = ProviderService::get_trash(&app_state, AppType::Claude).expect("claude trash");
+    assert_eq!(trash[0].provider.id, "universal-claude-relay");
+    let log = ProviderService::get_audit_log(
+        &app_state,
+        AppType::Claude,
+        Some("universal-claude-relay"),
+        None,
+    )
+    .expect("audit log");
+    assert_eq!(
+        log.iter().map(|e| e.action.as_str()).collect::<Vec<_>>(),
+        ["delete", "create"]
+    );
+
+    let err = ProviderService::apply_universal(&app_state, "relay", &[AppType::OpenCode])
+        .expect_err("opencode is not supported");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "provider.universal_app_unsupported",
+            ..
+        }
+    ));
+}
+
+#[test]
+fn switch_uses_fastest_healthy_endpoint_when_auto_select_is_on() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    let mut provider = Provider::with_id(
+        "relay".to_string(),
+        "Relay".to_string(),
+        json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-relay",
+                "ANTHROPIC_BASE_URL": "https://a.example.com"
+            }
+        }),
+        None,
+    );
+    provider.meta = Some(ProviderMeta {
+        endpoint_auto_select: Some(true),
+        ..Default::default()
+    });
+    ProviderService::add(&app_state, AppType::Claude, provider).expect("add provider");
+    for (url, latency) in [
+        ("https://a.example.com", Some(300)),
+        ("https://b.example.com", Some(90)),
+        ("https://c.example.com", None),
+    ] {
+        app_state
+            .db
+            .add_custom_endpoint("claude", "relay", url)
+            .expect("add endpoint");
+        app_state
+            .db
+            .record_endpoint_benchmark("claude", "relay", url, latency, 1)
+            .expect("record benchmark");
+    }
+
+    ProviderService::switch_from(
+        &app_state,
+        AppType::Claude,
+        "relay",
+        ProviderAuditOrigin::Api,
+    )
+    .expect("switch provider");
+
+    let live: serde_json::Value =
+        read_json_file(&get_claude_settings_path()).expect("read claude live settings");
+    assert_eq!(live["env"]["ANTHROPIC_BASE_URL"], "https://b.example.com");
+    let stored = app_state.db.get_all_providers("claude").unwrap()["relay"].clone();
+    assert_eq!(
+        stored.settings_config["env"]["ANTHROPIC_BASE_URL"],
+        "https://b.example.com"
+    );
+    assert!(
+        stored.meta.unwrap().custom_endpoints["https://b.example.com"]
+            .last_used
+            .is_some()
+    );
+    // 改写配置与切换一样记入审计，来源相同
+    let log = ProviderService::get_audit_log(&app_state, AppType::Claude, Some("relay"), Some(2))
+        .expect("audit log");
+    let summary: Vec<(&str, &str)> = log
+        .iter()
+        .map(|e| (e.action.as_str(), e.origin.as_str()))
+        .collect();
+    assert_eq!(summary, [("switch", "api"), ("update", "api")]);
+    assert_eq!(
+        log[1].new_value.as_ref().unwrap()["settingsConfig"]["env"]["ANTHROPIC_BASE_URL"],
+        "https://b.example.com"
+    );
+}
+
+#[test]
+fn provider_changes_are_recorded_in_audit_log_with_origin() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    let relay = |id: &str, url: &str| {
+        Provider::with_id(
+            id.to_string(),
+            id.to_string(),
+            json!({
+                "env": {
+                    "ANTHROPIC_AUTH_TOKEN": "sk-secret",
+                    "ANTHROPIC_BASE_URL": url
+                }
+            }),
+            None,
+        )
+    };
+    ProviderService::add(
+        &app_state,
+        AppType::Claude,
+        relay("a", "https://a.example.com"),
+    )
+    .expect("add a");
+    ProviderService::add(
+        &app_state,
+        AppType::Claude,
+        relay("b", "https://b.example.com"),
+    )
+    .expect("add b");
+    ProviderService::update(
+        &app_state,
+        AppType::Claude,
+        relay("b", "https://b2.example.com"),
+    )
+    .expect("update b");
+    ProviderService::switch_from(
+        &app_state,
+        AppType::Claude,
+        "b",
+        ProviderAuditOrigin::Deeplink,
+    )
+    .expect("switch to b");
+    ProviderService::delete_from(&app_state, AppType::Claude, "a", ProviderAuditOrigin::Api)
+        .expect("delete a");
+
+    let log =
+        ProviderService::get_audit_log(&app_state, AppType::Claude, None, None).expect("audit log");
+    let summary: Vec<(&str, &str, &str)> = log
+        .iter()
+        .map(|e| (e.action.as_str(), e.origin.as_str(), e.provider_id.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("delete", "api", "a"),
+            ("switch", "deeplink", "b"),
+            ("update", "ui", "b"),
+            ("create", "ui", "b"),
+            ("create", "ui", "a"),
+        ]
+    );
+
+    let switch = &log[1];
+    assert_eq!(switch.old_value, Some(json!({ "providerId": "a" })));
+    assert_eq!(switch.new_value, Some(json!({ "providerId": "b" })));
+
+    let update = &log[2];
+    let old = update.old_value.as_ref().expect("old value");
+    let new = update.new_value.as_ref().expect("new value");
+    assert_eq!(
+        old["settingsConfig"]["env"]["ANTHROPIC_BASE_URL"],
+        "https://b.example.com"
+    );
+    assert_eq!(
+        new["settingsConfig"]["env"]["ANTHROPIC_BASE_URL"],
+        "https://b2.example.com"
+    );
+    assert_eq!(new["settingsConfig"]["env"]["ANTHROPIC_AUTH_TOKEN"], "***");
+    assert!(log[0].new_value.is_none());
+
+    let only_a = ProviderService::get_audit_log(&app_state, AppType::Claude, Some("a"), Some(1))
+        .expect("audit log for a");
+    assert_eq!(only_a.len(), 1);
+    assert_eq!(only_a[0].action.as_str(), "delete");
+}
+
+#[test]
+fn import_and_switch_backfill_are_audited() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let settings_path = get_claude_settings_path();
+    std::fs::create_dir_all(settings_path.parent().expect("settings dir"))
+        .expect("create claude settings dir");
+    let live = |url: &str| {
+        json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-secret",
+                "ANTHROPIC_BASE_URL": url
+            }
+        })
+    };
+    std::fs::write(
+        &settings_path,
+        serde_json::to_string_pretty(&live("https://old.example.com")).expect("serialize"),
+    )
+    .expect("seed claude settings.json");
+
+    let app_state = create_test_state().expect("create test state");
+    assert!(
+        ProviderService::import_default_config(&app_state, AppType::Claude)
+            .expect("import default config")
+    );
+
+    ProviderService::add(
+        &app_state,
+        AppType::Claude,
+        Provider::with_id(
+            "b".to_string(),
+            "b".to_string(),
+            live("https://b.example.com"),
+            None,
+        ),
+    )
+    .expect("add b");
+    // live 配置在外部被修改，切换时回填到 default
+    std::fs::write(
+        &settings_path,
+        serde_json::to_string_pretty(&live("https://new.example.com")).expect("serialize"),
+    )
+    .expect("edit claude settings.json");
+    ProviderService::switch(&app_state, AppType::Claude, "b").expect("switch to b");
+
+    let log = ProviderService::get_audit_log(&app_state, AppType::Claude, Some("default"), None)
+        .expect("audit log");
+    let summary: Vec<(&str, &str)> = log
+        .iter()
+        .map(|e| (e.action.as_str(), e.origin.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        [("update", "ui"), ("switch", "import"), ("create", "import")]
+    );
+
+    let backfill = &log[0];
+    assert_eq!(
+        backfill.old_value.as_ref().expect("old value")["settingsConfig"]["env"]
+            ["ANTHROPIC_BASE_URL"],
+        "https://old.example.com"
+    );
+    assert_eq!(
+        backfill.new_value.as_ref().expect("new value")["settingsConfig"]["env"]
+            ["ANTHROPIC_BASE_URL"],
+        "https://new.example.com"
+    );
+}
+
+#[test]
+fn trust_archive_bulk_and_trash_changes_are_audited() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app_state = create_test_state().expect("create test state");
+    for id in ["a", "b"] {
+        ProviderService::add(
+            &app_state,
+            AppType::Claude,
+            Provider::with_id(
+                id.to_string(),
+                id.to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-secret" } }),
+                None,
+            ),
+        )
+        .expect("add provider");
+    }
+    ProviderService::set_trust(
+        &app_state,
+        AppType::Claude,
+        "b",
+        ProviderTrust::VerifiedRelay,
+    )
+    .expect("set trust");
+    ProviderService::set_archived(&app_state, AppType::Claude, "b", true).expect("archive");
+    ProviderService::set_archived(&app_state, AppType::Claude, "b", false).expect("unarchive");
+    ProviderService::bulk_update(
+        &app_state,
+        AppType::Claude,
+        &["b".to_string()],
+        &json!({ "env": { "ANTHROPIC_MODEL": "glm-4.6" } }),
+    )
+    .expect("bulk update");
+    ProviderService::delete(&app_state, AppType::Claude, "b").expect("delete");
+    ProviderService::restore(&app_state, AppType::Claude, "b").expect("restore");
+    ProviderService::delete(&app_state, AppType::Claude, "b").expect("delete again");
+    ProviderService::purge_trash(&app_state, AppType::Claude, Some("b")).expect("purge");
+
+    let log = ProviderService::get_audit_log(&app_state, AppType::Claude, Some("b"), None)
+        .expect("audit log");
+    let actions: Vec<&str> = log.iter().map(|e| e.action.as_str()).collect();
+    assert_eq!(
+        actions,
+        [
+            "purge", "delete", "restore", "delete", "update", "update", "update", "update",
+            "create"
+        ]
+    );
+    assert!(log.iter().all(|e| e.origin == ProviderAuditOrigin::Ui));
+    let bulk = log[4].new_value.as_ref().expect("bulk new value");
+    assert_eq!(bulk["settingsConfig"]["env"]["ANTHROPIC_MODEL"], "glm-4.6");
+    assert_eq!(bulk["settingsConfig"]["env"]["ANTHROPIC_AUTH_TOKEN"], "***");
+    let trusted = log[7].new_value.as_ref().expect("trust new value");
+    assert_eq!(trusted["meta"]["trust"], "verified_relay");
+}
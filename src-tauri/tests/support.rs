@@ -2,7 +2,8 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, OnceLock};
 
 use cc_switch_lib::{
-    update_settings, AppSettings, AppState, Database, MultiAppConfig, ProxyService,
+    install_recorder, update_settings, AppSettings, AppState, Database, MultiAppConfig,
+    ProxyService,
 };
 
 /// 为测试设置隔离的 HOME 目录，避免污染真实用户数据。
@@ -56,6 +57,8 @@ pub fn test_mutex() -> &'static Mutex<()> {
 pub fn create_test_state() -> Result<AppState, Box<dyn std::error::Error>> {
     let db = Arc::new(Database::init()?);
     let proxy_service = ProxyService::new(db.clone());
+    // 与应用启动时一样，外部修改冲突记录到本次的数据库
+    install_recorder(db.clone(), None);
     Ok(AppState { db, proxy_service })
 }
 
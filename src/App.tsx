@@ -33,6 +33,8 @@ import {
   type ProviderSwitchEvent,
 } from "@/lib/api";
 import { checkAllEnvConflicts, checkEnvConflicts } from "@/lib/api/env";
+import type { LatencyRegression } from "@/lib/api/model-test";
+import { showSystemNotification } from "@/lib/notification";
 import { useProviderActions } from "@/hooks/useProviderActions";
 import { openclawKeys } from "@/hooks/useOpenClaw";
 import { useProxyStatus } from "@/hooks/useProxyStatus";
@@ -378,6 +380,56 @@ function App() {
     };
   }, [queryClient, t]);
 
+  useEffect(() => {
+    let unsubscribe: (() => void) | undefined;
+    let active = true;
+
+    const setupListener = async () => {
+      try {
+        const off = await listen<LatencyRegression>(
+          "latency-regression",
+          (event) => {
+            const payload = event.payload;
+            const candidates = payload.candidates
+              .map((c) => `${c.providerName} (${c.latencyMs}ms)`)
+              .join(", ");
+            const message = t("streamCheck.latencyRegressionToast", {
+              provider: payload.providerName,
+              latency: payload.latencyMs,
+              baseline: payload.baselineMs,
+              ratio: payload.ratio.toFixed(1),
+            });
+            const description = candidates
+              ? t("streamCheck.latencyRegressionCandidates", { candidates })
+              : undefined;
+            toast.warning(message, { description });
+            // 窗口在后台或已隐藏到托盘时，应用内提示看不到，同时发送系统通知
+            void showSystemNotification(
+              t("streamCheck.latencyRegressionTitle"),
+              description ? `${message}\n${description}` : message,
+            );
+          },
+        );
+        if (!active) {
+          off();
+          return;
+        }
+        unsubscribe = off;
+      } catch (error) {
+        console.error(
+          "[App] Failed to subscribe latency-regression event",
+          error,
+        );
+      }
+    };
+
+    void setupListener();
+    return () => {
+      active = false;
+      unsubscribe?.();
+    };
+  }, [t]);
+
   useEffect(() => {
     const checkEnvOnStartup = async () => {
       try {
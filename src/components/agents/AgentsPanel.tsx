@@ -20,7 +20,7 @@ import { AppToggleGroup } from "@/components/common/AppToggleGroup";
 import { ListItemRow } from "@/components/common/ListItemRow";
 import { FullScreenPanel } from "@/components/common/FullScreenPanel";
 import MarkdownEditor from "@/components/MarkdownEditor";
-import { MCP_SKILLS_APP_IDS } from "@/config/appConfig";
+import { APP_IDS } from "@/config/appConfig";
 
 interface AgentsPanelProps {
   onOpenChange: (open: boolean) => void;
@@ -30,11 +30,11 @@ export interface AgentsPanelHandle {
   openAdd: () => void;
 }
 
-// Helper: convert agent apps (4-key) to full Record<AppId, boolean>
+// Helper: convert agent apps to full Record<AppId, boolean>
 function toAppRecord(
   apps: AgentDefinition["apps"],
 ): Record<AppId, boolean> {
-  return { ...apps, openclaw: false };
+  return { ...apps, openclaw: apps.openclaw ?? false };
 }
 
 // Helper: generate slug from name
@@ -77,7 +77,7 @@ const AgentsPanel = React.forwardRef<AgentsPanelHandle, AgentsPanelProps>(
         openclaw: 0,
       };
       agentEntries.forEach(([_, agent]) => {
-        for (const app of MCP_SKILLS_APP_IDS) {
+        for (const app of APP_IDS) {
           if (agent.apps[app as AgentAppId]) counts[app]++;
         }
       });
@@ -89,7 +89,6 @@ const AgentsPanel = React.forwardRef<AgentsPanelHandle, AgentsPanelProps>(
       app: AppId,
       enabled: boolean,
     ) => {
-      if (app === "openclaw") return;
       try {
         await toggleAppMutation.mutateAsync({
           agentId,
@@ -142,7 +141,7 @@ const AgentsPanel = React.forwardRef<AgentsPanelHandle, AgentsPanelProps>(
         <AppCountBar
           totalLabel={t("agents.count", { count: agentEntries.length })}
           counts={enabledCounts}
-          appIds={MCP_SKILLS_APP_IDS}
+          appIds={APP_IDS}
         />
 
         <div className="flex-1 overflow-y-auto overflow-x-hidden pb-24">
@@ -256,7 +255,7 @@ const AgentListItem: React.FC<AgentListItemProps> = ({
       <AppToggleGroup
         apps={toAppRecord(agent.apps)}
         onToggle={(app, enabled) => onToggleApp(id, app, enabled)}
-        appIds={MCP_SKILLS_APP_IDS}
+        appIds={APP_IDS}
       />
 
       <div className="flex items-center gap-0.5 flex-shrink-0 opacity-0 group-hover:opacity-100 transition-opacity">
@@ -332,6 +331,7 @@ const AgentFormPanel: React.FC<AgentFormPanelProps> = ({
     codex: initialData?.apps.codex ?? false,
     gemini: initialData?.apps.gemini ?? false,
     opencode: initialData?.apps.opencode ?? false,
+    openclaw: initialData?.apps.openclaw ?? false,
   });
 
   const [errors, setErrors] = useState<{
@@ -469,7 +469,7 @@ const AgentFormPanel: React.FC<AgentFormPanelProps> = ({
             {t("agents.targetApps")}
           </label>
           <div className="flex items-center gap-4 flex-wrap">
-            {MCP_SKILLS_APP_IDS.map((app) => (
+            {APP_IDS.map((app) => (
               <label
                 key={app}
                 className="flex items-center gap-2 cursor-pointer select-none text-sm"
@@ -30,13 +30,14 @@ const PromptFormModal: React.FC<PromptFormModalProps> = ({
 }) => {
   const { t } = useTranslation();
   const appName = t(`apps.${appId}`);
-  const filenameMap: Record<Exclude<AppId, "openclaw">, string> = {
+  const filenameMap: Record<AppId, string> = {
     claude: "CLAUDE.md",
     codex: "AGENTS.md",
     gemini: "GEMINI.md",
     opencode: "AGENTS.md",
+    openclaw: "AGENTS.md",
   };
-  const filename = filenameMap[appId as Exclude<AppId, "openclaw">];
+  const filename = filenameMap[appId];
   const [name, setName] = useState("");
   const [description, setDescription] = useState("");
   const [content, setContent] = useState("");
@@ -82,7 +83,13 @@ const PromptFormModal: React.FC<PromptFormModalProps> = ({
         name: name.trim(),
         description: description.trim() || undefined,
         content: content.trim(),
-        apps: initialData?.apps || { claude: false, codex: false, gemini: false, opencode: false },
+        apps: initialData?.apps || {
+          claude: false,
+          codex: false,
+          gemini: false,
+          opencode: false,
+          openclaw: false,
+        },
         createdAt: initialData?.createdAt || timestamp,
         updatedAt: timestamp,
       };
@@ -9,7 +9,7 @@ import MarkdownEditor from "@/components/MarkdownEditor";
 import { FullScreenPanel } from "@/components/common/FullScreenPanel";
 import type { Prompt, PromptApps } from "@/lib/api/prompts";
 import { useUpsertPrompt } from "@/hooks/usePrompts";
-import { MCP_SKILLS_APP_IDS } from "@/config/appConfig";
+import { APP_IDS } from "@/config/appConfig";
 
 interface PromptFormPanelProps {
   editingId?: string;
@@ -35,6 +35,7 @@ const PromptFormPanel: React.FC<PromptFormPanelProps> = ({
     codex: initialData?.apps.codex ?? false,
     gemini: initialData?.apps.gemini ?? false,
     opencode: initialData?.apps.opencode ?? false,
+    openclaw: initialData?.apps.openclaw ?? false,
   });
 
   useEffect(() => {
@@ -129,7 +130,7 @@ const PromptFormPanel: React.FC<PromptFormPanelProps> = ({
             {t("prompts.targetApps", { defaultValue: "Target Apps" })}
           </label>
           <div className="flex items-center gap-4 flex-wrap">
-            {MCP_SKILLS_APP_IDS.map((app) => (
+            {APP_IDS.map((app) => (
               <label
                 key={app}
                 className="flex items-center gap-2 cursor-pointer select-none text-sm"
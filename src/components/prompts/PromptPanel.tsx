@@ -15,7 +15,7 @@ import { ConfirmDialog } from "../ConfirmDialog";
 import { AppCountBar } from "@/components/common/AppCountBar";
 import { AppToggleGroup } from "@/components/common/AppToggleGroup";
 import { ListItemRow } from "@/components/common/ListItemRow";
-import { MCP_SKILLS_APP_IDS } from "@/config/appConfig";
+import { APP_IDS } from "@/config/appConfig";
 import PromptFormPanel from "./PromptFormPanel";
 
 interface PromptPanelProps {
@@ -61,7 +61,7 @@ const PromptPanel = React.forwardRef<PromptPanelHandle, PromptPanelProps>(
         openclaw: 0,
       };
       promptEntries.forEach(([_, prompt]) => {
-        for (const app of MCP_SKILLS_APP_IDS) {
+        for (const app of APP_IDS) {
           if (prompt.apps[app as keyof typeof prompt.apps]) counts[app]++;
         }
       });
@@ -123,7 +123,7 @@ const PromptPanel = React.forwardRef<PromptPanelHandle, PromptPanelProps>(
         <AppCountBar
           totalLabel={t("prompts.count", { count: promptEntries.length })}
           counts={enabledCounts}
-          appIds={MCP_SKILLS_APP_IDS}
+          appIds={APP_IDS}
         />
 
         <div className="flex-1 overflow-y-auto overflow-x-hidden pb-24">
@@ -234,7 +234,7 @@ const PromptListItem: React.FC<PromptListItemProps> = ({
       <AppToggleGroup
         apps={toAppRecord(prompt.apps)}
         onToggle={(app, enabled) => onToggleApp(id, app, enabled)}
-        appIds={MCP_SKILLS_APP_IDS}
+        appIds={APP_IDS}
       />
 
       <div className="flex items-center gap-0.5 flex-shrink-0 opacity-0 group-hover:opacity-100 transition-opacity">
@@ -36,6 +36,7 @@ interface ToolVersion {
   name: string;
   version: string | null;
   latest_version: string | null;
+  latest_version_stale?: boolean;
   error: string | null;
   env_type: "windows" | "wsl" | "macos" | "linux" | "unknown";
   wsl_distro: string | null;
@@ -27,6 +27,16 @@ export function WindowSettings({ settings, onChange }: WindowSettingsProps) {
           onCheckedChange={(value) => onChange({ launchOnStartup: value })}
         />
 
+        {settings.launchOnStartup && (
+          <ToggleRow
+            icon={<EyeOff className="h-4 w-4 text-amber-500" />}
+            title={t("settings.autostartMinimized")}
+            description={t("settings.autostartMinimizedDescription")}
+            checked={!!settings.autostartMinimized}
+            onCheckedChange={(value) => onChange({ autostartMinimized: value })}
+          />
+        )}
+
         <ToggleRow
           icon={<EyeOff className="h-4 w-4 text-green-500" />}
           title={t("settings.silentStartup")}
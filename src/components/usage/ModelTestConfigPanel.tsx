@@ -27,6 +27,8 @@ export function ModelTestConfigPanel() {
     codexModel: "gpt-5.1-codex@low",
     geminiModel: "gemini-3-pro-preview",
     testPrompt: "Who are you?",
+    regressionFactor: "2",
+    baselineWindow: "20",
   });
 
   useEffect(() => {
@@ -46,6 +48,8 @@ export function ModelTestConfigPanel() {
         codexModel: data.codexModel,
         geminiModel: data.geminiModel,
         testPrompt: data.testPrompt || "Who are you?",
+        regressionFactor: String(data.regressionFactor ?? 2),
+        baselineWindow: String(data.baselineWindow ?? 20),
       });
     } catch (e) {
       setError(String(e));
@@ -70,6 +74,8 @@ export function ModelTestConfigPanel() {
         codexModel: config.codexModel,
         geminiModel: config.geminiModel,
         testPrompt: config.testPrompt || "Who are you?",
+        regressionFactor: parseFloat(config.regressionFactor) || 2,
+        baselineWindow: parseNum(config.baselineWindow, 20),
       };
       await saveStreamCheckConfig(parsed);
       toast.success(t("streamCheck.configSaved"), {
@@ -192,6 +198,23 @@ export function ModelTestConfigPanel() {
               }
             />
           </div>
+
+          <div className="space-y-2">
+            <Label htmlFor="regressionFactor">
+              {t("streamCheck.regressionFactor")}
+            </Label>
+            <Input
+              id="regressionFactor"
+              type="number"
+              min={1.1}
+              max={10}
+              step={0.5}
+              value={config.regressionFactor}
+              onChange={(e) =>
+                setConfig({ ...config, regressionFactor: e.target.value })
+              }
+            />
+          </div>
         </div>
 
         {/* 检查提示词配置 */}
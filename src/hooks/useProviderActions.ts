@@ -137,6 +137,17 @@ export function useProviderActions(activeApp: AppId) {
         const result = await switchProviderMutation.mutateAsync(provider.id);
         await syncClaudePlugin(provider);
 
+        // 预检失败但仍然切换（warn 模式）：提示具体错误
+        if (result?.preflight && !result.preflight.success) {
+          toast.warning(
+            t("notifications.preflightFailed", {
+              defaultValue: "切换前预检失败：{{message}}",
+              message: result.preflight.message,
+            }),
+            { duration: 8000, closeButton: true },
+          );
+        }
+
         // Show backfill warning if present
         if (result?.warnings?.length) {
           toast.warning(
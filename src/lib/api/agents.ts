@@ -1,15 +1,23 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "./invoke";
+import type { GeminiAgentOutput } from "@/types";
 
 export interface AgentDefinition {
   id: string;
   name: string;
   content: string;
   description?: string;
+  tools?: string;
+  model?: string;
+  color?: string;
+  sortIndex?: number;
+  group?: string;
+  tags?: string[];
   apps: {
     claude: boolean;
     codex: boolean;
     gemini: boolean;
     opencode: boolean;
+    openclaw: boolean;
   };
   createdAt?: number;
   updatedAt?: number;
@@ -17,22 +25,110 @@ export interface AgentDefinition {
 
 export type AgentDefinitionsMap = Record<string, AgentDefinition>;
 
-export const AGENT_APP_IDS = ["claude", "codex", "gemini", "opencode"] as const;
+export interface AgentTemplate {
+  id: string;
+  name: string;
+  description: string;
+  content: string;
+  tools?: string;
+}
+
+export interface AgentRevision {
+  agentId: string;
+  version: number;
+  createdAt: number;
+  snapshot: AgentDefinition;
+}
+
+export interface AgentDiffLine {
+  kind: "equal" | "added" | "removed";
+  text: string;
+}
+
+export interface AgentRevisionDiff {
+  agentId: string;
+  fromVersion: number;
+  toVersion: number;
+  changedFields: string[];
+  contentDiff: AgentDiffLine[];
+}
+
+export interface AgentDrift {
+  agentId: string;
+  app: AgentAppId;
+  status: "modified" | "missing";
+  path: string;
+  changedFields: string[];
+  contentDiff: AgentDiffLine[];
+}
+
+/** `agent-files-changed` 事件：文件监听发现托管的 agent 文件被删除或修改 */
+export interface AgentFilesChangedEvent {
+  /** 已自动恢复的条目 */
+  restored: AgentDrift[];
+  /** 需要用户处理的偏差（拉回或恢复） */
+  drifts: AgentDrift[];
+}
+
+export interface AgentValidationIssue {
+  field: "id" | "name" | "description" | "content";
+  code: "invalid_slug" | "empty" | "unsafe_yaml" | "multiline";
+  message: string;
+}
+
+export type AgentDuplicateStrategy = "skip" | "overwrite" | "rename";
+
+export interface AgentImportResult {
+  imported: string[];
+  overwritten: string[];
+  skipped: string[];
+  renamed: { from: string; to: string }[];
+}
+
+export const AGENT_APP_IDS = [
+  "claude",
+  "codex",
+  "gemini",
+  "opencode",
+  "openclaw",
+] as const;
 export type AgentAppId = (typeof AGENT_APP_IDS)[number];
 
 export const agentsApi = {
   /**
-   * 获取所有 Agent 定义
+   * 获取所有 Agent 定义（传入 tags 时只返回带有全部指定标签的 agent）
    */
-  async getAll(): Promise<AgentDefinitionsMap> {
-    return await invoke("get_agent_definitions");
+  async getAll(tags?: string[]): Promise<AgentDefinitionsMap> {
+    return await invoke("get_agent_definitions", { tags });
+  },
+
+  /**
+   * 按关键词搜索 Agent（name / description / content），顺序与列表一致，可再按标签筛选
+   */
+  async search(query: string, tags?: string[]): Promise<AgentDefinition[]> {
+    return await invoke("search_agents", { query, tags });
   },
 
   /**
    * 新增或更新 Agent 定义
+   *
+   * 新启用工具中已有同名的非 cc-switch 文件时默认报错，`overwriteUnmanaged` 为 true 时覆盖
+   */
+  async upsert(
+    agent: AgentDefinition,
+    overwriteUnmanaged?: boolean,
+  ): Promise<void> {
+    return await invoke("upsert_agent_definition", {
+      agent,
+      overwriteUnmanaged,
+    });
+  },
+
+  /**
+   * 校验 Agent 定义，返回全部问题（为空表示可以保存）
    */
-  async upsert(agent: AgentDefinition): Promise<void> {
-    return await invoke("upsert_agent_definition", { agent });
+  async validate(agent: AgentDefinition): Promise<AgentValidationIssue[]> {
+    return await invoke("validate_agent_definition", { agent });
   },
 
   /**
@@ -43,13 +139,145 @@ export const agentsApi = {
   },
 
   /**
-   * 切换 Agent 在指定工具的启用状态
+   * 切换 Agent 在指定工具的启用状态（`overwriteUnmanaged` 为 true 时覆盖同名的非托管文件）
    */
   async toggleApp(
     agentId: string,
     app: AgentAppId,
     enabled: boolean,
+    overwriteUnmanaged?: boolean,
   ): Promise<void> {
-    return await invoke("toggle_agent_app", { agentId, app, enabled });
+    return await invoke("toggle_agent_app", {
+      agentId,
+      app,
+      enabled,
+      overwriteUnmanaged,
+    });
+  },
+
+  /**
+   * 批量切换多个 Agent 在多个工具的启用状态，返回实际变化的 agent id
+   */
+  async bulkToggle(
+    ids: string[],
+    apps: AgentDefinition["apps"],
+    enabled: boolean,
+    overwriteUnmanaged?: boolean,
+  ): Promise<string[]> {
+    return await invoke("bulk_toggle_agents", {
+      ids,
+      apps,
+      enabled,
+      overwriteUnmanaged,
+    });
+  },
+
+  /**
+   * 按给定 id 顺序调整 Agent 排序（同时调整 AGENTS.md / GEMINI.md 中的区块顺序）
+   */
+  async reorder(ids: string[]): Promise<void> {
+    return await invoke("reorder_agents", { ids });
+  },
+
+  /**
+   * 获取内置 Agent 模板
+   */
+  async getTemplates(): Promise<AgentTemplate[]> {
+    return await invoke("get_agent_templates");
+  },
+
+  /**
+   * 基于内置模板新建 Agent
+   */
+  async createFromTemplate(
+    templateId: string,
+    id?: string,
+    apps?: AgentDefinition["apps"],
+  ): Promise<AgentDefinition> {
+    return await invoke("create_agent_from_template", { templateId, id, apps });
+  },
+
+  /**
+   * 获取 Agent 历史版本（最新在前）
+   */
+  async getHistory(agentId: string): Promise<AgentRevision[]> {
+    return await invoke("get_agent_history", { agentId });
+  },
+
+  /**
+   * 对比 Agent 的两个历史版本
+   */
+  async diffVersions(
+    agentId: string,
+    fromVersion: number,
+    toVersion: number,
+  ): Promise<AgentRevisionDiff> {
+    return await invoke("diff_agent_versions", {
+      agentId,
+      fromVersion,
+      toVersion,
+    });
+  },
+
+  /**
+   * 回滚 Agent 到指定历史版本
+   */
+  async rollback(agentId: string, version: number): Promise<AgentDefinition> {
+    return await invoke("rollback_agent", { agentId, version });
+  },
+
+  /**
+   * 检查工具中的 Agent 文件是否被外部修改或删除
+   */
+  async checkDrift(): Promise<AgentDrift[]> {
+    return await invoke("check_agent_drift");
+  },
+
+  /**
+   * 将指定工具文件中的外部修改拉回数据库
+   */
+  async pullFromApp(
+    agentId: string,
+    app: AgentAppId,
+  ): Promise<AgentDefinition> {
+    return await invoke("pull_agent_from_app", { agentId, app });
+  },
+
+  /**
+   * 接管指定工具中同名的非 cc-switch 文件，把文件内容导入数据库并对该工具启用
+   */
+  async adoptFile(agentId: string, app: AgentAppId): Promise<AgentDefinition> {
+    return await invoke("adopt_agent_file", { agentId, app });
+  },
+
+  /**
+   * 按数据库定义恢复指定工具中的 agent 文件（丢弃外部修改）
+   */
+  async restoreFile(agentId: string, app: AgentAppId): Promise<void> {
+    return await invoke("restore_agent_file", { agentId, app });
+  },
+
+  /**
+   * 切换 Gemini agent 输出方式并迁移已写入的 agent，返回迁移数量
+   */
+  async setGeminiOutput(output: GeminiAgentOutput): Promise<number> {
+    return await invoke("set_gemini_agent_output", { output });
+  },
+
+  /**
+   * 导出 Agent 到 JSON 导出包（ids 为空时导出全部）
+   */
+  async exportBundle(filePath: string, ids?: string[]): Promise<number> {
+    return await invoke("export_agents_bundle", { filePath, ids });
+  },
+
+  /**
+   * 从 JSON 导出包导入 Agent
+   */
+  async importBundle(
+    filePath: string,
+    strategy: AgentDuplicateStrategy = "skip",
+  ): Promise<AgentImportResult> {
+    return await invoke("import_agents_bundle", { filePath, strategy });
   },
 };
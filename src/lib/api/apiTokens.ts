@@ -0,0 +1,43 @@
+import { invoke } from "./invoke";
+
+// read：列出供应商（密钥被遮蔽）；switch：切换供应商；
+// mutateConfig：修改/删除数据并可读取密钥
+export type ApiScope = "read" | "switch" | "mutateConfig";
+
+export interface ApiToken {
+  id: string;
+  name: string;
+  scopes: ApiScope[];
+  // Unix 秒
+  createdAt: number;
+  lastUsedAt?: number | null;
+}
+
+export interface IssuedApiToken {
+  token: ApiToken;
+  // 令牌明文，只返回这一次
+  secret: string;
+}
+
+export const apiTokensApi = {
+  /**
+   * 获取全部本地 API 令牌
+   */
+  async list(): Promise<ApiToken[]> {
+    return await invoke("get_api_tokens");
+  },
+
+  /**
+   * 创建令牌；请求管理接口时放在 `Authorization: Bearer` 中
+   */
+  async create(name: string, scopes: ApiScope[]): Promise<IssuedApiToken> {
+    return await invoke("create_api_token", { name, scopes });
+  },
+
+  /**
+   * 吊销令牌
+   */
+  async revoke(id: string): Promise<boolean> {
+    return await invoke("revoke_api_token", { id });
+  },
+};
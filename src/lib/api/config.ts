@@ -1,5 +1,6 @@
 // 配置相关 API
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "./invoke";
+import type { AppId } from "./types";
 
 export type AppType = "claude" | "codex" | "gemini" | "omo" | "omo_slim";
 
@@ -26,3 +27,53 @@ export async function setCommonConfigSnippet(
 ): Promise<void> {
   return invoke("set_common_config_snippet", { appType, snippet });
 }
+
+export type ContextBudgetLevel = "ok" | "warning" | "critical";
+
+export interface ContextBudgetItem {
+  kind: "prompt" | "agent" | "skill";
+  id: string;
+  name: string;
+  tokens: number;
+}
+
+export interface ContextBudget {
+  app: AppId;
+  // 工具默认模型的大致上下文窗口
+  contextWindow: number;
+  // 按占用从高到低排序
+  items: ContextBudgetItem[];
+  totalTokens: number;
+  // 占上下文窗口的比例（0 ~ 1）
+  share: number;
+  level: ContextBudgetLevel;
+}
+
+/**
+ * 估算指定工具中已启用的提示词、Agent 与 Skill 描述占用的上下文
+ * @param app - 应用 id
+ */
+export async function getContextBudget(app: AppId): Promise<ContextBudget> {
+  return invoke<ContextBudget>("get_context_budget", { app });
+}
+
+export interface ComposedFile {
+  path: string;
+  content: string;
+}
+
+export interface ComposedContext {
+  app: AppId;
+  // 指令文件在前（含 cc-switch 标记），其后为独立成文件的 agent
+  files: ComposedFile[];
+}
+
+/**
+ * 预览同步后指定工具实际读到的提示词与 Agent 内容（不写入文件）
+ * @param app - 应用 id
+ */
+export async function previewComposedContext(
+  app: AppId,
+): Promise<ComposedContext> {
+  return invoke<ComposedContext>("preview_composed_context", { app });
+}
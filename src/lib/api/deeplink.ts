@@ -1,6 +1,6 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "./invoke";
 
-export type ResourceType = "provider" | "prompt" | "mcp" | "skill";
+export type ResourceType = "provider" | "prompt" | "mcp" | "skill" | "share";
 
 export interface DeepLinkImportRequest {
   version: string;
@@ -47,6 +47,31 @@ export interface DeepLinkImportRequest {
   usageAccessToken?: string;
   usageUserId?: string;
   usageAutoInterval?: number;
+
+  // Share link fields
+  share?: ShareRef;
+}
+
+export interface ShareRef {
+  relay: string;
+  id: string;
+  key: string;
+}
+
+export type ShareKind = "prompt" | "agent";
+
+export interface ShareLink {
+  url: string;
+  // Unix seconds
+  expiresAt: number;
+}
+
+// Same shape for prompt and agent imports
+export interface ShareImportCounts {
+  imported: string[];
+  overwritten: string[];
+  skipped: string[];
+  renamed: Array<{ from: string; to: string }>;
 }
 
 export interface McpImportResult {
@@ -67,7 +92,8 @@ export type ImportResult =
       importedIds: string[];
       failed: Array<{ id: string; error: string }>;
     }
-  | { type: "skill"; key: string };
+  | { type: "skill"; key: string }
+  | { type: "share"; kind: ShareKind; result: ShareImportCounts };
 
 export const deeplinkApi = {
   /**
@@ -101,4 +127,16 @@ export const deeplinkApi = {
   ): Promise<ImportResult> => {
     return invoke("import_from_deeplink_unified", { request });
   },
+
+  /**
+   * Encrypt a prompt or agent, upload it to the configured relay and return a
+   * ccswitch://v1/share link that expires after `ttl` seconds
+   */
+  createShareLink: async (
+    kind: ShareKind,
+    id: string,
+    ttl: number,
+  ): Promise<ShareLink> => {
+    return invoke("create_share_link", { kind, id, ttl });
+  },
 };
@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "./invoke";
 import type {
   ProviderHealth,
   CircuitBreakerConfig,
@@ -20,6 +20,40 @@ export interface Provider {
   iconColor?: string;
 }
 
+export interface FailoverImportResult {
+  apps: string[];
+  remapped: {
+    appType: string;
+    providerName: string;
+    from: string;
+    to: string;
+  }[];
+  unmatched: {
+    appType: string;
+    providerId: string;
+    providerName: string;
+  }[];
+  schedules: string[];
+  unmatchedSchedules: {
+    scheduleId: string;
+    app: string;
+    promptId: string;
+    promptName: string;
+  }[];
+}
+
+export type InjectedFailureKind =
+  | "timeout"
+  | "connection"
+  | "server_error"
+  | "rate_limit";
+
+export interface InjectedFailure {
+  appType: string;
+  providerId: string;
+  kind: InjectedFailureKind;
+}
+
 export const failoverApi = {
   // ========== 熔断器 API ==========
 
@@ -72,8 +106,18 @@ export const failoverApi = {
   },
 
   // 添加供应商到故障转移队列
-  async addToFailoverQueue(appType: string, providerId: string): Promise<void> {
-    return invoke("add_to_failover_queue", { appType, providerId });
+  // 未验证的供应商需在用户确认后以 confirmUntrusted = true 重试
+  // （未确认时错误的 details.key 为 provider.untrusted_needs_confirmation）
+  async addToFailoverQueue(
+    appType: string,
+    providerId: string,
+    confirmUntrusted?: boolean,
+  ): Promise<void> {
+    return invoke("add_to_failover_queue", {
+      appType,
+      providerId,
+      confirmUntrusted,
+    });
   },
 
   // 从故障转移队列移除供应商
@@ -96,4 +140,39 @@ export const failoverApi = {
   ): Promise<void> {
     return invoke("set_auto_failover_enabled", { appType, enabled });
   },
+
+  // ========== 故障注入（调试）==========
+
+  // 为供应商注入模拟故障，kind 为 null 时清除
+  async injectProviderFailure(
+    appType: string,
+    providerId: string,
+    kind: InjectedFailureKind | null,
+  ): Promise<void> {
+    return invoke("inject_provider_failure", {
+      app: appType,
+      id: providerId,
+      kind,
+    });
+  },
+
+  // 获取当前生效的故障注入
+  async getInjectedFailures(): Promise<InjectedFailure[]> {
+    return invoke("get_injected_failures");
+  },
+
+  // 清除所有故障注入
+  async clearInjectedFailures(): Promise<void> {
+    return invoke("clear_injected_failures");
+  },
+
+  // 导出故障转移队列与策略到 JSON 文件
+  async exportConfig(filePath: string): Promise<void> {
+    return invoke("export_failover_config", { filePath });
+  },
+
+  // 从 JSON 文件导入故障转移配置（按名称重映射供应商）
+  async importConfig(filePath: string): Promise<FailoverImportResult> {
+    return invoke("import_failover_config", { filePath });
+  },
 };
@@ -0,0 +1,89 @@
+import { invoke } from "./invoke";
+import type { AgentDiffLine } from "./agents";
+
+export interface FeedSubscription {
+  id: string;
+  url: string;
+  name: string;
+  // 关闭后不再自动拉取
+  enabled: boolean;
+  lastCheckedAt?: number;
+  // 最近一次拉取失败的原因
+  lastError?: string;
+  createdAt: number;
+}
+
+export type FeedItemKind = "prompt" | "agent" | "mcp";
+
+export interface FeedUpdate {
+  subscriptionId: string;
+  subscriptionName: string;
+  kind: FeedItemKind;
+  itemId: string;
+  name: string;
+  // new：本地没有同 id 条目；updated：接受后会覆盖本地条目；
+  // conflict：本地同 id 条目不是来自该订阅，不会被覆盖
+  status: "new" | "updated" | "conflict";
+  // 源中的条目内容
+  payload: Record<string, unknown>;
+  // 本地内容到源中内容的逐行差异（MCP 为 server 配置的 JSON）
+  diff: AgentDiffLine[];
+  // 新增或改变启动命令的 MCP 服务器，接受时需要确认
+  requiresConfirmation: boolean;
+}
+
+export const feedsApi = {
+  async getSubscriptions(): Promise<FeedSubscription[]> {
+    return await invoke("get_feed_subscriptions");
+  },
+
+  /**
+   * 添加订阅（仅 https），添加后立即拉取一次
+   */
+  async addSubscription(url: string, name?: string): Promise<FeedSubscription> {
+    return await invoke("add_feed_subscription", { url, name });
+  },
+
+  async removeSubscription(id: string): Promise<boolean> {
+    return await invoke("remove_feed_subscription", { id });
+  },
+
+  async setEnabled(id: string, enabled: boolean): Promise<void> {
+    return await invoke("set_feed_subscription_enabled", { id, enabled });
+  },
+
+  /**
+   * 立即拉取订阅，返回该订阅的待处理更新数
+   */
+  async refresh(id: string): Promise<number> {
+    return await invoke("refresh_feed_subscription", { id });
+  },
+
+  async getUpdates(): Promise<FeedUpdate[]> {
+    return await invoke("get_feed_updates");
+  },
+
+  /**
+   * 接受更新：写入本地（保留启用状态）并同步到已启用的工具
+   * requiresConfirmation 为 true 的更新需在用户确认后传入 confirmed
+   */
+  async accept(update: FeedUpdate, confirmed = false): Promise<void> {
+    return await invoke("accept_feed_update", {
+      subscriptionId: update.subscriptionId,
+      kind: update.kind,
+      itemId: update.itemId,
+      confirmed,
+    });
+  },
+
+  /**
+   * 忽略当前版本，源中内容再次变化时重新提示
+   */
+  async dismiss(update: FeedUpdate): Promise<void> {
+    return await invoke("dismiss_feed_update", {
+      subscriptionId: update.subscriptionId,
+      kind: update.kind,
+      itemId: update.itemId,
+    });
+  },
+};
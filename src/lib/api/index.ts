@@ -5,14 +5,70 @@ export { providersApi, universalProvidersApi } from "./providers";
 export { settingsApi } from "./settings";
 export { backupsApi } from "./settings";
 export { mcpApi } from "./mcp";
+export type { McpDevcontainerExport } from "./mcp";
 export { promptsApi } from "./prompts";
 export { skillsApi } from "./skills";
+export type { SkillIntegrityIssue, SkillRepairResult } from "./skills";
 export { usageApi } from "./usage";
 export { vscodeApi } from "./vscode";
 export { proxyApi } from "./proxy";
 export { openclawApi } from "./openclaw";
 export { sessionsApi } from "./sessions";
 export { workspaceApi } from "./workspace";
+export { jobsApi } from "./jobs";
+export { feedsApi } from "./feeds";
+export { markdownImportApi } from "./markdownImport";
+export { scheduleApi } from "./schedule";
+export { legacyConfigApi } from "./legacyConfig";
+export { apiTokensApi } from "./apiTokens";
 export * as configApi from "./config";
-export type { ProviderSwitchEvent } from "./providers";
-export type { Prompt, PromptApps } from "./prompts";
+export { CommandError, isCommandError } from "./invoke";
+export type { CommandErrorCode } from "./invoke";
+export type {
+  BulkUpdateResult,
+  FastestProviderPick,
+  ProfileApplyResult,
+  ProviderAttachment,
+  ProviderAuditAction,
+  ProviderAuditEntry,
+  ProviderAuditOrigin,
+  ProviderBenchmark,
+  ProviderDiff,
+  ProviderDiffEntry,
+  ProviderEnvScript,
+  ProviderImportResult,
+  ProviderMetrics,
+  ProviderPreset,
+  ProviderProfile,
+  ProviderQuota,
+  ProviderSetupGuide,
+  ProviderSortMode,
+  ProviderSwitchEvent,
+  ProviderTag,
+  TrashedProvider,
+  UniversalApplyResult,
+} from "./providers";
+export type {
+  Prompt,
+  PromptApps,
+  PromptFileBackup,
+  PromptFilePreview,
+  PromptIssue,
+  PromptProject,
+} from "./prompts";
+export type { FeedSubscription, FeedUpdate } from "./feeds";
+export type { MarkdownNote, MarkdownImportResult } from "./markdownImport";
+export type {
+  AppliedPromptSchedule,
+  PromptSchedule,
+  Schedule,
+  ScheduledRun,
+  Weekday,
+} from "./schedule";
+export type {
+  LegacyConfigChange,
+  LegacyConfigDiff,
+  LegacyConfigStatus,
+  LegacyMergeSelection,
+} from "./legacyConfig";
+export type { ApiScope, ApiToken, IssuedApiToken } from "./apiTokens";
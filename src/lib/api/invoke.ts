@@ -0,0 +1,69 @@
+import { invoke as tauriInvoke } from "@tauri-apps/api/core";
+
+/** 后端 `CommandError.code` 的取值 */
+export type CommandErrorCode =
+  | "not_found"
+  | "conflict"
+  | "validation"
+  | "io"
+  | "parse"
+  | "database"
+  | "internal";
+
+interface CommandErrorPayload {
+  code: CommandErrorCode;
+  message: string;
+  details?: Record<string, unknown>;
+}
+
+/**
+ * 命令返回的结构化错误
+ *
+ * `message` 与旧版返回的错误字符串一致，`String(error)` 仍得到原始信息；
+ * 可按 `code` 分支处理，`details` 中带有本地化键、文件路径或校验问题等附加信息。
+ */
+export class CommandError extends Error {
+  readonly code: CommandErrorCode;
+  readonly details?: Record<string, unknown>;
+
+  constructor(payload: CommandErrorPayload) {
+    super(payload.message);
+    this.name = "CommandError";
+    this.code = payload.code;
+    this.details = payload.details;
+  }
+
+  toString(): string {
+    return this.message;
+  }
+}
+
+const isCommandErrorPayload = (
+  value: unknown,
+): value is CommandErrorPayload =>
+  typeof value === "object" &&
+  value !== null &&
+  typeof (value as CommandErrorPayload).code === "string" &&
+  typeof (value as CommandErrorPayload).message === "string";
+
+export const isCommandError = (
+  error: unknown,
+  code?: CommandErrorCode,
+): error is CommandError =>
+  error instanceof CommandError && (code === undefined || error.code === code);
+
+/** 调用 Tauri 命令，将结构化错误转换为 {@link CommandError}，其余错误原样抛出 */
+export async function invoke<T>(
+  cmd: string,
+  args?: Parameters<typeof tauriInvoke>[1],
+  options?: Parameters<typeof tauriInvoke>[2],
+): Promise<T> {
+  try {
+    return await tauriInvoke<T>(cmd, args, options);
+  } catch (error) {
+    if (isCommandErrorPayload(error)) {
+      throw new CommandError(error);
+    }
+    throw error;
+  }
+}
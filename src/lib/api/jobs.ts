@@ -0,0 +1,107 @@
+import { invoke } from "./invoke";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { AppId } from "./types";
+
+export type JobKind =
+  | "skill-repo-refresh"
+  | "stream-check"
+  | "db-backup"
+  | "full-resync";
+
+/** `job-progress` 事件：current 为已完成的步骤数 */
+export interface JobProgressEvent {
+  jobId: string;
+  kind: JobKind;
+  current: number;
+  total: number;
+  message: string;
+}
+
+/** `job-completed` 事件：result 为任务结果（结构随任务类型而定） */
+export interface JobCompletedEvent<T = unknown> {
+  jobId: string;
+  kind: JobKind;
+  result: T;
+}
+
+/** `job-failed` 事件：被取消的任务同样以此事件结束（cancelled 为 true） */
+export interface JobFailedEvent {
+  jobId: string;
+  kind: JobKind;
+  error: string;
+  cancelled: boolean;
+}
+
+export const jobsApi = {
+  /**
+   * 后台强制刷新所有已启用的技能仓库索引，返回任务 id
+   */
+  async startSkillRepoRefresh(): Promise<string> {
+    return await invoke("start_skill_repo_refresh_job");
+  },
+
+  /**
+   * 后台批量流式健康检查，返回任务 id
+   */
+  async startStreamCheck(
+    appType: AppId,
+    proxyTargetsOnly = false,
+  ): Promise<string> {
+    return await invoke("start_stream_check_job", {
+      appType,
+      proxyTargetsOnly,
+    });
+  },
+
+  /**
+   * 后台创建数据库备份，返回任务 id
+   */
+  async startDbBackup(): Promise<string> {
+    return await invoke("start_db_backup_job");
+  },
+
+  /**
+   * 后台将当前配置全量重新写入各工具，返回任务 id
+   */
+  async startFullResync(): Promise<string> {
+    return await invoke("start_full_resync_job");
+  },
+
+  /**
+   * 请求取消任务；任务不存在或已结束时返回 false
+   */
+  async cancel(id: string): Promise<boolean> {
+    return await invoke("cancel_job", { id });
+  },
+
+  /**
+   * 获取运行中的任务 id
+   */
+  async getRunning(): Promise<string[]> {
+    return await invoke("get_running_jobs");
+  },
+
+  async onProgress(
+    handler: (event: JobProgressEvent) => void,
+  ): Promise<UnlistenFn> {
+    return await listen<JobProgressEvent>("job-progress", (event) =>
+      handler(event.payload),
+    );
+  },
+
+  async onCompleted(
+    handler: (event: JobCompletedEvent) => void,
+  ): Promise<UnlistenFn> {
+    return await listen<JobCompletedEvent>("job-completed", (event) =>
+      handler(event.payload),
+    );
+  },
+
+  async onFailed(
+    handler: (event: JobFailedEvent) => void,
+  ): Promise<UnlistenFn> {
+    return await listen<JobFailedEvent>("job-failed", (event) =>
+      handler(event.payload),
+    );
+  },
+};
@@ -0,0 +1,71 @@
+import { invoke } from "./invoke";
+import type { AppId } from "./types";
+
+export interface LegacyConfigStatus {
+  path: string;
+  // 文件修改时间（Unix 毫秒）
+  modifiedAt: number;
+  // 上次迁移或处理时的文件修改时间
+  lastMergedAt?: number | null;
+}
+
+export type LegacyEntryKind = "provider" | "mcp" | "prompt";
+
+export interface LegacyConfigChange {
+  kind: LegacyEntryKind;
+  // 仅供应商有所属应用
+  app?: AppId | null;
+  id: string;
+  name: string;
+  action: "add" | "update";
+  changedFields: string[];
+  // 只含参与比较的字段；新增时为空
+  current?: Record<string, unknown> | null;
+  incoming: Record<string, unknown>;
+}
+
+export interface LegacyConfigDiff {
+  status: LegacyConfigStatus;
+  changes: LegacyConfigChange[];
+}
+
+export interface LegacyMergeSelection {
+  kind: LegacyEntryKind;
+  app?: AppId | null;
+  id: string;
+}
+
+export interface LegacyMergeResult {
+  merged: number;
+  failed: string[];
+}
+
+export const legacyConfigApi = {
+  /**
+   * 检查是否有比上次迁移更新的旧版 config.json（如被 dotfiles 同步恢复）
+   */
+  async getStatus(): Promise<LegacyConfigStatus | null> {
+    return await invoke("get_legacy_config_status");
+  },
+
+  /**
+   * 预览合并会新增或修改的供应商、MCP 服务器和提示词
+   */
+  async preview(): Promise<LegacyConfigDiff | null> {
+    return await invoke("preview_legacy_config_merge");
+  },
+
+  /**
+   * 合并选中的条目；同一版文件之后不再提示
+   */
+  async merge(selections: LegacyMergeSelection[]): Promise<LegacyMergeResult> {
+    return await invoke("merge_legacy_config", { selections });
+  },
+
+  /**
+   * 忽略当前这一版文件
+   */
+  async dismiss(): Promise<void> {
+    await invoke("dismiss_legacy_config");
+  },
+};
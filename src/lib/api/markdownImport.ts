@@ -0,0 +1,37 @@
+import { invoke } from "./invoke";
+import type { AgentDuplicateStrategy, AgentImportResult } from "./agents";
+
+export type MarkdownImportTarget = "prompt" | "agent";
+
+export interface MarkdownNote {
+  id: string;
+  name: string;
+  description?: string;
+  content: string;
+  tags: string[];
+  // 相对所选文件夹的子目录（导入为 Agent 时作为分组）
+  folder?: string;
+  sourcePath: string;
+}
+
+export interface MarkdownImportResult extends AgentImportResult {
+  // 正文为空或无法读取而被忽略的文件
+  ignoredFiles: string[];
+}
+
+export const markdownImportApi = {
+  /**
+   * 解析 Obsidian / Notion 导出的单个文件或整个文件夹，只预览不导入
+   */
+  async preview(path: string): Promise<MarkdownNote[]> {
+    return await invoke("preview_markdown_notes", { path });
+  },
+
+  async import(
+    path: string,
+    target: MarkdownImportTarget,
+    strategy: AgentDuplicateStrategy = "skip",
+  ): Promise<MarkdownImportResult> {
+    return await invoke("import_markdown_notes", { path, target, strategy });
+  },
+};
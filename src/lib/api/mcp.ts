@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "./invoke";
 import type {
   McpConfigResponse,
   McpServer,
@@ -8,6 +8,28 @@ import type {
 } from "@/types";
 import type { AppId } from "./types";
 
+/** MCP 服务器与供应商凭据的绑定 */
+export interface McpProviderBinding {
+  serverId: string;
+  appType: AppId;
+  /** 为空时跟随该应用的当前供应商 */
+  providerId?: string;
+  /** 服务器 env 变量名 -> 供应商配置字段路径（如 env.ANTHROPIC_AUTH_TOKEN） */
+  envMap: Record<string, string>;
+}
+
+/** MCP 服务器导出为 devcontainer / CI 片段的结果（密钥已替换为 `${VAR}` 引用） */
+export interface McpDevcontainerExport {
+  /** 项目级 .mcp.json */
+  mcpJson: string;
+  /** devcontainer.json 片段（features 与 remoteEnv） */
+  devcontainerJson: string;
+  /** docker-compose.yml 片段 */
+  dockerCompose: string;
+  /** 需要在宿主机或 CI 中提供的环境变量 */
+  envVars: string[];
+}
+
 export const mcpApi = {
   async getStatus(): Promise<McpStatus> {
     return await invoke("get_claude_mcp_status");
@@ -126,4 +148,32 @@ export const mcpApi = {
   async importFromApps(): Promise<number> {
     return await invoke("import_mcp_from_apps");
   },
+
+  /**
+   * 获取所有 MCP 服务器的供应商绑定
+   */
+  async getProviderBindings(): Promise<McpProviderBinding[]> {
+    return await invoke("get_mcp_provider_bindings");
+  },
+
+  /**
+   * 设置 MCP 服务器的供应商绑定（立即按绑定改写服务器 env）
+   */
+  async setProviderBinding(binding: McpProviderBinding): Promise<void> {
+    return await invoke("set_mcp_provider_binding", { binding });
+  },
+
+  /**
+   * 解除 MCP 服务器的供应商绑定
+   */
+  async removeProviderBinding(serverId: string): Promise<boolean> {
+    return await invoke("remove_mcp_provider_binding", { serverId });
+  },
+
+  /**
+   * 将选中的 MCP 服务器导出为 devcontainer / docker-compose 片段
+   */
+  async exportAsDevcontainer(ids: string[]): Promise<McpDevcontainerExport> {
+    return await invoke("export_mcp_as_devcontainer", { ids });
+  },
 };
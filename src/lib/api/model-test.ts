@@ -13,6 +13,32 @@ export interface StreamCheckConfig {
   codexModel: string;
   geminiModel: string;
   testPrompt: string;
+  /** 延迟超过基线的倍数时告警 */
+  regressionFactor?: number;
+  /** 计算基线使用的最近成功样本数 */
+  baselineWindow?: number;
+  /** 切换（含故障转移）后立即发送一次预热请求 */
+  warmUpOnSwitch?: boolean;
+  /** 切换前预检：off 不预检，warn 失败时提示，block 失败时拒绝切换 */
+  validateOnSwitch?: SwitchValidation;
+}
+
+export type SwitchValidation = "off" | "warn" | "block";
+
+export interface LatencyCandidate {
+  providerId: string;
+  providerName: string;
+  latencyMs: number;
+}
+
+export interface LatencyRegression {
+  appType: string;
+  providerId: string;
+  providerName: string;
+  latencyMs: number;
+  baselineMs: number;
+  ratio: number;
+  candidates: LatencyCandidate[];
 }
 
 export interface StreamCheckResult {
@@ -26,6 +52,14 @@ export interface StreamCheckResult {
   retryCount: number;
 }
 
+/** 切换后的预热结果（`provider-warmed-up` 事件） */
+export interface ProviderWarmUp {
+  appType: string;
+  providerId: string;
+  source: "switch" | "failover";
+  result: StreamCheckResult;
+}
+
 // ===== 流式健康检查 API =====
 
 /**
@@ -1,11 +1,19 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "./invoke";
 import type { AppId } from "./types";
+import type { OpenCodePromptTarget } from "@/types";
+import type {
+  AgentDiffLine,
+  AgentDuplicateStrategy,
+  AgentImportResult,
+} from "./agents";
+import type { MarkdownImportResult } from "./markdownImport";
 
 export interface PromptApps {
   claude: boolean;
   codex: boolean;
   gemini: boolean;
   opencode: boolean;
+  openclaw: boolean;
 }
 
 export interface Prompt {
@@ -13,26 +21,267 @@ export interface Prompt {
   name: string;
   content: string;
   description?: string;
+  tags?: string[];
   apps: PromptApps;
+  // 优先级，同一 app 启用多个提示词时按此顺序拼接
+  sortIndex?: number;
   createdAt?: number;
   updatedAt?: number;
 }
 
+export interface PromptProjectLink {
+  promptId: string;
+  app: AppId;
+}
+
+export interface PromptProject {
+  id: string;
+  // 项目根目录（绝对路径）
+  path: string;
+  name: string;
+  createdAt: number;
+  links: PromptProjectLink[];
+}
+
+// 提示词校验问题：error 会拒绝保存，warning 随保存结果返回
+export interface PromptIssue {
+  // 相关的应用；与应用无关的问题为 null
+  app: AppId | null;
+  level: "warning" | "error";
+  // region_marker / frontmatter / include / large / too_large
+  code: string;
+  message: string;
+}
+
+// 回收站中的提示词
+export interface TrashedPrompt {
+  prompt: Prompt;
+  // 删除时间（Unix 秒）
+  deletedAt: number;
+  // 删除时的项目挂载，恢复时一并写回
+  projectLinks: { projectId: string; app: AppId }[];
+}
+
+// 提示词历史快照（批量改写等不经编辑器的变更前记录）
+export interface PromptRevision {
+  promptId: string;
+  version: number;
+  reason: "bulk_replace" | "rollback" | "external_edit";
+  // 快照时间（Unix 毫秒）
+  createdAt: number;
+  snapshot: Prompt;
+}
+
+// 在 cc-switch 之外被修改过的提示词文件
+export interface PromptDrift {
+  app: AppId;
+  path: string;
+  // 托管区域的当前正文；区域被整段删除时为 null
+  current: string | null;
+  // 按启用的提示词应写入的正文
+  expected: string;
+  enabledPromptIds: string[];
+}
+
+export type DriftResolution = "import" | "merge" | "discard";
+
+// 与 Agent 导入结果结构一致
+export type PromptImportResult = AgentImportResult;
+
+export type BulkReplaceKind = "prompts" | "agents" | "all";
+
+export interface ReplaceMatch {
+  kind: "prompt" | "agent";
+  id: string;
+  name: string;
+  count: number;
+  // 只含新增 / 删除行
+  changes: AgentDiffLine[];
+}
+
+export interface BulkReplaceResult {
+  matches: ReplaceMatch[];
+  totalMatches: number;
+  applied: boolean;
+  // 写入前生成的数据库备份 id
+  backupId?: string;
+}
+
+// cc-switch 首次改写提示词文件前留下的备份
+export interface PromptFileBackup {
+  // 备份文件名，恢复时作为标识
+  id: string;
+  app: AppId;
+  originalPath: string;
+  backupPath: string;
+  sizeBytes: number;
+  // Unix 秒
+  createdAt: number;
+}
+
+// 同步后 app 提示词文件的预览
+export interface PromptFilePreview {
+  app: AppId;
+  path: string;
+  // 启用的提示词展开引用、替换模板变量后拼接的结果（托管区域正文，不含横幅）
+  rendered: string;
+  // 同步后文件的完整内容；为空表示文件将被删除或保持不存在
+  content: string;
+  // 与文件当前内容是否不同
+  changed: boolean;
+}
+
 export const promptsApi = {
   async getAllPrompts(): Promise<Record<string, Prompt>> {
     return await invoke("get_prompts");
   },
 
-  async upsertPrompt(prompt: Prompt): Promise<void> {
+  /** 获取对 app 启用的提示词（按优先级排序） */
+  async getPromptsForApp(app: AppId): Promise<Record<string, Prompt>> {
+    return await invoke("get_prompts_for_app", { app });
+  },
+
+  /** 获取对 app 启用的优先级最高的提示词，没有时返回 null */
+  async getEnabledPrompt(app: AppId): Promise<Prompt | null> {
+    return await invoke("get_enabled_prompt", { app });
+  },
+
+  /** 按关键词全文搜索提示词（name / description / content），结果按优先级排序 */
+  async searchPrompts(query: string): Promise<Prompt[]> {
+    return await invoke("search_prompts", { query });
+  },
+
+  /**
+   * 保存提示词，返回校验警告；存在错误级别的问题时拒绝保存
+   */
+  async upsertPrompt(prompt: Prompt): Promise<PromptIssue[]> {
     return await invoke("upsert_prompt", { prompt });
   },
 
+  /**
+   * 校验提示词（大小限制与已知会出错的写法），不保存
+   */
+  async validatePrompt(prompt: Prompt): Promise<PromptIssue[]> {
+    return await invoke("validate_prompt", { prompt });
+  },
+
   async deletePrompt(id: string): Promise<void> {
     return await invoke("delete_prompt", { id });
   },
 
-  async toggleApp(id: string, app: AppId, enabled: boolean): Promise<void> {
-    return await invoke("toggle_prompt_app", { id, app, enabled });
+  /**
+   * 复制提示词，副本不对任何工具启用
+   */
+  async duplicatePrompt(id: string): Promise<Prompt> {
+    return await invoke("duplicate_prompt", { id });
+  },
+
+  /**
+   * 获取回收站中的提示词（最近删除的在前）
+   */
+  async getTrash(): Promise<TrashedPrompt[]> {
+    return await invoke("get_prompt_trash");
+  },
+
+  /**
+   * 从回收站恢复提示词，并按删除前的启用状态重写工具文件
+   */
+  async restorePrompt(id: string): Promise<Prompt> {
+    return await invoke("restore_prompt", { id });
+  },
+
+  /**
+   * 永久删除回收站中的提示词；不传 id 时清空回收站
+   */
+  async purgeTrash(id?: string): Promise<number> {
+    return await invoke("purge_prompt_trash", { id });
+  },
+
+  /**
+   * 获取提示词的历史快照（最新在前）
+   */
+  async getHistory(id: string): Promise<PromptRevision[]> {
+    return await invoke("get_prompt_history", { id });
+  },
+
+  /**
+   * 将提示词回滚到指定历史快照（只恢复名称、描述与正文）
+   */
+  async rollback(id: string, version: number): Promise<Prompt> {
+    return await invoke("rollback_prompt", { id, version });
+  },
+
+  /**
+   * 切换提示词启用状态；exclusive 为 false 时与已启用的提示词并存（默认互斥）
+   */
+  async toggleApp(
+    id: string,
+    app: AppId,
+    enabled: boolean,
+    exclusive?: boolean,
+  ): Promise<void> {
+    return await invoke("toggle_prompt_app", { id, app, enabled, exclusive });
+  },
+
+  /**
+   * 按给定 id 顺序调整提示词优先级
+   */
+  async reorder(ids: string[]): Promise<void> {
+    return await invoke("reorder_prompts", { ids });
+  },
+
+  /**
+   * 预览编辑中的正文展开引用、替换 {{os}}、{{date}}、{{active_provider}} 等模板变量后的内容；
+   * 整个 app 文件的预览见 previewFile
+   */
+  async previewRender(app: AppId, content: string): Promise<string> {
+    return await invoke("preview_prompt_render", { app, content });
+  },
+
+  /**
+   * 预览同步后 app 提示词文件的完整内容与启用提示词的渲染结果（不写入）
+   * 传入 enabledIds 时按这组 id 模拟该 app 的启用状态
+   */
+  async previewFile(
+    app: AppId,
+    enabledIds?: string[],
+  ): Promise<PromptFilePreview> {
+    return await invoke("preview_prompt_file", { app, enabledIds });
+  },
+
+  async getProjects(): Promise<PromptProject[]> {
+    return await invoke("get_prompt_projects");
+  },
+
+  /**
+   * 登记项目目录；name 缺省时取目录名
+   */
+  async addProject(path: string, name?: string): Promise<PromptProject> {
+    return await invoke("add_prompt_project", { path, name });
+  },
+
+  /**
+   * 取消登记项目，并移除项目文件中写入的提示词区域
+   */
+  async removeProject(id: string): Promise<boolean> {
+    return await invoke("remove_prompt_project", { id });
+  },
+
+  /**
+   * 在项目中挂载或取消挂载提示词（写入项目下的 CLAUDE.md / AGENTS.md / GEMINI.md）
+   */
+  async toggleProjectPrompt(
+    projectId: string,
+    promptId: string,
+    app: AppId,
+    enabled: boolean,
+  ): Promise<void> {
+    return await invoke("toggle_project_prompt", {
+      projectId,
+      promptId,
+      app,
+      enabled,
+    });
   },
 
   async importFromFile(app: AppId): Promise<string> {
@@ -42,4 +291,84 @@ export const promptsApi = {
   async getCurrentFileContent(app: AppId): Promise<string | null> {
     return await invoke("get_current_prompt_file_content", { app });
   },
+
+  /** 列出提示词文件备份（最新的在前） */
+  async listBackups(): Promise<PromptFileBackup[]> {
+    return await invoke("list_prompt_backups");
+  },
+
+  /** 用备份覆盖原提示词文件 */
+  async restoreBackup(id: string): Promise<PromptFileBackup> {
+    return await invoke("restore_prompt_backup", { id });
+  },
+
+  /**
+   * 导出提示词到导出包（.zip 为 Markdown 压缩包，其余为 JSON；ids 为空时导出全部）
+   */
+  async exportBundle(filePath: string, ids?: string[]): Promise<number> {
+    return await invoke("export_prompts_bundle", { filePath, ids });
+  },
+
+  /**
+   * 从导出包导入提示词（启用状态不随导出包生效）
+   */
+  async importBundle(
+    filePath: string,
+    strategy: AgentDuplicateStrategy = "skip",
+  ): Promise<PromptImportResult> {
+    return await invoke("import_prompts_bundle", { filePath, strategy });
+  },
+
+  /**
+   * 把文件夹中的 Markdown 文件批量导入为提示词（每个 .md 一条）
+   */
+  async importFromDir(
+    path: string,
+    strategy: AgentDuplicateStrategy = "skip",
+  ): Promise<MarkdownImportResult> {
+    return await invoke("import_prompts_from_dir", { path, strategy });
+  },
+
+  /**
+   * 检查各应用的提示词文件是否被外部修改
+   */
+  async detectDrift(): Promise<PromptDrift[]> {
+    return await invoke("detect_prompt_drift");
+  },
+
+  /**
+   * 处理提示词文件的外部修改，返回导入或写回的提示词 id
+   */
+  async resolveDrift(
+    app: AppId,
+    resolution: DriftResolution,
+  ): Promise<string | null> {
+    return await invoke("resolve_prompt_drift", { app, resolution });
+  },
+
+  /**
+   * 切换 OpenCode 提示词写入位置，并把已启用的提示词迁移到新位置
+   */
+  async setOpenCodeTarget(target: OpenCodePromptTarget): Promise<void> {
+    return await invoke("set_opencode_prompt_target", { target });
+  },
+
+  /**
+   * 在提示词 / Agent 正文中批量查找替换（dryRun 为 true 时只预览）
+   */
+  async bulkReplace(
+    kind: BulkReplaceKind,
+    pattern: string,
+    replacement: string,
+    regex: boolean,
+    dryRun: boolean,
+  ): Promise<BulkReplaceResult> {
+    return await invoke("bulk_replace", {
+      kind,
+      pattern,
+      replacement,
+      regex,
+      dryRun,
+    });
+  },
 };
@@ -1,11 +1,14 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "./invoke";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type {
   Provider,
+  ProviderTrust,
+  QuotaType,
   UniversalProvider,
   UniversalProvidersMap,
 } from "@/types";
 import type { AppId } from "./types";
+import type { StreamCheckResult } from "./model-test";
 
 export interface ProviderSortUpdate {
   id: string;
@@ -17,13 +20,292 @@ export interface ProviderSwitchEvent {
   providerId: string;
 }
 
+/** 供应商测速结果，耗时单位为毫秒；经代理访问时 connectMs / tlsMs 为空 */
+export interface ProviderBenchmark {
+  providerId: string;
+  providerName: string;
+  url: string;
+  connectMs: number | null;
+  tlsMs: number | null;
+  firstByteMs: number | null;
+  httpStatus: number | null;
+  error: string | null;
+  measuredAt: number;
+}
+
+/** 供应商组合：一组“应用 -> 供应商”的对应关系，可一次性切换 */
+export interface ProviderProfile {
+  name: string;
+  /** 应用 -> 供应商 id */
+  providers: Partial<Record<AppId, string>>;
+  createdAt?: number;
+  updatedAt?: number;
+}
+
+export interface ProfileApplyResult {
+  /** 实际切换了供应商的应用 */
+  switched: AppId[];
+  warnings: string[];
+}
+
+export type CredentialIssueKind = "expired" | "invalid" | "quota_exhausted";
+
+export interface ProviderIssue {
+  providerId: string;
+  appType: AppId;
+  kind: CredentialIssueKind;
+  httpStatus: number;
+  message: string;
+  source: "health_check" | "proxy";
+  detectedAt: number;
+  remediation: "relogin" | "top_up" | "rotate_key";
+}
+
+export interface MissingSecret {
+  appType: AppId;
+  providerId: string;
+  providerName: string;
+  // 建议填写的字段路径（settingsConfig 中以 . 分隔）
+  keyPath: string;
+  keyPaths: string[];
+}
+
+export interface SecretUpdateResult {
+  providerId: string;
+  probe: StreamCheckResult | null;
+}
+
 export interface SwitchResult {
   warnings: string[];
+  /** 切换前预检结果（开启预检且执行了预检时存在） */
+  preflight?: StreamCheckResult;
+}
+
+export interface GuideText {
+  zh: string;
+  en: string;
+}
+
+export interface ProviderSetupGuide {
+  category: string;
+  title: GuideText;
+  summary: GuideText;
+  fields: Array<{
+    key: string;
+    // 为空表示适用于全部 app
+    apps: AppId[];
+    required: boolean;
+    label: GuideText;
+    help: GuideText;
+    example?: string;
+  }>;
+  baseUrlPatterns: Array<{ pattern: string; note: GuideText }>;
+  pitfalls: GuideText[];
+}
+
+export interface FastestProviderPick {
+  /** 选中的供应商；没有可用的测速结果时为空 */
+  providerId?: string;
+  switched: boolean;
+  /** 比选中者更快、但因未验证而被跳过的供应商名称 */
+  skippedUntrusted: string[];
+  warnings: string[];
+}
+
+/** 内置供应商预设 */
+export interface ProviderPreset {
+  id: string;
+  name: string;
+  category: string;
+  websiteUrl: string;
+  apiKeyUrl?: string;
+  icon?: string;
+  iconColor?: string;
+  /** 应用 -> 配置；apiKeyPath 为空表示使用官方登录，不需要 API Key */
+  apps: Partial<
+    Record<
+      AppId,
+      { settingsConfig: Record<string, unknown>; apiKeyPath?: string }
+    >
+  >;
+}
+
+/** 供应商导入结果 */
+export interface ProviderImportResult {
+  appType: AppId;
+  /** 导入后的供应商 id（原 id 已被占用时为新生成的 id） */
+  imported: string[];
+  /** 仍缺少密钥、需要补录的供应商 */
+  missingSecrets: MissingSecret[];
+  /** 没有导入的供应商及原因 */
+  failed: string[];
+}
+
+/** 供应商备注附件（文件保存在 ~/.cc-switch/attachments 下） */
+export interface ProviderAttachment {
+  id: string;
+  providerId: string;
+  fileName: string;
+  /** 文件大小（字节） */
+  size: number;
+  /** 添加时间（Unix 秒） */
+  addedAt: number;
+}
+
+/** 批量编辑结果 */
+export interface BulkUpdateResult {
+  updated: string[];
+  /** 应用补丁后配置没有变化的供应商 */
+  unchanged: string[];
+  warnings: string[];
+}
+
+/** 供应商余额 */
+export interface ProviderQuota {
+  providerId: string;
+  quotaType: QuotaType;
+  total?: number;
+  used?: number;
+  remaining?: number;
+  /** 金额单位，如 USD、CNY */
+  unit: string;
+  /** 查询时间（Unix 秒） */
+  checkedAt: number;
+}
+
+/** 供应商配置的一处差异（相对左侧供应商） */
+export interface ProviderDiffEntry {
+  /** JSON 路径，如 $.env.ANTHROPIC_BASE_URL */
+  path: string;
+  kind: "added" | "removed" | "changed";
+  left?: unknown;
+  right?: unknown;
+}
+
+/** 两个供应商配置的比较结果 */
+export interface ProviderDiff {
+  leftId: string;
+  rightId: string;
+  entries: ProviderDiffEntry[];
+}
+
+/** 环境变量脚本 */
+export interface ProviderEnvScript {
+  shell: "sh" | "fish" | "powershell";
+  path: string;
+  /** 需要加入 shell 启动文件的一行 */
+  sourceLine: string;
+}
+
+/** 标签及使用该标签的供应商数量 */
+export interface ProviderTag {
+  tag: string;
+  count: number;
+}
+
+/** 供应商列表的排序方式 */
+export type ProviderSortMode = "manual" | "latency" | "successRate" | "lastUsed";
+
+/** 供应商的运行指标 */
+export interface ProviderMetrics {
+  /** 最近一次成功检查的延迟，没有检查记录时取最近一次测速（毫秒） */
+  latencyMs?: number;
+  /** 最近若干次流式检查的成功率（0 ~ 1） */
+  successRate?: number;
+  /** 最近一次经代理请求的时间（Unix 秒） */
+  lastUsedAt?: number;
+}
+
+export type ProviderAuditAction =
+  | "create"
+  | "update"
+  | "delete"
+  | "restore"
+  | "purge"
+  | "switch";
+
+export type ProviderAuditOrigin =
+  | "ui"
+  | "deeplink"
+  | "failover"
+  | "schedule"
+  | "api"
+  | "import";
+
+/** 供应商变更审计记录；变更前后的值中密钥已替换为 *** */
+export interface ProviderAuditEntry {
+  id: number;
+  appType: string;
+  providerId: string;
+  action: ProviderAuditAction;
+  origin: ProviderAuditOrigin;
+  /** 变更前的供应商（切换时为 { providerId }），创建、恢复与永久删除时为空 */
+  oldValue?: unknown;
+  /** 变更后的供应商（切换时为 { providerId }），删除与永久删除时为空 */
+  newValue?: unknown;
+  /** 变更时间（Unix 毫秒） */
+  createdAt: number;
+}
+
+/** 回收站中的供应商 */
+export interface TrashedProvider {
+  provider: Provider;
+  /** 删除时间（Unix 秒） */
+  deletedAt: number;
+}
+
+/** 统一供应商应用到各应用的结果 */
+export interface UniversalApplyResult {
+  applied: Array<{ app: AppId; providerId: string; created: boolean }>;
+  /** 因应用被取消而删除的供应商 id */
+  removed: string[];
 }
 
 export const providersApi = {
-  async getAll(appId: AppId): Promise<Record<string, Provider>> {
-    return await invoke("get_providers", { app: appId });
+  /** 获取供应商；`sort` 不为 manual 时按运行指标排序，没有指标的排在后面 */
+  async getAll(
+    appId: AppId,
+    sort?: ProviderSortMode,
+  ): Promise<Record<string, Provider>> {
+    return await invoke("get_providers", { app: appId, sort });
+  },
+
+  /** 比较两个供应商的配置，按 JSON 路径列出差异 */
+  async diff(idA: string, idB: string, appId: AppId): Promise<ProviderDiff> {
+    return await invoke("diff_providers", { app: appId, idA, idB });
+  },
+
+  /** 按当前供应商重新生成环境变量脚本 */
+  async getEnvScripts(appId: AppId): Promise<ProviderEnvScript[]> {
+    return await invoke("get_provider_env_scripts", { app: appId });
+  },
+
+  /** 获取带有指定标签的供应商（不区分大小写） */
+  async getByTag(appId: AppId, tag: string): Promise<Record<string, Provider>> {
+    return await invoke("get_providers_by_tag", { app: appId, tag });
+  },
+
+  /** 获取应用下使用过的标签 */
+  async getTags(appId: AppId): Promise<ProviderTag[]> {
+    return await invoke("get_provider_tags", { app: appId });
+  },
+
+  /** 获取供应商变更审计日志（按时间倒序），不传 providerId 时返回该应用的全部记录 */
+  async getAuditLog(
+    appId: AppId,
+    providerId?: string,
+    limit?: number,
+  ): Promise<ProviderAuditEntry[]> {
+    return await invoke("get_provider_audit_log", {
+      app: appId,
+      providerId,
+      limit,
+    });
+  },
+
+  /** 获取各供应商的运行指标 */
+  async getMetrics(appId: AppId): Promise<Record<string, ProviderMetrics>> {
+    return await invoke("get_provider_metrics", { app: appId });
   },
 
   async getCurrent(appId: AppId): Promise<string> {
@@ -38,10 +320,146 @@ export const providersApi = {
     return await invoke("update_provider", { provider, app: appId });
   },
 
+  /**
+   * 批量编辑：对选中供应商的 settingsConfig 应用同一个 JSON Merge Patch
+   * （null 删除字段），例如 { env: { ANTHROPIC_MODEL: "..." } }
+   */
+  async bulkUpdate(
+    ids: string[],
+    patch: Record<string, unknown>,
+    appId: AppId,
+  ): Promise<BulkUpdateResult> {
+    return await invoke("bulk_update_providers", { ids, patch, app: appId });
+  },
+
   async delete(id: string, appId: AppId): Promise<boolean> {
     return await invoke("delete_provider", { id, app: appId });
   },
 
+  /** 获取回收站中的供应商（最近删除的在前） */
+  async getTrash(appId: AppId): Promise<TrashedProvider[]> {
+    return await invoke("get_provider_trash", { app: appId });
+  },
+
+  /** 从回收站恢复供应商 */
+  async restore(id: string, appId: AppId): Promise<Provider> {
+    return await invoke("restore_provider", { id, app: appId });
+  },
+
+  /** 永久删除回收站中的供应商；不传 id 时清空该应用的回收站 */
+  async purgeTrash(appId: AppId, id?: string): Promise<number> {
+    return await invoke("purge_provider_trash", { app: appId, id });
+  },
+
+  /** 将文件添加为供应商备注的附件（单个文件不超过 20 MB） */
+  async addAttachment(
+    providerId: string,
+    filePath: string,
+    appId: AppId,
+  ): Promise<ProviderAttachment> {
+    return await invoke("add_provider_attachment", {
+      providerId,
+      filePath,
+      app: appId,
+    });
+  },
+
+  async listAttachments(
+    providerId: string,
+    appId: AppId,
+  ): Promise<ProviderAttachment[]> {
+    return await invoke("list_provider_attachments", {
+      providerId,
+      app: appId,
+    });
+  },
+
+  /** 用系统默认程序打开附件 */
+  async openAttachment(
+    providerId: string,
+    id: string,
+    appId: AppId,
+  ): Promise<boolean> {
+    return await invoke("open_provider_attachment", {
+      providerId,
+      id,
+      app: appId,
+    });
+  },
+
+  async removeAttachment(
+    providerId: string,
+    id: string,
+    appId: AppId,
+  ): Promise<boolean> {
+    return await invoke("remove_provider_attachment", {
+      providerId,
+      id,
+      app: appId,
+    });
+  },
+
+  /**
+   * 复制供应商为可编辑的本地副本（只读引用只能复制后编辑）
+   */
+  async duplicate(id: string, appId: AppId): Promise<Provider> {
+    return await invoke("duplicate_provider", { id, app: appId });
+  },
+
+  /**
+   * 将团队模板仓库中的供应商写入为只读引用，返回写入的 id
+   */
+  async syncReferences(
+    source: string,
+    providers: Provider[],
+    appId: AppId,
+  ): Promise<string[]> {
+    return await invoke("sync_reference_providers", {
+      source,
+      providers,
+      app: appId,
+    });
+  },
+
+  /**
+   * 获取供应商的凭据问题（过期 / 无效 / 额度耗尽）及处理建议
+   */
+  async getIssues(id: string, appId: AppId): Promise<ProviderIssue[]> {
+    return await invoke("get_provider_issues", { id, app: appId });
+  },
+
+  /** 列出密钥为空或为占位符的供应商（不传 appId 时检查全部应用） */
+  async getMissingSecrets(appId?: AppId): Promise<MissingSecret[]> {
+    return await invoke("get_providers_missing_secrets", {
+      app: appId ?? null,
+    });
+  },
+
+  /** 补录供应商密钥，probe 为 true 时随后做一次健康检查 */
+  async setSecret(
+    appId: AppId,
+    providerId: string,
+    keyPath: string,
+    value: string,
+    probe = false,
+  ): Promise<SecretUpdateResult> {
+    return await invoke("set_provider_secret", {
+      app: appId,
+      providerId,
+      keyPath,
+      value,
+      probe,
+    });
+  },
+
+  /**
+   * 切换供应商密钥的存储位置（系统钥匙串 / 数据库明文），并迁移已保存的密钥
+   * 返回重新保存的供应商数量
+   */
+  async setKeychainStorage(enabled: boolean): Promise<number> {
+    return await invoke("set_keychain_storage", { enabled });
+  },
+
   /**
    * Remove provider from live config only (for additive mode apps like OpenCode)
    * Does NOT delete from database - provider remains in the list
@@ -50,8 +468,147 @@ export const providersApi = {
     return await invoke("remove_provider_from_live_config", { id, app: appId });
   },
 
-  async switch(id: string, appId: AppId): Promise<SwitchResult> {
-    return await invoke("switch_provider", { id, app: appId });
+  /** 切换供应商；force 为 true 时跳过切换前预检 */
+  async switch(
+    id: string,
+    appId: AppId,
+    force?: boolean,
+  ): Promise<SwitchResult> {
+    return await invoke("switch_provider", { id, app: appId, force });
+  },
+
+  /** 获取供应商分类的内置配置指南（离线可用），未收录时返回 null */
+  async getSetupGuide(category: string): Promise<ProviderSetupGuide | null> {
+    return await invoke("get_provider_setup_guide", { category });
+  },
+
+  /** 获取内置供应商预设，指定 appId 时只返回支持该应用的预设 */
+  async getPresets(appId?: AppId): Promise<ProviderPreset[]> {
+    return await invoke("get_provider_presets", { app: appId });
+  },
+
+  /** 从内置预设创建供应商，只需提供 API Key */
+  async createFromPreset(
+    appId: AppId,
+    presetId: string,
+    apiKey: string,
+    name?: string,
+  ): Promise<Provider> {
+    return await invoke("create_provider_from_preset", {
+      app: appId,
+      presetId,
+      apiKey,
+      name,
+    });
+  },
+
+  /**
+   * 导出供应商到 JSON 文件，返回导出数量
+   * 提供 passphrase 时密钥加密保存，否则密钥被清空；ids 为空时导出全部
+   */
+  async exportToFile(
+    appId: AppId,
+    filePath: string,
+    options: { ids?: string[]; passphrase?: string } = {},
+  ): Promise<number> {
+    return await invoke("export_providers", {
+      app: appId,
+      filePath,
+      ids: options.ids,
+      passphrase: options.passphrase,
+    });
+  },
+
+  /** 从 JSON 文件导入供应商；密钥已加密时需要 passphrase */
+  async importFromFile(
+    filePath: string,
+    passphrase?: string,
+  ): Promise<ProviderImportResult> {
+    return await invoke("import_providers", { filePath, passphrase });
+  },
+
+  /** 将供应商标记为维护中，直到 until（Unix 秒） */
+  async setMaintenance(
+    id: string,
+    appId: AppId,
+    until: number,
+  ): Promise<Provider> {
+    return await invoke("set_provider_maintenance", { id, app: appId, until });
+  },
+
+  /** 并发测速全部供应商，返回按首字节耗时排序的结果（失败的排在最后） */
+  async benchmark(
+    appId: AppId,
+    timeoutSecs?: number,
+  ): Promise<ProviderBenchmark[]> {
+    return await invoke("benchmark_providers", { app: appId, timeoutSecs });
+  },
+
+  /** 按最近一次测速结果切换到最快的供应商（跳过未验证且未获允许的供应商） */
+  async switchToFastest(appId: AppId): Promise<FastestProviderPick> {
+    return await invoke("switch_to_fastest_provider", { app: appId });
+  },
+
+  async setTrust(
+    id: string,
+    appId: AppId,
+    trust: ProviderTrust,
+  ): Promise<Provider> {
+    return await invoke("set_provider_trust", { id, app: appId, trust });
+  },
+
+  /** 归档或取消归档供应商；当前供应商不能归档 */
+  async setArchived(
+    id: string,
+    appId: AppId,
+    archived: boolean,
+  ): Promise<Provider> {
+    return await invoke("set_provider_archived", { id, app: appId, archived });
+  },
+
+  /** 查询供应商余额 */
+  async queryQuota(id: string, appId: AppId): Promise<ProviderQuota> {
+    return await invoke("query_provider_quota", { id, app: appId });
+  },
+
+  /** 设置未验证的供应商是否允许被自动化功能使用 */
+  async setAutomationAllowed(
+    id: string,
+    appId: AppId,
+    allowed: boolean,
+  ): Promise<Provider> {
+    return await invoke("set_provider_automation_allowed", {
+      id,
+      app: appId,
+      allowed,
+    });
+  },
+
+  /** 获取各供应商最近一次的测速结果 */
+  async getBenchmarks(appId: AppId): Promise<ProviderBenchmark[]> {
+    return await invoke("get_provider_benchmarks", { app: appId });
+  },
+
+  async getProfiles(): Promise<ProviderProfile[]> {
+    return await invoke("get_provider_profiles");
+  },
+
+  async saveProfile(profile: ProviderProfile): Promise<ProviderProfile> {
+    return await invoke("save_provider_profile", { profile });
+  },
+
+  async deleteProfile(name: string): Promise<boolean> {
+    return await invoke("delete_provider_profile", { name });
+  },
+
+  /** 应用供应商组合；任何一个应用切换失败时已切换的应用会回滚 */
+  async applyProfile(name: string): Promise<ProfileApplyResult> {
+    return await invoke("apply_provider_profile", { name });
+  },
+
+  /** 提前结束维护窗口，返回之前是否处于维护中 */
+  async clearMaintenance(id: string, appId: AppId): Promise<boolean> {
+    return await invoke("clear_provider_maintenance", { id, app: appId });
   },
 
   async importDefault(appId: AppId): Promise<boolean> {
@@ -151,4 +708,14 @@ export const universalProvidersApi = {
   async sync(id: string): Promise<boolean> {
     return await invoke("sync_universal_provider", { id });
   },
+
+  /**
+   * 把统一供应商应用到指定的应用，未列出的应用删除已生成的供应商
+   */
+  async apply(
+    id: string,
+    apps: Array<"claude" | "codex" | "gemini">,
+  ): Promise<UniversalApplyResult> {
+    return await invoke("apply_universal_provider", { id, apps });
+  },
 };
@@ -0,0 +1,76 @@
+import { invoke } from "./invoke";
+import type { AppId } from "./types";
+
+export type Weekday = "Mon" | "Tue" | "Wed" | "Thu" | "Fri" | "Sat" | "Sun";
+
+export interface Schedule {
+  // IANA 时区，如 Asia/Shanghai（可用 Intl.DateTimeFormat().resolvedOptions().timeZone 取本机时区）
+  timeZone: string;
+  // 当地时刻，HH:MM
+  at: string;
+  // 为空表示每天
+  days: Weekday[];
+}
+
+export interface ScheduledRun {
+  // Unix 毫秒
+  at: number;
+  // 规则时区中的当地时间（RFC 3339，含当时的偏移）
+  local: string;
+}
+
+export interface PromptSchedule {
+  // 新建时留空
+  id: string;
+  app: AppId;
+  // 到点后独占启用的提示词
+  promptId: string;
+  schedule: Schedule;
+  enabled: boolean;
+  // 以下由后端维护（Unix 毫秒）
+  lastRunAt?: number | null;
+  lastError?: string | null;
+  createdAt?: number;
+  updatedAt?: number;
+}
+
+// `prompt-schedule-applied` 事件的元素
+export interface AppliedPromptSchedule {
+  scheduleId: string;
+  app: AppId;
+  promptId: string;
+  runAt: number;
+}
+
+export const scheduleApi = {
+  /**
+   * 预览定时规则接下来的执行时间，夏令时切换按规则时区计算
+   */
+  async getNextRuns(
+    schedule: Schedule,
+    count?: number,
+  ): Promise<ScheduledRun[]> {
+    return await invoke("get_next_runs", { schedule, count });
+  },
+
+  /**
+   * 获取全部定时切换提示词规则
+   */
+  async getPromptSchedules(): Promise<PromptSchedule[]> {
+    return await invoke("get_prompt_schedules");
+  },
+
+  /**
+   * 新增或更新规则；保存之前的计划执行不会再补上
+   */
+  async savePromptSchedule(schedule: PromptSchedule): Promise<PromptSchedule> {
+    return await invoke("save_prompt_schedule", { schedule });
+  },
+
+  /**
+   * 删除规则
+   */
+  async deletePromptSchedule(id: string): Promise<boolean> {
+    return await invoke("delete_prompt_schedule", { id });
+  },
+};
@@ -1,5 +1,10 @@
-import { invoke } from "@tauri-apps/api/core";
-import type { Settings, WebDavSyncSettings, RemoteSnapshotInfo } from "@/types";
+import { invoke } from "./invoke";
+import type {
+  ExternalEditPolicy,
+  Settings,
+  WebDavSyncSettings,
+  RemoteSnapshotInfo,
+} from "@/types";
 import type { AppId } from "./types";
 
 export interface ConfigTransferResult {
@@ -18,6 +23,61 @@ export interface WebDavSyncResult {
   status: string;
 }
 
+// 应用启用状态矩阵的结果
+export interface EnablementApplyResult {
+  // 形如 "prompt claude +base"、"provider codex -> packycode"
+  changes: string[];
+  // 本机不存在而被跳过的条目
+  missing: string[];
+}
+
+export type SyncTargetKind = "prompt" | "agent";
+
+export interface SyncTarget {
+  app: AppId;
+  path: string;
+  mode: "whole_file" | "managed_block";
+  markerStart?: string;
+  markerEnd?: string;
+  enabled: boolean;
+  exists: boolean;
+  paused: boolean;
+}
+
+export interface ExternalEditConflict {
+  app: AppId;
+  kind: "prompt" | "agent" | "mcp";
+  id: string;
+  path: string;
+  policy: ExternalEditPolicy;
+  // 被覆盖前的外部修改内容（仅 versions）
+  snapshot?: string | null;
+  // 快照在提示词或 agent 历史中的版本号；无法对应到单个条目时为空
+  revision?: number | null;
+  detectedAt: number;
+}
+
+// 正在实时跟踪的托管文件
+export interface FileWatch {
+  id: string;
+  path: string;
+  // Unix 毫秒
+  startedAt: number;
+}
+
+// managed-file-changed 事件内容
+export interface FileChangeEvent {
+  watchId: string;
+  path: string;
+  kind: "created" | "modified" | "removed";
+  source: "ccSwitch" | "external";
+  // Unix 毫秒
+  at: number;
+  // 只含新增 / 删除行
+  diff: { kind: "added" | "removed"; text: string }[];
+  diffTruncated: boolean;
+}
+
 export const settingsApi = {
   async get(): Promise<Settings> {
     return await invoke("get_settings");
@@ -71,6 +131,58 @@ export const settingsApi = {
     return await invoke("set_app_config_dir_override", { path });
   },
 
+  async getPausedSyncApps(): Promise<AppId[]> {
+    return await invoke("get_paused_sync_apps");
+  },
+
+  async setAppSyncPaused(appId: AppId, paused: boolean): Promise<boolean> {
+    return await invoke("set_app_sync_paused", { app: appId, paused });
+  },
+
+  async setExternalEditPolicy(
+    appId: AppId,
+    policy: ExternalEditPolicy,
+  ): Promise<boolean> {
+    return await invoke("set_external_edit_policy", { app: appId, policy });
+  },
+
+  async getExternalEditConflicts(): Promise<ExternalEditConflict[]> {
+    return await invoke("get_external_edit_conflicts");
+  },
+
+  async clearExternalEditConflicts(): Promise<boolean> {
+    return await invoke("clear_external_edit_conflicts");
+  },
+
+  /** 列出可以实时跟踪的托管文件 */
+  async listManagedFiles(): Promise<string[]> {
+    return await invoke("list_managed_files");
+  },
+
+  /** 开始跟踪托管文件，变化通过 managed-file-changed 事件推送 */
+  async watchFile(path: string): Promise<FileWatch> {
+    return await invoke("watch_file", { path });
+  },
+
+  async unwatchFile(id: string): Promise<boolean> {
+    return await invoke("unwatch_file", { id });
+  },
+
+  async getFileWatches(): Promise<FileWatch[]> {
+    return await invoke("get_file_watches");
+  },
+
+  async getSyncTargets(
+    kind: SyncTargetKind,
+    id: string,
+  ): Promise<SyncTarget[]> {
+    return await invoke("get_sync_targets", { kind, id });
+  },
+
+  async stripManagedBanners(): Promise<number> {
+    return await invoke("strip_managed_banners");
+  },
+
   async applyClaudePluginConfig(options: {
     official: boolean;
   }): Promise<boolean> {
@@ -98,10 +210,29 @@ export const settingsApi = {
     return await invoke("export_config_to_file", { filePath });
   },
 
+  async generateConfigReport(
+    path: string,
+    format: "html" | "markdown",
+  ): Promise<ConfigTransferResult> {
+    return await invoke("generate_config_report", { path, format });
+  },
+
   async importConfigFromFile(filePath: string): Promise<ConfigTransferResult> {
     return await invoke("import_config_from_file", { filePath });
   },
 
+  /** 导出启用状态矩阵（YAML，仅含启用关系与当前供应商） */
+  async exportEnablementMatrix(filePath: string): Promise<void> {
+    await invoke("export_enablement_matrix", { filePath });
+  },
+
+  /** 从 YAML 应用启用状态矩阵 */
+  async importEnablementMatrix(
+    filePath: string,
+  ): Promise<EnablementApplyResult> {
+    return await invoke("import_enablement_matrix", { filePath });
+  },
+
   // ─── WebDAV v2 sync ───────────────────────────────────────
 
   async webdavTestConnection(
@@ -180,6 +311,7 @@ export const settingsApi = {
       name: string;
       version: string | null;
       latest_version: string | null;
+      latest_version_stale?: boolean;
       error: string | null;
       env_type: "windows" | "wsl" | "macos" | "linux" | "unknown";
       wsl_distro: string | null;
@@ -188,6 +320,10 @@ export const settingsApi = {
     return await invoke("get_tool_versions", { tools, wslShellByTool });
   },
 
+  async clearRemoteCache(): Promise<number> {
+    return await invoke("clear_remote_cache");
+  },
+
   async getRectifierConfig(): Promise<RectifierConfig> {
     return await invoke("get_rectifier_config");
   },
@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "./invoke";
 
 import type { AppId } from "@/lib/api/types";
 
@@ -37,6 +37,8 @@ export interface DiscoverableSkill {
   repoOwner: string;
   repoName: string;
   repoBranch: string;
+  /** 仓库刷新失败，来自过期的本地缓存 */
+  stale?: boolean;
 }
 
 /** 未管理的 Skill（用于导入） */
@@ -69,6 +71,23 @@ export interface SkillRepo {
   enabled: boolean;
 }
 
+/** SSOT 中文件缺失或被改动的 Skill */
+export interface SkillIntegrityIssue {
+  skillId: string;
+  name: string;
+  directory: string;
+  missing: string[];
+  modified: string[];
+  /** 是否记录了来源仓库，可以重新下载 */
+  repairable: boolean;
+}
+
+/** Skill 修复结果 */
+export interface SkillRepairResult {
+  repaired: string[];
+  failed: { skillId: string; error: string }[];
+}
+
 // ========== API ==========
 
 export const skillsApi = {
@@ -107,6 +126,16 @@ export const skillsApi = {
     return await invoke("import_skills_from_apps", { directories });
   },
 
+  /** 校验 SSOT 中 Skill 文件是否完整 */
+  async verifyIntegrity(): Promise<SkillIntegrityIssue[]> {
+    return await invoke("verify_skills_integrity");
+  },
+
+  /** 按来源仓库与提交重新下载并恢复 Skill（ids 为空时修复全部有问题的 Skill） */
+  async repair(ids: string[] = []): Promise<SkillRepairResult> {
+    return await invoke("repair_skills", { ids });
+  },
+
   /** 发现可安装的 Skills（从仓库获取） */
   async discoverAvailable(): Promise<DiscoverableSkill[]> {
     return await invoke("discover_available_skills");
@@ -3,6 +3,7 @@ import type {
   UsageSummary,
   DailyStats,
   ProviderStats,
+  LatencyHeatmap,
   ModelStats,
   RequestLog,
   LogFilters,
@@ -62,6 +63,14 @@ export const usageApi = {
     return invoke("get_provider_stats");
   },
 
+  getLatencyHeatmap: async (
+    providerId: string,
+    appType?: string,
+    days?: number,
+  ): Promise<LatencyHeatmap> => {
+    return invoke("get_latency_heatmap", { providerId, appType, days });
+  },
+
   getModelStats: async (): Promise<ModelStats[]> => {
     return invoke("get_model_stats");
   },
@@ -9,18 +9,31 @@ export interface EndpointLatencyResult {
   error?: string;
 }
 
+export interface StaleEndpoint {
+  providerId: string;
+  url: string;
+  consecutiveFailures: number;
+  lastBenchmarkedAt?: number;
+  lastUsedAt?: number;
+}
+
 export const vscodeApi = {
   async getLiveProviderSettings(appId: AppId) {
     return await invoke("read_live_provider_settings", { app: appId });
   },
 
+  /**
+   * 端点测速；传入 appId 与 providerId 时结果会记入该供应商自定义端点的测速记录
+   */
   async testApiEndpoints(
     urls: string[],
-    options?: { timeoutSecs?: number },
+    options?: { timeoutSecs?: number; appId?: AppId; providerId?: string },
   ): Promise<EndpointLatencyResult[]> {
     return await invoke("test_api_endpoints", {
       urls,
       timeoutSecs: options?.timeoutSecs,
+      app: options?.appId,
+      providerId: options?.providerId,
     });
   },
 
@@ -70,6 +83,21 @@ export const vscodeApi = {
     });
   },
 
+  /**
+   * 列出（dryRun）或移除连续测速失败的自定义端点
+   */
+  async pruneStaleEndpoints(
+    appId: AppId,
+    options: { providerId?: string; minFailures?: number; dryRun: boolean },
+  ): Promise<StaleEndpoint[]> {
+    return await invoke("prune_stale_endpoints", {
+      app: appId,
+      providerId: options.providerId,
+      minFailures: options.minFailures,
+      dryRun: options.dryRun,
+    });
+  },
+
   async exportConfigToFile(filePath: string) {
     return await invoke("export_config_to_file", {
       filePath,
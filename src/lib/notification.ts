@@ -0,0 +1,17 @@
+// 系统通知：使用 WebView 提供的 Notification API，不支持或用户拒绝授权时静默跳过
+export const showSystemNotification = async (
+  title: string,
+  body?: string,
+): Promise<void> => {
+  try {
+    if (typeof Notification === "undefined") return;
+    let permission = Notification.permission;
+    if (permission === "default") {
+      permission = await Notification.requestPermission();
+    }
+    if (permission !== "granted") return;
+    new Notification(title, { body });
+  } catch (error) {
+    console.warn("[notification] Failed to show system notification", error);
+  }
+};
@@ -17,7 +17,7 @@ export interface Provider {
   category?: ProviderCategory;
   createdAt?: number; // 添加时间戳（毫秒）
   sortIndex?: number; // 排序索引（用于自定义拖拽排序）
-  // 备注信息
+  // 备注信息（Markdown；附件通过 providersApi.listAttachments 获取）
   notes?: string;
   // 新增：是否为商业合作伙伴
   isPartner?: boolean;
@@ -28,6 +28,10 @@ export interface Provider {
   iconColor?: string; // 图标颜色（Hex 格式，如 "#00A67E"）
   // 是否加入故障转移队列
   inFailoverQueue?: boolean;
+  // 是否已归档（不出现在切换列表与故障转移队列中，通过 providersApi.setArchived 修改）
+  archived?: boolean;
+  // 标签（如厂商、地区、价格档位），比较时不区分大小写
+  tags?: string[];
 }
 
 export interface AppConfig {
@@ -40,6 +44,12 @@ export interface CustomEndpoint {
   url: string;
   addedAt: number;
   lastUsed?: number;
+  // 最近一次测速时间（毫秒）
+  lastBenchmarked?: number;
+  // 连续测速失败次数，成功一次即清零
+  consecutiveFailures?: number;
+  // 最近一次成功测速的延迟（毫秒），失败后清空
+  lastLatencyMs?: number;
 }
 
 // 端点候选项（用于端点测速弹窗）
@@ -150,6 +160,38 @@ export interface ProviderMeta {
   // - "ANTHROPIC_AUTH_TOKEN" (默认): 大多数第三方/聚合供应商
   // - "ANTHROPIC_API_KEY": 少数供应商需要原生 API Key
   apiKeyField?: "ANTHROPIC_AUTH_TOKEN" | "ANTHROPIC_API_KEY";
+  // 来源信息；kind 为 reference 时是团队模板的只读引用，只能复制后编辑
+  origin?: ProviderOrigin;
+  // 维护窗口结束时间（Unix 秒）；窗口内健康检查与故障转移会跳过该供应商
+  maintenanceUntil?: number;
+  // 信任级别；未设置时官方 / 厂商官方 / 云服务商分类视为 official，其余视为 unverified
+  trust?: ProviderTrust;
+  // 未验证的供应商是否允许被故障转移、自动选择最快供应商等自动化功能使用
+  automationAllowed?: boolean;
+  // 余额接口类型；未设置时按 Base URL 的域名识别
+  quotaType?: QuotaType;
+  // 余额接口地址；未设置时使用该类型的默认地址（openai_billing 为 {Base URL}/v1/dashboard/billing）
+  quotaUrl?: string;
+  // 该供应商为当前供应商时导出的环境变量，写入 ~/.cc-switch/env/<app>.{sh,fish,ps1}
+  env?: Record<string, string>;
+}
+
+export type QuotaType =
+  | "openai_billing"
+  | "openrouter"
+  | "deepseek"
+  | "siliconflow"
+  | "moonshot";
+
+export type ProviderTrust = "official" | "verified_relay" | "unverified";
+
+export interface ProviderOrigin {
+  // universal：由统一供应商生成，source 为统一供应商 id
+  kind: "local" | "reference" | "universal";
+  // 来源标识（如模板仓库地址）
+  source?: string;
+  // 最近一次从来源同步的时间（Unix 毫秒）
+  syncedAt?: number;
 }
 
 // Skill 同步方式
@@ -206,6 +248,12 @@ export interface RemoteSnapshotInfo {
   artifacts: string[];
 }
 
+// Gemini agent 输出方式
+export type GeminiAgentOutput = "markers" | "files";
+
+// OpenCode 全局提示词写入位置
+export type OpenCodePromptTarget = "agents" | "instructions";
+
 // 应用设置类型（用于设置对话框与 Tauri API）
 // 存储在本地 ~/.cc-switch/settings.json，不随数据库同步
 export interface Settings {
@@ -222,6 +270,8 @@ export interface Settings {
   launchOnStartup?: boolean;
   // 静默启动（程序启动时不显示主窗口）
   silentStartup?: boolean;
+  // 开机自启时以后台模式运行（仅托盘与后台服务，首次打开时才创建主窗口）
+  autostartMinimized?: boolean;
   // 是否启用主页面本地代理功能（默认关闭）
   enableLocalProxy?: boolean;
   // User has confirmed the local proxy first-run notice
@@ -273,8 +323,69 @@ export interface Settings {
   // Windows: "cmd" | "powershell" | "wt"
   // Linux: "gnome-terminal" | "konsole" | "xfce4-terminal" | "alacritty" | "kitty" | "ghostty"
   preferredTerminal?: string;
+
+  // 托管的 agent 文件被外部删除时自动恢复（默认开启；关闭后仅提示）
+  autoRestoreAgentFiles?: boolean;
+  // 在托管的 agent 文件、marker 区块与提示词区域顶部写入来源说明注释（默认关闭）
+  managedBanner?: boolean;
+
+  // ===== 提示词导入设置 =====
+  // 导入提示词文件（首次启动、手动、深链接）后的规范化选项，默认全部关闭
+  promptImportNormalization?: {
+    normalizeLineEndings: boolean;
+    stripManagedSections: boolean;
+    trimTrailingWhitespace: boolean;
+  };
+  // OpenCode 提示词写入 AGENTS.md 托管区域，或独立文件并登记到 opencode.json 的 instructions
+  // （只读；通过 promptsApi.setOpenCodeTarget 切换并迁移）
+  opencodePromptTarget?: OpenCodePromptTarget;
+
+  // ===== 提示词回收站设置 =====
+  // 删除的提示词在回收站中保留的天数（默认 30，0 表示不自动清理）
+  promptTrashRetentionDays?: number;
+
+  // ===== 供应商回收站设置 =====
+  // 删除的供应商在回收站中保留的天数（默认 30，0 表示不自动清理）
+  providerTrashRetentionDays?: number;
+
+  // ===== 提示词大小限制设置 =====
+  // 各应用提示词文件的大小限制覆盖（字节，键为应用 id，未设置的字段使用默认值）
+  promptSizeLimits?: Record<string, { warnBytes?: number; maxBytes?: number }>;
+
+  // ===== Agent 同步设置 =====
+  // Codex AGENTS.md 中 cc-switch 托管区域的位置（默认 bottom）
+  codexAgentsPlacement?: "top" | "bottom";
+  // Gemini agent 输出方式：GEMINI.md 区块或 ~/.gemini/agents/{id}.md
+  // （只读；通过 agentsApi.setGeminiOutput 切换并迁移）
+  geminiAgentOutput?: GeminiAgentOutput;
+
+  // ===== id 生成设置 =====
+  // 各场景的 id 模式覆盖，键：importedPrompt / autoImportedPrompt /
+  // deeplinkPrompt / deeplinkProvider / duplicateProvider / duplicatePrompt /
+  // presetProvider / importedProvider
+  // 占位符：{slug} {app} {date} {time} {ts}
+  idPatterns?: Record<string, string>;
+
+  // ===== 同步暂停设置 =====
+  // 已暂停同步的应用（只读，通过 settingsApi.setAppSyncPaused 修改）
+  pausedSyncApps?: string[];
+
+  // ===== 外部修改处理设置 =====
+  // 各应用检测到托管内容被外部修改时的处理方式（未列出的应用为 overwrite）
+  externalEditPolicies?: Record<string, ExternalEditPolicy>;
+
+  // ===== 分享设置 =====
+  // 分享链接使用的中转服务地址（https，未配置时无法创建分享链接）
+  shareRelayUrl?: string;
+
+  // ===== 密钥存储设置 =====
+  // 供应商 API Key 存入系统钥匙串，数据库只保留引用
+  // （只读；通过 providersApi.setKeychainStorage 切换并迁移）
+  storeSecretsInKeychain?: boolean;
 }
 
+export type ExternalEditPolicy = "overwrite" | "preserve" | "versions";
+
 export interface SessionMeta {
   providerId: string;
   sessionId: string;
@@ -328,12 +439,24 @@ export interface McpServer {
   tags?: string[];
   homepage?: string;
   docs?: string;
+  // 外部来源（如 Gemini 扩展）；有值时为只读条目，不能编辑、删除或切换
+  origin?: McpOrigin;
   // 兼容旧字段（v3.6.x 及以前）
   enabled?: boolean; // 已废弃，v3.7.0 使用 apps 字段
   source?: string;
   [key: string]: any;
 }
 
+// MCP 服务器的外部来源
+export interface McpOrigin {
+  // 目前只有 "gemini-extension"
+  kind: string;
+  // 扩展名
+  name: string;
+  // 定义所在的文件
+  path: string;
+}
+
 // MCP 服务器映射（id -> McpServer）
 export type McpServersMap = Record<string, McpServer>;
 
@@ -80,6 +80,23 @@ export interface ProviderStats {
   avgLatencyMs: number;
 }
 
+export interface LatencyHourBucket {
+  // 本地时间的小时（0-23）
+  hour: number;
+  sampleCount: number;
+  avgLatencyMs?: number;
+  p50LatencyMs?: number;
+  p90LatencyMs?: number;
+}
+
+export interface LatencyHeatmap {
+  providerId: string;
+  appType?: string;
+  days: number;
+  // 固定 24 个，按小时排列
+  buckets: LatencyHourBucket[];
+}
+
 export interface ModelStats {
   model: string;
   requestCount: number;